@@ -0,0 +1,336 @@
+use regex::Regex;
+use serde_json::Value;
+
+use crate::core::agent_adapter::{self, AgentAdapter, AgentEvent, PermissionRequest};
+
+/// Claude Code's summary line when a run ends, e.g. `Total cost: $0.1234`.
+const COST_PATTERN: &str = r"(?i)total cost:\s*\$([0-9]+(?:\.[0-9]+)?)";
+
+/// Claude Code's exit hint, e.g. `claude --resume 3f2a...`.
+const RESUME_HINT_PATTERN: &str = r"claude (?:--resume|-r) ([0-9a-fA-F-]{8,})";
+
+/// Headers of Claude Code's permission dialogs and the tool each one is for.
+const PERMISSION_HEADERS: &[(&str, &str)] = &[
+    ("Bash command", "Bash"),
+    ("Read file", "Read"),
+    ("Edit file", "Edit"),
+    ("Write file", "Write"),
+    ("Create file", "Write"),
+];
+
+/// Characters of the box Claude Code draws around dialogs.
+const BOX_CHARS: &[char] = &['│', '╭', '╮', '╰', '╯', '─', '|'];
+
+/// Longest tool result text passed on in a `ToolResult` event.
+const MAX_TOOL_RESULT_CHARS: usize = 16 * 1024;
+
+/// Adapter for the `claude` CLI (Claude Code).
+///
+/// Understands both the interactive UI, from which only the closing cost
+/// line, resume hint, and API errors are parsed, and `--output-format
+/// stream-json`, where every line is a JSON message that maps to session,
+/// text, tool call, tool result, cost, usage, and throttling events.
+pub struct ClaudeAdapter {
+    cost: Regex,
+    resume_hint: Regex,
+}
+
+impl Default for ClaudeAdapter {
+    fn default() -> Self {
+        Self {
+            cost: Regex::new(COST_PATTERN).expect("valid cost pattern"),
+            resume_hint: Regex::new(RESUME_HINT_PATTERN).expect("valid resume hint pattern"),
+        }
+    }
+}
+
+impl AgentAdapter for ClaudeAdapter {
+    fn name(&self) -> &'static str {
+        "claude"
+    }
+
+    fn default_command(&self) -> &'static str {
+        "claude"
+    }
+
+    fn uses_mcp(&self) -> bool {
+        true
+    }
+
+    fn resume_args(&self, agent_session_id: Option<&str>) -> Option<String> {
+        Some(match agent_session_id {
+            Some(id) => format!("--resume {id}"),
+            None => "--continue".to_string(),
+        })
+    }
+
+    fn prompt_patterns(&self) -> &'static [&'static str] {
+        &[
+            r"(?i)do you want to (proceed|continue|make this edit|create|run)",
+            r"❯\s*1\.\s*yes",
+        ]
+    }
+
+    /// Reads the last dialog of the form header, target, "Do you want to
+    /// ...?". The target is every line between header and question, so a
+    /// multi-line command is never judged by its first line. Lines the
+    /// terminal wrapped are joined back together, and for commands the
+    /// description line Claude Code prints below every command is dropped.
+    /// Option 1 ("Yes") is preselected, so Enter approves once.
+    fn parse_permission_prompt(&self, lines: &[&str]) -> Option<PermissionRequest> {
+        let lines: Vec<DialogLine> = lines
+            .iter()
+            .map(|l| DialogLine::parse(l))
+            .filter(|l| !l.text.is_empty())
+            .collect();
+        let question = lines.iter().rposition(|l| l.text.starts_with("Do you want to"))?;
+        let (header, tool) = lines[..question].iter().enumerate().rev().find_map(|(i, line)| {
+            PERMISSION_HEADERS
+                .iter()
+                .find(|(header, _)| line.text == *header)
+                .map(|(_, tool)| (i, *tool))
+        })?;
+        let mut target = unwrap_lines(lines.get(header + 1..question)?);
+        if tool == "Bash" {
+            target.pop();
+        }
+        (!target.is_empty()).then(|| PermissionRequest {
+            tool: tool.to_string(),
+            target: target.join("\n"),
+        })
+    }
+
+    fn compose_prompt(&self, text: &str, files: &[String]) -> String {
+        agent_adapter::mention_files_prompt(text, files)
+    }
+
+    fn parse_output(&self, line: &str) -> Vec<AgentEvent> {
+        let line = line.trim();
+        if line.starts_with('{') {
+            if let Ok(message) = serde_json::from_str::<Value>(line) {
+                return parse_stream_message(&message);
+            }
+        }
+        if let Some(id) = self.resume_hint.captures(line).and_then(|c| c.get(1)) {
+            return vec![AgentEvent::AgentSession {
+                id: id.as_str().to_string(),
+            }];
+        }
+        if let Some(usd) = self.cost.captures(line).and_then(|c| c.get(1)?.as_str().parse().ok()) {
+            return vec![AgentEvent::Cost { usd }];
+        }
+        agent_adapter::parse_throttle(line).into_iter().collect()
+    }
+}
+
+/// One line of a dialog with the box drawn around it removed.
+struct DialogLine<'a> {
+    text: &'a str,
+    /// Spaces between the left border and the text.
+    left: usize,
+    /// Spaces between the text and the right border, if the line has one.
+    right: Option<usize>,
+}
+
+impl<'a> DialogLine<'a> {
+    fn parse(line: &'a str) -> Self {
+        let is_border = |c: char| BOX_CHARS.contains(&c);
+        let line = line.trim_end();
+        let (line, bordered) = match line.strip_suffix(is_border) {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        let line = line.trim_start();
+        let line = line.strip_prefix(is_border).unwrap_or(line);
+        let left = line.len() - line.trim_start_matches(' ').len();
+        let right = bordered.then(|| line.len() - line.trim_end_matches(' ').len());
+        Self {
+            text: line.trim(),
+            left,
+            right,
+        }
+    }
+}
+
+/// Joins the dialog lines the terminal wrapped back into the lines they
+/// were printed as. The box pads its contents equally on both sides, so a
+/// line whose text reaches as close to the right border as the contents
+/// start from the left one was cut at the box's edge and continues on the
+/// next line. Without a right border nothing is joined.
+fn unwrap_lines(lines: &[DialogLine]) -> Vec<String> {
+    let padding = lines.iter().map(|l| l.left).min().unwrap_or(0);
+    let mut joined = Vec::new();
+    let mut continues = false;
+    for line in lines {
+        match joined.last_mut() {
+            Some(last) if continues => *last += line.text,
+            _ => joined.push(line.text.to_string()),
+        }
+        continues = line.right == Some(padding);
+    }
+    joined
+}
+
+/// Maps one `stream-json` message to events. The `system` init message
+/// carries the conversation ID; the closing `result` message carries cost,
+/// token usage, and, for a failed run, the API error. Other `system`
+/// messages and unknown types produce none.
+fn parse_stream_message(message: &Value) -> Vec<AgentEvent> {
+    let blocks = || {
+        message["message"]["content"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+    };
+    match message["type"].as_str() {
+        Some("system") if message["subtype"] == "init" => message["session_id"]
+            .as_str()
+            .map(|id| vec![AgentEvent::AgentSession { id: id.to_string() }])
+            .unwrap_or_default(),
+        Some("assistant") => blocks()
+            .filter_map(|block| match block["type"].as_str()? {
+                "text" => Some(AgentEvent::AssistantText {
+                    text: block["text"].as_str()?.to_string(),
+                }),
+                "tool_use" => Some(AgentEvent::ToolCall {
+                    id: block["id"].as_str()?.to_string(),
+                    name: block["name"].as_str()?.to_string(),
+                    input: block["input"].clone(),
+                }),
+                _ => None,
+            })
+            .collect(),
+        Some("user") => blocks()
+            .filter(|block| block["type"] == "tool_result")
+            .filter_map(|block| {
+                Some(AgentEvent::ToolResult {
+                    tool_use_id: block["tool_use_id"].as_str()?.to_string(),
+                    content: tool_result_text(&block["content"]),
+                    is_error: block["is_error"].as_bool().unwrap_or(false),
+                })
+            })
+            .collect(),
+        Some("result") => {
+            let mut events = Vec::new();
+            if let Some(usd) = message["total_cost_usd"].as_f64() {
+                events.push(AgentEvent::Cost { usd });
+            }
+            let usage = &message["usage"];
+            if usage.is_object() {
+                let tokens = |key: &str| usage[key].as_u64().unwrap_or(0);
+                events.push(AgentEvent::Usage {
+                    input_tokens: tokens("input_tokens")
+                        + tokens("cache_creation_input_tokens")
+                        + tokens("cache_read_input_tokens"),
+                    output_tokens: tokens("output_tokens"),
+                });
+            }
+            if message["is_error"] == true {
+                events.extend(message["result"].as_str().and_then(agent_adapter::parse_throttle_error));
+            }
+            events
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Flattens tool result content, either a string or a list of text blocks,
+/// truncated to `MAX_TOOL_RESULT_CHARS`.
+fn tool_result_text(content: &Value) -> String {
+    let text = match content {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+    text.chars().take(MAX_TOOL_RESULT_CHARS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::approval::{ApprovalDecision, ApprovalPolicy};
+
+    /// Draws `rows` the way Claude Code boxes a dialog 40 columns wide,
+    /// with one column of padding inside the border.
+    fn dialog(rows: &[&str]) -> Vec<String> {
+        let mut lines = vec![format!("╭{}╮", "─".repeat(38))];
+        lines.extend(rows.iter().map(|row| format!("│ {row:<36} │")));
+        lines.push(format!("╰{}╯", "─".repeat(38)));
+        lines
+    }
+
+    fn parse(lines: &[String]) -> Option<PermissionRequest> {
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        ClaudeAdapter::default().parse_permission_prompt(&lines)
+    }
+
+    fn policy(allow: &str) -> ApprovalPolicy {
+        ApprovalPolicy {
+            allow: vec![allow.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn drops_the_description_line_of_a_command() {
+        let lines = dialog(&[
+            "Bash command",
+            "",
+            "  npm test -- --watch=false",
+            "  Run the test suite",
+            "",
+            "Do you want to proceed?",
+            "❯ 1. Yes",
+        ]);
+        let request = parse(&lines).expect("permission prompt");
+        assert_eq!(request.tool, "Bash");
+        assert_eq!(request.target, "npm test -- --watch=false");
+        assert_eq!(policy("Bash(npm test*)").evaluate(&request, None), ApprovalDecision::Approve);
+    }
+
+    #[test]
+    fn keeps_the_lines_of_a_multi_line_command() {
+        let lines = dialog(&[
+            "Bash command",
+            "  npm test",
+            "  rm -rf ~",
+            "  Run the test suite",
+            "Do you want to proceed?",
+        ]);
+        let request = parse(&lines).expect("permission prompt");
+        assert_eq!(request.target, "npm test\nrm -rf ~");
+        assert_eq!(policy("Bash(npm test*)").evaluate(&request, None), ApprovalDecision::Ask);
+    }
+
+    #[test]
+    fn joins_a_wrapped_path() {
+        let lines = dialog(&[
+            "Read file",
+            "  src/components/sessions/terminal",
+            "  /panel.tsx",
+            "Do you want to proceed?",
+        ]);
+        let request = parse(&lines).expect("permission prompt");
+        assert_eq!(request.tool, "Read");
+        assert_eq!(request.target, "src/components/sessions/terminal/panel.tsx");
+    }
+
+    #[test]
+    fn joins_a_wrapped_command_and_description() {
+        let lines = dialog(&[
+            "Bash command",
+            "  npm test -- src/components/sessi",
+            "  ons/terminal",
+            "  Run the tests of the session ter",
+            "  minal",
+            "Do you want to proceed?",
+        ]);
+        let request = parse(&lines).expect("permission prompt");
+        assert_eq!(request.target, "npm test -- src/components/sessions/terminal");
+    }
+}
@@ -0,0 +1,51 @@
+use regex::Regex;
+
+use crate::core::agent_adapter::{self, AgentAdapter, AgentEvent};
+
+/// Codex's exit hint, e.g. `To continue this session, run codex resume 0199...`.
+const RESUME_HINT_PATTERN: &str = r"codex resume ([0-9a-fA-F-]{8,})";
+
+/// Adapter for the `codex` CLI (OpenAI Codex).
+pub struct CodexAdapter {
+    resume_hint: Regex,
+}
+
+impl Default for CodexAdapter {
+    fn default() -> Self {
+        Self {
+            resume_hint: Regex::new(RESUME_HINT_PATTERN).expect("valid resume hint pattern"),
+        }
+    }
+}
+
+impl AgentAdapter for CodexAdapter {
+    fn name(&self) -> &'static str {
+        "codex"
+    }
+
+    fn default_command(&self) -> &'static str {
+        "codex"
+    }
+
+    fn uses_mcp(&self) -> bool {
+        true
+    }
+
+    fn resume_args(&self, agent_session_id: Option<&str>) -> Option<String> {
+        Some(match agent_session_id {
+            Some(id) => format!("resume {id}"),
+            None => "resume --last".to_string(),
+        })
+    }
+
+    fn prompt_patterns(&self) -> &'static [&'static str] {
+        &[r"(?i)allow (command|this command)\?", r"(?i)approve (this|the) (command|patch|edit)"]
+    }
+
+    fn parse_output(&self, line: &str) -> Vec<AgentEvent> {
+        if let Some(id) = self.resume_hint.captures(line).and_then(|c| c.get(1)) {
+            return vec![AgentEvent::AgentSession { id: id.as_str().to_string() }];
+        }
+        agent_adapter::parse_throttle(line).into_iter().collect()
+    }
+}
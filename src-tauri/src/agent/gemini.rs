@@ -0,0 +1,34 @@
+use crate::core::agent_adapter::{self, AgentAdapter, AgentEvent};
+
+/// Adapter for the `gemini` CLI (Gemini CLI).
+pub struct GeminiAdapter;
+
+impl AgentAdapter for GeminiAdapter {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn default_command(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn uses_mcp(&self) -> bool {
+        true
+    }
+
+    fn resume_args(&self, _agent_session_id: Option<&str>) -> Option<String> {
+        Some("--resume latest".to_string())
+    }
+
+    fn compose_prompt(&self, text: &str, files: &[String]) -> String {
+        agent_adapter::mention_files_prompt(text, files)
+    }
+
+    fn prompt_patterns(&self) -> &'static [&'static str] {
+        &[r"(?i)allow (once|always|execution)", r"(?i)waiting for user confirmation"]
+    }
+
+    fn parse_output(&self, line: &str) -> Vec<AgentEvent> {
+        agent_adapter::parse_throttle(line).into_iter().collect()
+    }
+}
@@ -0,0 +1,67 @@
+mod claude;
+mod codex;
+mod gemini;
+
+use std::sync::OnceLock;
+
+use regex::RegexSet;
+
+use crate::core::agent_adapter::AgentAdapter;
+use crate::core::session_manager::AiMode;
+
+struct Registered {
+    adapter: Box<dyn AgentAdapter>,
+    prompts: RegexSet,
+}
+
+/// The built-in agent adapters, with their prompt patterns compiled once.
+pub struct AgentRegistry {
+    adapters: Vec<Registered>,
+}
+
+impl AgentRegistry {
+    fn builtin() -> Self {
+        let adapters: Vec<Box<dyn AgentAdapter>> = vec![
+            Box::new(claude::ClaudeAdapter::default()),
+            Box::new(gemini::GeminiAdapter),
+            Box::new(codex::CodexAdapter::default()),
+        ];
+        Self {
+            adapters: adapters
+                .into_iter()
+                .map(|adapter| Registered {
+                    prompts: RegexSet::new(adapter.prompt_patterns()).expect("valid agent prompt patterns"),
+                    adapter,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the adapter for a session mode, or `None` for `Plain` and
+    /// custom agents.
+    pub fn get(&self, mode: &AiMode) -> Option<&dyn AgentAdapter> {
+        self.find(mode).map(|r| r.adapter.as_ref())
+    }
+
+    /// Returns the compiled prompt patterns of the mode's adapter, if it
+    /// has any.
+    pub fn prompt_patterns(&self, mode: &AiMode) -> Option<&RegexSet> {
+        self.find(mode).map(|r| &r.prompts).filter(|set| !set.is_empty())
+    }
+
+    fn find(&self, mode: &AiMode) -> Option<&Registered> {
+        let name = match mode {
+            AiMode::Claude => "claude",
+            AiMode::Gemini => "gemini",
+            AiMode::Codex => "codex",
+            AiMode::Plain | AiMode::Custom(_) => return None,
+        };
+        self.adapters.iter().find(|r| r.adapter.name() == name)
+    }
+}
+
+/// Returns the process-wide registry of built-in agent adapters.
+pub fn registry() -> &'static AgentRegistry {
+    static REGISTRY: OnceLock<AgentRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(AgentRegistry::builtin)
+}
@@ -0,0 +1,387 @@
+mod stream;
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+
+use crate::core::audit::{AuditLog, AuditSource};
+use crate::core::metrics;
+use crate::core::orchestrator::BranchSpec;
+use crate::core::remote_control::{self, parse_args};
+use crate::commands::session::validate_mode;
+use crate::core::agent_launcher::AgentLauncher;
+use crate::core::session_manager::{AiMode, LaunchProfile};
+use crate::core::storage;
+use crate::core::task_queue::TaskQueue;
+use crate::http::{self, write_body, write_response, Request};
+
+/// Current on-disk format of `api-server.json`.
+const SETTINGS_VERSION: u32 = 1;
+
+/// Port used until the user picks another.
+const DEFAULT_PORT: u16 = 7420;
+
+/// Longest a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How the API server is configured. Disabled by default. It only listens
+/// on 127.0.0.1: the API speaks plain HTTP, so the token would cross the
+/// network in the clear; reach it from other devices through an SSH tunnel.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+/// The API server's settings and, while it runs, its address.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ApiServerStatus {
+    pub settings: ApiSettings,
+    pub listening_on: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SettingsFile {
+    version: u32,
+    settings: ApiSettings,
+}
+
+/// Body of `POST /api/tasks`. Unlike `enqueue_task`, no launch profile is
+/// taken: its startup script, test command, and environment would let any
+/// client with the token run commands. The task gets the repository's
+/// defaults.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EnqueueTaskBody {
+    description: String,
+    repo_path: String,
+    branch_spec: BranchSpec,
+    mode: AiMode,
+}
+
+struct Running {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+/// Optional HTTP API for driving Maestro from scripts, editors, or other
+/// devices, mirroring the core session commands.
+///
+/// Every request needs `Authorization: Bearer <token>`; the stream endpoint
+/// also accepts `?token=`, since browsers cannot set headers on WebSockets.
+/// Bodies and responses are JSON; errors are `{"error": "..."}`.
+///
+/// - `GET /api/sessions[?repo_path=]`, `POST /api/sessions` (as the MCP
+///   `create_session` tool), `GET /api/sessions/{id}`
+/// - `POST /api/sessions/{id}/prompt` with `{text, files?}`
+/// - `GET /api/sessions/{id}/output[?max_bytes=]`: recent plain output
+/// - `GET /api/sessions/{id}/stream`: WebSocket of live terminal output
+/// - `GET /api/tasks`, `POST /api/tasks` with `{description, repo_path,
+///   branch_spec, mode}` (as `enqueue_task`, without a launch profile)
+/// - `GET /metrics`: performance counters in the Prometheus text format
+///   (see `Metrics`), for a scraper configured with the token
+///
+/// Settings, token included, live in `api-server.json` in the data dir,
+/// readable only by the user. Placed in Tauri managed state.
+pub struct ApiServer {
+    app_handle: AppHandle,
+    path: PathBuf,
+    settings: Mutex<ApiSettings>,
+    running: Mutex<Option<Running>>,
+}
+
+impl ApiServer {
+    /// Loads the settings, generating a token on first use. Call `start`
+    /// to serve if enabled.
+    pub fn load(app_handle: AppHandle) -> io::Result<Self> {
+        let path = storage::data_dir().join("api-server.json");
+        let settings = match storage::load_json::<SettingsFile>(&path) {
+            Ok(Some(file))
+                if file.version <= SETTINGS_VERSION && !file.settings.token.is_empty() =>
+            {
+                file.settings
+            }
+            Ok(_) => ApiSettings {
+                enabled: false,
+                port: DEFAULT_PORT,
+                token: http::new_token()?,
+            },
+            Err(e) => return Err(e),
+        };
+        let server = Self {
+            app_handle,
+            path,
+            settings: Mutex::new(settings),
+            running: Mutex::new(None),
+        };
+        server.save(&server.lock_settings())?;
+        Ok(server)
+    }
+
+    /// Returns the settings and where the server is listening.
+    pub fn status(&self) -> ApiServerStatus {
+        ApiServerStatus {
+            settings: self.lock_settings().clone(),
+            listening_on: self.lock_running().as_ref().map(|r| r.addr.to_string()),
+        }
+    }
+
+    /// Starts serving if enabled and not already running.
+    pub fn start(&self) -> io::Result<()> {
+        let settings = self.lock_settings().clone();
+        let mut running = self.lock_running();
+        if !settings.enabled || running.is_some() {
+            return Ok(());
+        }
+        let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, settings.port))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let app = self.app_handle.clone();
+        let token = settings.token;
+        let task = tauri::async_runtime::spawn(async move {
+            let listener = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    log::error!("API server failed to start: {e}");
+                    return;
+                }
+            };
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let app = app.clone();
+                        let token = token.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = serve_connection(app, stream, &token).await {
+                                log::debug!("API connection from {peer} failed: {e}");
+                            }
+                        });
+                    }
+                    Err(e) => log::warn!("API server accept failed: {e}"),
+                }
+            }
+        });
+        *running = Some(Running { addr, task });
+        log::info!("API server listening on {addr}");
+        Ok(())
+    }
+
+    /// Stops accepting connections. Open streams end when their session's
+    /// shell exits or the client disconnects.
+    pub fn stop(&self) {
+        if let Some(running) = self.lock_running().take() {
+            running.task.abort();
+            log::info!("API server on {} stopped", running.addr);
+        }
+    }
+
+    /// Applies new settings, keeping the token, and restarts the server.
+    /// If the new address cannot be bound, the server stays stopped and the
+    /// error is returned; the settings are saved either way.
+    pub fn configure(&self, enabled: bool, port: u16) -> io::Result<ApiServerStatus> {
+        {
+            let mut settings = self.lock_settings();
+            settings.enabled = enabled;
+            settings.port = port;
+            self.save(&settings)?;
+        }
+        self.stop();
+        self.start()?;
+        Ok(self.status())
+    }
+
+    /// Replaces the token, invalidating every client, and restarts the
+    /// server if it was running.
+    pub fn regenerate_token(&self) -> io::Result<ApiServerStatus> {
+        {
+            let mut settings = self.lock_settings();
+            settings.token = http::new_token()?;
+            self.save(&settings)?;
+        }
+        self.stop();
+        self.start()?;
+        Ok(self.status())
+    }
+
+    fn save(&self, settings: &ApiSettings) -> io::Result<()> {
+        let file = SettingsFile {
+            version: SETTINGS_VERSION,
+            settings: settings.clone(),
+        };
+        storage::save_json_private(&self.path, &file)
+    }
+
+    fn lock_settings(&self) -> MutexGuard<'_, ApiSettings> {
+        match self.settings.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn lock_running(&self) -> MutexGuard<'_, Option<Running>> {
+        match self.running.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Handles one request. WebSocket upgrades take the connection over;
+/// everything else is answered and the connection closed.
+async fn serve_connection(app: AppHandle, stream: TcpStream, token: &str) -> io::Result<()> {
+    let peer = stream.peer_addr().ok().map(|addr| addr.ip().to_string());
+    let mut reader = BufReader::new(stream);
+    let read = tokio::time::timeout(REQUEST_TIMEOUT, http::read_request(&mut reader)).await;
+    let request = match read {
+        Ok(read) => match read? {
+            Ok(request) => request,
+            Err(status) => return write_response(reader.get_mut(), status, None).await,
+        },
+        Err(_) => return write_response(reader.get_mut(), "408 Request Timeout", None).await,
+    };
+
+    let authorized = request.has_bearer(token)
+        || (request.path.ends_with("/stream")
+            && request
+                .query_param("token")
+                .is_some_and(|given| http::constant_time_eq(given.as_bytes(), token.as_bytes())));
+    if !authorized {
+        let body = json!({ "error": "Missing or invalid token" });
+        return write_response(reader.get_mut(), "401 Unauthorized", Some(&body)).await;
+    }
+
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    if let (["metrics"], "GET") = (segments.as_slice(), request.method.as_str()) {
+        let text = metrics::registry().prometheus(&app);
+        return write_body(reader.get_mut(), "200 OK", "text/plain; version=0.0.4", &text).await;
+    }
+    if let (["api", "sessions", id, "stream"], "GET") =
+        (segments.as_slice(), request.method.as_str())
+    {
+        let Ok(session_id) = id.parse() else {
+            let body = json!({ "error": format!("Session {id} not found") });
+            return write_response(reader.get_mut(), "404 Not Found", Some(&body)).await;
+        };
+        return stream::serve(app, reader.into_inner(), &request, session_id).await;
+    }
+
+    let result = route(&app, &request, &segments).await;
+    if request.method == "POST" {
+        record_request(&app, &request, &segments, peer.as_deref(), &result);
+    }
+    let (status, body) = match result {
+        Ok(body) => ("200 OK", body),
+        Err((status, message)) => (status, json!({ "error": message })),
+    };
+    write_response(reader.get_mut(), status, Some(&body)).await
+}
+
+type RouteError = (&'static str, String);
+
+/// Records a request that changes sessions or tasks in the audit log, with
+/// the client's address as its origin.
+fn record_request(
+    app: &AppHandle,
+    request: &Request,
+    segments: &[&str],
+    peer: Option<&str>,
+    result: &Result<Value, RouteError>,
+) {
+    let command = match segments {
+        ["api", "sessions"] => "create_session",
+        ["api", "sessions", _, "prompt"] => "send_prompt",
+        ["api", "tasks"] => "enqueue_task",
+        _ => return,
+    };
+    let mut params = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+    if let ["api", "sessions", id, "prompt"] = segments {
+        if let Ok(id) = id.parse::<u32>() {
+            params["session_id"] = json!(id);
+        }
+    }
+    let result = result.as_ref().map_err(|(_, message)| message);
+    app.state::<AuditLog>()
+        .record(AuditSource::Api, peer, command, params, &result);
+}
+
+async fn route(app: &AppHandle, request: &Request, segments: &[&str]) -> Result<Value, RouteError> {
+    let bad_request = |e: String| ("400 Bad Request", e);
+    let not_found = |e: String| ("404 Not Found", e);
+    let body = || -> Result<Value, RouteError> {
+        if request.body.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_slice(&request.body).map_err(|e| bad_request(format!("Invalid JSON: {e}")))
+    };
+    let session_id = |id: &str| -> Result<u32, RouteError> {
+        id.parse()
+            .map_err(|_| not_found(format!("Session {id} not found")))
+    };
+
+    match (request.method.as_str(), segments) {
+        ("GET", ["api", "sessions"]) => {
+            let args = json!({ "repo_path": request.query_param("repo_path") });
+            remote_control::list_sessions(app, parse_args(args).map_err(bad_request)?)
+                .map_err(bad_request)
+        }
+        ("POST", ["api", "sessions"]) => {
+            let args = parse_args(body()?).map_err(bad_request)?;
+            remote_control::create_session(app, args)
+                .await
+                .map_err(bad_request)
+        }
+        ("GET", ["api", "sessions", id]) => {
+            remote_control::get_session(app, session_id(id)?).map_err(not_found)
+        }
+        ("POST", ["api", "sessions", id, "prompt"]) => {
+            let mut args = body()?;
+            args["session_id"] = json!(session_id(id)?);
+            let prompt = remote_control::send_prompt(app, parse_args(args).map_err(bad_request)?)
+                .await
+                .map_err(bad_request)?;
+            Ok(json!({ "prompt": prompt }))
+        }
+        ("GET", ["api", "sessions", id, "output"]) => {
+            let max_bytes = request
+                .query_param("max_bytes")
+                .and_then(|v| v.parse::<usize>().ok());
+            let args = json!({ "session_id": session_id(id)?, "max_bytes": max_bytes });
+            let output =
+                remote_control::read_session_output(app, parse_args(args).map_err(bad_request)?)
+                    .map_err(not_found)?;
+            Ok(json!({ "output": output }))
+        }
+        ("GET", ["api", "tasks"]) => Ok(json!(app.state::<TaskQueue>().tasks())),
+        ("POST", ["api", "tasks"]) => {
+            let task: EnqueueTaskBody = parse_args(body()?).map_err(bad_request)?;
+            if task.repo_path.is_empty() {
+                return Err(bad_request("repo_path must not be empty".to_string()));
+            }
+            validate_mode(&app.state::<AgentLauncher>(), &task.mode).map_err(|e| bad_request(e.message))?;
+            let task = app
+                .state::<TaskQueue>()
+                .enqueue(
+                    task.description,
+                    task.repo_path,
+                    task.branch_spec,
+                    task.mode,
+                    LaunchProfile::default(),
+                )
+                .map_err(bad_request)?;
+            Ok(json!(task))
+        }
+        _ => Err(not_found("Unknown route".to_string())),
+    }
+}
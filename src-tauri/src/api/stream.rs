@@ -0,0 +1,105 @@
+use std::io;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::core::scrollback::Scrollback;
+use crate::core::session_manager::SessionManager;
+use crate::http::{write_response, Request};
+
+/// Scrollback sent when a stream opens, so the client starts with context.
+const BACKLOG_BYTES: usize = 16 * 1024;
+
+/// Messages buffered per stream; output beyond this is dropped for a slow
+/// client rather than held in memory.
+const STREAM_BUFFER: usize = 256;
+
+/// Upgrades the connection to a WebSocket and forwards the session's
+/// terminal output as JSON text messages: first
+/// `{"kind": "backlog", "data"}` with recent output (escape sequences
+/// removed), then `{"kind": "output", "data"}` with raw output as it
+/// arrives, and `{"kind": "exit", "code"}` when the shell exits, after
+/// which the stream closes. Messages from the client are ignored.
+pub(super) async fn serve(
+    app: AppHandle,
+    mut stream: TcpStream,
+    request: &Request,
+    session_id: u32,
+) -> io::Result<()> {
+    let pty_id = match app.state::<SessionManager>().get_session(session_id) {
+        Some(session) => session.pty_id,
+        None => {
+            let body = json!({ "error": format!("Session {session_id} not found") });
+            return write_response(&mut stream, "404 Not Found", Some(&body)).await;
+        }
+    };
+    let Some(pty_id) = pty_id else {
+        let body = json!({ "error": format!("Session {session_id} has no terminal") });
+        return write_response(&mut stream, "409 Conflict", Some(&body)).await;
+    };
+    let is_upgrade = request
+        .header("upgrade")
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    let Some(key) = request.header("sec-websocket-key").filter(|_| is_upgrade) else {
+        let body = json!({ "error": "Expected a WebSocket upgrade" });
+        return write_response(&mut stream, "426 Upgrade Required", Some(&body)).await;
+    };
+
+    let head = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    stream.write_all(head.as_bytes()).await?;
+    let mut ws = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+
+    let (tx, mut rx) = mpsc::channel::<(Message, bool)>(STREAM_BUFFER);
+    let output_tx = tx.clone();
+    let output_id = app.listen(format!("pty-output-{pty_id}"), move |event| {
+        if let Ok(data) = serde_json::from_str::<String>(event.payload()) {
+            let message = json!({ "kind": "output", "data": data });
+            let _ = output_tx.try_send((Message::text(message.to_string()), false));
+        }
+    });
+    let exit_id = app.listen(format!("pty-exit-{pty_id}"), move |event| {
+        let code = serde_json::from_str::<Option<u32>>(event.payload())
+            .ok()
+            .flatten();
+        let message = json!({ "kind": "exit", "code": code });
+        let _ = tx.try_send((Message::text(message.to_string()), true));
+    });
+
+    let backlog = app.state::<Scrollback>().tail(pty_id, BACKLOG_BYTES);
+    let mut result = ws
+        .send(Message::text(
+            json!({ "kind": "backlog", "data": backlog }).to_string(),
+        ))
+        .await;
+    while result.is_ok() {
+        tokio::select! {
+            message = rx.recv() => {
+                let Some((message, last)) = message else { break };
+                result = ws.send(message).await;
+                if last {
+                    break;
+                }
+            }
+            incoming = ws.next() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+    app.unlisten(output_id);
+    app.unlisten(exit_id);
+
+    let _ = ws.close(None).await;
+    result.map_err(io::Error::other)
+}
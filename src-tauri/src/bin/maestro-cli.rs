@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(maestro_linux_lib::run_cli(std::env::args().skip(1).collect()))
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::core::clock;
+use crate::core::process_manager::ProcessManager;
+use crate::core::remote_control::{self, CreateSessionArgs};
+use crate::core::session_manager::{AiMode, SessionActivity, SessionManager, SessionStatus, StatusCause};
+use crate::core::storage;
+
+const USAGE: &str = "\
+Usage: maestro-cli [--data-dir DIR] <command>
+
+Commands:
+  status [--json]      Print the saved sessions
+  create --repo PATH --branch NAME [--base REF] [--agent NAME]
+         [--prompt TEXT] [--timeout SECS] [--idle SECS]
+                       Run one session until its agent finishes
+  run JOB.toml         Run the tasks of a job file until their agents finish
+  help                 Print this message
+
+Sessions are saved in the desktop app's data directory (or DIR), where the
+app shows them afterwards. `create` and `run` refuse to start while the app
+or another run uses the data directory, and need a display, e.g. through
+xvfb-run.
+";
+
+/// Time between checks of the sessions a job waits for.
+const POLL_INTERVAL_SECS: u64 = 1;
+
+/// Longest a job runs unless it sets `timeout_secs`.
+const DEFAULT_TIMEOUT_SECS: u64 = 60 * 60;
+
+/// Silence after which a working agent counts as finished unless the job
+/// sets `idle_secs`.
+const DEFAULT_IDLE_SECS: u64 = 5 * 60;
+
+/// A job file for `maestro-cli run`, in TOML:
+///
+/// ```toml
+/// repo = "../app"        # relative to the job file
+/// agent = "claude"       # for tasks that do not name one
+/// timeout_secs = 3600    # for the whole job
+/// idle_secs = 300        # silence after which an agent counts as finished
+///
+/// [[tasks]]
+/// branch = "fix-login"
+/// base_ref = "main"      # create the branch; otherwise check it out
+/// prompt = "Fix the redirect loop on the login page"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Job {
+    repo: PathBuf,
+    agent: Option<String>,
+    timeout_secs: Option<u64>,
+    idle_secs: Option<u64>,
+    tasks: Vec<JobTask>,
+}
+
+/// One session of a job; see `remote_control::CreateSessionArgs`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobTask {
+    branch: String,
+    base_ref: Option<String>,
+    agent: Option<String>,
+    prompt: Option<String>,
+}
+
+enum Command {
+    Help,
+    Status { json: bool },
+    Run(Job),
+}
+
+/// How a task's session ended up when the job stopped waiting for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// The agent or its shell exited cleanly.
+    Finished,
+    /// The agent went quiet for the job's `idle_secs`.
+    Quiet,
+    NeedsInput,
+    Failed,
+    TimedOut,
+    Removed,
+}
+
+impl Outcome {
+    fn is_success(self) -> bool {
+        matches!(self, Outcome::Finished | Outcome::Quiet)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Finished => "finished",
+            Outcome::Quiet => "finished (quiet)",
+            Outcome::NeedsInput => "waiting for input",
+            Outcome::Failed => "failed",
+            Outcome::TimedOut => "timed out",
+            Outcome::Removed => "removed",
+        }
+    }
+}
+
+struct TaskRun {
+    branch: String,
+    session_id: u32,
+    status: Option<SessionStatus>,
+    started: bool,
+    outcome: Option<Outcome>,
+}
+
+/// Runs `maestro-cli` with its arguments, program name excluded, and
+/// returns the exit code: 0 on success, 1 if a task did not finish, 2 on
+/// bad usage.
+pub fn run(args: Vec<String>) -> i32 {
+    let mut args = args.into_iter().peekable();
+    if args.next_if(|arg| arg == "--data-dir").is_some() {
+        let Some(dir) = args.next() else {
+            return usage_error("--data-dir needs a value");
+        };
+        // Read by `storage::data_dir`; set before any state is loaded.
+        std::env::set_var("MAESTRO_DATA_DIR", dir);
+    }
+    match parse(args.collect()) {
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            0
+        }
+        Ok(Command::Status { json }) => status(json),
+        Ok(Command::Run(job)) => {
+            if let Err(e) = storage::lock_data_dir() {
+                eprintln!("maestro-cli: {e}");
+                return 1;
+            }
+            crate::run_headless(move |app| run_job(app, job))
+        }
+        Err(e) => usage_error(&e),
+    }
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("maestro-cli: {message}\n\n{USAGE}");
+    2
+}
+
+fn parse(args: Vec<String>) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Help);
+    };
+    match command.as_str() {
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        "status" => match rest {
+            [] => Ok(Command::Status { json: false }),
+            [flag] if flag == "--json" => Ok(Command::Status { json: true }),
+            _ => Err("status takes only --json".to_string()),
+        },
+        "create" => parse_create(rest).map(Command::Run),
+        "run" => match rest {
+            [path] => load_job(Path::new(path)).map(Command::Run),
+            _ => Err("run takes one job file".to_string()),
+        },
+        other => Err(format!("unknown command '{other}'")),
+    }
+}
+
+/// Turns the flags of `create` into a job with a single task.
+fn parse_create(args: &[String]) -> Result<Job, String> {
+    let mut flags = HashMap::new();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let name = match flag.as_str() {
+            "--repo" | "--branch" | "--base" | "--agent" | "--prompt" | "--timeout" | "--idle" => flag.as_str(),
+            _ => return Err(format!("unknown option '{flag}' for create")),
+        };
+        let value = args.next().ok_or_else(|| format!("{name} needs a value"))?;
+        flags.insert(name, value.clone());
+    }
+    let secs = |name: &str| -> Result<Option<u64>, String> {
+        flags
+            .get(name)
+            .map(|v| v.parse().map_err(|_| format!("{name} must be a number of seconds")))
+            .transpose()
+    };
+    let repo = flags.get("--repo").ok_or("create needs --repo")?;
+    let repo = std::path::absolute(repo).map_err(|e| format!("Invalid repository path {repo}: {e}"))?;
+    Ok(Job {
+        repo,
+        agent: None,
+        timeout_secs: secs("--timeout")?,
+        idle_secs: secs("--idle")?,
+        tasks: vec![JobTask {
+            branch: flags.get("--branch").ok_or("create needs --branch")?.clone(),
+            base_ref: flags.get("--base").cloned(),
+            agent: flags.get("--agent").cloned(),
+            prompt: flags.get("--prompt").cloned(),
+        }],
+    })
+}
+
+/// Reads a job file, resolving its `repo` against the file's directory.
+fn load_job(path: &Path) -> Result<Job, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let mut job: Job = toml::from_str(&text).map_err(|e| format!("Invalid job file {}: {e}", path.display()))?;
+    if job.tasks.is_empty() {
+        return Err(format!("{} has no tasks", path.display()));
+    }
+    if job.repo.is_relative() {
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        job.repo = std::path::absolute(dir.join(&job.repo))
+            .map_err(|e| format!("Invalid repository path {}: {e}", job.repo.display()))?;
+    }
+    Ok(job)
+}
+
+/// Prints the sessions saved in the data directory, as last persisted.
+fn status(json: bool) -> i32 {
+    let sessions = SessionManager::load().all_sessions();
+    if json {
+        return match serde_json::to_string_pretty(&sessions) {
+            Ok(text) => {
+                println!("{text}");
+                0
+            }
+            Err(e) => {
+                eprintln!("maestro-cli: {e}");
+                1
+            }
+        };
+    }
+    if sessions.is_empty() {
+        println!("No sessions");
+        return 0;
+    }
+    println!("{:>4}  {:<10}  {:<8}  {:<32}  {:>7}  TITLE", "ID", "STATUS", "AGENT", "BRANCH", "COST");
+    for session in sessions {
+        let cost = session.cost_usd.map(|usd| format!("${usd:.2}")).unwrap_or_default();
+        println!(
+            "{:>4}  {:<10}  {:<8}  {:<32}  {:>7}  {}",
+            session.id,
+            format!("{:?}", session.status),
+            agent_name(&session.mode),
+            session.branch.as_deref().unwrap_or("-"),
+            cost,
+            session.title.as_deref().unwrap_or(""),
+        );
+    }
+    0
+}
+
+fn agent_name(mode: &AiMode) -> &str {
+    match mode {
+        AiMode::Claude => "claude",
+        AiMode::Gemini => "gemini",
+        AiMode::Codex => "codex",
+        AiMode::Plain => "plain",
+        AiMode::Custom(name) => name,
+    }
+}
+
+/// Creates a session per task and waits until every agent has finished,
+/// needs input, failed, or the job timed out, printing status changes as
+/// they happen. The shells are then closed; the sessions stay saved and
+/// can be resumed in the app.
+async fn run_job(app: AppHandle, job: Job) -> i32 {
+    let timeout_secs = job.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+    let idle_secs = job.idle_secs.unwrap_or(DEFAULT_IDLE_SECS) as i64;
+    let repo_path = job.repo.to_string_lossy().into_owned();
+    let mut runs = Vec::new();
+    let mut failed = false;
+    for task in job.tasks {
+        let args = CreateSessionArgs {
+            repo_path: repo_path.clone(),
+            branch: task.branch.clone(),
+            base_ref: task.base_ref,
+            agent: task.agent.or_else(|| job.agent.clone()),
+            prompt: task.prompt,
+        };
+        match remote_control::start_session(&app, args).await {
+            Ok(session) => {
+                let branch = session.branch.unwrap_or(task.branch);
+                println!("[{branch}] created session {}", session.id);
+                runs.push(TaskRun {
+                    branch,
+                    session_id: session.id,
+                    status: None,
+                    started: false,
+                    outcome: None,
+                });
+            }
+            Err(e) => {
+                eprintln!("[{}] failed to create a session: {e}", task.branch);
+                failed = true;
+            }
+        }
+    }
+
+    let sessions = app.state::<SessionManager>();
+    let deadline = clock::now_secs() + timeout_secs as i64;
+    loop {
+        let now = clock::now_secs();
+        let activity: HashMap<u32, SessionActivity> = sessions
+            .activity()
+            .into_iter()
+            .map(|a| (a.session_id, a))
+            .collect();
+        for run in runs.iter_mut().filter(|r| r.outcome.is_none()) {
+            let Some(activity) = activity.get(&run.session_id) else {
+                run.outcome = Some(Outcome::Removed);
+                continue;
+            };
+            if run.status != Some(activity.status) {
+                println!("[{}] {:?}", run.branch, activity.status);
+                run.status = Some(activity.status);
+            }
+            run.started |= activity.status == SessionStatus::Working;
+            let quiet_since = activity.last_output_at.unwrap_or(0).max(activity.status_since);
+            run.outcome = match activity.status {
+                SessionStatus::Done => Some(Outcome::Finished),
+                SessionStatus::Error => Some(Outcome::Failed),
+                SessionStatus::Idle if run.started => Some(Outcome::Finished),
+                SessionStatus::Working if now - quiet_since >= idle_secs => Some(Outcome::Quiet),
+                SessionStatus::NeedsInput if activity.secs_in_status >= idle_secs => Some(Outcome::NeedsInput),
+                _ => None,
+            };
+        }
+        if runs.iter().all(|r| r.outcome.is_some()) {
+            break;
+        }
+        if now >= deadline {
+            for run in runs.iter_mut().filter(|r| r.outcome.is_none()) {
+                run.outcome = Some(Outcome::TimedOut);
+            }
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+    }
+
+    let processes = app.state::<ProcessManager>();
+    println!();
+    for run in &runs {
+        let outcome = run.outcome.unwrap_or(Outcome::TimedOut);
+        println!("{:>4}  {:<32}  {}", run.session_id, run.branch, outcome.label());
+        failed |= !outcome.is_success();
+        let Some(session) = sessions.get_session(run.session_id) else {
+            continue;
+        };
+        if let Some(pty_id) = session.pty_id {
+            // Unbound first so the shell's exit does not overwrite the outcome
+            sessions.unbind_pty(run.session_id);
+            if processes.has_session(pty_id) {
+                if let Err(e) = processes.kill_session(pty_id).await {
+                    eprintln!("[{}] failed to close the shell: {e}", run.branch);
+                }
+            }
+        }
+        let status = if outcome.is_success() {
+            SessionStatus::Idle
+        } else {
+            SessionStatus::Error
+        };
+        if !matches!(session.status, SessionStatus::Done | SessionStatus::Idle) || !outcome.is_success() {
+            sessions.update_status_with_detail(
+                run.session_id,
+                status,
+                StatusCause::Manual,
+                Some(format!("maestro-cli: {}", outcome.label())),
+            );
+        }
+    }
+    i32::from(failed)
+}
@@ -0,0 +1,40 @@
+use tauri::{AppHandle, Manager};
+
+use crate::api::{ApiServer, ApiServerStatus};
+use crate::core::AppError;
+
+fn server(app_handle: &AppHandle) -> Result<tauri::State<'_, ApiServer>, AppError> {
+    app_handle
+        .try_state::<ApiServer>()
+        .ok_or_else(|| AppError::from("API server is unavailable"))
+}
+
+/// Exposes `ApiServer::status` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_api_server(app_handle: AppHandle) -> Result<ApiServerStatus, AppError> {
+    Ok(server(&app_handle)?.status())
+}
+
+/// Exposes `ApiServer::configure` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn configure_api_server(
+    app_handle: AppHandle,
+    enabled: bool,
+    port: u16,
+) -> Result<ApiServerStatus, AppError> {
+    server(&app_handle)?
+        .configure(enabled, port)
+        .map_err(|e| format!("Failed to start API server on port {port}: {e}"))
+        .map_err(AppError::from)
+}
+
+/// Exposes `ApiServer::regenerate_token` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn regenerate_api_token(app_handle: AppHandle) -> Result<ApiServerStatus, AppError> {
+    server(&app_handle)?
+        .regenerate_token()
+        .map_err(AppError::from)
+}
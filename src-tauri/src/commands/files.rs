@@ -0,0 +1,235 @@
+use std::path::Path;
+
+use serde_json::json;
+use tauri::{AppHandle, State, Window};
+
+use crate::core::audit::AuditLog;
+use crate::core::file_stream::{FileChunk, FileLines, LineIndexCache};
+use crate::core::files::{self, DirListing, FileContent, ListDirFilter, RecentFile};
+use crate::core::fs_watcher::FsWatcher;
+use crate::core::opener;
+use crate::core::search::{self, SearchOptions, SearchSummary};
+use crate::core::AppError;
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_manager::WorktreeManager;
+use crate::git::FileDiff;
+
+/// Exposes `files::list_dir` to the frontend.
+/// Lists a directory under `root` (usually a worktree) for the project
+/// tree. `path` defaults to `root` itself and `depth` to one level.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_dir(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    root: String,
+    path: Option<String>,
+    depth: Option<u32>,
+    filter: Option<ListDirFilter>,
+) -> Result<DirListing, AppError> {
+    if root.is_empty() {
+        return Err(AppError::invalid_argument("root must not be empty"));
+    }
+    files::list_dir(
+        &sessions,
+        &worktrees,
+        Path::new(&root),
+        path.as_deref().unwrap_or(""),
+        depth.unwrap_or(1),
+        filter.unwrap_or_default(),
+    )
+    .await
+    .map_err(AppError::from)
+}
+
+/// Exposes `files::recent_files` to the frontend.
+/// Returns the files changed on disk most recently in a worktree, whether
+/// or not they are staged.
+#[tauri::command]
+#[specta::specta]
+pub async fn recent_files(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    worktree_path: String,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<RecentFile>, AppError> {
+    if worktree_path.is_empty() {
+        return Err(AppError::invalid_argument("worktree_path must not be empty"));
+    }
+    files::recent_files(&sessions, &worktrees, Path::new(&worktree_path), since, limit)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `files::diff_files` to the frontend.
+/// Returns the hunks between two files, tracked by git or not.
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_files(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    path_a: String,
+    path_b: String,
+) -> Result<FileDiff, AppError> {
+    files::diff_files(&sessions, &worktrees, Path::new(&path_a), Path::new(&path_b))
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `files::read_file` to the frontend.
+/// Only files inside open repositories and worktrees can be read.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_file(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    path: String,
+    max_bytes: Option<u64>,
+) -> Result<FileContent, AppError> {
+    files::read_file(&sessions, &worktrees, Path::new(&path), max_bytes)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `LineIndexCache::read_file_stream` to the frontend.
+/// Reads a byte range of a large file; page through it from `end`.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_file_stream(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    indexes: State<'_, LineIndexCache>,
+    path: String,
+    offset: u64,
+    len: u64,
+) -> Result<FileChunk, AppError> {
+    indexes
+        .read_file_stream(&sessions, &worktrees, Path::new(&path), offset, len)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `LineIndexCache::read_file_lines` to the frontend.
+/// Reads lines of a large file by number.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_file_lines(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    indexes: State<'_, LineIndexCache>,
+    path: String,
+    start_line: u64,
+    count: u64,
+) -> Result<FileLines, AppError> {
+    indexes
+        .read_file_lines(&sessions, &worktrees, Path::new(&path), start_line, count)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `files::write_file` to the frontend.
+/// Only files inside open repositories and worktrees can be written.
+#[tauri::command]
+#[specta::specta]
+pub async fn write_file(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    path: String,
+    content: String,
+) -> Result<FileContent, AppError> {
+    // The content itself is too large to keep for every save
+    let params = json!({ "path": path, "bytes": content.len() });
+    audit
+        .track(window.label(), "write_file", params, async {
+            files::write_file(&sessions, &worktrees, Path::new(&path), &content)
+                .await
+                .map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `search::search_repo` to the frontend.
+/// Listen on `search-results` for matches, tagged with `search_id`; the
+/// returned summary marks the end of the search.
+#[tauri::command]
+#[specta::specta]
+pub async fn search_repo(
+    app_handle: AppHandle,
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    search_id: u32,
+    root: String,
+    query: String,
+    options: Option<SearchOptions>,
+) -> Result<SearchSummary, AppError> {
+    if root.is_empty() {
+        return Err(AppError::invalid_argument("root must not be empty"));
+    }
+    search::search_repo(
+        &app_handle,
+        &sessions,
+        &worktrees,
+        search_id,
+        Path::new(&root),
+        &query,
+        options.unwrap_or_default(),
+    )
+    .await
+    .map_err(AppError::from)
+}
+
+/// Exposes `FsWatcher::watch` to the frontend.
+/// Listen on `fs-changed-{session_id}` for the changed paths.
+#[tauri::command]
+#[specta::specta]
+pub async fn watch_worktree(
+    sessions: State<'_, SessionManager>,
+    watcher: State<'_, FsWatcher>,
+    session_id: u32,
+) -> Result<(), AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    watcher.watch(&session).map_err(AppError::from)
+}
+
+/// Exposes `FsWatcher::unwatch` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn unwatch_worktree(
+    watcher: State<'_, FsWatcher>,
+    session_id: u32,
+) -> Result<bool, AppError> {
+    Ok(watcher.unwatch(session_id))
+}
+
+/// Exposes `opener::open_in_editor` to the frontend.
+/// Returns the program the file was opened with.
+#[tauri::command]
+#[specta::specta]
+pub async fn open_in_editor(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    path: String,
+    line: Option<u32>,
+) -> Result<String, AppError> {
+    opener::open_in_editor(&sessions, &worktrees, Path::new(&path), line)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `opener::reveal_in_file_manager` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn reveal_in_file_manager(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    path: String,
+) -> Result<(), AppError> {
+    opener::reveal_in_file_manager(&sessions, &worktrees, Path::new(&path))
+        .await
+        .map_err(AppError::from)
+}
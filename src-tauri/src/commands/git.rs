@@ -1,9 +1,16 @@
 use std::path::PathBuf;
 
+use serde_json::json;
+use tauri::{State, Window};
+
+use crate::core::audit::AuditLog;
+use crate::core::session_lock::OperationLock;
+use crate::core::session_manager::SessionManager;
+use crate::core::AppError;
 use crate::git::{BranchInfo, CommitInfo, Git, GitError, WorktreeInfo};
 
 /// Returns `Err(GitError::NotARepo)` if the given path string is empty.
-fn validate_repo_path(repo_path: &str) -> Result<(), GitError> {
+pub(crate) fn validate_repo_path(repo_path: &str) -> Result<(), GitError> {
     if repo_path.is_empty() {
         return Err(GitError::NotARepo {
             path: PathBuf::from(""),
@@ -15,82 +22,122 @@ fn validate_repo_path(repo_path: &str) -> Result<(), GitError> {
 /// Exposes `Git::list_branches` to the frontend.
 /// Returns all local and remote branches (excluding HEAD pointer entries).
 #[tauri::command]
-pub async fn git_branches(repo_path: String) -> Result<Vec<BranchInfo>, GitError> {
+#[specta::specta]
+pub async fn git_branches(repo_path: String) -> Result<Vec<BranchInfo>, AppError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.list_branches().await
+    git.list_branches().await.map_err(AppError::from)
 }
 
 /// Exposes `Git::current_branch` to the frontend.
 /// Returns the branch name, or a short commit hash if HEAD is detached.
 #[tauri::command]
-pub async fn git_current_branch(repo_path: String) -> Result<String, GitError> {
+#[specta::specta]
+pub async fn git_current_branch(repo_path: String) -> Result<String, AppError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.current_branch().await.map_err(AppError::from)
+}
+
+/// Exposes `Git::default_branch` to the frontend.
+/// Returns the branch `origin/HEAD` points to, or a conventional fallback.
+#[tauri::command]
+#[specta::specta]
+pub async fn git_default_branch(repo_path: String) -> Result<String, AppError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.current_branch().await
+    git.default_branch().await.map_err(AppError::from)
 }
 
 /// Exposes `Git::uncommitted_count` to the frontend.
 /// Returns the number of dirty files (staged + unstaged + untracked).
 #[tauri::command]
-pub async fn git_uncommitted_count(repo_path: String) -> Result<usize, GitError> {
+#[specta::specta]
+pub async fn git_uncommitted_count(repo_path: String) -> Result<usize, AppError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.uncommitted_count().await
+    git.uncommitted_count().await.map_err(AppError::from)
 }
 
 /// Exposes `Git::worktree_list` to the frontend.
 /// Returns all worktrees (including the main one) with path, HEAD, and branch info.
 #[tauri::command]
-pub async fn git_worktree_list(repo_path: String) -> Result<Vec<WorktreeInfo>, GitError> {
+#[specta::specta]
+pub async fn git_worktree_list(repo_path: String) -> Result<Vec<WorktreeInfo>, AppError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.worktree_list().await
+    git.worktree_list().await.map_err(AppError::from)
 }
 
 /// Exposes `Git::worktree_add` to the frontend.
 /// Creates a new worktree at `path`, optionally on a new branch from `checkout_ref`.
 #[tauri::command]
+#[specta::specta]
 pub async fn git_worktree_add(
+    window: Window,
+    audit: State<'_, AuditLog>,
     repo_path: String,
     path: String,
     new_branch: Option<String>,
     checkout_ref: Option<String>,
-) -> Result<WorktreeInfo, GitError> {
-    validate_repo_path(&repo_path)?;
-    let git = Git::new(&repo_path);
-    let wt_path = PathBuf::from(&path);
-    git.worktree_add(
-        &wt_path,
-        new_branch.as_deref(),
-        checkout_ref.as_deref(),
-    )
-    .await
+) -> Result<WorktreeInfo, AppError> {
+    let params = json!({
+        "repo_path": repo_path,
+        "path": path,
+        "new_branch": new_branch,
+        "checkout_ref": checkout_ref,
+    });
+    audit
+        .track(window.label(), "git_worktree_add", params, async {
+            validate_repo_path(&repo_path)?;
+            let git = Git::new(&repo_path);
+            let wt_path = PathBuf::from(&path);
+            git.worktree_add(
+                &wt_path,
+                new_branch.as_deref(),
+                checkout_ref.as_deref(),
+            )
+            .await
+            .map_err(AppError::from)
+        })
+        .await
 }
 
 /// Exposes `Git::worktree_remove` to the frontend.
 /// Removes a worktree directory; `force` bypasses uncommitted-changes checks.
+/// Fails with `Busy` while another operation holds the worktree.
 #[tauri::command]
+#[specta::specta]
 pub async fn git_worktree_remove(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    sessions: State<'_, SessionManager>,
     repo_path: String,
     path: String,
     force: bool,
-) -> Result<(), GitError> {
-    validate_repo_path(&repo_path)?;
-    let git = Git::new(&repo_path);
-    let wt_path = PathBuf::from(&path);
-    git.worktree_remove(&wt_path, force).await
+) -> Result<(), AppError> {
+    let params = json!({ "repo_path": repo_path, "path": path, "force": force });
+    audit
+        .track(window.label(), "git_worktree_remove", params, async {
+            validate_repo_path(&repo_path)?;
+            let git = Git::new(&repo_path);
+            let wt_path = PathBuf::from(&path);
+            let _lock = OperationLock::acquire(&sessions, "remove", &[&wt_path])?;
+            git.worktree_remove(&wt_path, force).await.map_err(AppError::from)
+        })
+        .await
 }
 
 /// Exposes `Git::commit_log` to the frontend.
 /// Returns up to `max_count` commits in topological order across all or current branch.
 #[tauri::command]
+#[specta::specta]
 pub async fn git_commit_log(
     repo_path: String,
     max_count: usize,
     all_branches: bool,
-) -> Result<Vec<CommitInfo>, GitError> {
+) -> Result<Vec<CommitInfo>, AppError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.commit_log(max_count, all_branches).await
+    git.commit_log(max_count, all_branches).await.map_err(AppError::from)
 }
@@ -0,0 +1,36 @@
+use serde_json::json;
+use tauri::{AppHandle, Manager, State, Window};
+
+use crate::core::audit::AuditLog;
+use crate::core::deep_link::{self, TaskProposal};
+use crate::core::launch::{LaunchRequest, LaunchRequests};
+use crate::core::task_queue::QueuedTask;
+use crate::core::AppError;
+
+/// Exposes `LaunchRequests::take` to the frontend.
+/// Returns what Maestro's own command line asked to open, once its
+/// `maestro://` links are carried out; later launches arrive on
+/// `launch-request`.
+#[tauri::command]
+#[specta::specta]
+pub async fn take_launch_request(app_handle: AppHandle) -> Result<Option<LaunchRequest>, AppError> {
+    Ok(app_handle.try_state::<LaunchRequests>().and_then(|requests| requests.take()))
+}
+
+/// Exposes `deep_link::confirm_task` to the frontend.
+/// Queues the task of a `maestro://task` link once the user accepted it.
+#[tauri::command]
+#[specta::specta]
+pub async fn confirm_deep_link_task(
+    window: Window,
+    app_handle: AppHandle,
+    audit: State<'_, AuditLog>,
+    proposal: TaskProposal,
+) -> Result<QueuedTask, AppError> {
+    let params = json!({ "repo_path": proposal.repo_path, "description": proposal.prompt });
+    audit
+        .track(window.label(), "confirm_deep_link_task", params, async {
+            deep_link::confirm_task(&app_handle, proposal).await.map_err(AppError::from)
+        })
+        .await
+}
@@ -0,0 +1,84 @@
+use tauri::{AppHandle, State};
+
+use crate::core::audit::{AuditEntry, AuditFilter, AuditLog};
+use crate::core::diagnostics::{self, DiagnosticBundle};
+use crate::core::healthcheck::{self, HealthReport};
+use crate::core::logging::{self, LogLevel, LogRecord};
+use crate::core::memory_budget::{MemoryBudget, MemoryUsage};
+use crate::core::metrics::{self, MetricsSnapshot};
+use crate::core::settings::SettingsStore;
+use crate::core::AppError;
+
+/// Records `get_recent_logs` returns when the caller gives no limit.
+const DEFAULT_LOG_LIMIT: usize = 500;
+
+/// Exposes `logging::recent` to the frontend.
+/// Returns the latest records at `level` (default `info`) or more severe,
+/// oldest first. Older records are in the files under `logging::log_dir`.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_recent_logs(
+    level: Option<LogLevel>,
+    limit: Option<usize>,
+) -> Result<Vec<LogRecord>, AppError> {
+    Ok(logging::recent(level.unwrap_or_default(), limit.unwrap_or(DEFAULT_LOG_LIMIT)))
+}
+
+/// Exposes `logging::set_level` to the frontend.
+/// The level is saved in the settings, so it also applies after a restart;
+/// a level set through `RUST_LOG` takes precedence.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_log_level(settings: State<'_, SettingsStore>, level: LogLevel) -> Result<(), AppError> {
+    settings.modify(|s| s.log_level = level)?;
+    Ok(())
+}
+
+/// Exposes `diagnostics::create_bundle` to the frontend.
+/// Returns where the zip was written, to reveal it or attach it to a bug
+/// report.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_diagnostic_bundle() -> Result<DiagnosticBundle, AppError> {
+    diagnostics::create_bundle().await.map_err(AppError::from)
+}
+
+/// Exposes `healthcheck::run_healthcheck` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_healthcheck(app_handle: AppHandle) -> Result<HealthReport, AppError> {
+    Ok(healthcheck::run_healthcheck(&app_handle).await)
+}
+
+/// Exposes `AuditLog::query` to the frontend.
+/// Returns the recorded commands matching `filter` (all by default), newest
+/// first.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_audit_log(
+    audit: State<'_, AuditLog>,
+    filter: Option<AuditFilter>,
+) -> Result<Vec<AuditEntry>, AppError> {
+    let audit = audit.inner().clone();
+    tokio::task::spawn_blocking(move || audit.query(&filter.unwrap_or_default()))
+        .await
+        .map_err(|e| AppError::from(format!("Failed to read the audit log: {e}")))?
+        .map_err(AppError::from)
+}
+
+/// Exposes `MemoryBudget::usage` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_memory_usage(
+    app_handle: AppHandle,
+    budget: State<'_, MemoryBudget>,
+) -> Result<MemoryUsage, AppError> {
+    Ok(budget.usage(&app_handle))
+}
+
+/// Exposes `Metrics::snapshot` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_metrics(app_handle: AppHandle) -> Result<MetricsSnapshot, AppError> {
+    Ok(metrics::registry().snapshot(&app_handle))
+}
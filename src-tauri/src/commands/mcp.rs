@@ -0,0 +1,12 @@
+use tauri::{AppHandle, Manager};
+
+use crate::core::AppError;
+use crate::mcp::McpServerInfo;
+
+/// Returns the MCP server's URL and bearer token, or `None` if it failed to
+/// start.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_mcp_server_info(app_handle: AppHandle) -> Result<Option<McpServerInfo>, AppError> {
+    Ok(app_handle.try_state::<McpServerInfo>().map(|info| info.inner().clone()))
+}
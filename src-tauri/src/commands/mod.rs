@@ -1,3 +1,13 @@
+pub mod api;
+pub mod files;
 pub mod git;
+pub mod launch;
+pub mod logs;
+pub mod mcp;
 pub mod session;
+pub mod settings;
+pub mod snippets;
+pub mod startup;
 pub mod terminal;
+pub mod windows;
+pub mod worktree;
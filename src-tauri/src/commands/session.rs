@@ -1,59 +1,1293 @@
-use tauri::State;
+use std::path::Path;
 
-use crate::core::session_manager::{AiMode, SessionConfig, SessionManager, SessionStatus};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+use crate::core::activity_report::{self, ActivityReport, ReportRange};
+use crate::core::agent_adapter::AgentEvent;
+use crate::core::agent_launcher::{AgentCommands, AgentLauncher, CustomAgent};
+use crate::core::agent_output;
+use crate::core::approval::ApprovalPolicy;
+use crate::core::audit::AuditLog;
+use crate::core::auto_push::AutoPushConfig;
+use crate::core::board::{self, BoardState};
+use crate::core::ci_checks::{BranchChecks, CheckCache};
+use crate::core::guardrails::{GuardrailPolicy, Guardrails, HeldCommand};
+use crate::core::forks::{self, ForkInfo};
+use crate::core::handoff::{self, CopyHandoff, PatchHandoff};
+use crate::core::harvest::{ResultHarvester, SessionResult};
+use crate::core::issues;
+use crate::core::orchestrator::{
+    self, BranchSpec, FanoutResult, FanoutVariant, SessionTeardown, VariantResult,
+};
+use crate::core::pr_description::{self, PrDraft, PrTemplates};
+use crate::core::provider_usage::{ProviderUsage, UsageTracker};
+use crate::core::pull_request::{self, PrFeedback, PrState, PullRequest, PullRequestSummary};
+use crate::core::retention::{self, CleanupCandidate, CleanupReport, RetentionManager, RetentionPolicy};
+use crate::core::scheduler::{LaunchOutcome, LaunchScheduler};
+use crate::core::scratchpad::{Scratchpad, ScratchpadStore};
+use crate::core::task_queue::{QueuedTask, TaskQueue};
+use crate::core::session_archive::{ArchiveFilter, ArchivedSession, SessionArchive};
+use crate::core::session_lock::OperationLock;
+use crate::core::settings::SettingsStore;
+use crate::core::transcript::{TranscriptEntry, TranscriptMatch, TranscriptStore};
+use crate::core::watchdog::{AgentWatchdog, WatchdogPolicy};
+use crate::core::workspace_search::{self, WorkspaceHit};
+use crate::core::error::ErrorCode;
+use crate::core::{AppError, ProcessManager};
+use crate::core::session_manager::{
+    AiMode, LaunchProfile, SessionActivity, SessionConfig, SessionDetailsPatch, SessionHistory,
+    SessionManager, SessionStatus, StatusCause,
+};
+use crate::core::worktree_manager::WorktreeManager;
+use crate::core::worktree_progress::{self, WorktreeProgress};
 
 /// Exposes `SessionManager::all_sessions` to the frontend.
-/// Returns a snapshot of all active sessions in arbitrary order.
+/// Returns a snapshot of all active sessions in display order, or only
+/// those of `repo_path` when given.
 #[tauri::command]
-pub async fn get_sessions(state: State<'_, SessionManager>) -> Result<Vec<SessionConfig>, String> {
-    Ok(state.all_sessions())
+#[specta::specta]
+pub async fn get_sessions(
+    state: State<'_, SessionManager>,
+    repo_path: Option<String>,
+) -> Result<Vec<SessionConfig>, AppError> {
+    Ok(match repo_path {
+        Some(repo_path) => state.sessions_in_repo(Path::new(&repo_path)),
+        None => state.all_sessions(),
+    })
 }
 
 /// Exposes `SessionManager::create_session` to the frontend.
-/// Registers a new session with `Starting` status. Returns an error if the
-/// session ID already exists.
+/// Registers a new session with `Starting` status. Returns an error if
+/// `repo_path` is empty, the session ID already exists, or `mode` names an
+/// unknown custom agent.
 #[tauri::command]
+#[specta::specta]
 pub async fn create_session(
     state: State<'_, SessionManager>,
+    launcher: State<'_, AgentLauncher>,
     id: u32,
     mode: AiMode,
-) -> Result<SessionConfig, String> {
-    state.create_session(id, mode)
-        .map_err(|existing| format!("Session {} already exists", existing.id))
+    repo_path: String,
+) -> Result<SessionConfig, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    validate_mode(&launcher, &mode)?;
+    state.create_session(id, mode, repo_path)
+        .map_err(|existing| {
+            AppError::new(ErrorCode::AlreadyExists, format!("Session {} already exists", existing.id))
+                .with("session_id", existing.id)
+        })
 }
 
 /// Exposes `SessionManager::update_status` to the frontend.
 /// Returns `false` if the session does not exist (no error raised).
 #[tauri::command]
+#[specta::specta]
 pub async fn update_session_status(
     state: State<'_, SessionManager>,
     session_id: u32,
     status: SessionStatus,
-) -> Result<bool, String> {
-    Ok(state.update_status(session_id, status))
+) -> Result<bool, AppError> {
+    Ok(state.update_status(session_id, status, StatusCause::Manual))
+}
+
+/// Exposes `SessionManager::activity` to the frontend.
+/// Returns created/last-output times, time in current status, and total
+/// working time for every session.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_activity(
+    state: State<'_, SessionManager>,
+) -> Result<Vec<SessionActivity>, AppError> {
+    Ok(state.activity())
+}
+
+/// Exposes `board::board_state` to the frontend.
+/// Returns every session, or only those of `repo_path` when given, with
+/// its PTY liveness, activity, and checkout status in one call.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_board_state(app_handle: AppHandle, repo_path: Option<String>) -> Result<BoardState, AppError> {
+    Ok(board::board_state(&app_handle, repo_path.as_deref().map(Path::new)).await)
+}
+
+/// Exposes `SessionManager::history` to the frontend.
+/// Returns every recorded status change with its cause, plus the seconds
+/// spent in each status.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_history(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+) -> Result<SessionHistory, AppError> {
+    state
+        .history(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))
 }
 
 /// Exposes `SessionManager::assign_branch` to the frontend.
-/// Links a session to a branch and optional worktree path. Returns an error
+/// Links a session to a branch and optional worktree path, and records the
+/// session as the worktree's owner in the worktree registry. Returns an error
 /// string if the session does not exist.
 #[tauri::command]
+#[specta::specta]
 pub async fn assign_session_branch(
     state: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
     session_id: u32,
     branch: String,
     worktree_path: Option<String>,
-) -> Result<SessionConfig, String> {
-    state
+) -> Result<SessionConfig, AppError> {
+    let config = state
         .assign_branch(session_id, branch, worktree_path)
-        .ok_or_else(|| format!("Session {} not found", session_id))
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    if let Some(ref path) = config.worktree_path {
+        worktrees.link_session(Path::new(path), session_id);
+    }
+    Ok(config)
+}
+
+/// Exposes `SessionManager::update_details` to the frontend.
+/// Updates any of title, notes, and tags; omitted fields are left unchanged.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_session_details(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    patch: SessionDetailsPatch,
+) -> Result<SessionConfig, AppError> {
+    state
+        .update_details(session_id, patch)
+        .ok_or_else(|| AppError::session_not_found(session_id))
+}
+
+/// Exposes `SessionManager::reorder` to the frontend.
+/// Takes session IDs in the desired order and returns the full, reordered list.
+#[tauri::command]
+#[specta::specta]
+pub async fn reorder_sessions(
+    state: State<'_, SessionManager>,
+    ids: Vec<u32>,
+) -> Result<Vec<SessionConfig>, AppError> {
+    Ok(state.reorder(&ids))
+}
+
+/// Exposes `ScratchpadStore::get` to the frontend.
+/// Returns an empty scratchpad if the session has none yet.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_scratchpad(
+    sessions: State<'_, SessionManager>,
+    scratchpads: State<'_, ScratchpadStore>,
+    session_id: u32,
+) -> Result<Scratchpad, AppError> {
+    if sessions.get_session(session_id).is_none() {
+        return Err(AppError::session_not_found(session_id));
+    }
+    scratchpads.get(session_id).map_err(AppError::from)
+}
+
+/// Exposes `ScratchpadStore::set` to the frontend.
+/// Meant to be called on every (debounced) edit, so saves are cheap and
+/// only touch this session's file.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_session_scratchpad(
+    sessions: State<'_, SessionManager>,
+    scratchpads: State<'_, ScratchpadStore>,
+    session_id: u32,
+    content: String,
+) -> Result<Scratchpad, AppError> {
+    if sessions.get_session(session_id).is_none() {
+        return Err(AppError::session_not_found(session_id));
+    }
+    scratchpads.set(session_id, content).map_err(AppError::from)
+}
+
+/// Exposes `SessionManager::set_pinned` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn pin_session(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    pinned: bool,
+) -> Result<SessionConfig, AppError> {
+    state
+        .set_pinned(session_id, pinned)
+        .ok_or_else(|| AppError::session_not_found(session_id))
+}
+
+/// Exposes `SessionManager::bind_pty` to the frontend.
+/// Records which PTY runs the session's terminal.
+#[tauri::command]
+#[specta::specta]
+pub async fn bind_session_pty(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    pty_id: u32,
+) -> Result<SessionConfig, AppError> {
+    state
+        .bind_pty(session_id, pty_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))
+}
+
+/// Exposes `SessionManager::unbind_pty` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn unbind_session_pty(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+) -> Result<SessionConfig, AppError> {
+    state
+        .unbind_pty(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))
 }
 
 /// Exposes `SessionManager::remove_session` to the frontend.
-/// Returns the removed session config, or `None` if it was not found.
+/// If the session has a bound PTY it is killed as well, so removing a
+/// session never leaves an orphaned shell behind. The session is recorded
+/// in the session archive. Returns the removed session config, or `None` if
+/// it was not found.
 #[tauri::command]
+#[specta::specta]
 pub async fn remove_session(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<Option<SessionConfig>, AppError> {
+    let params = json!({ "session_id": session_id });
+    audit
+        .track(window.label(), "remove_session", params, async {
+            let removed = state.remove_session(session_id);
+            if let Some(pty_id) = removed.as_ref().and_then(|s| s.pty_id).filter(|id| processes.has_session(*id)) {
+                let pm = processes.inner().clone();
+                if let Err(e) = pm.kill_session(pty_id).await {
+                    log::warn!("Failed to kill PTY {pty_id} of removed session {session_id}: {e}");
+                }
+            }
+            Ok(removed)
+        })
+        .await
+}
+
+/// Exposes `orchestrator::destroy_session` to the frontend.
+/// Kills the session's PTY, optionally removes its worktree and deletes its
+/// branch, and removes the session, reporting which steps failed. The
+/// worktree goes to the trash unless `permanent` is set. Fails with `Busy`
+/// while another operation holds the session's checkout.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn destroy_session(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    sessions: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    worktrees: State<'_, WorktreeManager>,
+    session_id: u32,
+    remove_worktree: bool,
+    delete_branch: bool,
+    permanent: Option<bool>,
+) -> Result<SessionTeardown, AppError> {
+    let permanent = permanent.unwrap_or(false);
+    let params = json!({
+        "session_id": session_id,
+        "remove_worktree": remove_worktree,
+        "delete_branch": delete_branch,
+        "permanent": permanent,
+    });
+    audit
+        .track(window.label(), "destroy_session", params, async {
+            let _lock = OperationLock::for_session(&sessions, "destroy", session_id)?;
+            orchestrator::destroy_session(
+                &worktrees,
+                &processes,
+                &sessions,
+                session_id,
+                remove_worktree,
+                delete_branch,
+                permanent,
+            )
+            .await
+        })
+        .await
+}
+
+/// Exposes `SessionArchive::query` to the frontend.
+/// Returns removed sessions matching `filter`, most recently ended first.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_archive(
+    archive: State<'_, SessionArchive>,
+    filter: Option<ArchiveFilter>,
+) -> Result<Vec<ArchivedSession>, AppError> {
+    Ok(archive.query(&filter.unwrap_or_default()))
+}
+
+/// Exposes `activity_report::activity_report` to the frontend.
+/// `range` defaults to the last 30 days in UTC.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_activity_report(
+    app_handle: AppHandle,
+    range: Option<ReportRange>,
+) -> Result<ActivityReport, AppError> {
+    activity_report::activity_report(&app_handle, range.unwrap_or_default())
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `RetentionManager::policy` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_retention_policy(
+    state: State<'_, RetentionManager>,
+) -> Result<RetentionPolicy, AppError> {
+    Ok(state.policy())
+}
+
+/// Exposes `RetentionManager::set_policy` to the frontend.
+/// Takes effect immediately; the cleanup task runs a pass right away if the
+/// new policy is active.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_retention_policy(
+    state: State<'_, RetentionManager>,
+    policy: RetentionPolicy,
+) -> Result<(), AppError> {
+    state.set_policy(policy).map_err(AppError::from)
+}
+
+/// Exposes `AgentWatchdog::policy` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_watchdog_policy(
+    state: State<'_, AgentWatchdog>,
+) -> Result<WatchdogPolicy, AppError> {
+    Ok(state.policy())
+}
+
+/// Exposes `AgentWatchdog::set_policy` to the frontend.
+/// Listen on `agent-watchdog` for crashed and stalled agents.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_watchdog_policy(
+    state: State<'_, AgentWatchdog>,
+    policy: WatchdogPolicy,
+) -> Result<(), AppError> {
+    state.set_policy(policy).map_err(AppError::from)
+}
+
+/// Dry run of the retention policy: lists the sessions the next cleanup pass
+/// would remove. `policy` defaults to the saved one, so a draft can be
+/// previewed before saving it.
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_session_cleanup(
+    retention: State<'_, RetentionManager>,
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    policy: Option<RetentionPolicy>,
+) -> Result<Vec<CleanupCandidate>, AppError> {
+    let policy = policy.unwrap_or_else(|| retention.policy());
+    Ok(retention::plan(&policy, &sessions, &worktrees).await)
+}
+
+/// Applies the saved retention policy immediately, emitting the same
+/// `sessions-cleaned` event as the background task, and returns the report.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_session_cleanup_now(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+) -> Result<CleanupReport, AppError> {
+    audit
+        .track(window.label(), "run_session_cleanup_now", json!({}), async {
+            let report = retention::run_cleanup(&app_handle).await;
+            let _ = app_handle.emit(retention::SESSIONS_CLEANED_EVENT, report.clone());
+            Ok(report)
+        })
+        .await
+}
+
+/// Exposes `ResultHarvester::get` to the frontend.
+/// Returns the result collected when the session last reached `Done`, or
+/// `None` if it has not yet.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_result(
+    harvester: State<'_, ResultHarvester>,
+    session_id: u32,
+) -> Result<Option<SessionResult>, AppError> {
+    harvester.get(session_id).map_err(AppError::from)
+}
+
+/// Exposes `handoff::copy_between_worktrees` to the frontend.
+/// Copies `paths`, relative to the worktree root, from one session's
+/// worktree into another's, reporting each path that failed.
+#[tauri::command]
+#[specta::specta]
+pub async fn copy_between_worktrees(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    src_session: u32,
+    dest_session: u32,
+    paths: Vec<String>,
+) -> Result<CopyHandoff, AppError> {
+    let params = json!({ "src_session": src_session, "dest_session": dest_session, "paths": paths });
+    audit
+        .track(window.label(), "copy_between_worktrees", params, async {
+            let sessions = app_handle.state::<SessionManager>();
+            let _lock = OperationLock::for_session(&sessions, "handoff", dest_session)?;
+            handoff::copy_between_worktrees(&app_handle, src_session, dest_session, paths.clone()).await
+        })
+        .await
+}
+
+/// Exposes `handoff::patch_between_worktrees` to the frontend.
+/// Applies the source session's changes, limited to `paths` if given, to
+/// the destination's worktree with `git apply --3way`.
+#[tauri::command]
+#[specta::specta]
+pub async fn patch_between_worktrees(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    src_session: u32,
+    dest_session: u32,
+    paths: Option<Vec<String>>,
+) -> Result<PatchHandoff, AppError> {
+    let params = json!({ "src_session": src_session, "dest_session": dest_session, "paths": paths });
+    audit
+        .track(window.label(), "patch_between_worktrees", params, async {
+            let sessions = app_handle.state::<SessionManager>();
+            let _lock = OperationLock::for_session(&sessions, "handoff", dest_session)?;
+            handoff::patch_between_worktrees(&app_handle, src_session, dest_session, paths.unwrap_or_default()).await
+        })
+        .await
+}
+
+/// Exposes `orchestrator::create_full_session` to the frontend.
+/// Creates the worktree, spawns its shell, and registers the session in one
+/// call, rolling back every step if any of them fails. `profile` defaults to
+/// an empty launch profile. Returns an operation ID at once; the checkout's
+/// progress and the outcome, with the new session's ID, follow on
+/// `worktree-progress-{op_id}`.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_full_session(
+    app_handle: AppHandle,
+    window: Window,
+    launcher: State<'_, AgentLauncher>,
+    repo_path: String,
+    branch_spec: BranchSpec,
+    mode: AiMode,
+    profile: Option<LaunchProfile>,
+) -> Result<String, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    validate_mode(&launcher, &mode)?;
+    let profile = profile.unwrap_or_default();
+    profile.validate()?;
+    let op_id = worktree_progress::start_op(&app_handle);
+    let label = window.label().to_string();
+    let id = op_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let app = app_handle;
+        let audit = app.state::<AuditLog>();
+        // Profiles are left out: their environment may hold credentials
+        let params = json!({ "repo_path": repo_path, "branch_spec": branch_spec, "mode": mode });
+        let created = audit
+            .track(&label, "create_full_session", params, async {
+                orchestrator::create_full_session(
+                    app.clone(),
+                    &app.state::<WorktreeManager>(),
+                    &app.state::<ProcessManager>(),
+                    &app.state::<SessionManager>(),
+                    &app.state::<LaunchScheduler>(),
+                    Path::new(&repo_path),
+                    branch_spec,
+                    mode,
+                    profile,
+                    Some(&id),
+                )
+                .await
+                .map_err(AppError::from)
+            })
+            .await;
+        let state = match created {
+            Ok(session) => WorktreeProgress::Finished {
+                path: session.worktree_path.unwrap_or_default(),
+                session_id: Some(session.id),
+            },
+            Err(error) => WorktreeProgress::Failed { error },
+        };
+        worktree_progress::update(&app, &id, state);
+    });
+    Ok(op_id)
+}
+
+/// Exposes `orchestrator::fanout_task` to the frontend.
+/// Starts one session per variant on fresh branches from `base_ref` and
+/// sends each the same `prompt`. `group` names the comparison group.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn fanout_task(
+    app_handle: AppHandle,
+    window: Window,
+    sessions: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    worktrees: State<'_, WorktreeManager>,
+    repo_path: String,
+    prompt: String,
+    variants: Vec<FanoutVariant>,
+    group: Option<String>,
+    base_ref: Option<String>,
+) -> Result<FanoutResult, AppError> {
+    let launcher = app_handle.state::<AgentLauncher>();
+    let scheduler = app_handle.state::<LaunchScheduler>();
+    let audit = app_handle.state::<AuditLog>();
+    let params = json!({
+        "repo_path": repo_path,
+        "prompt": prompt,
+        "variants": variants
+            .iter()
+            .map(|v| json!({ "mode": v.mode, "label": v.label }))
+            .collect::<Vec<_>>(),
+        "group": group,
+        "base_ref": base_ref,
+    });
+    audit
+        .track(window.label(), "fanout_task", params, async {
+            if repo_path.is_empty() {
+                return Err(AppError::invalid_argument("repo_path must not be empty"));
+            }
+            if prompt.trim().is_empty() {
+                return Err(AppError::invalid_argument("prompt must not be empty"));
+            }
+            for variant in &variants {
+                validate_mode(&launcher, &variant.mode)?;
+                variant.profile.validate()?;
+            }
+            orchestrator::fanout_task(
+                app_handle.clone(),
+                &worktrees,
+                &processes,
+                &sessions,
+                &scheduler,
+                Path::new(&repo_path),
+                prompt,
+                variants,
+                group,
+                base_ref,
+            )
+            .await
+            .map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `orchestrator::compare_group` to the frontend.
+/// Returns each session of a fan-out with its diffstat, for side-by-side
+/// review.
+#[tauri::command]
+#[specta::specta]
+pub async fn compare_session_group(
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    group: String,
+) -> Result<Vec<VariantResult>, AppError> {
+    Ok(orchestrator::compare_group(&worktrees, &sessions, &group).await)
+}
+
+/// Exposes `orchestrator::create_dependent_session` to the frontend.
+/// Registers a session that starts on a new `branch`, cut from the upstream
+/// session's branch, once the upstream session reaches `Done`.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_dependent_session(
+    app_handle: AppHandle,
+    window: Window,
+    sessions: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    worktrees: State<'_, WorktreeManager>,
+    upstream_id: u32,
+    branch: String,
+    mode: AiMode,
+    profile: Option<LaunchProfile>,
+) -> Result<SessionConfig, AppError> {
+    let launcher = app_handle.state::<AgentLauncher>();
+    let scheduler = app_handle.state::<LaunchScheduler>();
+    let audit = app_handle.state::<AuditLog>();
+    let params = json!({ "upstream_id": upstream_id, "branch": branch, "mode": mode });
+    audit
+        .track(window.label(), "create_dependent_session", params, async {
+            if branch.trim().is_empty() {
+                return Err(AppError::invalid_argument("branch must not be empty"));
+            }
+            validate_mode(&launcher, &mode)?;
+            let profile = profile.unwrap_or_default();
+            profile.validate()?;
+            orchestrator::create_dependent_session(
+                app_handle.clone(),
+                &worktrees,
+                &processes,
+                &sessions,
+                &scheduler,
+                upstream_id,
+                branch,
+                mode,
+                profile,
+            )
+            .await
+        })
+        .await
+}
+
+/// Exposes `orchestrator::spawn_session_shell` to the frontend.
+/// Starts a shell, startup script, and agent for a session without a PTY.
+/// `resume` (default `false`) continues the agent's last conversation.
+#[tauri::command]
+#[specta::specta]
+pub async fn spawn_session_shell(
+    app_handle: AppHandle,
+    sessions: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    scheduler: State<'_, LaunchScheduler>,
+    session_id: u32,
+    resume: Option<bool>,
+) -> Result<SessionConfig, AppError> {
+    orchestrator::spawn_session_shell(
+        app_handle,
+        &processes,
+        &sessions,
+        &scheduler,
+        session_id,
+        resume.unwrap_or(false),
+    )
+}
+
+/// Exposes `orchestrator::resume_agent` to the frontend.
+/// Relaunches the session's agent on its previous conversation, spawning a
+/// new shell in the same worktree if the old one is gone.
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_agent(
+    app_handle: AppHandle,
+    sessions: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    scheduler: State<'_, LaunchScheduler>,
+    session_id: u32,
+) -> Result<SessionConfig, AppError> {
+    orchestrator::resume_agent(app_handle, &processes, &sessions, &scheduler, session_id).await
+}
+
+/// Exposes `issues::import_issue` to the frontend.
+/// Fetches a GitHub or GitLab issue (URL or number) and creates a session
+/// on a branch named after it, with the issue as the agent's first prompt.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_issue(
+    app_handle: AppHandle,
+    repo_path: String,
+    issue_ref: String,
+    agent: Option<String>,
+    base_ref: Option<String>,
+) -> Result<SessionConfig, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    issues::import_issue(&app_handle, Path::new(&repo_path), &issue_ref, agent, base_ref)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `forks::detect_fork` to the frontend.
+/// Describes the repository's remotes: where session branches are pushed
+/// and which ref they should be diffed and rebased against.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_fork_info(repo_path: String) -> Result<ForkInfo, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    forks::detect_fork(Path::new(&repo_path)).await.map_err(AppError::from)
+}
+
+/// Exposes `forks::add_upstream_remote` to the frontend.
+/// Adds and fetches the `upstream` remote, by default the repository
+/// `origin` was forked from on GitHub.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_upstream_remote(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    repo_path: String,
+    url: Option<String>,
+) -> Result<ForkInfo, AppError> {
+    let params = json!({ "repo_path": repo_path, "url": url });
+    audit
+        .track(window.label(), "add_upstream_remote", params, async {
+            if repo_path.is_empty() {
+                return Err(AppError::invalid_argument("repo_path must not be empty"));
+            }
+            forks::add_upstream_remote(Path::new(&repo_path), url).await.map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `forks::rebase_session_on_upstream` to the frontend.
+/// Rebases the session's branch onto the freshly fetched default branch of
+/// `upstream` (or `origin`), returning the new HEAD. Fails with `Busy`
+/// while another operation holds the session's checkout.
+#[tauri::command]
+#[specta::specta]
+pub async fn rebase_session_on_upstream(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    sessions: State<'_, SessionManager>,
+    session_id: u32,
+) -> Result<String, AppError> {
+    let params = json!({ "session_id": session_id });
+    audit
+        .track(window.label(), "rebase_session_on_upstream", params, async {
+            let _lock = OperationLock::for_session(&sessions, "rebase", session_id)?;
+            forks::rebase_session_on_upstream(&sessions, session_id).await
+        })
+        .await
+}
+
+/// Exposes `pr_description::generate_pr_body` to the frontend.
+/// Returns a title and description for the session's pull request, built
+/// from its task, commits and diffstat, to edit before `create_pr`.
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_pr_body(app_handle: AppHandle, session_id: u32) -> Result<PrDraft, AppError> {
+    pr_description::generate_pr_body(&app_handle, session_id).await
+}
+
+/// Exposes `PrTemplates::template` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_pr_template(
+    state: State<'_, PrTemplates>,
+    repo_path: String,
+) -> Result<String, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    Ok(state.template(Path::new(&repo_path)))
+}
+
+/// Exposes `PrTemplates::set_template` to the frontend.
+/// A missing or blank `template` restores the default.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_pr_template(
+    state: State<'_, PrTemplates>,
+    repo_path: String,
+    template: Option<String>,
+) -> Result<(), AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    state
+        .set_template(Path::new(&repo_path), template)
+        .map_err(AppError::from)
+}
+
+/// Exposes `pull_request::create_pr` to the frontend.
+/// Pushes the session's branch and opens a GitHub pull request for it with
+/// `gh`, returning its number and URL.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_pr(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    sessions: State<'_, SessionManager>,
+    worktrees: State<'_, WorktreeManager>,
+    session_id: u32,
+    title: String,
+    body: String,
+    draft: bool,
+    base: Option<String>,
+) -> Result<PullRequest, AppError> {
+    let params = json!({ "session_id": session_id, "title": title, "draft": draft, "base": base });
+    audit
+        .track(window.label(), "create_pr", params, async {
+            pull_request::create_pr(&worktrees, &sessions, session_id, &title, &body, draft, base.as_deref()).await
+        })
+        .await
+}
+
+/// Exposes `pull_request::list_prs` to the frontend.
+/// Lists the repository's GitHub pull requests (open ones by default) with
+/// CI and review state, and the session working on each branch, if any.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_prs(
+    sessions: State<'_, SessionManager>,
+    repo_path: String,
+    state: Option<PrState>,
+) -> Result<Vec<PullRequestSummary>, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    pull_request::list_prs(&sessions, Path::new(&repo_path), state.unwrap_or_default())
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `CheckCache::get` to the frontend.
+/// Returns the CI checks of the branch's head commit on GitHub, cached for
+/// a minute unless `refresh` is set, and updates the `ci_status` of the
+/// sessions on the branch.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_branch_checks(
+    sessions: State<'_, SessionManager>,
+    checks: State<'_, CheckCache>,
+    repo_path: String,
+    branch: String,
+    refresh: Option<bool>,
+) -> Result<BranchChecks, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    checks
+        .get(&sessions, Path::new(&repo_path), &branch, refresh.unwrap_or(false))
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `pull_request::get_pr_comments` to the frontend.
+/// Returns the review threads, anchored to files and lines, and the review
+/// summaries of a GitHub pull request.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_pr_comments(repo_path: String, pr_number: u64) -> Result<PrFeedback, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    pull_request::get_pr_comments(Path::new(&repo_path), pr_number).await.map_err(AppError::from)
+}
+
+/// Sends the unresolved review feedback on the session's pull request (all
+/// of it with `include_resolved`) to its agent as a follow-up task, as
+/// `send_agent_prompt` does, and returns the text sent.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn send_pr_feedback(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    sessions: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    launcher: State<'_, AgentLauncher>,
+    session_id: u32,
+    include_resolved: Option<bool>,
+) -> Result<String, AppError> {
+    let params = json!({ "session_id": session_id, "include_resolved": include_resolved });
+    audit
+        .track(window.label(), "send_pr_feedback", params, async {
+            let session = sessions
+                .get_session(session_id)
+                .ok_or_else(|| AppError::session_not_found(session_id))?;
+            let (Some(repo_path), Some(pr_number)) = (session.repo(), session.pr_number) else {
+                return Err(AppError::not_found(format!("Session {session_id} has no pull request")));
+            };
+            let feedback = pull_request::get_pr_comments(repo_path, pr_number).await?;
+            let text = pull_request::feedback_prompt(&feedback, include_resolved.unwrap_or(false))
+                .ok_or_else(|| format!("Pull request #{pr_number} has no feedback to address"))?;
+            let prompt = launcher.send_prompt(&processes, &session, &text, &[]).await?;
+            agent_output::record_event(&app_handle, &session, AgentEvent::Prompt { text: prompt.clone() });
+            Ok(prompt)
+        })
+        .await
+}
+
+/// Replaces a session's launch profile. Takes effect the next time its
+/// shell is spawned.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_session_profile(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    profile: LaunchProfile,
+) -> Result<SessionConfig, AppError> {
+    profile.validate()?;
+    state
+        .modify(session_id, |session| session.profile = profile)
+        .ok_or_else(|| AppError::session_not_found(session_id))
+}
+
+/// Replaces a session's auto-approval policy. Takes effect with the next
+/// permission prompt.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_session_approval_policy(
     state: State<'_, SessionManager>,
     session_id: u32,
-) -> Result<Option<SessionConfig>, String> {
-    Ok(state.remove_session(session_id))
+    policy: ApprovalPolicy,
+) -> Result<SessionConfig, AppError> {
+    policy.validate()?;
+    state
+        .modify(session_id, |session| session.approval = policy)
+        .ok_or_else(|| AppError::session_not_found(session_id))
+}
+
+/// Sets or clears (`None`) where the session's branch is pushed once it
+/// reaches `Done`.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_session_auto_push(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    auto_push: Option<AutoPushConfig>,
+) -> Result<SessionConfig, AppError> {
+    let params = json!({ "session_id": session_id, "auto_push": auto_push });
+    audit
+        .track(window.label(), "set_session_auto_push", params, async {
+            if auto_push.as_ref().is_some_and(|config| config.remote.trim().is_empty()) {
+                return Err(AppError::invalid_argument("remote must not be empty"));
+            }
+            state
+                .modify(session_id, |session| session.auto_push = auto_push)
+                .ok_or_else(|| AppError::session_not_found(session_id))
+        })
+        .await
+}
+
+/// Exposes `Guardrails::policy` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_guardrail_policy(
+    state: State<'_, Guardrails>,
+) -> Result<GuardrailPolicy, AppError> {
+    Ok(state.policy())
+}
+
+/// Exposes `Guardrails::set_policy` to the frontend.
+/// Listen on `guardrail-triggered` for matching agent commands.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_guardrail_policy(
+    state: State<'_, Guardrails>,
+    policy: GuardrailPolicy,
+) -> Result<(), AppError> {
+    state.set_policy(policy).map_err(AppError::from)
+}
+
+/// Exposes `Guardrails::held` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_held_commands(state: State<'_, Guardrails>) -> Result<Vec<HeldCommand>, AppError> {
+    Ok(state.held())
+}
+
+/// Exposes `Guardrails::resolve` to the frontend.
+/// `approve` lets the held agent continue; otherwise it is terminated.
+#[tauri::command]
+#[specta::specta]
+pub async fn resolve_held_command(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, Guardrails>,
+    session_id: u32,
+    approve: bool,
+) -> Result<(), AppError> {
+    let params = json!({ "session_id": session_id, "approve": approve });
+    audit
+        .track(window.label(), "resolve_held_command", params, async {
+            state.resolve(&app_handle, session_id, approve).map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `AgentLauncher::commands` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_agent_commands(
+    launcher: State<'_, AgentLauncher>,
+) -> Result<AgentCommands, AppError> {
+    Ok(launcher.commands())
+}
+
+/// Exposes `AgentLauncher::set_commands` to the frontend.
+/// Replaces the per-mode command templates used to auto-launch agents,
+/// through the settings so `settings-changed` is emitted.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_agent_commands(
+    settings: State<'_, SettingsStore>,
+    commands: AgentCommands,
+) -> Result<(), AppError> {
+    settings.modify(|s| s.agent_commands = commands).map(|_| ()).map_err(AppError::from)
+}
+
+/// Exposes `LaunchScheduler::request_launch` to the frontend.
+/// Starts the agent CLI in a session whose PTY was bound by the frontend, or
+/// queues it if the working-session cap is reached. A launched outcome
+/// carries the command typed into the shell, or `None` if there was nothing
+/// to launch (no bound PTY, or a `Plain` session). `resume` (default
+/// `false`) continues the agent's last conversation where supported.
+#[tauri::command]
+#[specta::specta]
+pub async fn launch_session_agent(
+    scheduler: State<'_, LaunchScheduler>,
+    session_id: u32,
+    resume: Option<bool>,
+) -> Result<LaunchOutcome, AppError> {
+    scheduler.request_launch(session_id, resume.unwrap_or(false))
+}
+
+/// Exposes `AgentLauncher::send_prompt` to the frontend.
+/// Types a task into the session's running agent and submits it, returning
+/// the text that was sent. `files` (default none) are referenced the way
+/// the agent understands. The prompt is recorded in the session's
+/// transcript and emitted as an agent event.
+#[tauri::command]
+#[specta::specta]
+pub async fn send_agent_prompt(
+    app_handle: AppHandle,
+    sessions: State<'_, SessionManager>,
+    processes: State<'_, ProcessManager>,
+    launcher: State<'_, AgentLauncher>,
+    session_id: u32,
+    text: String,
+    files: Option<Vec<String>>,
+) -> Result<String, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let prompt = launcher
+        .send_prompt(&processes, &session, &text, &files.unwrap_or_default())
+        .await?;
+    agent_output::record_event(&app_handle, &session, AgentEvent::Prompt { text: prompt.clone() });
+    Ok(prompt)
+}
+
+/// Exposes `TranscriptStore::get` to the frontend.
+/// Returns everything recorded for the session's agent conversation.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_transcript(
+    sessions: State<'_, SessionManager>,
+    transcripts: State<'_, TranscriptStore>,
+    session_id: u32,
+) -> Result<Vec<TranscriptEntry>, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let transcripts = transcripts.inner().clone();
+    tokio::task::spawn_blocking(move || transcripts.get(&session))
+        .await
+        .map_err(|e| AppError::from(format!("Failed to read the transcript: {e}")))?
+        .map_err(AppError::from)
+}
+
+/// Exposes `TranscriptStore::search` to the frontend.
+/// Searches the transcripts of live and removed sessions alike.
+#[tauri::command]
+#[specta::specta]
+pub async fn search_transcripts(
+    transcripts: State<'_, TranscriptStore>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptMatch>, AppError> {
+    let transcripts = transcripts.inner().clone();
+    tokio::task::spawn_blocking(move || transcripts.search(&query, limit))
+        .await
+        .map_err(|e| AppError::from(format!("Failed to search transcripts: {e}")))
+}
+
+/// Exposes `workspace_search::search_everything` to the frontend.
+/// Searches open sessions' scrollback, notes, and scratchpads, archived
+/// sessions' notes, and every transcript at once.
+#[tauri::command]
+#[specta::specta]
+pub async fn search_everything(
+    app_handle: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<WorkspaceHit>, AppError> {
+    tokio::task::spawn_blocking(move || workspace_search::search_everything(&app_handle, &query, limit))
+        .await
+        .map_err(|e| AppError::from(format!("Failed to search: {e}")))
+}
+
+/// Exposes `LaunchScheduler::max_working` to the frontend (0 = unlimited).
+#[tauri::command]
+#[specta::specta]
+pub async fn get_max_working_sessions(
+    scheduler: State<'_, LaunchScheduler>,
+) -> Result<usize, AppError> {
+    Ok(scheduler.max_working())
+}
+
+/// Exposes `LaunchScheduler::set_max_working` to the frontend.
+/// The cap is saved in the settings. Raising it starts queued sessions, then
+/// queued tasks, immediately.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_max_working_sessions(
+    settings: State<'_, SettingsStore>,
+    max: usize,
+) -> Result<(), AppError> {
+    settings.modify(|s| s.max_working_sessions = max).map(|_| ()).map_err(AppError::from)
+}
+
+/// Exposes `LaunchScheduler::queued` to the frontend.
+/// Returns the IDs of sessions waiting for a slot, oldest first.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_launch_queue(scheduler: State<'_, LaunchScheduler>) -> Result<Vec<u32>, AppError> {
+    Ok(scheduler.queued())
+}
+
+/// Exposes `LaunchScheduler::is_paused` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_launches_paused(scheduler: State<'_, LaunchScheduler>) -> Result<bool, AppError> {
+    Ok(scheduler.is_paused())
+}
+
+/// Exposes `LaunchScheduler::set_paused` to the frontend.
+/// While paused, agent launches and queued tasks wait in the queue.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_launches_paused(
+    scheduler: State<'_, LaunchScheduler>,
+    paused: bool,
+) -> Result<(), AppError> {
+    scheduler.set_paused(paused);
+    Ok(())
+}
+
+/// Exposes `UsageTracker::usage` to the frontend.
+/// Listen on `provider-usage-changed` for updates to one provider.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_provider_usage(
+    usage: State<'_, UsageTracker>,
+) -> Result<Vec<ProviderUsage>, AppError> {
+    Ok(usage.usage())
+}
+
+/// Exposes `TaskQueue::tasks` to the frontend.
+/// Returns the tasks waiting for a slot, next first.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_task_queue(tasks: State<'_, TaskQueue>) -> Result<Vec<QueuedTask>, AppError> {
+    Ok(tasks.tasks())
+}
+
+/// Exposes `TaskQueue::enqueue` to the frontend.
+/// Queues `description` to run in a new session created as by
+/// `create_full_session`; it starts right away if a slot is free. `profile`
+/// defaults to an empty launch profile.
+#[tauri::command]
+#[specta::specta]
+pub async fn enqueue_task(
+    tasks: State<'_, TaskQueue>,
+    launcher: State<'_, AgentLauncher>,
+    description: String,
+    repo_path: String,
+    branch_spec: BranchSpec,
+    mode: AiMode,
+    profile: Option<LaunchProfile>,
+) -> Result<QueuedTask, AppError> {
+    if repo_path.is_empty() {
+        return Err(AppError::invalid_argument("repo_path must not be empty"));
+    }
+    validate_mode(&launcher, &mode)?;
+    let profile = profile.unwrap_or_default();
+    profile.validate()?;
+    tasks.enqueue(description, repo_path, branch_spec, mode, profile).map_err(AppError::from)
+}
+
+/// Exposes `TaskQueue::reorder` to the frontend.
+/// Moves the given tasks to the front in that order and returns the queue.
+#[tauri::command]
+#[specta::specta]
+pub async fn reorder_task_queue(
+    tasks: State<'_, TaskQueue>,
+    task_ids: Vec<u32>,
+) -> Result<Vec<QueuedTask>, AppError> {
+    Ok(tasks.reorder(&task_ids))
+}
+
+/// Exposes `TaskQueue::cancel` to the frontend.
+/// Removes a task that has not started yet.
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_task(
+    tasks: State<'_, TaskQueue>,
+    task_id: u32,
+) -> Result<QueuedTask, AppError> {
+    tasks
+        .cancel(task_id)
+        .ok_or_else(|| AppError::not_found(format!("Task {task_id} not found")))
+}
+
+/// Exposes `AgentLauncher::custom_agents` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_custom_agents(
+    launcher: State<'_, AgentLauncher>,
+) -> Result<Vec<CustomAgent>, AppError> {
+    Ok(launcher.custom_agents())
+}
+
+/// Exposes `AgentLauncher::save_custom_agent` to the frontend.
+/// Adds a custom agent or replaces the one with the same name.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_custom_agent(
+    launcher: State<'_, AgentLauncher>,
+    agent: CustomAgent,
+) -> Result<(), AppError> {
+    launcher.save_custom_agent(agent).map_err(AppError::from)
+}
+
+/// Exposes `AgentLauncher::delete_custom_agent` to the frontend.
+/// Returns `false` if no custom agent had that name.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_custom_agent(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    launcher: State<'_, AgentLauncher>,
+    name: String,
+) -> Result<bool, AppError> {
+    let params = json!({ "name": name });
+    audit
+        .track(window.label(), "delete_custom_agent", params, async {
+            launcher.delete_custom_agent(&name).map_err(AppError::from)
+        })
+        .await
+}
+
+/// Rejects `AiMode::Custom` names that are not in the agent registry.
+pub(crate) fn validate_mode(launcher: &AgentLauncher, mode: &AiMode) -> Result<(), AppError> {
+    match mode {
+        AiMode::Custom(name) if !launcher.has_custom_agent(name) => {
+            Err(AppError::invalid_argument(format!("Unknown custom agent '{name}'")))
+        }
+        _ => Ok(()),
+    }
 }
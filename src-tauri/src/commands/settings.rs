@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use tauri::{State, Window};
+
+use crate::core::audit::AuditLog;
+use crate::core::backup::{self, BackupSummary};
+use crate::core::settings::{Settings, SettingsStore};
+use crate::core::updates::{self, UpdateInfo};
+use crate::core::AppError;
+
+/// Exposes `SettingsStore::get` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_settings(state: State<'_, SettingsStore>) -> Result<Settings, AppError> {
+    Ok(state.get())
+}
+
+/// Exposes `SettingsStore::update` to the frontend.
+/// Returns the saved settings; listen on `settings-changed` for changes
+/// made elsewhere (e.g. the session cap from the session list).
+#[tauri::command]
+#[specta::specta]
+pub async fn update_settings(
+    state: State<'_, SettingsStore>,
+    settings: Settings,
+) -> Result<Settings, AppError> {
+    state.update(settings).map_err(AppError::from)
+}
+
+/// Exposes `backup::backup` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn backup_app_data(dest: String) -> Result<BackupSummary, AppError> {
+    backup::backup(PathBuf::from(dest)).await.map_err(AppError::from)
+}
+
+/// Exposes `backup::restore` to the frontend.
+/// The restored data replaces the current data at the next start, so the
+/// frontend should offer to restart.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_app_data(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    src: String,
+) -> Result<BackupSummary, AppError> {
+    let params = json!({ "src": src });
+    audit
+        .track(window.label(), "restore_app_data", params, async {
+            backup::restore(PathBuf::from(&src)).await.map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `updates::check` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_for_updates() -> Result<UpdateInfo, AppError> {
+    updates::check().await.map_err(AppError::from)
+}
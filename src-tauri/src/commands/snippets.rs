@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+
+use tauri::{AppHandle, State};
+
+use crate::core::snippets::{self, Snippet, SnippetLibrary};
+use crate::core::AppError;
+
+/// Exposes `SnippetLibrary::list` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_snippets(library: State<'_, SnippetLibrary>) -> Result<Vec<Snippet>, AppError> {
+    Ok(library.list())
+}
+
+/// Exposes `SnippetLibrary::save` to the frontend.
+/// Pass `id` 0 to create a snippet; returns it with its ID.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_snippet(library: State<'_, SnippetLibrary>, snippet: Snippet) -> Result<Snippet, AppError> {
+    library.save(snippet).map_err(AppError::from)
+}
+
+/// Exposes `SnippetLibrary::delete` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_snippet(library: State<'_, SnippetLibrary>, snippet_id: u32) -> Result<bool, AppError> {
+    Ok(library.delete(snippet_id)?)
+}
+
+/// Exposes `snippets::send_snippet` to the frontend.
+/// `vars` fills the snippet's placeholders (see `Snippet::placeholders`);
+/// returns the text sent.
+#[tauri::command]
+#[specta::specta]
+pub async fn send_snippet(
+    app_handle: AppHandle,
+    session_id: u32,
+    snippet_id: u32,
+    vars: Option<BTreeMap<String, String>>,
+) -> Result<String, AppError> {
+    snippets::send_snippet(&app_handle, session_id, snippet_id, &vars.unwrap_or_default()).await
+}
@@ -0,0 +1,79 @@
+use serde_json::json;
+use tauri::{AppHandle, State, Window};
+
+use crate::core::audit::AuditLog;
+use crate::core::startup::{self, AppliedProfile, StartupProfile, StartupProfiles};
+use crate::core::AppError;
+
+/// Exposes `StartupProfiles::list` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_profiles(profiles: State<'_, StartupProfiles>) -> Result<Vec<StartupProfile>, AppError> {
+    Ok(profiles.list())
+}
+
+/// Exposes `StartupProfiles::save` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_profile(
+    profiles: State<'_, StartupProfiles>,
+    profile: StartupProfile,
+) -> Result<(), AppError> {
+    profiles.save(profile).map_err(AppError::from)
+}
+
+/// Exposes `StartupProfiles::delete` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_profile(profiles: State<'_, StartupProfiles>, name: String) -> Result<bool, AppError> {
+    Ok(profiles.delete(&name)?)
+}
+
+/// Exposes `startup::apply` to the frontend.
+/// Starts the profile's sessions now; the frontend opens its repositories
+/// and restores its layout from the result.
+#[tauri::command]
+#[specta::specta]
+pub async fn load_profile(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    profiles: State<'_, StartupProfiles>,
+    name: String,
+) -> Result<AppliedProfile, AppError> {
+    let params = json!({ "name": name });
+    audit
+        .track(window.label(), "load_profile", params, async {
+            let profile = profiles
+                .get(&name)
+                .ok_or_else(|| AppError::not_found(format!("Startup profile '{name}' not found")))?;
+            Ok(startup::apply(&app_handle, profile).await)
+        })
+        .await
+}
+
+/// Exposes `StartupProfiles::startup` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_startup_profile(profiles: State<'_, StartupProfiles>) -> Result<Option<String>, AppError> {
+    Ok(profiles.startup())
+}
+
+/// Exposes `StartupProfiles::set_startup` to the frontend.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_startup_profile(
+    profiles: State<'_, StartupProfiles>,
+    name: Option<String>,
+) -> Result<(), AppError> {
+    profiles.set_startup(name).map_err(AppError::from)
+}
+
+/// Exposes `StartupProfiles::take_applied` to the frontend.
+/// Listen on `startup-profile-applied` before calling it, since the profile
+/// may still be starting.
+#[tauri::command]
+#[specta::specta]
+pub async fn take_applied_profile(profiles: State<'_, StartupProfiles>) -> Result<Option<AppliedProfile>, AppError> {
+    Ok(profiles.take_applied())
+}
@@ -1,6 +1,20 @@
-use tauri::{AppHandle, State};
+use serde::Serialize;
+use serde_json::json;
+use specta::Type;
+use tauri::{AppHandle, State, Window};
 
-use crate::core::{ProcessManager, PtyError};
+use crate::core::audit::AuditLog;
+use crate::core::clipboard::{self, CopiedOutput, CopyMode};
+use crate::core::scrollback::{MarkedOutput, OutputMark, Scrollback};
+use crate::core::{AppError, ProcessManager, PtyError, SessionManager};
+
+/// Outcome of a broadcast write to one Maestro session. `error` is set if
+/// the session does not exist, has no bound PTY, or the write failed.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BroadcastResult {
+    pub session_id: u32,
+    pub error: Option<String>,
+}
 
 /// Exposes `ProcessManager::spawn_shell` to the frontend.
 ///
@@ -8,11 +22,12 @@ use crate::core::{ProcessManager, PtyError};
 /// forwarding to the process manager. Returns the new session ID.
 /// The frontend should listen on `pty-output-{id}` for shell output events.
 #[tauri::command]
+#[specta::specta]
 pub async fn spawn_shell(
     app_handle: AppHandle,
     state: State<'_, ProcessManager>,
     cwd: Option<String>,
-) -> Result<u32, PtyError> {
+) -> Result<u32, AppError> {
     // Validate cwd if provided: must exist and be a directory
     let canonical_cwd = if let Some(ref dir) = cwd {
         let path = std::path::Path::new(dir);
@@ -20,53 +35,197 @@ pub async fn spawn_shell(
             .canonicalize()
             .map_err(|e| PtyError::spawn_failed(format!("Invalid cwd '{dir}': {e}")))?;
         if !canonical.is_dir() {
-            return Err(PtyError::spawn_failed(format!(
-                "cwd '{dir}' is not a directory"
-            )));
+            return Err(PtyError::spawn_failed(format!("cwd '{dir}' is not a directory")).into());
         }
         Some(canonical.to_string_lossy().into_owned())
     } else {
         None
     };
     let pm = state.inner().clone();
-    pm.spawn_shell(app_handle, canonical_cwd)
+    pm.spawn_shell(app_handle, canonical_cwd).map_err(AppError::from)
 }
 
 /// Exposes `ProcessManager::write_stdin` to the frontend.
 /// Sends raw text (including control sequences like `\r`) to the PTY.
 #[tauri::command]
+#[specta::specta]
 pub async fn write_stdin(
     state: State<'_, ProcessManager>,
     session_id: u32,
     data: String,
-) -> Result<(), PtyError> {
+) -> Result<(), AppError> {
     let pm = state.inner().clone();
-    pm.write_stdin(session_id, &data)
+    pm.write_stdin(session_id, &data).map_err(AppError::from)
+}
+
+/// Sends the same input to the PTYs of several Maestro sessions (note:
+/// session IDs, not PTY IDs), e.g. "continue\r" or Ctrl-C (`\u0003`).
+/// Every session is attempted; failures are reported per session.
+#[tauri::command]
+#[specta::specta]
+pub async fn broadcast_stdin(
+    state: State<'_, ProcessManager>,
+    sessions: State<'_, SessionManager>,
+    session_ids: Vec<u32>,
+    data: String,
+) -> Result<Vec<BroadcastResult>, AppError> {
+    Ok(broadcast(&state, &sessions, &session_ids, &data))
+}
+
+/// Like `broadcast_stdin`, targeting every session tagged `tag`.
+#[tauri::command]
+#[specta::specta]
+pub async fn broadcast_stdin_to_tag(
+    state: State<'_, ProcessManager>,
+    sessions: State<'_, SessionManager>,
+    tag: String,
+    data: String,
+) -> Result<Vec<BroadcastResult>, AppError> {
+    let session_ids: Vec<u32> = sessions
+        .all_sessions()
+        .into_iter()
+        .filter(|s| s.tags.contains(&tag))
+        .map(|s| s.id)
+        .collect();
+    Ok(broadcast(&state, &sessions, &session_ids, &data))
+}
+
+fn broadcast(
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    session_ids: &[u32],
+    data: &str,
+) -> Vec<BroadcastResult> {
+    session_ids
+        .iter()
+        .map(|&session_id| {
+            let error = match sessions.get_session(session_id) {
+                None => Some(format!("Session {} not found", session_id)),
+                Some(session) => match session.pty_id {
+                    None => Some(format!("Session {session_id} has no terminal")),
+                    Some(pty_id) => processes.write_stdin(pty_id, data).err().map(|e| e.to_string()),
+                },
+            };
+            BroadcastResult { session_id, error }
+        })
+        .collect()
 }
 
 /// Exposes `ProcessManager::resize_pty` to the frontend.
 /// Rejects dimensions that are zero or exceed 500 to prevent misuse.
 #[tauri::command]
+#[specta::specta]
 pub async fn resize_pty(
     state: State<'_, ProcessManager>,
     session_id: u32,
     rows: u16,
     cols: u16,
-) -> Result<(), PtyError> {
+) -> Result<(), AppError> {
     if rows == 0 || cols == 0 || rows > 500 || cols > 500 {
-        return Err(PtyError::resize_failed("Invalid dimensions"));
+        return Err(PtyError::resize_failed("Invalid dimensions").into());
     }
     let pm = state.inner().clone();
-    pm.resize_pty(session_id, rows, cols)
+    pm.resize_pty(session_id, rows, cols).map_err(AppError::from)
 }
 
 /// Exposes `ProcessManager::kill_session` to the frontend.
-/// Gracefully terminates the PTY session (SIGTERM, then SIGKILL after 3s).
+/// Gracefully terminates the PTY session (SIGTERM, then SIGKILL after 3s)
+/// and clears the PTY binding of whichever Maestro session owned it.
 #[tauri::command]
+#[specta::specta]
 pub async fn kill_session(
+    window: Window,
+    audit: State<'_, AuditLog>,
     state: State<'_, ProcessManager>,
+    sessions: State<'_, SessionManager>,
     session_id: u32,
-) -> Result<(), PtyError> {
-    let pm = state.inner().clone();
-    pm.kill_session(session_id).await
+) -> Result<(), AppError> {
+    // `session_id` is a PTY ID here; the audit entry names the owning session
+    let owner = sessions.session_for_pty(session_id).map(|s| s.id);
+    let params = json!({ "pty_id": session_id, "session_id": owner });
+    audit
+        .track(window.label(), "kill_session", params, async {
+            let pm = state.inner().clone();
+            pm.kill_session(session_id).await?;
+            if let Some(owner) = owner {
+                sessions.unbind_pty(owner);
+            }
+            Ok(())
+        })
+        .await
+}
+
+/// Exposes `clipboard::copy_last_output` to the frontend.
+/// Puts the session's last command output, or its last lines of scrollback,
+/// on the system clipboard and returns what was copied.
+#[tauri::command]
+#[specta::specta]
+pub async fn copy_last_output(app_handle: AppHandle, session_id: u32, mode: CopyMode) -> Result<CopiedOutput, AppError> {
+    clipboard::copy_last_output(&app_handle, session_id, mode).await
+}
+
+/// Returns the PTY bound to Maestro session `session_id`.
+fn pty_of(sessions: &SessionManager, session_id: u32) -> Result<u32, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    session
+        .pty_id
+        .ok_or_else(|| AppError::not_found(format!("Session {session_id} has no terminal")))
+}
+
+/// Exposes `Scrollback::add_mark` to the frontend.
+/// Marks the current end of the session's output with `label`, as a
+/// waypoint to come back to with `get_output_at_mark`.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_mark(
+    sessions: State<'_, SessionManager>,
+    scrollback: State<'_, Scrollback>,
+    session_id: u32,
+    label: String,
+) -> Result<OutputMark, AppError> {
+    if label.trim().is_empty() {
+        return Err(AppError::invalid_argument("Mark label must not be empty"));
+    }
+    let pty_id = pty_of(&sessions, session_id)?;
+    scrollback
+        .add_mark(pty_id, &label)
+        .ok_or_else(|| AppError::invalid_argument(format!("Session {session_id} has no output to mark yet")))
+}
+
+/// Exposes `Scrollback::marks` to the frontend.
+/// Returns the session's marks, its own and those added at each command,
+/// oldest first, or none if it has no terminal.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_marks(
+    sessions: State<'_, SessionManager>,
+    scrollback: State<'_, Scrollback>,
+    session_id: u32,
+) -> Result<Vec<OutputMark>, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    Ok(session.pty_id.map(|pty_id| scrollback.marks(pty_id)).unwrap_or_default())
+}
+
+/// Exposes `Scrollback::output_from_mark` to the frontend.
+/// Returns the session's output from a mark on, up to `max_bytes`, or its
+/// transcript since the mark once the output is no longer buffered.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_output_at_mark(
+    sessions: State<'_, SessionManager>,
+    scrollback: State<'_, Scrollback>,
+    session_id: u32,
+    mark_id: u64,
+    max_bytes: Option<usize>,
+) -> Result<MarkedOutput, AppError> {
+    let pty_id = pty_of(&sessions, session_id)?;
+    let scrollback = scrollback.inner().clone();
+    tokio::task::spawn_blocking(move || scrollback.output_from_mark(pty_id, mark_id, max_bytes))
+        .await
+        .map_err(|e| AppError::from(format!("Failed to read mark {mark_id}: {e}")))?
+        .ok_or_else(|| AppError::not_found(format!("Mark {mark_id} is not in session {session_id}'s output")))
 }
@@ -0,0 +1,57 @@
+use serde::Serialize;
+use specta::Type;
+use tauri::{State, Window};
+
+use crate::core::scrollback::Scrollback;
+use crate::core::session_manager::{SessionConfig, SessionManager};
+use crate::core::windows::SessionWindows;
+use crate::core::AppError;
+
+/// What a window needs to show a session's terminal it did not show
+/// before: the session and its recent raw output to replay.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SessionAttachment {
+    pub session: SessionConfig,
+    pub output: String,
+}
+
+/// Exposes `SessionWindows::open` to the frontend.
+/// Returns the window's label.
+#[tauri::command]
+#[specta::specta]
+pub async fn open_session_window(windows: State<'_, SessionWindows>, session_id: u32) -> Result<String, AppError> {
+    windows.open(session_id)
+}
+
+/// Exposes `SessionWindows::session_of` to the frontend.
+/// Returns the session the calling window shows, or `None` for the main
+/// window.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_window_session(window: Window, windows: State<'_, SessionWindows>) -> Result<Option<u32>, AppError> {
+    Ok(windows.session_of(window.label()))
+}
+
+/// Exposes `Scrollback::replay` to the frontend.
+/// Attaches the calling window to a session's terminal: the session the
+/// window shows if `session_id` is omitted, as a session window does when
+/// it opens, or the given one, as the main window does when a session
+/// window closes.
+#[tauri::command]
+#[specta::specta]
+pub async fn attach_session_terminal(
+    window: Window,
+    windows: State<'_, SessionWindows>,
+    sessions: State<'_, SessionManager>,
+    scrollback: State<'_, Scrollback>,
+    session_id: Option<u32>,
+) -> Result<SessionAttachment, AppError> {
+    let session_id = session_id
+        .or_else(|| windows.session_of(window.label()))
+        .ok_or_else(|| AppError::invalid_argument(format!("Window {} shows no session", window.label())))?;
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let output = session.pty_id.map(|pty_id| scrollback.replay(pty_id)).unwrap_or_default();
+    Ok(SessionAttachment { session, output })
+}
@@ -0,0 +1,556 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+use super::git::validate_repo_path;
+use crate::core::audit::AuditLog;
+use crate::core::merge_gate::{self, MergeGateReport};
+use crate::core::maintenance::{self, PruneReport, PruneScheduler};
+use crate::core::repo_config::{self, RepoConfig, RepoConfigTrust};
+use crate::core::session_lock::OperationLock;
+use crate::core::session_manager::SessionManager;
+use crate::core::test_runner::{self, TestRun};
+use crate::core::AppError;
+use crate::core::worktree_progress::{self, WorktreeProgress};
+use crate::core::worktree_registry::WorktreeMetadata;
+use crate::core::worktree_manager::{
+    BulkRemoveResult, CommitWorktree, IntegrateResult, PrWorktree, RemovePolicy, UniqueWorktree,
+    WorktreeManager, WorktreeStatus,
+};
+use crate::git::{
+    DiffSummary, Git, MergePreview, MergeStrategy, PushResult, WorktreeArchive,
+};
+
+/// Exposes `WorktreeManager::create` to the frontend.
+/// Checks out `branch` in a new managed worktree, or creates `branch` from
+/// `base_ref` when one is given. Returns the worktree path.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_create(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    branch: String,
+    base_ref: Option<String>,
+) -> Result<String, AppError> {
+    let params = json!({ "repo_path": repo_path, "branch": branch, "base_ref": base_ref });
+    audit
+        .track(window.label(), "worktree_create", params, async {
+            validate_repo_path(&repo_path)?;
+            let path = state
+                .create(&branch, base_ref.as_deref(), Path::new(&repo_path))
+                .await?;
+            Ok(path.to_string_lossy().into_owned())
+        })
+        .await
+}
+
+/// Exposes `worktree_progress::start_create` to the frontend.
+/// Like `worktree_create`, but returns an operation ID at once and reports
+/// the checkout's progress on `worktree-progress-{op_id}`, for repositories
+/// with many files.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_create_start(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    repo_path: String,
+    branch: String,
+    base_ref: Option<String>,
+) -> Result<String, AppError> {
+    let params = json!({ "repo_path": repo_path, "branch": branch, "base_ref": base_ref });
+    audit
+        .track(window.label(), "worktree_create_start", params, async {
+            validate_repo_path(&repo_path)?;
+            Ok(worktree_progress::start_create(
+                &app_handle,
+                PathBuf::from(&repo_path),
+                branch.clone(),
+                base_ref.clone(),
+            ))
+        })
+        .await
+}
+
+/// Exposes `worktree_progress::progress` to the frontend.
+/// Returns the latest state of a `worktree_create_start` operation, or
+/// `None` if it is unknown.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_worktree_progress(op_id: String) -> Result<Option<WorktreeProgress>, AppError> {
+    Ok(worktree_progress::progress(&op_id))
+}
+
+/// Exposes `RepoConfig::load` to the frontend.
+/// Returns the repository's `.maestro/config.toml`, or `None` if it has
+/// none; an invalid file is an error, so the settings panel can show why.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_repo_config(repo_path: String) -> Result<Option<RepoConfig>, AppError> {
+    validate_repo_path(&repo_path)?;
+    RepoConfig::load(Path::new(&repo_path)).map_err(AppError::from)
+}
+
+/// Exposes `repo_config::trust_status` to the frontend.
+/// Returns whether the repository's `.maestro/config.toml` is approved to
+/// run its setup, startup script, and environment, with the hash to
+/// approve.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_repo_config_trust(repo_path: String) -> Result<RepoConfigTrust, AppError> {
+    validate_repo_path(&repo_path)?;
+    repo_config::trust_status(Path::new(&repo_path)).map_err(AppError::from)
+}
+
+/// Exposes `repo_config::trust` to the frontend.
+/// Approves the reviewed `.maestro/config.toml` whose hash is `hash`.
+#[tauri::command]
+#[specta::specta]
+pub async fn trust_repo_config(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    repo_path: String,
+    hash: String,
+) -> Result<(), AppError> {
+    let params = json!({ "repo_path": repo_path, "hash": hash });
+    audit
+        .track(window.label(), "trust_repo_config", params, async {
+            validate_repo_path(&repo_path)?;
+            repo_config::trust(Path::new(&repo_path), &hash).map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::status_summary` to the frontend.
+/// Returns dirty counts, ahead/behind, and last commit for every managed worktree.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_status_summary(
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+) -> Result<Vec<WorktreeStatus>, AppError> {
+    validate_repo_path(&repo_path)?;
+    state.status_summary(Path::new(&repo_path)).await.map_err(AppError::from)
+}
+
+/// Exposes `WorktreeManager::diff` to the frontend.
+/// Returns the diffstat and changed files of a worktree relative to `base_ref`.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_diff(
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    wt_path: String,
+    base_ref: String,
+) -> Result<DiffSummary, AppError> {
+    validate_repo_path(&repo_path)?;
+    state
+        .diff(Path::new(&repo_path), Path::new(&wt_path), &base_ref)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Exposes `WorktreeManager::integrate` to the frontend.
+/// Merges the worktree branch into the main checkout's current branch and,
+/// if `cleanup` is set, removes the worktree and deletes the merged branch.
+/// The repository's merge gate runs first (see `merge_gate::check`); if a
+/// check fails, nothing is merged and `MergeGateFailed` names the failures;
+/// if the branch moves after the gate, `BranchMoved`.
+/// Both checkouts are locked meanwhile (see `session_lock::OperationLock`),
+/// failing with `Busy` if another operation holds either.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn worktree_integrate(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    wt_path: String,
+    strategy: MergeStrategy,
+    cleanup: bool,
+) -> Result<IntegrateResult, AppError> {
+    let params = json!({
+        "repo_path": repo_path,
+        "wt_path": wt_path,
+        "strategy": strategy,
+        "cleanup": cleanup,
+    });
+    audit
+        .track(window.label(), "worktree_integrate", params, async {
+            validate_repo_path(&repo_path)?;
+            let sessions = app_handle.state::<SessionManager>();
+            let _lock = OperationLock::acquire(&sessions, "integrate", &[Path::new(&repo_path), Path::new(&wt_path)])?;
+            let gate = merge_gate::check(&app_handle, Path::new(&repo_path), Path::new(&wt_path)).await?;
+            if !gate.passed {
+                return Err(gate.to_error());
+            }
+            let mut result = state
+                .integrate(Path::new(&repo_path), Path::new(&wt_path), strategy, cleanup, Some(&gate.head))
+                .await?;
+            if cleanup {
+                let params = json!({ "repo_path": repo_path, "wt_path": wt_path, "branch": result.branch });
+                let outcome = result.cleanup_error.as_ref().map_or(Ok(()), Err);
+                audit.record_automation("merge_cleanup", "worktree_remove", params, &outcome);
+            }
+            result.gate = Some(gate);
+            Ok(result)
+        })
+        .await
+}
+
+/// Exposes `merge_gate::check` to the frontend.
+/// Runs the merge gate without merging, reporting every check. Like the
+/// gate of `worktree_integrate`, it may commit the worktree's changes and
+/// run its tests.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_merge_gate(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    repo_path: String,
+    wt_path: String,
+) -> Result<MergeGateReport, AppError> {
+    let params = json!({ "repo_path": repo_path, "wt_path": wt_path });
+    audit
+        .track(window.label(), "check_merge_gate", params, async {
+            validate_repo_path(&repo_path)?;
+            let sessions = app_handle.state::<SessionManager>();
+            let _lock = OperationLock::acquire(&sessions, "merge gate", &[Path::new(&repo_path), Path::new(&wt_path)])?;
+            merge_gate::check(&app_handle, Path::new(&repo_path), Path::new(&wt_path))
+                .await
+                .map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `Git::merge_preview` to the frontend.
+/// Reports whether merging `branch` into `target` would conflict, and in
+/// which files, without touching any working tree.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_merge_preview(
+    repo_path: String,
+    branch: String,
+    target: String,
+) -> Result<MergePreview, AppError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.merge_preview(&branch, &target).await.map_err(AppError::from)
+}
+
+/// Exposes `WorktreeManager::remove` to the frontend.
+/// Force-removes a worktree; with `archive`, uncommitted changes are
+/// snapshotted first and the snapshot (if any) is returned. The worktree
+/// goes to the trash unless `permanent` is set. Fails with `Busy` while
+/// another operation holds the worktree.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn worktree_remove(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    sessions: State<'_, SessionManager>,
+    repo_path: String,
+    wt_path: String,
+    archive: bool,
+    permanent: Option<bool>,
+) -> Result<Option<WorktreeArchive>, AppError> {
+    let permanent = permanent.unwrap_or(false);
+    let params = json!({
+        "repo_path": repo_path,
+        "wt_path": wt_path,
+        "archive": archive,
+        "permanent": permanent,
+    });
+    audit
+        .track(window.label(), "worktree_remove", params, async {
+            validate_repo_path(&repo_path)?;
+            let _lock = OperationLock::acquire(&sessions, "remove", &[Path::new(&wt_path)])?;
+            state
+                .remove(Path::new(&repo_path), Path::new(&wt_path), archive, permanent)
+                .await
+                .map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::remove_bulk` to the frontend.
+/// Removes many managed worktrees in one call with per-path results.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn worktree_remove_bulk(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    sessions: State<'_, SessionManager>,
+    repo_path: String,
+    paths: Vec<String>,
+    policy: RemovePolicy,
+    permanent: Option<bool>,
+) -> Result<Vec<BulkRemoveResult>, AppError> {
+    let permanent = permanent.unwrap_or(false);
+    let params = json!({
+        "repo_path": repo_path,
+        "paths": paths,
+        "policy": policy,
+        "permanent": permanent,
+    });
+    audit
+        .track(window.label(), "worktree_remove_bulk", params, async {
+            validate_repo_path(&repo_path)?;
+            let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+            let locked: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+            let _lock = OperationLock::acquire(&sessions, "remove", &locked)?;
+            Ok(state
+                .remove_bulk(Path::new(&repo_path), &paths, policy, permanent)
+                .await)
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::list_archives` to the frontend.
+/// Returns all worktree snapshots for the repo, newest first.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_worktree_archives(
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+) -> Result<Vec<WorktreeArchive>, AppError> {
+    validate_repo_path(&repo_path)?;
+    state.list_archives(Path::new(&repo_path)).await.map_err(AppError::from)
+}
+
+/// Exposes `WorktreeManager::restore_archive` to the frontend.
+/// Returns the path of the worktree the snapshot was restored into.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_worktree_archive(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    ref_name: String,
+) -> Result<String, AppError> {
+    let params = json!({ "repo_path": repo_path, "ref_name": ref_name });
+    audit
+        .track(window.label(), "restore_worktree_archive", params, async {
+            validate_repo_path(&repo_path)?;
+            let path = state
+                .restore_archive(Path::new(&repo_path), &ref_name)
+                .await?;
+            Ok(path.to_string_lossy().into_owned())
+        })
+        .await
+}
+
+/// Returns the automatic prune interval in seconds (0 = disabled).
+#[tauri::command]
+#[specta::specta]
+pub async fn get_prune_interval(state: State<'_, PruneScheduler>) -> Result<u64, AppError> {
+    Ok(state.interval_secs())
+}
+
+/// Sets the automatic prune interval in seconds; 0 disables scheduled pruning.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_prune_interval(
+    state: State<'_, PruneScheduler>,
+    secs: u64,
+) -> Result<(), AppError> {
+    state.set_interval_secs(secs);
+    Ok(())
+}
+
+/// Runs a prune pass over all known repos immediately, emitting the same
+/// `worktrees-pruned` event as the scheduled task, and returns the report.
+/// With `dry_run`, nothing is removed or emitted; the report lists what
+/// would be.
+#[tauri::command]
+#[specta::specta]
+pub async fn prune_worktrees_now(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    dry_run: Option<bool>,
+) -> Result<PruneReport, AppError> {
+    let dry_run = dry_run.unwrap_or(false);
+    audit
+        .track(window.label(), "prune_worktrees_now", json!({ "dry_run": dry_run }), async {
+            let report = maintenance::prune_all(&state, dry_run).await;
+            if !dry_run {
+                let _ = app_handle.emit(maintenance::WORKTREES_PRUNED_EVENT, report.clone());
+            }
+            Ok(report)
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::create_from_pr` to the frontend.
+/// Fetches the PR/MR head into a `pr-<n>` branch and opens a managed worktree on it.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_from_pr(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    pr_number: u64,
+) -> Result<PrWorktree, AppError> {
+    let params = json!({ "repo_path": repo_path, "pr_number": pr_number });
+    audit
+        .track(window.label(), "worktree_from_pr", params, async {
+            validate_repo_path(&repo_path)?;
+            state.create_from_pr(Path::new(&repo_path), pr_number).await.map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::create_unique` to the frontend.
+/// Creates a worktree for `branch`, falling back to a new `<branch>-N` branch
+/// cut from it if `branch` is already checked out.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_create_unique(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    branch: String,
+) -> Result<UniqueWorktree, AppError> {
+    let params = json!({ "repo_path": repo_path, "branch": branch });
+    audit
+        .track(window.label(), "worktree_create_unique", params, async {
+            validate_repo_path(&repo_path)?;
+            state
+                .create_unique(&branch, Path::new(&repo_path), |_| {})
+                .await
+                .map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::create_at` to the frontend.
+/// Creates a worktree at a commit or tag, on a new `branch` if given, else
+/// on a detached HEAD.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_create_at(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    commit_ish: String,
+    branch: Option<String>,
+) -> Result<CommitWorktree, AppError> {
+    let params = json!({ "repo_path": repo_path, "commit_ish": commit_ish, "branch": branch });
+    audit
+        .track(window.label(), "worktree_create_at", params, async {
+            validate_repo_path(&repo_path)?;
+            state
+                .create_at(Path::new(&repo_path), &commit_ish, branch.as_deref())
+                .await
+                .map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::metadata` to the frontend.
+/// Returns provenance (session, base ref, creation time, last activity) for
+/// each managed worktree of the repo.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_metadata(
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+) -> Result<Vec<WorktreeMetadata>, AppError> {
+    validate_repo_path(&repo_path)?;
+    Ok(state.metadata(Path::new(&repo_path)))
+}
+
+/// Exposes `WorktreeManager::rename` to the frontend.
+/// Renames the worktree's branch and moves its directory to match. Returns
+/// the new worktree path.
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_rename(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    sessions: State<'_, SessionManager>,
+    repo_path: String,
+    wt_path: String,
+    new_branch: String,
+) -> Result<String, AppError> {
+    let params = json!({ "repo_path": repo_path, "wt_path": wt_path, "new_branch": new_branch });
+    audit
+        .track(window.label(), "worktree_rename", params, async {
+            validate_repo_path(&repo_path)?;
+            let _lock = OperationLock::acquire(&sessions, "rename", &[Path::new(&wt_path)])?;
+            let path = state
+                .rename(Path::new(&repo_path), Path::new(&wt_path), &new_branch)
+                .await?;
+            Ok(path.to_string_lossy().into_owned())
+        })
+        .await
+}
+
+/// Exposes `WorktreeManager::publish` to the frontend.
+/// Pushes the worktree branch to `remote` with upstream tracking, with
+/// `--force-with-lease` if `force_with_lease` is set (default off).
+#[tauri::command]
+#[specta::specta]
+pub async fn worktree_publish(
+    window: Window,
+    audit: State<'_, AuditLog>,
+    state: State<'_, WorktreeManager>,
+    repo_path: String,
+    wt_path: String,
+    remote: String,
+    force_with_lease: Option<bool>,
+) -> Result<PushResult, AppError> {
+    let force_with_lease = force_with_lease.unwrap_or(false);
+    let params = json!({
+        "repo_path": repo_path,
+        "wt_path": wt_path,
+        "remote": remote,
+        "force_with_lease": force_with_lease,
+    });
+    audit
+        .track(window.label(), "worktree_publish", params, async {
+            validate_repo_path(&repo_path)?;
+            state
+                .publish(Path::new(&repo_path), Path::new(&wt_path), &remote, force_with_lease)
+                .await
+                .map_err(AppError::from)
+        })
+        .await
+}
+
+/// Exposes `test_runner::run_tests` to the frontend.
+/// Runs the worktree's test command; listen on `test-output` for its
+/// output as it is printed. The result is also stored on the worktree's
+/// session.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_tests(
+    app_handle: AppHandle,
+    window: Window,
+    audit: State<'_, AuditLog>,
+    worktree_path: String,
+) -> Result<TestRun, AppError> {
+    let params = json!({ "worktree_path": worktree_path });
+    audit
+        .track(window.label(), "run_tests", params, async {
+            test_runner::run_tests(&app_handle, Path::new(&worktree_path)).await
+        })
+        .await
+}
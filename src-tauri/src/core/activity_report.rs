@@ -0,0 +1,273 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::clock;
+use super::repo_config;
+use super::session_archive::{ArchiveFilter, SessionArchive};
+use super::session_manager::{SessionManager, SessionStatus};
+use super::transcript::TranscriptStore;
+use crate::git::Git;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Days reported when the range has no start.
+const DEFAULT_DAYS: i64 = 30;
+
+/// Longest range reported, in days.
+const MAX_DAYS: i64 = 366;
+
+/// Most commits read per branch.
+pub(super) const MAX_COMMITS_PER_BRANCH: usize = 5_000;
+
+/// Period of `activity_report`. `since` and `until` are Unix seconds,
+/// defaulting to the last `DEFAULT_DAYS` days up to now. Days are
+/// calendar days at `utc_offset_mins` from UTC (pass the viewer's offset,
+/// e.g. 120 for UTC+2), so they match the dashboard's local dates.
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(default)]
+pub struct ReportRange {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub utc_offset_mins: i32,
+}
+
+/// What happened on one day. `date` is `YYYY-MM-DD`.
+///
+/// `sessions_started` and `sessions_ended` count sessions created and
+/// removed that day. `working_secs` is time agents spent `Working`,
+/// `commits` the commits made on session branches (by author date), and
+/// `cost_usd` the agent cost reported that day.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct DayActivity {
+    pub date: String,
+    pub sessions_started: u32,
+    pub sessions_ended: u32,
+    pub working_secs: i64,
+    pub commits: u32,
+    pub cost_usd: f64,
+}
+
+/// Activity per day over a range, oldest day first with every day present,
+/// and the totals over the range. `errors` lists branches whose commits
+/// could not be counted, e.g. because they were deleted before their
+/// commits were recorded in the session archive.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ActivityReport {
+    pub since: i64,
+    pub until: i64,
+    pub utc_offset_mins: i32,
+    pub days: Vec<DayActivity>,
+    pub total_sessions: u32,
+    pub total_working_secs: i64,
+    pub total_commits: u32,
+    pub total_cost_usd: f64,
+    pub errors: Vec<String>,
+}
+
+/// Days of the range, by day number since the epoch in its time zone.
+struct Days {
+    since: i64,
+    until: i64,
+    offset: i64,
+    days: BTreeMap<i64, DayActivity>,
+}
+
+impl Days {
+    fn new(since: i64, until: i64, utc_offset_mins: i32) -> Self {
+        let mut days = Self {
+            since,
+            until,
+            offset: i64::from(utc_offset_mins) * 60,
+            days: BTreeMap::new(),
+        };
+        for day in days.day_of(since)..=days.day_of(until) {
+            days.days.insert(
+                day,
+                DayActivity {
+                    date: date_of(day),
+                    ..DayActivity::default()
+                },
+            );
+        }
+        days
+    }
+
+    fn day_of(&self, at: i64) -> i64 {
+        (at + self.offset).div_euclid(DAY_SECS)
+    }
+
+    /// The day `at` falls on, if it is in the range.
+    fn at(&mut self, at: i64) -> Option<&mut DayActivity> {
+        if at < self.since || at > self.until {
+            return None;
+        }
+        let day = self.day_of(at);
+        self.days.get_mut(&day)
+    }
+
+    /// Adds the `Working` period from `start` to `end`, split at midnight
+    /// and clipped to the range.
+    fn add_working(&mut self, start: i64, end: i64) {
+        let mut start = start.max(self.since);
+        let end = end.min(self.until);
+        while start < end {
+            let midnight = (self.day_of(start) + 1) * DAY_SECS - self.offset;
+            let stop = end.min(midnight);
+            if let Some(day) = self.at(start) {
+                day.working_secs += stop - start;
+            }
+            start = stop;
+        }
+    }
+}
+
+/// Aggregates session activity per day over `range`, for the dashboard and
+/// report view.
+///
+/// Sessions come from the open sessions and the session archive. Working
+/// time of open sessions is split by day from their status history; removed
+/// sessions only keep a total, counted on the day they ended. Commits are
+/// those on each session branch and not on the repository's base branch
+/// (see `repo_config::base_branch`), each counted once: the ones the
+/// session archive recorded, which outlive the branch, and those on the
+/// live branches of open sessions and of archived sessions with no record.
+/// Costs come from the agent transcripts.
+pub async fn activity_report(app: &AppHandle, range: ReportRange) -> Result<ActivityReport, String> {
+    let now = clock::now_secs();
+    let until = range.until.unwrap_or(now);
+    let since = range.since.unwrap_or(until - DEFAULT_DAYS * DAY_SECS);
+    if since > until {
+        return Err("The report range ends before it starts".to_string());
+    }
+    if until - since > MAX_DAYS * DAY_SECS {
+        return Err(format!("The report range must be at most {MAX_DAYS} days"));
+    }
+    if !(-14 * 60..=14 * 60).contains(&range.utc_offset_mins) {
+        return Err(format!("Invalid UTC offset: {} minutes", range.utc_offset_mins));
+    }
+    let mut days = Days::new(since, until, range.utc_offset_mins);
+    // Branches to count commits on, as (repository, directory, branch).
+    let mut branches = BTreeSet::new();
+    let mut counted = HashSet::new();
+    let mut count_commit = |days: &mut Days, hash: String, timestamp: i64| {
+        if counted.insert(hash) {
+            if let Some(day) = days.at(timestamp) {
+                day.commits += 1;
+            }
+        }
+    };
+    let archive = app.try_state::<SessionArchive>();
+
+    let manager = app.state::<SessionManager>();
+    for session in manager.all_sessions() {
+        if let Some(day) = days.at(session.created_at) {
+            day.sessions_started += 1;
+        }
+        if let Some(history) = manager.history(session.id) {
+            let transitions = &history.transitions;
+            for (i, transition) in transitions.iter().enumerate() {
+                if transition.to == SessionStatus::Working {
+                    let end = transitions.get(i + 1).map_or(now, |next| next.at);
+                    days.add_working(transition.at, end);
+                }
+            }
+        }
+        for commit in archive.iter().flat_map(|archive| archive.recorded_commits(&session)) {
+            count_commit(&mut days, commit.hash, commit.timestamp);
+        }
+        if let Some(branch) = session.branch.clone().filter(|_| !session.repo_path.is_empty()) {
+            let dir = session.worktree_path.clone().unwrap_or_else(|| session.repo_path.clone());
+            branches.insert((PathBuf::from(&session.repo_path), PathBuf::from(dir), branch));
+        }
+    }
+    let archived = archive
+        .map(|archive| archive.query(&ArchiveFilter::default()))
+        .unwrap_or_default();
+    for entry in archived.iter().filter(|e| e.ended_at >= since) {
+        if let Some(day) = days.at(entry.created_at) {
+            day.sessions_started += 1;
+        }
+        if let Some(day) = days.at(entry.ended_at) {
+            day.sessions_ended += 1;
+            day.working_secs += entry.working_secs;
+        }
+        if let Some(ref commits) = entry.commits {
+            for commit in commits {
+                count_commit(&mut days, commit.hash.clone(), commit.timestamp);
+            }
+        } else if let Some(branch) = entry.branch.clone().filter(|_| !entry.repo_path.is_empty()) {
+            let repo = PathBuf::from(&entry.repo_path);
+            branches.insert((repo.clone(), repo, branch));
+        }
+    }
+
+    let transcripts = app.state::<TranscriptStore>().inner().clone();
+    let costs = tokio::task::spawn_blocking(move || transcripts.costs(since, until))
+        .await
+        .map_err(|e| format!("Failed to read transcripts: {e}"))?;
+    for (at, usd) in costs {
+        if let Some(day) = days.at(at) {
+            day.cost_usd += usd;
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut bases: BTreeMap<PathBuf, Option<String>> = BTreeMap::new();
+    for (repo, dir, branch) in branches {
+        if !bases.contains_key(&repo) {
+            let base = match repo_config::base_branch(&repo).await {
+                Ok(base) => Some(base),
+                Err(e) => {
+                    errors.push(format!("{}: failed to find the base branch: {e}", repo.display()));
+                    None
+                }
+            };
+            bases.insert(repo.clone(), base);
+        }
+        let Some(base) = bases[&repo].clone().filter(|base| *base != branch) else {
+            continue;
+        };
+        let dir = if dir.is_dir() { dir } else { repo.clone() };
+        match Git::new(&dir).commits_between(&base, &branch, MAX_COMMITS_PER_BRANCH).await {
+            Ok(commits) => {
+                for commit in commits {
+                    count_commit(&mut days, commit.hash, commit.timestamp);
+                }
+            }
+            Err(e) => errors.push(format!("{}: failed to count commits on {branch}: {e}", repo.display())),
+        }
+    }
+
+    let days: Vec<DayActivity> = days.days.into_values().collect();
+    Ok(ActivityReport {
+        since,
+        until,
+        utc_offset_mins: range.utc_offset_mins,
+        total_sessions: days.iter().map(|d| d.sessions_started).sum(),
+        total_working_secs: days.iter().map(|d| d.working_secs).sum(),
+        total_commits: days.iter().map(|d| d.commits).sum(),
+        total_cost_usd: days.iter().map(|d| d.cost_usd).sum(),
+        days,
+        errors,
+    })
+}
+
+/// Formats a day number since the epoch as `YYYY-MM-DD` (proleptic
+/// Gregorian calendar).
+fn date_of(day: i64) -> String {
+    // Howard Hinnant's `civil_from_days`.
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
@@ -0,0 +1,236 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Something that happened in an agent conversation, emitted on
+/// `agent-event-{session_id}` and recorded in the session's transcript.
+/// Everything but `Prompt` is recognized by `AgentAdapter::parse_output`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AgentEvent {
+    /// A task sent to the agent with `AgentLauncher::send_prompt`.
+    Prompt { text: String },
+    /// The agent's own identifier for the conversation, used to resume it.
+    AgentSession { id: String },
+    /// Text the agent wrote to the user.
+    AssistantText { text: String },
+    /// A tool invocation; `id` pairs it with its `ToolResult`.
+    ToolCall {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    /// The outcome of a tool invocation. `content` may be truncated.
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        is_error: bool,
+    },
+    /// Cost of the agent run that just ended, in US dollars.
+    Cost { usd: f64 },
+    /// Tokens used by the agent run that just ended.
+    Usage { input_tokens: u64, output_tokens: u64 },
+    /// The provider refused or deferred a request; `message` is the line
+    /// that said so. `retry_after_secs` is set if the message gave a delay.
+    Throttled {
+        reason: ThrottleReason,
+        message: String,
+        retry_after_secs: Option<u64>,
+    },
+}
+
+/// Why a provider turned a request away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleReason {
+    /// Too many requests (HTTP 429).
+    RateLimit,
+    /// The provider is overloaded (HTTP 529 or 503).
+    Overloaded,
+    /// A usage limit or quota is used up; lasts until it resets.
+    Quota,
+}
+
+/// An action an agent is asking permission for, as recognized by
+/// `AgentAdapter::parse_permission_prompt`. `tool` uses Claude Code's tool
+/// names (`Bash`, `Read`, `Edit`, `Write`, ...); `target` is the full
+/// command text, lines joined with `\n`, or the file path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+pub struct PermissionRequest {
+    pub tool: String,
+    pub target: String,
+}
+
+/// Knowledge about one agent CLI that the rest of the backend needs to
+/// drive it: how to start and resume it, how it asks for input, and what
+/// its output means.
+///
+/// Built-in implementations live in the `agent` module and are looked up
+/// by session mode through `agent::registry()`. User-defined agents are not
+/// adapters; they are configured as `CustomAgent`s instead.
+pub trait AgentAdapter: Send + Sync {
+    /// Stable identifier, used as the agent's key in settings and logs.
+    fn name(&self) -> &'static str;
+
+    /// Command-line template used when the user has not configured one.
+    fn default_command(&self) -> &'static str;
+
+    /// Whether the CLI can be configured as a client of Maestro's MCP
+    /// server; only such agents get its address (see `mcp::agent_env`).
+    fn uses_mcp(&self) -> bool {
+        false
+    }
+
+    /// Arguments appended to the command line to resume a conversation:
+    /// the one with `agent_session_id` if known and supported, otherwise
+    /// the most recent one in the working directory. `None` if the CLI
+    /// cannot resume.
+    fn resume_args(&self, _agent_session_id: Option<&str>) -> Option<String> {
+        None
+    }
+
+    /// Regexes that, matched near the end of the output, mean the agent is
+    /// waiting for the user. Used in addition to the generic patterns in
+    /// `status_inference`.
+    fn prompt_patterns(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Recognizes a permission prompt at the end of the output, given its
+    /// last non-empty, escape-stripped lines. `None` means the prompt is not
+    /// understood and is always left to the user.
+    fn parse_permission_prompt(&self, _lines: &[&str]) -> Option<PermissionRequest> {
+        None
+    }
+
+    /// Keystrokes that approve the prompt `parse_permission_prompt`
+    /// recognized, once.
+    fn approve_keys(&self) -> &'static str {
+        "\r"
+    }
+
+    /// Text typed into the agent's input box for a task about `files`. The
+    /// default lists the files below the text.
+    fn compose_prompt(&self, text: &str, files: &[String]) -> String {
+        list_files_prompt(text, files)
+    }
+
+    /// Keystrokes that submit a composed prompt.
+    fn submit_keys(&self) -> &'static str {
+        "\r"
+    }
+
+    /// Interprets one complete, escape-stripped output line. A line may
+    /// carry several events, e.g. a structured message with multiple parts.
+    fn parse_output(&self, _line: &str) -> Vec<AgentEvent> {
+        Vec::new()
+    }
+}
+
+/// Appends `files` to `text` as a plain list, for agents without a file
+/// reference syntax.
+pub fn list_files_prompt(text: &str, files: &[String]) -> String {
+    if files.is_empty() {
+        return text.to_string();
+    }
+    let list: Vec<String> = files.iter().map(|f| format!("- {f}")).collect();
+    format!("{text}\n\nRelevant files:\n{}", list.join("\n"))
+}
+
+/// Appends `files` to `text` as `@path` mentions, which Claude Code and
+/// Gemini CLI resolve to the file contents.
+pub fn mention_files_prompt(text: &str, files: &[String]) -> String {
+    if files.is_empty() {
+        return text.to_string();
+    }
+    let mentions: Vec<String> = files.iter().map(|f| format!("@{f}")).collect();
+    format!("{text}\n\n{}", mentions.join(" "))
+}
+
+/// Longest message kept in a `Throttled` event.
+const MAX_THROTTLE_MESSAGE_CHARS: usize = 300;
+
+struct ThrottlePatterns {
+    error_line: Regex,
+    reasons: Vec<(ThrottleReason, Regex)>,
+    retry_after: Regex,
+}
+
+fn throttle_patterns() -> &'static ThrottlePatterns {
+    static PATTERNS: OnceLock<ThrottlePatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let regex = |pattern| Regex::new(pattern).expect("valid throttle pattern");
+        ThrottlePatterns {
+            // How the CLIs start the line reporting a failed API request,
+            // after at most a few glyphs of decoration: Claude Code's and
+            // Gemini CLI's `API Error:`, Codex's `stream error:`, and the
+            // usage-limit notices of Claude Code and Codex
+            error_line: regex(
+                r"^[^\w\[]{0,3}\s*\[?(?:API Error:?\s|stream error:|Claude AI usage limit reached\||You've hit your usage limit)",
+            ),
+            reasons: vec![
+                (
+                    ThrottleReason::Quota,
+                    regex(
+                        r"(?i)usage limit reached|hit your usage limit|quota exceeded|exceeded your current quota|resource_exhausted|credit balance is too low",
+                    ),
+                ),
+                (
+                    ThrottleReason::Overloaded,
+                    regex(r"(?i)overloaded_error|api error:? 5(29|03)\b|(model|servers?) (is |are )?(currently )?overloaded"),
+                ),
+                (
+                    ThrottleReason::RateLimit,
+                    regex(r"(?i)rate_limit_error|api error:? 429\b|429 too many requests|rate limit (reached|exceeded)"),
+                ),
+            ],
+            retry_after: regex(
+                r"(?i)(?:retry|try again)(?:-after:| after| in)\s*(\d+)\s*(s|secs?|seconds?|m|mins?|minutes?)?\b",
+            ),
+        }
+    })
+}
+
+/// Recognizes the rate-limit, overload, and quota errors that agent CLIs
+/// print when their provider's API turns them away, in one escape-stripped
+/// output line. Only the CLIs' own error lines count, so an agent merely
+/// talking about rate limits is not taken for a throttled one. Shared by
+/// the built-in adapters, whose providers report these in similar words.
+pub fn parse_throttle(line: &str) -> Option<AgentEvent> {
+    let line = line.trim_start();
+    if !throttle_patterns().error_line.is_match(line) {
+        return None;
+    }
+    parse_throttle_error(line)
+}
+
+/// Classifies a message already known to be an API error, such as the
+/// `result` of a failed Claude Code run, like `parse_throttle`.
+pub fn parse_throttle_error(message: &str) -> Option<AgentEvent> {
+    let patterns = throttle_patterns();
+    let reason = patterns
+        .reasons
+        .iter()
+        .find(|(_, regex)| regex.is_match(message))
+        .map(|(reason, _)| *reason)?;
+    let retry_after_secs = patterns.retry_after.captures(message).and_then(|c| {
+        let n: u64 = c.get(1)?.as_str().parse().ok()?;
+        let minutes = c.get(2).is_some_and(|unit| unit.as_str().to_ascii_lowercase().starts_with('m'));
+        Some(if minutes { n * 60 } else { n })
+    });
+    Some(AgentEvent::Throttled {
+        reason,
+        message: message.trim().chars().take(MAX_THROTTLE_MESSAGE_CHARS).collect(),
+        retry_after_secs,
+    })
+}
+
+/// Returns `true` if an agent session ID is safe to put on a command line
+/// unquoted. IDs are read from agent output, so anything else is ignored.
+pub fn is_valid_agent_session_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
@@ -0,0 +1,427 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::agent_adapter;
+use super::clock;
+use super::error::PtyError;
+use super::process_manager::ProcessManager;
+use super::session_manager::{AiMode, SessionConfig};
+use super::storage;
+use crate::agent;
+
+/// Current on-disk format of `agent-commands.json`.
+const AGENT_COMMANDS_VERSION: u32 = 1;
+
+/// Pause between pasting a prompt and submitting it. Agent TUIs treat a
+/// submit key arriving in the same read as the paste as part of the paste.
+const SUBMIT_DELAY: Duration = Duration::from_millis(150);
+
+/// Prompts longer than this are saved to a file and referenced instead of
+/// pasted, since large pastes are collapsed or truncated by some agents.
+const MAX_INLINE_PROMPT_BYTES: usize = 16 * 1024;
+
+/// Prompt files kept in `prompts/`; the oldest are deleted first.
+const MAX_PROMPT_FILES: usize = 100;
+
+/// Start and end markers of a bracketed paste.
+const PASTE_START: &str = "\x1b[200~";
+const PASTE_END: &str = "\x1b[201~";
+
+/// Shell command line used to start each agent CLI, typed into the session's
+/// shell so the user lands back at a prompt when the agent exits.
+///
+/// Templates may reference `{session_id}`, `{branch}`, and `{worktree}`;
+/// values are shell-quoted on expansion. An empty template disables
+/// auto-launch for that mode. Defaults come from each mode's `AgentAdapter`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct AgentCommands {
+    pub claude: String,
+    pub gemini: String,
+    pub codex: String,
+}
+
+impl Default for AgentCommands {
+    fn default() -> Self {
+        let default = |mode: AiMode| {
+            agent::registry()
+                .get(&mode)
+                .map_or("", |adapter| adapter.default_command())
+                .to_string()
+        };
+        Self {
+            claude: default(AiMode::Claude),
+            gemini: default(AiMode::Gemini),
+            codex: default(AiMode::Codex),
+        }
+    }
+}
+
+impl AgentCommands {
+    /// Returns the template for a built-in mode.
+    fn template(&self, mode: &AiMode) -> Option<&str> {
+        match mode {
+            AiMode::Claude => Some(&self.claude),
+            AiMode::Gemini => Some(&self.gemini),
+            AiMode::Codex => Some(&self.codex),
+            AiMode::Plain | AiMode::Custom(_) => None,
+        }
+    }
+}
+
+/// A user-defined agent, selected with `AiMode::Custom(name)`.
+///
+/// `command` is the executable; each entry of `args` is a template with the
+/// same placeholders as `AgentCommands` and becomes one shell-quoted
+/// argument. `env` is set for the agent process only. `prompt_patterns` are
+/// regexes that, when matched near the end of the output, mark the session
+/// as `NeedsInput` in addition to the built-in patterns.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CustomAgent {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub prompt_patterns: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AgentCommandsFile {
+    version: u32,
+    commands: AgentCommands,
+    #[serde(default)]
+    custom: Vec<CustomAgent>,
+}
+
+/// In-memory state; `prompts` caches the compiled `prompt_patterns` of each
+/// custom agent by name.
+#[derive(Default)]
+struct Registry {
+    commands: AgentCommands,
+    custom: Vec<CustomAgent>,
+    prompts: HashMap<String, RegexSet>,
+}
+
+impl Registry {
+    fn file(&self) -> AgentCommandsFile {
+        AgentCommandsFile {
+            version: AGENT_COMMANDS_VERSION,
+            commands: self.commands.clone(),
+            custom: self.custom.clone(),
+        }
+    }
+}
+
+/// Starts the configured agent CLI inside a session's PTY.
+///
+/// Holds the per-mode command templates and the custom agent registry, both
+/// persisted to `agent-commands.json` in the data dir. Designed to be placed
+/// in Tauri managed state.
+pub struct AgentLauncher {
+    path: PathBuf,
+    registry: Mutex<Registry>,
+}
+
+impl AgentLauncher {
+    /// Loads the command templates and custom agents, falling back to the
+    /// defaults if the file is missing, unreadable, or from a newer version.
+    /// Custom agents whose prompt patterns no longer compile are kept but
+    /// contribute no patterns.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("agent-commands.json");
+        let mut registry = Registry::default();
+        match storage::load_json::<AgentCommandsFile>(&path) {
+            Ok(Some(file)) if file.version <= AGENT_COMMANDS_VERSION => {
+                registry.commands = file.commands;
+                for agent in file.custom {
+                    match RegexSet::new(&agent.prompt_patterns) {
+                        Ok(set) => {
+                            registry.prompts.insert(agent.name.clone(), set);
+                        }
+                        Err(e) => log::warn!("Ignoring prompt patterns of agent '{}': {e}", agent.name),
+                    }
+                    registry.custom.push(agent);
+                }
+            }
+            Ok(Some(file)) => {
+                log::error!(
+                    "{} has version {} (newer than supported {}); using default agent commands",
+                    path.display(),
+                    file.version,
+                    AGENT_COMMANDS_VERSION
+                );
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to load {}: {e}; using default agent commands", path.display());
+            }
+        }
+        Self {
+            path,
+            registry: Mutex::new(registry),
+        }
+    }
+
+    /// Returns a snapshot of the current command templates.
+    pub fn commands(&self) -> AgentCommands {
+        self.lock().commands.clone()
+    }
+
+    /// Replaces the command templates and persists them.
+    pub fn set_commands(&self, commands: AgentCommands) -> io::Result<()> {
+        let mut registry = self.lock();
+        let mut file = registry.file();
+        file.commands = commands.clone();
+        storage::save_json(&self.path, &file)?;
+        registry.commands = commands;
+        Ok(())
+    }
+
+    /// Returns all custom agents in registration order.
+    pub fn custom_agents(&self) -> Vec<CustomAgent> {
+        self.lock().custom.clone()
+    }
+
+    /// Returns `true` if a custom agent with this name is registered.
+    pub fn has_custom_agent(&self, name: &str) -> bool {
+        self.lock().custom.iter().any(|a| a.name == name)
+    }
+
+    /// Adds a custom agent, or replaces the one with the same name, and
+    /// persists the registry. Fails if the name or command is empty or a
+    /// prompt pattern is not a valid regex.
+    pub fn save_custom_agent(&self, agent: CustomAgent) -> Result<(), String> {
+        if agent.name.trim().is_empty() {
+            return Err("Agent name must not be empty".to_string());
+        }
+        if agent.command.trim().is_empty() {
+            return Err(format!("Agent '{}' has an empty command", agent.name));
+        }
+        if let Some(key) = agent.env.keys().find(|k| !is_env_name(k)) {
+            return Err(format!("Invalid environment variable name '{key}'"));
+        }
+        let prompts = RegexSet::new(&agent.prompt_patterns)
+            .map_err(|e| format!("Invalid prompt pattern for agent '{}': {e}", agent.name))?;
+
+        let mut registry = self.lock();
+        let mut file = registry.file();
+        match file.custom.iter_mut().find(|a| a.name == agent.name) {
+            Some(existing) => *existing = agent.clone(),
+            None => file.custom.push(agent.clone()),
+        }
+        storage::save_json(&self.path, &file).map_err(|e| e.to_string())?;
+        registry.custom = file.custom;
+        registry.prompts.insert(agent.name, prompts);
+        Ok(())
+    }
+
+    /// Removes a custom agent and persists the registry. Returns `false` if
+    /// no agent had that name. Sessions still referring to it keep their mode
+    /// but no longer auto-launch.
+    pub fn delete_custom_agent(&self, name: &str) -> Result<bool, String> {
+        let mut registry = self.lock();
+        let mut file = registry.file();
+        let before = file.custom.len();
+        file.custom.retain(|a| a.name != name);
+        if file.custom.len() == before {
+            return Ok(false);
+        }
+        storage::save_json(&self.path, &file).map_err(|e| e.to_string())?;
+        registry.custom = file.custom;
+        registry.prompts.remove(name);
+        Ok(true)
+    }
+
+    /// Returns the compiled prompt patterns of a custom agent, if any.
+    pub fn prompt_patterns(&self, name: &str) -> Option<RegexSet> {
+        self.lock().prompts.get(name).filter(|set| !set.is_empty()).cloned()
+    }
+
+    /// Expands the command line for the session's mode. Returns `None` for
+    /// `Plain` sessions, modes whose template is empty, and unknown custom
+    /// agents. With `resume`, the adapter's resume arguments are appended so
+    /// the agent continues the session's recorded conversation, or its most
+    /// recent one in the worktree; custom agents and agents that cannot
+    /// resume start fresh.
+    pub fn command_for(&self, session: &SessionConfig, resume: bool) -> Option<String> {
+        let registry = self.lock();
+        let command = match session.mode {
+            AiMode::Plain => return None,
+            AiMode::Custom(ref name) => {
+                let agent = registry.custom.iter().find(|a| &a.name == name)?;
+                let mut parts: Vec<String> = agent
+                    .env
+                    .iter()
+                    .map(|(key, value)| format!("{key}={}", shell_quote(value)))
+                    .collect();
+                parts.push(agent.command.clone());
+                parts.extend(
+                    agent
+                        .args
+                        .iter()
+                        .map(|arg| shell_quote(&expand_raw(arg, session))),
+                );
+                parts.join(" ")
+            }
+            ref mode => {
+                let adapter = agent::registry().get(mode)?;
+                let mut command = expand(registry.commands.template(mode)?, session);
+                if command.trim().is_empty() {
+                    return None;
+                }
+                let agent_session_id = session
+                    .agent_session_id
+                    .as_deref()
+                    .filter(|id| agent_adapter::is_valid_agent_session_id(id));
+                if let (true, Some(args)) = (resume, adapter.resume_args(agent_session_id)) {
+                    command.push(' ');
+                    command.push_str(&args);
+                }
+                command
+            }
+        };
+        (!command.trim().is_empty()).then_some(command)
+    }
+
+    /// Types the session's agent command into its bound PTY, resuming the
+    /// last conversation if `resume` is set. Returns the command that was
+    /// sent, or `None` if the session has no PTY or its mode has nothing to
+    /// launch.
+    pub fn launch(
+        &self,
+        processes: &ProcessManager,
+        session: &SessionConfig,
+        resume: bool,
+    ) -> Result<Option<String>, PtyError> {
+        let (Some(pty_id), Some(command)) = (session.pty_id, self.command_for(session, resume)) else {
+            return Ok(None);
+        };
+        processes.write_stdin(pty_id, &format!("{command}\n"))?;
+        log::info!("Launched `{command}` in session {} (pty {pty_id})", session.id);
+        Ok(Some(command))
+    }
+
+    /// Delivers a task to the session's running agent and returns the text
+    /// that was typed.
+    ///
+    /// The prompt is composed by the mode's adapter (custom agents get the
+    /// files as a plain list), sent as a bracketed paste so newlines stay in
+    /// the input box, and submitted with the adapter's submit keys after
+    /// `SUBMIT_DELAY`. Text over `MAX_INLINE_PROMPT_BYTES` is written to
+    /// `prompts/` in the data dir, which keeps the newest `MAX_PROMPT_FILES`,
+    /// and passed as a file instead.
+    pub async fn send_prompt(
+        &self,
+        processes: &ProcessManager,
+        session: &SessionConfig,
+        text: &str,
+        files: &[String],
+    ) -> Result<String, String> {
+        if session.mode == AiMode::Plain {
+            return Err(format!("Session {} has no agent", session.id));
+        }
+        let pty_id = session
+            .pty_id
+            .ok_or_else(|| format!("Session {} has no PTY", session.id))?;
+        if text.trim().is_empty() && files.is_empty() {
+            return Err("Prompt must not be empty".to_string());
+        }
+
+        let mut files = files.to_vec();
+        let text = if text.len() > MAX_INLINE_PROMPT_BYTES {
+            let name = format!("{}-session-{}.md", clock::now_millis(), session.id);
+            let text = text.to_string();
+            let path = tokio::task::spawn_blocking(move || save_prompt_file(&name, &text))
+                .await
+                .map_err(|e| format!("Prompt file task failed: {e}"))?
+                .map_err(|e| format!("Failed to save prompt: {e}"))?;
+            files.insert(0, path.to_string_lossy().into_owned());
+            "Carry out the task described in the first file below.".to_string()
+        } else {
+            text.to_string()
+        };
+
+        let adapter = agent::registry().get(&session.mode);
+        let prompt = match adapter {
+            Some(adapter) => adapter.compose_prompt(&text, &files),
+            None => agent_adapter::list_files_prompt(&text, &files),
+        };
+        let submit = adapter.map_or("\r", |adapter| adapter.submit_keys());
+
+        processes
+            .write_stdin(pty_id, &format!("{PASTE_START}{prompt}{PASTE_END}"))
+            .map_err(|e| e.to_string())?;
+        tokio::time::sleep(SUBMIT_DELAY).await;
+        processes.write_stdin(pty_id, submit).map_err(|e| e.to_string())?;
+        log::info!("Sent a {}-byte prompt to session {}", prompt.len(), session.id);
+        Ok(prompt)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Registry> {
+        match self.registry.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Writes a prompt too long to paste to `prompts/` in the data dir, then
+/// deletes all but the newest `MAX_PROMPT_FILES` there. Names start with
+/// the time they were written, so name order is age order.
+fn save_prompt_file(name: &str, text: &str) -> io::Result<PathBuf> {
+    let dir = storage::data_dir().join("prompts");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(name);
+    std::fs::write(&path, text)?;
+
+    let mut prompts: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    prompts.sort();
+    let excess = prompts.len().saturating_sub(MAX_PROMPT_FILES);
+    for old in &prompts[..excess] {
+        if let Err(e) = std::fs::remove_file(old) {
+            log::warn!("Failed to remove old prompt file {}: {e}", old.display());
+        }
+    }
+    Ok(path)
+}
+
+/// Expands placeholders in a command-line template, shell-quoting each value.
+fn expand(template: &str, session: &SessionConfig) -> String {
+    template
+        .replace("{session_id}", &session.id.to_string())
+        .replace("{branch}", &shell_quote(session.branch.as_deref().unwrap_or("")))
+        .replace("{worktree}", &shell_quote(session.worktree_path.as_deref().unwrap_or("")))
+}
+
+/// Expands placeholders in a single-argument template without quoting; the
+/// caller quotes the whole argument.
+fn expand_raw(template: &str, session: &SessionConfig) -> String {
+    template
+        .replace("{session_id}", &session.id.to_string())
+        .replace("{branch}", session.branch.as_deref().unwrap_or(""))
+        .replace("{worktree}", session.worktree_path.as_deref().unwrap_or(""))
+}
+
+/// Returns `true` for names usable as `NAME=value` shell assignments.
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Wraps `value` in single quotes for POSIX shells.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,113 @@
+use dashmap::DashMap;
+use regex::Regex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::agent_adapter::{self, AgentEvent};
+use super::guardrails::Guardrails;
+use super::process_manager::PtyListener;
+use super::provider_usage::UsageTracker;
+use super::session_manager::{SessionConfig, SessionManager};
+use super::status_inference::{visible_line, ANSI_ESCAPE_PATTERN};
+use super::transcript::TranscriptStore;
+use crate::agent;
+
+/// Longest line buffered while waiting for its newline. Structured agent
+/// output puts whole tool results on one line, so this is far larger than
+/// a terminal line; anything longer is dropped rather than parsed.
+const MAX_LINE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Turns agent output into structured events.
+///
+/// Registered as a `PtyListener`; reassembles the output of every PTY bound
+/// to a session with a built-in agent into complete lines and passes each
+/// to the mode's `AgentAdapter`. Every resulting event goes through
+/// `record_event`.
+pub struct AgentOutputPipeline {
+    app_handle: AppHandle,
+    ansi: Regex,
+    /// Unterminated last line of each PTY's output.
+    partial: DashMap<u32, String>,
+}
+
+impl AgentOutputPipeline {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            ansi: Regex::new(ANSI_ESCAPE_PATTERN).expect("valid escape pattern"),
+            partial: DashMap::new(),
+        }
+    }
+
+    /// Appends `text` to the PTY's partial line and returns the lines it
+    /// completed.
+    fn complete_lines(&self, pty_id: u32, text: &str) -> Vec<String> {
+        let mut partial = self.partial.entry(pty_id).or_default();
+        partial.push_str(text);
+        let Some(end) = partial.rfind('\n') else {
+            if partial.len() > MAX_LINE_BYTES {
+                log::warn!("Dropping over-long output line of PTY {pty_id}");
+                partial.clear();
+            }
+            return Vec::new();
+        };
+        let rest = partial.split_off(end + 1);
+        let lines = partial.lines().map(str::to_string).collect();
+        *partial = rest;
+        lines
+    }
+}
+
+impl PtyListener for AgentOutputPipeline {
+    fn on_output(&self, pty_id: u32, text: &str) {
+        let sessions = self.app_handle.state::<SessionManager>();
+        let Some(session) = sessions.session_for_pty(pty_id) else {
+            return;
+        };
+        let Some(adapter) = agent::registry().get(&session.mode) else {
+            return;
+        };
+
+        for line in self.complete_lines(pty_id, text) {
+            let line = self.ansi.replace_all(&line, "");
+            for event in adapter.parse_output(visible_line(&line)) {
+                record_event(&self.app_handle, &session, event);
+            }
+        }
+    }
+
+    fn on_exit(&self, pty_id: u32, _exit_code: Option<u32>) {
+        self.partial.remove(&pty_id);
+    }
+}
+
+/// Publishes an agent event: emits it on `agent-event-{session_id}`,
+/// alongside the raw `pty-output-{id}` stream, appends it to the session's
+/// transcript, updates the session with reported costs and agent session
+/// IDs, counts usage and throttling per provider, and screens shell tool
+/// calls with the guardrails. Reads `SessionManager`, `TranscriptStore`,
+/// `UsageTracker`, and `Guardrails` from managed state.
+pub fn record_event(app: &AppHandle, session: &SessionConfig, event: AgentEvent) {
+    if let Some(usage) = app.try_state::<UsageTracker>() {
+        usage.record(session, &event);
+    }
+    if let (AgentEvent::ToolCall { name, input, .. }, Some(guardrails)) = (&event, app.try_state::<Guardrails>()) {
+        guardrails.screen_tool_call(app, session, name, input);
+    }
+    match event {
+        AgentEvent::Cost { usd } => {
+            app.state::<SessionManager>().add_cost(session.id, usd);
+        }
+        AgentEvent::AgentSession { ref id }
+            if agent_adapter::is_valid_agent_session_id(id)
+                && session.agent_session_id.as_ref() != Some(id) =>
+        {
+            app.state::<SessionManager>()
+                .modify(session.id, |s| s.agent_session_id = Some(id.clone()));
+        }
+        _ => {}
+    }
+    if let Err(e) = app.state::<TranscriptStore>().append(session, &event) {
+        log::warn!("Failed to record transcript of session {}: {e}", session.id);
+    }
+    let _ = app.emit(&format!("agent-event-{}", session.id), &event);
+}
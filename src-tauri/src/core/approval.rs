@@ -0,0 +1,145 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::agent_adapter::PermissionRequest;
+
+/// Tools whose target is a file path, so `path_scopes` applies to them.
+const FILE_TOOLS: &[&str] = &["Read", "Edit", "Write"];
+
+/// Characters that let a command run more than what a rule describes:
+/// chaining, pipes, substitution, redirection, and further lines.
+const SHELL_METACHARACTERS: &[char] = &[';', '&', '|', '$', '(', ')', '`', '<', '>', '\n'];
+
+/// Which agent permission prompts a session answers on its own.
+///
+/// Rules use the form `Tool` or `Tool(pattern)`, where `pattern` matches
+/// the action's target (a command line or file path) and `*` matches any
+/// run of characters, e.g. `Read`, `Bash(npm test*)`, `Edit(src/*)`. A
+/// prompt is approved only if an `allow` rule matches, no `deny` rule
+/// does, and, for file tools, the path lies inside one of `path_scopes`.
+/// A command containing shell metacharacters (`;&|$()` and the like, or a
+/// newline) is only approved by an `allow` rule naming it exactly, so
+/// `Bash(npm test*)` does not approve `npm test && rm -rf ~`. Everything
+/// else is left to the user, so an empty policy always asks.
+///
+/// `path_scopes` are relative to the session's worktree unless absolute;
+/// empty means the worktree itself.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ApprovalPolicy {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    #[serde(default)]
+    pub path_scopes: Vec<String>,
+}
+
+/// Outcome of `ApprovalPolicy::evaluate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Approve,
+    Ask,
+}
+
+impl ApprovalPolicy {
+    /// Rejects rules that are not `Tool` or `Tool(pattern)`.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.allow.iter().chain(&self.deny).find(|r| parse_rule(r).is_none()) {
+            Some(rule) => Err(format!("Invalid approval rule '{rule}'")),
+            None => Ok(()),
+        }
+    }
+
+    /// Decides whether `request` may be approved for a session working in
+    /// `worktree`.
+    pub fn evaluate(&self, request: &PermissionRequest, worktree: Option<&Path>) -> ApprovalDecision {
+        let file_tool = FILE_TOOLS.contains(&request.tool.as_str());
+        let exact = !file_tool && request.target.contains(SHELL_METACHARACTERS);
+        if self.deny.iter().any(|rule| rule_matches(rule, request, false))
+            || !self.allow.iter().any(|rule| rule_matches(rule, request, exact))
+        {
+            return ApprovalDecision::Ask;
+        }
+        if file_tool && !self.in_scope(&request.target, worktree) {
+            return ApprovalDecision::Ask;
+        }
+        ApprovalDecision::Approve
+    }
+
+    fn in_scope(&self, target: &str, worktree: Option<&Path>) -> bool {
+        let Some(worktree) = worktree else {
+            return false;
+        };
+        let path = normalize(&worktree.join(target));
+        if self.path_scopes.is_empty() {
+            return path.starts_with(normalize(worktree));
+        }
+        self.path_scopes
+            .iter()
+            .any(|scope| path.starts_with(normalize(&worktree.join(scope))))
+    }
+}
+
+/// Splits a rule into its tool and optional target pattern.
+fn parse_rule(rule: &str) -> Option<(&str, Option<&str>)> {
+    let rule = rule.trim();
+    match rule.split_once('(') {
+        Some((tool, rest)) => {
+            let pattern = rest.strip_suffix(')')?;
+            (!tool.is_empty()).then_some((tool, Some(pattern)))
+        }
+        None => (!rule.is_empty()).then_some((rule, None)),
+    }
+}
+
+/// Whether `rule` covers `request`. With `exact`, only a rule whose
+/// pattern is the target itself does; `*` matches literally then.
+fn rule_matches(rule: &str, request: &PermissionRequest, exact: bool) -> bool {
+    match parse_rule(rule) {
+        Some((tool, pattern)) if tool == request.tool => match (pattern, exact) {
+            (Some(p), true) => p == request.target,
+            (Some(p), false) => wildcard_match(p, &request.target),
+            (None, exact) => !exact,
+        },
+        _ => false,
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` matches any run of
+/// characters and everything else matches literally.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Resolves `.` and `..` lexically, so a target cannot escape its scope
+/// with `..` segments. Symlinks are not followed.
+pub(super) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
@@ -0,0 +1,214 @@
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+use super::clock;
+use super::storage;
+
+/// Entries kept when the log is trimmed at startup.
+const MAX_ENTRIES: usize = 50_000;
+
+/// Entries `query` returns when the filter gives no limit.
+const DEFAULT_QUERY_LIMIT: usize = 200;
+
+/// Who issued a recorded command: a window of the app, an agent through
+/// the MCP server, a client of the HTTP API, a `maestro://` link, or
+/// Maestro itself (scheduled prunes, retention cleanup, auto-push, merge
+/// cleanup, and watchdog restarts).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSource {
+    Window,
+    Mcp,
+    Api,
+    DeepLink,
+    Automation,
+}
+
+/// One recorded command. `origin` is the window label for `Window`
+/// commands, the client address for `Api` ones, and the task's name for
+/// `Automation` ones; `session_id` is taken
+/// from the parameters when they name a session. `error` is set if the
+/// command failed. `at` is a Unix epoch timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AuditEntry {
+    pub at: i64,
+    pub source: AuditSource,
+    pub origin: Option<String>,
+    pub command: String,
+    pub session_id: Option<u32>,
+    pub params: Value,
+    pub error: Option<String>,
+}
+
+/// Which entries `AuditLog::query` returns. Every field set must match;
+/// `since` and `until` bound `at` inclusively.
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub command: Option<String>,
+    pub source: Option<AuditSource>,
+    pub session_id: Option<u32>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub failed_only: bool,
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.command.as_ref().is_none_or(|c| *c == entry.command)
+            && self.source.is_none_or(|s| s == entry.source)
+            && self.session_id.is_none_or(|id| entry.session_id == Some(id))
+            && self.since.is_none_or(|since| entry.at >= since)
+            && self.until.is_none_or(|until| entry.at <= until)
+            && (!self.failed_only || entry.error.is_some())
+    }
+}
+
+struct Inner {
+    path: PathBuf,
+    /// Serializes appends so concurrent commands never interleave lines.
+    write_lock: Mutex<()>,
+}
+
+/// Append-only record of the commands that change worktrees, branches,
+/// sessions, and files, whoever issued them, in `audit-log.jsonl` in the
+/// data dir. Trimmed to the newest `MAX_ENTRIES` at startup. Cheap to
+/// clone; placed in Tauri managed state.
+#[derive(Clone)]
+pub struct AuditLog {
+    inner: Arc<Inner>,
+}
+
+impl AuditLog {
+    /// Opens the log in the data dir, trimming it if it grew too long.
+    pub fn load() -> Self {
+        let log = Self {
+            inner: Arc::new(Inner {
+                path: storage::data_dir().join("audit-log.jsonl"),
+                write_lock: Mutex::new(()),
+            }),
+        };
+        if let Err(e) = log.trim() {
+            log::warn!("Failed to trim the audit log: {e}");
+        }
+        log
+    }
+
+    /// Appends an entry for a finished command. Failing to write is only
+    /// logged, so auditing never fails the command itself.
+    pub fn record<T, E: Display>(
+        &self,
+        source: AuditSource,
+        origin: Option<&str>,
+        command: &str,
+        params: Value,
+        result: &Result<T, E>,
+    ) {
+        let entry = AuditEntry {
+            at: clock::now_secs(),
+            source,
+            origin: origin.map(str::to_string),
+            command: command.to_string(),
+            session_id: params
+                .get("session_id")
+                .and_then(Value::as_u64)
+                .and_then(|id| u32::try_from(id).ok()),
+            params,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        };
+        if let Err(e) = self.append(&entry) {
+            log::warn!("Failed to record {command} in the audit log: {e}");
+        }
+    }
+
+    /// Records an action Maestro took on its own; `task` names what took it
+    /// (e.g. `scheduled_prune`).
+    pub fn record_automation<T, E: Display>(&self, task: &str, command: &str, params: Value, result: &Result<T, E>) {
+        self.record(AuditSource::Automation, Some(task), command, params, result);
+    }
+
+    /// Runs a command issued from the window labelled `window` and records
+    /// it with its outcome.
+    pub async fn track<T, E: Display>(
+        &self,
+        window: &str,
+        command: &str,
+        params: Value,
+        run: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let result = run.await;
+        self.record(AuditSource::Window, Some(window), command, params, &result);
+        result
+    }
+
+    /// Returns the entries matching `filter`, newest first. Lines that
+    /// fail to parse are skipped. Reads the whole file, so call it off the
+    /// async runtime.
+    pub fn query(&self, filter: &AuditFilter) -> io::Result<Vec<AuditEntry>> {
+        let limit = filter.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+        Ok(self
+            .read()?
+            .into_iter()
+            .rev()
+            .filter(|entry| filter.matches(entry))
+            .take(limit)
+            .collect())
+    }
+
+    fn append(&self, entry: &AuditEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        line.push(b'\n');
+        let _guard = self.lock();
+        std::fs::create_dir_all(storage::data_dir())?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.inner.path)?
+            .write_all(&line)
+    }
+
+    fn read(&self) -> io::Result<Vec<AuditEntry>> {
+        let file = match std::fs::File::open(&self.inner.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect())
+    }
+
+    fn trim(&self) -> io::Result<()> {
+        let entries = self.read()?;
+        if entries.len() <= MAX_ENTRIES {
+            return Ok(());
+        }
+        let mut kept = Vec::new();
+        for entry in &entries[entries.len() - MAX_ENTRIES..] {
+            serde_json::to_writer(&mut kept, entry).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            kept.push(b'\n');
+        }
+        let _guard = self.lock();
+        let tmp = self.inner.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, kept)?;
+        std::fs::rename(&tmp, &self.inner.path)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        match self.inner.write_lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::audit::AuditLog;
+use super::session_lock::OperationLock;
+use super::session_manager::{SessionConfig, SessionListener, SessionManager, SessionStatus, StatusTransition};
+use super::windows;
+use super::worktree_manager::WorktreeManager;
+use crate::git::PushResult;
+
+/// Event emitted with an `AutoPushOutcome` after every automatic push.
+pub const SESSION_AUTO_PUSH_EVENT: &str = "session-auto-push";
+
+/// Per-session setting to push the branch when the session reaches
+/// `Done`. With `force_with_lease`, a branch the agent rewrote (e.g. by
+/// rebasing) is pushed over its remote copy unless someone else pushed to
+/// it meanwhile; a plain force push is never made.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct AutoPushConfig {
+    pub remote: String,
+    #[serde(default)]
+    pub force_with_lease: bool,
+}
+
+/// Payload of `session-auto-push`: `push` is git's outcome, which may be a
+/// rejection, or `error` why no push happened.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AutoPushOutcome {
+    pub session_id: u32,
+    pub remote: String,
+    pub push: Option<PushResult>,
+    pub error: Option<String>,
+}
+
+/// Pushes a session's worktree branch with upstream tracking when it
+/// reaches `Done`, if its `auto_push` is set. Only committed work is
+/// pushed. A session busy with another operation (see
+/// `session_lock::OperationLock`) is skipped and reported as an error. Each
+/// push is recorded in the audit log. Registered as a `SessionListener`.
+pub struct AutoPusher {
+    app_handle: AppHandle,
+}
+
+impl AutoPusher {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl SessionListener for AutoPusher {
+    fn on_status_changed(&self, session: &SessionConfig, transition: &StatusTransition) {
+        let Some(config) = session.auto_push.clone() else {
+            return;
+        };
+        if transition.to != SessionStatus::Done {
+            return;
+        }
+        let app = self.app_handle.clone();
+        let session = session.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = push(&app, &session, &config).await;
+            let params = serde_json::json!({
+                "session_id": session.id,
+                "remote": config.remote,
+                "force_with_lease": config.force_with_lease,
+            });
+            app.state::<AuditLog>().record_automation("auto_push", "worktree_publish", params, &result);
+            let outcome = match result {
+                Ok(push) => {
+                    if push.rejected {
+                        log::warn!("Session {}: auto-push rejected: {}", session.id, push.summary);
+                    }
+                    AutoPushOutcome {
+                        session_id: session.id,
+                        remote: config.remote,
+                        push: Some(push),
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Session {}: auto-push failed: {e}", session.id);
+                    AutoPushOutcome {
+                        session_id: session.id,
+                        remote: config.remote,
+                        push: None,
+                        error: Some(e),
+                    }
+                }
+            };
+            windows::emit_to_session(&app, session.id, SESSION_AUTO_PUSH_EVENT, &outcome);
+        });
+    }
+}
+
+async fn push(app: &AppHandle, session: &SessionConfig, config: &AutoPushConfig) -> Result<PushResult, String> {
+    let (Some(repo_path), Some(wt_path)) = (session.repo(), &session.worktree_path) else {
+        return Err(format!("Session {} has no worktree", session.id));
+    };
+    let sessions = app.state::<SessionManager>();
+    let _lock = OperationLock::for_session(&sessions, "auto-push", session.id)
+        .map_err(|e| format!("Skipped: {}", e.message))?;
+    app.state::<WorktreeManager>()
+        .publish(repo_path, Path::new(wt_path), &config.remote, config.force_with_lease)
+        .await
+        .map_err(|e| e.to_string())
+}
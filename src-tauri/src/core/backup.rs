@@ -0,0 +1,309 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::clock;
+use super::storage;
+
+/// Current format of backup archives; restoring a newer one is refused.
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Name of the manifest at the root of a backup archive.
+const MANIFEST: &str = "manifest.json";
+
+/// Files of the data dir included in a backup: the settings and policies,
+/// startup profiles, snippets, sessions and their archive, queued tasks, and the
+/// worktree metadata registry. `api-server.json` and `mcp-server.json`
+/// hold access tokens and are left out; new ones are generated.
+const BACKUP_FILES: &[&str] = &[
+    "settings.json",
+    "agent-commands.json",
+    "watchdog-policy.json",
+    "retention-policy.json",
+    "guardrail-policy.json",
+    "pr-templates.json",
+    "startup-profiles.json",
+    "snippets.json",
+    "sessions.json",
+    "session-archive.json",
+    "task-queue.json",
+    "worktree-registry.json",
+];
+
+/// Directories of the data dir included in a backup, with everything in
+/// them: transcripts, scratchpads, and harvested session results.
+const BACKUP_DIRS: &[&str] = &["transcripts", "scratchpads", "results"];
+
+/// How many of the backups `restore` takes of the current data are kept in
+/// `<data dir>/backups`; older ones are deleted.
+const KEPT_SAFETY_BACKUPS: usize = 5;
+
+/// What a backup archive holds besides its files.
+#[derive(Serialize, Deserialize)]
+struct BackupManifest {
+    format: u32,
+    app_version: String,
+    created_at: i64,
+    files: Vec<String>,
+}
+
+/// A backup written by `backup` or staged by `restore`. `files` are the
+/// paths inside the data dir.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BackupSummary {
+    pub path: String,
+    pub size: u64,
+    pub app_version: String,
+    pub created_at: i64,
+    pub files: Vec<String>,
+}
+
+/// Directory a restored backup is staged in until the next start.
+fn pending_dir() -> PathBuf {
+    storage::data_dir().join("restore-pending")
+}
+
+/// Writes Maestro's data (see `BACKUP_FILES` and `BACKUP_DIRS`, worktree
+/// contents excluded) to a zip at `dest`, or to
+/// `maestro-backup-<unix ms>.zip` inside `dest` if it is a directory.
+pub async fn backup(dest: PathBuf) -> Result<BackupSummary, String> {
+    tokio::task::spawn_blocking(move || {
+        let path = if dest.is_dir() {
+            dest.join(format!("maestro-backup-{}.zip", clock::now_millis()))
+        } else {
+            dest
+        };
+        let manifest = write_backup(&path).map_err(|e| {
+            let _ = std::fs::remove_file(&path);
+            format!("Failed to write {}: {e}", path.display())
+        })?;
+        log::info!("Backup of {} files written to {}", manifest.files.len(), path.display());
+        Ok(summary(&path, manifest))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn write_backup(path: &Path) -> io::Result<BackupManifest> {
+    let data_dir = storage::data_dir();
+    let mut files: Vec<String> = BACKUP_FILES
+        .iter()
+        .filter(|name| data_dir.join(name).is_file())
+        .map(|name| name.to_string())
+        .collect();
+    for dir in BACKUP_DIRS {
+        collect_files(&data_dir, &data_dir.join(dir), &mut files);
+    }
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: clock::now_secs(),
+        files,
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(MANIFEST, options).map_err(io::Error::other)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(io::Error::other)?)?;
+    for name in &manifest.files {
+        zip.start_file(format!("data/{name}"), options).map_err(io::Error::other)?;
+        zip.write_all(&std::fs::read(data_dir.join(name))?)?;
+    }
+    zip.finish().map_err(io::Error::other)?;
+    Ok(manifest)
+}
+
+/// Appends the files below `dir`, as `/`-separated paths relative to
+/// `root`, in name order.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_files(root, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+            files.push(parts.join("/"));
+        }
+    }
+}
+
+/// Checks the backup at `src` and stages it to replace Maestro's data at
+/// the next start (see `apply_pending_restore`), since the running app
+/// keeps its state in memory and would overwrite restored files. Refuses
+/// backups of a newer archive format or Maestro version; files of older
+/// ones are upgraded by `migrations` at the next start. The current data
+/// is first backed up to `<data dir>/backups`, which keeps the last
+/// `KEPT_SAFETY_BACKUPS`.
+pub async fn restore(src: PathBuf) -> Result<BackupSummary, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut archive = File::open(&src)
+            .map_err(io::Error::other)
+            .and_then(|file| ZipArchive::new(file).map_err(io::Error::other))
+            .map_err(|e| format!("Failed to open {}: {e}", src.display()))?;
+        let manifest = read_manifest(&mut archive)?;
+        check_version(&manifest)?;
+
+        let backups = storage::data_dir().join("backups");
+        let safety = backups.join(format!("pre-restore-{}.zip", clock::now_millis()));
+        write_backup(&safety).map_err(|e| format!("Failed to back up the current data: {e}"))?;
+        prune_safety_backups(&backups);
+
+        let staging = pending_dir();
+        let _ = std::fs::remove_dir_all(&staging);
+        stage(&mut archive, &manifest, &staging).map_err(|e| {
+            let _ = std::fs::remove_dir_all(&staging);
+            format!("Failed to extract {}: {e}", src.display())
+        })?;
+        log::info!(
+            "Restore of {} staged for the next start; current data backed up to {}",
+            src.display(),
+            safety.display()
+        );
+        Ok(summary(&src, manifest))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Deletes all but the newest `KEPT_SAFETY_BACKUPS` backups `restore` wrote
+/// to `dir`. Their names end in the time they were taken, so name order is
+/// age order.
+fn prune_safety_backups(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("pre-restore-") && name.ends_with(".zip"))
+        })
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(KEPT_SAFETY_BACKUPS);
+    for path in &backups[..excess] {
+        if let Err(e) = std::fs::remove_file(path) {
+            log::warn!("Failed to remove old backup {}: {e}", path.display());
+        }
+    }
+}
+
+fn read_manifest(archive: &mut ZipArchive<File>) -> Result<BackupManifest, String> {
+    let mut json = String::new();
+    archive
+        .by_name(MANIFEST)
+        .map_err(|_| "Not a Maestro backup: no manifest".to_string())?
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read the manifest: {e}"))?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid manifest: {e}"))
+}
+
+fn check_version(manifest: &BackupManifest) -> Result<(), String> {
+    if manifest.format > BACKUP_FORMAT_VERSION {
+        return Err(format!(
+            "Backup format {} is newer than this Maestro supports ({BACKUP_FORMAT_VERSION})",
+            manifest.format
+        ));
+    }
+    let current = env!("CARGO_PKG_VERSION");
+    match (version_triple(&manifest.app_version), version_triple(current)) {
+        (Some(backup), Some(running)) if backup > running => Err(format!(
+            "Backup was made by Maestro {}, newer than this one ({current})",
+            manifest.app_version
+        )),
+        (None, _) => Err(format!("Invalid Maestro version '{}' in backup", manifest.app_version)),
+        _ => Ok(()),
+    }
+}
+
+/// Parses `major.minor.patch`, ignoring any pre-release or build suffix.
+pub(super) fn version_triple(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+/// Extracts the manifest's files into `staging`, accepting only the files
+/// and directories a backup may hold.
+fn stage(archive: &mut ZipArchive<File>, manifest: &BackupManifest, staging: &Path) -> io::Result<()> {
+    for name in &manifest.files {
+        let allowed = BACKUP_FILES.contains(&name.as_str())
+            || BACKUP_DIRS.iter().any(|dir| name.starts_with(&format!("{dir}/")));
+        let relative = Path::new(name);
+        let safe = relative
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+        if !allowed || !safe {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unexpected file {name}")));
+        }
+        let mut entry = archive
+            .by_name(&format!("data/{name}"))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{name}: {e}")))?;
+        let target = staging.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(&target)?)?;
+    }
+    std::fs::write(
+        staging.join(MANIFEST),
+        serde_json::to_vec_pretty(manifest).map_err(io::Error::other)?,
+    )
+}
+
+/// Moves a staged restore into the data dir, so the data dir holds exactly
+/// what the backup does: files and directories the backup leaves out are
+/// removed. Runs at start, once the data dir is locked and before anything
+/// loads its state from it; does nothing if no restore is pending.
+pub fn apply_pending_restore() {
+    let staging = pending_dir();
+    if !staging.join(MANIFEST).is_file() {
+        return;
+    }
+    let data_dir = storage::data_dir();
+    let mut failed = false;
+    for name in BACKUP_FILES.iter().chain(BACKUP_DIRS) {
+        let staged = staging.join(name);
+        let target = data_dir.join(name);
+        let _ = std::fs::remove_dir_all(&target);
+        let _ = std::fs::remove_file(&target);
+        if !staged.exists() {
+            continue;
+        }
+        if let Err(e) = std::fs::rename(&staged, &target) {
+            log::error!("Failed to restore {}: {e}", target.display());
+            failed = true;
+        }
+    }
+    if let Err(e) = std::fs::remove_dir_all(&staging) {
+        log::warn!("Failed to remove {}: {e}", staging.display());
+    }
+    if failed {
+        log::error!("Restore incomplete; the previous data is in {}", data_dir.join("backups").display());
+    } else {
+        log::info!("Restored backup into {}", data_dir.display());
+    }
+}
+
+fn summary(path: &Path, manifest: BackupManifest) -> BackupSummary {
+    BackupSummary {
+        path: path.to_string_lossy().into_owned(),
+        size: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        app_version: manifest.app_version,
+        created_at: manifest.created_at,
+        files: manifest.files,
+    }
+}
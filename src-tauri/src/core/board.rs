@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tokio::task::JoinSet;
+
+use super::clock;
+use super::process_manager::ProcessManager;
+use super::session_manager::{SessionActivity, SessionConfig, SessionManager};
+use super::worktree_manager::WorktreeStatus;
+
+/// One session as the board draws it.
+///
+/// `pty_alive` is whether the session's shell is still running.
+/// `checkout` is the git state of the directory the session works in (its
+/// worktree, else its repository), or `None` if it has neither; sessions
+/// sharing a directory get the same status.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BoardEntry {
+    pub session: SessionConfig,
+    pub pty_alive: bool,
+    pub activity: SessionActivity,
+    pub checkout: Option<WorktreeStatus>,
+}
+
+/// Everything the session board shows, in display order. `at` is the Unix
+/// epoch time the state was gathered.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BoardState {
+    pub at: i64,
+    pub entries: Vec<BoardEntry>,
+}
+
+/// Gathers the sessions, of `repo_path` only if given, with their PTY
+/// liveness, activity, and git status in one pass, so the board is drawn
+/// from a single call instead of one per subsystem and session.
+///
+/// Each directory is checked once, on its own tokio task, so the call
+/// takes roughly as long as the slowest checkout.
+pub async fn board_state(app: &AppHandle, repo_path: Option<&Path>) -> BoardState {
+    let manager = app.state::<SessionManager>();
+    let sessions = match repo_path {
+        Some(repo_path) => manager.sessions_in_repo(repo_path),
+        None => manager.all_sessions(),
+    };
+    let mut activity: HashMap<u32, SessionActivity> =
+        manager.activity().into_iter().map(|a| (a.session_id, a)).collect();
+
+    let mut tasks = JoinSet::new();
+    let mut checked = HashMap::new();
+    for session in &sessions {
+        if let Some(dir) = checkout_dir(session) {
+            if !checked.contains_key(&dir) {
+                checked.insert(dir.clone(), None);
+                tasks.spawn(async move {
+                    let status = WorktreeStatus::of_checkout(&dir).await;
+                    (dir, status)
+                });
+            }
+        }
+    }
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((dir, status)) => {
+                checked.insert(dir, Some(status));
+            }
+            Err(e) => log::warn!("Board checkout status task failed: {e}"),
+        }
+    }
+
+    let pm = app.state::<ProcessManager>();
+    let entries = sessions
+        .into_iter()
+        .filter_map(|session| {
+            let activity = activity.remove(&session.id)?;
+            let checkout = checkout_dir(&session).and_then(|dir| checked.get(&dir).cloned().flatten());
+            Some(BoardEntry {
+                pty_alive: session.pty_id.is_some_and(|id| pm.is_running(id)),
+                activity,
+                checkout,
+                session,
+            })
+        })
+        .collect();
+    BoardState {
+        at: clock::now_secs(),
+        entries,
+    }
+}
+
+/// The directory a session works in.
+fn checkout_dir(session: &SessionConfig) -> Option<PathBuf> {
+    session
+        .worktree_path
+        .as_deref()
+        .or(Some(session.repo_path.as_str()).filter(|p| !p.is_empty()))
+        .map(PathBuf::from)
+}
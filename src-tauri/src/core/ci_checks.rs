@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::clock;
+use super::pull_request::{self, CiStatus};
+use super::session_manager::SessionManager;
+
+/// How long fetched checks are served from the cache.
+const CHECKS_TTL: Duration = Duration::from_secs(60);
+
+/// Result of one check run or commit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckState {
+    Pending,
+    Passed,
+    Failed,
+    /// Neutral or skipped; counts as neither passed nor failed.
+    Skipped,
+}
+
+/// A check run (GitHub Actions and other apps) or a commit status
+/// (older CI integrations) reported for a commit.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BranchCheck {
+    pub name: String,
+    pub state: CheckState,
+    pub url: Option<String>,
+}
+
+/// Checks of the head commit of a branch on GitHub. `sha` is `None` and
+/// `checks` empty if the branch has not been pushed. `fetched_at` is Unix
+/// seconds.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BranchChecks {
+    pub branch: String,
+    pub sha: Option<String>,
+    pub ci: CiStatus,
+    pub checks: Vec<BranchCheck>,
+    pub fetched_at: i64,
+}
+
+#[derive(Deserialize)]
+struct GhCheckRuns {
+    check_runs: Vec<GhCheckRun>,
+}
+
+#[derive(Deserialize)]
+struct GhCheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+    head_sha: String,
+}
+
+#[derive(Deserialize)]
+struct GhCombinedStatus {
+    sha: String,
+    statuses: Vec<GhStatus>,
+}
+
+#[derive(Deserialize)]
+struct GhStatus {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+}
+
+/// Fetches CI results of branches from GitHub with `gh api` and keeps them
+/// for `CHECKS_TTL`, so polling session cards does not hit the API each
+/// time. Each fetch rolls the result up onto the sessions working on the
+/// branch (`SessionConfig::ci_status`). Placed in Tauri managed state.
+pub struct CheckCache {
+    entries: DashMap<(PathBuf, String), BranchChecks>,
+}
+
+impl CheckCache {
+    pub fn new() -> Self {
+        Self {
+            entries: DashMap::new(),
+        }
+    }
+
+    /// Returns the checks of `branch` in the repository's GitHub remote,
+    /// from the cache unless they are stale or `refresh` is set.
+    pub async fn get(
+        &self,
+        sessions: &SessionManager,
+        repo_path: &Path,
+        branch: &str,
+        refresh: bool,
+    ) -> Result<BranchChecks, String> {
+        let key = (repo_path.to_path_buf(), branch.to_string());
+        if !refresh {
+            if let Some(cached) = self.entries.get(&key) {
+                if clock::now_secs() - cached.fetched_at < CHECKS_TTL.as_secs() as i64 {
+                    return Ok(cached.clone());
+                }
+            }
+        }
+
+        let checks = fetch(repo_path, branch).await?;
+        self.entries.insert(key, checks.clone());
+        for session in sessions.sessions_in_repo(repo_path) {
+            if session.branch.as_deref() == Some(branch) && session.ci_status != Some(checks.ci) {
+                sessions.modify(session.id, |session| session.ci_status = Some(checks.ci));
+            }
+        }
+        Ok(checks)
+    }
+}
+
+/// Percent-encodes a branch name for a GitHub API path, keeping the `/` of
+/// `feature/x` branches, which GitHub resolves as part of the ref.
+fn encode_ref(branch: &str) -> String {
+    let mut encoded = String::new();
+    for byte in branch.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Queries the check runs and combined commit status of the branch's head
+/// commit. The branch is resolved by GitHub, so it must have been pushed.
+async fn fetch(repo_path: &Path, branch: &str) -> Result<BranchChecks, String> {
+    let fetched_at = clock::now_secs();
+    let commit = encode_ref(branch);
+    let runs_path = format!("repos/{{owner}}/{{repo}}/commits/{commit}/check-runs?per_page=100");
+    let runs = match pull_request::gh(repo_path, &["api", &runs_path]).await {
+        Ok(stdout) => stdout,
+        Err(e) if e.contains("HTTP 404") || e.contains("HTTP 422") => {
+            return Ok(BranchChecks {
+                branch: branch.to_string(),
+                sha: None,
+                ci: CiStatus::None,
+                checks: Vec::new(),
+                fetched_at,
+            });
+        }
+        Err(e) => return Err(e),
+    };
+    let runs: GhCheckRuns =
+        serde_json::from_str(&runs).map_err(|e| format!("Unexpected check runs from GitHub: {e}"))?;
+    let status_path = format!("repos/{{owner}}/{{repo}}/commits/{commit}/status");
+    let status: GhCombinedStatus = serde_json::from_str(&pull_request::gh(repo_path, &["api", &status_path]).await?)
+        .map_err(|e| format!("Unexpected commit status from GitHub: {e}"))?;
+
+    let sha = runs
+        .check_runs
+        .first()
+        .map(|run| run.head_sha.clone())
+        .unwrap_or(status.sha);
+    let mut checks: Vec<BranchCheck> = runs
+        .check_runs
+        .into_iter()
+        .map(|run| BranchCheck {
+            state: match (run.status.as_str(), run.conclusion.as_deref()) {
+                ("completed", Some("success")) => CheckState::Passed,
+                ("completed", Some("neutral" | "skipped" | "stale")) => CheckState::Skipped,
+                ("completed", _) => CheckState::Failed,
+                _ => CheckState::Pending,
+            },
+            name: run.name,
+            url: run.html_url,
+        })
+        .collect();
+    checks.extend(status.statuses.into_iter().map(|status| BranchCheck {
+        state: match status.state.as_str() {
+            "success" => CheckState::Passed,
+            "pending" => CheckState::Pending,
+            _ => CheckState::Failed,
+        },
+        name: status.context,
+        url: status.target_url,
+    }));
+    Ok(BranchChecks {
+        branch: branch.to_string(),
+        ci: roll_up(checks.iter().map(|c| c.state)),
+        sha: Some(sha),
+        checks,
+        fetched_at,
+    })
+}
+
+/// Combines check results: any failure makes the whole `Failing`, then any
+/// pending check `Pending`. Skipped checks are ignored.
+pub fn roll_up(states: impl Iterator<Item = CheckState>) -> CiStatus {
+    let mut ci = CiStatus::None;
+    for state in states {
+        ci = match (state, ci) {
+            (CheckState::Failed, _) | (_, CiStatus::Failing) => CiStatus::Failing,
+            (CheckState::Pending, _) | (_, CiStatus::Pending) => CiStatus::Pending,
+            (CheckState::Passed, _) => CiStatus::Passing,
+            (CheckState::Skipped, ci) => ci,
+        };
+    }
+    ci
+}
@@ -0,0 +1,133 @@
+use std::io;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+
+use super::error::{AppError, ErrorCode};
+use super::scrollback::Scrollback;
+use super::session_manager::SessionManager;
+
+/// Longest a clipboard tool may take to take the text.
+const CLIPBOARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most lines `Lines` copies.
+const MAX_LINES: usize = 5_000;
+
+/// What `copy_last_output` copies.
+#[derive(Debug, Clone, Copy, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CopyMode {
+    /// Output of the last command, found by shell-integration markers or
+    /// shell prompts (see `Scrollback::last_command_output`).
+    LastCommand,
+    /// The last `count` lines of scrollback, trailing blank lines dropped.
+    Lines { count: usize },
+}
+
+/// What `copy_last_output` put on the clipboard, and with which program.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CopiedOutput {
+    pub text: String,
+    pub lines: usize,
+    pub program: String,
+}
+
+/// Copies output of session `session_id`'s terminal to the system
+/// clipboard, so an agent's error can be pasted into a bug report: the last
+/// command's output, or the last lines of scrollback, per `mode`.
+pub async fn copy_last_output(app: &AppHandle, session_id: u32, mode: CopyMode) -> Result<CopiedOutput, AppError> {
+    let session = app
+        .state::<SessionManager>()
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let pty_id = session.pty_id.ok_or_else(|| {
+        AppError::not_found(format!("Session {session_id} has no terminal")).with("session_id", session_id)
+    })?;
+    let scrollback = app.state::<Scrollback>();
+    let text = match mode {
+        CopyMode::LastCommand => scrollback.last_command_output(pty_id).ok_or_else(|| {
+            AppError::not_found("No command output found in the buffered output; copy the last lines instead")
+        })?,
+        CopyMode::Lines { count } => {
+            if count == 0 || count > MAX_LINES {
+                return Err(AppError::invalid_argument(format!("Line count must be between 1 and {MAX_LINES}")));
+            }
+            let output = scrollback.tail(pty_id, usize::MAX);
+            let lines: Vec<&str> = output.trim_end().lines().collect();
+            lines[lines.len().saturating_sub(count)..].join("\n")
+        }
+    };
+    if text.trim().is_empty() {
+        return Err(
+            AppError::not_found(format!("Session {session_id} has no output to copy")).with("session_id", session_id),
+        );
+    }
+    let program = copy_to_clipboard(&text).await?;
+    log::info!("Copied {} bytes of session {session_id}'s output with {program}", text.len());
+    Ok(CopiedOutput {
+        lines: text.lines().count(),
+        text,
+        program,
+    })
+}
+
+/// Puts `text` on the clipboard with `wl-copy` under Wayland, else `xclip`
+/// or `xsel` under X11, returning the program used.
+async fn copy_to_clipboard(text: &str) -> Result<String, AppError> {
+    let mut tools: Vec<(&str, &[&str])> = Vec::new();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        tools.push(("wl-copy", &[]));
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        tools.push(("xclip", &["-selection", "clipboard"]));
+        tools.push(("xsel", &["--clipboard", "--input"]));
+    }
+    if tools.is_empty() {
+        return Err(AppError::new(
+            ErrorCode::Failed,
+            "No display to copy to: neither WAYLAND_DISPLAY nor DISPLAY is set",
+        ));
+    }
+    for (program, args) in tools {
+        match pipe_to(program, args, text).await {
+            Ok(()) => return Ok(program.to_string()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(AppError::new(ErrorCode::SpawnFailed, format!("Failed to copy with {program}: {e}"))
+                    .with("program", program))
+            }
+        }
+    }
+    Err(AppError::not_found("No clipboard tool found; install wl-clipboard (Wayland) or xclip (X11)"))
+}
+
+/// Runs `program` with `text` on its stdin and waits for it to exit. The
+/// tools fork to keep serving the selection, so output is not captured:
+/// the fork would hold the pipe open.
+async fn pipe_to(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    let run = async {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        child.wait().await
+    };
+    let status = tokio::time::timeout(CLIPBOARD_TIMEOUT, run)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "timed out"))??;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("exited with {status}")))
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use super::audit::{AuditLog, AuditSource};
+use super::issues;
+use super::orchestrator::BranchSpec;
+use super::remote_control;
+use super::repo_config;
+use super::session_manager::{LaunchProfile, SessionManager};
+use super::task_queue::{QueuedTask, TaskQueue};
+use super::worktree_manager::WorktreeManager;
+use crate::git::Git;
+
+/// URL scheme of Maestro deep links.
+pub const SCHEME: &str = "maestro";
+
+/// What a handled deep link leaves for the frontend to show.
+///
+/// The links are:
+/// - `maestro://open?repo=<path>` opens a repository (`OpenRepo`).
+/// - `maestro://session/<id>` brings a session into view (`OpenSession`).
+/// - `maestro://task?repo=<path>&prompt=<text>` proposes a task
+///   (`TaskProposed`), which is only queued once the user confirms it with
+///   `confirm_task`: any web page can open such a link. `repo` must be a
+///   repository Maestro already knows (see `is_known_repo`). Optional
+///   `agent` (`claude` by default, as for `remote_control::create_session`),
+///   `branch` (`task-<prompt slug>` by default), and `base` (the default
+///   branch) pick where it runs; a new branch is cut from `base`, an
+///   existing one is checked out.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLink {
+    OpenRepo { repo_path: String },
+    OpenSession { session_id: u32 },
+    TaskProposed { proposal: TaskProposal },
+}
+
+/// A task a `maestro://task` link asks to queue, for the user to confirm.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TaskProposal {
+    pub repo_path: String,
+    pub prompt: String,
+    pub agent: Option<String>,
+    pub branch: Option<String>,
+    pub base: Option<String>,
+}
+
+/// Carries out a `maestro://` link, returning what the frontend should
+/// show, or why the link was rejected.
+pub async fn handle(app: &AppHandle, link: &str) -> Result<DeepLink, String> {
+    let url = Url::parse(link).map_err(|e| format!("Invalid URL: {e}"))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Not a {SCHEME}:// link"));
+    }
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let param = |name: &str| query.get(name).map(String::as_str).filter(|v| !v.is_empty());
+    match url.host_str().unwrap_or_default() {
+        "open" => {
+            let repo_path = repo_dir(param("repo").ok_or("Missing repo")?)?;
+            Ok(DeepLink::OpenRepo { repo_path })
+        }
+        "session" => {
+            let id = url.path().trim_matches('/');
+            let session_id: u32 = id.parse().map_err(|_| format!("Invalid session ID '{id}'"))?;
+            if app.state::<SessionManager>().get_session(session_id).is_none() {
+                return Err(format!("Session {session_id} not found"));
+            }
+            Ok(DeepLink::OpenSession { session_id })
+        }
+        "task" => {
+            let proposal = TaskProposal {
+                repo_path: repo_dir(param("repo").ok_or("Missing repo")?)?,
+                prompt: param("prompt").ok_or("Missing prompt")?.to_string(),
+                agent: param("agent").map(str::to_string),
+                branch: param("branch").map(str::to_string),
+                base: param("base").map(str::to_string),
+            };
+            let result = check_proposal(app, &proposal).await;
+            let params = serde_json::to_value(&proposal).unwrap_or_default();
+            app.state::<AuditLog>()
+                .record(AuditSource::DeepLink, None, "propose_task", params, &result);
+            result?;
+            Ok(DeepLink::TaskProposed { proposal })
+        }
+        action => Err(format!("Unknown action '{action}'")),
+    }
+}
+
+/// Resolves `path` to an absolute directory.
+fn repo_dir(path: &str) -> Result<String, String> {
+    match std::fs::canonicalize(path) {
+        Ok(dir) if dir.is_dir() => Ok(dir.to_string_lossy().into_owned()),
+        _ => Err(format!("{path} is not a directory")),
+    }
+}
+
+/// Whether `repo_path` is a repository Maestro already works with: that of
+/// a session, or one with managed worktrees.
+pub async fn is_known_repo(app: &AppHandle, repo_path: &Path) -> bool {
+    let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let target = canonical(repo_path);
+    let sessions: Vec<PathBuf> = app
+        .state::<SessionManager>()
+        .all_sessions()
+        .into_iter()
+        .filter(|s| !s.repo_path.is_empty())
+        .map(|s| PathBuf::from(s.repo_path))
+        .collect();
+    let managed = app.state::<WorktreeManager>().known_repos().await;
+    sessions.iter().chain(&managed).any(|repo| canonical(repo) == target)
+}
+
+/// Rejects a proposal for an unknown repository or agent.
+async fn check_proposal(app: &AppHandle, proposal: &TaskProposal) -> Result<(), String> {
+    if !is_known_repo(app, Path::new(&proposal.repo_path)).await {
+        return Err(format!(
+            "{} is not a repository open in Maestro; open it before queueing tasks for it",
+            proposal.repo_path
+        ));
+    }
+    let default_agent = repo_config::default_agent(Path::new(&proposal.repo_path));
+    remote_control::agent_mode(app, proposal.agent.as_deref().unwrap_or(&default_agent)).map(|_| ())
+}
+
+/// Queues a task the user confirmed from a `TaskProposed` link, as
+/// `enqueue_task` does. The proposal is checked again, since it comes back
+/// from the frontend.
+pub async fn confirm_task(app: &AppHandle, proposal: TaskProposal) -> Result<QueuedTask, String> {
+    check_proposal(app, &proposal).await?;
+    let TaskProposal {
+        repo_path,
+        prompt,
+        agent,
+        branch,
+        base,
+    } = proposal;
+    let default_agent = repo_config::default_agent(Path::new(&repo_path));
+    let mode = remote_control::agent_mode(app, agent.as_deref().unwrap_or(&default_agent))?;
+    let branch = match branch {
+        Some(branch) => branch,
+        None => format!("task-{}", issues::slug(&prompt)).trim_end_matches('-').to_string(),
+    };
+    let git = Git::new(&repo_path);
+    let branch_spec = if git.branch_exists(&branch).await.map_err(|e| e.to_string())? {
+        BranchSpec::Unique { branch }
+    } else {
+        let base_ref = match base {
+            Some(base) => base,
+            None => repo_config::base_branch(Path::new(&repo_path)).await.map_err(|e| e.to_string())?,
+        };
+        BranchSpec::New { branch, base_ref }
+    };
+    app.state::<TaskQueue>()
+        .enqueue(prompt, repo_path, branch_spec, mode, LaunchProfile::default())
+}
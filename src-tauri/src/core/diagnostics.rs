@@ -0,0 +1,273 @@
+use std::backtrace::Backtrace;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+use specta::Type;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::clock;
+use super::logging::{self, LogRecord};
+use super::storage;
+use super::worktree_manager;
+
+/// Crash reports kept in the crash dir; older ones are deleted.
+const MAX_CRASH_REPORTS: usize = 20;
+
+/// Log records included in a crash report.
+const CRASH_LOG_RECORDS: usize = 200;
+
+/// Stands in for the secrets `redact` removes.
+const REDACTED: &str = "<redacted>";
+
+/// Settings and policies included in a diagnostic bundle, with secrets
+/// removed (see `redact`). `api-server.json` and `mcp-server.json` hold
+/// access tokens and are left out.
+const CONFIG_FILES: &[&str] = &[
+    "settings.json",
+    "agent-commands.json",
+    "watchdog-policy.json",
+    "retention-policy.json",
+    "guardrail-policy.json",
+    "pr-templates.json",
+    "sessions.json",
+    "worktree-registry.json",
+];
+
+/// What a crash report records: the panic, where it happened, the last log
+/// records, and the sessions as last persisted, with secrets removed.
+#[derive(Serialize)]
+struct CrashReport {
+    at: i64,
+    version: &'static str,
+    thread: String,
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+    recent_logs: Vec<LogRecord>,
+    sessions: Option<serde_json::Value>,
+}
+
+/// A diagnostic bundle written by `create_bundle`. `files` are the paths
+/// inside the zip.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DiagnosticBundle {
+    pub path: String,
+    pub size: u64,
+    pub files: Vec<String>,
+}
+
+/// Directory holding crash reports (`crash-<unix ms>.json`).
+pub fn crash_dir() -> PathBuf {
+    storage::data_dir().join("crashes")
+}
+
+/// Makes every panic, on any thread, write a crash report to `crash_dir`
+/// and log an error before the default hook runs. Panics in release builds
+/// abort right after, so the report is the only trace they leave besides
+/// stderr.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        match write_crash_report(message.clone(), location.clone()) {
+            Ok(path) => log::error!(
+                "Panic at {}: {message}; crash report written to {}",
+                location.as_deref().unwrap_or("unknown location"),
+                path.display()
+            ),
+            Err(e) => log::error!("Panic: {message}; failed to write a crash report: {e}"),
+        }
+        default_hook(info);
+    }));
+}
+
+fn write_crash_report(message: String, location: Option<String>) -> io::Result<PathBuf> {
+    let sessions = std::fs::read(storage::data_dir().join("sessions.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .map(|mut sessions| {
+            redact(&mut sessions);
+            sessions
+        });
+    let report = CrashReport {
+        at: clock::now_secs(),
+        version: env!("CARGO_PKG_VERSION"),
+        thread: std::thread::current().name().unwrap_or("unnamed").to_string(),
+        message,
+        location,
+        backtrace: Backtrace::force_capture().to_string(),
+        recent_logs: logging::try_recent(CRASH_LOG_RECORDS),
+        sessions,
+    };
+    let dir = crash_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("crash-{}.json", clock::now_millis()));
+    let json = serde_json::to_vec_pretty(&report).map_err(io::Error::other)?;
+    std::fs::write(&path, json)?;
+    prune_crash_reports(&dir);
+    Ok(path)
+}
+
+/// Deletes all but the newest `MAX_CRASH_REPORTS` reports.
+fn prune_crash_reports(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    // Names embed the time, so they sort oldest first
+    reports.sort();
+    let excess = reports.len().saturating_sub(MAX_CRASH_REPORTS);
+    for path in &reports[..excess] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Replaces the values of every `env` map and every `startup_script` in
+/// persisted state: launch profiles and custom agents keep API keys in
+/// their environment, and scripts often export them.
+fn redact(value: &mut serde_json::Value) {
+    use serde_json::Value;
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                match (key.as_str(), field) {
+                    ("env", Value::Object(vars)) => {
+                        for var in vars.values_mut() {
+                            *var = Value::from(REDACTED);
+                        }
+                    }
+                    ("startup_script", script @ Value::String(_)) => *script = Value::from(REDACTED),
+                    (_, field) => redact(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Writes a zip for bug reports to `<data dir>/diagnostics`, holding the
+/// log files, crash reports, the settings and session state (without
+/// access tokens, environment values, or startup scripts), and
+/// `environment.txt` describing the system and the tools Maestro runs.
+pub async fn create_bundle() -> Result<DiagnosticBundle, String> {
+    tokio::task::spawn_blocking(|| {
+        let dir = storage::data_dir().join("diagnostics");
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        let path = dir.join(format!("maestro-diagnostics-{}.zip", clock::now_millis()));
+        let files = write_bundle(&path).map_err(|e| {
+            let _ = std::fs::remove_file(&path);
+            format!("Failed to write {}: {e}", path.display())
+        })?;
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        log::info!("Diagnostic bundle written to {}", path.display());
+        Ok(DiagnosticBundle {
+            path: path.to_string_lossy().into_owned(),
+            size,
+            files,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn write_bundle(path: &Path) -> io::Result<Vec<String>> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut files = Vec::new();
+    let mut add = |zip: &mut ZipWriter<File>, name: String, bytes: &[u8]| -> io::Result<()> {
+        zip.start_file(name.as_str(), options).map_err(io::Error::other)?;
+        zip.write_all(bytes)?;
+        files.push(name);
+        Ok(())
+    };
+
+    add(&mut zip, "environment.txt".to_string(), environment_info().as_bytes())?;
+    let data_dir = storage::data_dir();
+    for name in CONFIG_FILES {
+        // A file that does not parse cannot be redacted, so it is left out
+        let Some(mut config) = std::fs::read(data_dir.join(name))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+        else {
+            continue;
+        };
+        redact(&mut config);
+        let bytes = serde_json::to_vec_pretty(&config).map_err(io::Error::other)?;
+        add(&mut zip, format!("config/{name}"), &bytes)?;
+    }
+    for (folder, dir) in [("logs", logging::log_dir()), ("crashes", crash_dir())] {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|e| e.path())).collect();
+        paths.sort();
+        for file in paths.iter().filter(|p| p.is_file()) {
+            if let Ok(bytes) = std::fs::read(file) {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                add(&mut zip, format!("{folder}/{name}"), &bytes)?;
+            }
+        }
+    }
+    zip.finish().map_err(io::Error::other)?;
+    Ok(files)
+}
+
+/// Describes the app build, the system, and the versions of the tools
+/// Maestro shells out to, one `key: value` per line.
+fn environment_info() -> String {
+    let os = std::fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|release| {
+            release
+                .lines()
+                .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+                .map(|name| name.trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| std::env::consts::OS.to_string());
+    let var = |name: &str| std::env::var(name).unwrap_or_else(|_| "(unset)".to_string());
+    let mut info = vec![
+        ("maestro", env!("CARGO_PKG_VERSION").to_string()),
+        ("os", os),
+        ("kernel", tool_version("uname", &["-sr"])),
+        ("arch", std::env::consts::ARCH.to_string()),
+        ("desktop", var("XDG_CURRENT_DESKTOP")),
+        ("session_type", var("XDG_SESSION_TYPE")),
+        ("shell", var("SHELL")),
+        ("lang", var("LANG")),
+        ("data_dir", storage::data_dir().display().to_string()),
+        ("worktree_base_dir", worktree_manager::worktree_base_dir().display().to_string()),
+    ];
+    for tool in ["git", "rg", "gh"] {
+        info.push((tool, tool_version(tool, &["--version"])));
+    }
+    info.iter().map(|(key, value)| format!("{key}: {value}\n")).collect()
+}
+
+/// First line of a tool's version output, or why there is none.
+fn tool_version(program: &str, args: &[&str]) -> String {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string(),
+        Ok(output) => format!("(exited with {})", output.status),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => "(not installed)".to_string(),
+        Err(e) => format!("(failed: {e})"),
+    }
+}
@@ -1,24 +1,31 @@
 use serde::Serialize;
+use specta::Type;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::io;
+
+use crate::git::GitError;
 
 /// Discriminant for PTY errors, serialized to the frontend for programmatic
 /// error handling (e.g., distinguishing "session gone" from "write failed").
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Type)]
 pub enum PtyErrorCode {
     SpawnFailed,
     SessionNotFound,
     WriteFailed,
     ResizeFailed,
     KillFailed,
+    SignalFailed,
     IdOverflow,
 }
 
 /// Structured PTY error with a machine-readable code and human-readable message.
 ///
-/// Serialized as JSON to the Tauri frontend. Implements `std::error::Error`
-/// so it can be used with `?` in command handlers. Constructors are provided
+/// Reaches the Tauri frontend as an `AppError` with the matching code.
+/// Implements `std::error::Error` so it can be used with `?` in command
+/// handlers. Constructors are provided
 /// for each error variant to keep call sites concise.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Type)]
 pub struct PtyError {
     pub code: PtyErrorCode,
     pub message: String,
@@ -73,6 +80,14 @@ impl PtyError {
         }
     }
 
+    /// Signaling a job running in the PTY (stop, continue) failed.
+    pub fn signal_failed(msg: impl Into<String>) -> Self {
+        Self {
+            code: PtyErrorCode::SignalFailed,
+            message: msg.into(),
+        }
+    }
+
     /// Atomic session ID counter overflowed u32::MAX.
     pub fn id_overflow() -> Self {
         Self {
@@ -81,3 +96,174 @@ impl PtyError {
         }
     }
 }
+
+/// Discriminant for `AppError`, serialized to the frontend so it can branch
+/// on the kind of failure of any command. PTY and git errors keep their own
+/// kinds; `Failed` covers everything not yet given one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+pub enum ErrorCode {
+    SessionNotFound,
+    InvalidArgument,
+    NotFound,
+    AlreadyExists,
+    Io,
+    SpawnFailed,
+    WriteFailed,
+    ResizeFailed,
+    KillFailed,
+    SignalFailed,
+    IdOverflow,
+    GitNotFound,
+    GitCommandFailed,
+    GitKilled,
+    GitSpawnFailed,
+    GitInvalidOutput,
+    NotARepo,
+    BranchAlreadyCheckedOut,
+    NamesExhausted,
+    WorktreeNotFound,
+    UnmanagedPath,
+    TrashFailed,
+    ArchiveNotFound,
+    DetachedHead,
+    DirtyWorkingTree,
+    MergeConflict,
+    MergeGateFailed,
+    BranchMoved,
+    Busy,
+    Failed,
+}
+
+/// Error returned by every IPC command: a machine-readable code, a
+/// human-readable message, and the values it is about (session ID, path,
+/// git command, ...) as `context`, omitted when empty.
+///
+/// Converts from `PtyError`, `GitError`, `io::Error`, and the plain
+/// `String` errors of subsystems that have no typed errors, so command
+/// handlers can use `?` on all of them. A `String` becomes `Failed`, so
+/// core functions whose failures the frontend tells apart (a missing
+/// session, a bad argument) return `AppError` themselves.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, String>,
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            context: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a context value, replacing any with the same key.
+    pub fn with(mut self, key: &str, value: impl ToString) -> Self {
+        self.context.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// No Maestro session exists with the given ID.
+    pub fn session_not_found(id: u32) -> Self {
+        Self::new(ErrorCode::SessionNotFound, format!("Session {id} not found")).with("session_id", id)
+    }
+
+    /// An argument was missing, empty, or out of range.
+    pub fn invalid_argument(msg: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidArgument, msg)
+    }
+
+    /// Something other than a session (a task, an agent, a file) is gone.
+    pub fn not_found(msg: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, msg)
+    }
+}
+
+impl From<PtyError> for AppError {
+    fn from(e: PtyError) -> Self {
+        let code = match e.code {
+            PtyErrorCode::SpawnFailed => ErrorCode::SpawnFailed,
+            PtyErrorCode::SessionNotFound => ErrorCode::SessionNotFound,
+            PtyErrorCode::WriteFailed => ErrorCode::WriteFailed,
+            PtyErrorCode::ResizeFailed => ErrorCode::ResizeFailed,
+            PtyErrorCode::KillFailed => ErrorCode::KillFailed,
+            PtyErrorCode::SignalFailed => ErrorCode::SignalFailed,
+            PtyErrorCode::IdOverflow => ErrorCode::IdOverflow,
+        };
+        Self::new(code, e.message)
+    }
+}
+
+impl From<GitError> for AppError {
+    fn from(e: GitError) -> Self {
+        let message = e.to_string();
+        match e {
+            GitError::GitNotFound => Self::new(ErrorCode::GitNotFound, message),
+            GitError::CommandFailed { code, stderr, command } => Self::new(ErrorCode::GitCommandFailed, message)
+                .with("command", command)
+                .with("exit_code", code)
+                .with("stderr", stderr),
+            GitError::Killed { command } => Self::new(ErrorCode::GitKilled, message).with("command", command),
+            GitError::SpawnError { command, .. } => {
+                Self::new(ErrorCode::GitSpawnFailed, message).with("command", command)
+            }
+            GitError::InvalidUtf8(_) | GitError::ParseError { .. } => Self::new(ErrorCode::GitInvalidOutput, message),
+            GitError::NotARepo { path } => Self::new(ErrorCode::NotARepo, message).with("path", path.display()),
+            GitError::BranchAlreadyCheckedOut { branch, path } => {
+                Self::new(ErrorCode::BranchAlreadyCheckedOut, message)
+                    .with("branch", branch)
+                    .with("path", path)
+            }
+            GitError::NamesExhausted { name, .. } => Self::new(ErrorCode::NamesExhausted, message).with("name", name),
+            GitError::WorktreeNotFound(path) => Self::new(ErrorCode::WorktreeNotFound, message).with("path", path),
+            GitError::UnmanagedPath { path } => Self::new(ErrorCode::UnmanagedPath, message).with("path", path),
+            GitError::TrashFailed { path, .. } => Self::new(ErrorCode::TrashFailed, message).with("path", path),
+            GitError::ArchiveNotFound(archive) => {
+                Self::new(ErrorCode::ArchiveNotFound, message).with("archive", archive)
+            }
+            GitError::DetachedHead { path } => Self::new(ErrorCode::DetachedHead, message).with("path", path),
+            GitError::DirtyWorkingTree { path } => Self::new(ErrorCode::DirtyWorkingTree, message).with("path", path),
+            GitError::MergeConflict { files } => {
+                Self::new(ErrorCode::MergeConflict, message).with("files", files.join("\n"))
+            }
+            GitError::UnknownRevision(rev) => Self::new(ErrorCode::NotFound, message).with("revision", rev),
+            GitError::BranchMoved { branch, expected, actual } => Self::new(ErrorCode::BranchMoved, message)
+                .with("branch", branch)
+                .with("expected", expected)
+                .with("actual", actual),
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        let code = match e.kind() {
+            io::ErrorKind::NotFound => ErrorCode::NotFound,
+            _ => ErrorCode::Io,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Failed, message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCode::Failed, message)
+    }
+}
@@ -0,0 +1,331 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+use dashmap::DashMap;
+use serde::Serialize;
+use specta::Type;
+
+use super::files;
+use super::session_manager::SessionManager;
+use super::worktree_manager::WorktreeManager;
+
+/// Lines between two offsets kept in a line index.
+const CHECKPOINT_LINES: u64 = 1024;
+
+/// Most bytes one chunk or one batch of lines carries over IPC.
+const MAX_CHUNK_BYTES: u64 = 1024 * 1024;
+
+/// Most lines one `read_file_lines` call returns.
+const MAX_LINES: u64 = 10_000;
+
+/// Most files whose line index is kept; the least recently read is dropped
+/// beyond that.
+const MAX_INDEXED_FILES: usize = 32;
+
+/// Bytes of a chunk returned by `read_file_stream`. `text` is decoded
+/// lossily; `offset` and `end` are moved off UTF-8 continuation bytes so a
+/// character is never split between chunks, and the next chunk starts at
+/// `end`. `first_line` is the 0-based line `offset` falls on.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FileChunk {
+    pub path: String,
+    pub offset: u64,
+    pub end: u64,
+    pub size: u64,
+    pub eof: bool,
+    pub first_line: u64,
+    pub text: String,
+}
+
+/// Lines returned by `read_file_lines`, without line terminators.
+/// `total_lines` counts the lines indexed so far, which is all of them as
+/// of the call. A line longer than `MAX_CHUNK_BYTES` is cut, and
+/// `next_offset` then points into it.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FileLines {
+    pub path: String,
+    pub start_line: u64,
+    pub lines: Vec<String>,
+    pub total_lines: u64,
+    pub size: u64,
+    pub next_offset: u64,
+}
+
+/// Offsets of every `CHECKPOINT_LINES`th line start of a file, up to
+/// `indexed_to`, for files that only grow (logs); a file that shrank or
+/// changed without growing is indexed again.
+#[derive(Clone)]
+struct LineIndex {
+    last_read: Instant,
+    modified: Option<SystemTime>,
+    indexed_to: u64,
+    newlines: u64,
+    ends_with_newline: bool,
+    checkpoints: Vec<u64>,
+}
+
+impl LineIndex {
+    fn new() -> Self {
+        Self {
+            last_read: Instant::now(),
+            modified: None,
+            indexed_to: 0,
+            newlines: 0,
+            ends_with_newline: true,
+            checkpoints: vec![0],
+        }
+    }
+
+    /// Lines in the indexed part, counting an unterminated last line.
+    fn lines(&self) -> u64 {
+        self.newlines + u64::from(self.indexed_to > 0 && !self.ends_with_newline)
+    }
+
+    /// Indexes the bytes appended since the last update.
+    fn extend(&mut self, file: &mut File, size: u64) -> io::Result<()> {
+        file.seek(SeekFrom::Start(self.indexed_to))?;
+        let mut reader = BufReader::with_capacity(64 * 1024, file.take(size - self.indexed_to));
+        let mut offset = self.indexed_to;
+        loop {
+            let buf = reader.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            for (i, byte) in buf.iter().enumerate() {
+                if *byte == b'\n' {
+                    self.newlines += 1;
+                    if self.newlines.is_multiple_of(CHECKPOINT_LINES) {
+                        self.checkpoints.push(offset + i as u64 + 1);
+                    }
+                }
+            }
+            self.ends_with_newline = buf.last() == Some(&b'\n');
+            let len = buf.len();
+            offset += len as u64;
+            reader.consume(len);
+        }
+        self.indexed_to = offset;
+        Ok(())
+    }
+
+    /// Returns the offset where 0-based `line` starts.
+    fn line_start(&self, file: &mut File, line: u64) -> io::Result<u64> {
+        let checkpoint = ((line / CHECKPOINT_LINES) as usize).min(self.checkpoints.len() - 1);
+        let start = self.checkpoints[checkpoint];
+        let mut remaining = line - checkpoint as u64 * CHECKPOINT_LINES;
+        file.seek(SeekFrom::Start(start))?;
+        let mut reader = BufReader::with_capacity(64 * 1024, file.take(self.indexed_to - start));
+        let mut offset = start;
+        while remaining > 0 {
+            let buf = reader.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            let mut consumed = buf.len();
+            for (i, byte) in buf.iter().enumerate() {
+                if *byte == b'\n' {
+                    remaining -= 1;
+                    if remaining == 0 {
+                        consumed = i + 1;
+                        break;
+                    }
+                }
+            }
+            offset += consumed as u64;
+            reader.consume(consumed);
+        }
+        Ok(offset)
+    }
+
+    /// Returns the 0-based line `offset` falls on.
+    fn line_at(&self, file: &mut File, offset: u64) -> io::Result<u64> {
+        let checkpoint = self.checkpoints.partition_point(|&start| start <= offset) - 1;
+        let start = self.checkpoints[checkpoint];
+        file.seek(SeekFrom::Start(start))?;
+        let mut reader = BufReader::with_capacity(64 * 1024, file.take(offset - start));
+        let mut line = checkpoint as u64 * CHECKPOINT_LINES;
+        loop {
+            let buf = reader.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            line += buf.iter().filter(|&&b| b == b'\n').count() as u64;
+            let len = buf.len();
+            reader.consume(len);
+        }
+        Ok(line)
+    }
+}
+
+/// Line indexes of the last `MAX_INDEXED_FILES` large files viewed, kept
+/// so paging through a multi-hundred-megabyte log only scans what was
+/// appended since the last read. Placed in Tauri managed state.
+pub struct LineIndexCache {
+    indexes: DashMap<PathBuf, LineIndex>,
+}
+
+impl LineIndexCache {
+    pub fn new() -> Self {
+        Self {
+            indexes: DashMap::new(),
+        }
+    }
+
+    /// Reads up to `len` bytes (at most 1 MiB) from `offset` of a file
+    /// inside an open repository or worktree, without loading the rest.
+    pub async fn read_file_stream(
+        &self,
+        sessions: &SessionManager,
+        worktrees: &WorktreeManager,
+        path: &Path,
+        offset: u64,
+        len: u64,
+    ) -> Result<FileChunk, String> {
+        let path = files::resolve_scoped(sessions, worktrees, path).await?;
+        let cached = self.indexes.get(&path).map(|index| index.clone());
+        let (chunk, index) = blocking(&path, move |file, path| {
+            let (size, index) = update_index(file, cached)?;
+            let mut start = offset.min(size);
+            let mut bytes = Vec::new();
+            file.seek(SeekFrom::Start(start))?;
+            file.take(len.min(MAX_CHUNK_BYTES)).read_to_end(&mut bytes)?;
+
+            // Skip a partial character at the start and hold back one at
+            // the end, unless the file ends there
+            let lead = bytes.iter().take_while(|&&b| is_continuation(b)).count().min(3);
+            bytes.drain(..lead);
+            start += lead as u64;
+            let end_of_file = start + bytes.len() as u64 >= size;
+            if !end_of_file {
+                if let Err(e) = std::str::from_utf8(&bytes) {
+                    if e.error_len().is_none() {
+                        bytes.truncate(e.valid_up_to());
+                    }
+                }
+            }
+            let end = start + bytes.len() as u64;
+            let first_line = index.line_at(file, start.min(index.indexed_to))?;
+            let chunk = FileChunk {
+                path: path.to_string_lossy().into_owned(),
+                offset: start,
+                end,
+                size,
+                eof: end >= size,
+                first_line,
+                text: String::from_utf8_lossy(&bytes).into_owned(),
+            };
+            Ok((chunk, index))
+        })
+        .await?;
+        self.store(path, index);
+        Ok(chunk)
+    }
+
+    /// Reads up to `count` lines (at most 10,000 and 1 MiB) from 0-based
+    /// `start_line` of a file inside an open repository or worktree,
+    /// indexing the file on first use and its new lines after that.
+    pub async fn read_file_lines(
+        &self,
+        sessions: &SessionManager,
+        worktrees: &WorktreeManager,
+        path: &Path,
+        start_line: u64,
+        count: u64,
+    ) -> Result<FileLines, String> {
+        let path = files::resolve_scoped(sessions, worktrees, path).await?;
+        let cached = self.indexes.get(&path).map(|index| index.clone());
+        let count = count.min(MAX_LINES) as usize;
+        let (lines, index) = blocking(&path, move |file, path| {
+            let (size, index) = update_index(file, cached)?;
+            let total_lines = index.lines();
+            let start = index.line_start(file, start_line.min(total_lines))?;
+            let mut buf = Vec::new();
+            file.seek(SeekFrom::Start(start))?;
+            file.take(MAX_CHUNK_BYTES).read_to_end(&mut buf)?;
+            let reached_end = start + buf.len() as u64 >= size;
+
+            let mut lines = Vec::new();
+            let mut pos = 0;
+            while lines.len() < count && pos < buf.len() {
+                let rest = &buf[pos..];
+                let (line, next) = match rest.iter().position(|&b| b == b'\n') {
+                    Some(i) => (&rest[..i], pos + i + 1),
+                    None if reached_end || lines.is_empty() => (rest, buf.len()),
+                    None => break,
+                };
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                lines.push(String::from_utf8_lossy(line).into_owned());
+                pos = next;
+            }
+            let lines = FileLines {
+                path: path.to_string_lossy().into_owned(),
+                start_line: start_line.min(total_lines),
+                lines,
+                total_lines,
+                size,
+                next_offset: start + pos as u64,
+            };
+            Ok((lines, index))
+        })
+        .await?;
+        self.store(path, index);
+        Ok(lines)
+    }
+
+    /// Keeps `index` for `path`, dropping the least recently read index if
+    /// there are too many.
+    fn store(&self, path: PathBuf, mut index: LineIndex) {
+        index.last_read = Instant::now();
+        self.indexes.insert(path, index);
+        if self.indexes.len() > MAX_INDEXED_FILES {
+            let oldest = self
+                .indexes
+                .iter()
+                .min_by_key(|entry| entry.last_read)
+                .map(|entry| entry.key().clone());
+            if let Some(oldest) = oldest {
+                self.indexes.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Brings `cached` up to date with the file, or indexes it from scratch if
+/// it shrank or was rewritten in place. Returns the file's size.
+fn update_index(file: &mut File, cached: Option<LineIndex>) -> io::Result<(u64, LineIndex)> {
+    let metadata = file.metadata()?;
+    let size = metadata.len();
+    let modified = metadata.modified().ok();
+    let mut index = match cached {
+        Some(index) if index.indexed_to < size || (index.indexed_to == size && index.modified == modified) => index,
+        _ => LineIndex::new(),
+    };
+    if index.indexed_to < size {
+        index.extend(file, size)?;
+    }
+    index.modified = modified;
+    Ok((size, index))
+}
+
+/// Opens `path` and runs `read` on it on the blocking pool.
+async fn blocking<T: Send + 'static>(
+    path: &Path,
+    read: impl FnOnce(&mut File, &Path) -> io::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut file = File::open(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if !file.metadata().map(|m| m.is_file()).unwrap_or(false) {
+            return Err(format!("{} is not a file", path.display()));
+        }
+        read(&mut file, &path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
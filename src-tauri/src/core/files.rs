@@ -0,0 +1,501 @@
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::session_manager::SessionManager;
+use super::worktree_manager::{self, WorktreeManager};
+use crate::git::{FileDiff, Git};
+
+/// Deepest listing `list_dir` returns; deeper requests are clamped.
+const MAX_DEPTH: u32 = 8;
+
+/// Most entries `list_dir` returns in one listing, over all levels.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Bytes `read_file` returns when the caller gives no limit.
+const DEFAULT_READ_BYTES: u64 = 1024 * 1024;
+
+/// Largest file `read_file` returns content of and `write_file` writes.
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Bytes inspected for NUL bytes to tell binary files apart, as git does.
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// Files `recent_files` returns when the caller gives no limit.
+const DEFAULT_RECENT_FILES: usize = 50;
+
+/// Most files `recent_files` returns.
+const MAX_RECENT_FILES: usize = 1_000;
+
+/// What a directory entry is. Symlinks are not followed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+/// Which entries `list_dir` skips. Hidden entries are those whose name
+/// starts with `.`; ignored ones are excluded by the repository's ignore
+/// rules. Both are skipped unless asked for.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Type)]
+#[serde(default)]
+pub struct ListDirFilter {
+    pub show_hidden: bool,
+    pub show_ignored: bool,
+}
+
+/// A file or directory in a listing. `path` is relative to the listing's
+/// root, with `/` separators; `modified` is Unix seconds. `children` is
+/// `None` for files, unreadable directories, and directories below the
+/// requested depth, so the tree can be expanded lazily with another
+/// `list_dir`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FileEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: EntryKind,
+    pub size: u64,
+    pub modified: Option<i64>,
+    pub hidden: bool,
+    pub ignored: bool,
+    pub children: Option<Vec<FileEntry>>,
+}
+
+/// A directory listing. `truncated` is set if `MAX_ENTRIES` was reached.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DirListing {
+    pub path: String,
+    pub entries: Vec<FileEntry>,
+    pub truncated: bool,
+}
+
+/// A file found by `recent_files`. `path` is relative to the worktree and
+/// `modified` is Unix seconds.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RecentFile {
+    pub path: String,
+    pub size: u64,
+    pub modified: i64,
+}
+
+/// A file's content as read by `read_file`. `content` is `None` for binary
+/// files (those with a NUL byte near the start or not valid UTF-8).
+/// `truncated` is set if the file is longer than the bytes read; `size` is
+/// its full length and `modified` Unix seconds.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FileContent {
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<i64>,
+    pub binary: bool,
+    pub truncated: bool,
+    pub content: Option<String>,
+}
+
+/// Lists the directory at `path` (relative to `root`, e.g. a worktree,
+/// empty for `root` itself) and its subdirectories down to `depth` levels
+/// (1 lists only its entries), directories first, then by name. `.git` is
+/// never listed. Ignore status comes from git; outside a repository,
+/// nothing is ignored. `root` must lie inside an open repository or
+/// worktree; see `resolve_scoped`.
+pub async fn list_dir(
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    root: &Path,
+    path: &str,
+    depth: u32,
+    filter: ListDirFilter,
+) -> Result<DirListing, String> {
+    let root = &resolve_scoped(sessions, worktrees, root).await?;
+    let rel = relative_path(path)?;
+    let dir = root.join(&rel);
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let rel = rel.to_string_lossy().replace('\\', "/");
+
+    let ignored: HashSet<String> = match Git::new(root).ignored_paths(if rel.is_empty() { "." } else { &rel }).await {
+        Ok(paths) => paths.into_iter().map(|p| p.trim_end_matches('/').to_string()).collect(),
+        Err(e) => {
+            log::debug!("No ignore status for {}: {e}", root.display());
+            HashSet::new()
+        }
+    };
+    let parent_ignored = !rel.is_empty() && is_ignored(&ignored, &rel);
+
+    let depth = depth.clamp(1, MAX_DEPTH);
+    tokio::task::spawn_blocking(move || {
+        let mut walk = Walk {
+            ignored,
+            filter,
+            remaining: MAX_ENTRIES,
+        };
+        let entries = walk
+            .read(&dir, &rel, depth, parent_ignored)
+            .map_err(|e| format!("Failed to list {}: {e}", dir.display()))?;
+        Ok(DirListing {
+            path: rel,
+            entries,
+            truncated: walk.remaining == 0,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Lines of context `diff_files` shows around each change, as git does.
+const DIFF_CONTEXT_LINES: u32 = 3;
+
+/// Compares two files inside open repositories or worktrees, e.g. the same
+/// file in two agents' worktrees, whether or not git tracks them.
+pub async fn diff_files(
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    path_a: &Path,
+    path_b: &Path,
+) -> Result<FileDiff, String> {
+    let path_a = resolve_scoped(sessions, worktrees, path_a).await?;
+    let path_b = resolve_scoped(sessions, worktrees, path_b).await?;
+    for path in [&path_a, &path_b] {
+        if !path.is_file() {
+            return Err(format!("{} is not a file", path.display()));
+        }
+    }
+    let dir = path_a.parent().unwrap_or(&path_a);
+    Git::new(dir)
+        .diff_files(&path_a, &path_b, DIFF_CONTEXT_LINES)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the files under `root` (usually a worktree) modified most
+/// recently, newest first: up to `limit` (default 50, at most 1000),
+/// modified at or after `since` (Unix seconds) if given. Files excluded by
+/// ignore rules and `.git` are skipped; symlinks are not followed. `root`
+/// must lie inside an open repository or worktree; see `resolve_scoped`.
+pub async fn recent_files(
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    root: &Path,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<RecentFile>, String> {
+    let root = &resolve_scoped(sessions, worktrees, root).await?;
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    let ignored: HashSet<String> = match Git::new(root).ignored_paths(".").await {
+        Ok(paths) => paths.into_iter().map(|p| p.trim_end_matches('/').to_string()).collect(),
+        Err(e) => {
+            log::debug!("No ignore status for {}: {e}", root.display());
+            HashSet::new()
+        }
+    };
+    let limit = limit.unwrap_or(DEFAULT_RECENT_FILES).clamp(1, MAX_RECENT_FILES);
+    let since = since.unwrap_or(i64::MIN);
+    let root = root.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut files = Vec::new();
+        collect_recent(&root, "", &ignored, since, &mut files);
+        files.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)));
+        files.truncate(limit);
+        files
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Adds the files under `dir` modified at or after `since` to `files`.
+/// Unreadable directories are skipped.
+fn collect_recent(dir: &Path, rel: &str, ignored: &HashSet<String>, since: i64, files: &mut Vec<RecentFile>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name == ".git" {
+            continue;
+        }
+        let path = if rel.is_empty() {
+            name
+        } else {
+            format!("{rel}/{name}")
+        };
+        if ignored.contains(&path) {
+            continue;
+        }
+        let Ok(metadata) = entry.path().symlink_metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_recent(&entry.path(), &path, ignored, since, files);
+        } else if metadata.is_file() {
+            let Some(modified) = unix_secs(metadata.modified().ok()) else {
+                continue;
+            };
+            if modified >= since {
+                files.push(RecentFile {
+                    path,
+                    size: metadata.len(),
+                    modified,
+                });
+            }
+        }
+    }
+}
+
+/// Rejects absolute paths and `..`, so listings stay inside the root.
+fn relative_path(path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+    let mut rel = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => rel.push(part),
+            Component::CurDir => {}
+            _ => return Err(format!("{} is not a path inside the root", path.display())),
+        }
+    }
+    Ok(rel)
+}
+
+/// Whether `path` or one of its ancestors is in `ignored`.
+fn is_ignored(ignored: &HashSet<String>, path: &str) -> bool {
+    let mut prefix = path;
+    loop {
+        if ignored.contains(prefix) {
+            return true;
+        }
+        match prefix.rfind('/') {
+            Some(cut) => prefix = &prefix[..cut],
+            None => return false,
+        }
+    }
+}
+
+struct Walk {
+    ignored: HashSet<String>,
+    filter: ListDirFilter,
+    remaining: usize,
+}
+
+impl Walk {
+    fn read(&mut self, dir: &Path, rel: &str, depth: u32, parent_ignored: bool) -> io::Result<Vec<FileEntry>> {
+        let mut entries = Vec::new();
+        for dir_entry in std::fs::read_dir(dir)? {
+            if self.remaining == 0 {
+                break;
+            }
+            let dir_entry = dir_entry?;
+            let name = dir_entry.file_name().to_string_lossy().into_owned();
+            if name == ".git" {
+                continue;
+            }
+            let path = if rel.is_empty() {
+                name.clone()
+            } else {
+                format!("{rel}/{name}")
+            };
+            let hidden = name.starts_with('.');
+            let ignored = parent_ignored || self.ignored.contains(&path);
+            if (hidden && !self.filter.show_hidden) || (ignored && !self.filter.show_ignored) {
+                continue;
+            }
+
+            // Entries that vanish mid-listing are skipped
+            let Ok(metadata) = dir_entry.path().symlink_metadata() else {
+                continue;
+            };
+            let kind = if metadata.file_type().is_symlink() {
+                EntryKind::Symlink
+            } else if metadata.is_dir() {
+                EntryKind::Dir
+            } else {
+                EntryKind::File
+            };
+            let modified = unix_secs(metadata.modified().ok());
+            self.remaining -= 1;
+
+            let children = if kind == EntryKind::Dir && depth > 1 {
+                self.read(&dir_entry.path(), &path, depth - 1, ignored).ok()
+            } else {
+                None
+            };
+            entries.push(FileEntry {
+                name,
+                path,
+                kind,
+                size: if kind == EntryKind::File { metadata.len() } else { 0 },
+                modified,
+                hidden,
+                ignored,
+                children,
+            });
+        }
+        entries.sort_by(|a, b| {
+            (a.kind != EntryKind::Dir)
+                .cmp(&(b.kind != EntryKind::Dir))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(entries)
+    }
+}
+
+/// Reads up to `max_bytes` (default 1 MiB, at most 16 MiB) of a text file
+/// inside an open repository or worktree; see `resolve_scoped`. A cut that
+/// splits a UTF-8 character drops the partial character.
+pub async fn read_file(
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    path: &Path,
+    max_bytes: Option<u64>,
+) -> Result<FileContent, String> {
+    let path = resolve_scoped(sessions, worktrees, path).await?;
+    let limit = max_bytes.unwrap_or(DEFAULT_READ_BYTES).min(MAX_FILE_BYTES);
+    tokio::task::spawn_blocking(move || {
+        let fail = |e: io::Error| format!("Failed to read {}: {e}", path.display());
+        let file = std::fs::File::open(&path).map_err(fail)?;
+        let metadata = file.metadata().map_err(fail)?;
+        if !metadata.is_file() {
+            return Err(format!("{} is not a file", path.display()));
+        }
+        let mut bytes = Vec::new();
+        file.take(limit).read_to_end(&mut bytes).map_err(fail)?;
+
+        let truncated = (bytes.len() as u64) < metadata.len();
+        let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+        let content = if sniff.contains(&0) {
+            None
+        } else {
+            match String::from_utf8(bytes) {
+                Ok(text) => Some(text),
+                Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+                    let valid = e.utf8_error().valid_up_to();
+                    let mut bytes = e.into_bytes();
+                    bytes.truncate(valid);
+                    String::from_utf8(bytes).ok()
+                }
+                Err(_) => None,
+            }
+        };
+        Ok(FileContent {
+            path: path.to_string_lossy().into_owned(),
+            size: metadata.len(),
+            modified: unix_secs(metadata.modified().ok()),
+            binary: content.is_none(),
+            truncated,
+            content,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Replaces (or creates) a file inside an open repository or worktree with
+/// `content`, atomically: it is written to a new sibling temp file with a
+/// random name, synced, and renamed over the target, keeping the target's
+/// permissions. Files in
+/// `.git` are refused. Returns the file as `read_file` would.
+pub async fn write_file(
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    path: &Path,
+    content: &str,
+) -> Result<FileContent, String> {
+    if content.len() as u64 > MAX_FILE_BYTES {
+        return Err(format!("Content is larger than {MAX_FILE_BYTES} bytes"));
+    }
+    let path = resolve_scoped(sessions, worktrees, path).await?;
+    if path.components().any(|c| c.as_os_str() == ".git") {
+        return Err(format!("Refusing to write inside .git: {}", path.display()));
+    }
+    let bytes = content.len();
+    let content = content.to_string();
+    let target = path.clone();
+    tokio::task::spawn_blocking(move || {
+        let fail = |e: io::Error| format!("Failed to write {}: {e}", target.display());
+        let existing = match std::fs::metadata(&target) {
+            Ok(metadata) if !metadata.is_file() => return Err(format!("{} is not a file", target.display())),
+            Ok(metadata) => Some(metadata.permissions()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(fail(e)),
+        };
+        let name = target.file_name().ok_or_else(|| format!("{} is not a file", target.display()))?;
+        let dir = target.parent().ok_or_else(|| format!("{} is not a file", target.display()))?;
+        // Created exclusively (`create_new`) under a random name, so a
+        // symlink planted at the name is never written through; removed on
+        // failure when dropped
+        let mut tmp = tempfile::Builder::new()
+            .prefix(&format!(".{}.", name.to_string_lossy()))
+            .suffix(".maestro-tmp")
+            .tempfile_in(dir)
+            .map_err(fail)?;
+        let written = (|| {
+            tmp.write_all(content.as_bytes())?;
+            // Temp files are private; a new file gets the usual mode
+            let permissions = existing.unwrap_or_else(|| std::fs::Permissions::from_mode(0o644));
+            tmp.as_file().set_permissions(permissions)?;
+            tmp.as_file().sync_all()
+        })();
+        written.map_err(fail)?;
+        tmp.persist(&target).map_err(|e| fail(e.error))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    log::info!("Wrote {} ({bytes} bytes)", path.display());
+    read_file(sessions, worktrees, &path, Some(MAX_FILE_BYTES)).await
+}
+
+/// Resolves `path` (absolute, symlinks followed) and checks that it lies
+/// inside an open repository or worktree: the repository or worktree of a
+/// session, a repository with managed worktrees, or the managed worktree
+/// directory. A repository or worktree counts as inside itself, the
+/// managed worktree directory does not. A file that does not exist yet is
+/// resolved through its parent directory, which must exist.
+pub(super) async fn resolve_scoped(sessions: &SessionManager, worktrees: &WorktreeManager, path: &Path) -> Result<PathBuf, String> {
+    if !path.is_absolute() {
+        return Err(format!("{} is not an absolute path", path.display()));
+    }
+    let resolved = match tokio::fs::canonicalize(path).await {
+        Ok(resolved) => resolved,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                return Err(format!("{} does not exist", path.display()));
+            };
+            tokio::fs::canonicalize(parent)
+                .await
+                .map_err(|e| format!("Cannot resolve {}: {e}", parent.display()))?
+                .join(name)
+        }
+        Err(e) => return Err(format!("Cannot resolve {}: {e}", path.display())),
+    };
+
+    let base = worktree_manager::worktree_base_dir();
+    let mut roots: Vec<PathBuf> = worktrees.known_repos().await;
+    roots.push(base.clone());
+    for session in sessions.all_sessions() {
+        roots.extend(session.repo().map(Path::to_path_buf));
+        roots.extend(session.worktree_path.as_ref().map(PathBuf::from));
+    }
+    let base = tokio::fs::canonicalize(&base).await.ok();
+    for root in roots {
+        if let Ok(root) = tokio::fs::canonicalize(&root).await {
+            if resolved.starts_with(&root) && base.as_ref() != Some(&resolved) {
+                return Ok(resolved);
+            }
+        }
+    }
+    Err(format!("{} is not inside an open repository or worktree", path.display()))
+}
+
+fn unix_secs(time: Option<SystemTime>) -> Option<i64> {
+    time.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
@@ -0,0 +1,185 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::error::AppError;
+use super::pull_request::gh;
+use super::session_manager::SessionManager;
+use crate::git::{Git, GitError, RemoteInfo};
+
+/// Remote holding the user's copy of the project, where branches are pushed.
+pub const FORK_REMOTE: &str = "origin";
+
+/// Remote of the project a fork was made from, conventionally.
+pub const UPSTREAM_REMOTE: &str = "upstream";
+
+/// Remote layout of a repository.
+///
+/// In a fork setup, `origin` is the user's fork, which session branches
+/// are pushed to, and `upstream` the original project, which they are
+/// diffed and rebased against: `base_ref` (e.g. `upstream/main`) is the
+/// ref to use as their base. Without `upstream`, `origin` plays both roles.
+/// `missing_upstream_url` is set when GitHub reports `origin` as a fork but
+/// no `upstream` remote is configured; see `add_upstream_remote`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ForkInfo {
+    pub remotes: Vec<RemoteInfo>,
+    pub is_fork: bool,
+    pub push_remote: Option<String>,
+    pub base_remote: Option<String>,
+    pub base_branch: Option<String>,
+    pub base_ref: Option<String>,
+    pub missing_upstream_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhRepoView {
+    is_fork: bool,
+    parent: Option<GhParent>,
+}
+
+#[derive(Deserialize)]
+struct GhParent {
+    name: String,
+    owner: GhOwner,
+}
+
+#[derive(Deserialize)]
+struct GhOwner {
+    login: String,
+}
+
+/// Describes the repository's remotes. Only asks GitHub (through `gh`)
+/// whether `origin` is a fork if no `upstream` remote exists and `origin`
+/// is on GitHub; failures of that check are logged and ignored.
+pub async fn detect_fork(repo_path: &Path) -> Result<ForkInfo, String> {
+    let git = Git::new(repo_path);
+    let remotes = git.remotes().await.map_err(|e| e.to_string())?;
+    let url_of = |name: &str| remotes.iter().find(|r| r.name == name).map(|r| r.url.clone());
+    let origin_url = url_of(FORK_REMOTE);
+    let has_upstream = url_of(UPSTREAM_REMOTE).is_some();
+
+    let push_remote = origin_url.as_ref().map(|_| FORK_REMOTE.to_string());
+    let base_remote = if has_upstream {
+        Some(UPSTREAM_REMOTE.to_string())
+    } else {
+        push_remote.clone()
+    };
+    let base_branch = match &base_remote {
+        Some(remote) => git.remote_default_branch(remote).await.map_err(|e| e.to_string())?,
+        None => None,
+    };
+    let missing_upstream_url = match origin_url {
+        Some(url) if !has_upstream && github_slug(&url).is_some() => match github_parent_url(repo_path).await {
+            Ok(parent) => parent,
+            Err(e) => {
+                log::warn!("Cannot check whether {} is a fork: {e}", repo_path.display());
+                None
+            }
+        },
+        _ => None,
+    };
+
+    Ok(ForkInfo {
+        base_ref: base_remote
+            .as_ref()
+            .zip(base_branch.as_ref())
+            .map(|(remote, branch)| format!("{remote}/{branch}")),
+        is_fork: has_upstream || missing_upstream_url.is_some(),
+        remotes,
+        push_remote,
+        base_remote,
+        base_branch,
+        missing_upstream_url,
+    })
+}
+
+/// Adds the `upstream` remote, pointing to `url` or, by default, to the
+/// repository GitHub says `origin` was forked from, fetches it, and returns
+/// the new layout.
+pub async fn add_upstream_remote(repo_path: &Path, url: Option<String>) -> Result<ForkInfo, String> {
+    let git = Git::new(repo_path);
+    let remotes = git.remotes().await.map_err(|e| e.to_string())?;
+    if remotes.iter().any(|r| r.name == UPSTREAM_REMOTE) {
+        return Err(format!("Remote '{UPSTREAM_REMOTE}' already exists"));
+    }
+    let url = match url.filter(|u| !u.trim().is_empty()) {
+        Some(url) => url,
+        None => github_parent_url(repo_path)
+            .await?
+            .ok_or("origin is not a GitHub fork; give the upstream URL")?,
+    };
+    git.add_remote(UPSTREAM_REMOTE, &url).await.map_err(|e| e.to_string())?;
+    git.fetch_remote(UPSTREAM_REMOTE).await.map_err(|e| e.to_string())?;
+    log::info!("Added remote {UPSTREAM_REMOTE} ({url}) to {}", repo_path.display());
+    detect_fork(repo_path).await
+}
+
+/// Fetches the base remote (`upstream` if configured, else `origin`) and
+/// rebases the session's branch onto its default branch. The worktree must
+/// be clean; a conflicting rebase is aborted and reported. Returns the
+/// branch's new HEAD.
+pub async fn rebase_session_on_upstream(sessions: &SessionManager, session_id: u32) -> Result<String, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let (Some(repo_path), Some(wt_path)) = (session.repo(), &session.worktree_path) else {
+        return Err(
+            AppError::invalid_argument(format!("Session {session_id} has no worktree")).with("session_id", session_id),
+        );
+    };
+    let wt = Git::new(wt_path);
+    if wt.uncommitted_count().await? > 0 {
+        return Err(GitError::DirtyWorkingTree {
+            path: wt_path.clone(),
+        }
+        .into());
+    }
+
+    let remote = detect_fork(repo_path)
+        .await?
+        .base_remote
+        .ok_or_else(|| AppError::not_found("The repository has no remote to rebase onto"))?;
+    let git = Git::new(repo_path);
+    git.fetch_remote(&remote).await?;
+    let branch = git
+        .remote_default_branch(&remote)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Cannot determine the default branch of {remote}")))?;
+    let head = wt.rebase_onto(&format!("{remote}/{branch}")).await?;
+    log::info!("Session {session_id}: rebased onto {remote}/{branch}");
+    Ok(head)
+}
+
+/// Returns the `owner/repo` slug of the project pull requests should target
+/// if the repository is a fork with an `upstream` remote on GitHub, else
+/// `None` (the `gh` default, `origin`'s repository, is right).
+pub async fn upstream_slug(repo_path: &Path) -> Option<String> {
+    let remotes = Git::new(repo_path).remotes().await.ok()?;
+    let upstream = remotes.iter().find(|r| r.name == UPSTREAM_REMOTE)?;
+    let (owner, repo) = github_slug(&upstream.url)?;
+    Some(format!("{owner}/{repo}"))
+}
+
+/// Splits a GitHub remote URL (HTTPS or SSH) into owner and repository.
+pub fn github_slug(url: &str) -> Option<(String, String)> {
+    let (_, path) = url.split_once("github.com")?;
+    let path = path.trim_start_matches([':', '/']).trim_end_matches('/');
+    let (owner, repo) = path.split_once('/')?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    (!owner.is_empty() && !repo.is_empty() && !repo.contains('/')).then(|| (owner.to_string(), repo.to_string()))
+}
+
+/// Asks GitHub which repository `origin` was forked from, returning its
+/// clone URL, or `None` if `origin` is not a fork.
+async fn github_parent_url(repo_path: &Path) -> Result<Option<String>, String> {
+    let stdout = gh(repo_path, &["repo", "view", "--json", "isFork,parent"]).await?;
+    let view: GhRepoView =
+        serde_json::from_str(&stdout).map_err(|e| format!("Unexpected repository data from gh: {e}"))?;
+    Ok(view
+        .parent
+        .filter(|_| view.is_fork)
+        .map(|parent| format!("https://github.com/{}/{}.git", parent.owner.login, parent.name)))
+}
@@ -0,0 +1,250 @@
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+use tokio::time::Instant;
+
+use super::session_manager::{SessionConfig, SessionListener};
+use crate::git::Git;
+
+/// Quiet time after a change before it is reported, so a burst of writes
+/// (a build, a checkout, an agent's edit) becomes one event.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest a change waits to be reported while writes keep coming.
+const MAX_LATENCY: Duration = Duration::from_millis(1500);
+
+/// Most paths listed in one event; the rest are only counted by `truncated`.
+const MAX_REPORTED_PATHS: usize = 500;
+
+/// Payload of `fs-changed-{session_id}`: files and directories created,
+/// modified, or removed in the session's worktree, relative to it.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FsChange {
+    pub session_id: u32,
+    pub paths: Vec<String>,
+    pub truncated: bool,
+}
+
+/// A changed path, and whether the change may have added a directory,
+/// which then needs a watch of its own.
+type Change = (PathBuf, bool);
+
+struct Watch {
+    path: PathBuf,
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    watches: DashMap<u32, Watch>,
+}
+
+/// Watches the worktrees of the sessions the frontend shows and emits
+/// `fs-changed-{session_id}` with the changed paths, debounced, so diff
+/// panels and status counters refresh as the agent edits files. Changes
+/// inside `.git` and the directories git ignores, such as build output and
+/// dependencies, are not watched.
+///
+/// Registered as a `SessionListener` to stop watching removed sessions.
+/// Cheap to clone; also placed in Tauri managed state.
+#[derive(Clone)]
+pub struct FsWatcher {
+    inner: Arc<Inner>,
+}
+
+impl FsWatcher {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                watches: DashMap::new(),
+            }),
+        }
+    }
+
+    /// Starts watching the session's worktree, directory by directory (see
+    /// `add_watches`). Does nothing if it is already watched; a session
+    /// moved to another worktree is watched there instead.
+    pub fn watch(&self, session: &SessionConfig) -> Result<(), String> {
+        let Some(wt_path) = &session.worktree_path else {
+            return Err(format!("Session {} has no worktree", session.id));
+        };
+        let root = PathBuf::from(wt_path);
+        if self
+            .inner
+            .watches
+            .get(&session.id)
+            .is_some_and(|watch| watch.path == root)
+        {
+            return Ok(());
+        }
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Change>();
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                let new_dir = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_)));
+                for path in event.paths {
+                    let _ = tx.send((path, new_dir));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("File watcher error: {e}"),
+        })
+        .map_err(|e| format!("Failed to create a file watcher: {e}"))?;
+        watcher
+            .watch(&root, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {e}", root.display()))?;
+        let watcher = Arc::new(Mutex::new(watcher));
+
+        // Ends when the watcher, which owns the sender, is dropped
+        tauri::async_runtime::spawn(report_changes(
+            self.inner.app_handle.clone(),
+            session.id,
+            root.clone(),
+            Arc::downgrade(&watcher),
+            rx,
+        ));
+        self.inner.watches.insert(
+            session.id,
+            Watch {
+                path: root,
+                _watcher: watcher,
+            },
+        );
+        log::debug!("Session {}: watching {wt_path}", session.id);
+        Ok(())
+    }
+
+    /// Stops watching the session's worktree. Returns whether it was watched.
+    pub fn unwatch(&self, session_id: u32) -> bool {
+        self.inner.watches.remove(&session_id).is_some()
+    }
+}
+
+impl SessionListener for FsWatcher {
+    fn on_removed(&self, session: &SessionConfig) {
+        self.unwatch(session.id);
+    }
+}
+
+async fn report_changes(
+    app: AppHandle,
+    session_id: u32,
+    root: PathBuf,
+    watcher: Weak<Mutex<RecommendedWatcher>>,
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<Change>,
+) {
+    watch_tree(&watcher, &root, root.clone()).await;
+    let event_name = format!("fs-changed-{session_id}");
+    while let Some(first) = rx.recv().await {
+        let deadline = Instant::now() + MAX_LATENCY;
+        let mut changed = BTreeSet::new();
+        let mut truncated = false;
+        let mut pending = Some(first);
+        loop {
+            while let Some((path, new_dir)) = pending.take().or_else(|| rx.try_recv().ok()) {
+                let Some(rel) = relative(&root, &path) else {
+                    continue;
+                };
+                if new_dir && path.is_dir() {
+                    watch_tree(&watcher, &root, path).await;
+                }
+                if changed.len() < MAX_REPORTED_PATHS {
+                    changed.insert(rel);
+                } else if !changed.contains(&rel) {
+                    truncated = true;
+                }
+            }
+            // Keep collecting until the worktree has been quiet for a while,
+            // or the first change has waited long enough
+            let wait = DEBOUNCE.min(deadline.saturating_duration_since(Instant::now()));
+            match tokio::time::timeout(wait, rx.recv()).await {
+                Ok(Some(change)) => pending = Some(change),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        if changed.is_empty() {
+            continue;
+        }
+        let change = FsChange {
+            session_id,
+            paths: changed.into_iter().collect(),
+            truncated,
+        };
+        let _ = app.emit(&event_name, &change);
+    }
+}
+
+/// Watches `dir` and the directories below it with `add_watches`, skipping
+/// those git ignores as of now.
+async fn watch_tree(watcher: &Weak<Mutex<RecommendedWatcher>>, root: &Path, dir: PathBuf) {
+    let rel = relative(root, &dir).unwrap_or_default();
+    let ignored: HashSet<PathBuf> = match Git::new(root)
+        .ignored_paths(if rel.is_empty() { "." } else { &rel })
+        .await
+    {
+        Ok(paths) => paths
+            .iter()
+            .filter_map(|p| p.strip_suffix('/'))
+            .map(|p| root.join(p))
+            .collect(),
+        Err(e) => {
+            log::debug!("Failed to list ignored paths in {}: {e}", dir.display());
+            HashSet::new()
+        }
+    };
+    let watcher = watcher.clone();
+    let _ = tokio::task::spawn_blocking(move || {
+        if let Some(watcher) = watcher.upgrade() {
+            add_watches(&watcher, &dir, &ignored);
+        }
+    })
+    .await;
+}
+
+/// Watches `dir` and, recursively, its subdirectories, except `.git` and
+/// `ignored`, each with a watch of its own. A recursive watch of the whole
+/// worktree would also cover build output and dependencies, whose churn
+/// and size would drown out the agent's edits.
+fn add_watches(watcher: &Mutex<RecommendedWatcher>, dir: &Path, ignored: &HashSet<PathBuf>) {
+    if ignored.contains(dir) || dir.file_name().is_some_and(|name| name == ".git") {
+        return;
+    }
+    if let Err(e) = lock(watcher).watch(dir, RecursiveMode::NonRecursive) {
+        log::debug!("Failed to watch {}: {e}", dir.display());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            add_watches(watcher, &entry.path(), ignored);
+        }
+    }
+}
+
+fn lock(watcher: &Mutex<RecommendedWatcher>) -> MutexGuard<'_, RecommendedWatcher> {
+    match watcher.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Returns `path` relative to `root` with `/` separators, or `None` for
+/// paths outside it or inside `.git`.
+fn relative(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    if rel.starts_with(".git") {
+        return None;
+    }
+    Some(rel.to_string_lossy().replace('\\', "/"))
+}
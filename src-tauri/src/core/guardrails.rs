@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::approval::normalize;
+use super::clock;
+use super::process_manager::ProcessManager;
+use super::session_manager::{SessionConfig, SessionListener, SessionManager, SessionStatus, StatusCause};
+use super::storage;
+use super::windows;
+
+/// Current on-disk format of `guardrail-policy.json`.
+const GUARDRAIL_POLICY_VERSION: u32 = 1;
+
+/// Event emitted whenever an agent command matches a guardrail.
+pub const GUARDRAIL_TRIGGERED_EVENT: &str = "guardrail-triggered";
+
+/// Tools whose calls run a shell command: Claude Code's, Gemini CLI's, and
+/// Codex's.
+const SHELL_TOOLS: &[&str] = &["Bash", "run_shell_command", "shell"];
+
+/// Longest command shown in a status detail.
+const MAX_DETAIL_CHARS: usize = 200;
+
+/// Commands agents must not run without the user looking first.
+///
+/// With `block_rm_outside_worktree`, a recursive or forced `rm` of a path
+/// outside the session's worktree (absolute, under `~`, or escaping with
+/// `..`) matches. With `protected_branches` non-empty, a forced `git push`
+/// to one of them matches; a push without a refspec counts as a push of
+/// the session's branch. With `block_pipe_to_shell`, piping `curl` or
+/// `wget` output into a shell matches. `patterns` are extra regexes matched
+/// against the whole command. Nothing is checked unless `enabled`.
+/// Fields missing from a saved policy take their default values.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct GuardrailPolicy {
+    pub enabled: bool,
+    pub block_rm_outside_worktree: bool,
+    pub protected_branches: Vec<String>,
+    pub block_pipe_to_shell: bool,
+    pub patterns: Vec<String>,
+}
+
+impl Default for GuardrailPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            block_rm_outside_worktree: true,
+            protected_branches: vec!["main".to_string(), "master".to_string()],
+            block_pipe_to_shell: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl GuardrailPolicy {
+    /// Rejects patterns that are not valid regexes.
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in &self.patterns {
+            Regex::new(pattern).map_err(|e| format!("Invalid guardrail pattern '{pattern}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Payload of the `guardrail-triggered` event. `held` is `true` if the
+/// agent was stopped until `Guardrails::resolve`; otherwise the command was
+/// seen in a permission prompt, which is left to the user.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct GuardrailAlert {
+    pub session_id: u32,
+    pub command: String,
+    pub rule: String,
+    pub held: bool,
+    pub at: i64,
+}
+
+/// An agent stopped by a guardrail, waiting for the user's decision.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HeldCommand {
+    pub session_id: u32,
+    pub command: String,
+    pub rule: String,
+    pub held_at: i64,
+    #[serde(skip)]
+    pty_id: u32,
+    #[serde(skip)]
+    pgid: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GuardrailPolicyFile {
+    version: u32,
+    policy: GuardrailPolicy,
+}
+
+struct Checks {
+    policy: GuardrailPolicy,
+    patterns: Vec<Regex>,
+}
+
+/// Screens the shell commands agents run against the `GuardrailPolicy`.
+///
+/// Commands come from two places. A permission prompt for a matching
+/// command is never auto-approved (see `status_inference`), so the user
+/// decides as usual. A matching shell tool call seen in structured agent
+/// output means the agent runs commands without asking; its foreground job
+/// is stopped with SIGSTOP and held until `resolve`, and the session is set
+/// to `NeedsInput`. Output is only seen once the agent has printed the
+/// call, so a fast command may already have started; it is stopped along
+/// with the agent. Placed in Tauri managed state.
+pub struct Guardrails {
+    path: PathBuf,
+    checks: Mutex<Checks>,
+    held: Mutex<HashMap<u32, HeldCommand>>,
+    /// Last prompt command reported per session, so a prompt that stays on
+    /// screen is reported once.
+    prompted: Mutex<HashMap<u32, String>>,
+    pipe_to_shell: Regex,
+}
+
+impl Guardrails {
+    /// Loads the policy from the data dir, starting disabled if the file is
+    /// missing or unreadable.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("guardrail-policy.json");
+        let policy = match storage::load_json::<GuardrailPolicyFile>(&path) {
+            Ok(Some(file)) if file.version <= GUARDRAIL_POLICY_VERSION => file.policy,
+            Ok(Some(file)) => {
+                log::warn!("Ignoring guardrail policy with unknown version {}", file.version);
+                GuardrailPolicy::default()
+            }
+            Ok(None) => GuardrailPolicy::default(),
+            Err(e) => {
+                log::error!("Failed to load guardrail policy {}: {e}", path.display());
+                GuardrailPolicy::default()
+            }
+        };
+        Self {
+            path,
+            checks: Mutex::new(compile(policy)),
+            held: Mutex::new(HashMap::new()),
+            prompted: Mutex::new(HashMap::new()),
+            pipe_to_shell: Regex::new(r"\b(curl|wget)\b[^|;&]*\|\s*(sudo\s+)?(env\s+)?(ba|z|da|k|fi)?sh\b")
+                .expect("valid pipe-to-shell pattern"),
+        }
+    }
+
+    /// Returns the current policy.
+    pub fn policy(&self) -> GuardrailPolicy {
+        self.lock_checks().policy.clone()
+    }
+
+    /// Validates and persists a new policy.
+    pub fn set_policy(&self, policy: GuardrailPolicy) -> Result<(), String> {
+        policy.validate()?;
+        let mut checks = self.lock_checks();
+        let file = GuardrailPolicyFile {
+            version: GUARDRAIL_POLICY_VERSION,
+            policy: policy.clone(),
+        };
+        storage::save_json(&self.path, &file).map_err(|e| e.to_string())?;
+        *checks = compile(policy);
+        Ok(())
+    }
+
+    /// Returns the rule `command` breaks for a session working in
+    /// `worktree` on `branch`, or `None` if it may run.
+    pub fn check(&self, command: &str, worktree: Option<&Path>, branch: Option<&str>) -> Option<String> {
+        let checks = self.lock_checks();
+        let policy = &checks.policy;
+        if !policy.enabled {
+            return None;
+        }
+        for segment in split_commands(command) {
+            let words = words(segment);
+            if policy.block_rm_outside_worktree && removes_outside(&words, worktree) {
+                return Some("rm outside the worktree".to_string());
+            }
+            let targets = force_push_targets(&words, branch);
+            if let Some(target) = targets.iter().find(|t| policy.protected_branches.contains(t)) {
+                return Some(format!("force push to {target}"));
+            }
+        }
+        if policy.block_pipe_to_shell && self.pipe_to_shell.is_match(command) {
+            return Some("download piped into a shell".to_string());
+        }
+        checks
+            .patterns
+            .iter()
+            .find(|p| p.is_match(command))
+            .map(|p| format!("matches '{}'", p.as_str()))
+    }
+
+    /// Checks a permission prompt's command and reports a match. Returns
+    /// `true` if the prompt must be left to the user.
+    pub fn screen_prompt(&self, app: &AppHandle, session: &SessionConfig, command: &str) -> bool {
+        let Some(rule) = self.check_session(session, command) else {
+            return false;
+        };
+        let mut prompted = self.lock_prompted();
+        if prompted.insert(session.id, command.to_string()).as_deref() == Some(command) {
+            return true;
+        }
+        drop(prompted);
+        log::warn!("Session {}: guardrail '{rule}' on prompt for `{command}`", session.id);
+        windows::emit_to_session(
+            app,
+            session.id,
+            GUARDRAIL_TRIGGERED_EVENT,
+            GuardrailAlert {
+                session_id: session.id,
+                command: command.to_string(),
+                rule,
+                held: false,
+                at: clock::now_secs(),
+            },
+        );
+        true
+    }
+
+    /// Checks a tool call the agent is making and, if it is a shell
+    /// command that matches, holds the agent.
+    pub fn screen_tool_call(&self, app: &AppHandle, session: &SessionConfig, tool: &str, input: &Value) {
+        if !SHELL_TOOLS.contains(&tool) || self.is_held(session.id) {
+            return;
+        }
+        let command = match &input["command"] {
+            Value::String(command) => command.clone(),
+            Value::Array(parts) => parts.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(" "),
+            _ => return,
+        };
+        let Some(rule) = self.check_session(session, &command) else {
+            return;
+        };
+        let Some(pty_id) = session.pty_id else {
+            return;
+        };
+
+        let held = match app.state::<ProcessManager>().stop_foreground_job(pty_id) {
+            Ok(Some(pgid)) => {
+                let held_at = clock::now_secs();
+                self.lock_held().insert(
+                    session.id,
+                    HeldCommand {
+                        session_id: session.id,
+                        command: command.clone(),
+                        rule: rule.clone(),
+                        held_at,
+                        pty_id,
+                        pgid,
+                    },
+                );
+                let shown: String = command.chars().take(MAX_DETAIL_CHARS).collect();
+                app.state::<SessionManager>().update_status_with_detail(
+                    session.id,
+                    SessionStatus::NeedsInput,
+                    StatusCause::AutoDetected,
+                    Some(format!("Held by guardrail ({rule}): {shown}")),
+                );
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                log::warn!("Session {}: failed to hold agent: {e}", session.id);
+                false
+            }
+        };
+        log::warn!("Session {}: guardrail '{rule}' on `{command}` (held: {held})", session.id);
+        windows::emit_to_session(
+            app,
+            session.id,
+            GUARDRAIL_TRIGGERED_EVENT,
+            GuardrailAlert {
+                session_id: session.id,
+                command,
+                rule,
+                held,
+                at: clock::now_secs(),
+            },
+        );
+    }
+
+    /// Returns the agents currently held, oldest first.
+    pub fn held(&self) -> Vec<HeldCommand> {
+        let mut held: Vec<HeldCommand> = self.lock_held().values().cloned().collect();
+        held.sort_by_key(|h| h.held_at);
+        held
+    }
+
+    /// Returns `true` if the session's agent is held; its output (the
+    /// shell reporting the stopped job) says nothing about the agent.
+    pub fn is_held(&self, session_id: u32) -> bool {
+        self.lock_held().contains_key(&session_id)
+    }
+
+    /// Ends a hold. Approving resumes the agent in the foreground with
+    /// `fg`, command included. Rejecting terminates the agent and the
+    /// command; the session's shell returns to its prompt, from where the
+    /// agent can be resumed.
+    pub fn resolve(&self, app: &AppHandle, session_id: u32, approve: bool) -> Result<(), String> {
+        let held = self
+            .lock_held()
+            .remove(&session_id)
+            .ok_or_else(|| format!("Session {session_id} has no held command"))?;
+        self.lock_prompted().remove(&session_id);
+        let processes = app.state::<ProcessManager>();
+        let result = if approve {
+            processes.write_stdin(held.pty_id, "fg\n")
+        } else {
+            processes
+                .signal_job(held.pty_id, held.pgid, libc::SIGTERM)
+                .and_then(|_| processes.signal_job(held.pty_id, held.pgid, libc::SIGCONT))
+        };
+        if let Err(e) = result {
+            self.lock_held().insert(session_id, held);
+            return Err(e.to_string());
+        }
+        let (status, verdict) = if approve {
+            (SessionStatus::Working, "approved")
+        } else {
+            (SessionStatus::Idle, "rejected")
+        };
+        log::info!("Session {session_id}: held command {verdict}: `{}`", held.command);
+        app.state::<SessionManager>()
+            .update_status(session_id, status, StatusCause::Manual);
+        Ok(())
+    }
+
+    /// Forgets the hold and the reported prompt of a session whose shell
+    /// exited or that was removed.
+    pub fn release(&self, session_id: u32) {
+        self.lock_held().remove(&session_id);
+        self.lock_prompted().remove(&session_id);
+    }
+
+    fn check_session(&self, session: &SessionConfig, command: &str) -> Option<String> {
+        let worktree = session.worktree_path.as_deref().map(Path::new);
+        self.check(command, worktree, session.branch.as_deref())
+    }
+
+    fn lock_checks(&self) -> MutexGuard<'_, Checks> {
+        match self.checks.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn lock_held(&self) -> MutexGuard<'_, HashMap<u32, HeldCommand>> {
+        match self.held.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn lock_prompted(&self) -> MutexGuard<'_, HashMap<u32, String>> {
+        match self.prompted.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Releases the `Guardrails` hold of every removed session. A session torn
+/// down while held is killed without its shell reporting an exit, so
+/// nothing else would. Registered as a `SessionListener`.
+pub struct GuardrailCleanup {
+    app_handle: AppHandle,
+}
+
+impl GuardrailCleanup {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl SessionListener for GuardrailCleanup {
+    fn on_removed(&self, session: &SessionConfig) {
+        self.app_handle.state::<Guardrails>().release(session.id);
+    }
+}
+
+/// Compiles the policy's patterns; invalid ones, which `set_policy`
+/// rejects but a hand-edited file may contain, are skipped.
+fn compile(policy: GuardrailPolicy) -> Checks {
+    let patterns = policy
+        .patterns
+        .iter()
+        .filter_map(|p| match Regex::new(p) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Ignoring invalid guardrail pattern '{p}': {e}");
+                None
+            }
+        })
+        .collect();
+    Checks { policy, patterns }
+}
+
+/// Splits a command line into simple commands at `;`, `&&`, `||`, `|`,
+/// and newlines. Quoting is not understood, so a separator inside quotes
+/// splits too, which only makes the checks stricter.
+fn split_commands(command: &str) -> impl Iterator<Item = &str> {
+    command
+        .split(['\n', ';', '&', '|'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Returns the words of a simple command with quotes removed, skipping
+/// leading `sudo`, `env`, and variable assignments.
+fn words(segment: &str) -> Vec<String> {
+    let words: Vec<String> = segment
+        .split_whitespace()
+        .map(|w| w.trim_matches(['"', '\'']).to_string())
+        .collect();
+    let start = words
+        .iter()
+        .position(|w| !(w == "sudo" || w == "env" || (w.contains('=') && !w.starts_with('-'))))
+        .unwrap_or(words.len());
+    words[start..].to_vec()
+}
+
+/// Returns `true` for `rm -r`/`rm -f` of a path that is not inside
+/// `worktree`. Without a worktree, every absolute or home path counts.
+fn removes_outside(words: &[String], worktree: Option<&Path>) -> bool {
+    if words.first().map(String::as_str) != Some("rm") {
+        return false;
+    }
+    let (flags, targets): (Vec<&String>, Vec<&String>) = words[1..].iter().partition(|w| w.starts_with('-'));
+    let forceful = flags.iter().any(|f| {
+        matches!(f.as_str(), "--recursive" | "--force")
+            || (!f.starts_with("--") && f.contains(['r', 'R', 'f']))
+    });
+    if !forceful {
+        return false;
+    }
+    targets.iter().any(|target| {
+        if target.starts_with('~') || target.starts_with('$') {
+            return true;
+        }
+        match worktree {
+            Some(worktree) => {
+                let root = normalize(worktree);
+                !normalize(&worktree.join(target.as_str())).starts_with(&root)
+            }
+            None => target.starts_with('/') || target.contains(".."),
+        }
+    })
+}
+
+/// Returns the branches a `git push` force-updates: the destination of
+/// every refspec when the push is forced, or of each `+`-prefixed refspec
+/// otherwise. Empty if the command is not such a push.
+fn force_push_targets(words: &[String], branch: Option<&str>) -> Vec<String> {
+    if words.first().map(String::as_str) != Some("git") {
+        return Vec::new();
+    }
+    let Some(push) = words.iter().position(|w| w == "push") else {
+        return Vec::new();
+    };
+    let args = &words[push + 1..];
+    let forced = args.iter().any(|a| {
+        a.starts_with("--force") || (a.starts_with('-') && !a.starts_with("--") && a.contains('f'))
+    });
+    let refspecs: Vec<&String> = args.iter().filter(|a| !a.starts_with('-')).skip(1).collect();
+    if refspecs.is_empty() {
+        return branch.filter(|_| forced).map(str::to_string).into_iter().collect();
+    }
+    refspecs
+        .into_iter()
+        .filter_map(|refspec| {
+            let (plus, refspec) = match refspec.strip_prefix('+') {
+                Some(rest) => (true, rest),
+                None => (false, refspec.as_str()),
+            };
+            let destination = refspec.rsplit(':').next().unwrap_or(refspec);
+            let destination = destination.trim_start_matches("refs/heads/");
+            (forced || plus).then(|| destination.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(command: &str) -> Vec<String> {
+        force_push_targets(&words(command), Some("feature"))
+    }
+
+    #[test]
+    fn checks_every_refspec_of_a_forced_push() {
+        assert_eq!(targets("git push --force origin feature main"), ["feature", "main"]);
+        assert_eq!(targets("git push -f origin main feature"), ["main", "feature"]);
+        assert_eq!(targets("git push origin feature main"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn uses_the_destination_of_a_refspec() {
+        assert_eq!(targets("git push -f origin HEAD:refs/heads/main"), ["main"]);
+        assert_eq!(targets("git push --force origin main:feature"), ["feature"]);
+    }
+
+    #[test]
+    fn forces_only_plus_refspecs_of_an_unforced_push() {
+        assert_eq!(targets("git push origin +main"), ["main"]);
+        assert_eq!(targets("git push origin +HEAD:main feature"), ["main"]);
+        assert_eq!(targets("git push origin main +feature"), ["feature"]);
+    }
+
+    #[test]
+    fn falls_back_to_the_session_branch_without_refspecs() {
+        assert_eq!(targets("git push --force-with-lease"), ["feature"]);
+        assert_eq!(targets("git push origin"), Vec::<String>::new());
+    }
+}
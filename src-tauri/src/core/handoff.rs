@@ -0,0 +1,253 @@
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::error::{AppError, ErrorCode};
+use super::harvest;
+use super::repo_config;
+use super::session_manager::{SessionConfig, SessionManager};
+use crate::git::Git;
+
+/// A path that could not be handed off, and why.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HandoffFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// Outcome of `copy_between_worktrees`: the paths copied, relative to the
+/// worktree roots, and those that failed.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CopyHandoff {
+    pub copied: Vec<String>,
+    pub failed: Vec<HandoffFailure>,
+}
+
+/// Outcome of `patch_between_worktrees`. `files` are the paths the patch
+/// touched; `conflicts` those left with conflict markers in the
+/// destination, to be resolved there.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PatchHandoff {
+    pub base_ref: String,
+    pub files: Vec<String>,
+    pub conflicts: Vec<String>,
+}
+
+/// Returns both sessions, checking they differ and have worktrees.
+fn sessions_of(
+    app: &AppHandle,
+    src_session: u32,
+    dest_session: u32,
+) -> Result<(SessionConfig, SessionConfig), AppError> {
+    if src_session == dest_session {
+        return Err(AppError::invalid_argument("Source and destination are the same session"));
+    }
+    let sessions = app.state::<SessionManager>();
+    let find = |id: u32| {
+        let session = sessions.get_session(id).ok_or_else(|| AppError::session_not_found(id))?;
+        match session.worktree_path {
+            Some(_) => Ok(session),
+            None => Err(AppError::invalid_argument(format!("Session {id} has no worktree")).with("session_id", id)),
+        }
+    };
+    Ok((find(src_session)?, find(dest_session)?))
+}
+
+fn worktree_of(session: &SessionConfig) -> PathBuf {
+    PathBuf::from(session.worktree_path.as_deref().unwrap_or_default())
+}
+
+/// Checks that `path` is relative, stays inside the worktree, and is not
+/// git's own metadata at any depth, returning it without `.` components.
+fn validate_path(path: &str) -> Result<PathBuf, String> {
+    if !repo_config::is_inside(path) {
+        return Err("must be a relative path inside the worktree".to_string());
+    }
+    let path: PathBuf = Path::new(path)
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect();
+    if path.as_os_str().is_empty() {
+        return Err("must name a file or directory inside the worktree".to_string());
+    }
+    if path.components().any(|c| c == Component::Normal(".git".as_ref())) {
+        return Err("git metadata cannot be handed off".to_string());
+    }
+    Ok(path)
+}
+
+/// Checks that the directory `path` lies in does not resolve outside
+/// `root` through a symlink, so a linked directory in a worktree cannot
+/// read or write files elsewhere. `path` itself may be a symlink: it is
+/// copied as one, not followed.
+fn check_contained(root: &Path, path: &Path) -> Result<(), String> {
+    let root = std::fs::canonicalize(root).map_err(|e| format!("worktree is unavailable: {e}"))?;
+    // The nearest existing ancestor, for a target whose directories do not
+    // exist yet
+    let existing = path
+        .parent()
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .find(|dir| dir.symlink_metadata().is_ok())
+        .ok_or("worktree is unavailable")?;
+    let resolved = std::fs::canonicalize(existing).map_err(|e| e.to_string())?;
+    if resolved.starts_with(&root) {
+        Ok(())
+    } else {
+        Err("leads outside the worktree through a symlink".to_string())
+    }
+}
+
+/// Copies `source` to `target` like `repo_config::copy_recursive`, but
+/// replaces whatever non-directory is at each target path, symlinks
+/// included, instead of writing through it.
+fn copy_tree(source: &Path, target: &Path) -> io::Result<()> {
+    if target.symlink_metadata().is_ok_and(|m| !m.is_dir()) {
+        std::fs::remove_file(target)?;
+    }
+    let metadata = source.symlink_metadata()?;
+    if metadata.is_symlink() {
+        std::os::unix::fs::symlink(std::fs::read_link(source)?, target)
+    } else if metadata.is_dir() {
+        std::fs::create_dir_all(target)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(source, target).map(|_| ())
+    }
+}
+
+/// Copies files or directories, given relative to the worktree root, from
+/// one session's worktree into the same place in another's, so one agent's
+/// output can be handed to the next.
+///
+/// Existing files in the destination are overwritten and directories are
+/// merged; nothing is committed. Paths reaching outside either worktree
+/// through a symlink are refused. Each path is copied on its own, so one
+/// failure does not stop the rest.
+pub async fn copy_between_worktrees(
+    app: &AppHandle,
+    src_session: u32,
+    dest_session: u32,
+    paths: Vec<String>,
+) -> Result<CopyHandoff, AppError> {
+    let (src, dest) = sessions_of(app, src_session, dest_session)?;
+    if paths.is_empty() {
+        return Err(AppError::invalid_argument("No paths to copy"));
+    }
+    let (src_root, dest_root) = (worktree_of(&src), worktree_of(&dest));
+    tokio::task::spawn_blocking(move || {
+        let mut handoff = CopyHandoff {
+            copied: Vec::new(),
+            failed: Vec::new(),
+        };
+        for path in paths {
+            let copied = validate_path(&path).and_then(|relative| {
+                let source = src_root.join(&relative);
+                let target = dest_root.join(&relative);
+                if source.symlink_metadata().is_err() {
+                    return Err(format!("not found in session {src_session}'s worktree"));
+                }
+                check_contained(&src_root, &source)?;
+                check_contained(&dest_root, &target)?;
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                copy_tree(&source, &target).map_err(|e| e.to_string())
+            });
+            match copied {
+                Ok(()) => handoff.copied.push(path),
+                Err(error) => handoff.failed.push(HandoffFailure { path, error }),
+            }
+        }
+        log::info!(
+            "Copied {} paths from session {src_session} to session {dest_session}",
+            handoff.copied.len()
+        );
+        handoff
+    })
+    .await
+    .map_err(|e| AppError::new(ErrorCode::Failed, format!("Copy task failed: {e}")))
+}
+
+/// Applies what one session changed to another session's worktree as a
+/// patch, instead of copying whole files, so changes the destination made
+/// to the same files are kept.
+///
+/// The patch covers every change in the source worktree since its branch
+/// left its base (see `harvest::base_ref_of`), committed, uncommitted,
+/// and untracked, limited to `paths` if given. It is applied with
+/// `git apply --3way`; hunks that conflict are left marked in the files
+/// and listed in `conflicts`. Nothing is committed.
+pub async fn patch_between_worktrees(
+    app: &AppHandle,
+    src_session: u32,
+    dest_session: u32,
+    paths: Vec<String>,
+) -> Result<PatchHandoff, AppError> {
+    let (src, dest) = sessions_of(app, src_session, dest_session)?;
+    if let Some((path, e)) = paths.iter().find_map(|p| validate_path(p).err().map(|e| (p, e))) {
+        return Err(AppError::invalid_argument(format!("{path}: {e}")).with("path", path));
+    }
+    let (src_root, dest_root) = (worktree_of(&src), worktree_of(&dest));
+    let base_ref = harvest::base_ref_of(app, &src, &src_root).await?;
+    let patch = Git::new(&src_root).full_patch(&base_ref, &paths).await?;
+    let files = patched_files(&patch);
+    if files.is_empty() {
+        return Err(AppError::not_found(format!("Session {src_session} has no changes to hand off"))
+            .with("session_id", src_session));
+    }
+
+    // A fresh file with a random name, so nothing planted in the shared
+    // temp dir is written through; removed when dropped
+    let patch_file = tokio::task::spawn_blocking(move || -> io::Result<tempfile::NamedTempFile> {
+        let mut file = tempfile::Builder::new()
+            .prefix("maestro-handoff-")
+            .suffix(".patch")
+            .tempfile()?;
+        file.write_all(patch.as_bytes())?;
+        Ok(file)
+    })
+    .await
+    .map_err(|e| AppError::new(ErrorCode::Failed, format!("Patch task failed: {e}")))??;
+    let dest_git = Git::new(&dest_root);
+    let applied = dest_git.apply_patch(patch_file.path()).await;
+    drop(patch_file);
+
+    let conflicts = match applied {
+        Ok(()) => Vec::new(),
+        Err(e) => {
+            let conflicts = dest_git.conflicted_files().await.unwrap_or_default();
+            if conflicts.is_empty() {
+                return Err(AppError::from(e).with("session_id", dest_session));
+            }
+            conflicts
+        }
+    };
+    log::info!(
+        "Applied {} changed files from session {src_session} to session {dest_session} ({} conflicts)",
+        files.len(),
+        conflicts.len()
+    );
+    Ok(PatchHandoff {
+        base_ref,
+        files,
+        conflicts,
+    })
+}
+
+/// Returns the paths a patch changes, from its `diff --git` headers (the
+/// new path, for renames).
+fn patched_files(patch: &str) -> Vec<String> {
+    patch
+        .lines()
+        .filter_map(|line| line.strip_prefix("diff --git a/"))
+        .filter_map(|paths| paths.rsplit_once(" b/").map(|(_, new)| new.to_string()))
+        .collect()
+}
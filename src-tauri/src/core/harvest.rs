@@ -0,0 +1,230 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::clock;
+use super::repo_config;
+use super::session_manager::{SessionConfig, SessionListener, SessionStatus, StatusTransition};
+use super::storage;
+use super::test_runner::{self, TestRun};
+use super::worktree_manager::WorktreeManager;
+use crate::git::{CommitInfo, DiffSummary, Git};
+
+/// Current on-disk format of a result file.
+const RESULT_VERSION: u32 = 1;
+
+/// Longest patch kept in a result, in bytes.
+const MAX_PATCH_BYTES: usize = 1024 * 1024;
+
+/// Most commits listed in a result.
+const MAX_COMMITS: usize = 200;
+
+/// What a session produced, collected when it reached `Done`.
+/// `harvested_at` is a Unix epoch timestamp.
+///
+/// `diff` and `patch` compare the worktree with the point its branch left
+/// `base_ref`; `commits` are the branch's commits since then, newest first.
+/// Parts that could not be collected are `None` or empty, with the reason
+/// in `errors`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SessionResult {
+    pub session_id: u32,
+    pub harvested_at: i64,
+    pub branch: Option<String>,
+    pub base_ref: Option<String>,
+    pub diff: Option<DiffSummary>,
+    pub patch: Option<String>,
+    pub patch_truncated: bool,
+    pub commits: Vec<CommitInfo>,
+    pub test: Option<TestRun>,
+    pub cost_usd: Option<f64>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ResultFile {
+    version: u32,
+    result: SessionResult,
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    dir: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+/// Collects a result bundle for every session that reaches `Done`.
+///
+/// Registered as a `SessionListener`. Harvesting runs in the background and
+/// ends by saving the result under `results/` in the data dir and emitting
+/// it as `session-result-{id}`. A session that reaches `Done` again is
+/// harvested again. The file is deleted with the session. Cheap to clone,
+/// so the same harvester is both a listener and Tauri managed state.
+#[derive(Clone)]
+pub struct ResultHarvester {
+    inner: Arc<Inner>,
+}
+
+impl ResultHarvester {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                dir: storage::data_dir().join("results"),
+                write_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    fn path(&self, session_id: u32) -> PathBuf {
+        self.inner.dir.join(format!("{session_id}.json"))
+    }
+
+    /// Returns the session's last harvested result, `None` if it never
+    /// reached `Done`.
+    pub fn get(&self, session_id: u32) -> io::Result<Option<SessionResult>> {
+        Ok(storage::load_json::<ResultFile>(&self.path(session_id))?.map(|file| file.result))
+    }
+
+    /// Collects, saves, and emits the session's result.
+    pub async fn harvest(&self, session: &SessionConfig) -> SessionResult {
+        let result = collect(&self.inner.app_handle, session).await;
+        let file = ResultFile {
+            version: RESULT_VERSION,
+            result,
+        };
+        {
+            let _guard = self.lock();
+            if let Err(e) = storage::save_json(&self.path(session.id), &file) {
+                log::error!("Failed to save result of session {}: {e}", session.id);
+            }
+        }
+        log::info!("Harvested result of session {}", session.id);
+        let _ = self
+            .inner
+            .app_handle
+            .emit(&format!("session-result-{}", session.id), &file.result);
+        file.result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        match self.inner.write_lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl SessionListener for ResultHarvester {
+    fn on_status_changed(&self, session: &SessionConfig, _transition: &StatusTransition) {
+        if session.status != SessionStatus::Done {
+            return;
+        }
+        let harvester = self.clone();
+        let session = session.clone();
+        tauri::async_runtime::spawn(async move {
+            harvester.harvest(&session).await;
+        });
+    }
+
+    fn on_removed(&self, session: &SessionConfig) {
+        let path = self.path(session.id);
+        let _guard = self.lock();
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("Failed to delete result {}: {e}", path.display()),
+        }
+    }
+}
+
+async fn collect(app: &AppHandle, session: &SessionConfig) -> SessionResult {
+    let mut result = SessionResult {
+        session_id: session.id,
+        harvested_at: clock::now_secs(),
+        branch: session.branch.clone(),
+        base_ref: None,
+        diff: None,
+        patch: None,
+        patch_truncated: false,
+        commits: Vec::new(),
+        test: None,
+        cost_usd: session.cost_usd,
+        errors: Vec::new(),
+    };
+    let Some(wt) = session.worktree_path.as_deref().map(Path::new) else {
+        result.errors.push("Session has no worktree".to_string());
+        return result;
+    };
+
+    match base_ref_of(app, session, wt).await {
+        Ok(base_ref) => {
+            let git = Git::new(wt);
+            match git.diff_against(&base_ref).await {
+                Ok(diff) => result.diff = Some(diff),
+                Err(e) => result.errors.push(format!("Failed to diff against {base_ref}: {e}")),
+            }
+            match git.diff_patch(&base_ref).await {
+                Ok(patch) => {
+                    result.patch_truncated = patch.len() > MAX_PATCH_BYTES;
+                    result.patch = Some(truncate_end(patch, MAX_PATCH_BYTES));
+                }
+                Err(e) => result.errors.push(format!("Failed to read patch: {e}")),
+            }
+            match git.commits_since(&base_ref, MAX_COMMITS).await {
+                Ok(commits) => result.commits = commits,
+                Err(e) => result.errors.push(format!("Failed to list commits: {e}")),
+            }
+            result.base_ref = Some(base_ref);
+        }
+        Err(e) => result.errors.push(e),
+    }
+
+    if let Some(command) = session.profile.test_command.as_deref() {
+        if !command.trim().is_empty() {
+            match test_runner::run(command, wt, session.profile.shell_env(), |_| {}).await {
+                Ok(test) => result.test = Some(test),
+                Err(e) => result.errors.push(format!("Failed to run test command: {e}")),
+            }
+        }
+    }
+    for e in &result.errors {
+        log::warn!("Result of session {}: {e}", session.id);
+    }
+    result
+}
+
+/// The ref the session's branch was created from, falling back to the
+/// repository's base branch (see `repo_config::base_branch`).
+pub(super) async fn base_ref_of(app: &AppHandle, session: &SessionConfig, wt: &Path) -> Result<String, String> {
+    let worktrees = app.state::<WorktreeManager>();
+    if let Some(base_ref) = worktrees.base_ref_of(wt) {
+        return Ok(base_ref);
+    }
+    let repo_path = match session.repo() {
+        Some(repo) => repo.to_path_buf(),
+        None => worktrees
+            .repo_of(wt)
+            .await
+            .ok_or_else(|| format!("Cannot determine the repository of {}", wt.display()))?,
+    };
+    repo_config::base_branch(&repo_path)
+        .await
+        .map_err(|e| format!("Cannot determine the base branch: {e}"))
+}
+
+/// Keeps the first `max` bytes of `text`, cut at a character boundary.
+fn truncate_end(mut text: String, max: usize) -> String {
+    if text.len() > max {
+        let mut end = max;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
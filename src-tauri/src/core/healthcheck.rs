@@ -0,0 +1,265 @@
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+use super::agent_launcher::AgentLauncher;
+use super::worktree_manager;
+
+/// Event emitted with the `HealthReport` of the check run at startup.
+pub const HEALTHCHECK_EVENT: &str = "healthcheck-report";
+
+/// Longest a tool may take to print its version.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Free space in the worktree base dir below which a warning is reported.
+const LOW_DISK_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Free space in the worktree base dir below which an error is reported.
+const CRITICAL_DISK_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Outcome of one check, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// One check of `run_healthcheck`. `name` identifies it (`git`,
+/// `agent:claude`, ...); `detail` is the version found or what is wrong.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl HealthCheck {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// All checks of `run_healthcheck`; `status` is the worst of them.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HealthReport {
+    pub status: CheckStatus,
+    pub checks: Vec<HealthCheck>,
+}
+
+/// Checks what Maestro needs from the system: git and a git identity, a
+/// forge CLI (`gh` or `glab`) for pull requests, the CLI of every agent with
+/// a launch command, a usable `$SHELL`, free disk space where worktrees are
+/// created, and permission to show notifications. Missing git is an error;
+/// the rest only limit some features and are warnings.
+pub async fn run_healthcheck(app: &AppHandle) -> HealthReport {
+    let mut checks = vec![check_git().await, check_git_identity().await, check_forge_cli().await];
+    let launcher = app.state::<AgentLauncher>();
+    checks.extend(join_all(agent_programs(&launcher).into_iter().map(check_agent)).await);
+    checks.push(check_shell());
+    checks.push(check_disk_space());
+    checks.push(check_notifications(app));
+    let status = checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Ok);
+    HealthReport { status, checks }
+}
+
+/// Runs the health check in the background at startup, logging every
+/// problem and emitting the report on `healthcheck-report`.
+pub fn spawn_startup_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let report = run_healthcheck(&app).await;
+        for check in &report.checks {
+            match check.status {
+                CheckStatus::Ok => log::info!("Health check {}: {}", check.name, check.detail),
+                CheckStatus::Warning => log::warn!("Health check {}: {}", check.name, check.detail),
+                CheckStatus::Error => log::error!("Health check {}: {}", check.name, check.detail),
+            }
+        }
+        let _ = app.emit(HEALTHCHECK_EVENT, &report);
+    });
+}
+
+async fn check_git() -> HealthCheck {
+    match first_line("git", &["--version"]).await {
+        Ok(version) => HealthCheck::new("git", CheckStatus::Ok, version),
+        Err(e) => HealthCheck::new("git", CheckStatus::Error, format!("{e}. Git operations will fail.")),
+    }
+}
+
+async fn check_git_identity() -> HealthCheck {
+    // No scope flag: the effective value, from the system or global config
+    let name = first_line("git", &["config", "user.name"]).await;
+    let email = first_line("git", &["config", "user.email"]).await;
+    match (name, email) {
+        (Ok(name), Ok(email)) if !name.is_empty() && !email.is_empty() => {
+            HealthCheck::new("git_identity", CheckStatus::Ok, format!("{name} <{email}>"))
+        }
+        _ => HealthCheck::new(
+            "git_identity",
+            CheckStatus::Warning,
+            "user.name or user.email is not set; commits fail unless each repo sets them",
+        ),
+    }
+}
+
+async fn check_forge_cli() -> HealthCheck {
+    let mut found = Vec::new();
+    for program in ["gh", "glab"] {
+        if let Ok(version) = first_line(program, &["--version"]).await {
+            found.push(version);
+        }
+    }
+    if found.is_empty() {
+        HealthCheck::new(
+            "forge_cli",
+            CheckStatus::Warning,
+            "Neither gh nor glab is installed; pull request features are unavailable",
+        )
+    } else {
+        HealthCheck::new("forge_cli", CheckStatus::Ok, found.join("; "))
+    }
+}
+
+/// Returns `(agent name, program)` for every agent with a launch command:
+/// the first word that is not a variable assignment of each built-in
+/// template and each custom agent's command.
+fn agent_programs(launcher: &AgentLauncher) -> Vec<(String, String)> {
+    let commands = launcher.commands();
+    let mut agents: Vec<(String, String)> = [
+        ("claude", commands.claude),
+        ("gemini", commands.gemini),
+        ("codex", commands.codex),
+    ]
+    .into_iter()
+    .map(|(name, template)| (name.to_string(), template))
+    .collect();
+    agents.extend(
+        launcher
+            .custom_agents()
+            .into_iter()
+            .map(|agent| (agent.name, agent.command)),
+    );
+    agents
+        .into_iter()
+        .filter_map(|(name, command)| {
+            let program = command.split_whitespace().find(|word| !word.contains('='))?.to_string();
+            Some((name, program))
+        })
+        .collect()
+}
+
+async fn check_agent((name, program): (String, String)) -> HealthCheck {
+    let check = format!("agent:{name}");
+    match first_line(&program, &["--version"]).await {
+        Ok(version) => HealthCheck::new(check, CheckStatus::Ok, version),
+        // Agents are launched from a login shell, whose PATH may find them
+        Err(e) => HealthCheck::new(check, CheckStatus::Warning, format!("{e} (on Maestro's PATH)")),
+    }
+}
+
+fn check_shell() -> HealthCheck {
+    let Ok(shell) = std::env::var("SHELL") else {
+        return HealthCheck::new("shell", CheckStatus::Warning, "$SHELL is not set; terminals use /bin/sh");
+    };
+    let path = Path::new(&shell);
+    let executable = std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0);
+    if !path.is_absolute() || !executable {
+        return HealthCheck::new(
+            "shell",
+            CheckStatus::Error,
+            format!("$SHELL ({shell}) is not an executable file; terminals cannot start"),
+        );
+    }
+    let listed = std::fs::read_to_string("/etc/shells")
+        .map(|shells| shells.lines().any(|line| line.trim() == shell))
+        .unwrap_or(true);
+    if listed {
+        HealthCheck::new("shell", CheckStatus::Ok, shell)
+    } else {
+        HealthCheck::new("shell", CheckStatus::Warning, format!("{shell} is not listed in /etc/shells"))
+    }
+}
+
+fn check_disk_space() -> HealthCheck {
+    let base = worktree_manager::worktree_base_dir();
+    // The base dir is created with the first worktree
+    let Some(existing) = base.ancestors().find(|dir| dir.exists()) else {
+        return HealthCheck::new("disk_space", CheckStatus::Warning, format!("{} does not exist", base.display()));
+    };
+    let free = match free_bytes(existing) {
+        Ok(free) => free,
+        Err(e) => {
+            return HealthCheck::new(
+                "disk_space",
+                CheckStatus::Warning,
+                format!("Cannot read free space of {}: {e}", existing.display()),
+            )
+        }
+    };
+    let status = if free < CRITICAL_DISK_BYTES {
+        CheckStatus::Error
+    } else if free < LOW_DISK_BYTES {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    };
+    let detail = format!("{:.1} GiB free in {}", free as f64 / (1024.0 * 1024.0 * 1024.0), base.display());
+    HealthCheck::new("disk_space", status, detail)
+}
+
+fn free_bytes(path: &Path) -> io::Result<u64> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+fn check_notifications(app: &AppHandle) -> HealthCheck {
+    match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => HealthCheck::new("notifications", CheckStatus::Ok, "Granted"),
+        Ok(state) => HealthCheck::new(
+            "notifications",
+            CheckStatus::Warning,
+            format!("Permission is {state:?}; session alerts are not shown"),
+        ),
+        Err(e) => HealthCheck::new("notifications", CheckStatus::Warning, format!("Cannot read permission: {e}")),
+    }
+}
+
+/// First line of what a program prints, trimmed.
+async fn first_line(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(VERSION_TIMEOUT, output).await {
+        Ok(Ok(output)) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string()),
+        Ok(Ok(output)) => Err(format!("{program} exited with {}", output.status)),
+        Ok(Err(e)) if e.kind() == io::ErrorKind::NotFound => Err(format!("{program} is not installed")),
+        Ok(Err(e)) => Err(format!("Failed to run {program}: {e}")),
+        Err(_) => Err(format!("{program} did not answer within {}s", VERSION_TIMEOUT.as_secs())),
+    }
+}
@@ -0,0 +1,234 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use super::pull_request::forge_cli;
+use super::remote_control::{self, CreateSessionArgs};
+use super::repo_config;
+use super::session_manager::{SessionConfig, SessionManager};
+use crate::git::Git;
+
+/// Longest title slug in a branch derived from an issue.
+const MAX_SLUG_CHARS: usize = 40;
+
+/// Where an issue lives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueTracker {
+    Github,
+    Gitlab,
+}
+
+/// The issue a session was created from, kept so the pull request for its
+/// branch can refer to it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct IssueLink {
+    pub tracker: IssueTracker,
+    pub number: u64,
+    pub url: String,
+}
+
+impl IssueLink {
+    /// Returns the `owner/repo#N` reference GitHub resolves from any
+    /// repository, or `None` for GitLab issues.
+    pub fn github_reference(&self) -> Option<String> {
+        if self.tracker != IssueTracker::Github {
+            return None;
+        }
+        let path = self.url.split_once("://")?.1;
+        let mut parts = path.split('/').skip(1);
+        let (owner, repo) = (parts.next()?, parts.next()?);
+        Some(format!("{owner}/{repo}#{}", self.number))
+    }
+}
+
+/// An issue fetched from a tracker.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct Issue {
+    pub tracker: IssueTracker,
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct GhIssue {
+    number: u64,
+    title: String,
+    body: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct GlabIssue {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+    web_url: String,
+}
+
+/// Fetches an issue with the tracker's CLI (`gh` or `glab`, installed and
+/// logged in). `issue_ref` is an issue URL, whose host picks the tracker,
+/// or a number (`42` or `#42`) of the repository's own tracker, picked by
+/// the host of its `origin` remote.
+pub async fn fetch_issue(repo_path: &Path, issue_ref: &str) -> Result<Issue, String> {
+    let issue_ref = issue_ref.trim();
+    let tracker = if issue_ref.starts_with("https://") || issue_ref.starts_with("http://") {
+        tracker_of(issue_ref)
+    } else {
+        issue_ref
+            .trim_start_matches('#')
+            .parse::<u64>()
+            .map_err(|_| format!("'{issue_ref}' is neither an issue number nor an issue URL"))?;
+        let origin = Git::new(repo_path)
+            .remote_url("origin")
+            .await
+            .map_err(|e| e.to_string())?;
+        tracker_of(&origin)
+    };
+    let number_or_url = issue_ref.trim_start_matches('#');
+
+    match tracker {
+        IssueTracker::Github => {
+            let args = ["issue", "view", number_or_url, "--json", "number,title,body,url"];
+            let issue: GhIssue = serde_json::from_str(&forge_cli("gh", repo_path, &args).await?)
+                .map_err(|e| format!("Unexpected issue data from gh: {e}"))?;
+            Ok(Issue {
+                tracker,
+                number: issue.number,
+                title: issue.title,
+                body: issue.body,
+                url: issue.url,
+            })
+        }
+        IssueTracker::Gitlab => {
+            let args = ["issue", "view", number_or_url, "--output", "json"];
+            let issue: GlabIssue = serde_json::from_str(&forge_cli("glab", repo_path, &args).await?)
+                .map_err(|e| format!("Unexpected issue data from glab: {e}"))?;
+            Ok(Issue {
+                tracker,
+                number: issue.iid,
+                title: issue.title,
+                body: issue.description.unwrap_or_default(),
+                url: issue.web_url,
+            })
+        }
+    }
+}
+
+/// Picks the tracker of an issue or remote URL by its host: GitLab for
+/// `gitlab.com` and hosts named `gitlab.<domain>`, else GitHub. Issue URLs
+/// of other self-hosted GitLab instances are recognized by their
+/// `/-/issues/` path.
+fn tracker_of(url: &str) -> IssueTracker {
+    let parsed = Url::parse(url).ok();
+    let host = match parsed.as_ref().and_then(Url::host_str) {
+        Some(host) => Some(host),
+        // scp-like SSH remotes: `git@gitlab.com:group/project.git`
+        None => url
+            .split_once('@')
+            .and_then(|(_, rest)| rest.split_once(':'))
+            .map(|(host, _)| host),
+    };
+    let gitlab_host = host.is_some_and(|host| {
+        let host = host.to_ascii_lowercase();
+        host == "gitlab.com" || host.starts_with("gitlab.")
+    });
+    let gitlab_path = parsed.as_ref().is_some_and(|url| url.path().contains("/-/issues/"));
+    if gitlab_host || gitlab_path {
+        IssueTracker::Gitlab
+    } else {
+        IssueTracker::Github
+    }
+}
+
+/// Returns `issue-<n>-<title-slug>`, the branch a session for the issue
+/// works on.
+fn branch_name(issue: &Issue) -> String {
+    let slug = slug(&issue.title);
+    if slug.is_empty() {
+        format!("issue-{}", issue.number)
+    } else {
+        format!("issue-{}-{slug}", issue.number)
+    }
+}
+
+/// Lowercases `text` and joins its runs of ASCII letters and digits with
+/// `-`, cut at a word boundary to at most `MAX_SLUG_CHARS`, for use in
+/// branch names. Empty if `text` has no letters or digits.
+pub fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.len() > MAX_SLUG_CHARS {
+        slug.truncate(MAX_SLUG_CHARS);
+        if let Some(cut) = slug.rfind('-') {
+            slug.truncate(cut);
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Creates a session for an issue, as `remote_control::create_session`
+/// does: on branch `issue-<n>-<title-slug>`, cut from `base_ref` (or the
+/// repository's default branch) unless it already exists, with the
+/// issue as the agent's first prompt. The session is titled after the
+/// issue and linked to it, so `pull_request::create_pr` can close it.
+pub async fn import_issue(
+    app: &AppHandle,
+    repo_path: &Path,
+    issue_ref: &str,
+    agent: Option<String>,
+    base_ref: Option<String>,
+) -> Result<SessionConfig, String> {
+    let issue = fetch_issue(repo_path, issue_ref).await?;
+    let branch = branch_name(&issue);
+    let git = Git::new(repo_path);
+    let base_ref = if git.branch_exists(&branch).await.map_err(|e| e.to_string())? {
+        None
+    } else {
+        match base_ref {
+            Some(base_ref) => Some(base_ref),
+            None => Some(repo_config::base_branch(repo_path).await.map_err(|e| e.to_string())?),
+        }
+    };
+    let prompt = format!(
+        "Resolve issue #{}: {}\n\n{}\n\n({})",
+        issue.number,
+        issue.title,
+        issue.body.trim(),
+        issue.url
+    );
+    let args = CreateSessionArgs {
+        repo_path: repo_path.to_string_lossy().into_owned(),
+        branch,
+        base_ref,
+        agent,
+        prompt: Some(prompt),
+    };
+    let session = remote_control::start_session(app, args).await?;
+
+    let link = IssueLink {
+        tracker: issue.tracker,
+        number: issue.number,
+        url: issue.url,
+    };
+    let title = format!("#{} {}", issue.number, issue.title);
+    log::info!("Session {}: imported issue {}", session.id, link.url);
+    Ok(app
+        .state::<SessionManager>()
+        .modify(session.id, |session| {
+            session.title = Some(title);
+            session.issue = Some(link);
+        })
+        .unwrap_or(session))
+}
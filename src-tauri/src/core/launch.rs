@@ -0,0 +1,197 @@
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager, WindowEvent};
+
+use super::deep_link::{self, DeepLink};
+
+/// Event emitted with the `LaunchRequest` of every later launch of Maestro,
+/// which exits after handing its arguments to the running instance.
+pub const LAUNCH_REQUEST_EVENT: &str = "launch-request";
+
+/// Label of the window brought to the front by a later launch or the tray.
+const MAIN_WINDOW: &str = "main";
+
+/// Command-line arguments sorted into repositories (existing directories,
+/// resolved against the working directory of the launch) and deep links,
+/// in the order given. Flags and paths that are not directories are
+/// ignored.
+#[derive(Debug, Clone, Default)]
+struct LaunchArgs {
+    repo_paths: Vec<String>,
+    deep_links: Vec<String>,
+}
+
+impl LaunchArgs {
+    /// Parses command-line arguments, without the program name.
+    fn parse(args: &[String], cwd: &Path) -> Self {
+        let mut parsed = Self::default();
+        let prefix = format!("{}://", deep_link::SCHEME);
+        for arg in args {
+            if arg.starts_with('-') {
+                continue;
+            }
+            if arg.get(..prefix.len()).is_some_and(|p| p.eq_ignore_ascii_case(&prefix)) {
+                parsed.deep_links.push(arg.clone());
+                continue;
+            }
+            match std::fs::canonicalize(cwd.join(arg)) {
+                Ok(path) if path.is_dir() => parsed.repo_paths.push(path.to_string_lossy().into_owned()),
+                _ => log::warn!("Ignoring launch argument {arg}: not a directory"),
+            }
+        }
+        parsed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.repo_paths.is_empty() && self.deep_links.is_empty()
+    }
+
+    /// Carries out the deep links, dropping (and logging) those rejected.
+    async fn handle(self, app: &AppHandle) -> LaunchRequest {
+        let mut links = Vec::new();
+        for link in &self.deep_links {
+            match deep_link::handle(app, link).await {
+                Ok(handled) => links.push(handled),
+                Err(e) => log::warn!("Ignoring deep link {link}: {e}"),
+            }
+        }
+        LaunchRequest {
+            repo_paths: self.repo_paths,
+            links,
+        }
+    }
+}
+
+/// What a launch of Maestro asked the frontend to open: repositories given
+/// on its command line, and what its `maestro://` links left to show once
+/// the backend carried them out.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct LaunchRequest {
+    pub repo_paths: Vec<String>,
+    pub links: Vec<DeepLink>,
+}
+
+impl LaunchRequest {
+    pub fn is_empty(&self) -> bool {
+        self.repo_paths.is_empty() && self.links.is_empty()
+    }
+}
+
+/// Where the request of the process's own command line is.
+enum Initial {
+    /// Being handled, and not asked for yet.
+    Pending,
+    /// Handled, waiting for the frontend to take it.
+    Ready(LaunchRequest),
+    /// Asked for; a request handled after that is emitted instead.
+    Taken,
+}
+
+/// Keeps the request of this process's own command line until the frontend
+/// takes it, since it is handled before any window listens for
+/// `launch-request`. Placed in Tauri managed state by the app only.
+pub struct LaunchRequests {
+    args: Mutex<Option<LaunchArgs>>,
+    initial: Mutex<Initial>,
+}
+
+impl LaunchRequests {
+    /// Parses the arguments the process was started with.
+    pub fn from_env() -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let args = LaunchArgs::parse(&args, &cwd);
+        Self {
+            args: Mutex::new((!args.is_empty()).then_some(args)),
+            initial: Mutex::new(Initial::Pending),
+        }
+    }
+
+    /// Carries out the deep links of the process's command line in the
+    /// background. Call once from setup, after the task queue is managed.
+    /// If the frontend asked for the request before it was ready, it is
+    /// emitted on `launch-request` instead.
+    pub fn handle(&self, app: &AppHandle) {
+        let Some(args) = lock(&self.args).take() else {
+            return;
+        };
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let request = args.handle(&app).await;
+            if request.is_empty() {
+                return;
+            }
+            let requests = app.state::<LaunchRequests>();
+            let mut initial = lock(&requests.initial);
+            match *initial {
+                Initial::Taken => {
+                    let _ = app.emit(LAUNCH_REQUEST_EVENT, &request);
+                }
+                _ => *initial = Initial::Ready(request),
+            }
+        });
+    }
+
+    /// Returns the request of the process's command line the first time it
+    /// is called, if it is ready, then `None`.
+    pub fn take(&self) -> Option<LaunchRequest> {
+        match std::mem::replace(&mut *lock(&self.initial), Initial::Taken) {
+            Initial::Ready(request) => Some(request),
+            Initial::Pending | Initial::Taken => None,
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Unminimizes, shows, and focuses the main window.
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Makes closing the main window hide it instead, so Maestro keeps running
+/// in the tray until quit from there.
+pub fn hide_main_window_on_close(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    let hidden = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { api, .. } = event {
+            api.prevent_close();
+            let _ = hidden.hide();
+        }
+    });
+}
+
+/// Handles a later launch of Maestro, whose arguments (`args`, starting
+/// with the program name) and working directory the single-instance plugin
+/// forwards before that process exits: brings the main window to the front,
+/// carries out its deep links, and emits what it asked to open on
+/// `launch-request`.
+pub fn forward(app: &AppHandle, args: Vec<String>, cwd: String) {
+    show_main_window(app);
+    let args = LaunchArgs::parse(args.get(1..).unwrap_or_default(), Path::new(&cwd));
+    if args.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let request = args.handle(&app).await;
+        if !request.is_empty() {
+            let _ = app.emit(LAUNCH_REQUEST_EVENT, &request);
+        }
+    });
+}
@@ -0,0 +1,334 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_log::{AsLog, NormalizeEvent};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use super::clock;
+use super::metrics;
+use super::storage;
+
+/// Log files kept in the log dir, one per day; older ones are deleted.
+const MAX_LOG_FILES: usize = 7;
+
+/// Records kept in memory for `recent`.
+const RECENT_CAPACITY: usize = 5_000;
+
+/// Target of the spans tauri opens around each IPC request.
+const IPC_TARGET: &str = "tauri::ipc::protocol";
+
+/// Environment variable that, set to a level name, overrides the
+/// configured level.
+const LEVEL_ENV: &str = "RUST_LOG";
+
+/// Severity of a log record, most severe first. Filtering by a level keeps
+/// it and everything more severe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+
+    /// Parses a level name such as `debug`, ignoring case.
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(LogLevel::Error),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "trace" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+
+    fn of(level: &Level) -> Self {
+        match *level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            Level::TRACE => LogLevel::Trace,
+        }
+    }
+}
+
+/// A log record as kept for `recent`. `at_ms` is Unix time in
+/// milliseconds; `message` includes any structured fields as `key=value`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LogRecord {
+    pub at_ms: i64,
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+}
+
+struct Logging {
+    level: reload::Handle<LevelFilter, Registry>,
+    /// Level set through `RUST_LOG`, which `set_level` leaves in place.
+    env_level: Option<LogLevel>,
+    /// Flushes the file writer when dropped; held for the process lifetime.
+    _file_guard: Option<WorkerGuard>,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+
+static RECENT: Mutex<VecDeque<LogRecord>> = Mutex::new(VecDeque::new());
+
+/// Directory holding the rotated log files (`maestro.YYYY-MM-DD.log`).
+pub fn log_dir() -> PathBuf {
+    storage::data_dir().join("logs")
+}
+
+/// Installs the global logger at `Info`, or at the level `RUST_LOG` names
+/// (only a plain level such as `debug` is understood): records from both
+/// `log` and `tracing` go to stderr, to a log file rotated daily under
+/// `log_dir`, and to the in-memory buffer read by `recent`. Tauri's IPC
+/// spans are read regardless of the level to time commands in `metrics`.
+/// Does nothing if called again. A log dir that cannot be created only
+/// disables the file.
+pub fn init() {
+    if LOGGING.get().is_some() {
+        return;
+    }
+    let env_value = std::env::var(LEVEL_ENV).ok().filter(|value| !value.trim().is_empty());
+    let env_level = env_value.as_deref().and_then(LogLevel::parse);
+    let initial = env_level.unwrap_or_default();
+    let (filter, level) = reload::Layer::new(initial.filter());
+    let dir = log_dir();
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("maestro")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir);
+    let (file_layer, file_guard, file_error) = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer().with_ansi(false).with_writer(writer);
+            (Some(layer), Some(guard), None)
+        }
+        Err(e) => (None, None, Some(e)),
+    };
+    let output = fmt::layer()
+        .with_writer(std::io::stderr)
+        .and_then(file_layer)
+        .and_then(RecentLayer)
+        .with_filter(filter);
+    let ipc = CommandTimingLayer.with_filter(Targets::new().with_target(IPC_TARGET, LevelFilter::TRACE));
+    let installed = tracing_subscriber::registry().with(output).with(ipc).try_init();
+    if let Err(e) = installed {
+        eprintln!("Failed to install the logger: {e}");
+        return;
+    }
+    // The IPC filter raised the maximum `log` records are checked against
+    log::set_max_level(initial.filter().as_log());
+    let _ = LOGGING.set(Logging {
+        level,
+        env_level,
+        _file_guard: file_guard,
+    });
+    if let Some(e) = file_error {
+        log::error!("Failed to open the log dir {}: {e}; logging to stderr only", dir.display());
+    }
+    match (env_value, env_level) {
+        (Some(_), Some(level)) => log::info!("Log level set to {level:?} by {LEVEL_ENV}"),
+        (Some(value), None) => log::warn!("Ignoring {LEVEL_ENV}={value}: not a log level"),
+        (None, _) => {}
+    }
+}
+
+/// Changes the most verbose level recorded from now on, unless `RUST_LOG`
+/// set one.
+pub fn set_level(level: LogLevel) -> Result<(), String> {
+    let logging = LOGGING.get().ok_or("Logging is not initialized")?;
+    if let Some(env_level) = logging.env_level {
+        log::info!("Keeping log level {env_level:?} from {LEVEL_ENV} over {level:?}");
+        return Ok(());
+    }
+    logging
+        .level
+        .reload(level.filter())
+        .map_err(|e| format!("Failed to change the log level: {e}"))?;
+    // `log` macros check their own maximum before reaching the subscriber
+    log::set_max_level(level.filter().as_log());
+    log::info!("Log level set to {level:?}");
+    Ok(())
+}
+
+/// Returns the last `limit` records kept in memory at `level` or more
+/// severe, oldest first. Records from before the last
+/// `RECENT_CAPACITY` are only in the log files.
+pub fn recent(level: LogLevel, limit: usize) -> Vec<LogRecord> {
+    let recent = match RECENT.lock() {
+        Ok(recent) => recent,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let mut records: Vec<LogRecord> = recent
+        .iter()
+        .rev()
+        .filter(|record| record.level <= level)
+        .take(limit)
+        .cloned()
+        .collect();
+    records.reverse();
+    records
+}
+
+/// Like `recent` with every level, but returns nothing rather than wait if
+/// the buffer is locked, for use while panicking.
+pub fn try_recent(limit: usize) -> Vec<LogRecord> {
+    let Ok(recent) = RECENT.try_lock() else {
+        return Vec::new();
+    };
+    let skip = recent.len().saturating_sub(limit);
+    recent.iter().skip(skip).cloned().collect()
+}
+
+/// Keeps the last `RECENT_CAPACITY` records in `RECENT`.
+struct RecentLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        // Records from the `log` crate carry their real target only here
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let record = LogRecord {
+            at_ms: clock::now_millis() as i64,
+            level: LogLevel::of(metadata.level()),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+        };
+        let mut recent = match RECENT.lock() {
+            Ok(recent) => recent,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if recent.len() >= RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(record);
+    }
+}
+
+/// Times each IPC request in `metrics`, from tauri's `ipc::request::handle`
+/// span, which lives until the response is sent, to its close. A request
+/// fails when its `ipc::request::response` span carries an `error`.
+struct CommandTimingLayer;
+
+/// Kept in the extensions of an `ipc::request::handle` span.
+struct CommandTiming {
+    command: String,
+    started: Instant,
+    failed: bool,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CommandTimingLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        match attrs.metadata().name() {
+            "ipc::request::handle" => {
+                let mut visitor = CommandVisitor::default();
+                attrs.record(&mut visitor);
+                span.extensions_mut().insert(CommandTiming {
+                    command: visitor.command,
+                    started: Instant::now(),
+                    failed: false,
+                });
+            }
+            "ipc::request::response" if attrs.fields().field("error").is_some() => {
+                for ancestor in span.scope().skip(1) {
+                    if let Some(timing) = ancestor.extensions_mut().get_mut::<CommandTiming>() {
+                        timing.failed = true;
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let Some(timing) = extensions.get::<CommandTiming>() {
+            metrics::registry().command(&timing.command, timing.started.elapsed(), timing.failed);
+        }
+    }
+}
+
+/// Reads the `cmd` field of an `ipc::request::handle` span.
+#[derive(Default)]
+struct CommandVisitor {
+    command: String,
+}
+
+impl Visit for CommandVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "cmd" {
+            self.command = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "cmd" {
+            self.command = format!("{value:?}");
+        }
+    }
+}
+
+/// Formats an event's `message` followed by its other fields.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let name = field.name();
+        // Fields `tracing-log` adds to describe the `log` call site
+        if name.starts_with("log.") {
+            return;
+        }
+        if name == "message" {
+            let fields = std::mem::take(&mut self.message);
+            let _ = write!(self.message, "{value:?}{fields}");
+        } else {
+            let _ = write!(self.message, " {name}={value:?}");
+        }
+    }
+}
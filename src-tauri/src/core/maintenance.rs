@@ -0,0 +1,146 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+use super::audit::AuditLog;
+use super::clock;
+use super::worktree_manager::{PruneOutcome, WorktreeManager};
+
+/// Default time between automatic prune passes.
+pub const DEFAULT_PRUNE_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Event emitted after every prune pass, scheduled or manual, that was not
+/// a dry run.
+pub const WORKTREES_PRUNED_EVENT: &str = "worktrees-pruned";
+
+/// Outcome of pruning a single repository: what was removed, or `error`
+/// if the prune failed. `managed_worktrees` is the number left afterwards.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RepoPruneResult {
+    pub repo_path: String,
+    pub managed_worktrees: usize,
+    pub pruned: Option<PruneOutcome>,
+    pub error: Option<String>,
+}
+
+/// Payload of the `worktrees-pruned` event. `ran_at` is a Unix epoch timestamp.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PruneReport {
+    pub ran_at: i64,
+    pub dry_run: bool,
+    pub repos: Vec<RepoPruneResult>,
+}
+
+/// Interval configuration for the background prune task.
+///
+/// Placed in Tauri managed state. An interval of 0 disables scheduled
+/// pruning. Changing the interval wakes the task so the new value takes
+/// effect immediately rather than after the old sleep finishes.
+pub struct PruneScheduler {
+    interval_secs: AtomicU64,
+    changed: Notify,
+}
+
+impl Default for PruneScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PruneScheduler {
+    /// Creates a scheduler with the default one-hour interval.
+    pub fn new() -> Self {
+        Self {
+            interval_secs: AtomicU64::new(DEFAULT_PRUNE_INTERVAL_SECS),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Returns the current interval in seconds (0 = disabled).
+    pub fn interval_secs(&self) -> u64 {
+        self.interval_secs.load(Ordering::Relaxed)
+    }
+
+    /// Sets a new interval in seconds (0 disables) and wakes the task.
+    pub fn set_interval_secs(&self, secs: u64) {
+        self.interval_secs.store(secs, Ordering::Relaxed);
+        self.changed.notify_one();
+    }
+}
+
+/// Prunes every known repository in turn and collects a report; with
+/// `dry_run`, only reports what would be removed.
+///
+/// Failures are recorded per repo and never stop the pass, so one broken
+/// repository cannot block cleanup of the others.
+pub async fn prune_all(manager: &WorktreeManager, dry_run: bool) -> PruneReport {
+    let ran_at = clock::now_secs();
+
+    let mut repos = Vec::new();
+    for repo in manager.known_repos().await {
+        let (pruned, error) = match manager.prune(&repo, dry_run).await {
+            Ok(outcome) => (Some(outcome), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let managed_worktrees = manager
+            .list_managed(&repo)
+            .await
+            .map(|wts| wts.len())
+            .unwrap_or(0);
+        if let Some(ref e) = error {
+            log::warn!("Scheduled prune failed for {}: {e}", repo.display());
+        }
+        repos.push(RepoPruneResult {
+            repo_path: repo.to_string_lossy().into_owned(),
+            managed_worktrees,
+            pruned,
+            error,
+        });
+    }
+
+    PruneReport { ran_at, dry_run, repos }
+}
+
+/// Spawns the background task that runs `prune_all` every interval and
+/// emits `worktrees-pruned` with the report. Repos where something was
+/// removed, or the prune failed, are recorded in the audit log.
+///
+/// Reads `PruneScheduler` and `WorktreeManager` from managed state, so both
+/// must be registered before this is called from `setup`.
+pub fn spawn_prune_task(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let scheduler = app.state::<PruneScheduler>();
+            let secs = scheduler.interval_secs();
+            if secs == 0 {
+                scheduler.changed.notified().await;
+                continue;
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(secs)) => {}
+                _ = scheduler.changed.notified() => continue,
+            }
+
+            let report = prune_all(&app.state::<WorktreeManager>(), false).await;
+            log::info!("Scheduled prune covered {} repo(s)", report.repos.len());
+            let audit = app.state::<AuditLog>();
+            for repo in &report.repos {
+                let removed = repo.pruned.as_ref().is_some_and(|p| {
+                    !p.stale_refs.is_empty() || !p.orphaned_dirs.is_empty() || !p.failed.is_empty()
+                });
+                if !removed && repo.error.is_none() {
+                    continue;
+                }
+                let params = serde_json::json!({ "repo_path": repo.repo_path, "pruned": repo.pruned });
+                let result = repo.error.as_ref().map_or(Ok(()), Err);
+                audit.record_automation("scheduled_prune", "prune_worktrees", params, &result);
+            }
+            let _ = app.emit(WORKTREES_PRUNED_EVENT, report);
+        }
+    });
+}
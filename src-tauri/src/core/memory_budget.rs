@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::process_manager::ProcessManager;
+use super::scrollback::Scrollback;
+
+/// Event emitted with the `MemoryUsage` when output had to be trimmed to
+/// stay within the budget, at most once per `WARNING_INTERVAL`.
+pub const MEMORY_BUDGET_EVENT: &str = "memory-budget-exceeded";
+
+/// Minimum time between two `memory-budget-exceeded` events.
+const WARNING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Bytes of PTY output held in memory, against the budget.
+///
+/// `channel_bytes` is output read from the PTYs and not yet emitted to the
+/// frontend; `scrollback_bytes` is what `Scrollback` keeps. `trimmed_ptys`
+/// lists the PTYs whose scrollback was trimmed, oldest activity first
+/// (empty unless this comes with `memory-budget-exceeded`).
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MemoryUsage {
+    pub channel_bytes: usize,
+    pub scrollback_bytes: usize,
+    pub budget_bytes: usize,
+    pub trimmed_ptys: Vec<u32>,
+}
+
+/// Global cap on the memory held by PTY output across all sessions.
+///
+/// Checked by `Scrollback` after each chunk of output it records. When the
+/// output queued in the PTY channels and the scrollback buffers together
+/// exceed the budget, the scrollback of the least recently active PTYs is
+/// trimmed down to three quarters of it, so the check is not hit again on
+/// every chunk, and `memory-budget-exceeded` is emitted. Channel output
+/// cannot be trimmed; it drains as the frontend keeps up. A budget of 0
+/// means unlimited. Placed in Tauri managed state.
+pub struct MemoryBudget {
+    budget_bytes: AtomicUsize,
+    last_warning: Mutex<Option<Instant>>,
+}
+
+impl MemoryBudget {
+    pub fn new() -> Self {
+        Self {
+            budget_bytes: AtomicUsize::new(0),
+            last_warning: Mutex::new(None),
+        }
+    }
+
+    /// Sets the budget in MiB; 0 means unlimited.
+    pub fn set_budget_mb(&self, megabytes: usize) {
+        self.budget_bytes.store(megabytes.saturating_mul(1024 * 1024), Ordering::Relaxed);
+    }
+
+    /// Returns the bytes held right now.
+    pub fn usage(&self, app: &AppHandle) -> MemoryUsage {
+        MemoryUsage {
+            channel_bytes: app
+                .try_state::<ProcessManager>()
+                .map_or(0, |pm| pm.queued_output_bytes()),
+            scrollback_bytes: app.try_state::<Scrollback>().map_or(0, |s| s.held_bytes()),
+            budget_bytes: self.budget_bytes.load(Ordering::Relaxed),
+            trimmed_ptys: Vec::new(),
+        }
+    }
+
+    /// Trims scrollback if the output held exceeds the budget.
+    pub fn enforce(&self, app: &AppHandle) {
+        let budget = self.budget_bytes.load(Ordering::Relaxed);
+        if budget == 0 {
+            return;
+        }
+        let mut usage = self.usage(app);
+        if usage.channel_bytes + usage.scrollback_bytes <= budget {
+            return;
+        }
+        let Some(scrollback) = app.try_state::<Scrollback>() else {
+            return;
+        };
+        let target = (budget / 4 * 3).saturating_sub(usage.channel_bytes);
+        let held = usage.scrollback_bytes;
+        usage.trimmed_ptys = scrollback.trim_oldest(target);
+        usage.scrollback_bytes = scrollback.held_bytes();
+
+        let mut last_warning = lock(&self.last_warning);
+        if last_warning.is_some_and(|at| at.elapsed() < WARNING_INTERVAL) {
+            return;
+        }
+        *last_warning = Some(Instant::now());
+        drop(last_warning);
+        log::warn!(
+            "PTY output exceeded the memory budget of {budget} bytes ({} queued, {held} in scrollback); trimmed the scrollback of PTYs {:?}",
+            usage.channel_bytes,
+            usage.trimmed_ptys
+        );
+        let _ = app.emit(MEMORY_BUDGET_EVENT, &usage);
+    }
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
@@ -0,0 +1,242 @@
+use std::path::Path;
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::error::{AppError, ErrorCode};
+use super::repo_config::RepoConfig;
+use super::session_manager::SessionManager;
+use super::test_runner::{self, TestRun};
+use super::worktree_manager::WorktreeManager;
+use crate::git::{Git, GitError};
+
+/// Commit message of `auto_commit` when the config gives none.
+const DEFAULT_AUTO_COMMIT_MESSAGE: &str = "Commit remaining changes before merging";
+
+/// Lines of a failed command's output quoted in its check's detail.
+const FAILURE_OUTPUT_LINES: usize = 20;
+
+/// A check of the merge gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GateCheckKind {
+    /// Neither checkout has uncommitted changes.
+    Clean,
+    /// The branch merges into the target without conflicts.
+    Conflicts,
+    /// The configured lint command succeeds.
+    Lint,
+    /// The test command succeeds.
+    Tests,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GateStatus {
+    Passed,
+    Failed,
+    /// Not configured, or not run because an earlier check failed.
+    Skipped,
+}
+
+/// Result of one check, with what it found.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct GateCheck {
+    pub check: GateCheckKind,
+    pub status: GateStatus,
+    pub detail: String,
+}
+
+/// Results of the merge gate, in the order the checks ran. `passed` is
+/// set if none failed. `head` is the branch commit that was checked.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MergeGateReport {
+    pub branch: String,
+    pub target_branch: String,
+    pub head: String,
+    pub passed: bool,
+    pub checks: Vec<GateCheck>,
+}
+
+impl MergeGateReport {
+    /// The `MergeGateFailed` error for a failed gate, with each failed
+    /// check's detail as context.
+    pub fn to_error(&self) -> AppError {
+        let failed: Vec<&GateCheck> = self.checks.iter().filter(|c| c.status == GateStatus::Failed).collect();
+        let names: Vec<String> = failed.iter().map(|c| check_name(c.check)).collect();
+        let mut error = AppError::new(
+            ErrorCode::MergeGateFailed,
+            format!("Merging {} into {} is blocked: {} failed", self.branch, self.target_branch, names.join(", ")),
+        );
+        for (check, name) in failed.iter().zip(&names) {
+            error = error.with(name, &check.detail);
+        }
+        error
+    }
+}
+
+fn check_name(check: GateCheckKind) -> String {
+    serde_json::to_value(check)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Runs the checks that must pass before the worktree at `wt_path` is
+/// merged into the branch checked out at `repo_path`, as configured in the
+/// repository's `[merge_gate]` (see `repo_config::MergeGateConfig`).
+///
+/// Both checkouts must be clean, unless `auto_commit` commits the
+/// worktree's changes, and the branch must merge without conflicts. Then
+/// the lint command and the tests run, if configured, and only if nothing
+/// failed before them, as they can take minutes. Both commands come from
+/// the main checkout's config only, never the worktree's, which the agent
+/// can edit. Test runs are recorded on the worktree's session like
+/// `run_tests`.
+pub async fn check(app: &AppHandle, repo_path: &Path, wt_path: &Path) -> Result<MergeGateReport, GitError> {
+    let wt = app.state::<WorktreeManager>().find_worktree(repo_path, wt_path).await?;
+    let branch = wt.branch.clone().ok_or_else(|| GitError::DetachedHead { path: wt.path.clone() })?;
+    let wt_path = Path::new(&wt.path);
+    let main = Git::new(repo_path);
+    let target_branch = main.current_branch().await?;
+    let config = RepoConfig::for_repo(repo_path).merge_gate;
+    let mut checks = Vec::new();
+
+    let main_dirty = main.uncommitted_count().await?;
+    let wt_dirty = Git::new(wt_path).uncommitted_count().await?;
+    let clean = if main_dirty > 0 {
+        failed(
+            GateCheckKind::Clean,
+            format!("The main checkout has {main_dirty} uncommitted changes"),
+        )
+    } else if wt_dirty == 0 {
+        passed(GateCheckKind::Clean, "No uncommitted changes".to_string())
+    } else if config.auto_commit {
+        let message = config
+            .auto_commit_message
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(DEFAULT_AUTO_COMMIT_MESSAGE);
+        match Git::new(wt_path).commit_all(message).await {
+            Ok(head) => passed(
+                GateCheckKind::Clean,
+                format!("Committed {wt_dirty} uncommitted changes as {}", &head[..head.len().min(7)]),
+            ),
+            Err(e) => failed(GateCheckKind::Clean, format!("Failed to commit {wt_dirty} changes: {e}")),
+        }
+    } else {
+        failed(
+            GateCheckKind::Clean,
+            format!("The worktree has {wt_dirty} uncommitted changes"),
+        )
+    };
+    checks.push(clean);
+    let head = main.resolve_commit(&branch).await?;
+
+    let preview = main.merge_preview(&branch, &target_branch).await?;
+    checks.push(if preview.conflicts {
+        failed(
+            GateCheckKind::Conflicts,
+            format!("Conflicts with {target_branch} in {}", preview.files.join(", ")),
+        )
+    } else {
+        passed(GateCheckKind::Conflicts, format!("Merges cleanly into {target_branch}"))
+    });
+
+    let lint = match config.lint_command.as_deref() {
+        None => skipped(GateCheckKind::Lint, "No lint command configured"),
+        Some(_) if any_failed(&checks) => skipped(GateCheckKind::Lint, "Not run: an earlier check failed"),
+        Some(command) => {
+            let env = app
+                .state::<SessionManager>()
+                .all_sessions()
+                .into_iter()
+                .find(|s| s.worktree_path.as_deref().map(Path::new) == Some(wt_path))
+                .map(|s| s.profile.shell_env())
+                .unwrap_or_default();
+            match test_runner::run(command, wt_path, env, |_| {}).await {
+                Ok(run) => outcome(GateCheckKind::Lint, &run),
+                Err(e) => failed(GateCheckKind::Lint, format!("Failed to run '{command}': {e}")),
+            }
+        }
+    };
+    checks.push(lint);
+
+    let test_command = RepoConfig::for_repo(repo_path)
+        .agent
+        .test_command
+        .filter(|command| !command.trim().is_empty());
+    let tests = if !config.require_tests {
+        skipped(GateCheckKind::Tests, "Not required")
+    } else if any_failed(&checks) {
+        skipped(GateCheckKind::Tests, "Not run: an earlier check failed")
+    } else if let Some(command) = test_command {
+        match test_runner::run_and_record(app, wt_path, &command).await {
+            Ok(run) => outcome(GateCheckKind::Tests, &run),
+            Err(e) => failed(GateCheckKind::Tests, e.message),
+        }
+    } else {
+        failed(
+            GateCheckKind::Tests,
+            format!("No test command is configured; set agent.test_command in {}", repo_path.display()),
+        )
+    };
+    checks.push(tests);
+
+    Ok(MergeGateReport {
+        passed: !any_failed(&checks),
+        branch,
+        target_branch,
+        head,
+        checks,
+    })
+}
+
+fn passed(check: GateCheckKind, detail: String) -> GateCheck {
+    GateCheck {
+        check,
+        status: GateStatus::Passed,
+        detail,
+    }
+}
+
+fn failed(check: GateCheckKind, detail: String) -> GateCheck {
+    GateCheck {
+        check,
+        status: GateStatus::Failed,
+        detail,
+    }
+}
+
+fn skipped(check: GateCheckKind, detail: &str) -> GateCheck {
+    GateCheck {
+        check,
+        status: GateStatus::Skipped,
+        detail: detail.to_string(),
+    }
+}
+
+fn any_failed(checks: &[GateCheck]) -> bool {
+    checks.iter().any(|c| c.status == GateStatus::Failed)
+}
+
+/// The check's result from a command run: passed on exit 0, else failed
+/// with the end of its output.
+fn outcome(check: GateCheckKind, run: &TestRun) -> GateCheck {
+    let counts = run
+        .summary
+        .map(|s| format!(" ({} passed, {} failed, {} skipped)", s.passed, s.failed, s.skipped))
+        .unwrap_or_default();
+    if run.passed() {
+        return passed(check, format!("'{}' succeeded{counts}", run.command));
+    }
+    let reason = match (run.timed_out, run.exit_code) {
+        (true, _) => "timed out".to_string(),
+        (false, Some(code)) => format!("exited with {code}"),
+        (false, None) => "was killed".to_string(),
+    };
+    let lines: Vec<&str> = run.output.lines().collect();
+    let tail = lines[lines.len().saturating_sub(FAILURE_OUTPUT_LINES)..].join("\n");
+    failed(check, format!("'{}' {reason}{counts}\n{tail}", run.command))
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::process_manager::ProcessManager;
+use super::scrollback::Scrollback;
+
+/// Seconds over which rates are averaged.
+const RATE_WINDOW_SECS: usize = 60;
+
+/// How often something happened: in total, and per second over the last
+/// minute.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RateStats {
+    pub name: String,
+    pub total: u64,
+    pub per_sec: f64,
+}
+
+/// How long something took, over every run since startup. `failures` are
+/// runs that returned an error or, for git, exited non-zero.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TimingStats {
+    pub name: String,
+    pub count: u64,
+    pub failures: u64,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Output waiting in the PTY pipeline: read from the PTYs and not yet
+/// emitted (`queued_output_bytes`), and kept by `Scrollback`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ChannelDepths {
+    pub ptys: usize,
+    pub queued_output_bytes: usize,
+    pub scrollback_bytes: usize,
+}
+
+/// Everything `Metrics` measured, sorted by name within each list.
+///
+/// `events` counts the terminal events emitted by the PTY pipeline
+/// (`pty-output`, `pty-exit`) and the per-session events routed by
+/// `windows::emit_to_session`, by name without the PTY ID. `command_calls`
+/// counts every IPC command invoked; `commands` times every IPC command
+/// from the request to the response, counting error responses as
+/// failures. `git_commands` times git subprocesses by subcommand.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MetricsSnapshot {
+    pub uptime_secs: u64,
+    pub events: Vec<RateStats>,
+    pub command_calls: Vec<RateStats>,
+    pub commands: Vec<TimingStats>,
+    pub git_commands: Vec<TimingStats>,
+    pub channels: ChannelDepths,
+}
+
+/// A counter with per-second buckets for the last `RATE_WINDOW_SECS`.
+struct Counter {
+    total: u64,
+    /// Count of each second, at `second % RATE_WINDOW_SECS`, and which
+    /// second it counts.
+    buckets: [(u64, u64); RATE_WINDOW_SECS],
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self {
+            total: 0,
+            buckets: [(0, 0); RATE_WINDOW_SECS],
+        }
+    }
+}
+
+impl Counter {
+    fn add(&mut self, second: u64) {
+        self.total += 1;
+        let bucket = &mut self.buckets[second as usize % RATE_WINDOW_SECS];
+        if bucket.0 != second {
+            *bucket = (second, 0);
+        }
+        bucket.1 += 1;
+    }
+
+    fn stats(&self, name: &str, second: u64) -> RateStats {
+        let recent: u64 = self
+            .buckets
+            .iter()
+            .filter(|(at, _)| second.saturating_sub(*at) < RATE_WINDOW_SECS as u64)
+            .map(|(_, count)| count)
+            .sum();
+        RateStats {
+            name: name.to_string(),
+            total: self.total,
+            per_sec: recent as f64 / RATE_WINDOW_SECS as f64,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Timing {
+    count: u64,
+    failures: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl Timing {
+    fn add(&mut self, elapsed: Duration, failed: bool) {
+        self.count += 1;
+        self.failures += u64::from(failed);
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    fn stats(&self, name: &str) -> TimingStats {
+        let total_ms = self.total.as_secs_f64() * 1000.0;
+        TimingStats {
+            name: name.to_string(),
+            count: self.count,
+            failures: self.failures,
+            total_ms,
+            mean_ms: if self.count == 0 { 0.0 } else { total_ms / self.count as f64 },
+            max_ms: self.max.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// In-process performance counters for the PTY and git pipelines and the
+/// IPC layer, so regressions show up as numbers instead of impressions.
+///
+/// Recording is a short lock on a map; nothing is persisted, so counters
+/// start from zero with the process. Read with `snapshot`, or as
+/// Prometheus text with `prometheus` (served on `GET /metrics` by the HTTP
+/// API). Global, since the git runner has no app handle; see `registry`.
+pub struct Metrics {
+    started: Instant,
+    events: Mutex<HashMap<String, Counter>>,
+    command_calls: Mutex<HashMap<String, Counter>>,
+    commands: Mutex<HashMap<String, Timing>>,
+    git_commands: Mutex<HashMap<String, Timing>>,
+}
+
+/// Returns the process-wide metrics registry.
+pub fn registry() -> &'static Metrics {
+    static REGISTRY: OnceLock<Metrics> = OnceLock::new();
+    REGISTRY.get_or_init(|| Metrics {
+        started: Instant::now(),
+        events: Mutex::new(HashMap::new()),
+        command_calls: Mutex::new(HashMap::new()),
+        commands: Mutex::new(HashMap::new()),
+        git_commands: Mutex::new(HashMap::new()),
+    })
+}
+
+impl Metrics {
+    /// Counts an emitted event.
+    pub fn event(&self, name: &str) {
+        self.count(&self.events, name);
+    }
+
+    /// Counts an invoked IPC command.
+    pub fn command_call(&self, name: &str) {
+        self.count(&self.command_calls, name);
+    }
+
+    /// Records how long an IPC command took to produce its result.
+    pub fn command(&self, name: &str, elapsed: Duration, failed: bool) {
+        Self::time(&self.commands, name, elapsed, failed);
+    }
+
+    /// Records how long a git subprocess ran.
+    pub fn git_command(&self, subcommand: &str, elapsed: Duration, failed: bool) {
+        Self::time(&self.git_commands, subcommand, elapsed, failed);
+    }
+
+    fn count(&self, counters: &Mutex<HashMap<String, Counter>>, name: &str) {
+        let second = self.started.elapsed().as_secs();
+        let mut counters = lock(counters);
+        match counters.get_mut(name) {
+            Some(counter) => counter.add(second),
+            None => counters.entry(name.to_string()).or_default().add(second),
+        }
+    }
+
+    fn time(timings: &Mutex<HashMap<String, Timing>>, name: &str, elapsed: Duration, failed: bool) {
+        let mut timings = lock(timings);
+        match timings.get_mut(name) {
+            Some(timing) => timing.add(elapsed, failed),
+            None => timings.entry(name.to_string()).or_default().add(elapsed, failed),
+        }
+    }
+
+    /// Returns everything measured so far, with the current channel depths.
+    pub fn snapshot(&self, app: &AppHandle) -> MetricsSnapshot {
+        let second = self.started.elapsed().as_secs();
+        let rates = |counters: &Mutex<HashMap<String, Counter>>| {
+            let mut stats: Vec<RateStats> = lock(counters).iter().map(|(name, c)| c.stats(name, second)).collect();
+            stats.sort_by(|a, b| a.name.cmp(&b.name));
+            stats
+        };
+        let timings = |timings: &Mutex<HashMap<String, Timing>>| {
+            let mut stats: Vec<TimingStats> = lock(timings).iter().map(|(name, t)| t.stats(name)).collect();
+            stats.sort_by(|a, b| a.name.cmp(&b.name));
+            stats
+        };
+        let pm = app.try_state::<ProcessManager>();
+        MetricsSnapshot {
+            uptime_secs: second,
+            events: rates(&self.events),
+            command_calls: rates(&self.command_calls),
+            commands: timings(&self.commands),
+            git_commands: timings(&self.git_commands),
+            channels: ChannelDepths {
+                ptys: pm.as_ref().map_or(0, |pm| pm.session_count()),
+                queued_output_bytes: pm.as_ref().map_or(0, |pm| pm.queued_output_bytes()),
+                scrollback_bytes: app.try_state::<Scrollback>().map_or(0, |s| s.held_bytes()),
+            },
+        }
+    }
+
+    /// Renders `snapshot` in the Prometheus text exposition format.
+    pub fn prometheus(&self, app: &AppHandle) -> String {
+        let snapshot = self.snapshot(app);
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE maestro_uptime_seconds gauge");
+        let _ = writeln!(out, "maestro_uptime_seconds {}", snapshot.uptime_secs);
+        for (metric, label, stats) in [
+            ("maestro_events", "event", &snapshot.events),
+            ("maestro_ipc_calls", "command", &snapshot.command_calls),
+        ] {
+            let _ = writeln!(out, "# TYPE {metric}_total counter");
+            for s in stats {
+                let _ = writeln!(out, "{metric}_total{{{label}=\"{}\"}} {}", escape(&s.name), s.total);
+            }
+            let _ = writeln!(out, "# TYPE {metric}_per_second gauge");
+            for s in stats {
+                let _ = writeln!(out, "{metric}_per_second{{{label}=\"{}\"}} {}", escape(&s.name), s.per_sec);
+            }
+        }
+        for (metric, label, stats) in [
+            ("maestro_ipc_duration_seconds", "command", &snapshot.commands),
+            ("maestro_git_duration_seconds", "subcommand", &snapshot.git_commands),
+        ] {
+            let _ = writeln!(out, "# TYPE {metric} summary");
+            for s in stats {
+                let name = escape(&s.name);
+                let _ = writeln!(out, "{metric}_sum{{{label}=\"{name}\"}} {}", s.total_ms / 1000.0);
+                let _ = writeln!(out, "{metric}_count{{{label}=\"{name}\"}} {}", s.count);
+            }
+            let _ = writeln!(out, "# TYPE {metric}_max gauge");
+            for s in stats {
+                let _ = writeln!(out, "{metric}_max{{{label}=\"{}\"}} {}", escape(&s.name), s.max_ms / 1000.0);
+            }
+            let failures = metric.replace("_duration_seconds", "_failures_total");
+            let _ = writeln!(out, "# TYPE {failures} counter");
+            for s in stats {
+                let _ = writeln!(out, "{failures}{{{label}=\"{}\"}} {}", escape(&s.name), s.failures);
+            }
+        }
+        let channels = &snapshot.channels;
+        for (metric, value) in [
+            ("maestro_ptys", channels.ptys),
+            ("maestro_pty_queued_output_bytes", channels.queued_output_bytes),
+            ("maestro_scrollback_bytes", channels.scrollback_bytes),
+        ] {
+            let _ = writeln!(out, "# TYPE {metric} gauge");
+            let _ = writeln!(out, "{metric} {value}");
+        }
+        out
+    }
+}
+
+/// Escapes a Prometheus label value.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
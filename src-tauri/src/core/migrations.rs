@@ -0,0 +1,138 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::clock;
+use super::storage;
+
+/// One step upgrading a persisted JSON file from version `from` to
+/// `from + 1`. `migrate` rewrites the parsed file in place; the runner
+/// then sets its `version`.
+pub struct Migration {
+    /// File in the data dir, or directory whose `*.json` files are all
+    /// migrated (e.g. `scratchpads`).
+    pub target: &'static str,
+    pub from: u32,
+    pub description: &'static str,
+    pub migrate: fn(&mut Value) -> Result<(), String>,
+}
+
+/// Every migration step, oldest first.
+///
+/// A change to a persisted format bumps the owning module's version
+/// constant (e.g. `SESSIONS_VERSION`) and adds the step from the previous
+/// version here, so files written by older builds are upgraded before
+/// they are loaded instead of being ignored by the version check.
+const MIGRATIONS: &[Migration] = &[];
+
+/// A file with steps to run, as read from disk.
+struct Pending {
+    path: PathBuf,
+    target: &'static str,
+    version: u32,
+    value: Value,
+}
+
+/// Brings the persisted files in the data dir up to the current formats.
+/// Must run before any state is loaded.
+///
+/// Each file is upgraded through every step from its `version` on; files
+/// without steps to run are not touched. Before anything is rewritten, the
+/// files about to change are copied to `<data dir>/backups/migrate-<unix
+/// ms>`. A file whose step fails is left as it was and logged, so the
+/// owning module falls back to its defaults rather than loading a half
+/// upgraded file; if the copies cannot be made, nothing is migrated.
+pub fn run() {
+    let data_dir = storage::data_dir();
+    let steps = MIGRATIONS;
+    let targets: BTreeSet<&'static str> = steps.iter().map(|s| s.target).collect();
+    let pending: Vec<Pending> = targets
+        .into_iter()
+        .flat_map(|target| target_files(&data_dir, target).into_iter().map(move |path| (target, path)))
+        .filter_map(|(target, path)| {
+            let value = storage::load_json::<Value>(&path).ok().flatten()?;
+            let version = u32::try_from(value.get("version")?.as_u64()?).ok()?;
+            steps
+                .iter()
+                .any(|s| s.target == target && s.from == version)
+                .then_some(Pending {
+                    path,
+                    target,
+                    version,
+                    value,
+                })
+        })
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    let backup_dir = data_dir.join("backups").join(format!("migrate-{}", clock::now_millis()));
+    if let Err(e) = back_up(&data_dir, &backup_dir, &pending) {
+        log::error!("Failed to back up files before migrating them to {}: {e}; not migrating", backup_dir.display());
+        return;
+    }
+
+    for Pending {
+        path,
+        target,
+        mut version,
+        mut value,
+    } in pending
+    {
+        let from = version;
+        let result = loop {
+            let Some(step) = steps.iter().find(|s| s.target == target && s.from == version) else {
+                break Ok(());
+            };
+            if let Err(e) = (step.migrate)(&mut value) {
+                break Err(format!("step from version {version} ({}) failed: {e}", step.description));
+            }
+            version += 1;
+        };
+        if let Err(e) = result {
+            log::error!("Failed to migrate {}: {e}; left at version {from}", path.display());
+            continue;
+        }
+        if let Some(object) = value.as_object_mut() {
+            object.insert("version".to_string(), Value::from(version));
+        }
+        match storage::save_json(&path, &value) {
+            Ok(()) => log::info!("Migrated {} from version {from} to {version}", path.display()),
+            Err(e) => log::error!("Failed to save migrated {}: {e}", path.display()),
+        }
+    }
+}
+
+/// The files a target names: its `*.json` files if it is a directory,
+/// else the file itself if it exists.
+fn target_files(data_dir: &Path, target: &str) -> Vec<PathBuf> {
+    let path = data_dir.join(target);
+    if !path.is_dir() {
+        return if path.is_file() { vec![path] } else { Vec::new() };
+    }
+    let Ok(entries) = std::fs::read_dir(&path) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Copies the pending files to `backup_dir`, keeping their paths relative
+/// to the data dir.
+fn back_up(data_dir: &Path, backup_dir: &Path, pending: &[Pending]) -> std::io::Result<()> {
+    for file in pending {
+        let relative = file.path.strip_prefix(data_dir).unwrap_or(&file.path);
+        let copy = backup_dir.join(relative);
+        if let Some(parent) = copy.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&file.path, &copy)?;
+    }
+    Ok(())
+}
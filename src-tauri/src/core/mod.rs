@@ -1,9 +1,71 @@
+pub mod activity_report;
+pub mod agent_adapter;
+pub mod agent_launcher;
+pub mod agent_output;
+pub mod approval;
+pub mod audit;
+pub mod auto_push;
+pub mod backup;
+pub mod board;
+pub mod ci_checks;
+pub mod clipboard;
+pub mod clock;
+pub mod deep_link;
+pub mod diagnostics;
 pub mod error;
+pub mod file_stream;
+pub mod files;
+pub mod forks;
+pub mod fs_watcher;
+pub mod guardrails;
+pub mod handoff;
+pub mod harvest;
+pub mod healthcheck;
+pub mod issues;
+pub mod launch;
+pub mod logging;
+pub mod maintenance;
+pub mod memory_budget;
+pub mod merge_gate;
+pub mod metrics;
+pub mod migrations;
+pub mod notifier;
+pub mod opener;
+pub mod orchestrator;
+pub mod output_links;
+pub mod pipeline;
+pub mod pr_description;
 pub mod process_manager;
+pub mod provider_usage;
+pub mod pull_request;
+pub mod remote_control;
+pub mod repo_config;
+pub mod retention;
+pub mod scheduler;
+pub mod scratchpad;
+pub mod scrollback;
+pub mod search;
+pub mod session_archive;
+pub mod session_lock;
 pub mod session_manager;
+pub mod settings;
+pub mod snippets;
+pub mod status_inference;
+pub mod startup;
+pub mod storage;
+pub mod task_queue;
+pub mod test_runner;
+pub mod transcript;
+pub mod tray;
+pub mod updates;
+pub mod watchdog;
+pub mod windows;
+pub mod workspace_search;
 pub mod worktree_manager;
+pub mod worktree_progress;
+pub mod worktree_registry;
 
-pub use error::PtyError;
+pub use error::{AppError, PtyError};
 pub use process_manager::ProcessManager;
 pub use session_manager::SessionManager;
 pub use worktree_manager::WorktreeManager;
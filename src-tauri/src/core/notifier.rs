@@ -0,0 +1,61 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use super::session_manager::{SessionConfig, SessionListener, SessionStatus, StatusTransition};
+
+/// Raises desktop notifications when a session needs attention.
+///
+/// Registered as a `SessionListener`. On a transition to `NeedsInput` or
+/// `Error` while no Maestro window has focus, shows an OS notification
+/// titled with the session's title (or branch) and the transition detail,
+/// such as the detected prompt line, as the body. Nothing is shown while
+/// the user is looking at the app, since the UI already reflects the change.
+pub struct SessionNotifier {
+    app_handle: AppHandle,
+}
+
+impl SessionNotifier {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+
+    fn app_focused(&self) -> bool {
+        self.app_handle
+            .webview_windows()
+            .values()
+            .any(|w| w.is_focused().unwrap_or(false))
+    }
+}
+
+impl SessionListener for SessionNotifier {
+    fn on_status_changed(&self, session: &SessionConfig, transition: &StatusTransition) {
+        let headline = match transition.to {
+            SessionStatus::NeedsInput => "needs input",
+            SessionStatus::Error => "stopped with an error",
+            _ => return,
+        };
+        if self.app_focused() {
+            return;
+        }
+
+        let name = session
+            .title
+            .clone()
+            .or_else(|| session.branch.clone())
+            .unwrap_or_else(|| format!("Session {}", session.id));
+        let body = transition
+            .detail
+            .clone()
+            .unwrap_or_else(|| format!("{name} {headline}"));
+        let result = self
+            .app_handle
+            .notification()
+            .builder()
+            .title(format!("{name} {headline}"))
+            .body(body)
+            .show();
+        if let Err(e) = result {
+            log::warn!("Failed to show notification for session {}: {e}", session.id);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use super::files;
+use super::session_manager::SessionManager;
+use super::worktree_manager::WorktreeManager;
+
+/// Longest the file manager may take to answer `ShowItems`.
+const DBUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Editors that run in a terminal, which a desktop app has none of; files
+/// are handed to the desktop's default application instead.
+const TERMINAL_EDITORS: &[&str] = &["vi", "vim", "nvim", "nano", "pico", "ed", "hx", "helix", "micro", "kak", "joe", "mg"];
+
+/// Opens a file inside an open repository or worktree in the user's
+/// editor, at `line` (1-based) if the editor is known to take one.
+///
+/// The editor is `$VISUAL`, else `$EDITOR`, unless it runs in a terminal
+/// (e.g. `vim`); then, or without either, the file is opened with
+/// `xdg-open` (or `gio open`), which cannot jump to a line. Returns the
+/// program used.
+pub async fn open_in_editor(
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    path: &Path,
+    line: Option<u32>,
+) -> Result<String, String> {
+    let path = files::resolve_scoped(sessions, worktrees, path).await?;
+    let file = path.to_string_lossy().into_owned();
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty());
+    if let Some(editor) = editor {
+        let mut words = editor.split_whitespace().map(str::to_string);
+        let program = words.next().unwrap_or_default();
+        let name = Path::new(&program)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if !TERMINAL_EDITORS.contains(&name.as_str()) {
+            let mut args: Vec<String> = words.collect();
+            args.extend(editor_args(&name, &file, line));
+            spawn_detached(&program, &args)?;
+            log::info!("Opened {file} in {program}");
+            return Ok(program);
+        }
+    }
+    open_with_default(&file)
+}
+
+/// Shows a file or directory inside an open repository or worktree in the
+/// file manager: selected in its folder through the freedesktop
+/// `FileManager1` D-Bus interface if the file manager implements it,
+/// otherwise by opening the folder containing it.
+pub async fn reveal_in_file_manager(
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    path: &Path,
+) -> Result<(), String> {
+    let path = files::resolve_scoped(sessions, worktrees, path).await?;
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    let uri = format!("array:string:{}", file_uri(&path));
+    let show = tokio::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+            &uri,
+            "string:",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(DBUS_TIMEOUT, show).await {
+        Ok(Ok(output)) if output.status.success() => return Ok(()),
+        Ok(Ok(output)) => log::debug!(
+            "FileManager1.ShowItems failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Ok(Err(e)) => log::debug!("Cannot run dbus-send: {e}"),
+        Err(_) => log::debug!("FileManager1.ShowItems timed out"),
+    }
+    let folder = if path.is_dir() { path.as_path() } else { path.parent().unwrap_or(&path) };
+    open_with_default(&folder.to_string_lossy()).map(|_| ())
+}
+
+/// Arguments opening `file` at `line` in the editor named `name`.
+fn editor_args(name: &str, file: &str, line: Option<u32>) -> Vec<String> {
+    let Some(line) = line else {
+        return vec![file.to_string()];
+    };
+    match name {
+        "code" | "code-insiders" | "codium" | "vscodium" | "cursor" | "windsurf" => {
+            vec!["--goto".to_string(), format!("{file}:{line}")]
+        }
+        "subl" | "sublime_text" | "zed" | "zeditor" | "lapce" => vec![format!("{file}:{line}")],
+        "idea" | "pycharm" | "clion" | "goland" | "rustrover" | "webstorm" | "phpstorm" | "rider" => {
+            vec!["--line".to_string(), line.to_string(), file.to_string()]
+        }
+        "gvim" | "emacs" | "emacsclient" | "gedit" | "gnome-text-editor" | "kate" | "kwrite" | "geany" | "mousepad" | "pluma"
+        | "xed" => vec![format!("+{line}"), file.to_string()],
+        _ => vec![file.to_string()],
+    }
+}
+
+/// Opens `target` with the desktop's default application, returning the
+/// program used.
+fn open_with_default(target: &str) -> Result<String, String> {
+    match spawn_detached("xdg-open", &[target.to_string()]) {
+        Ok(()) => Ok("xdg-open".to_string()),
+        Err(_) => {
+            spawn_detached("gio", &["open".to_string(), target.to_string()])?;
+            Ok("gio".to_string())
+        }
+    }
+}
+
+/// Starts a program that outlives the call; it is reaped in the background.
+fn spawn_detached(program: &str, args: &[String]) -> Result<(), String> {
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => format!("{program} is not installed"),
+            _ => format!("Failed to run {program}: {e}"),
+        })?;
+    tauri::async_runtime::spawn(async move {
+        let _ = child.wait().await;
+    });
+    Ok(())
+}
+
+/// Returns the `file://` URI of an absolute path, percent-encoding every
+/// byte outside the characters RFC 3986 allows unescaped in a path.
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
@@ -0,0 +1,776 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::agent_adapter::AgentEvent;
+use super::agent_launcher::AgentLauncher;
+use super::agent_output;
+use super::clock;
+use super::error::{AppError, ErrorCode, PtyError, PtyErrorCode};
+use super::process_manager::ProcessManager;
+use super::repo_config::{self, RepoConfig};
+use super::scheduler::LaunchScheduler;
+use super::session_manager::{
+    AiMode, LaunchProfile, SessionConfig, SessionManager, SessionStatus, StatusCause,
+};
+use super::worktree_manager::WorktreeManager;
+use super::worktree_progress::{self, WorktreeProgress};
+use crate::git::{DiffStat, Git, WorktreeArchive};
+use crate::mcp;
+
+/// How often a freshly launched session is checked for a ready agent.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Seconds without output after which a launched agent is taken to be
+/// waiting at its input box.
+const READY_QUIET_SECS: i64 = 2;
+
+/// Longest wait for a launched agent to settle before a prompt is sent
+/// anyway. Time spent `Queued` by the scheduler does not count.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Which branch a new session's worktree should check out.
+///
+/// `Existing` checks out an existing branch, `New` creates `branch` from
+/// `base_ref`, and `Unique` behaves like `Existing` but falls back to a
+/// suffixed branch (`<branch>-2`, ...) if `branch` is already in use.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub enum BranchSpec {
+    Existing { branch: String },
+    New { branch: String, base_ref: String },
+    Unique { branch: String },
+}
+
+/// One agent configuration in a `fanout_task` comparison. Model choices
+/// and other agent settings go in `profile`, e.g. an environment variable
+/// the agent reads. `label` names the variant in the session title.
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct FanoutVariant {
+    pub mode: AiMode,
+    #[serde(default)]
+    pub profile: LaunchProfile,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Outcome of `fanout_task`. `errors` lists the variants that could not be
+/// started; the others keep running.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct FanoutResult {
+    pub group: String,
+    pub base_ref: String,
+    pub sessions: Vec<SessionConfig>,
+    pub errors: Vec<String>,
+}
+
+/// One session of a comparison group as shown side by side with the
+/// others. `diffstat` covers committed, uncommitted, and untracked changes
+/// since the variant's branch was cut; `error` says why it is missing.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct VariantResult {
+    pub session_id: u32,
+    pub title: Option<String>,
+    pub mode: AiMode,
+    pub branch: Option<String>,
+    pub status: SessionStatus,
+    pub cost_usd: Option<f64>,
+    pub diffstat: Option<DiffStat>,
+    pub error: Option<String>,
+}
+
+/// Everything `create_full_session` provisioned so far, so a failure at any
+/// step can undo exactly the earlier steps.
+struct Provisioned {
+    repo_path: PathBuf,
+    wt_path: PathBuf,
+    branch: String,
+    created_branch: bool,
+}
+
+impl Provisioned {
+    /// Removes the worktree and, if this flow created it, the branch.
+    async fn rollback(&self, worktrees: &WorktreeManager) {
+        if let Err(e) = worktrees.remove(&self.repo_path, &self.wt_path, false, true).await {
+            log::error!("Rollback: failed to remove worktree {}: {e}", self.wt_path.display());
+        }
+        if self.created_branch {
+            if let Err(e) = Git::new(&self.repo_path).delete_branch(&self.branch, true).await {
+                log::error!("Rollback: failed to delete branch {}: {e}", self.branch);
+            }
+        }
+    }
+}
+
+/// Outcome of `destroy_session`. Each flag records a step that succeeded;
+/// `errors` lists the steps that failed, in the order they ran. `archive`
+/// is the snapshot of uncommitted changes taken before the worktree was
+/// removed, if there were any.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SessionTeardown {
+    pub session_id: u32,
+    pub pty_killed: bool,
+    pub worktree_removed: bool,
+    pub archive: Option<WorktreeArchive>,
+    pub branch_deleted: bool,
+    pub errors: Vec<String>,
+}
+
+/// Creates a worktree, spawns a shell in it, and registers a session bound
+/// to both, as a single all-or-nothing operation. With `op_id`, the
+/// checkout's progress is reported on it (see `worktree_progress`).
+///
+/// Steps run in that order; if the shell fails to spawn, the worktree (and
+/// any branch this call created) is removed again, so a failed attempt
+/// never leaks a worktree. Session registration itself cannot fail, but the
+/// PTY and worktree are still released if binding them goes wrong.
+///
+/// The repository's `.maestro/config.toml` (see `RepoConfig`) brings its
+/// shared dirs into the worktree and its agent defaults into `profile`.
+/// The shell is spawned with the profile's environment. Once the session
+/// exists, the repository's setup commands, the profile's startup script,
+/// and then the agent CLI for `mode` are started in its shell, the last
+/// subject to the scheduler's cap on working sessions. A failed launch is
+/// logged but does not undo the session, since the user can still start the
+/// agent by hand.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_full_session(
+    app_handle: AppHandle,
+    worktrees: &WorktreeManager,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    repo_path: &Path,
+    branch_spec: BranchSpec,
+    mode: AiMode,
+    profile: LaunchProfile,
+    op_id: Option<&str>,
+) -> Result<SessionConfig, String> {
+    let provisioned = match branch_spec {
+        BranchSpec::Existing { branch } => {
+            let wt_path = worktree_progress::create(&app_handle, op_id, &branch, None, repo_path)
+                .await
+                .map_err(|e| e.to_string())?;
+            Provisioned {
+                repo_path: repo_path.to_path_buf(),
+                wt_path,
+                branch,
+                created_branch: false,
+            }
+        }
+        BranchSpec::New { branch, base_ref } => {
+            let wt_path = worktree_progress::create(&app_handle, op_id, &branch, Some(&base_ref), repo_path)
+                .await
+                .map_err(|e| e.to_string())?;
+            Provisioned {
+                repo_path: repo_path.to_path_buf(),
+                wt_path,
+                branch,
+                created_branch: true,
+            }
+        }
+        BranchSpec::Unique { branch } => {
+            let unique = worktrees
+                .create_unique(&branch, repo_path, |progress| {
+                    if let Some(op_id) = op_id {
+                        worktree_progress::update(&app_handle, op_id, WorktreeProgress::Running { progress });
+                    }
+                })
+                .await
+                .map_err(|e| e.to_string())?;
+            Provisioned {
+                repo_path: repo_path.to_path_buf(),
+                wt_path: PathBuf::from(unique.path),
+                created_branch: unique.branch != branch,
+                branch: unique.branch,
+            }
+        }
+    };
+
+    let repo_config = RepoConfig::for_repo(repo_path);
+    repo_config.share_dirs(repo_path, &provisioned.wt_path);
+    let profile = repo_config.merge_profile(profile);
+
+    let cwd = provisioned.wt_path.to_string_lossy().into_owned();
+    let mut env = mcp::agent_env(&app_handle, &mode);
+    env.extend(profile.shell_env());
+    let pty_id = match processes.spawn_shell_with_env(app_handle, Some(cwd.clone()), &env) {
+        Ok(id) => id,
+        Err(e) => {
+            provisioned.rollback(worktrees).await;
+            return Err(e.to_string());
+        }
+    };
+
+    let session = sessions.create_next_session(mode, repo_path.to_string_lossy().into_owned());
+    let bound = sessions
+        .modify(session.id, |s| s.profile = profile)
+        .and_then(|_| sessions.assign_branch(session.id, provisioned.branch.clone(), Some(cwd)))
+        .and_then(|_| sessions.bind_pty(session.id, pty_id));
+    let Some(config) = bound else {
+        sessions.remove_session(session.id);
+        if let Err(e) = processes.kill_session(pty_id).await {
+            log::error!("Rollback: failed to kill PTY {pty_id}: {e}");
+        }
+        provisioned.rollback(worktrees).await;
+        return Err(format!("Session {} disappeared during creation", session.id));
+    };
+
+    worktrees.link_session(&provisioned.wt_path, config.id);
+    start_session(processes, scheduler, &config, false, &repo_config.worktree.setup);
+    log::info!(
+        "Created session {} on {} (pty {pty_id})",
+        config.id,
+        provisioned.branch
+    );
+    Ok(config)
+}
+
+/// Spawns a new shell for an existing session that has no PTY (for example
+/// one restored after a restart), in its worktree and with its launch
+/// profile, binds it, and starts the startup script and agent as
+/// `create_full_session` does. With `resume`, the agent continues its last
+/// conversation in the worktree instead of starting fresh.
+pub fn spawn_session_shell(
+    app_handle: AppHandle,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    session_id: u32,
+    resume: bool,
+) -> Result<SessionConfig, AppError> {
+    spawn_and_start(app_handle, processes, sessions, scheduler, session_id, resume, &[])
+}
+
+/// `spawn_session_shell`, running `setup` commands in the new shell first.
+fn spawn_and_start(
+    app_handle: AppHandle,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    session_id: u32,
+    resume: bool,
+    setup: &[String],
+) -> Result<SessionConfig, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    if let Some(pty_id) = session.pty_id {
+        return Err(AppError::new(ErrorCode::AlreadyExists, format!("Session {session_id} already has PTY {pty_id}"))
+            .with("session_id", session_id)
+            .with("pty_id", pty_id));
+    }
+
+    let mut env = mcp::agent_env(&app_handle, &session.mode);
+    env.extend(session.profile.shell_env());
+    let pty_id = processes.spawn_shell_with_env(app_handle, session.worktree_path.clone(), &env)?;
+    let Some(config) = sessions.bind_pty(session_id, pty_id) else {
+        let processes = processes.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = processes.kill_session(pty_id).await {
+                log::error!("Rollback: failed to kill PTY {pty_id}: {e}");
+            }
+        });
+        return Err(AppError::session_not_found(session_id));
+    };
+    start_session(processes, scheduler, &config, resume, setup);
+    Ok(config)
+}
+
+/// Relaunches a session's agent on its previous conversation, in the same
+/// worktree. A session that lost its shell (exited, killed, or restored
+/// after a restart) gets a new one first, as in `spawn_session_shell`;
+/// otherwise the resume command is typed into the existing shell, which
+/// should be back at its prompt. Either way the launch goes through the
+/// scheduler and may be queued.
+pub async fn resume_agent(
+    app_handle: AppHandle,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    session_id: u32,
+) -> Result<SessionConfig, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    if session.mode == AiMode::Plain {
+        return Err(AppError::invalid_argument(format!("Session {session_id} has no agent to resume"))
+            .with("session_id", session_id));
+    }
+    // A shell that just exited may not be released yet, but its last status came from the exit
+    let shell_exited = sessions
+        .history(session_id)
+        .and_then(|h| h.transitions.last().map(|t| t.cause == StatusCause::Exit))
+        .unwrap_or(false);
+    match session.pty_id {
+        Some(pty_id) if processes.has_session(pty_id) && !shell_exited => {}
+        Some(pty_id) => {
+            if processes.has_session(pty_id) {
+                if let Err(e) = processes.kill_session(pty_id).await {
+                    log::warn!("Failed to release exited PTY {pty_id}: {e}");
+                }
+            }
+            sessions.unbind_pty(session_id);
+            return spawn_session_shell(app_handle, processes, sessions, scheduler, session_id, true);
+        }
+        None => return spawn_session_shell(app_handle, processes, sessions, scheduler, session_id, true),
+    }
+    scheduler.request_launch(session_id, true)?;
+    sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))
+}
+
+/// Runs the same task through several agents at once so their results can
+/// be compared.
+///
+/// Every variant gets a session, created as by `create_full_session`, on a
+/// fresh branch `<group>-<n>` cut from `base_ref` (the repository's
+/// default branch if unset; see `Git::default_branch`). `group` defaults
+/// to `fanout-<timestamp>` and is recorded as each session's
+/// `comparison_group`. Once a variant's agent is ready, `prompt` is sent to
+/// it in the background. A variant that fails to start does not stop the
+/// others.
+#[allow(clippy::too_many_arguments)]
+pub async fn fanout_task(
+    app_handle: AppHandle,
+    worktrees: &WorktreeManager,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    repo_path: &Path,
+    prompt: String,
+    variants: Vec<FanoutVariant>,
+    group: Option<String>,
+    base_ref: Option<String>,
+) -> Result<FanoutResult, String> {
+    if variants.is_empty() {
+        return Err("At least one variant is required".to_string());
+    }
+    if let Some(variant) = variants.iter().find(|v| v.mode == AiMode::Plain) {
+        return Err(format!(
+            "Variant {} has no agent to run the task",
+            variant.label.as_deref().unwrap_or("Plain")
+        ));
+    }
+    let base_ref = match base_ref {
+        Some(base_ref) => base_ref,
+        None => repo_config::base_branch(repo_path).await.map_err(|e| e.to_string())?,
+    };
+    let group = group.unwrap_or_else(|| format!("fanout-{}", clock::now_secs()));
+
+    let mut result = FanoutResult {
+        group: group.clone(),
+        base_ref: base_ref.clone(),
+        sessions: Vec::new(),
+        errors: Vec::new(),
+    };
+    for (i, variant) in variants.into_iter().enumerate() {
+        let n = i + 1;
+        let branch_spec = BranchSpec::New {
+            branch: format!("{group}-{n}"),
+            base_ref: base_ref.clone(),
+        };
+        let created = create_full_session(
+            app_handle.clone(),
+            worktrees,
+            processes,
+            sessions,
+            scheduler,
+            repo_path,
+            branch_spec,
+            variant.mode,
+            variant.profile,
+            None,
+        )
+        .await;
+        let session = match created {
+            Ok(session) => session,
+            Err(e) => {
+                let label = variant.label.unwrap_or_else(|| format!("variant {n}"));
+                result.errors.push(format!("Failed to start {label}: {e}"));
+                continue;
+            }
+        };
+        let title = variant.label.unwrap_or_else(|| format!("Variant {n}"));
+        let session = sessions
+            .modify(session.id, |s| {
+                s.title = Some(title);
+                s.comparison_group = Some(group.clone());
+            })
+            .unwrap_or(session);
+
+        let app = app_handle.clone();
+        let prompt = prompt.clone();
+        let session_id = session.id;
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = send_prompt_when_ready(&app, session_id, &prompt).await {
+                log::warn!("Failed to send fan-out task to session {session_id}: {e}");
+            }
+        });
+        result.sessions.push(session);
+    }
+    for e in &result.errors {
+        log::warn!("Fan-out {group}: {e}");
+    }
+    log::info!("Fan-out {group} started {} sessions", result.sessions.len());
+    Ok(result)
+}
+
+/// Returns the sessions of a comparison group, oldest first, with each
+/// variant's diffstat against the base its branch was cut from.
+pub async fn compare_group(
+    worktrees: &WorktreeManager,
+    sessions: &SessionManager,
+    group: &str,
+) -> Vec<VariantResult> {
+    let mut members: Vec<SessionConfig> = sessions
+        .all_sessions()
+        .into_iter()
+        .filter(|s| s.comparison_group.as_deref() == Some(group))
+        .collect();
+    members.sort_by_key(|s| (s.created_at, s.id));
+
+    let mut results = Vec::with_capacity(members.len());
+    for session in members {
+        let diffstat = variant_diffstat(worktrees, &session).await;
+        results.push(VariantResult {
+            session_id: session.id,
+            title: session.title,
+            mode: session.mode,
+            branch: session.branch,
+            status: session.status,
+            cost_usd: session.cost_usd,
+            diffstat: diffstat.as_ref().ok().cloned(),
+            error: diffstat.err(),
+        });
+    }
+    results
+}
+
+async fn variant_diffstat(worktrees: &WorktreeManager, session: &SessionConfig) -> Result<DiffStat, String> {
+    let wt = session
+        .worktree_path
+        .as_deref()
+        .ok_or_else(|| format!("Session {} has no worktree", session.id))?;
+    let base_ref = worktrees
+        .base_ref_of(Path::new(wt))
+        .ok_or_else(|| format!("Base of worktree {wt} is unknown"))?;
+    let repo_path = match session.repo() {
+        Some(repo) => repo.to_path_buf(),
+        None => worktrees
+            .repo_of(Path::new(wt))
+            .await
+            .ok_or_else(|| format!("Cannot determine the repository of {wt}"))?,
+    };
+    worktrees
+        .diff(&repo_path, Path::new(wt), &base_ref)
+        .await
+        .map(|summary| summary.stat)
+        .map_err(|e| e.to_string())
+}
+
+/// Registers a session that starts only once `upstream_id` reaches `Done`.
+///
+/// The new session is `Blocked` with `branch` reserved but no worktree or
+/// shell. When the upstream session finishes, `start_dependent_session`
+/// creates `branch` from the upstream branch, so each stage builds on the
+/// previous stage's commits. If the upstream is already `Done` the session
+/// is started immediately. Since only new sessions can gain a dependency,
+/// pipelines cannot form cycles.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_dependent_session(
+    app_handle: AppHandle,
+    worktrees: &WorktreeManager,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    upstream_id: u32,
+    branch: String,
+    mode: AiMode,
+    profile: LaunchProfile,
+) -> Result<SessionConfig, AppError> {
+    let upstream = sessions
+        .get_session(upstream_id)
+        .ok_or_else(|| AppError::session_not_found(upstream_id))?;
+    if upstream.branch.is_none() || upstream.worktree_path.is_none() {
+        return Err(AppError::invalid_argument(format!("Session {upstream_id} has no worktree to build on"))
+            .with("session_id", upstream_id));
+    }
+
+    let profile = match upstream.repo() {
+        Some(repo) => RepoConfig::for_repo(repo).merge_profile(profile),
+        None => profile,
+    };
+    let session = sessions.create_next_session(mode, upstream.repo_path.clone());
+    sessions.modify(session.id, |s| {
+        s.branch = Some(branch);
+        s.depends_on = Some(upstream_id);
+        s.profile = profile;
+    });
+    sessions.update_status(session.id, SessionStatus::Blocked, StatusCause::Scheduled);
+    log::info!("Session {} will start after session {upstream_id}", session.id);
+
+    if upstream.status == SessionStatus::Done {
+        return start_dependent_session(app_handle, worktrees, processes, sessions, scheduler, session.id).await;
+    }
+    sessions
+        .get_session(session.id)
+        .ok_or_else(|| AppError::session_not_found(session.id))
+}
+
+/// Starts a `Blocked` session whose upstream reached `Done`: creates its
+/// reserved branch from the upstream branch in a new worktree, then spawns
+/// its shell and agent. On failure the worktree is rolled back and the
+/// session is marked `Error`.
+pub async fn start_dependent_session(
+    app_handle: AppHandle,
+    worktrees: &WorktreeManager,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    session_id: u32,
+) -> Result<SessionConfig, AppError> {
+    let result = provision_dependent(app_handle, worktrees, processes, sessions, scheduler, session_id).await;
+    if let Err(ref e) = result {
+        log::error!("Failed to start dependent session {session_id}: {e}");
+        sessions.update_status(session_id, SessionStatus::Error, StatusCause::Scheduled);
+    }
+    result
+}
+
+async fn provision_dependent(
+    app_handle: AppHandle,
+    worktrees: &WorktreeManager,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    scheduler: &LaunchScheduler,
+    session_id: u32,
+) -> Result<SessionConfig, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let upstream_id = session.depends_on.ok_or_else(|| {
+        AppError::invalid_argument(format!("Session {session_id} has no upstream session"))
+            .with("session_id", session_id)
+    })?;
+    let upstream = sessions
+        .get_session(upstream_id)
+        .ok_or_else(|| AppError::session_not_found(upstream_id))?;
+    let (Some(base_ref), Some(upstream_wt)) = (upstream.branch.clone(), upstream.worktree_path.clone()) else {
+        return Err(AppError::invalid_argument(format!("Upstream session {upstream_id} has no worktree"))
+            .with("session_id", upstream_id));
+    };
+    let branch = session.branch.ok_or_else(|| {
+        AppError::invalid_argument(format!("Session {session_id} has no branch reserved"))
+            .with("session_id", session_id)
+    })?;
+    let repo_path = match upstream.repo() {
+        Some(repo) => repo.to_path_buf(),
+        // Sessions from before repositories were tracked
+        None => worktrees
+            .repo_of(Path::new(&upstream_wt))
+            .await
+            .ok_or_else(|| {
+                AppError::not_found(format!("Cannot determine the repository of {upstream_wt}"))
+                    .with("path", &upstream_wt)
+            })?,
+    };
+
+    let wt_path = worktree_progress::create(&app_handle, None, &branch, Some(&base_ref), &repo_path).await?;
+    let provisioned = Provisioned {
+        repo_path,
+        wt_path,
+        branch,
+        created_branch: true,
+    };
+
+    let repo_config = RepoConfig::for_repo(&provisioned.repo_path);
+    repo_config.share_dirs(&provisioned.repo_path, &provisioned.wt_path);
+
+    let cwd = provisioned.wt_path.to_string_lossy().into_owned();
+    sessions.assign_branch(session_id, provisioned.branch.clone(), Some(cwd));
+    sessions.update_status(session_id, SessionStatus::Starting, StatusCause::Scheduled);
+    let setup = &repo_config.worktree.setup;
+    match spawn_and_start(app_handle, processes, sessions, scheduler, session_id, false, setup) {
+        Ok(config) => {
+            worktrees.link_session(&provisioned.wt_path, session_id);
+            log::info!(
+                "Started session {session_id} on {} after session {upstream_id}",
+                provisioned.branch
+            );
+            Ok(config)
+        }
+        Err(e) => {
+            provisioned.rollback(worktrees).await;
+            sessions.assign_branch(session_id, provisioned.branch.clone(), None);
+            Err(e)
+        }
+    }
+}
+
+/// Waits for a freshly launched session's agent to start and go quiet, then
+/// sends it `text` as a prompt and records it in the transcript.
+///
+/// There is no reliable "ready" signal across agents, so the agent counts
+/// as ready once it has printed something and been silent for
+/// `READY_QUIET_SECS`, or after `READY_TIMEOUT`. Fails if the session is
+/// removed or its shell exits first.
+pub async fn send_prompt_when_ready(app: &AppHandle, session_id: u32, text: &str) -> Result<(), String> {
+    let sessions = app.state::<SessionManager>();
+    let mut waited = Duration::ZERO;
+    let session = loop {
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+        let session = sessions
+            .get_session(session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        match session.status {
+            SessionStatus::Queued => continue,
+            SessionStatus::Done | SessionStatus::Error => {
+                return Err(format!("Session {session_id} stopped before the prompt was sent"));
+            }
+            _ => {}
+        }
+        waited += READY_POLL_INTERVAL;
+        let quiet = session
+            .last_output_at
+            .is_some_and(|at| clock::now_secs() - at >= READY_QUIET_SECS);
+        if (quiet && session.status != SessionStatus::Starting) || waited >= READY_TIMEOUT {
+            break session;
+        }
+    };
+    let prompt = app
+        .state::<AgentLauncher>()
+        .send_prompt(&app.state::<ProcessManager>(), &session, text, &[])
+        .await?;
+    agent_output::record_event(app, &session, AgentEvent::Prompt { text: prompt });
+    Ok(())
+}
+
+/// Types the `setup` commands and the profile's startup script into the
+/// session's freshly spawned shell, then asks the scheduler to launch (or
+/// resume) the agent, which may queue it. Failures are logged only.
+fn start_session(
+    processes: &ProcessManager,
+    scheduler: &LaunchScheduler,
+    config: &SessionConfig,
+    resume: bool,
+    setup: &[String],
+) {
+    if let Err(e) = run_setup(processes, config, setup) {
+        log::warn!("Failed to run setup commands for session {}: {e}", config.id);
+    }
+    if let Err(e) = run_startup_script(processes, config) {
+        log::warn!("Failed to run startup script for session {}: {e}", config.id);
+    }
+    if let Err(e) = scheduler.request_launch(config.id, resume) {
+        log::warn!("Failed to launch agent for session {}: {e}", config.id);
+    }
+}
+
+fn run_setup(processes: &ProcessManager, config: &SessionConfig, setup: &[String]) -> Result<(), PtyError> {
+    let Some(pty_id) = config.pty_id else {
+        return Ok(());
+    };
+    for command in setup.iter().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+        processes.write_stdin(pty_id, &format!("{command}\n"))?;
+    }
+    Ok(())
+}
+
+fn run_startup_script(processes: &ProcessManager, config: &SessionConfig) -> Result<(), PtyError> {
+    let (Some(pty_id), Some(script)) = (config.pty_id, config.profile.startup_script.as_deref()) else {
+        return Ok(());
+    };
+    if script.trim().is_empty() {
+        return Ok(());
+    }
+    let mut input = script.to_string();
+    if !input.ends_with('\n') {
+        input.push('\n');
+    }
+    processes.write_stdin(pty_id, &input)
+}
+
+/// Tears a session down in one call: kills its shell, optionally removes its
+/// worktree and deletes its branch, then removes the session itself.
+///
+/// Every step is attempted and failures are collected in the returned
+/// report rather than aborting, so the session is always removed. The
+/// worktree is archived before removal, so uncommitted work stays
+/// recoverable, and moved to the trash unless `permanent`. The branch is
+/// deleted without force, so git keeps a branch that is not merged and that
+/// is reported as an error; it is also kept if the worktree could not be
+/// removed, since git refuses to delete a checked-out branch.
+pub async fn destroy_session(
+    worktrees: &WorktreeManager,
+    processes: &ProcessManager,
+    sessions: &SessionManager,
+    session_id: u32,
+    remove_worktree: bool,
+    delete_branch: bool,
+    permanent: bool,
+) -> Result<SessionTeardown, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let mut teardown = SessionTeardown {
+        session_id,
+        pty_killed: false,
+        worktree_removed: false,
+        archive: None,
+        branch_deleted: false,
+        errors: Vec::new(),
+    };
+
+    if let Some(pty_id) = session.pty_id {
+        match processes.kill_session(pty_id).await {
+            Ok(()) => teardown.pty_killed = true,
+            // The shell already exited, which released its PTY
+            Err(e) if matches!(e.code, PtyErrorCode::SessionNotFound) => teardown.pty_killed = true,
+            Err(e) => teardown.errors.push(format!("Failed to kill PTY {pty_id}: {e}")),
+        }
+    }
+
+    let repo_path = match (session.repo(), session.worktree_path.as_deref()) {
+        (Some(repo), _) => Some(repo.to_path_buf()),
+        (None, Some(wt)) => worktrees.repo_of(Path::new(wt)).await,
+        (None, None) => None,
+    };
+    let mut checked_out = session.worktree_path.is_some();
+    if let (true, Some(wt)) = (remove_worktree, session.worktree_path.as_deref()) {
+        match repo_path {
+            Some(ref repo) => match worktrees.remove(repo, Path::new(wt), true, permanent).await {
+                Ok(archive) => {
+                    teardown.worktree_removed = true;
+                    teardown.archive = archive;
+                    checked_out = false;
+                }
+                Err(e) => teardown.errors.push(format!("Failed to remove worktree {wt}: {e}")),
+            },
+            None => teardown.errors.push(format!("Cannot determine the repository of {wt}")),
+        }
+    }
+
+    if let (true, Some(branch)) = (delete_branch, session.branch.as_deref()) {
+        if checked_out {
+            teardown.errors.push(format!("Kept branch {branch}: it is still checked out"));
+        } else if let Some(ref repo) = repo_path {
+            match Git::new(repo).delete_branch(branch, false).await {
+                Ok(()) => teardown.branch_deleted = true,
+                Err(e) => teardown.errors.push(format!("Failed to delete branch {branch}: {e}")),
+            }
+        } else {
+            teardown.errors.push(format!("Cannot delete branch {branch}: repository unknown"));
+        }
+    }
+
+    sessions.remove_session(session_id);
+    for e in &teardown.errors {
+        log::warn!("Teardown of session {session_id}: {e}");
+    }
+    log::info!("Destroyed session {session_id}");
+    Ok(teardown)
+}
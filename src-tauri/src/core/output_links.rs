@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use dashmap::DashMap;
+use regex::Regex;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::process_manager::PtyListener;
+use super::session_manager::{SessionConfig, SessionManager};
+use super::status_inference::{visible_line, ANSI_ESCAPE_PATTERN};
+
+/// Longest line buffered while waiting for its newline; longer ones are
+/// dropped rather than scanned.
+const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Most links emitted per output chunk.
+const MAX_LINKS_PER_CHUNK: usize = 200;
+
+/// URL schemes of OSC 8 hyperlinks that are passed on; others could make
+/// the desktop run arbitrary handlers when clicked.
+const LINK_SCHEMES: &[&str] = &["http://", "https://", "file://"];
+
+/// Named groups of the file reference patterns: the link text, the path,
+/// and the line and column numbers.
+const FILE_REF_GROUPS: [&str; 4] = ["text", "path", "line", "column"];
+
+/// A link found in PTY output. `text` is the text as the terminal shows it,
+/// for the UI to find on screen.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutputLink {
+    /// An OSC 8 hyperlink.
+    Hyperlink { text: String, url: String },
+    /// A reference to an existing file, like `src/main.rs:12:5` in compiler
+    /// output. `path` is absolute, resolved against the session's worktree
+    /// (or repository) if given relative.
+    File {
+        text: String,
+        path: String,
+        line: u32,
+        column: Option<u32>,
+    },
+}
+
+/// Finds clickable links in PTY output, so the frontend does not have to
+/// scan it.
+///
+/// Registered as a `PtyListener`; reassembles the output of every PTY bound
+/// to a session into complete lines and emits the OSC 8 hyperlinks and
+/// `path:line[:column]` references (also Python's `File "path", line N`
+/// and TypeScript's `path(line,col)`) of each chunk on `pty-links-{id}`,
+/// as a list of `OutputLink`s. File references are only reported for files
+/// that exist.
+pub struct OutputLinks {
+    app_handle: AppHandle,
+    ansi: Regex,
+    hyperlink: Regex,
+    file_refs: [Regex; 3],
+    /// Unterminated last line of each PTY's output.
+    partial: DashMap<u32, String>,
+}
+
+impl OutputLinks {
+    pub fn new(app_handle: AppHandle) -> Self {
+        let path = r"(?:~|\.{1,2})?/?(?:[\w.@+\-]+/)*[\w.@+\-]+\.[A-Za-z0-9]+";
+        Self {
+            app_handle,
+            ansi: Regex::new(ANSI_ESCAPE_PATTERN).expect("valid escape pattern"),
+            hyperlink: Regex::new(
+                r"\x1b\]8;[^;\x07\x1b]*;([^\x07\x1b]+)(?:\x07|\x1b\\)(.*?)\x1b\]8;[^;\x07\x1b]*;(?:\x07|\x1b\\)",
+            )
+            .expect("valid hyperlink pattern"),
+            file_refs: [
+                Regex::new(&format!(
+                    r#"(?:^|[\s'"(\[<=])(?P<text>(?P<path>{path}):(?P<line>\d+)(?::(?P<column>\d+))?)"#
+                ))
+                .expect("valid path pattern"),
+                Regex::new(r#"(?P<text>File "(?P<path>[^"]+)", line (?P<line>\d+))"#).expect("valid Python pattern"),
+                Regex::new(&format!(
+                    r"(?:^|[\s'(\[<])(?P<text>(?P<path>{path})\((?P<line>\d+),(?P<column>\d+)\))"
+                ))
+                .expect("valid TypeScript pattern"),
+            ],
+            partial: DashMap::new(),
+        }
+    }
+
+    /// Appends `text` to the PTY's partial line and returns the lines it
+    /// completed.
+    fn complete_lines(&self, pty_id: u32, text: &str) -> Vec<String> {
+        let mut partial = self.partial.entry(pty_id).or_default();
+        partial.push_str(text);
+        let Some(end) = partial.rfind('\n') else {
+            if partial.len() > MAX_LINE_BYTES {
+                partial.clear();
+            }
+            return Vec::new();
+        };
+        let rest = partial.split_off(end + 1);
+        let lines = partial.lines().map(str::to_string).collect();
+        *partial = rest;
+        lines
+    }
+
+    /// Returns the links in one line of raw output.
+    fn links_in(&self, line: &str, dir: Option<&Path>) -> Vec<OutputLink> {
+        let mut links = Vec::new();
+        for caps in self.hyperlink.captures_iter(line) {
+            let url = caps[1].to_string();
+            if LINK_SCHEMES.iter().any(|scheme| url.starts_with(scheme)) {
+                let text = self.ansi.replace_all(&caps[2], "").into_owned();
+                links.push(OutputLink::Hyperlink { text, url });
+            }
+        }
+        let Some(dir) = dir else {
+            return links;
+        };
+        let plain = self.ansi.replace_all(line, "");
+        let plain = visible_line(&plain);
+        for pattern in &self.file_refs {
+            for caps in pattern.captures_iter(plain) {
+                let [text, path, line, column] = FILE_REF_GROUPS.map(|group| caps.name(group).map(|m| m.as_str()));
+                let (Some(text), Some(path), Some(Ok(line))) = (text, path, line.map(str::parse::<u32>)) else {
+                    continue;
+                };
+                let Some(resolved) = resolve(dir, path) else {
+                    continue;
+                };
+                links.push(OutputLink::File {
+                    text: text.to_string(),
+                    path: resolved.to_string_lossy().into_owned(),
+                    line,
+                    column: column.and_then(|c| c.parse().ok()),
+                });
+            }
+        }
+        links
+    }
+}
+
+/// The directory relative paths in a session's output are resolved in.
+fn working_dir(session: &SessionConfig) -> Option<PathBuf> {
+    session
+        .worktree_path
+        .as_deref()
+        .or(Some(session.repo_path.as_str()).filter(|p| !p.is_empty()))
+        .map(PathBuf::from)
+}
+
+/// Returns the absolute path `path` refers to from `dir`, if it is a file.
+fn resolve(dir: &Path, path: &str) -> Option<PathBuf> {
+    let resolved = match path.strip_prefix("~/") {
+        Some(rest) => directories::BaseDirs::new()?.home_dir().join(rest),
+        None => dir.join(path),
+    };
+    resolved.is_file().then_some(resolved)
+}
+
+impl PtyListener for OutputLinks {
+    fn on_output(&self, pty_id: u32, text: &str) {
+        let sessions = self.app_handle.state::<SessionManager>();
+        let Some(session) = sessions.session_for_pty(pty_id) else {
+            return;
+        };
+        let dir = working_dir(&session);
+        let mut links = Vec::new();
+        for line in self.complete_lines(pty_id, text) {
+            links.extend(self.links_in(&line, dir.as_deref()));
+        }
+        if links.is_empty() {
+            return;
+        }
+        links.truncate(MAX_LINKS_PER_CHUNK);
+        let _ = self.app_handle.emit(&format!("pty-links-{pty_id}"), &links);
+    }
+
+    fn on_exit(&self, pty_id: u32, _exit_code: Option<u32>) {
+        self.partial.remove(&pty_id);
+    }
+}
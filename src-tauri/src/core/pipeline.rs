@@ -0,0 +1,72 @@
+use tauri::{AppHandle, Manager};
+
+use super::orchestrator;
+use super::process_manager::ProcessManager;
+use super::scheduler::LaunchScheduler;
+use super::session_manager::{
+    SessionConfig, SessionListener, SessionManager, SessionStatus, StatusCause, StatusTransition,
+};
+use super::worktree_manager::WorktreeManager;
+
+/// Advances session pipelines.
+///
+/// Registered as a `SessionListener`. When a session reaches `Done`, every
+/// `Blocked` session that depends on it is started in the background via
+/// `orchestrator::start_dependent_session`. When a session is removed before
+/// finishing, its blocked dependents can never start and are marked `Error`.
+pub struct PipelineRunner {
+    app_handle: AppHandle,
+}
+
+impl PipelineRunner {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+
+    /// Returns the blocked sessions waiting on `upstream_id`.
+    fn blocked_dependents(&self, upstream_id: u32) -> Vec<u32> {
+        self.app_handle
+            .state::<SessionManager>()
+            .all_sessions()
+            .into_iter()
+            .filter(|s| s.depends_on == Some(upstream_id) && s.status == SessionStatus::Blocked)
+            .map(|s| s.id)
+            .collect()
+    }
+}
+
+impl SessionListener for PipelineRunner {
+    fn on_status_changed(&self, session: &SessionConfig, _transition: &StatusTransition) {
+        if session.status != SessionStatus::Done {
+            return;
+        }
+        for dependent in self.blocked_dependents(session.id) {
+            let app = self.app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = orchestrator::start_dependent_session(
+                    app.clone(),
+                    &app.state::<WorktreeManager>(),
+                    &app.state::<ProcessManager>(),
+                    &app.state::<SessionManager>(),
+                    &app.state::<LaunchScheduler>(),
+                    dependent,
+                )
+                .await;
+            });
+        }
+    }
+
+    fn on_removed(&self, session: &SessionConfig) {
+        if session.status == SessionStatus::Done {
+            return;
+        }
+        let sessions = self.app_handle.state::<SessionManager>();
+        for dependent in self.blocked_dependents(session.id) {
+            log::warn!(
+                "Session {dependent} can no longer start: upstream session {} was removed",
+                session.id
+            );
+            sessions.update_status(dependent, SessionStatus::Error, StatusCause::Scheduled);
+        }
+    }
+}
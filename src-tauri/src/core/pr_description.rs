@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::agent_adapter::AgentEvent;
+use super::error::AppError;
+use super::harvest;
+use super::session_manager::SessionManager;
+use super::storage;
+use super::transcript::TranscriptStore;
+use crate::git::{CommitInfo, DiffSummary, Git};
+
+/// Current on-disk format of `pr-templates.json`.
+const PR_TEMPLATES_VERSION: u32 = 1;
+
+/// Most commits listed in a generated description.
+const MAX_COMMITS: usize = 100;
+
+/// Most changed files listed under the diffstat.
+const MAX_FILES: usize = 50;
+
+/// Template used for repositories without one of their own.
+///
+/// Placeholders, each replaced wherever it appears: `{{task}}` (the task the
+/// session was started with), `{{commits}}` (a list of commit summaries,
+/// oldest first), `{{diffstat}}` (totals and changed files), `{{branch}}`,
+/// `{{base}}` and `{{issue}}` (a closing reference to the session's issue,
+/// empty if it has none). Unknown placeholders are left as they are.
+pub const DEFAULT_PR_TEMPLATE: &str =
+    "## Summary\n\n{{task}}\n\n## Changes\n\n{{commits}}\n\n## Diffstat\n\n{{diffstat}}\n";
+
+/// A generated pull request title and description, to be edited before
+/// being passed to `pull_request::create_pr`. `base` is the ref the branch
+/// was compared with.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PrDraft {
+    pub session_id: u32,
+    pub title: String,
+    pub body: String,
+    pub base: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PrTemplatesFile {
+    version: u32,
+    templates: BTreeMap<String, String>,
+}
+
+/// Pull request description templates per repository, keyed by repository
+/// path and persisted to `pr-templates.json` in the data dir. Placed in
+/// Tauri managed state.
+pub struct PrTemplates {
+    path: PathBuf,
+    templates: Mutex<BTreeMap<String, String>>,
+}
+
+impl PrTemplates {
+    /// Loads the templates from the data dir, starting empty if the file is
+    /// missing or unreadable.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("pr-templates.json");
+        let templates = match storage::load_json::<PrTemplatesFile>(&path) {
+            Ok(Some(file)) if file.version <= PR_TEMPLATES_VERSION => file.templates,
+            Ok(Some(file)) => {
+                log::warn!("Ignoring PR templates with unknown version {}", file.version);
+                BTreeMap::new()
+            }
+            Ok(None) => BTreeMap::new(),
+            Err(e) => {
+                log::error!("Failed to load PR templates {}: {e}", path.display());
+                BTreeMap::new()
+            }
+        };
+        Self {
+            path,
+            templates: Mutex::new(templates),
+        }
+    }
+
+    /// Returns the repository's template, or `DEFAULT_PR_TEMPLATE`.
+    pub fn template(&self, repo_path: &Path) -> String {
+        self.lock()
+            .get(repo_path.to_string_lossy().as_ref())
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_PR_TEMPLATE.to_string())
+    }
+
+    /// Persists the repository's template; `None` or a blank template
+    /// restores the default.
+    pub fn set_template(&self, repo_path: &Path, template: Option<String>) -> io::Result<()> {
+        let mut templates = self.lock();
+        let mut updated = templates.clone();
+        let key = repo_path.to_string_lossy().into_owned();
+        match template.filter(|t| !t.trim().is_empty()) {
+            Some(template) => updated.insert(key, template),
+            None => updated.remove(&key),
+        };
+        let file = PrTemplatesFile {
+            version: PR_TEMPLATES_VERSION,
+            templates: updated,
+        };
+        storage::save_json(&self.path, &file)?;
+        *templates = file.templates;
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, String>> {
+        self.templates.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Fills the repository's template from the session's history: the first
+/// prompt in its transcript (else its title), its commits since the ref
+/// its branch was cut from, and the diffstat against that ref, which counts
+/// uncommitted changes too. The title is the session's title, else the
+/// summary of its only commit, else its branch.
+pub async fn generate_pr_body(app: &AppHandle, session_id: u32) -> Result<PrDraft, AppError> {
+    let session = app
+        .state::<SessionManager>()
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let (Some(repo_path), Some(wt_path), Some(branch)) = (session.repo(), &session.worktree_path, &session.branch) else {
+        return Err(AppError::invalid_argument(format!("Session {session_id} has no worktree branch"))
+            .with("session_id", session_id));
+    };
+    let wt_path = Path::new(wt_path);
+    let base = harvest::base_ref_of(app, &session, wt_path).await?;
+    let git = Git::new(wt_path);
+    let mut commits = git.commits_since(&base, MAX_COMMITS).await?;
+    commits.reverse();
+    let diff = git.diff_against(&base).await?;
+
+    let transcripts = app.state::<TranscriptStore>().inner().clone();
+    let transcript = {
+        let session = session.clone();
+        tokio::task::spawn_blocking(move || transcripts.get(&session))
+            .await
+            .map_err(|e| AppError::from(format!("Failed to read the transcript: {e}")))?
+            .map_err(|e| AppError::from(e).with("session_id", session_id))?
+    };
+    let task = transcript
+        .into_iter()
+        .find_map(|entry| match entry.event {
+            AgentEvent::Prompt { text } => Some(text),
+            _ => None,
+        })
+        .or_else(|| session.title.clone())
+        .unwrap_or_default();
+    let issue = session
+        .issue
+        .as_ref()
+        .map(|issue| match issue.github_reference() {
+            Some(reference) => format!("Closes {reference}"),
+            None => format!("Closes {}", issue.url),
+        })
+        .unwrap_or_default();
+
+    let title = match (&session.title, commits.as_slice()) {
+        (Some(title), _) => title.clone(),
+        (None, [commit]) => commit.summary.clone(),
+        (None, _) => branch.clone(),
+    };
+    let body = app
+        .state::<PrTemplates>()
+        .template(repo_path)
+        .replace("{{task}}", task.trim())
+        .replace("{{commits}}", &commit_list(&commits))
+        .replace("{{diffstat}}", &diffstat(&diff))
+        .replace("{{branch}}", branch)
+        .replace("{{base}}", &base)
+        .replace("{{issue}}", &issue);
+    Ok(PrDraft {
+        session_id,
+        title,
+        body,
+        base,
+    })
+}
+
+fn commit_list(commits: &[CommitInfo]) -> String {
+    if commits.is_empty() {
+        return "No commits yet.".to_string();
+    }
+    let mut list: Vec<String> = commits
+        .iter()
+        .map(|c| format!("- {} ({})", c.summary, c.short_hash))
+        .collect();
+    if commits.len() == MAX_COMMITS {
+        list.push("- …".to_string());
+    }
+    list.join("\n")
+}
+
+fn diffstat(diff: &DiffSummary) -> String {
+    let stat = &diff.stat;
+    let mut lines = vec![format!(
+        "{} files changed, {} insertions(+), {} deletions(-)",
+        stat.files_changed, stat.insertions, stat.deletions
+    )];
+    if !diff.files.is_empty() {
+        lines.push(String::new());
+    }
+    for file in diff.files.iter().take(MAX_FILES) {
+        let counts = match (file.additions, file.deletions) {
+            (Some(added), Some(deleted)) => format!("+{added} -{deleted}"),
+            _ => "binary".to_string(),
+        };
+        lines.push(format!("- `{}` ({counts})", file.path));
+    }
+    if diff.files.len() > MAX_FILES {
+        lines.push(format!("- … and {} more", diff.files.len() - MAX_FILES));
+    }
+    lines.join("\n")
+}
@@ -1,17 +1,20 @@
 #[cfg(not(unix))]
 compile_error!("process_manager requires a Unix platform (Linux/macOS)");
 
+use std::collections::BTreeMap;
 use std::io::{Read, Write};
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread::JoinHandle;
 
 use dashmap::DashMap;
+use futures_util::future::join_all;
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use tauri::{AppHandle, Emitter};
 use tokio::sync::Notify;
 
 use super::error::PtyError;
+use super::metrics;
 
 /// A single PTY session with its associated resources.
 struct PtySession {
@@ -31,9 +34,33 @@ struct PtySession {
     reader_handle: Mutex<Option<JoinHandle<()>>>,
 }
 
+/// Observer of PTY output and exit, registered with `ProcessManager::add_listener`.
+///
+/// Callbacks run on the per-PTY event emitter task, in output order, after the
+/// corresponding `pty-output-{id}` event is emitted. They must not block.
+pub trait PtyListener: Send + Sync {
+    /// Called with each decoded chunk of output.
+    fn on_output(&self, pty_id: u32, text: &str);
+
+    /// Called once when the shell exits on its own. `exit_code` is `None`
+    /// if the status could not be collected. Not called for `kill_session`.
+    fn on_exit(&self, pty_id: u32, exit_code: Option<u32>);
+}
+
+/// Messages from the reader thread to the event emitter task.
+enum PtyEvent {
+    Output(Vec<u8>),
+    Exit(Option<u32>),
+}
+
 struct Inner {
     sessions: DashMap<u32, PtySession>,
     next_id: AtomicU32,
+    listeners: RwLock<Vec<Arc<dyn PtyListener>>>,
+    /// Time `kill_session` waits after SIGTERM before SIGKILL, in ms.
+    kill_grace_ms: AtomicU64,
+    /// Output bytes read from all PTYs but not yet emitted.
+    queued_bytes: AtomicUsize,
 }
 
 /// Owns and manages all PTY sessions for the application lifetime.
@@ -52,6 +79,16 @@ impl Default for ProcessManager {
     }
 }
 
+impl Inner {
+    /// Snapshot of registered listeners, so callbacks run without the lock held.
+    fn listeners(&self) -> Vec<Arc<dyn PtyListener>> {
+        match self.listeners.read() {
+            Ok(listeners) => listeners.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
 impl ProcessManager {
     /// Creates a new manager with no active sessions.
     /// Session IDs start at 1 and increment atomically.
@@ -60,10 +97,34 @@ impl ProcessManager {
             inner: Arc::new(Inner {
                 sessions: DashMap::new(),
                 next_id: AtomicU32::new(1),
+                listeners: RwLock::new(Vec::new()),
+                kill_grace_ms: AtomicU64::new(3000),
+                queued_bytes: AtomicUsize::new(0),
             }),
         }
     }
 
+    /// Returns the output bytes read from all PTYs and still queued for
+    /// emission, which `MemoryBudget` counts against its cap.
+    pub fn queued_output_bytes(&self) -> usize {
+        self.inner.queued_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Sets how long `kill_session` waits for a shell to exit after SIGTERM
+    /// before sending SIGKILL.
+    pub fn set_kill_grace(&self, grace: std::time::Duration) {
+        self.inner.kill_grace_ms.store(grace.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Registers a listener for the output and exit of every PTY, including
+    /// those spawned before registration.
+    pub fn add_listener(&self, listener: Arc<dyn PtyListener>) {
+        match self.inner.listeners.write() {
+            Ok(mut listeners) => listeners.push(listener),
+            Err(poisoned) => poisoned.into_inner().push(listener),
+        }
+    }
+
     /// Spawns a login shell in a new PTY and returns its session ID.
     ///
     /// Uses `$SHELL` (falling back to `/bin/sh`) with `-l` for a login environment.
@@ -72,8 +133,20 @@ impl ProcessManager {
     /// A dedicated OS thread reads PTY output into a bounded 256-slot channel
     /// (~1 MB of 4 KB chunks), and a tokio task drains it into Tauri events
     /// named `pty-output-{id}`. If the channel fills, output is dropped and a
-    /// log message is emitted to make the loss visible.
+    /// log message is emitted to make the loss visible. When the shell exits
+    /// on its own, the reader thread reaps it and `pty-exit-{id}` is emitted
+    /// with the exit code (or `null` if it could not be collected).
     pub fn spawn_shell(&self, app_handle: AppHandle, cwd: Option<String>) -> Result<u32, PtyError> {
+        self.spawn_shell_with_env(app_handle, cwd, &BTreeMap::new())
+    }
+
+    /// Like `spawn_shell`, with `env` set on top of the inherited environment.
+    pub fn spawn_shell_with_env(
+        &self,
+        app_handle: AppHandle,
+        cwd: Option<String>,
+        env: &BTreeMap<String, String>,
+    ) -> Result<u32, PtyError> {
         let id = self
             .inner
             .next_id
@@ -101,8 +174,11 @@ impl ProcessManager {
         if let Some(ref dir) = cwd {
             cmd.cwd(dir);
         }
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
 
-        let child = pair
+        let mut child = pair
             .slave
             .spawn_command(cmd)
             .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn shell: {e}")))?;
@@ -138,12 +214,13 @@ impl ProcessManager {
         // Dedicated OS thread for reading PTY output.
         // Sends data through a bounded mpsc channel (~1 MB of 4 KB chunks) to a
         // tokio task that emits Tauri events.
-        let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<PtyEvent>(256);
 
         // Shutdown mechanism: dropping the master/writer FDs closes the PTY
         // file descriptor, which causes the blocking `reader.read()` call
         // below to return `Ok(0)` (EOF). This is the primary way the reader
         // thread terminates — no explicit signal is needed.
+        let reader_inner = self.inner.clone();
         let reader_handle = std::thread::Builder::new()
             .name(format!("pty-reader-{id}"))
             .spawn(move || {
@@ -154,7 +231,9 @@ impl ProcessManager {
                         Ok(n) => {
                             // blocking_send is used because this is an OS thread, not async.
                             // If the channel is full or closed, we break out of the loop.
-                            if tx.blocking_send(buf[..n].to_vec()).is_err() {
+                            reader_inner.queued_bytes.fetch_add(n, Ordering::Relaxed);
+                            if tx.blocking_send(PtyEvent::Output(buf[..n].to_vec())).is_err() {
+                                reader_inner.queued_bytes.fetch_sub(n, Ordering::Relaxed);
                                 log::warn!(
                                     "PTY reader {id}: channel send failed, dropping {} bytes",
                                     n
@@ -173,6 +252,15 @@ impl ProcessManager {
                         }
                     }
                 }
+                // Reap the shell so it does not linger as a zombie
+                let exit_code = match child.wait() {
+                    Ok(status) => Some(status.exit_code()),
+                    Err(e) => {
+                        log::debug!("PTY reader {id}: failed to collect exit status: {e}");
+                        None
+                    }
+                };
+                let _ = tx.blocking_send(PtyEvent::Exit(exit_code));
                 log::debug!("PTY reader {id} exited");
             })
             .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn reader thread: {e}")))?;
@@ -180,15 +268,35 @@ impl ProcessManager {
         // Tokio task: drain the channel and emit Tauri events
         let event_name = format!("pty-output-{id}");
         let app = app_handle.clone();
+        let inner = self.inner.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
                     data = rx.recv() => {
                         match data {
-                            Some(bytes) => {
+                            Some(PtyEvent::Output(bytes)) => {
+                                inner.queued_bytes.fetch_sub(bytes.len(), Ordering::Relaxed);
                                 // TODO(phase-2): stateful UTF-8 decoder for split multi-byte sequences
                                 let text = String::from_utf8_lossy(&bytes).into_owned();
-                                let _ = app.emit(&event_name, text);
+                                let _ = app.emit(&event_name, &text);
+                                metrics::registry().event("pty-output");
+                                for listener in inner.listeners() {
+                                    listener.on_output(id, &text);
+                                }
+                            }
+                            Some(PtyEvent::Exit(exit_code)) => {
+                                // kill_session removes the entry before signaling;
+                                // a deliberate kill is not reported as an exit
+                                if inner.sessions.remove(&id).is_none() {
+                                    break;
+                                }
+                                log::info!("PTY session {id} exited (code={exit_code:?})");
+                                let _ = app.emit(&format!("pty-exit-{id}"), exit_code);
+                                metrics::registry().event("pty-exit");
+                                for listener in inner.listeners() {
+                                    listener.on_exit(id, exit_code);
+                                }
+                                break;
                             }
                             None => break, // Channel closed
                         }
@@ -198,6 +306,13 @@ impl ProcessManager {
                     }
                 }
             }
+            // Output still queued is dropped; stop counting it
+            rx.close();
+            while let Ok(event) = rx.try_recv() {
+                if let PtyEvent::Output(bytes) = event {
+                    inner.queued_bytes.fetch_sub(bytes.len(), Ordering::Relaxed);
+                }
+            }
             log::debug!("PTY event emitter {id} exited");
         });
 
@@ -219,6 +334,29 @@ impl ProcessManager {
         Ok(id)
     }
 
+    /// Returns the number of PTYs not yet killed or exited.
+    pub fn session_count(&self) -> usize {
+        self.inner.sessions.len()
+    }
+
+    /// Returns `true` if the PTY exists and its shell is still running.
+    pub fn is_running(&self, session_id: u32) -> bool {
+        self.inner.sessions.get(&session_id).is_some_and(|session| {
+            let reader = match session.reader_handle.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            // The reader thread ends once it has reaped the shell
+            reader.as_ref().is_some_and(|handle| !handle.is_finished())
+        })
+    }
+
+    /// Returns `true` if the PTY exists: it has not been killed and its
+    /// shell has not exited (which releases it).
+    pub fn has_session(&self, session_id: u32) -> bool {
+        self.inner.sessions.contains_key(&session_id)
+    }
+
     /// Writes raw bytes to a session's PTY stdin and flushes immediately.
     ///
     /// Acquires the writer mutex; returns `WriteFailed` if the lock is poisoned
@@ -246,6 +384,49 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Returns the process group of the job in the PTY's foreground, such
+    /// as an agent started from the shell, or `None` if the shell itself is
+    /// in the foreground.
+    pub fn foreground_job(&self, session_id: u32) -> Result<Option<i32>, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+        let foreground = session
+            .master
+            .lock()
+            .map_err(|e| PtyError::signal_failed(format!("Master lock poisoned: {e}")))?
+            .process_group_leader();
+        Ok(foreground.filter(|&pgid| pgid != session.pgid))
+    }
+
+    /// Stops the job in the PTY's foreground (see `foreground_job`) with
+    /// SIGSTOP and returns its process group, or `None` if the shell itself
+    /// is in the foreground. With job control the shell reports the job as
+    /// stopped and shows its prompt; `fg` resumes it.
+    pub fn stop_foreground_job(&self, session_id: u32) -> Result<Option<i32>, PtyError> {
+        let Some(pgid) = self.foreground_job(session_id)? else {
+            return Ok(None);
+        };
+        signal_group(session_id, pgid, libc::SIGSTOP)?;
+        Ok(Some(pgid))
+    }
+
+    /// Sends `signal` to a job of the PTY, as returned by
+    /// `stop_foreground_job`. The shell's own group is refused.
+    pub fn signal_job(&self, session_id: u32, pgid: i32, signal: i32) -> Result<(), PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+        if pgid <= 0 || pgid == session.pgid {
+            return Err(PtyError::signal_failed(format!("{pgid} is not a job of session {session_id}")));
+        }
+        signal_group(session_id, pgid, signal)
+    }
+
     /// Resizes the PTY to the given dimensions, propagating SIGWINCH to the child.
     ///
     /// Pixel dimensions are always set to 0 (unused by terminal emulators).
@@ -277,8 +458,9 @@ impl ProcessManager {
     /// Terminates a PTY session with graceful escalation.
     ///
     /// Sends SIGTERM to the entire process group (via negative PGID), waits up
-    /// to 3 seconds for the lead process to exit, then escalates to SIGKILL if
-    /// it is still alive. After signaling, drops the master/writer FDs to EOF
+    /// to the kill grace period (3 seconds by default, see `set_kill_grace`)
+    /// for the lead process to exit, then escalates to SIGKILL if it is still
+    /// alive. After signaling, drops the master/writer FDs to EOF
     /// the reader thread, notifies the tokio event emitter to shut down, and
     /// joins the reader thread via `spawn_blocking` to avoid blocking the
     /// async runtime. The session is removed from the map before signaling,
@@ -303,8 +485,9 @@ impl ProcessManager {
             );
         }
 
-        // Wait up to 3 seconds for the lead process to exit
-        let exited = tokio::time::timeout(std::time::Duration::from_secs(3), async {
+        // Wait up to the grace period for the lead process to exit
+        let grace = std::time::Duration::from_millis(self.inner.kill_grace_ms.load(Ordering::Relaxed));
+        let exited = tokio::time::timeout(grace, async {
             loop {
                 let result = unsafe { libc::kill(pid, 0) };
                 if result != 0 {
@@ -350,4 +533,25 @@ impl ProcessManager {
         log::info!("Killed PTY session {session_id}");
         Ok(())
     }
+
+    /// Terminates every PTY session at once, each as `kill_session` does,
+    /// so shutting down waits for one grace period at most.
+    pub async fn kill_all(&self) {
+        let ids: Vec<u32> = self.inner.sessions.iter().map(|entry| *entry.key()).collect();
+        for result in join_all(ids.into_iter().map(|id| self.kill_session(id))).await {
+            if let Err(e) = result {
+                log::warn!("{e}");
+            }
+        }
+    }
+}
+
+fn signal_group(session_id: u32, pgid: i32, signal: i32) -> Result<(), PtyError> {
+    if unsafe { libc::kill(-pgid, signal) } != 0 {
+        return Err(PtyError::signal_failed(format!(
+            "Failed to signal job {pgid} of session {session_id}: {}",
+            std::io::Error::last_os_error()
+        )));
+    }
+    Ok(())
 }
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::agent_adapter::{AgentEvent, ThrottleReason};
+use super::clock;
+use super::scheduler::LaunchScheduler;
+use super::session_manager::{AiMode, SessionConfig};
+use crate::agent;
+
+/// Cooldown after a first rate-limit error, doubled for each one that
+/// follows within `STRIKE_WINDOW_SECS`.
+const RATE_LIMIT_COOLDOWN_SECS: u64 = 60;
+
+/// Cooldown after a first overload error, doubled like rate limits.
+const OVERLOADED_COOLDOWN_SECS: u64 = 30;
+
+/// Cooldown after a usage limit or quota message, which usually lasts
+/// until a reset hours away; the message's own delay wins if it has one.
+const QUOTA_COOLDOWN_SECS: u64 = 30 * 60;
+
+/// Longest cooldown, however many errors pile up.
+const MAX_COOLDOWN_SECS: u64 = 60 * 60;
+
+/// Throttles further apart than this start the backoff over.
+const STRIKE_WINDOW_SECS: i64 = 10 * 60;
+
+/// A session repeating the same error within this many seconds is counted
+/// once; agent UIs often redraw an error line several times.
+const REPEAT_WINDOW_SECS: i64 = 15;
+
+/// The most recent throttling error of a provider.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ThrottleRecord {
+    pub reason: ThrottleReason,
+    pub message: String,
+    pub session_id: u32,
+    pub at: i64,
+}
+
+/// Usage of one agent provider since Maestro started, as reported by its
+/// sessions' output. `cooldown_until` is set while new launches of the
+/// provider's agent are held back.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct ProviderUsage {
+    pub provider: String,
+    pub runs: u64,
+    pub cost_usd: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub rate_limited: u64,
+    pub overloaded: u64,
+    pub quota_exceeded: u64,
+    pub last_throttle: Option<ThrottleRecord>,
+    pub cooldown_until: Option<i64>,
+}
+
+#[derive(Default)]
+struct ProviderState {
+    usage: ProviderUsage,
+    /// Throttles in the current backoff streak.
+    strikes: u32,
+    /// Last throttle reported by each session, for deduplication.
+    last_by_session: HashMap<u32, (ThrottleReason, i64)>,
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    providers: Mutex<HashMap<&'static str, ProviderState>>,
+}
+
+/// Aggregates cost, token usage, and throttling errors per agent provider
+/// and decides when a provider needs a break.
+///
+/// Fed by `agent_output::record_event`. A rate-limit, overload, or quota
+/// error puts the provider into a cooldown, doubling with each further
+/// error in a streak, during which `LaunchScheduler` queues new launches
+/// of its agent instead of starting them; the queue drains when the
+/// cooldown ends. Running agents are left alone, since their CLIs retry on
+/// their own. Changes are emitted on `provider-usage-changed`. Cheap to
+/// clone; placed in Tauri managed state.
+#[derive(Clone)]
+pub struct UsageTracker {
+    inner: Arc<Inner>,
+}
+
+impl UsageTracker {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                providers: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Returns the usage of every provider that has reported any, by name.
+    pub fn usage(&self) -> Vec<ProviderUsage> {
+        let now = clock::now_secs();
+        let mut usage: Vec<ProviderUsage> = self
+            .lock()
+            .values()
+            .map(|state| snapshot(&state.usage, now))
+            .collect();
+        usage.sort_by(|a, b| a.provider.cmp(&b.provider));
+        usage
+    }
+
+    /// Returns `true` if new launches of the mode's agent should wait.
+    pub fn is_cooling_down(&self, mode: &AiMode) -> bool {
+        let Some(adapter) = agent::registry().get(mode) else {
+            return false;
+        };
+        self.lock()
+            .get(adapter.name())
+            .and_then(|state| state.usage.cooldown_until)
+            .is_some_and(|until| until > clock::now_secs())
+    }
+
+    /// Counts a cost, usage, or throttling event of the session's agent.
+    /// Other events are ignored.
+    pub fn record(&self, session: &SessionConfig, event: &AgentEvent) {
+        let Some(provider) = agent::registry().get(&session.mode).map(|a| a.name()) else {
+            return;
+        };
+        let now = clock::now_secs();
+        let mut cooldown = None;
+        let usage = {
+            let mut providers = self.lock();
+            let state = providers.entry(provider).or_insert_with(|| ProviderState {
+                usage: ProviderUsage {
+                    provider: provider.to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            match event {
+                AgentEvent::Cost { usd } => {
+                    state.usage.runs += 1;
+                    state.usage.cost_usd += usd;
+                }
+                AgentEvent::Usage {
+                    input_tokens,
+                    output_tokens,
+                } => {
+                    state.usage.input_tokens += input_tokens;
+                    state.usage.output_tokens += output_tokens;
+                }
+                AgentEvent::Throttled {
+                    reason,
+                    message,
+                    retry_after_secs,
+                } => {
+                    let repeated = state
+                        .last_by_session
+                        .get(&session.id)
+                        .is_some_and(|&(last, at)| last == *reason && now - at < REPEAT_WINDOW_SECS);
+                    if repeated {
+                        return;
+                    }
+                    state.last_by_session.insert(session.id, (*reason, now));
+                    let secs = state.throttle(session.id, *reason, message, *retry_after_secs, now);
+                    log::warn!("{provider} is throttling ({reason:?}); holding new launches for {secs}s");
+                    cooldown = Some(secs);
+                }
+                _ => return,
+            }
+            snapshot(&state.usage, now)
+        };
+
+        let app = &self.inner.app_handle;
+        let _ = app.emit("provider-usage-changed", &usage);
+        if let Some(secs) = cooldown {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(secs)).await;
+                let tracker = app.state::<UsageTracker>();
+                let usage = tracker.usage().into_iter().find(|u| u.provider == provider);
+                if let Some(usage) = usage.filter(|u| u.cooldown_until.is_none()) {
+                    let _ = app.emit("provider-usage-changed", &usage);
+                    app.state::<LaunchScheduler>().drain();
+                }
+            });
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<&'static str, ProviderState>> {
+        match self.inner.providers.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl ProviderState {
+    /// Counts a throttle, extends the cooldown, and returns its length.
+    fn throttle(
+        &mut self,
+        session_id: u32,
+        reason: ThrottleReason,
+        message: &str,
+        retry_after_secs: Option<u64>,
+        now: i64,
+    ) -> u64 {
+        let usage = &mut self.usage;
+        match reason {
+            ThrottleReason::RateLimit => usage.rate_limited += 1,
+            ThrottleReason::Overloaded => usage.overloaded += 1,
+            ThrottleReason::Quota => usage.quota_exceeded += 1,
+        }
+        let in_streak = usage
+            .last_throttle
+            .as_ref()
+            .is_some_and(|last| now - last.at < STRIKE_WINDOW_SECS);
+        self.strikes = if in_streak { self.strikes + 1 } else { 1 };
+
+        let base = match reason {
+            ThrottleReason::RateLimit => RATE_LIMIT_COOLDOWN_SECS,
+            ThrottleReason::Overloaded => OVERLOADED_COOLDOWN_SECS,
+            ThrottleReason::Quota => QUOTA_COOLDOWN_SECS,
+        };
+        let secs = retry_after_secs
+            .unwrap_or_else(|| base.saturating_mul(1 << (self.strikes - 1).min(16)))
+            .min(MAX_COOLDOWN_SECS);
+        let until = now + secs as i64;
+        usage.cooldown_until = Some(usage.cooldown_until.map_or(until, |current| current.max(until)));
+        usage.last_throttle = Some(ThrottleRecord {
+            reason,
+            message: message.to_string(),
+            session_id,
+            at: now,
+        });
+        (usage.cooldown_until.unwrap_or(until) - now) as u64
+    }
+}
+
+/// Copies `usage`, dropping a cooldown that has already ended.
+fn snapshot(usage: &ProviderUsage, now: i64) -> ProviderUsage {
+    let mut usage = usage.clone();
+    usage.cooldown_until = usage.cooldown_until.filter(|&until| until > now);
+    usage
+}
@@ -0,0 +1,581 @@
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::ci_checks::{self, CheckState};
+use super::error::AppError;
+use super::forks::{self, FORK_REMOTE};
+use super::session_manager::SessionManager;
+use super::worktree_manager::WorktreeManager;
+use crate::git::Git;
+
+/// Longest a `gh` or `glab` command may run, including its API calls.
+const FORGE_CLI_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Most pull requests `list_prs` returns, newest first.
+const MAX_LISTED_PRS: u32 = 200;
+
+/// Fields requested from `gh pr list --json`; see `GhPullRequest`.
+const PR_LIST_FIELDS: &str = concat!(
+    "number,title,url,headRefName,headRepositoryOwner,baseRefName,author,state,isDraft,",
+    "statusCheckRollup,reviewDecision"
+);
+
+/// A GitHub pull request for a session's branch. `existing` is `true` if
+/// the branch already had an open pull request, which was returned instead
+/// of a new one.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PullRequest {
+    pub session_id: u32,
+    pub number: u64,
+    pub url: String,
+    pub branch: String,
+    pub draft: bool,
+    pub existing: bool,
+}
+
+/// Which pull requests `list_prs` returns.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PrState {
+    #[default]
+    Open,
+    Closed,
+    Merged,
+    All,
+}
+
+impl PrState {
+    fn as_arg(self) -> &'static str {
+        match self {
+            PrState::Open => "open",
+            PrState::Closed => "closed",
+            PrState::Merged => "merged",
+            PrState::All => "all",
+        }
+    }
+}
+
+/// Combined result of a pull request's or branch's checks and commit
+/// statuses; see `ci_checks::roll_up`. `None` if it has none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    None,
+    Pending,
+    Passing,
+    Failing,
+}
+
+/// Review decision of a pull request. `None` if the repository requires
+/// no reviews and none were given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewState {
+    None,
+    ReviewRequired,
+    Approved,
+    ChangesRequested,
+}
+
+/// A pull request of a repository, as listed by `list_prs`. `state` is
+/// `open`, `closed`, or `merged`. `session_id` is the session working on
+/// `branch`, if any.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PullRequestSummary {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub branch: String,
+    pub base: String,
+    pub author: String,
+    pub state: String,
+    pub draft: bool,
+    pub ci: CiStatus,
+    pub review: ReviewState,
+    pub session_id: Option<u32>,
+}
+
+/// One entry of `gh pr list --json`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhPullRequest {
+    number: u64,
+    title: String,
+    url: String,
+    head_ref_name: String,
+    #[serde(default)]
+    head_repository_owner: Option<GhAuthor>,
+    base_ref_name: String,
+    author: Option<GhAuthor>,
+    state: String,
+    is_draft: bool,
+    #[serde(default)]
+    status_check_rollup: Option<Vec<GhCheck>>,
+    #[serde(default)]
+    review_decision: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GhAuthor {
+    login: String,
+}
+
+/// A check run (`status`, `conclusion`) or a commit status (`state`).
+#[derive(Deserialize)]
+struct GhCheck {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+/// Lists the repository's pull requests in `state` with the GitHub CLI,
+/// newest first (the upstream project's, in a fork), and matches their
+/// branches to the repository's sessions, so branches without one can be
+/// told apart. Only pull requests from `origin`'s repository are matched:
+/// another fork's branch of the same name is not the session's.
+pub async fn list_prs(
+    sessions: &SessionManager,
+    repo_path: &Path,
+    state: PrState,
+) -> Result<Vec<PullRequestSummary>, String> {
+    let limit = MAX_LISTED_PRS.to_string();
+    let mut args = vec![
+        "pr", "list", "--state", state.as_arg(), "--limit", &limit, "--json", PR_LIST_FIELDS,
+    ];
+    let upstream = forks::upstream_slug(repo_path).await;
+    if let Some(slug) = &upstream {
+        args.extend(["--repo", slug]);
+    }
+    let stdout = gh(repo_path, &args).await?;
+    let listed: Vec<GhPullRequest> =
+        serde_json::from_str(&stdout).map_err(|e| format!("Unexpected output from gh pr list: {e}"))?;
+
+    let own_owner = Git::new(repo_path)
+        .remote_url(FORK_REMOTE)
+        .await
+        .ok()
+        .and_then(|url| forks::github_slug(&url))
+        .map(|(owner, _)| owner);
+    let repo_sessions = sessions.sessions_in_repo(repo_path);
+    Ok(listed
+        .into_iter()
+        .map(|pr| PullRequestSummary {
+            session_id: repo_sessions
+                .iter()
+                .filter(|_| match (&own_owner, &pr.head_repository_owner) {
+                    (Some(own), Some(head)) => head.login.eq_ignore_ascii_case(own),
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                })
+                .find(|s| s.branch.as_deref() == Some(pr.head_ref_name.as_str()))
+                .map(|s| s.id),
+            ci: ci_status(pr.status_check_rollup.as_deref().unwrap_or_default()),
+            review: match pr.review_decision.as_deref() {
+                Some("APPROVED") => ReviewState::Approved,
+                Some("CHANGES_REQUESTED") => ReviewState::ChangesRequested,
+                Some("REVIEW_REQUIRED") => ReviewState::ReviewRequired,
+                _ => ReviewState::None,
+            },
+            number: pr.number,
+            title: pr.title,
+            url: pr.url,
+            branch: pr.head_ref_name,
+            base: pr.base_ref_name,
+            author: pr.author.map(|a| a.login).unwrap_or_default(),
+            state: pr.state.to_lowercase(),
+            draft: pr.is_draft,
+        })
+        .collect())
+}
+
+fn ci_status(checks: &[GhCheck]) -> CiStatus {
+    ci_checks::roll_up(checks.iter().map(|check| {
+        match (check.status.as_deref(), check.conclusion.as_deref().or(check.state.as_deref())) {
+            (Some(status), _) if status != "COMPLETED" => CheckState::Pending,
+            (_, Some("SUCCESS")) => CheckState::Passed,
+            (_, Some("NEUTRAL" | "SKIPPED" | "STALE")) => CheckState::Skipped,
+            (_, Some("PENDING" | "EXPECTED") | None) => CheckState::Pending,
+            _ => CheckState::Failed,
+        }
+    }))
+}
+
+/// Relations to the repository whose feedback `feedback_prompt` passes on
+/// to the agent, as GitHub's `authorAssociation` names them.
+const TRUSTED_ASSOCIATIONS: &[&str] = &["OWNER", "MEMBER", "COLLABORATOR"];
+
+/// One comment of a review thread or of a review's summary.
+/// `author_association` is the author's relation to the repository, as
+/// GitHub reports it (`OWNER`, `MEMBER`, `COLLABORATOR`, `CONTRIBUTOR`,
+/// `NONE`, ...).
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PrComment {
+    #[serde(deserialize_with = "login")]
+    pub author: String,
+    #[serde(alias = "authorAssociation", default)]
+    pub author_association: String,
+    pub body: String,
+    #[serde(alias = "createdAt")]
+    pub created_at: String,
+    pub url: String,
+}
+
+/// A conversation anchored to a diff line. `line` is the line in the
+/// current version of `path` (`None` once the thread is outdated) and
+/// `original_line` the one it was written against; a multi-line comment
+/// starts at `start_line`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ReviewThread {
+    pub id: String,
+    pub path: String,
+    pub line: Option<u32>,
+    pub original_line: Option<u32>,
+    pub start_line: Option<u32>,
+    pub resolved: bool,
+    pub outdated: bool,
+    pub comments: Vec<PrComment>,
+}
+
+/// A submitted review with a summary. `state` is `approved`,
+/// `changes_requested`, or `commented`; `author_association` is as for
+/// `PrComment`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PrReview {
+    pub author: String,
+    pub author_association: String,
+    pub state: String,
+    pub body: String,
+    pub submitted_at: Option<String>,
+}
+
+/// Reviewer feedback on a pull request, returned by `get_pr_comments`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PrFeedback {
+    pub number: u64,
+    pub threads: Vec<ReviewThread>,
+    pub reviews: Vec<PrReview>,
+}
+
+/// Query for `get_pr_comments`. Limits are the most GitHub returns per
+/// page; longer discussions are cut off.
+const PR_FEEDBACK_QUERY: &str = "\
+query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          id isResolved isOutdated path line originalLine startLine
+          comments(first: 100) { nodes { author { login } authorAssociation body createdAt url } }
+        }
+      }
+      reviews(first: 100) {
+        nodes { author { login } authorAssociation state body submittedAt }
+      }
+    }
+  }
+}";
+
+#[derive(Deserialize)]
+struct GhFeedbackResponse {
+    data: GhFeedbackData,
+}
+
+#[derive(Deserialize)]
+struct GhFeedbackData {
+    repository: GhFeedbackRepository,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhFeedbackRepository {
+    pull_request: Option<GhFeedbackPullRequest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhFeedbackPullRequest {
+    review_threads: GhNodes<GhReviewThread>,
+    reviews: GhNodes<GhReview>,
+}
+
+#[derive(Deserialize)]
+struct GhNodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThread {
+    id: String,
+    is_resolved: bool,
+    is_outdated: bool,
+    path: String,
+    line: Option<u32>,
+    original_line: Option<u32>,
+    start_line: Option<u32>,
+    comments: GhNodes<PrComment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReview {
+    #[serde(deserialize_with = "login")]
+    author: String,
+    author_association: String,
+    state: String,
+    body: String,
+    submitted_at: Option<String>,
+}
+
+/// Reads an `author { login }` object, which is `null` for deleted users.
+fn login<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<GhAuthor>::deserialize(deserializer)?
+        .map(|a| a.login)
+        .unwrap_or_else(|| "ghost".to_string()))
+}
+
+/// Fetches the review threads, with their file and line anchors, and the
+/// review summaries of a pull request in the repository's GitHub remote.
+pub async fn get_pr_comments(repo_path: &Path, pr_number: u64) -> Result<PrFeedback, String> {
+    let query = format!("query={PR_FEEDBACK_QUERY}");
+    let number = format!("number={pr_number}");
+    // gh fills in `{owner}` and `{repo}` from `origin`
+    let (owner, name) = match forks::upstream_slug(repo_path).await.and_then(|slug| {
+        slug.split_once('/').map(|(o, r)| (o.to_string(), r.to_string()))
+    }) {
+        Some((owner, repo)) => (format!("owner={owner}"), format!("name={repo}")),
+        None => ("owner={owner}".to_string(), "name={repo}".to_string()),
+    };
+    let args = [
+        "api", "graphql", "-f", &query, "-F", &owner, "-F", &name, "-F", &number,
+    ];
+    let stdout = gh(repo_path, &args).await?;
+    let response: GhFeedbackResponse =
+        serde_json::from_str(&stdout).map_err(|e| format!("Unexpected review data from GitHub: {e}"))?;
+    let pr = response
+        .data
+        .repository
+        .pull_request
+        .ok_or_else(|| format!("Pull request #{pr_number} not found"))?;
+
+    Ok(PrFeedback {
+        number: pr_number,
+        threads: pr
+            .review_threads
+            .nodes
+            .into_iter()
+            .map(|t| ReviewThread {
+                id: t.id,
+                path: t.path,
+                line: t.line,
+                original_line: t.original_line,
+                start_line: t.start_line,
+                resolved: t.is_resolved,
+                outdated: t.is_outdated,
+                comments: t.comments.nodes,
+            })
+            .collect(),
+        reviews: pr
+            .reviews
+            .nodes
+            .into_iter()
+            .filter(|r| !r.body.trim().is_empty())
+            .map(|r| PrReview {
+                author: r.author,
+                author_association: r.author_association,
+                state: r.state.to_lowercase(),
+                body: r.body,
+                submitted_at: r.submitted_at,
+            })
+            .collect(),
+    })
+}
+
+/// Writes reviewer feedback as a follow-up task for the agent that made the
+/// changes: review summaries, then every unresolved thread (all threads
+/// with `include_resolved`) with its file and line. Returns `None` if
+/// there is nothing to address.
+///
+/// Only feedback from the repository's owners, members, and collaborators
+/// is included: anyone can comment on a public pull request, and the agent
+/// would follow their instructions.
+pub fn feedback_prompt(feedback: &PrFeedback, include_resolved: bool) -> Option<String> {
+    let trusted = |association: &str| TRUSTED_ASSOCIATIONS.contains(&association);
+    let threads: Vec<(&ReviewThread, Vec<&PrComment>)> = feedback
+        .threads
+        .iter()
+        .filter(|t| include_resolved || !t.resolved)
+        .map(|t| (t, t.comments.iter().filter(|c| trusted(&c.author_association)).collect::<Vec<_>>()))
+        .filter(|(_, comments)| !comments.is_empty())
+        .collect();
+    let reviews: Vec<&PrReview> = feedback
+        .reviews
+        .iter()
+        .filter(|r| r.state != "approved" && trusted(&r.author_association))
+        .collect();
+    if threads.is_empty() && reviews.is_empty() {
+        return None;
+    }
+
+    let mut prompt = format!(
+        "Address the review feedback on pull request #{}, then commit and push the fixes.\n",
+        feedback.number
+    );
+    for review in reviews {
+        prompt.push_str(&format!("\nReview by {} ({}):\n{}\n", review.author, review.state, review.body.trim()));
+    }
+    for (thread, comments) in threads {
+        let anchor = match (thread.start_line, thread.line.or(thread.original_line)) {
+            (Some(start), Some(end)) if start != end => format!("{}:{start}-{end}", thread.path),
+            (_, Some(line)) => format!("{}:{line}", thread.path),
+            _ => thread.path.clone(),
+        };
+        prompt.push_str(&format!("\n{anchor}\n"));
+        for comment in comments {
+            prompt.push_str(&format!("- {}: {}\n", comment.author, comment.body.trim()));
+        }
+    }
+    Some(prompt)
+}
+
+/// Pushes the session's worktree branch to `origin` and opens a pull
+/// request for it with the GitHub CLI (`gh`), which must be installed and
+/// logged in. In a fork with an `upstream` remote, the pull request targets
+/// the upstream project; see `forks`. `base` defaults to the target's
+/// default branch. If the session was created from a GitHub issue, the
+/// body closes it. The pull request's number and URL are stored on the
+/// session.
+pub async fn create_pr(
+    worktrees: &WorktreeManager,
+    sessions: &SessionManager,
+    session_id: u32,
+    title: &str,
+    body: &str,
+    draft: bool,
+    base: Option<&str>,
+) -> Result<PullRequest, AppError> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let (Some(repo_path), Some(wt_path), Some(branch)) = (session.repo(), &session.worktree_path, &session.branch) else {
+        return Err(AppError::invalid_argument(format!("Session {session_id} has no worktree branch"))
+            .with("session_id", session_id));
+    };
+    if title.trim().is_empty() {
+        return Err(AppError::invalid_argument("title must not be empty"));
+    }
+
+    let push = worktrees
+        .publish(repo_path, Path::new(wt_path), FORK_REMOTE, false)
+        .await?;
+    if push.rejected {
+        return Err(format!("Push of {branch} to {FORK_REMOTE} was rejected: {}", push.summary).into());
+    }
+
+    // From a fork, the pull request goes to the upstream project and names
+    // the fork's owner with the branch
+    let upstream = forks::upstream_slug(repo_path).await;
+    let mut head = branch.clone();
+    if upstream.is_some() {
+        let origin = Git::new(repo_path).remote_url(FORK_REMOTE).await?;
+        if let Some((owner, _)) = forks::github_slug(&origin) {
+            head = format!("{owner}:{branch}");
+        }
+    }
+
+    let mut body = body.to_string();
+    if let Some(reference) = session.issue.as_ref().and_then(|issue| issue.github_reference()) {
+        if !body.contains(&reference) {
+            body = format!("{}\n\nCloses {reference}", body.trim_end()).trim_start().to_string();
+        }
+    }
+    let mut args = vec!["pr", "create", "--head", &head, "--title", title, "--body", &body];
+    if draft {
+        args.push("--draft");
+    }
+    if let Some(slug) = &upstream {
+        args.extend(["--repo", slug]);
+    }
+    if let Some(base) = base.filter(|b| !b.is_empty()) {
+        args.extend(["--base", base]);
+    }
+    let (url, existing) = match gh(Path::new(wt_path), &args).await {
+        Ok(stdout) => (find_pr_url(&stdout), false),
+        // gh names the open pull request of the branch in its error
+        Err(e) if e.contains("already exists") => (find_pr_url(&e), true),
+        Err(e) => return Err(e.into()),
+    };
+    let url = url.ok_or("gh did not print the pull request URL")?;
+    let number = url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| format!("Unexpected pull request URL from gh: {url}"))?;
+
+    sessions.modify(session_id, |session| {
+        session.pr_number = Some(number);
+        session.pr_url = Some(url.clone());
+    });
+    log::info!("Session {session_id}: pull request #{number} for {branch}");
+    Ok(PullRequest {
+        session_id,
+        number,
+        url,
+        branch: branch.clone(),
+        draft,
+        existing,
+    })
+}
+
+/// Runs `gh` in `dir`; see `forge_cli`.
+pub(super) async fn gh(dir: &Path, args: &[&str]) -> Result<String, String> {
+    forge_cli("gh", dir, args).await
+}
+
+/// Runs a code forge's CLI (`gh` or `glab`) in `dir` with prompts disabled
+/// and returns its output if it exited successfully. On failure, the error
+/// carries the CLI's stderr for callers to inspect.
+pub(super) async fn forge_cli(program: &str, dir: &Path, args: &[&str]) -> Result<String, String> {
+    let child = tokio::process::Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .env("GH_PROMPT_DISABLED", "1")
+        .env("NO_PROMPT", "1")
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => format!("{program} is not installed"),
+            _ => format!("Failed to run {program}: {e}"),
+        })?;
+    // Dropping the future kills the process
+    let output = tokio::time::timeout(FORGE_CLI_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("{program} timed out after {}s", FORGE_CLI_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("Failed to run {program}: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let command = args.iter().take(2).copied().collect::<Vec<_>>().join(" ");
+        return Err(format!("{program} {command} failed: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the first pull request URL in `text`.
+fn find_pr_url(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .find(|word| word.starts_with("https://") && word.contains("/pull/"))
+        .map(str::to_string)
+}
@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use super::agent_adapter::AgentEvent;
+use super::agent_launcher::AgentLauncher;
+use super::agent_output;
+use super::orchestrator::{self, BranchSpec};
+use super::process_manager::ProcessManager;
+use super::repo_config;
+use super::scheduler::LaunchScheduler;
+use super::scrollback::Scrollback;
+use super::session_manager::{AiMode, LaunchProfile, SessionConfig, SessionManager};
+use super::worktree_manager::WorktreeManager;
+
+/// Output returned by `read_session_output` when no limit is given.
+const DEFAULT_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// Arguments of `list_sessions`.
+#[derive(Deserialize)]
+pub struct ListSessionsArgs {
+    pub repo_path: Option<String>,
+}
+
+/// Arguments of `create_session`. `agent` is `claude` (the default),
+/// `gemini`, `codex`, `plain`, or a custom agent's name. Without `base_ref`
+/// an existing `branch` is checked out, suffixed if it is in use; with it,
+/// `branch` is created from `base_ref`.
+#[derive(Deserialize)]
+pub struct CreateSessionArgs {
+    pub repo_path: String,
+    pub branch: String,
+    pub base_ref: Option<String>,
+    pub agent: Option<String>,
+    pub prompt: Option<String>,
+}
+
+/// Arguments of `read_session_output`.
+#[derive(Deserialize)]
+pub struct ReadOutputArgs {
+    pub session_id: u32,
+    pub max_bytes: Option<usize>,
+}
+
+/// Arguments of `send_prompt`.
+#[derive(Deserialize)]
+pub struct SendPromptArgs {
+    pub session_id: u32,
+    pub text: String,
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// Deserializes the JSON arguments of an operation for remote clients (the
+/// MCP server and the HTTP API); a missing object counts as empty.
+pub fn parse_args<T: for<'de> Deserialize<'de>>(arguments: Value) -> Result<T, String> {
+    let arguments = if arguments.is_null() {
+        json!({})
+    } else {
+        arguments
+    };
+    serde_json::from_value(arguments).map_err(|e| format!("Invalid arguments: {e}"))
+}
+
+fn to_value(value: &impl serde::Serialize) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+/// Returns all sessions, or those of `repo_path`, in display order.
+pub fn list_sessions(app: &AppHandle, args: ListSessionsArgs) -> Result<Value, String> {
+    let sessions = app.state::<SessionManager>();
+    let list = match args.repo_path {
+        Some(repo_path) => sessions.sessions_in_repo(Path::new(&repo_path)),
+        None => sessions.all_sessions(),
+    };
+    to_value(&list)
+}
+
+/// Returns one session.
+pub fn get_session(app: &AppHandle, session_id: u32) -> Result<Value, String> {
+    let session = app
+        .state::<SessionManager>()
+        .get_session(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    to_value(&session)
+}
+
+/// Creates a session as `orchestrator::create_full_session` does and, if
+/// given, sends `prompt` to its agent once it is ready.
+pub async fn create_session(app: &AppHandle, args: CreateSessionArgs) -> Result<Value, String> {
+    to_value(&start_session(app, args).await?)
+}
+
+/// Like `create_session`, returning the session itself.
+pub async fn start_session(app: &AppHandle, args: CreateSessionArgs) -> Result<SessionConfig, String> {
+    if args.repo_path.is_empty() {
+        return Err("repo_path must not be empty".to_string());
+    }
+    let agent = args
+        .agent
+        .clone()
+        .unwrap_or_else(|| repo_config::default_agent(Path::new(&args.repo_path)));
+    let mode = agent_mode(app, &agent)?;
+    if args.prompt.is_some() && mode == AiMode::Plain {
+        return Err("A plain session has no agent to send the prompt to".to_string());
+    }
+    let branch_spec = match args.base_ref {
+        Some(base_ref) => BranchSpec::New {
+            branch: args.branch,
+            base_ref,
+        },
+        None => BranchSpec::Unique {
+            branch: args.branch,
+        },
+    };
+    let session = orchestrator::create_full_session(
+        app.clone(),
+        &app.state::<WorktreeManager>(),
+        &app.state::<ProcessManager>(),
+        &app.state::<SessionManager>(),
+        &app.state::<LaunchScheduler>(),
+        Path::new(&args.repo_path),
+        branch_spec,
+        mode,
+        LaunchProfile::default(),
+        None,
+    )
+    .await?;
+    if let Some(prompt) = args.prompt {
+        let app = app.clone();
+        let session_id = session.id;
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = orchestrator::send_prompt_when_ready(&app, session_id, &prompt).await {
+                log::warn!("Failed to send remote prompt to session {session_id}: {e}");
+            }
+        });
+    }
+    Ok(session)
+}
+
+/// Returns the mode of the agent remote clients call `name`: `claude`,
+/// `gemini`, `codex`, `plain`, or a custom agent's name.
+pub fn agent_mode(app: &AppHandle, name: &str) -> Result<AiMode, String> {
+    match name {
+        "claude" => Ok(AiMode::Claude),
+        "gemini" => Ok(AiMode::Gemini),
+        "codex" => Ok(AiMode::Codex),
+        "plain" => Ok(AiMode::Plain),
+        name if app.state::<AgentLauncher>().has_custom_agent(name) => Ok(AiMode::Custom(name.to_string())),
+        name => Err(format!("Unknown agent '{name}'")),
+    }
+}
+
+/// Returns the session's recent output, escape sequences removed.
+pub fn read_session_output(app: &AppHandle, args: ReadOutputArgs) -> Result<String, String> {
+    let session = app
+        .state::<SessionManager>()
+        .get_session(args.session_id)
+        .ok_or_else(|| format!("Session {} not found", args.session_id))?;
+    let pty_id = session
+        .pty_id
+        .ok_or_else(|| format!("Session {} has no terminal", args.session_id))?;
+    Ok(app
+        .state::<Scrollback>()
+        .tail(pty_id, args.max_bytes.unwrap_or(DEFAULT_OUTPUT_BYTES)))
+}
+
+/// Sends a prompt to the session's running agent, records it, and returns
+/// the text typed.
+pub async fn send_prompt(app: &AppHandle, args: SendPromptArgs) -> Result<String, String> {
+    let session = app
+        .state::<SessionManager>()
+        .get_session(args.session_id)
+        .ok_or_else(|| format!("Session {} not found", args.session_id))?;
+    let prompt = app
+        .state::<AgentLauncher>()
+        .send_prompt(
+            &app.state::<ProcessManager>(),
+            &session,
+            &args.text,
+            &args.files,
+        )
+        .await?;
+    agent_output::record_event(
+        app,
+        &session,
+        AgentEvent::Prompt {
+            text: prompt.clone(),
+        },
+    );
+    Ok(prompt)
+}
@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+
+use super::session_manager::LaunchProfile;
+use super::storage;
+use crate::git::{Git, GitError};
+
+/// Where a repository keeps its Maestro configuration, relative to its
+/// root.
+pub const REPO_CONFIG_PATH: &str = ".maestro/config.toml";
+
+/// File in the data directory recording the approved configuration of
+/// each repository: canonical repository path to SHA-256 of the file.
+const TRUST_FILE: &str = "repo-trust.json";
+
+/// Most configuration files kept parsed at once.
+const MAX_CACHED_CONFIGS: usize = 64;
+
+/// A parsed configuration file and its hash, valid while the file's
+/// modification time is `modified`.
+struct CachedConfig {
+    modified: SystemTime,
+    loaded: (RepoConfig, String),
+    last_used: Instant,
+}
+
+/// Whether the repository's configuration may run commands: `hash` is the
+/// SHA-256 of its current `.maestro/config.toml` (`None` if it has none),
+/// and `trusted` whether the user approved exactly that content.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RepoConfigTrust {
+    pub hash: Option<String>,
+    pub trusted: bool,
+}
+
+/// How `shared_dirs` reach a new worktree: linked to the main checkout's
+/// copy (shared by every worktree), or copied (each worktree gets its own).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SharedDirStrategy {
+    #[default]
+    Symlink,
+    Copy,
+}
+
+/// The `[worktree]` table: how new worktrees of the repository are set up.
+///
+/// `base_branch` is the branch new branches start from when none is given
+/// (the repository's default branch if unset). `shared_dirs` are paths,
+/// relative to the root, brought over from the main checkout, e.g.
+/// `node_modules` or an untracked `.env`. `setup` commands are typed into
+/// the session's shell, in order, before its startup script and agent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct WorktreeDefaults {
+    pub base_branch: Option<String>,
+    pub setup: Vec<String>,
+    pub shared_dirs: Vec<String>,
+    pub shared_dir_strategy: SharedDirStrategy,
+}
+
+/// The `[agent]` table: defaults for the sessions of the repository.
+///
+/// `default` is the agent started when none is chosen (`claude`,
+/// `gemini`, `codex`, `plain`, or a custom agent's name). The rest are
+/// merged into each new session's `LaunchProfile`, which wins on conflicts:
+/// its variables override `env`, its `extra_path` entries come first, and
+/// its startup script and test command replace these.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct AgentDefaults {
+    pub default: Option<String>,
+    pub env: BTreeMap<String, String>,
+    pub extra_path: Vec<String>,
+    pub startup_script: Option<String>,
+    pub test_command: Option<String>,
+}
+
+/// The `[merge_gate]` table: checks `worktree_integrate` runs before
+/// merging a worktree (see `merge_gate`).
+///
+/// Uncommitted changes in the worktree are committed with
+/// `auto_commit_message` if `auto_commit` is set, and block the merge
+/// otherwise. `require_tests` runs `agent.test_command`, and
+/// `lint_command` is run in the worktree if set; both must succeed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct MergeGateConfig {
+    pub auto_commit: bool,
+    pub auto_commit_message: Option<String>,
+    pub require_tests: bool,
+    pub lint_command: Option<String>,
+}
+
+/// Per-repository settings committed in `.maestro/config.toml`, so a team
+/// shares its Maestro workflow with the code. Layered over the global
+/// settings: whatever the file leaves out falls back to them.
+///
+/// A cloned repository is not trusted to run code: `setup`, the startup
+/// script, `env`, and `extra_path` are ignored (see `for_repo`) until the
+/// user approves the file's current content with `trust`, as direnv does.
+/// Any change to the file needs a new approval.
+///
+/// ```toml
+/// [worktree]
+/// base_branch = "develop"
+/// setup = ["npm ci"]
+/// shared_dirs = [".env"]
+/// shared_dir_strategy = "copy"
+///
+/// [agent]
+/// default = "codex"
+/// test_command = "npm test"
+/// env = { NODE_ENV = "development" }
+///
+/// [merge_gate]
+/// require_tests = true
+/// lint_command = "npm run lint"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepoConfig {
+    pub worktree: WorktreeDefaults,
+    pub agent: AgentDefaults,
+    pub merge_gate: MergeGateConfig,
+}
+
+impl RepoConfig {
+    /// Reads the configuration of the repository at `repo_path`, or `None`
+    /// if it has none.
+    pub fn load(repo_path: &Path) -> Result<Option<Self>, String> {
+        Ok(Self::load_hashed(repo_path)?.map(|(config, _)| config))
+    }
+
+    /// Like `load`, also returning the hash of the file's content.
+    ///
+    /// Parsed files are cached until their modification time changes, so
+    /// callers such as `base_branch` only stat the file. The cache holds
+    /// the `MAX_CACHED_CONFIGS` most recently used files and forgets a file
+    /// once it is gone, e.g. with its removed worktree.
+    fn load_hashed(repo_path: &Path) -> Result<Option<(Self, String)>, String> {
+        let path = repo_path.join(REPO_CONFIG_PATH);
+        let modified = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata.modified().ok(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                config_cache().remove(&path);
+                return Ok(None);
+            }
+            Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+        };
+        if let Some(cached) = config_cache().get_mut(&path).filter(|c| Some(c.modified) == modified) {
+            cached.last_used = Instant::now();
+            return Ok(Some(cached.loaded.clone()));
+        }
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                config_cache().remove(&path);
+                return Ok(None);
+            }
+            Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+        };
+        let config: Self = toml::from_str(&text).map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+        config.validate().map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+        let loaded = (config, hash_of(&text));
+        if let Some(modified) = modified {
+            let mut cache = config_cache();
+            cache.insert(
+                path,
+                CachedConfig {
+                    modified,
+                    loaded: loaded.clone(),
+                    last_used: Instant::now(),
+                },
+            );
+            if cache.len() > MAX_CACHED_CONFIGS {
+                let oldest = cache
+                    .iter()
+                    .min_by_key(|(_, cached)| cached.last_used)
+                    .map(|(path, _)| path.clone());
+                if let Some(oldest) = oldest {
+                    cache.remove(&oldest);
+                }
+            }
+        }
+        Ok(Some(loaded))
+    }
+
+    /// Like `load`, but an unreadable or invalid file is logged and
+    /// ignored, so a broken config never blocks creating sessions. Unless
+    /// the user approved the file's current content, the settings that run
+    /// code (`setup`, `startup_script`, `env`, `extra_path`) are dropped.
+    pub fn for_repo(repo_path: &Path) -> Self {
+        match Self::load_hashed(repo_path) {
+            Ok(Some((mut config, hash))) => {
+                if !is_trusted(repo_path, &hash) && config.runs_code() {
+                    log::warn!(
+                        "{} in {} is not trusted; ignoring its setup, startup script, and environment",
+                        REPO_CONFIG_PATH,
+                        repo_path.display()
+                    );
+                    config.worktree.setup.clear();
+                    config.agent.startup_script = None;
+                    config.agent.env.clear();
+                    config.agent.extra_path.clear();
+                }
+                config
+            }
+            Ok(None) => Self::default(),
+            Err(e) => {
+                log::warn!("{e}; using the global settings");
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether the file sets anything that is run or applied to the shell
+    /// of new sessions.
+    fn runs_code(&self) -> bool {
+        !self.worktree.setup.is_empty()
+            || self.agent.startup_script.is_some()
+            || !self.agent.env.is_empty()
+            || !self.agent.extra_path.is_empty()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(dir) = self.worktree.shared_dirs.iter().find(|dir| !is_inside(dir)) {
+            return Err(format!("shared_dirs entry '{dir}' must be a relative path inside the repository"));
+        }
+        if self.worktree.base_branch.as_deref().is_some_and(|b| b.trim().is_empty()) {
+            return Err("base_branch must not be empty".to_string());
+        }
+        if self.merge_gate.lint_command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            return Err("lint_command must not be empty".to_string());
+        }
+        self.profile().validate()
+    }
+
+    /// The `[agent]` defaults as a launch profile.
+    fn profile(&self) -> LaunchProfile {
+        LaunchProfile {
+            env: self.agent.env.clone(),
+            extra_path: self.agent.extra_path.clone(),
+            startup_script: self.agent.startup_script.clone(),
+            test_command: self.agent.test_command.clone(),
+        }
+    }
+
+    /// Merges the `[agent]` defaults into a session's profile, which wins
+    /// on conflicts.
+    pub fn merge_profile(&self, profile: LaunchProfile) -> LaunchProfile {
+        let mut merged = self.profile();
+        merged.env.extend(profile.env);
+        merged.extra_path.splice(0..0, profile.extra_path);
+        merged.startup_script = profile.startup_script.or(merged.startup_script);
+        merged.test_command = profile.test_command.or(merged.test_command);
+        merged
+    }
+
+    /// Brings the `shared_dirs` of the main checkout at `repo_path` into
+    /// the new worktree at `wt_path`. Paths missing from the checkout or
+    /// already present in the worktree are skipped; failures are logged,
+    /// since the worktree is usable either way.
+    pub fn share_dirs(&self, repo_path: &Path, wt_path: &Path) {
+        for dir in &self.worktree.shared_dirs {
+            let source = repo_path.join(dir);
+            let target = wt_path.join(dir);
+            if !source.exists() || target.symlink_metadata().is_ok() {
+                continue;
+            }
+            let shared = target
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| match self.worktree.shared_dir_strategy {
+                    SharedDirStrategy::Symlink => std::os::unix::fs::symlink(&source, &target),
+                    SharedDirStrategy::Copy => copy_recursive(&source, &target),
+                });
+            if let Err(e) = shared {
+                log::warn!("Failed to share {dir} into {}: {e}", wt_path.display());
+            }
+        }
+    }
+}
+
+fn hash_of(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Parsed configuration files by path (see `RepoConfig::load_hashed`).
+fn config_cache() -> MutexGuard<'static, HashMap<PathBuf, CachedConfig>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedConfig>>> = OnceLock::new();
+    match CACHE.get_or_init(Default::default).lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn trust_path() -> PathBuf {
+    storage::data_dir().join(TRUST_FILE)
+}
+
+/// Serializes updates of the trust file.
+fn trust_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    match LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn trust_key(repo_path: &Path) -> String {
+    std::fs::canonicalize(repo_path)
+        .unwrap_or_else(|_| repo_path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn trusted_hashes() -> HashMap<String, String> {
+    storage::load_json(&trust_path())
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to read {TRUST_FILE}: {e}");
+            None
+        })
+        .unwrap_or_default()
+}
+
+fn is_trusted(repo_path: &Path, hash: &str) -> bool {
+    trusted_hashes().get(&trust_key(repo_path)).is_some_and(|h| h == hash)
+}
+
+/// Returns whether the configuration of the repository at `repo_path` is
+/// approved, and the hash to pass to `trust` to approve it.
+pub fn trust_status(repo_path: &Path) -> Result<RepoConfigTrust, String> {
+    Ok(match RepoConfig::load_hashed(repo_path)? {
+        Some((_, hash)) => RepoConfigTrust {
+            trusted: is_trusted(repo_path, &hash),
+            hash: Some(hash),
+        },
+        None => RepoConfigTrust {
+            hash: None,
+            trusted: false,
+        },
+    })
+}
+
+/// Approves the configuration of the repository at `repo_path` whose hash
+/// is `hash`, as returned by `trust_status` for the content the user
+/// reviewed. Fails if the file changed since, so an approval never covers
+/// content nobody saw.
+pub fn trust(repo_path: &Path, hash: &str) -> Result<(), String> {
+    let current = RepoConfig::load_hashed(repo_path)?
+        .map(|(_, hash)| hash)
+        .ok_or_else(|| format!("{} has no {REPO_CONFIG_PATH}", repo_path.display()))?;
+    if current != hash {
+        return Err(format!("{REPO_CONFIG_PATH} changed since it was reviewed; review it again"));
+    }
+    let _guard = trust_lock();
+    let mut hashes = trusted_hashes();
+    hashes.insert(trust_key(repo_path), current);
+    storage::save_json(&trust_path(), &hashes).map_err(|e| format!("Failed to save {TRUST_FILE}: {e}"))?;
+    log::info!("Trusted {REPO_CONFIG_PATH} of {}", repo_path.display());
+    Ok(())
+}
+
+/// Returns `true` for a relative path that stays inside the directory it
+/// is joined to.
+pub(super) fn is_inside(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Copies a file or directory tree, recreating symlinks instead of
+/// following them.
+pub(super) fn copy_recursive(source: &Path, target: &Path) -> io::Result<()> {
+    let metadata = source.symlink_metadata()?;
+    if metadata.is_symlink() {
+        std::os::unix::fs::symlink(std::fs::read_link(source)?, target)
+    } else if metadata.is_dir() {
+        std::fs::create_dir_all(target)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(source, target).map(|_| ())
+    }
+}
+
+/// The branch new branches of the repository start from when none is
+/// given: its configured `base_branch`, else its default branch.
+pub async fn base_branch(repo_path: &Path) -> Result<String, GitError> {
+    match RepoConfig::for_repo(repo_path).worktree.base_branch {
+        Some(branch) => Ok(branch),
+        None => Git::new(repo_path).default_branch().await,
+    }
+}
+
+/// The agent started in the repository when none is chosen: its configured
+/// default, else `claude`.
+pub fn default_agent(repo_path: &Path) -> String {
+    RepoConfig::for_repo(repo_path)
+        .agent
+        .default
+        .unwrap_or_else(|| "claude".to_string())
+}
@@ -0,0 +1,343 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+use super::audit::AuditLog;
+use super::clock;
+use super::process_manager::ProcessManager;
+use super::repo_config;
+use super::session_lock::OperationLock;
+use super::session_manager::{SessionConfig, SessionManager, SessionStatus};
+use super::storage;
+use super::worktree_manager::WorktreeManager;
+use crate::git::Git;
+
+/// Current on-disk format of `retention-policy.json`.
+const RETENTION_POLICY_VERSION: u32 = 1;
+
+/// Time between automatic cleanup passes while a policy is active.
+const CLEANUP_INTERVAL_SECS: u64 = 10 * 60;
+
+/// Event emitted after every cleanup pass that removed at least one session.
+pub const SESSIONS_CLEANED_EVENT: &str = "sessions-cleaned";
+
+/// When finished sessions are removed automatically.
+///
+/// A `Done` session is eligible once it has been `Done` for `max_age_secs`,
+/// or, with `when_merged`, once its branch is merged into `merge_target`
+/// (the repository's base branch if unset, see
+/// `repo_config::base_branch`). With neither rule set the policy is
+/// inactive. `remove_worktrees` also removes the session's worktree;
+/// uncommitted changes are archived first, and a failed archive keeps the
+/// worktree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct RetentionPolicy {
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+    #[serde(default)]
+    pub when_merged: bool,
+    #[serde(default)]
+    pub merge_target: Option<String>,
+    #[serde(default)]
+    pub remove_worktrees: bool,
+}
+
+impl RetentionPolicy {
+    /// Returns `true` if any rule is set.
+    pub fn is_active(&self) -> bool {
+        self.max_age_secs.is_some() || self.when_merged
+    }
+}
+
+/// Which rule made a session eligible for cleanup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+pub enum CleanupReason {
+    Expired,
+    Merged,
+}
+
+/// A `Done` session the policy would remove. `done_since` is a Unix epoch
+/// timestamp.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CleanupCandidate {
+    pub session_id: u32,
+    pub title: Option<String>,
+    pub branch: Option<String>,
+    pub worktree_path: Option<String>,
+    pub done_since: i64,
+    pub reason: CleanupReason,
+}
+
+/// Outcome of cleaning up one candidate. `error` is set if the worktree
+/// could not be removed; the session itself is removed either way.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CleanupResult {
+    pub candidate: CleanupCandidate,
+    pub worktree_removed: bool,
+    pub error: Option<String>,
+}
+
+/// Payload of the `sessions-cleaned` event. `ran_at` is a Unix epoch timestamp.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CleanupReport {
+    pub ran_at: i64,
+    pub results: Vec<CleanupResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RetentionPolicyFile {
+    version: u32,
+    policy: RetentionPolicy,
+}
+
+/// Persisted retention policy for the background cleanup task.
+///
+/// Placed in Tauri managed state. Changing the policy wakes the task so a
+/// newly enabled policy is applied right away.
+pub struct RetentionManager {
+    path: PathBuf,
+    policy: Mutex<RetentionPolicy>,
+    changed: Notify,
+}
+
+impl RetentionManager {
+    /// Loads the policy from the data dir, starting inactive if the file is
+    /// missing or unreadable.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("retention-policy.json");
+        let policy = match storage::load_json::<RetentionPolicyFile>(&path) {
+            Ok(Some(file)) if file.version <= RETENTION_POLICY_VERSION => file.policy,
+            Ok(Some(file)) => {
+                log::warn!("Ignoring retention policy with unknown version {}", file.version);
+                RetentionPolicy::default()
+            }
+            Ok(None) => RetentionPolicy::default(),
+            Err(e) => {
+                log::error!("Failed to load retention policy {}: {e}", path.display());
+                RetentionPolicy::default()
+            }
+        };
+        Self {
+            path,
+            policy: Mutex::new(policy),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Returns the current policy.
+    pub fn policy(&self) -> RetentionPolicy {
+        self.lock().clone()
+    }
+
+    /// Persists a new policy and wakes the cleanup task.
+    pub fn set_policy(&self, policy: RetentionPolicy) -> io::Result<()> {
+        let mut current = self.lock();
+        let file = RetentionPolicyFile {
+            version: RETENTION_POLICY_VERSION,
+            policy: policy.clone(),
+        };
+        storage::save_json(&self.path, &file)?;
+        *current = policy;
+        drop(current);
+        self.changed.notify_one();
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RetentionPolicy> {
+        match self.policy.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Lists the sessions `policy` would remove right now, without touching
+/// anything. Age is checked before merge state, so an expired session is
+/// reported as `Expired` even if its branch is also merged. Git failures
+/// while checking merge state only exclude that session.
+pub async fn plan(
+    policy: &RetentionPolicy,
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+) -> Vec<CleanupCandidate> {
+    if !policy.is_active() {
+        return Vec::new();
+    }
+    let now = clock::now_secs();
+
+    let mut candidates = Vec::new();
+    for activity in sessions.activity() {
+        if activity.status != SessionStatus::Done {
+            continue;
+        }
+        let Some(session) = sessions.get_session(activity.session_id) else {
+            continue;
+        };
+
+        let expired = policy
+            .max_age_secs
+            .is_some_and(|max| now - activity.status_since >= max as i64);
+        let reason = if expired {
+            Some(CleanupReason::Expired)
+        } else if policy.when_merged {
+            is_branch_merged(policy, worktrees, &session)
+                .await
+                .then_some(CleanupReason::Merged)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            candidates.push(CleanupCandidate {
+                session_id: session.id,
+                title: session.title,
+                branch: session.branch,
+                worktree_path: session.worktree_path,
+                done_since: activity.status_since,
+                reason,
+            });
+        }
+    }
+    candidates
+}
+
+/// Returns the session's repository, asking git for sessions from before
+/// repositories were tracked.
+async fn repo_of(worktrees: &WorktreeManager, session: &SessionConfig) -> Option<PathBuf> {
+    match (session.repo(), session.worktree_path.as_deref()) {
+        (Some(repo), _) => Some(repo.to_path_buf()),
+        (None, Some(wt_path)) => worktrees.repo_of(Path::new(wt_path)).await,
+        (None, None) => None,
+    }
+}
+
+async fn is_branch_merged(policy: &RetentionPolicy, worktrees: &WorktreeManager, session: &SessionConfig) -> bool {
+    let Some(ref branch) = session.branch else {
+        return false;
+    };
+    let Some(repo) = repo_of(worktrees, session).await else {
+        return false;
+    };
+    let git = Git::new(&repo);
+    let target = match policy.merge_target {
+        Some(ref target) => target.clone(),
+        None => match repo_config::base_branch(&repo).await {
+            Ok(target) => target,
+            Err(e) => {
+                log::warn!("Cannot resolve merge target of {}: {e}", repo.display());
+                return false;
+            }
+        },
+    };
+    if target == *branch {
+        return false;
+    }
+    match git.is_merged(branch, &target).await {
+        Ok(merged) => merged,
+        Err(e) => {
+            log::warn!("Cannot check whether {branch} is merged into {target}: {e}");
+            false
+        }
+    }
+}
+
+/// Removes every session `plan` selects, killing its shell and, if the
+/// policy says so, removing its worktree.
+///
+/// Each removal is independent; a failure is recorded on that result and
+/// the pass continues with the next session.
+pub async fn run_cleanup(app: &AppHandle) -> CleanupReport {
+    let ran_at = clock::now_secs();
+    let policy = app.state::<RetentionManager>().policy();
+    let sessions = app.state::<SessionManager>();
+    let worktrees = app.state::<WorktreeManager>();
+    let processes = app.state::<ProcessManager>().inner().clone();
+
+    let mut results = Vec::new();
+    for candidate in plan(&policy, &sessions, &worktrees).await {
+        // A session busy with a merge or rebase is left for the next pass
+        let _lock = match OperationLock::for_session(&sessions, "cleanup", candidate.session_id) {
+            Ok(lock) => lock,
+            Err(e) => {
+                log::info!("Skipping cleanup of session {}: {}", candidate.session_id, e.message);
+                continue;
+            }
+        };
+        let Some(removed) = sessions.remove_session(candidate.session_id) else {
+            continue;
+        };
+        if let Some(pty_id) = removed.pty_id.filter(|id| processes.has_session(*id)) {
+            if let Err(e) = processes.kill_session(pty_id).await {
+                log::warn!("Failed to kill PTY {pty_id} of cleaned-up session {}: {e}", removed.id);
+            }
+        }
+
+        let mut result = CleanupResult {
+            candidate,
+            worktree_removed: false,
+            error: None,
+        };
+        if let (true, Some(ref wt_path)) = (policy.remove_worktrees, &removed.worktree_path) {
+            let wt_path = Path::new(wt_path);
+            match repo_of(&worktrees, &removed).await {
+                Some(repo) => match worktrees.remove(&repo, wt_path, true, true).await {
+                    Ok(_) => result.worktree_removed = true,
+                    Err(e) => result.error = Some(e.to_string()),
+                },
+                None => result.error = Some(format!("No repository found for {}", wt_path.display())),
+            }
+        }
+        if let Some(ref e) = result.error {
+            log::warn!("Cleanup of session {} left its worktree: {e}", removed.id);
+        }
+        results.push(result);
+    }
+
+    CleanupReport { ran_at, results }
+}
+
+/// Spawns the background task that applies the retention policy every
+/// `CLEANUP_INTERVAL_SECS` and emits `sessions-cleaned` when something was
+/// removed.
+///
+/// Reads `RetentionManager`, `SessionManager`, `WorktreeManager`, and
+/// `ProcessManager` from managed state, so all must be registered before
+/// this is called from `setup`.
+pub fn spawn_cleanup_task(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let retention = app.state::<RetentionManager>();
+            if !retention.policy().is_active() {
+                retention.changed.notified().await;
+                continue;
+            }
+
+            let report = run_cleanup(&app).await;
+            let audit = app.state::<AuditLog>();
+            for result in &report.results {
+                let params = serde_json::json!({
+                    "session_id": result.candidate.session_id,
+                    "candidate": result.candidate,
+                    "worktree_removed": result.worktree_removed,
+                });
+                let outcome = result.error.as_ref().map_or(Ok(()), Err);
+                audit.record_automation("retention", "remove_session", params, &outcome);
+            }
+            if !report.results.is_empty() {
+                log::info!("Retention policy removed {} session(s)", report.results.len());
+                let _ = app.emit(SESSIONS_CLEANED_EVENT, report);
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(CLEANUP_INTERVAL_SECS)) => {}
+                _ = retention.changed.notified() => {}
+            }
+        }
+    });
+}
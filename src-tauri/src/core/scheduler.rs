@@ -0,0 +1,293 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::agent_launcher::AgentLauncher;
+use super::error::AppError;
+use super::process_manager::ProcessManager;
+use super::provider_usage::UsageTracker;
+use super::session_manager::{
+    AiMode, SessionConfig, SessionListener, SessionManager, SessionStatus, StatusCause, StatusTransition,
+};
+use super::task_queue::TaskQueue;
+
+/// Event emitted with the new state (`true` = paused) whenever launches are
+/// paused or resumed.
+pub const LAUNCHES_PAUSED_EVENT: &str = "launches-paused";
+
+/// Result of asking the scheduler to start a session's agent.
+///
+/// `Launched` carries the command typed into the shell (`None` if the mode
+/// has nothing to launch); `Queued` carries the 1-based queue position.
+#[derive(Debug, Clone, Serialize, Type)]
+pub enum LaunchOutcome {
+    Launched(Option<String>),
+    Queued(usize),
+}
+
+#[derive(Default)]
+struct Slots {
+    /// Sessions waiting for a slot, oldest first.
+    queue: VecDeque<u32>,
+    /// Queued sessions whose agent should resume its last conversation.
+    resume: HashSet<u32>,
+    /// Sessions launched but not yet reported a status of their own; they
+    /// hold a slot until their first status change.
+    pending: HashSet<u32>,
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    /// Maximum sessions in `Working` at once; 0 means unlimited.
+    max_working: AtomicUsize,
+    /// While set, every launch is queued as if no slot were free.
+    paused: AtomicBool,
+    slots: Mutex<Slots>,
+}
+
+/// Caps how many sessions may be `Working` at once.
+///
+/// Agent launches go through `request_launch`. When the cap is reached, or
+/// the agent's provider is cooling down after throttling errors (see
+/// `UsageTracker`), the session is set to `Queued` instead, and it is
+/// launched automatically, in request order, once a `Working` session
+/// moves to another status or is removed, or the cooldown ends. Sessions
+/// of a provider still cooling down are skipped over in the meantime.
+/// Launches can also be paused altogether, queueing every one until they
+/// are resumed. The cap cannot stop an agent that is already running from
+/// resuming work; it only defers launches. Cheap to clone; registered as a
+/// `SessionListener` and placed in Tauri managed state.
+#[derive(Clone)]
+pub struct LaunchScheduler {
+    inner: Arc<Inner>,
+}
+
+impl LaunchScheduler {
+    /// Creates a scheduler with no cap.
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                max_working: AtomicUsize::new(0),
+                paused: AtomicBool::new(false),
+                slots: Mutex::new(Slots::default()),
+            }),
+        }
+    }
+
+    /// Returns the current cap; 0 means unlimited.
+    pub fn max_working(&self) -> usize {
+        self.inner.max_working.load(Ordering::Relaxed)
+    }
+
+    /// Changes the cap and starts queued sessions if it was raised.
+    pub fn set_max_working(&self, max: usize) {
+        self.inner.max_working.store(max, Ordering::Relaxed);
+        log::info!("Working session cap set to {max}");
+        self.drain();
+    }
+
+    /// Returns whether launches are paused.
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Relaxed)
+    }
+
+    /// Pauses or resumes launches and emits `launches-paused`. Resuming
+    /// starts queued sessions, then queued tasks. Not persisted: every start
+    /// of Maestro launches normally.
+    pub fn set_paused(&self, paused: bool) {
+        if self.inner.paused.swap(paused, Ordering::Relaxed) == paused {
+            return;
+        }
+        log::info!("Launches {}", if paused { "paused" } else { "resumed" });
+        let app = &self.inner.app_handle;
+        let _ = app.emit(LAUNCHES_PAUSED_EVENT, paused);
+        if !paused {
+            self.drain();
+            if let Some(tasks) = app.try_state::<TaskQueue>() {
+                tasks.dispatch();
+            }
+        }
+    }
+
+    /// Returns the queued session IDs, oldest first.
+    pub fn queued(&self) -> Vec<u32> {
+        self.lock().queue.iter().copied().collect()
+    }
+
+    /// Returns how many more agents of `mode`'s provider could be launched
+    /// right now without being queued, or `None` if there is no cap.
+    /// `Some(0)` while launches are paused or the provider is cooling down.
+    /// Queued sessions count as holding a slot, so new work never overtakes
+    /// them, except those whose provider is cooling down, which cannot take
+    /// a slot before then.
+    pub fn free_slots(&self, mode: &AiMode) -> Option<usize> {
+        if self.is_paused() || self.is_mode_cooling_down(mode) {
+            return Some(0);
+        }
+        let max = self.max_working();
+        if max == 0 {
+            return None;
+        }
+        let sessions = self.inner.app_handle.state::<SessionManager>();
+        let slots = self.lock();
+        let waiting = slots
+            .queue
+            .iter()
+            .filter_map(|&id| sessions.get_session(id))
+            .filter(|session| !self.is_cooling_down(session))
+            .count();
+        Some(max.saturating_sub(self.slots_in_use(&sessions, &slots) + waiting))
+    }
+
+    /// Launches the session's agent now if a slot is free, otherwise queues
+    /// it and sets its status to `Queued`. `resume` is passed on to
+    /// `AgentLauncher::launch`, also when the launch is deferred.
+    pub fn request_launch(&self, session_id: u32, resume: bool) -> Result<LaunchOutcome, AppError> {
+        let sessions = self.inner.app_handle.state::<SessionManager>();
+        let session = sessions
+            .get_session(session_id)
+            .ok_or_else(|| AppError::session_not_found(session_id))?;
+
+        let position = {
+            let mut slots = self.lock();
+            if let Some(i) = slots.queue.iter().position(|&id| id == session_id) {
+                return Ok(LaunchOutcome::Queued(i + 1));
+            }
+            if self.has_free_slot(&sessions, &slots) && !self.is_cooling_down(&session) {
+                slots.pending.insert(session_id);
+                None
+            } else {
+                slots.queue.push_back(session_id);
+                if resume {
+                    slots.resume.insert(session_id);
+                }
+                Some(slots.queue.len())
+            }
+        };
+
+        match position {
+            Some(position) => {
+                log::info!("Session {session_id} queued at position {position}");
+                sessions.update_status(session_id, SessionStatus::Queued, StatusCause::Scheduled);
+                Ok(LaunchOutcome::Queued(position))
+            }
+            None => self
+                .launch(&session, resume)
+                .map(LaunchOutcome::Launched)
+                .map_err(AppError::from),
+        }
+    }
+
+    fn launch(&self, session: &SessionConfig, resume: bool) -> Result<Option<String>, String> {
+        let app = &self.inner.app_handle;
+        let result = app
+            .state::<AgentLauncher>()
+            .launch(&app.state::<ProcessManager>(), session, resume)
+            .map_err(|e| e.to_string());
+        if !matches!(result, Ok(Some(_))) {
+            // Nothing is running, so the slot is not held
+            self.lock().pending.remove(&session.id);
+        }
+        result
+    }
+
+    /// Starts queued sessions, oldest first, while slots are free, skipping
+    /// those whose provider is cooling down.
+    pub fn drain(&self) {
+        let sessions = self.inner.app_handle.state::<SessionManager>();
+        loop {
+            let (session, resume) = {
+                let mut slots = self.lock();
+                if !self.has_free_slot(&sessions, &slots) {
+                    return;
+                }
+                let Some((i, session)) = slots
+                    .queue
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, &id)| Some((i, sessions.get_session(id)?)))
+                    .find(|(_, session)| !self.is_cooling_down(session))
+                else {
+                    return;
+                };
+                slots.queue.remove(i);
+                slots.pending.insert(session.id);
+                let resume = slots.resume.remove(&session.id);
+                (session, resume)
+            };
+            let next = session.id;
+            log::info!("Starting queued session {next}");
+            sessions.update_status(next, SessionStatus::Starting, StatusCause::Scheduled);
+            if let Err(e) = self.launch(&session, resume) {
+                log::warn!("Failed to launch queued session {next}: {e}");
+            }
+        }
+    }
+
+    fn is_cooling_down(&self, session: &SessionConfig) -> bool {
+        self.is_mode_cooling_down(&session.mode)
+    }
+
+    fn is_mode_cooling_down(&self, mode: &AiMode) -> bool {
+        self.inner
+            .app_handle
+            .try_state::<UsageTracker>()
+            .is_some_and(|usage| usage.is_cooling_down(mode))
+    }
+
+    fn has_free_slot(&self, sessions: &SessionManager, slots: &Slots) -> bool {
+        if self.is_paused() {
+            return false;
+        }
+        let max = self.max_working();
+        if max == 0 {
+            return true;
+        }
+        self.slots_in_use(sessions, slots) < max
+    }
+
+    /// Counts `Working` sessions plus launches still holding a slot.
+    fn slots_in_use(&self, sessions: &SessionManager, slots: &Slots) -> usize {
+        let working = sessions
+            .all_sessions()
+            .iter()
+            .filter(|s| s.status == SessionStatus::Working && !slots.pending.contains(&s.id))
+            .count();
+        working + slots.pending.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Slots> {
+        match self.inner.slots.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl SessionListener for LaunchScheduler {
+    fn on_status_changed(&self, session: &SessionConfig, transition: &StatusTransition) {
+        let released = {
+            let mut slots = self.lock();
+            let was_pending = session.status != SessionStatus::Starting && slots.pending.remove(&session.id);
+            was_pending || transition.from == Some(SessionStatus::Working)
+        };
+        if released {
+            self.drain();
+        }
+    }
+
+    fn on_removed(&self, session: &SessionConfig) {
+        {
+            let mut slots = self.lock();
+            slots.queue.retain(|&id| id != session.id);
+            slots.resume.remove(&session.id);
+            slots.pending.remove(&session.id);
+        }
+        self.drain();
+    }
+}
@@ -0,0 +1,136 @@
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::clock;
+use super::session_manager::SessionConfig;
+use super::storage;
+
+/// Current on-disk format of a scratchpad file.
+const SCRATCHPAD_VERSION: u32 = 1;
+
+/// Largest scratchpad accepted, in bytes. Meant for task notes and links,
+/// not for pasting logs.
+const MAX_SCRATCHPAD_BYTES: usize = 256 * 1024;
+
+/// A session's scratchpad document. `updated_at` is a Unix epoch timestamp,
+/// `None` if the scratchpad was never saved.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct Scratchpad {
+    pub content: String,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScratchpadFile {
+    version: u32,
+    scratchpad: Scratchpad,
+}
+
+/// Per-session scratchpad documents, one file per session under
+/// `scratchpads/` in the data dir.
+///
+/// Kept apart from `sessions.json` so frequent autosaves of a long document
+/// do not rewrite every session's config. When a session is removed,
+/// `SessionArchive` moves its scratchpad to `scratchpads/archived/` (see
+/// `archive`). Cheap to clone, so the archive and Tauri managed state can
+/// share the same store.
+#[derive(Clone)]
+pub struct ScratchpadStore {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    dir: PathBuf,
+    /// Serializes saves; `storage::save_json` writes through a fixed temp
+    /// file per target.
+    write_lock: Mutex<()>,
+}
+
+impl Default for ScratchpadStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScratchpadStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                dir: storage::data_dir().join("scratchpads"),
+                write_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    fn path(&self, session_id: u32) -> PathBuf {
+        self.inner.dir.join(format!("{session_id}.json"))
+    }
+
+    /// Returns the session's scratchpad, empty if none was saved yet.
+    pub fn get(&self, session_id: u32) -> io::Result<Scratchpad> {
+        Ok(storage::load_json::<ScratchpadFile>(&self.path(session_id))?
+            .map(|file| file.scratchpad)
+            .unwrap_or_default())
+    }
+
+    /// Replaces the session's scratchpad and returns the saved document.
+    /// Rejects content over `MAX_SCRATCHPAD_BYTES`.
+    pub fn set(&self, session_id: u32, content: String) -> Result<Scratchpad, String> {
+        if content.len() > MAX_SCRATCHPAD_BYTES {
+            return Err(format!(
+                "Scratchpad is {} bytes; the limit is {MAX_SCRATCHPAD_BYTES}",
+                content.len()
+            ));
+        }
+        let scratchpad = Scratchpad {
+            content,
+            updated_at: Some(clock::now_secs()),
+        };
+        let file = ScratchpadFile {
+            version: SCRATCHPAD_VERSION,
+            scratchpad,
+        };
+        let _guard = self.lock();
+        storage::save_json(&self.path(session_id), &file).map_err(|e| e.to_string())?;
+        Ok(file.scratchpad)
+    }
+
+    /// Moves the removed session's scratchpad out of the live ones, to a
+    /// file named after the session's ID and creation time so a reused ID
+    /// does not overwrite it. Returns the new path, or `None` if the session
+    /// never saved a scratchpad or it could not be moved.
+    pub fn archive(&self, session: &SessionConfig) -> Option<PathBuf> {
+        let from = self.path(session.id);
+        let to = self
+            .inner
+            .dir
+            .join("archived")
+            .join(format!("{}-{}.json", session.id, session.created_at));
+        let _guard = self.lock();
+        if !from.exists() {
+            return None;
+        }
+        let moved = to
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::rename(&from, &to));
+        match moved {
+            Ok(()) => Some(to),
+            Err(e) => {
+                log::warn!("Failed to archive scratchpad {}: {e}", from.display());
+                None
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        match self.inner.write_lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
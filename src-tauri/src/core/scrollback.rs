@@ -0,0 +1,539 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use regex::Regex;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::clock;
+use super::memory_budget::MemoryBudget;
+use super::process_manager::PtyListener;
+use super::session_manager::{SessionConfig, SessionListener, SessionManager};
+use super::status_inference::{ANSI_ESCAPE_PATTERN, SHELL_PROMPT_PATTERN};
+use super::transcript::{self, TranscriptStore};
+
+/// Bytes of escape-stripped output kept per PTY.
+const SCROLLBACK_BYTES: usize = 64 * 1024;
+
+/// Bytes of raw output kept per PTY for `replay`.
+const REPLAY_BYTES: usize = 256 * 1024;
+
+/// Shell-integration (OSC 133) markers: a prompt is about to be drawn, a
+/// command's output starts, and it ended.
+const PROMPT_START_MARKER: &str = "\x1b]133;A";
+const OUTPUT_START_MARKER: &str = "\x1b]133;C";
+const OUTPUT_END_MARKER: &str = "\x1b]133;D";
+
+/// Bytes `output_from_mark` returns when the caller gives no limit.
+const DEFAULT_MARK_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// Marks kept per PTY; the oldest are dropped first.
+const MAX_MARKS: usize = 500;
+
+/// Longest mark label, in characters.
+const MAX_LABEL_CHARS: usize = 200;
+
+/// Event emitted with an `OutputMarkAdded` for every mark added, by
+/// `add_mark` or at a command boundary.
+pub const OUTPUT_MARK_EVENT: &str = "output-mark-added";
+
+/// A named position in a PTY's output. `offset` counts bytes of
+/// escape-stripped output since the PTY started, so it stays valid as old
+/// output is dropped; `at` is a Unix epoch timestamp. `auto` marks are added
+/// where a command's output starts, labelled with the command line: at
+/// `OUTPUT_START_MARKER` if the shell emits it, else after a line typed at
+/// a shell prompt (see `SHELL_PROMPT_PATTERN`).
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct OutputMark {
+    pub id: u64,
+    pub label: String,
+    pub offset: u64,
+    pub at: i64,
+    pub auto: bool,
+}
+
+/// Payload of `output-mark-added`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct OutputMarkAdded {
+    pub session_id: u32,
+    pub mark: OutputMark,
+}
+
+/// Output of a PTY from a mark on, up to a size limit; `truncated` is set
+/// if more output follows. Once the output has left the buffer, `text` is
+/// the session's agent transcript since the mark was added instead, and
+/// `from_transcript` is set.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MarkedOutput {
+    pub mark: OutputMark,
+    pub text: String,
+    pub truncated: bool,
+    pub from_transcript: bool,
+}
+
+/// A shell prompt at the end of a PTY's output, waiting for a command.
+#[derive(Clone, Copy)]
+struct PendingPrompt {
+    /// Offset of the start of the prompt's line.
+    offset: u64,
+    /// Bytes of the line that are the prompt, before anything typed.
+    len: usize,
+}
+
+/// Output kept for one PTY.
+#[derive(Default)]
+struct PtyBuffers {
+    /// Escape-stripped output, for `tail`.
+    plain: String,
+    /// Raw output, escape sequences kept, for `replay`.
+    raw: String,
+    /// Bytes dropped from the front of `plain` so far, the offset of its
+    /// first byte in the whole output.
+    plain_dropped: u64,
+    /// The last `MAX_MARKS` marks, oldest first.
+    marks: VecDeque<OutputMark>,
+    /// Whether the shell emits OSC 133 markers; if not, commands are found
+    /// from its prompts.
+    shell_integration: bool,
+    /// Prompt the shell is waiting at, if any.
+    prompt: Option<PendingPrompt>,
+    /// Start of the output of the command typed at the last prompt.
+    running: Option<u64>,
+    /// Output of the last `MAX_MARKS` commands found from prompts, oldest
+    /// first.
+    commands: VecDeque<Range<u64>>,
+    /// Value of `Inner::clock` at the last output, to find the least
+    /// recently active PTYs.
+    last_output: u64,
+}
+
+impl PtyBuffers {
+    fn len(&self) -> usize {
+        self.plain.len() + self.raw.len()
+    }
+
+    /// Offset of the end of the output.
+    fn plain_end(&self) -> u64 {
+        self.plain_dropped + self.plain.len() as u64
+    }
+
+    /// Cuts `plain` to its last `keep` bytes. Marks are kept; their output
+    /// is then read from the transcript.
+    fn trim_plain(&mut self, keep: usize) {
+        let before = self.plain.len();
+        truncate_front(&mut self.plain, keep);
+        self.plain_dropped += (before - self.plain.len()) as u64;
+    }
+
+    /// The part of `plain` at `range`, or `None` if its start was dropped.
+    fn plain_at(&self, range: Range<u64>) -> Option<&str> {
+        let start = usize::try_from(range.start.checked_sub(self.plain_dropped)?).ok()?;
+        let end = usize::try_from(range.end.checked_sub(self.plain_dropped)?).ok()?;
+        self.plain.get(start..end)
+    }
+
+    /// Finds command boundaries from shell prompts, for shells that emit no
+    /// OSC 133 markers: a line that ended at a prompt and was then finished
+    /// with a newline is a command, whose output runs to the next prompt.
+    /// Returns where the output of a command typed since starts, and the
+    /// command.
+    fn find_command(&mut self, shell_prompt: &Regex) -> Option<(u64, String)> {
+        let mut started = None;
+        if let Some(prompt) = self.prompt {
+            match self.plain_at(prompt.offset..self.plain_end()) {
+                Some(line) => {
+                    if let Some(end) = line.find('\n') {
+                        let command: String =
+                            line[..end].get(prompt.len..).unwrap_or("").chars().filter(|c| !c.is_control()).collect();
+                        self.prompt = None;
+                        if !command.trim().is_empty() {
+                            let offset = prompt.offset + end as u64 + 1;
+                            self.running = Some(offset);
+                            started = Some((offset, command));
+                        }
+                    }
+                }
+                None => self.prompt = None,
+            }
+        }
+        let line_start = self.plain.rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.plain[line_start..];
+        if self.prompt.is_none() && shell_prompt.is_match(line) {
+            let offset = self.plain_dropped + line_start as u64;
+            if let Some(start) = self.running.take() {
+                if self.commands.len() >= MAX_MARKS {
+                    self.commands.pop_front();
+                }
+                self.commands.push_back(start..offset);
+            }
+            self.prompt = Some(PendingPrompt {
+                offset,
+                len: line.len(),
+            });
+        }
+        started
+    }
+
+    fn push_mark(&mut self, mark: OutputMark) {
+        if self.marks.len() >= MAX_MARKS {
+            self.marks.pop_front();
+        }
+        self.marks.push_back(mark);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    ansi: Regex,
+    shell_prompt: Regex,
+    buffers: DashMap<u32, PtyBuffers>,
+    /// Bytes held in `buffers`, kept in step with them.
+    held: AtomicUsize,
+    /// Ticks once per output chunk.
+    clock: AtomicU64,
+    /// Source of mark IDs, unique across PTYs.
+    next_mark: AtomicU64,
+}
+
+/// Recent plain-text output of every PTY bound to a session, for readers
+/// outside the frontend's terminal, such as the MCP server, and its raw
+/// output, for terminals attached to the PTY later, such as a session
+/// window (see `windows`).
+///
+/// Registered as a `PtyListener` to collect output, escape sequences
+/// stripped, and as a `SessionListener` to drop a session's buffer when it
+/// is removed. Output of an exited shell stays readable until then. Counts
+/// the bytes it holds, which `MemoryBudget` caps across all PTYs with
+/// `trim_oldest`. Keeps named marks in each PTY's output, its own and one
+/// per command the shell reports or types at a prompt; once a mark's output
+/// is dropped, its session's transcript stands in for it. Cheap to clone;
+/// also placed in Tauri managed state.
+#[derive(Clone)]
+pub struct Scrollback {
+    inner: Arc<Inner>,
+}
+
+impl Scrollback {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                ansi: Regex::new(ANSI_ESCAPE_PATTERN).expect("valid escape pattern"),
+                shell_prompt: Regex::new(SHELL_PROMPT_PATTERN).expect("valid shell prompt pattern"),
+                buffers: DashMap::new(),
+                held: AtomicUsize::new(0),
+                clock: AtomicU64::new(0),
+                next_mark: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Returns up to the last `max_bytes` of the PTY's output, starting at a
+    /// line boundary when the buffer holds more than that.
+    pub fn tail(&self, pty_id: u32, max_bytes: usize) -> String {
+        let Some(buffers) = self.inner.buffers.get(&pty_id) else {
+            return String::new();
+        };
+        let buffer = &buffers.plain;
+        if buffer.len() <= max_bytes {
+            return buffer.clone();
+        }
+        let mut start = buffer.len() - max_bytes;
+        while !buffer.is_char_boundary(start) {
+            start += 1;
+        }
+        let tail = &buffer[start..];
+        match tail.find('\n') {
+            Some(i) => tail[i + 1..].to_string(),
+            None => tail.to_string(),
+        }
+    }
+
+    /// Returns the PTY's recent raw output, from the first line start in
+    /// the buffer, to replay into a terminal attached to it.
+    pub fn replay(&self, pty_id: u32) -> String {
+        let Some(buffers) = self.inner.buffers.get(&pty_id) else {
+            return String::new();
+        };
+        let buffer = &buffers.raw;
+        if buffer.len() < REPLAY_BYTES {
+            return buffer.clone();
+        }
+        match buffer.find('\n') {
+            Some(i) => buffer[i + 1..].to_string(),
+            None => buffer.clone(),
+        }
+    }
+
+    /// Returns the output of the last command run in the PTY's shell that
+    /// printed anything, escape sequences stripped, found by the shell's
+    /// integration markers: OSC 133 `C` where output starts, `D` (or the
+    /// next prompt's `A`) where it ends. Without markers, the output runs
+    /// from a line typed at a shell prompt to the next prompt. A command
+    /// still running yields its output so far. `None` if no command was
+    /// found, or the command's start has already left the buffer.
+    pub fn last_command_output(&self, pty_id: u32) -> Option<String> {
+        let buffers = self.inner.buffers.get(&pty_id)?;
+        if !buffers.shell_integration {
+            let running = buffers.running.map(|start| start..buffers.plain_end());
+            for range in running.into_iter().chain(buffers.commands.iter().rev().cloned()) {
+                let output = buffers.plain_at(range)?.trim_matches('\n');
+                if !output.trim().is_empty() {
+                    return Some(output.to_string());
+                }
+            }
+            return None;
+        }
+        let raw = buffers.raw.as_str();
+        let mut before = raw.len();
+        while let Some(start) = raw[..before].rfind(OUTPUT_START_MARKER) {
+            let output = &raw[start..];
+            let end = [OUTPUT_END_MARKER, PROMPT_START_MARKER]
+                .iter()
+                .filter_map(|marker| output[OUTPUT_START_MARKER.len()..].find(marker))
+                .min()
+                .map_or(output.len(), |i| i + OUTPUT_START_MARKER.len());
+            let plain = self.inner.ansi.replace_all(&output[..end], "").replace('\r', "");
+            let plain = plain.trim_matches('\n');
+            if !plain.trim().is_empty() {
+                return Some(plain.to_string());
+            }
+            before = start;
+        }
+        None
+    }
+
+    /// Marks the current end of the PTY's output with `label`, returning
+    /// the mark, or `None` if the PTY has printed nothing yet.
+    pub fn add_mark(&self, pty_id: u32, label: &str) -> Option<OutputMark> {
+        let mark = {
+            let mut buffers = self.inner.buffers.get_mut(&pty_id)?;
+            let mark = self.new_mark(buffers.plain_end(), label, false);
+            buffers.push_mark(mark.clone());
+            mark
+        };
+        self.emit_mark(pty_id, &mark);
+        Some(mark)
+    }
+
+    /// Returns the PTY's last `MAX_MARKS` marks, oldest first.
+    pub fn marks(&self, pty_id: u32) -> Vec<OutputMark> {
+        self.inner
+            .buffers
+            .get(&pty_id)
+            .map(|buffers| buffers.marks.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns up to `max_bytes` (default `DEFAULT_MARK_OUTPUT_BYTES`) of
+    /// the PTY's output from mark `mark_id` on, ending at a line boundary
+    /// when cut. Once that output has been dropped, returns the session's
+    /// transcript since the mark instead, read from disk, so call it off the
+    /// async runtime. `None` if there is no such mark, or neither is left.
+    pub fn output_from_mark(&self, pty_id: u32, mark_id: u64, max_bytes: Option<usize>) -> Option<MarkedOutput> {
+        let max_bytes = max_bytes.unwrap_or(DEFAULT_MARK_OUTPUT_BYTES);
+        let (mark, held) = {
+            let buffers = self.inner.buffers.get(&pty_id)?;
+            let mark = buffers.marks.iter().find(|m| m.id == mark_id)?.clone();
+            let held = buffers.plain_at(mark.offset..buffers.plain_end()).map(str::to_string);
+            (mark, held)
+        };
+        let (output, from_transcript) = match held {
+            Some(output) => (output, false),
+            None => (self.transcript_since(pty_id, mark.at)?, true),
+        };
+        if output.len() <= max_bytes {
+            return Some(MarkedOutput {
+                mark,
+                text: output,
+                truncated: false,
+                from_transcript,
+            });
+        }
+        let mut end = max_bytes;
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        let text = match output[..end].rfind('\n') {
+            Some(i) => &output[..=i],
+            None => &output[..end],
+        };
+        Some(MarkedOutput {
+            mark,
+            text: text.to_string(),
+            truncated: true,
+            from_transcript,
+        })
+    }
+
+    /// The text of the transcript entries of the PTY's session from `since`
+    /// (Unix seconds) on, one per line. `None` if there are none.
+    fn transcript_since(&self, pty_id: u32, since: i64) -> Option<String> {
+        let app = &self.inner.app_handle;
+        let session = app.state::<SessionManager>().session_for_pty(pty_id)?;
+        let entries = match app.state::<TranscriptStore>().get(&session) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to read the transcript of session {}: {e}", session.id);
+                return None;
+            }
+        };
+        let lines: Vec<_> = entries
+            .iter()
+            .filter(|entry| entry.at >= since)
+            .map(|entry| transcript::searchable_text(&entry.event))
+            .filter(|text| !text.is_empty())
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    fn new_mark(&self, offset: u64, label: &str, auto: bool) -> OutputMark {
+        OutputMark {
+            id: self.inner.next_mark.fetch_add(1, Ordering::Relaxed),
+            label: label.trim().chars().take(MAX_LABEL_CHARS).collect(),
+            offset,
+            at: clock::now_secs(),
+            auto,
+        }
+    }
+
+    fn emit_mark(&self, pty_id: u32, mark: &OutputMark) {
+        let app = &self.inner.app_handle;
+        if let Some(session) = app.state::<SessionManager>().session_for_pty(pty_id) {
+            let payload = OutputMarkAdded {
+                session_id: session.id,
+                mark: mark.clone(),
+            };
+            let _ = app.emit(OUTPUT_MARK_EVENT, &payload);
+        }
+    }
+
+    /// Returns the bytes of output held across all PTYs.
+    pub fn held_bytes(&self) -> usize {
+        self.inner.held.load(Ordering::Relaxed)
+    }
+
+    /// Drops the oldest half of the output of the least recently active
+    /// PTY, over and over, until at most `target` bytes are held. Returns
+    /// the PTYs trimmed.
+    pub fn trim_oldest(&self, target: usize) -> Vec<u32> {
+        let mut trimmed = Vec::new();
+        while self.held_bytes() > target {
+            let Some(pty_id) = self
+                .inner
+                .buffers
+                .iter()
+                .filter(|e| !e.value().is_empty())
+                .min_by_key(|e| e.value().last_output)
+                .map(|e| *e.key())
+            else {
+                break;
+            };
+            if let Some(mut buffers) = self.inner.buffers.get_mut(&pty_id) {
+                let before = buffers.len();
+                let keep_plain = buffers.plain.len() / 2;
+                let keep_raw = buffers.raw.len() / 2;
+                buffers.trim_plain(keep_plain);
+                truncate_front(&mut buffers.raw, keep_raw);
+                self.inner.held.fetch_sub(before - buffers.len(), Ordering::Relaxed);
+            }
+            if !trimmed.contains(&pty_id) {
+                trimmed.push(pty_id);
+            }
+        }
+        trimmed
+    }
+}
+
+impl PtyListener for Scrollback {
+    fn on_output(&self, pty_id: u32, text: &str) {
+        let app = &self.inner.app_handle;
+        if app.state::<SessionManager>().session_for_pty(pty_id).is_none() {
+            return;
+        }
+        let mut added = Vec::new();
+        {
+            let mut buffers = self.inner.buffers.entry(pty_id).or_default();
+            let before = buffers.len();
+            append(&mut buffers.raw, text, REPLAY_BYTES);
+            // Each command's output gets a mark, labelled with the line it
+            // was typed on
+            let mut bounds = vec![0];
+            bounds.extend(text.match_indices(OUTPUT_START_MARKER).map(|(i, _)| i));
+            bounds.push(text.len());
+            buffers.shell_integration |= bounds.len() > 2;
+            for (i, segment) in bounds.windows(2).enumerate() {
+                if i > 0 {
+                    let command = buffers.plain.lines().rev().find(|line| !line.trim().is_empty());
+                    let label = command.unwrap_or("Command").to_string();
+                    let mark = self.new_mark(buffers.plain_end(), &label, true);
+                    buffers.push_mark(mark.clone());
+                    added.push(mark);
+                }
+                let plain = self.inner.ansi.replace_all(&text[segment[0]..segment[1]], "").replace('\r', "");
+                buffers.plain.push_str(&plain);
+            }
+            if !buffers.shell_integration {
+                if let Some((offset, command)) = buffers.find_command(&self.inner.shell_prompt) {
+                    let mark = self.new_mark(offset, &command, true);
+                    buffers.push_mark(mark.clone());
+                    added.push(mark);
+                }
+            }
+            if buffers.plain.len() > SCROLLBACK_BYTES {
+                buffers.trim_plain(SCROLLBACK_BYTES);
+            }
+            buffers.last_output = self.inner.clock.fetch_add(1, Ordering::Relaxed);
+            let after = buffers.len();
+            if after >= before {
+                self.inner.held.fetch_add(after - before, Ordering::Relaxed);
+            } else {
+                self.inner.held.fetch_sub(before - after, Ordering::Relaxed);
+            }
+        }
+        for mark in &added {
+            self.emit_mark(pty_id, mark);
+        }
+        if let Some(budget) = app.try_state::<MemoryBudget>() {
+            budget.enforce(app);
+        }
+    }
+
+    fn on_exit(&self, _pty_id: u32, _exit_code: Option<u32>) {}
+}
+
+impl SessionListener for Scrollback {
+    fn on_removed(&self, session: &SessionConfig) {
+        if let Some(pty_id) = session.pty_id {
+            if let Some((_, buffers)) = self.inner.buffers.remove(&pty_id) {
+                self.inner.held.fetch_sub(buffers.len(), Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Appends `text`, dropping the oldest output beyond `max_bytes`.
+fn append(buffer: &mut String, text: &str, max_bytes: usize) {
+    buffer.push_str(text);
+    if buffer.len() > max_bytes {
+        truncate_front(buffer, max_bytes);
+    }
+}
+
+/// Drops the start of `buffer` so at most `keep` bytes remain, cut at a
+/// character boundary.
+fn truncate_front(buffer: &mut String, keep: usize) {
+    let mut cut = buffer.len().saturating_sub(keep);
+    while !buffer.is_char_boundary(cut) {
+        cut += 1;
+    }
+    buffer.drain(..cut);
+}
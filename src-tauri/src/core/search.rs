@@ -0,0 +1,250 @@
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+use super::files;
+use super::session_manager::SessionManager;
+use super::worktree_manager::WorktreeManager;
+
+/// Event emitted with a `SearchResults` batch while `search_repo` runs.
+pub const SEARCH_RESULTS_EVENT: &str = "search-results";
+
+/// Matches `search_repo` returns when the caller gives no limit.
+const DEFAULT_MAX_RESULTS: usize = 1_000;
+
+/// Most matches one search may return.
+const MAX_RESULTS: usize = 20_000;
+
+/// Matches collected before a batch is emitted.
+const BATCH_SIZE: usize = 200;
+
+/// Longest matching line reported, in bytes; longer ones are cut by rg.
+const MAX_LINE_BYTES: &str = "500";
+
+/// How the query's case is matched. `Smart` ignores case unless the query
+/// has an uppercase letter.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseMode {
+    #[default]
+    Smart,
+    Sensitive,
+    Insensitive,
+}
+
+/// Options of `search_repo`. The query is a literal string unless `regex`
+/// is set (Rust regex syntax). `globs` limit the files searched, as rg's
+/// `--glob` does (`!` excludes). Hidden files and files excluded by ignore
+/// rules are skipped unless included.
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub case: CaseMode,
+    pub regex: bool,
+    pub whole_word: bool,
+    pub globs: Vec<String>,
+    pub include_hidden: bool,
+    pub include_ignored: bool,
+    pub max_results: Option<usize>,
+}
+
+/// A matching line. `path` is relative to the search root, `line` is
+/// 1-based, and `ranges` are the byte ranges of the matches in `text`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SearchMatch {
+    pub path: String,
+    pub line: u64,
+    pub text: String,
+    pub ranges: Vec<(usize, usize)>,
+}
+
+/// Payload of `search-results`: the next matches of search `search_id`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SearchResults {
+    pub search_id: u32,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Outcome of a finished search. `truncated` is set if it stopped at the
+/// result limit.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SearchSummary {
+    pub search_id: u32,
+    pub matches: usize,
+    pub files: usize,
+    pub truncated: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+enum RgMessage {
+    Match(RgMatch),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct RgMatch {
+    path: RgText,
+    lines: RgText,
+    line_number: Option<u64>,
+    submatches: Vec<RgSubmatch>,
+}
+
+/// rg gives non-UTF-8 data as base64 `bytes` instead of `text`; such
+/// matches are skipped.
+#[derive(Deserialize)]
+struct RgText {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RgSubmatch {
+    start: usize,
+    end: usize,
+}
+
+/// Searches file contents under `root` with ripgrep (`rg`, which must be
+/// installed), emitting matches on `search-results` in batches as they are
+/// found, tagged with the caller's `search_id`. rg is stopped once the
+/// result limit is reached. `.git` is never searched. `root` must lie
+/// inside an open repository or worktree; see `files::resolve_scoped`.
+pub async fn search_repo(
+    app: &AppHandle,
+    sessions: &SessionManager,
+    worktrees: &WorktreeManager,
+    search_id: u32,
+    root: &Path,
+    query: &str,
+    options: SearchOptions,
+) -> Result<SearchSummary, String> {
+    if query.is_empty() {
+        return Err("query must not be empty".to_string());
+    }
+    let root = &files::resolve_scoped(sessions, worktrees, root).await?;
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    let started = Instant::now();
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS).clamp(1, MAX_RESULTS);
+
+    let mut args = vec![
+        "--json",
+        "--max-columns",
+        MAX_LINE_BYTES,
+        "--max-columns-preview",
+        "--glob",
+        "!.git",
+    ];
+    match options.case {
+        CaseMode::Smart => args.push("--smart-case"),
+        CaseMode::Sensitive => args.push("--case-sensitive"),
+        CaseMode::Insensitive => args.push("--ignore-case"),
+    }
+    if !options.regex {
+        args.push("--fixed-strings");
+    }
+    if options.whole_word {
+        args.push("--word-regexp");
+    }
+    if options.include_hidden {
+        args.push("--hidden");
+    }
+    if options.include_ignored {
+        args.push("--no-ignore");
+    }
+    for glob in &options.globs {
+        args.extend(["--glob", glob]);
+    }
+    args.extend(["--regexp", query, "--", "."]);
+
+    let mut child = tokio::process::Command::new("rg")
+        .args(&args)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => "ripgrep (rg) is not installed".to_string(),
+            _ => format!("Failed to run rg: {e}"),
+        })?;
+    let stdout = child.stdout.take().ok_or("rg has no stdout")?;
+    let mut stderr = child.stderr.take().ok_or("rg has no stderr")?;
+    // Drained alongside stdout so a flood of warnings cannot block rg
+    let errors = tauri::async_runtime::spawn(async move {
+        let mut errors = String::new();
+        let _ = stderr.read_to_string(&mut errors).await;
+        errors
+    });
+    let mut lines = BufReader::new(stdout).lines();
+
+    let mut batch = Vec::new();
+    let mut matches = 0;
+    let mut files = 0;
+    let mut last_path = String::new();
+    let mut truncated = false;
+    while let Some(line) = lines.next_line().await.map_err(|e| format!("Failed to read rg output: {e}"))? {
+        let Ok(RgMessage::Match(found)) = serde_json::from_str(&line) else {
+            continue;
+        };
+        let (Some(path), Some(text)) = (found.path.text, found.lines.text) else {
+            continue;
+        };
+        let path = path.strip_prefix("./").map(str::to_string).unwrap_or(path);
+        if path != last_path {
+            files += 1;
+            last_path = path.clone();
+        }
+        batch.push(SearchMatch {
+            path,
+            line: found.line_number.unwrap_or(0),
+            text: text.trim_end_matches(['\n', '\r']).to_string(),
+            ranges: found.submatches.iter().map(|m| (m.start, m.end)).collect(),
+        });
+        matches += 1;
+        if batch.len() >= BATCH_SIZE {
+            emit_batch(app, search_id, &mut batch);
+        }
+        if matches >= max_results {
+            truncated = true;
+            let _ = child.start_kill();
+            break;
+        }
+    }
+    emit_batch(app, search_id, &mut batch);
+
+    let status = child.wait().await.map_err(|e| format!("Failed to run rg: {e}"))?;
+    // rg exits with 1 when nothing matched and 2 on errors, which it also
+    // reports for unreadable files among the matches
+    if !truncated && status.code() == Some(2) && matches == 0 {
+        let errors = errors.await.unwrap_or_default();
+        return Err(format!("rg failed: {}", errors.trim()));
+    }
+    Ok(SearchSummary {
+        search_id,
+        matches,
+        files,
+        truncated,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+fn emit_batch(app: &AppHandle, search_id: u32, batch: &mut Vec<SearchMatch>) {
+    if batch.is_empty() {
+        return;
+    }
+    let results = SearchResults {
+        search_id,
+        matches: std::mem::take(batch),
+    };
+    let _ = app.emit(SEARCH_RESULTS_EVENT, &results);
+}
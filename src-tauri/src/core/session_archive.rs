@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::activity_report::MAX_COMMITS_PER_BRANCH;
+use super::clock;
+use super::repo_config;
+use super::scratchpad::ScratchpadStore;
+use super::session_manager::{AiMode, SessionConfig, SessionListener, SessionStatus, StatusTransition};
+use super::storage;
+use super::transcript;
+use crate::git::{CommitInfo, Git};
+
+/// Current on-disk format of `session-archive.json`.
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Archived runs kept on disk; the oldest are dropped first.
+const MAX_ARCHIVED_SESSIONS: usize = 5000;
+
+/// Record of a session after it was removed.
+///
+/// Times are Unix seconds; `duration_secs` runs from creation to removal.
+/// `cost_usd` is `None` if the agent never reported a cost, and
+/// `transcript_path` if nothing was recorded in the session's transcript.
+/// `scratchpad_path` is where the session's scratchpad was moved on
+/// removal, `None` if it never saved one.
+/// `commits` are the commits made on the session's branch, recorded each
+/// time its agent stopped working so they still count once the branch is
+/// merged or deleted; `None` for sessions archived before they were
+/// recorded.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ArchivedSession {
+    pub session_id: u32,
+    pub title: Option<String>,
+    pub mode: AiMode,
+    #[serde(default)]
+    pub repo_path: String,
+    pub branch: Option<String>,
+    pub worktree_path: Option<String>,
+    pub tags: Vec<String>,
+    pub notes: String,
+    pub final_status: SessionStatus,
+    pub created_at: i64,
+    pub ended_at: i64,
+    pub duration_secs: i64,
+    pub working_secs: i64,
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    #[serde(default)]
+    pub transcript_path: Option<String>,
+    #[serde(default)]
+    pub scratchpad_path: Option<String>,
+    #[serde(default)]
+    pub commits: Option<Vec<ArchivedCommit>>,
+}
+
+/// A commit made on a session's branch. `timestamp` is its author date in
+/// Unix seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ArchivedCommit {
+    pub hash: String,
+    pub timestamp: i64,
+}
+
+/// Commits recorded for a session that is still open, until it is
+/// archived. `created_at` tells it from a later session with the same ID.
+#[derive(Clone, Serialize, Deserialize)]
+struct PendingCommits {
+    created_at: i64,
+    commits: Vec<ArchivedCommit>,
+}
+
+/// Query for `SessionArchive::query`. Every set field must match; `repo_path`
+/// is compared component-wise and `text` is a case-insensitive substring
+/// match on title, branch, and notes. `since`/`until` bound `ended_at`
+/// (inclusive).
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+pub struct ArchiveFilter {
+    pub status: Option<SessionStatus>,
+    pub repo_path: Option<String>,
+    pub branch: Option<String>,
+    pub tag: Option<String>,
+    pub text: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+impl ArchiveFilter {
+    fn matches(&self, entry: &ArchivedSession) -> bool {
+        if self.status.is_some_and(|s| s != entry.final_status) {
+            return false;
+        }
+        if let Some(ref repo_path) = self.repo_path {
+            if Path::new(&entry.repo_path) != Path::new(repo_path) {
+                return false;
+            }
+        }
+        if let Some(ref branch) = self.branch {
+            if entry.branch.as_deref() != Some(branch.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref tag) = self.tag {
+            if !entry.tags.iter().any(|t| t == tag) {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|since| entry.ended_at < since)
+            || self.until.is_some_and(|until| entry.ended_at > until)
+        {
+            return false;
+        }
+        if let Some(ref text) = self.text {
+            let needle = text.to_lowercase();
+            let haystacks = [
+                entry.title.as_deref().unwrap_or(""),
+                entry.branch.as_deref().unwrap_or(""),
+                entry.notes.as_str(),
+            ];
+            if !haystacks.iter().any(|h| h.to_lowercase().contains(&needle)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchiveFile {
+    version: u32,
+    sessions: Vec<ArchivedSession>,
+    #[serde(default)]
+    pending: HashMap<u32, PendingCommits>,
+}
+
+/// Persisted history of finished sessions, appended to whenever a session
+/// is removed.
+///
+/// Registered as a `SessionListener`, so every removal path (commands,
+/// rollback, cleanup) is archived, and the commits on a session's branch
+/// are recorded whenever its agent leaves `Working`. Persistence failures
+/// are logged; the
+/// archive is informational and never blocks a removal. Cheap to clone, so
+/// the same archive can be both a listener and Tauri managed state.
+#[derive(Clone)]
+pub struct SessionArchive {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    file: PathBuf,
+    entries: Mutex<ArchiveFile>,
+    scratchpads: ScratchpadStore,
+}
+
+impl SessionArchive {
+    /// Loads the archive from the data dir, starting empty if the file is
+    /// missing or unreadable. Removed sessions' scratchpads are archived
+    /// through `scratchpads`.
+    pub fn load(scratchpads: ScratchpadStore) -> Self {
+        let file = storage::data_dir().join("session-archive.json");
+        let empty = || ArchiveFile {
+            version: ARCHIVE_VERSION,
+            sessions: Vec::new(),
+            pending: HashMap::new(),
+        };
+        let entries = match storage::load_json::<ArchiveFile>(&file) {
+            Ok(Some(archive)) => archive,
+            Ok(None) => empty(),
+            Err(e) => {
+                log::error!("Failed to load session archive {}: {e}", file.display());
+                empty()
+            }
+        };
+        Self {
+            inner: Arc::new(Inner {
+                file,
+                entries: Mutex::new(entries),
+                scratchpads,
+            }),
+        }
+    }
+
+    /// Returns matching archived sessions, most recently ended first.
+    pub fn query(&self, filter: &ArchiveFilter) -> Vec<ArchivedSession> {
+        self.lock()
+            .sessions
+            .iter()
+            .rev()
+            .filter(|e| filter.matches(e))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the commits recorded so far for `session`, which is still
+    /// open.
+    pub fn recorded_commits(&self, session: &SessionConfig) -> Vec<ArchivedCommit> {
+        match self.lock().pending.get(&session.id) {
+            Some(pending) if pending.created_at == session.created_at => pending.commits.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn archive(&self, session: &SessionConfig) {
+        let ended_at = clock::now_secs();
+        let scratchpad_path = self.inner.scratchpads.archive(session);
+        let mut entries = self.lock();
+        let commits = match entries.pending.remove(&session.id) {
+            Some(pending) if pending.created_at == session.created_at => Some(pending.commits),
+            _ if session.branch.is_some() => None,
+            _ => Some(Vec::new()),
+        };
+        let entry = ArchivedSession {
+            session_id: session.id,
+            title: session.title.clone(),
+            mode: session.mode.clone(),
+            repo_path: session.repo_path.clone(),
+            branch: session.branch.clone(),
+            worktree_path: session.worktree_path.clone(),
+            tags: session.tags.clone(),
+            notes: session.notes.clone(),
+            final_status: session.status,
+            created_at: session.created_at,
+            ended_at,
+            duration_secs: (ended_at - session.created_at).max(0),
+            working_secs: session.working_secs,
+            cost_usd: session.cost_usd,
+            transcript_path: Some(transcript::transcript_path(session))
+                .filter(|path| path.exists())
+                .map(|path| path.to_string_lossy().into_owned()),
+            scratchpad_path: scratchpad_path.map(|path| path.to_string_lossy().into_owned()),
+            commits,
+        };
+
+        entries.sessions.push(entry);
+        let excess = entries.sessions.len().saturating_sub(MAX_ARCHIVED_SESSIONS);
+        entries.sessions.drain(..excess);
+        self.save(&entries);
+    }
+
+    /// Adds `commits` to those recorded for `session`, whether it is still
+    /// open or was archived while they were being read.
+    fn record_commits(&self, session: &SessionConfig, commits: Vec<CommitInfo>) {
+        let mut entries = self.lock();
+        let archived = entries
+            .sessions
+            .iter_mut()
+            .rev()
+            .find(|e| e.session_id == session.id && e.created_at == session.created_at);
+        let recorded = match archived {
+            Some(entry) => entry.commits.get_or_insert_with(Vec::new),
+            None => {
+                let pending = entries.pending.entry(session.id).or_insert_with(|| PendingCommits {
+                    created_at: session.created_at,
+                    commits: Vec::new(),
+                });
+                if pending.created_at != session.created_at {
+                    *pending = PendingCommits {
+                        created_at: session.created_at,
+                        commits: Vec::new(),
+                    };
+                }
+                &mut pending.commits
+            }
+        };
+        let mut changed = false;
+        for commit in commits {
+            if !recorded.iter().any(|c| c.hash == commit.hash) {
+                recorded.push(ArchivedCommit {
+                    hash: commit.hash,
+                    timestamp: commit.timestamp,
+                });
+                changed = true;
+            }
+        }
+        if changed {
+            self.save(&entries);
+        }
+    }
+
+    fn save(&self, entries: &ArchiveFile) {
+        if let Err(e) = storage::save_json(&self.inner.file, entries) {
+            log::error!("Failed to persist session archive {}: {e}", self.inner.file.display());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ArchiveFile> {
+        match self.inner.entries.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl SessionListener for SessionArchive {
+    fn on_status_changed(&self, session: &SessionConfig, transition: &StatusTransition) {
+        if transition.from != Some(SessionStatus::Working) || transition.to == SessionStatus::Working {
+            return;
+        }
+        let (Some(repo), Some(branch)) = (session.repo(), session.branch.clone()) else {
+            return;
+        };
+        let repo = repo.to_path_buf();
+        let dir = session.worktree_path.clone().map_or_else(|| repo.clone(), PathBuf::from);
+        let archive = self.clone();
+        let session = session.clone();
+        tauri::async_runtime::spawn(async move {
+            let commits = match repo_config::base_branch(&repo).await {
+                Ok(base) if base != branch => {
+                    Git::new(&dir).commits_between(&base, &branch, MAX_COMMITS_PER_BRANCH).await
+                }
+                Ok(_) => return,
+                Err(e) => Err(e),
+            };
+            match commits {
+                Ok(commits) => archive.record_commits(&session, commits),
+                Err(e) => log::warn!("Failed to record the commits of session {}: {e}", session.id),
+            }
+        });
+    }
+
+    fn on_removed(&self, session: &SessionConfig) {
+        self.archive(session);
+    }
+}
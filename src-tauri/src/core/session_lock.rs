@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::clock;
+use super::error::{AppError, ErrorCode};
+use super::session_manager::{SessionConfig, SessionManager};
+
+/// A long operation holding a session's checkout, shown on the session
+/// while it runs. `since` is a Unix epoch timestamp.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct SessionLock {
+    pub operation: String,
+    pub since: i64,
+}
+
+/// Checkouts locked by running operations, keyed by canonical path.
+fn held() -> &'static Mutex<HashMap<PathBuf, SessionLock>> {
+    static HELD: OnceLock<Mutex<HashMap<PathBuf, SessionLock>>> = OnceLock::new();
+    HELD.get_or_init(Default::default)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn key(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The checkout a session works in: its worktree, else its repository.
+fn checkout_of(session: &SessionConfig) -> Option<PathBuf> {
+    session
+        .worktree_path
+        .as_deref()
+        .or(Some(session.repo_path.as_str()).filter(|p| !p.is_empty()))
+        .map(|p| key(Path::new(p)))
+}
+
+/// Advisory lock on one or more checkouts for the length of a destructive
+/// operation (merge, rebase, removal), so another window or an automation
+/// cannot run a conflicting one at the same time. Released on drop.
+///
+/// Locks are only checked by operations that take them, and live in memory
+/// only, so a crash never leaves a checkout locked.
+pub struct OperationLock<'a> {
+    sessions: Option<&'a SessionManager>,
+    paths: Vec<PathBuf>,
+}
+
+impl<'a> OperationLock<'a> {
+    /// Locks every checkout in `paths` for `operation`, or none of them:
+    /// fails with `Busy` if any is already locked. Sessions working in a
+    /// locked checkout show the lock as their `lock`.
+    pub fn acquire(sessions: &'a SessionManager, operation: &str, paths: &[&Path]) -> Result<Self, AppError> {
+        let locked = Self::lock_paths(Some(sessions), operation, paths)?;
+        locked.mark(Some(SessionLock {
+            operation: operation.to_string(),
+            since: clock::now_secs(),
+        }));
+        Ok(locked)
+    }
+
+    /// Locks `paths` like `acquire` without marking any session, for
+    /// checkouts no session works in (e.g. orphaned directories).
+    pub fn acquire_unmarked(operation: &str, paths: &[&Path]) -> Result<OperationLock<'static>, AppError> {
+        OperationLock::lock_paths(None, operation, paths)
+    }
+
+    fn lock_paths(sessions: Option<&'a SessionManager>, operation: &str, paths: &[&Path]) -> Result<Self, AppError> {
+        let mut paths: Vec<PathBuf> = paths.iter().map(|p| key(p)).collect();
+        paths.sort();
+        paths.dedup();
+        let entry = SessionLock {
+            operation: operation.to_string(),
+            since: clock::now_secs(),
+        };
+        {
+            let mut held = lock(held());
+            if let Some((path, other)) = paths.iter().find_map(|p| held.get(p).map(|l| (p, l))) {
+                return Err(AppError::new(
+                    ErrorCode::Busy,
+                    format!("{} is busy: {} is in progress", path.display(), other.operation),
+                )
+                .with("path", path.display())
+                .with("operation", &other.operation));
+            }
+            for path in &paths {
+                held.insert(path.clone(), entry.clone());
+            }
+        }
+        Ok(Self { sessions, paths })
+    }
+
+    /// Locks the checkout of session `session_id`, like `acquire`.
+    pub fn for_session(sessions: &'a SessionManager, operation: &str, session_id: u32) -> Result<Self, AppError> {
+        let session = sessions
+            .get_session(session_id)
+            .ok_or_else(|| AppError::session_not_found(session_id))?;
+        let paths: Vec<PathBuf> = checkout_of(&session).into_iter().collect();
+        let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        Self::acquire(sessions, operation, &paths)
+    }
+
+    /// Sets the `lock` of the sessions working in the locked checkouts.
+    fn mark(&self, entry: Option<SessionLock>) {
+        let Some(sessions) = self.sessions else {
+            return;
+        };
+        for session in sessions.all_sessions() {
+            if checkout_of(&session).is_some_and(|p| self.paths.contains(&p)) {
+                sessions.modify(session.id, |s| s.lock = entry.clone());
+            }
+        }
+    }
+}
+
+impl Drop for OperationLock<'_> {
+    fn drop(&mut self) {
+        let mut held = lock(held());
+        for path in &self.paths {
+            held.remove(path);
+        }
+        drop(held);
+        self.mark(None);
+    }
+}
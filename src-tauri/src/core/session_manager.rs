@@ -1,26 +1,55 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
 use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+
+use super::approval::ApprovalPolicy;
+use super::auto_push::AutoPushConfig;
+use super::issues::IssueLink;
+use super::pull_request::CiStatus;
+use super::session_lock::SessionLock;
+use super::test_runner::TestResult;
+use super::{clock, storage};
+
+/// Current on-disk format of `sessions.json`. Bump when `SessionConfig`
+/// changes incompatibly.
+const SESSIONS_VERSION: u32 = 1;
+
+/// Status transitions kept per session; older entries are dropped first.
+const MAX_STATUS_HISTORY: usize = 1000;
 
 /// Which AI backend a session is configured to use.
 ///
 /// `Plain` is a raw terminal with no AI agent attached, useful for
-/// manual shell work within a worktree.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// manual shell work within a worktree. `Custom` names a user-defined agent
+/// registered with `AgentLauncher`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
 pub enum AiMode {
     Claude,
     Gemini,
     Codex,
     Plain,
+    Custom(String),
 }
 
 /// Lifecycle state of a session, tracked for UI status indicators.
 ///
-/// Transitions are driven by the frontend; the backend does not enforce
+/// Transitions are driven by the frontend and, for agent sessions, by
+/// `StatusInference` watching terminal output; the backend does not enforce
 /// a state machine. Invalid transitions (e.g., `Done` -> `Working`) are
-/// allowed and the caller is responsible for correctness.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// allowed and the caller is responsible for correctness. `Blocked` means
+/// the session waits for the session in `depends_on` to reach `Done`;
+/// `Queued` means the agent launch is waiting for a free slot in
+/// `LaunchScheduler`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
 pub enum SessionStatus {
+    Blocked,
+    Queued,
     Starting,
     Idle,
     Working,
@@ -33,22 +62,281 @@ pub enum SessionStatus {
 ///
 /// `branch` and `worktree_path` are `None` until `assign_branch` is called,
 /// allowing sessions to be created before their worktree is ready.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `pty_id` is the `ProcessManager` session running this session's terminal;
+/// it is never persisted across restarts since PTYs do not survive them.
+/// `position` and `pinned` define the user-controlled list order: pinned
+/// sessions first, then ascending `position`, ties broken by `id`.
+/// Timestamps are Unix seconds. `last_output_at` is refreshed on terminal
+/// output without emitting an event, and `working_secs` accumulates
+/// completed periods in `Working` (the current one is added by `activity`).
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct SessionConfig {
     pub id: u32,
     pub mode: AiMode,
+    /// Repository the session works in. Empty only for sessions persisted
+    /// before repositories were tracked.
+    #[serde(default)]
+    pub repo_path: String,
     pub branch: Option<String>,
     pub status: SessionStatus,
     pub worktree_path: Option<String>,
+    #[serde(default)]
+    pub pty_id: Option<u32>,
+    /// User-chosen display name; `None` falls back to the default label.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Freeform user notes.
+    #[serde(default)]
+    pub notes: String,
+    /// User tags, trimmed and de-duplicated, in insertion order.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub position: u32,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub profile: LaunchProfile,
+    /// Agent permission prompts answered without the user; see
+    /// `status_inference`.
+    #[serde(default)]
+    pub approval: ApprovalPolicy,
+    /// Where the branch is pushed once the session is `Done`; `None` keeps
+    /// it local. See `auto_push`.
+    #[serde(default)]
+    pub auto_push: Option<AutoPushConfig>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub last_output_at: Option<i64>,
+    #[serde(default)]
+    pub working_secs: i64,
+    /// Session that must reach `Done` before this one is started; see
+    /// `orchestrator::create_dependent_session`.
+    #[serde(default)]
+    pub depends_on: Option<u32>,
+    /// Total agent cost in US dollars, summed over the runs whose cost the
+    /// agent reported. `None` if none was reported.
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    /// The agent's own ID for its latest conversation, used to resume it.
+    #[serde(default)]
+    pub agent_session_id: Option<String>,
+    /// Fan-out this session belongs to; see `orchestrator::fanout_task`.
+    #[serde(default)]
+    pub comparison_group: Option<String>,
+    /// Issue the session was created from; see `issues::import_issue`.
+    #[serde(default)]
+    pub issue: Option<IssueLink>,
+    /// Pull request opened for the session's branch; see
+    /// `pull_request::create_pr`.
+    #[serde(default)]
+    pub pr_number: Option<u64>,
+    #[serde(default)]
+    pub pr_url: Option<String>,
+    /// CI result of the branch when last fetched; see `ci_checks`.
+    #[serde(default)]
+    pub ci_status: Option<CiStatus>,
+    /// Outcome of the latest `test_runner::run_tests` in the worktree.
+    #[serde(default)]
+    pub test_result: Option<TestResult>,
+    /// Operation currently holding the session's checkout; see
+    /// `session_lock::OperationLock`.
+    #[serde(default)]
+    pub lock: Option<SessionLock>,
+}
+
+impl SessionConfig {
+    /// Returns the session's repository, or `None` if it was never recorded.
+    pub fn repo(&self) -> Option<&Path> {
+        (!self.repo_path.is_empty()).then(|| Path::new(&self.repo_path))
+    }
+}
+
+/// Environment applied when a session's shell is spawned.
+///
+/// `env` is set on the shell process and inherited by the agent;
+/// `extra_path` entries are prepended to `PATH` in order. `startup_script`
+/// is typed into the shell before the agent is launched. `test_command` is
+/// run in the worktree, with the same environment, to harvest the session's
+/// result once it is `Done`. Note that login profiles which reset `PATH`
+/// unconditionally will override `extra_path`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct LaunchProfile {
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub extra_path: Vec<String>,
+    #[serde(default)]
+    pub startup_script: Option<String>,
+    #[serde(default)]
+    pub test_command: Option<String>,
+}
+
+impl LaunchProfile {
+    /// Returns the variables to set on the shell, with `PATH` extended by
+    /// `extra_path` (based on `env["PATH"]` if set, else the app's own).
+    pub fn shell_env(&self) -> BTreeMap<String, String> {
+        let mut env = self.env.clone();
+        if !self.extra_path.is_empty() {
+            let base = env
+                .get("PATH")
+                .cloned()
+                .or_else(|| std::env::var("PATH").ok())
+                .unwrap_or_default();
+            let mut entries = self.extra_path.clone();
+            if !base.is_empty() {
+                entries.push(base);
+            }
+            env.insert("PATH".to_string(), entries.join(":"));
+        }
+        env
+    }
+
+    /// Rejects variable names the OS cannot represent.
+    pub fn validate(&self) -> Result<(), String> {
+        match self
+            .env
+            .keys()
+            .find(|k| k.is_empty() || k.contains(['=', '\0']))
+        {
+            Some(key) => Err(format!("Invalid environment variable name '{key}'")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Partial update for the user-editable session fields. `None` leaves a
+/// field unchanged; an empty `title` clears it.
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+pub struct SessionDetailsPatch {
+    pub title: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// What triggered a status change: session creation, a frontend call,
+/// `StatusInference` reading terminal output, the session's shell exiting,
+/// or backend scheduling (launch queue or pipeline dependencies).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum StatusCause {
+    Created,
+    Manual,
+    AutoDetected,
+    Exit,
+    Scheduled,
+}
+
+/// One entry in a session's status history. `from` is `None` for the
+/// initial status recorded at creation; `at` is Unix seconds. `detail` is
+/// optional context, such as the detected prompt line or the exit code.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StatusTransition {
+    pub from: Option<SessionStatus>,
+    pub to: SessionStatus,
+    pub at: i64,
+    pub cause: StatusCause,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// Status history of a session plus the total seconds spent in each status,
+/// with the current status counted up to now.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SessionHistory {
+    pub session_id: u32,
+    pub transitions: Vec<StatusTransition>,
+    pub time_in_status: HashMap<SessionStatus, i64>,
+}
+
+/// Point-in-time activity summary of a session, for spotting stuck agents.
+///
+/// `status_since` is when the current status was entered; `working_secs`
+/// includes the ongoing `Working` period, if any. `idle_secs` is the time
+/// since the last terminal output (or since creation if there was none).
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SessionActivity {
+    pub session_id: u32,
+    pub title: Option<String>,
+    pub branch: Option<String>,
+    pub status: SessionStatus,
+    pub created_at: i64,
+    pub last_output_at: Option<i64>,
+    pub status_since: i64,
+    pub secs_in_status: i64,
+    pub working_secs: i64,
+    pub idle_secs: i64,
+}
+
+/// Kind of change reported to the frontend, one Tauri event name each.
+#[derive(Debug, Clone, Copy)]
+pub enum SessionChange {
+    Created,
+    Updated,
+    Removed,
+}
+
+impl SessionChange {
+    /// Event name emitted for this change; the payload is the `SessionConfig`.
+    pub fn event_name(self) -> &'static str {
+        match self {
+            SessionChange::Created => "session-created",
+            SessionChange::Updated => "session-updated",
+            SessionChange::Removed => "session-removed",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionsFile {
+    version: u32,
+    sessions: Vec<SessionConfig>,
+    #[serde(default)]
+    history: HashMap<u32, Vec<StatusTransition>>,
+}
+
+/// Observer of session lifecycle changes, registered with
+/// `SessionManager::add_listener`.
+///
+/// Callbacks run synchronously on the thread that made the change, after it
+/// has been persisted and no map guards are held, so listeners may call back
+/// into the manager. They must not block.
+pub trait SessionListener: Send + Sync {
+    /// Called after a session's status actually changed; `transition` is
+    /// the history entry just recorded.
+    fn on_status_changed(&self, _session: &SessionConfig, _transition: &StatusTransition) {}
+
+    /// Called after a session was removed.
+    fn on_removed(&self, _session: &SessionConfig) {}
+}
+
+/// Write-through persistence target for `SessionManager`.
+///
+/// `write_lock` serializes saves so concurrent mutations cannot interleave
+/// writes to the shared temp file; the snapshot is taken inside the lock so
+/// the last writer always persists the newest state.
+struct SessionStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
 }
 
 /// Thread-safe session registry backed by `DashMap` for lock-free concurrent reads.
 ///
 /// Designed to be placed in Tauri managed state. All methods take `&self` so
 /// no exclusive access is needed, enabling safe concurrent access from
-/// multiple async command handlers.
+/// multiple async command handlers. When created with `load`, every mutation
+/// is written through to `sessions.json` in the data dir. Once an app handle
+/// is attached, every mutation also emits `session-created`,
+/// `session-updated`, or `session-removed` so all windows stay in sync,
+/// whether the change came from a command or from backend automation.
+/// Status changes are also appended to a per-session history, persisted in
+/// the same file.
 pub struct SessionManager {
     sessions: DashMap<u32, SessionConfig>,
+    history: DashMap<u32, Vec<StatusTransition>>,
+    store: Option<SessionStore>,
+    app_handle: OnceLock<AppHandle>,
+    listeners: RwLock<Vec<Arc<dyn SessionListener>>>,
 }
 
 impl Default for SessionManager {
@@ -58,29 +346,227 @@ impl Default for SessionManager {
 }
 
 impl SessionManager {
-    /// Creates an empty session registry.
+    /// Creates an empty, in-memory-only session registry.
     pub fn new() -> Self {
         Self {
             sessions: DashMap::new(),
+            history: DashMap::new(),
+            store: None,
+            app_handle: OnceLock::new(),
+            listeners: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Creates a registry backed by `sessions.json` in the data dir, loading
+    /// any sessions saved by a previous run.
+    ///
+    /// A missing file starts empty. An unreadable file, or one written by a
+    /// newer Maestro (higher `version`), is left untouched and the manager
+    /// runs in-memory only, so user data is never overwritten with a format
+    /// this build does not understand.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("sessions.json");
+        let sessions = DashMap::new();
+        let history = DashMap::new();
+
+        match storage::load_json::<SessionsFile>(&path) {
+            Ok(Some(file)) if file.version > SESSIONS_VERSION => {
+                log::error!(
+                    "{} has version {} (newer than supported {}); sessions will not be persisted",
+                    path.display(),
+                    file.version,
+                    SESSIONS_VERSION
+                );
+                return Self::new();
+            }
+            Ok(Some(file)) => {
+                for mut session in file.sessions {
+                    // The PTY died with the previous process, and the launch
+                    // queue and any operation locks with it
+                    session.pty_id = None;
+                    session.lock = None;
+                    if session.status == SessionStatus::Queued {
+                        session.status = SessionStatus::Idle;
+                    }
+                    sessions.insert(session.id, session);
+                }
+                for (id, transitions) in file.history {
+                    if sessions.contains_key(&id) {
+                        history.insert(id, transitions);
+                    }
+                }
+                log::info!("Loaded {} persisted session(s)", sessions.len());
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to load {}: {e}; sessions will not be persisted", path.display());
+                return Self::new();
+            }
+        }
+
+        Self {
+            sessions,
+            history,
+            store: Some(SessionStore {
+                path,
+                write_lock: Mutex::new(()),
+            }),
+            app_handle: OnceLock::new(),
+            listeners: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Attaches the app handle used to emit session change events. Called once
+    /// from `setup`; later calls are ignored.
+    pub fn attach_app_handle(&self, app_handle: AppHandle) {
+        let _ = self.app_handle.set(app_handle);
+    }
+
+    /// Registers a listener for status changes and removals.
+    pub fn add_listener(&self, listener: Arc<dyn SessionListener>) {
+        match self.listeners.write() {
+            Ok(mut listeners) => listeners.push(listener),
+            Err(poisoned) => poisoned.into_inner().push(listener),
+        }
+    }
+
+    /// Snapshot of registered listeners, so callbacks run without the lock held.
+    fn listeners(&self) -> Vec<Arc<dyn SessionListener>> {
+        match self.listeners.read() {
+            Ok(listeners) => listeners.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Persists the new state and notifies listeners of a change to `config`.
+    /// Must be called after all map guards are released.
+    fn changed(&self, change: SessionChange, config: &SessionConfig) {
+        self.persist();
+        self.emit(change, config);
+    }
+
+    fn emit(&self, change: SessionChange, config: &SessionConfig) {
+        if let Some(app) = self.app_handle.get() {
+            if let Err(e) = app.emit(change.event_name(), config) {
+                log::warn!("Failed to emit {}: {e}", change.event_name());
+            }
+        }
+    }
+
+    /// Writes the current session set to disk if persistence is enabled.
+    /// Failures are logged; the in-memory state remains authoritative.
+    fn persist(&self) {
+        let Some(ref store) = self.store else {
+            return;
+        };
+        let _guard = match store.write_lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let mut sessions = self.all_sessions();
+        sessions.sort_by_key(|s| s.id);
+        let history = self
+            .history
+            .iter()
+            .map(|e| (*e.key(), e.value().clone()))
+            .collect();
+        let file = SessionsFile {
+            version: SESSIONS_VERSION,
+            sessions,
+            history,
+        };
+        if let Err(e) = storage::save_json(&store.path, &file) {
+            log::error!("Failed to persist sessions to {}: {e}", store.path.display());
         }
     }
 
-    /// Inserts a new session with `Starting` status and no branch assigned.
-    /// Returns `Err` with the existing config if a session with this ID already exists.
-    pub fn create_session(&self, id: u32, mode: AiMode) -> Result<SessionConfig, SessionConfig> {
+    /// Inserts a new session in `repo_path` with `Starting` status and no
+    /// branch assigned. Returns `Err` with the existing config if a session
+    /// with this ID already exists.
+    pub fn create_session(
+        &self,
+        id: u32,
+        mode: AiMode,
+        repo_path: String,
+    ) -> Result<SessionConfig, Box<SessionConfig>> {
+        // New sessions go to the end of the list
+        let position = self
+            .sessions
+            .iter()
+            .map(|e| e.value().position.saturating_add(1))
+            .max()
+            .unwrap_or(0);
         let config = SessionConfig {
             id,
             mode,
+            repo_path,
             branch: None,
             status: SessionStatus::Starting,
             worktree_path: None,
+            pty_id: None,
+            title: None,
+            notes: String::new(),
+            tags: Vec::new(),
+            position,
+            pinned: false,
+            profile: LaunchProfile::default(),
+            approval: ApprovalPolicy::default(),
+            auto_push: None,
+            created_at: clock::now_secs(),
+            last_output_at: None,
+            working_secs: 0,
+            depends_on: None,
+            cost_usd: None,
+            agent_session_id: None,
+            comparison_group: None,
+            issue: None,
+            pr_number: None,
+            pr_url: None,
+            ci_status: None,
+            test_result: None,
+            lock: None,
         };
-        match self.sessions.entry(id) {
-            Entry::Occupied(e) => Err(e.get().clone()),
+        let result = match self.sessions.entry(id) {
+            Entry::Occupied(e) => Err(Box::new(e.get().clone())),
             Entry::Vacant(e) => {
                 e.insert(config.clone());
                 Ok(config)
             }
+        };
+        if let Ok(ref config) = result {
+            self.history.insert(
+                id,
+                vec![StatusTransition {
+                    from: None,
+                    to: config.status,
+                    at: clock::now_secs(),
+                    cause: StatusCause::Created,
+                    detail: None,
+                }],
+            );
+        }
+        // Notify only after the entry guard is released; `persist` iterates the map.
+        if let Ok(ref config) = result {
+            self.changed(SessionChange::Created, config);
+        }
+        result
+    }
+
+    /// Inserts a new session under the next free ID (one above the current
+    /// maximum). Used by backend-driven creation where no frontend-chosen ID
+    /// exists; retries if a concurrent caller claims the same ID first.
+    pub fn create_next_session(&self, mode: AiMode, repo_path: String) -> SessionConfig {
+        loop {
+            let id = self
+                .sessions
+                .iter()
+                .map(|e| *e.key())
+                .max()
+                .map_or(1, |max| max.saturating_add(1));
+            if let Ok(config) = self.create_session(id, mode.clone(), repo_path.clone()) {
+                return config;
+            }
         }
     }
 
@@ -89,36 +575,269 @@ impl SessionManager {
         self.sessions.get(&id).map(|s| s.clone())
     }
 
-    /// Updates the session's status in place. Returns `false` if the session
-    /// does not exist (no error is raised).
-    pub fn update_status(&self, id: u32, status: SessionStatus) -> bool {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
+    /// Updates the session's status in place, recording the transition and
+    /// its `cause` in the history if the status actually changed. Returns
+    /// `false` if the session does not exist (no error is raised).
+    pub fn update_status(&self, id: u32, status: SessionStatus, cause: StatusCause) -> bool {
+        self.update_status_with_detail(id, status, cause, None)
+    }
+
+    /// Like `update_status`, attaching `detail` to the recorded transition.
+    pub fn update_status_with_detail(
+        &self,
+        id: u32,
+        status: SessionStatus,
+        cause: StatusCause,
+        detail: Option<String>,
+    ) -> bool {
+        let mut transition = None;
+        let updated = {
+            let Some(mut session) = self.sessions.get_mut(&id) else {
+                return false;
+            };
+            let from = session.status;
             session.status = status;
-            true
-        } else {
-            false
+            if from != status {
+                let now = clock::now_secs();
+                let mut history = self.history.entry(id).or_default();
+                if from == SessionStatus::Working {
+                    let since = history.last().map_or(session.created_at, |t| t.at);
+                    session.working_secs += (now - since).max(0);
+                }
+                let entry = StatusTransition {
+                    from: Some(from),
+                    to: status,
+                    at: now,
+                    cause,
+                    detail,
+                };
+                history.push(entry.clone());
+                transition = Some(entry);
+                let excess = history.len().saturating_sub(MAX_STATUS_HISTORY);
+                history.drain(..excess);
+            }
+            session.clone()
+        };
+        self.changed(SessionChange::Updated, &updated);
+        if let Some(transition) = transition {
+            for listener in self.listeners() {
+                listener.on_status_changed(&updated, &transition);
+            }
         }
+        true
+    }
+
+    /// Stamps `last_output_at` on a session. Deliberately neither persisted
+    /// nor emitted on its own, since it changes on every chunk of output; it
+    /// is saved with the next real change.
+    pub fn record_output(&self, id: u32) {
+        if let Some(mut session) = self.sessions.get_mut(&id) {
+            session.last_output_at = Some(clock::now_secs());
+        }
+    }
+
+    /// Adds the cost of one agent run to the session's total, then persists
+    /// and emits `session-updated`.
+    pub fn add_cost(&self, id: u32, usd: f64) -> Option<SessionConfig> {
+        self.modify(id, |session| {
+            session.cost_usd = Some(session.cost_usd.unwrap_or(0.0) + usd);
+        })
+    }
+
+    /// Returns activity summaries for all sessions, in display order.
+    pub fn activity(&self) -> Vec<SessionActivity> {
+        let now = clock::now_secs();
+        self.all_sessions()
+            .into_iter()
+            .map(|session| {
+                let status_since = self
+                    .history
+                    .get(&session.id)
+                    .and_then(|h| h.last().map(|t| t.at))
+                    .unwrap_or(session.created_at);
+                let mut working_secs = session.working_secs;
+                if session.status == SessionStatus::Working {
+                    working_secs += (now - status_since).max(0);
+                }
+                SessionActivity {
+                    session_id: session.id,
+                    title: session.title,
+                    branch: session.branch,
+                    status: session.status,
+                    created_at: session.created_at,
+                    last_output_at: session.last_output_at,
+                    status_since,
+                    secs_in_status: (now - status_since).max(0),
+                    working_secs,
+                    idle_secs: (now - session.last_output_at.unwrap_or(session.created_at)).max(0),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the session's status history and time spent per status, or
+    /// `None` if the session does not exist. Times only cover the retained
+    /// history (the last `MAX_STATUS_HISTORY` transitions).
+    pub fn history(&self, id: u32) -> Option<SessionHistory> {
+        if !self.sessions.contains_key(&id) {
+            return None;
+        }
+        let transitions = self.history.get(&id).map(|h| h.clone()).unwrap_or_default();
+        let now = clock::now_secs();
+        let mut time_in_status = HashMap::new();
+        for (i, transition) in transitions.iter().enumerate() {
+            let end = transitions.get(i + 1).map_or(now, |next| next.at);
+            *time_in_status.entry(transition.to).or_insert(0) += (end - transition.at).max(0);
+        }
+        Some(SessionHistory {
+            session_id: id,
+            transitions,
+            time_in_status,
+        })
+    }
+
+    /// Applies `f` to a session, then persists and emits `session-updated`.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn modify(&self, id: u32, f: impl FnOnce(&mut SessionConfig)) -> Option<SessionConfig> {
+        let updated = {
+            let mut session = self.sessions.get_mut(&id)?;
+            f(&mut session);
+            session.clone()
+        };
+        self.changed(SessionChange::Updated, &updated);
+        Some(updated)
     }
 
     /// Associates a branch (and optional worktree path) with an existing session.
     /// Returns the updated config, or `None` if the session does not exist.
     pub fn assign_branch(&self, id: u32, branch: String, worktree_path: Option<String>) -> Option<SessionConfig> {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
+        self.modify(id, |session| {
             session.branch = Some(branch);
             session.worktree_path = worktree_path;
-            Some(session.clone())
-        } else {
-            None
+        })
+    }
+
+    /// Updates the title, notes, and/or tags of a session. Returns the
+    /// updated config, or `None` if the session does not exist.
+    pub fn update_details(&self, id: u32, patch: SessionDetailsPatch) -> Option<SessionConfig> {
+        self.modify(id, |session| {
+            if let Some(title) = patch.title {
+                let title = title.trim();
+                session.title = (!title.is_empty()).then(|| title.to_string());
+            }
+            if let Some(notes) = patch.notes {
+                session.notes = notes;
+            }
+            if let Some(tags) = patch.tags {
+                session.tags.clear();
+                for tag in tags {
+                    let tag = tag.trim();
+                    if !tag.is_empty() && !session.tags.iter().any(|t| t == tag) {
+                        session.tags.push(tag.to_string());
+                    }
+                }
+            }
+        })
+    }
+
+    /// Binds a PTY to the session, replacing any previous binding. Returns the
+    /// updated config, or `None` if the session does not exist.
+    pub fn bind_pty(&self, id: u32, pty_id: u32) -> Option<SessionConfig> {
+        self.set_pty(id, Some(pty_id))
+    }
+
+    /// Clears the session's PTY binding. Returns the updated config, or `None`
+    /// if the session does not exist.
+    pub fn unbind_pty(&self, id: u32) -> Option<SessionConfig> {
+        self.set_pty(id, None)
+    }
+
+    fn set_pty(&self, id: u32, pty_id: Option<u32>) -> Option<SessionConfig> {
+        self.modify(id, |session| session.pty_id = pty_id)
+    }
+
+    /// Pins or unpins a session. Pinned sessions sort before all others.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn set_pinned(&self, id: u32, pinned: bool) -> Option<SessionConfig> {
+        self.modify(id, |session| session.pinned = pinned)
+    }
+
+    /// Rewrites session positions so that `ids` come first in the given order,
+    /// followed by any sessions not listed in their current order. Unknown and
+    /// duplicate IDs are ignored. Persists once and emits `session-updated`
+    /// for every session whose position changed; returns the new ordering.
+    pub fn reorder(&self, ids: &[u32]) -> Vec<SessionConfig> {
+        let current = self.all_sessions();
+        let mut order: Vec<u32> = Vec::with_capacity(current.len());
+        for &id in ids {
+            if !order.contains(&id) && self.sessions.contains_key(&id) {
+                order.push(id);
+            }
+        }
+        for session in &current {
+            if !order.contains(&session.id) {
+                order.push(session.id);
+            }
+        }
+
+        let mut updated = Vec::new();
+        for (position, id) in order.iter().enumerate() {
+            let position = position as u32;
+            if let Some(mut session) = self.sessions.get_mut(id) {
+                if session.position != position {
+                    session.position = position;
+                    updated.push(session.clone());
+                }
+            }
         }
+        if !updated.is_empty() {
+            self.persist();
+            for session in &updated {
+                self.emit(SessionChange::Updated, session);
+            }
+        }
+        self.all_sessions()
+    }
+
+    /// Returns the session bound to `pty_id`, if any.
+    pub fn session_for_pty(&self, pty_id: u32) -> Option<SessionConfig> {
+        self.sessions
+            .iter()
+            .find(|e| e.value().pty_id == Some(pty_id))
+            .map(|e| e.value().clone())
     }
 
-    /// Returns a snapshot of all active sessions. Order is not guaranteed.
+    /// Returns a snapshot of all active sessions in display order: pinned
+    /// first, then by `position`, then by `id`.
     pub fn all_sessions(&self) -> Vec<SessionConfig> {
-        self.sessions.iter().map(|e| e.value().clone()).collect()
+        let mut sessions: Vec<SessionConfig> = self.sessions.iter().map(|e| e.value().clone()).collect();
+        sessions.sort_by_key(|s| (!s.pinned, s.position, s.id));
+        sessions
     }
 
-    /// Removes and returns a session. Returns `None` if not found.
+    /// Returns the sessions of one repository in display order. Paths are
+    /// compared component-wise, so a trailing slash does not matter.
+    pub fn sessions_in_repo(&self, repo_path: &Path) -> Vec<SessionConfig> {
+        self.all_sessions()
+            .into_iter()
+            .filter(|s| s.repo() == Some(repo_path))
+            .collect()
+    }
+
+    /// Removes and returns a session. Returns `None` if not found. An ongoing
+    /// `Working` period is folded into the returned `working_secs`, so
+    /// listeners see the session's final totals.
     pub fn remove_session(&self, id: u32) -> Option<SessionConfig> {
-        self.sessions.remove(&id).map(|(_, v)| v)
+        let mut removed = self.sessions.remove(&id).map(|(_, v)| v)?;
+        let history = self.history.remove(&id).map(|(_, h)| h).unwrap_or_default();
+        if removed.status == SessionStatus::Working {
+            let since = history.last().map_or(removed.created_at, |t| t.at);
+            removed.working_secs += (clock::now_secs() - since).max(0);
+        }
+        self.changed(SessionChange::Removed, &removed);
+        for listener in self.listeners() {
+            listener.on_removed(&removed);
+        }
+        Some(removed)
     }
 }
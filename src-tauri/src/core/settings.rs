@@ -0,0 +1,280 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::agent_launcher::{AgentCommands, AgentLauncher};
+use super::logging::{self, LogLevel};
+use super::memory_budget::MemoryBudget;
+use super::process_manager::ProcessManager;
+use super::scheduler::LaunchScheduler;
+use super::storage;
+use super::task_queue::TaskQueue;
+use super::worktree_manager;
+
+/// Current on-disk format of `settings.json`.
+const SETTINGS_VERSION: u32 = 1;
+
+/// Longest grace period `kill_grace_secs` may set.
+const MAX_KILL_GRACE_SECS: u64 = 60;
+
+/// Event emitted with the new `Settings` whenever they change.
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// Application settings edited from the settings panel.
+///
+/// `worktree_base_dir` overrides where managed worktrees are created
+/// (`<data dir>/worktrees` if unset) and takes effect at the next start.
+/// It cannot be changed while the current directory holds worktrees, which
+/// would no longer be managed.
+/// `kill_grace_secs` is how long a killed shell gets to exit before it is
+/// sent SIGKILL. `max_working_sessions` caps the agents working at once (0
+/// means unlimited). `agent_commands` are the templates used to launch each
+/// agent, owned by `AgentLauncher`. `log_level` is the most verbose level
+/// logged unless `RUST_LOG` names one. `memory_budget_mb` caps the PTY
+/// output held in memory across all sessions (0 means unlimited, see
+/// `MemoryBudget`). `check_for_updates` looks for a newer release at
+/// startup. Fields missing from a saved file take their default values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct Settings {
+    pub worktree_base_dir: Option<String>,
+    pub kill_grace_secs: u64,
+    pub max_working_sessions: usize,
+    pub agent_commands: AgentCommands,
+    pub log_level: LogLevel,
+    pub memory_budget_mb: usize,
+    pub check_for_updates: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            worktree_base_dir: None,
+            kill_grace_secs: 3,
+            max_working_sessions: 0,
+            agent_commands: AgentCommands::default(),
+            log_level: LogLevel::default(),
+            memory_budget_mb: 64,
+            check_for_updates: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Checks every field, returning the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(dir) = &self.worktree_base_dir {
+            if dir.trim().is_empty() {
+                return Err("worktree_base_dir must not be empty".to_string());
+            }
+            if !Path::new(dir).is_absolute() {
+                return Err(format!("worktree_base_dir must be an absolute path: {dir}"));
+            }
+            if Path::new(dir).is_file() {
+                return Err(format!("worktree_base_dir is a file: {dir}"));
+            }
+        }
+        if self.kill_grace_secs > MAX_KILL_GRACE_SECS {
+            return Err(format!("kill_grace_secs must be at most {MAX_KILL_GRACE_SECS}"));
+        }
+        Ok(())
+    }
+}
+
+/// The persisted part of `Settings`; agent commands live in
+/// `agent-commands.json`.
+#[derive(Serialize, Deserialize)]
+struct SettingsFile {
+    version: u32,
+    #[serde(default)]
+    worktree_base_dir: Option<String>,
+    #[serde(default = "default_kill_grace_secs")]
+    kill_grace_secs: u64,
+    #[serde(default)]
+    max_working_sessions: usize,
+    #[serde(default)]
+    log_level: LogLevel,
+    #[serde(default = "default_memory_budget_mb")]
+    memory_budget_mb: usize,
+    #[serde(default = "default_check_for_updates")]
+    check_for_updates: bool,
+}
+
+fn default_kill_grace_secs() -> u64 {
+    Settings::default().kill_grace_secs
+}
+
+fn default_memory_budget_mb() -> usize {
+    Settings::default().memory_budget_mb
+}
+
+fn default_check_for_updates() -> bool {
+    Settings::default().check_for_updates
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    path: PathBuf,
+    settings: Mutex<Settings>,
+}
+
+/// Holds the application settings, persisted to `settings.json` in the data
+/// dir, and applies them to the subsystems they configure. Every change is
+/// emitted on `settings-changed`.
+///
+/// Load it before anything creates worktrees: the worktree base dir is
+/// fixed for the rest of the process on load. Cheap to clone; placed in
+/// Tauri managed state.
+#[derive(Clone)]
+pub struct SettingsStore {
+    inner: Arc<Inner>,
+}
+
+impl SettingsStore {
+    /// Loads the settings from the data dir, falling back to the defaults
+    /// if the file is missing, unreadable, or invalid, and applies the
+    /// worktree base dir, kill grace period, memory budget, and log level.
+    /// Agent commands are taken from the `AgentLauncher`; the session cap
+    /// is applied by the caller once the `LaunchScheduler` exists.
+    pub fn load(app_handle: AppHandle) -> Self {
+        let path = storage::data_dir().join("settings.json");
+        let defaults = Settings::default();
+        let mut settings = match storage::load_json::<SettingsFile>(&path) {
+            Ok(Some(file)) if file.version <= SETTINGS_VERSION => Settings {
+                worktree_base_dir: file.worktree_base_dir,
+                kill_grace_secs: file.kill_grace_secs,
+                max_working_sessions: file.max_working_sessions,
+                log_level: file.log_level,
+                memory_budget_mb: file.memory_budget_mb,
+                check_for_updates: file.check_for_updates,
+                ..defaults
+            },
+            Ok(Some(file)) => {
+                log::warn!("Ignoring settings with unknown version {}", file.version);
+                defaults
+            }
+            Ok(None) => defaults,
+            Err(e) => {
+                log::error!("Failed to load settings {}: {e}", path.display());
+                defaults
+            }
+        };
+        if let Err(e) = settings.validate() {
+            log::error!("Ignoring invalid settings in {}: {e}", path.display());
+            settings = Settings::default();
+        }
+        settings.agent_commands = app_handle.state::<AgentLauncher>().commands();
+
+        if let Some(dir) = &settings.worktree_base_dir {
+            worktree_manager::set_worktree_base_dir(PathBuf::from(dir));
+        }
+        app_handle
+            .state::<ProcessManager>()
+            .set_kill_grace(Duration::from_secs(settings.kill_grace_secs));
+        app_handle
+            .state::<MemoryBudget>()
+            .set_budget_mb(settings.memory_budget_mb);
+        if let Err(e) = logging::set_level(settings.log_level) {
+            log::warn!("{e}");
+        }
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                path,
+                settings: Mutex::new(settings),
+            }),
+        }
+    }
+
+    /// Returns the current settings.
+    pub fn get(&self) -> Settings {
+        self.lock().clone()
+    }
+
+    /// Validates and persists new settings, applies them, and emits
+    /// `settings-changed`. Raising the session cap starts queued sessions,
+    /// then queued tasks; a new worktree base dir applies from the next
+    /// start and is refused while managed worktrees exist.
+    pub fn update(&self, settings: Settings) -> Result<Settings, String> {
+        settings.validate()?;
+        let app = &self.inner.app_handle;
+        let mut current = self.lock();
+        if settings.worktree_base_dir != current.worktree_base_dir {
+            let new_base = match &settings.worktree_base_dir {
+                Some(dir) => PathBuf::from(dir),
+                None => storage::data_dir().join("worktrees"),
+            };
+            if new_base != worktree_manager::worktree_base_dir() && worktree_manager::has_managed_worktrees() {
+                return Err(format!(
+                    "Cannot move the worktree base dir while {} holds worktrees; remove them first",
+                    worktree_manager::worktree_base_dir().display()
+                ));
+            }
+        }
+        if settings.agent_commands != current.agent_commands {
+            app.state::<AgentLauncher>()
+                .set_commands(settings.agent_commands.clone())
+                .map_err(|e| e.to_string())?;
+        }
+        self.save(&settings).map_err(|e| e.to_string())?;
+
+        app.state::<ProcessManager>()
+            .set_kill_grace(Duration::from_secs(settings.kill_grace_secs));
+        app.state::<MemoryBudget>().set_budget_mb(settings.memory_budget_mb);
+        if settings.log_level != current.log_level {
+            if let Err(e) = logging::set_level(settings.log_level) {
+                log::warn!("{e}");
+            }
+        }
+        let cap_changed = settings.max_working_sessions != current.max_working_sessions;
+        if settings.worktree_base_dir != current.worktree_base_dir {
+            log::info!("Worktree base dir changes to {:?} at the next start", settings.worktree_base_dir);
+        }
+        *current = settings.clone();
+        drop(current);
+
+        if cap_changed {
+            if let Some(scheduler) = app.try_state::<LaunchScheduler>() {
+                scheduler.set_max_working(settings.max_working_sessions);
+            }
+            if let Some(tasks) = app.try_state::<TaskQueue>() {
+                tasks.dispatch();
+            }
+        }
+        let _ = app.emit(SETTINGS_CHANGED_EVENT, &settings);
+        Ok(settings)
+    }
+
+    /// Applies `change` to a copy of the current settings and saves the
+    /// result as `update` does.
+    pub fn modify(&self, change: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+        let mut settings = self.get();
+        change(&mut settings);
+        self.update(settings)
+    }
+
+    fn save(&self, settings: &Settings) -> io::Result<()> {
+        let file = SettingsFile {
+            version: SETTINGS_VERSION,
+            worktree_base_dir: settings.worktree_base_dir.clone(),
+            kill_grace_secs: settings.kill_grace_secs,
+            max_working_sessions: settings.max_working_sessions,
+            log_level: settings.log_level,
+            memory_budget_mb: settings.memory_budget_mb,
+            check_for_updates: settings.check_for_updates,
+        };
+        storage::save_json(&self.inner.path, &file)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Settings> {
+        match self.inner.settings.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::agent_adapter::AgentEvent;
+use super::agent_launcher::AgentLauncher;
+use super::agent_output;
+use super::error::AppError;
+use super::process_manager::ProcessManager;
+use super::session_manager::{SessionConfig, SessionManager};
+use super::storage;
+
+/// Current on-disk format of `snippets.json`.
+const SNIPPETS_VERSION: u32 = 1;
+
+/// Whether a snippet is sent to the session's agent as a prompt or typed
+/// into its shell as commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetKind {
+    Prompt,
+    Shell,
+}
+
+/// A reusable prompt or shell snippet.
+///
+/// `body` may hold `{{name}}` placeholders, filled from the variables given
+/// when it is sent. `{{branch}}`, `{{repo}}`, `{{worktree}}`, and
+/// `{{session}}` default to the target session's branch, repository,
+/// worktree, and ID. `id` is assigned on creation; pass 0 to create.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Snippet {
+    #[serde(default)]
+    pub id: u32,
+    pub name: String,
+    pub kind: SnippetKind,
+    pub body: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl Snippet {
+    /// Returns the names of the placeholders in `body`, in order of first
+    /// appearance.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        let mut rest = self.body.as_str();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                break;
+            };
+            let name = after[..end].trim();
+            if is_placeholder_name(name) && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+            rest = &after[end + 2..];
+        }
+        names
+    }
+
+    /// Fills the placeholders from `vars`, then from the session's defaults.
+    /// Fails naming every placeholder left without a value.
+    pub fn render(&self, session: &SessionConfig, vars: &BTreeMap<String, String>) -> Result<String, String> {
+        let defaults = BTreeMap::from([
+            ("branch", session.branch.clone()),
+            ("repo", Some(session.repo_path.clone()).filter(|p| !p.is_empty())),
+            ("worktree", session.worktree_path.clone()),
+            ("session", Some(session.id.to_string())),
+        ]);
+        let mut text = self.body.clone();
+        let mut missing = Vec::new();
+        for name in self.placeholders() {
+            let value = vars
+                .get(&name)
+                .cloned()
+                .or_else(|| defaults.get(name.as_str()).cloned().flatten());
+            let Some(value) = value else {
+                missing.push(name);
+                continue;
+            };
+            text = replace_placeholder(&text, &name, &value);
+        }
+        if !missing.is_empty() {
+            return Err(format!("Snippet '{}' needs values for {}", self.name, missing.join(", ")));
+        }
+        Ok(text)
+    }
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Replaces `{{name}}`, with or without spaces inside the braces.
+fn replace_placeholder(text: &str, name: &str, value: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) if after[..end].trim() == name => {
+                out.push_str(&rest[..start]);
+                out.push_str(value);
+                rest = &after[end + 2..];
+            }
+            _ => {
+                out.push_str(&rest[..start + 2]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SnippetsFile {
+    version: u32,
+    next_id: u32,
+    snippets: Vec<Snippet>,
+}
+
+/// Library of reusable prompts and shell snippets, sorted by name and
+/// persisted to `snippets.json` in the data dir. Placed in Tauri managed
+/// state.
+pub struct SnippetLibrary {
+    path: PathBuf,
+    file: Mutex<SnippetsFile>,
+}
+
+impl SnippetLibrary {
+    /// Loads the library from the data dir, starting empty if the file is
+    /// missing or unreadable.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("snippets.json");
+        let file = match storage::load_json::<SnippetsFile>(&path) {
+            Ok(Some(file)) if file.version <= SNIPPETS_VERSION => file,
+            Ok(Some(file)) => {
+                log::warn!("Ignoring snippets with unknown version {}", file.version);
+                SnippetsFile::default()
+            }
+            Ok(None) => SnippetsFile::default(),
+            Err(e) => {
+                log::error!("Failed to load snippets {}: {e}", path.display());
+                SnippetsFile::default()
+            }
+        };
+        Self {
+            path,
+            file: Mutex::new(file),
+        }
+    }
+
+    /// Returns all snippets, sorted by name.
+    pub fn list(&self) -> Vec<Snippet> {
+        lock(&self.file).snippets.clone()
+    }
+
+    pub fn get(&self, id: u32) -> Option<Snippet> {
+        lock(&self.file).snippets.iter().find(|s| s.id == id).cloned()
+    }
+
+    /// Persists a snippet, creating it if its `id` is 0 or unknown and
+    /// replacing it otherwise. Returns it with its ID.
+    pub fn save(&self, mut snippet: Snippet) -> Result<Snippet, String> {
+        snippet.name = snippet.name.trim().to_string();
+        if snippet.name.is_empty() {
+            return Err("Snippet name must not be empty".to_string());
+        }
+        if snippet.body.trim().is_empty() {
+            return Err(format!("Snippet '{}' is empty", snippet.name));
+        }
+        let mut file = lock(&self.file);
+        let mut updated = SnippetsFile {
+            version: SNIPPETS_VERSION,
+            next_id: file.next_id,
+            snippets: file.snippets.clone(),
+        };
+        match updated.snippets.iter_mut().find(|s| s.id == snippet.id && s.id != 0) {
+            Some(existing) => *existing = snippet.clone(),
+            None => {
+                updated.next_id += 1;
+                snippet.id = updated.next_id;
+                updated.snippets.push(snippet.clone());
+            }
+        }
+        updated.snippets.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        storage::save_json(&self.path, &updated).map_err(|e| e.to_string())?;
+        *file = updated;
+        Ok(snippet)
+    }
+
+    /// Removes a snippet. Returns whether it existed.
+    pub fn delete(&self, id: u32) -> io::Result<bool> {
+        let mut file = lock(&self.file);
+        let mut updated = SnippetsFile {
+            version: SNIPPETS_VERSION,
+            next_id: file.next_id,
+            snippets: file.snippets.clone(),
+        };
+        updated.snippets.retain(|s| s.id != id);
+        if updated.snippets.len() == file.snippets.len() {
+            return Ok(false);
+        }
+        storage::save_json(&self.path, &updated)?;
+        *file = updated;
+        Ok(true)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Renders a snippet for a session and sends it, returning the text sent.
+///
+/// A `Prompt` goes to the session's agent as `AgentLauncher::send_prompt`
+/// does and is recorded in its transcript; a `Shell` snippet is typed into
+/// its terminal, each line run as a command.
+pub async fn send_snippet(
+    app: &AppHandle,
+    session_id: u32,
+    snippet_id: u32,
+    vars: &BTreeMap<String, String>,
+) -> Result<String, AppError> {
+    let session = app
+        .state::<SessionManager>()
+        .get_session(session_id)
+        .ok_or_else(|| AppError::session_not_found(session_id))?;
+    let snippet = app
+        .state::<SnippetLibrary>()
+        .get(snippet_id)
+        .ok_or_else(|| AppError::not_found(format!("Snippet {snippet_id} not found")).with("snippet_id", snippet_id))?;
+    let text = snippet.render(&session, vars)?;
+    let processes = app.state::<ProcessManager>();
+    match snippet.kind {
+        SnippetKind::Prompt => {
+            let prompt = app
+                .state::<AgentLauncher>()
+                .send_prompt(&processes, &session, &text, &[])
+                .await?;
+            agent_output::record_event(app, &session, AgentEvent::Prompt { text: prompt.clone() });
+            Ok(prompt)
+        }
+        SnippetKind::Shell => {
+            let pty_id = session.pty_id.ok_or_else(|| {
+                AppError::not_found(format!("Session {session_id} has no terminal")).with("session_id", session_id)
+            })?;
+            let mut input = text;
+            if !input.ends_with('\n') {
+                input.push('\n');
+            }
+            processes.write_stdin(pty_id, &input)?;
+            Ok(input)
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::remote_control::{self, CreateSessionArgs};
+use super::session_manager::{SessionConfig, SessionManager};
+use super::storage;
+
+/// Current on-disk format of `startup-profiles.json`.
+const STARTUP_PROFILES_VERSION: u32 = 1;
+
+/// Event emitted with the `AppliedProfile` once the startup profile was
+/// applied at launch.
+pub const STARTUP_PROFILE_APPLIED_EVENT: &str = "startup-profile-applied";
+
+/// A session a startup profile starts, as `remote_control::create_session`
+/// does: `agent` is `claude` (the default), `gemini`, `codex`, `plain`, or
+/// a custom agent's name; without `base_ref` an existing `branch` is
+/// checked out, with it `branch` is created from `base_ref`; `prompt`, if
+/// given, is sent to the agent once it is ready.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StartupSession {
+    pub repo_path: String,
+    pub branch: String,
+    #[serde(default)]
+    pub base_ref: Option<String>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// A named set of repositories to open and sessions to start together.
+/// `layout` holds the frontend's window layout hints and is stored as
+/// given.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StartupProfile {
+    pub name: String,
+    #[serde(default)]
+    pub repo_paths: Vec<String>,
+    #[serde(default)]
+    pub sessions: Vec<StartupSession>,
+    #[serde(default)]
+    pub layout: Option<Value>,
+}
+
+/// Result of applying a profile: the profile itself, for its repositories
+/// and layout, the sessions on its branches (started now or already
+/// present), and why the others could not be started.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AppliedProfile {
+    pub profile: StartupProfile,
+    pub sessions: Vec<SessionConfig>,
+    pub failures: Vec<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StartupProfilesFile {
+    version: u32,
+    /// Profile applied when Maestro starts, if any.
+    #[serde(default)]
+    startup: Option<String>,
+    profiles: Vec<StartupProfile>,
+}
+
+/// Startup profiles, sorted by name and persisted to
+/// `startup-profiles.json` in the data dir, with the one applied whenever
+/// the app starts. Placed in Tauri managed state.
+pub struct StartupProfiles {
+    path: PathBuf,
+    file: Mutex<StartupProfilesFile>,
+    /// What applying the startup profile at launch gave, until the frontend
+    /// takes it.
+    applied: Mutex<Option<AppliedProfile>>,
+}
+
+impl StartupProfiles {
+    /// Loads the profiles from the data dir, starting empty if the file is
+    /// missing or unreadable.
+    pub fn load() -> Self {
+        let path = storage::data_dir().join("startup-profiles.json");
+        let file = match storage::load_json::<StartupProfilesFile>(&path) {
+            Ok(Some(file)) if file.version <= STARTUP_PROFILES_VERSION => file,
+            Ok(Some(file)) => {
+                log::warn!("Ignoring startup profiles with unknown version {}", file.version);
+                StartupProfilesFile::default()
+            }
+            Ok(None) => StartupProfilesFile::default(),
+            Err(e) => {
+                log::error!("Failed to load startup profiles {}: {e}", path.display());
+                StartupProfilesFile::default()
+            }
+        };
+        Self {
+            path,
+            file: Mutex::new(file),
+            applied: Mutex::new(None),
+        }
+    }
+
+    /// Returns all profiles, sorted by name.
+    pub fn list(&self) -> Vec<StartupProfile> {
+        lock(&self.file).profiles.clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<StartupProfile> {
+        lock(&self.file).profiles.iter().find(|p| p.name == name).cloned()
+    }
+
+    /// Returns the name of the profile applied when the app starts.
+    pub fn startup(&self) -> Option<String> {
+        lock(&self.file).startup.clone()
+    }
+
+    /// Persists a profile, replacing the one with the same name.
+    pub fn save(&self, mut profile: StartupProfile) -> Result<(), String> {
+        profile.name = profile.name.trim().to_string();
+        if profile.name.is_empty() {
+            return Err("Profile name must not be empty".to_string());
+        }
+        if let Some(session) = profile.sessions.iter().find(|s| s.repo_path.is_empty() || s.branch.trim().is_empty()) {
+            return Err(format!("Session '{}' needs a repository and a branch", session.branch));
+        }
+        self.update(|file| {
+            file.profiles.retain(|p| p.name != profile.name);
+            file.profiles.push(profile);
+            file.profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// Removes a profile, and stops applying it at startup. Returns whether
+    /// it existed.
+    pub fn delete(&self, name: &str) -> io::Result<bool> {
+        let mut existed = false;
+        self.update(|file| {
+            let before = file.profiles.len();
+            file.profiles.retain(|p| p.name != name);
+            existed = file.profiles.len() != before;
+            if file.startup.as_deref() == Some(name) {
+                file.startup = None;
+            }
+        })?;
+        Ok(existed)
+    }
+
+    /// Makes a profile the one applied when the app starts; `None` applies
+    /// none.
+    pub fn set_startup(&self, name: Option<String>) -> Result<(), String> {
+        if let Some(name) = &name {
+            if self.get(name).is_none() {
+                return Err(format!("Startup profile '{name}' not found"));
+            }
+        }
+        self.update(|file| file.startup = name).map_err(|e| e.to_string())
+    }
+
+    /// Returns what applying the startup profile at launch gave the first
+    /// time it is called once that finished, then `None`.
+    pub fn take_applied(&self) -> Option<AppliedProfile> {
+        lock(&self.applied).take()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut StartupProfilesFile)) -> io::Result<()> {
+        let mut file = lock(&self.file);
+        let mut updated = StartupProfilesFile {
+            version: STARTUP_PROFILES_VERSION,
+            startup: file.startup.clone(),
+            profiles: file.profiles.clone(),
+        };
+        change(&mut updated);
+        storage::save_json(&self.path, &updated)?;
+        *file = updated;
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Starts the sessions of a profile, one at a time, skipping those whose
+/// repository already has a session on the branch, so applying a profile
+/// twice (or at every start while its sessions are restored) creates none
+/// twice. A session that fails to start is logged and skipped.
+pub async fn apply(app: &AppHandle, profile: StartupProfile) -> AppliedProfile {
+    let mut sessions = Vec::new();
+    let mut failures = Vec::new();
+    for spec in &profile.sessions {
+        let existing = app
+            .state::<SessionManager>()
+            .sessions_in_repo(Path::new(&spec.repo_path))
+            .into_iter()
+            .find(|s| s.branch.as_deref() == Some(spec.branch.as_str()));
+        if let Some(session) = existing {
+            sessions.push(session);
+            continue;
+        }
+        let args = CreateSessionArgs {
+            repo_path: spec.repo_path.clone(),
+            branch: spec.branch.clone(),
+            base_ref: spec.base_ref.clone(),
+            agent: spec.agent.clone(),
+            prompt: spec.prompt.clone(),
+        };
+        match remote_control::start_session(app, args).await {
+            Ok(session) => sessions.push(session),
+            Err(e) => {
+                log::warn!("Startup profile '{}': failed to start {}: {e}", profile.name, spec.branch);
+                failures.push(format!("{} ({}): {e}", spec.branch, spec.repo_path));
+            }
+        }
+    }
+    log::info!(
+        "Applied startup profile '{}': {} sessions, {} failed",
+        profile.name,
+        sessions.len(),
+        failures.len()
+    );
+    AppliedProfile {
+        profile,
+        sessions,
+        failures,
+    }
+}
+
+/// Applies the startup profile in the background, if one is set, keeping
+/// the result for `StartupProfiles::take_applied` and emitting it on
+/// `startup-profile-applied`. Called by the app once setup finished, not
+/// by headless backends.
+pub fn apply_at_startup(app: &AppHandle) {
+    let profiles = app.state::<StartupProfiles>();
+    let Some(name) = profiles.startup() else {
+        return;
+    };
+    let Some(profile) = profiles.get(&name) else {
+        log::warn!("Startup profile '{name}' not found");
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let applied = apply(&app, profile).await;
+        *lock(&app.state::<StartupProfiles>().applied) = Some(applied.clone());
+        let _ = app.emit(STARTUP_PROFILE_APPLIED_EVENT, &applied);
+    });
+}
@@ -0,0 +1,260 @@
+use std::path::Path;
+
+use dashmap::DashMap;
+use regex::{Regex, RegexSet};
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::agent_adapter::PermissionRequest;
+use super::agent_launcher::AgentLauncher;
+use super::approval::ApprovalDecision;
+use super::guardrails::Guardrails;
+use super::process_manager::{ProcessManager, PtyListener};
+use super::session_manager::{AiMode, SessionConfig, SessionManager, SessionStatus, StatusCause};
+use super::windows;
+use crate::agent;
+
+/// Bytes of recent (escape-stripped) output kept per PTY for pattern matching.
+const TAIL_BYTES: usize = 4096;
+
+/// Trailing lines searched for an agent's input prompt. Approval dialogs put
+/// the question above a short list of options, so the last line alone is not
+/// enough.
+const PROMPT_WINDOW_LINES: usize = 8;
+
+/// Longest prompt line recorded as the transition detail.
+const MAX_DETAIL_CHARS: usize = 200;
+
+/// Minimum printable characters in a chunk for it to count as the agent
+/// working. Keeps keystroke echo and cursor blinks from flipping the status.
+const MIN_WORKING_CHARS: usize = 24;
+
+/// Phrases any interactive CLI may print when it stops and waits for the
+/// user. Agent-specific prompts come from each `AgentAdapter`.
+const NEEDS_INPUT_PATTERNS: &[&str] = &[
+    r"(?i)[\[(]y/n[\])]",
+    r"(?i)press enter to (continue|confirm)",
+    r"(?i)waiting for (your )?(input|approval|confirmation)",
+];
+
+/// A returned shell prompt: a short line ending in `$`, `#`, or a `%` that
+/// does not follow a digit, so progress output such as `50%` is not one.
+pub(super) const SHELL_PROMPT_PATTERN: &str = r"^(?:.{0,200}[$#]|(?:.{0,200}[^\d\n])?%)\s*$";
+
+/// Matches CSI, OSC, and two-byte escape sequences.
+pub(super) const ANSI_ESCAPE_PATTERN: &str =
+    r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(\x07|\x1b\\)|\x1b[@-Z\\-_]";
+
+/// Event emitted when a permission prompt was answered by a session's
+/// approval policy.
+pub const PERMISSION_AUTO_APPROVED_EVENT: &str = "permission-auto-approved";
+
+/// Payload of the `permission-auto-approved` event.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AutoApproval {
+    pub session_id: u32,
+    pub request: PermissionRequest,
+}
+
+/// Heuristic status detection for sessions running an agent.
+///
+/// Registered as a `PtyListener`; watches the output of every PTY bound to
+/// a session whose mode is not `Plain` and transitions its status: an agent
+/// input prompt (generic, the adapter's, or a custom agent's
+/// `prompt_patterns`) means `NeedsInput`, a returned shell prompt means
+/// `Idle`, and any other substantial output means `Working`. A permission
+/// prompt the mode's adapter understands and the session's `ApprovalPolicy`
+/// approves, and no guardrail forbids, is answered on the user's behalf
+/// instead of raising `NeedsInput`. Sessions whose agent a guardrail holds
+/// are left alone. When a bound shell exits, the session becomes `Done`
+/// (exit code 0) or `Error` (anything else), regardless of mode. Status is
+/// only written when it actually changes, so steady streaming does not
+/// churn `sessions.json`. Output from any bound PTY, `Plain` included, also
+/// refreshes the session's `last_output_at`.
+pub struct StatusInference {
+    app_handle: AppHandle,
+    needs_input: RegexSet,
+    shell_prompt: Regex,
+    ansi: Regex,
+    tails: DashMap<u32, String>,
+}
+
+impl StatusInference {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            needs_input: RegexSet::new(NEEDS_INPUT_PATTERNS).expect("valid prompt patterns"),
+            shell_prompt: Regex::new(SHELL_PROMPT_PATTERN).expect("valid shell prompt pattern"),
+            ansi: Regex::new(ANSI_ESCAPE_PATTERN).expect("valid escape pattern"),
+            tails: DashMap::new(),
+        }
+    }
+
+    /// Decides the status implied by the latest output, or `None` if the chunk
+    /// is too small to say anything. For `NeedsInput`, also returns the line
+    /// that matched a prompt pattern.
+    fn classify(
+        &self,
+        tail: &str,
+        chunk: &str,
+        extra: Option<&RegexSet>,
+    ) -> Option<(SessionStatus, Option<String>)> {
+        let lines: Vec<&str> = tail
+            .lines()
+            .map(visible_line)
+            .filter(|l| !l.trim().is_empty())
+            .collect();
+        let window = &lines[lines.len().saturating_sub(PROMPT_WINDOW_LINES)..];
+
+        let needs_input = |l: &&str| {
+            self.needs_input.is_match(l) || extra.is_some_and(|set| set.is_match(l))
+        };
+        if let Some(line) = window.iter().find(|l| needs_input(l)) {
+            let prompt: String = line.trim().chars().take(MAX_DETAIL_CHARS).collect();
+            return Some((SessionStatus::NeedsInput, Some(prompt)));
+        }
+        if lines.last().is_some_and(|l| self.shell_prompt.is_match(l)) {
+            return Some((SessionStatus::Idle, None));
+        }
+        let printable = chunk.chars().filter(|c| !c.is_control()).count();
+        (printable >= MIN_WORKING_CHARS).then_some((SessionStatus::Working, None))
+    }
+
+    /// Answers the permission prompt at the end of `tail` if the session's
+    /// policy approves it. Returns `true` if it did.
+    fn auto_approve(&self, session: &SessionConfig, pty_id: u32, tail: &str) -> bool {
+        let Some(adapter) = agent::registry().get(&session.mode) else {
+            return false;
+        };
+        let lines: Vec<&str> = tail
+            .lines()
+            .map(visible_line)
+            .filter(|l| !l.trim().is_empty())
+            .collect();
+        let Some(request) = adapter.parse_permission_prompt(&lines) else {
+            return false;
+        };
+        let worktree = session.worktree_path.as_deref().map(Path::new);
+        if session.approval.evaluate(&request, worktree) != ApprovalDecision::Approve {
+            return false;
+        }
+        let guarded = request.tool == "Bash"
+            && self
+                .app_handle
+                .try_state::<Guardrails>()
+                .is_some_and(|g| g.screen_prompt(&self.app_handle, session, &request.target));
+        if guarded {
+            return false;
+        }
+
+        let processes = self.app_handle.state::<ProcessManager>();
+        if let Err(e) = processes.write_stdin(pty_id, adapter.approve_keys()) {
+            log::warn!("Session {}: failed to auto-approve {request:?}: {e}", session.id);
+            return false;
+        }
+        // The answered dialog must not be matched again by later chunks
+        self.tails.remove(&pty_id);
+        log::info!("Session {}: auto-approved {} {}", session.id, request.tool, request.target);
+        windows::emit_to_session(
+            &self.app_handle,
+            session.id,
+            PERMISSION_AUTO_APPROVED_EVENT,
+            AutoApproval {
+                session_id: session.id,
+                request,
+            },
+        );
+        true
+    }
+
+    fn set_status(
+        &self,
+        sessions: &SessionManager,
+        id: u32,
+        current: SessionStatus,
+        next: SessionStatus,
+        cause: StatusCause,
+        detail: Option<String>,
+    ) {
+        if current != next {
+            log::debug!("Session {id}: inferred {current:?} -> {next:?}");
+            sessions.update_status_with_detail(id, next, cause, detail);
+        }
+    }
+}
+
+impl PtyListener for StatusInference {
+    fn on_output(&self, pty_id: u32, text: &str) {
+        let sessions = self.app_handle.state::<SessionManager>();
+        let Some(session) = sessions.session_for_pty(pty_id) else {
+            return;
+        };
+        sessions.record_output(session.id);
+        if session.status == SessionStatus::Queued {
+            // Shell output while waiting for a launch slot says nothing about the agent
+            return;
+        }
+        if self
+            .app_handle
+            .try_state::<Guardrails>()
+            .is_some_and(|g| g.is_held(session.id))
+        {
+            return;
+        }
+        let extra = match session.mode {
+            AiMode::Plain => return,
+            AiMode::Custom(ref name) => self.app_handle.state::<AgentLauncher>().prompt_patterns(name),
+            ref mode => agent::registry().prompt_patterns(mode).cloned(),
+        };
+
+        let chunk = self.ansi.replace_all(text, "");
+        let tail = {
+            let mut tail = self.tails.entry(pty_id).or_default();
+            tail.push_str(&chunk);
+            if tail.len() > TAIL_BYTES {
+                let mut cut = tail.len() - TAIL_BYTES;
+                while !tail.is_char_boundary(cut) {
+                    cut += 1;
+                }
+                tail.drain(..cut);
+            }
+            tail.clone()
+        };
+
+        if let Some((next, detail)) = self.classify(&tail, &chunk, extra.as_ref()) {
+            if next == SessionStatus::NeedsInput && self.auto_approve(&session, pty_id, &tail) {
+                return;
+            }
+            self.set_status(&sessions, session.id, session.status, next, StatusCause::AutoDetected, detail);
+        }
+    }
+
+    fn on_exit(&self, pty_id: u32, exit_code: Option<u32>) {
+        self.tails.remove(&pty_id);
+        let sessions = self.app_handle.state::<SessionManager>();
+        let Some(session) = sessions.session_for_pty(pty_id) else {
+            return;
+        };
+        if let Some(guardrails) = self.app_handle.try_state::<Guardrails>() {
+            guardrails.release(session.id);
+        }
+        let next = if exit_code == Some(0) {
+            SessionStatus::Done
+        } else {
+            SessionStatus::Error
+        };
+        let detail = match exit_code {
+            Some(code) => format!("exit code {code}"),
+            None => "exit status unknown".to_string(),
+        };
+        self.set_status(&sessions, session.id, session.status, next, StatusCause::Exit, Some(detail));
+    }
+}
+
+/// Returns what a terminal would show for a line containing carriage
+/// returns: the text after the last `\r` (spinners and progress bars redraw
+/// the same line this way).
+pub(super) fn visible_line(line: &str) -> &str {
+    line.rsplit('\r').find(|s| !s.is_empty()).unwrap_or("")
+}
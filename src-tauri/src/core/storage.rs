@@ -0,0 +1,119 @@
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Root of Maestro's persisted data (`~/.local/share/maestro` on Linux, or
+/// `$MAESTRO_DATA_DIR` if set).
+///
+/// Worktrees, registries, and session state all live below this directory.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("MAESTRO_DATA_DIR").filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    directories::ProjectDirs::from("com", "maestro", "maestro")
+        .map(|p| p.data_dir().to_path_buf())
+        .unwrap_or_else(|| {
+            dirs_fallback()
+        })
+}
+
+/// Fallback if ProjectDirs fails (e.g., no HOME set).
+/// This GUI app assumes a user session on a desktop environment where HOME is set.
+/// Panicking here is intentional to fail fast in headless/container/systemd scenarios.
+fn dirs_fallback() -> PathBuf {
+    std::env::var("HOME")
+        .map(PathBuf::from)
+        .map(|p| p.join(".local").join("share").join("maestro"))
+        .expect("HOME environment variable must be set for Maestro's data directory")
+}
+
+/// Takes an exclusive lock on the data dir (`maestro.lock`) for the rest of
+/// the process, so the app and `maestro-cli` runs never share one. Fails if
+/// another process holds it; taking it again in the same process is a
+/// no-op.
+pub fn lock_data_dir() -> io::Result<()> {
+    static LOCK: Mutex<Option<File>> = Mutex::new(None);
+    let mut held = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if held.is_some() {
+        return Ok(());
+    }
+    let dir = data_dir();
+    std::fs::create_dir_all(&dir)?;
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join("maestro.lock"))?;
+    // SAFETY: the descriptor is open for as long as `file` lives
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let e = io::Error::last_os_error();
+        if e.kind() == io::ErrorKind::WouldBlock {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!("{} is in use by another Maestro process", dir.display()),
+            ));
+        }
+        return Err(e);
+    }
+    *held = Some(file);
+    Ok(())
+}
+
+/// Reads and deserializes a JSON file. Returns `Ok(None)` if the file does
+/// not exist, so first runs need no special casing by callers.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Serializes `value` as pretty JSON and atomically replaces `path` with it.
+///
+/// Writes to a sibling temp file, fsyncs it, then renames it over the
+/// target, so a crash mid-write leaves either the old or the new file and
+/// never a truncated one. Parent directories are created as needed.
+pub fn save_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    write_json(path, value, None)
+}
+
+/// Like `save_json`, for files holding secrets: the file is readable only
+/// by the user from the moment it is created.
+pub fn save_json_private<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    write_json(path, value, Some(0o600))
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T, mode: Option<u32>) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(value)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let tmp = path.with_extension("json.tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if let Some(mode) = mode {
+        options.mode(mode);
+    }
+    let mut file = options.open(&tmp)?;
+    if let Some(mode) = mode {
+        // A temp file left by an earlier run keeps its old mode when opened
+        file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+    }
+    file.write_all(&json)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)
+}
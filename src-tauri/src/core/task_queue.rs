@@ -0,0 +1,331 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::clock;
+use super::orchestrator::{self, BranchSpec};
+use super::process_manager::ProcessManager;
+use super::scheduler::LaunchScheduler;
+use super::session_manager::{
+    AiMode, LaunchProfile, SessionConfig, SessionListener, SessionManager, StatusTransition,
+};
+use super::storage;
+use super::worktree_manager::WorktreeManager;
+
+/// Current on-disk format of `task-queue.json`.
+const TASK_QUEUE_VERSION: u32 = 1;
+
+/// Event emitted whenever the queue or one of its tasks changes.
+pub const TASK_QUEUE_CHANGED_EVENT: &str = "task-queue-changed";
+
+/// Longest session title derived from a task description.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// A task waiting to be turned into a session. `created_at` is a Unix epoch
+/// timestamp.
+///
+/// The session is created like `orchestrator::create_full_session` with
+/// `repo_path`, `branch_spec`, `mode`, and `profile`, and `description` is
+/// sent to its agent as the first prompt.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct QueuedTask {
+    pub id: u32,
+    pub description: String,
+    pub repo_path: String,
+    pub branch_spec: BranchSpec,
+    pub mode: AiMode,
+    #[serde(default)]
+    pub profile: LaunchProfile,
+    pub created_at: i64,
+}
+
+/// Payload of the `task-queue-changed` event.
+///
+/// `Started` is emitted once the task's session exists; the task has left
+/// the queue by then. `Failed` means creating the session or sending the
+/// task to its agent failed; `session_id` is set in the latter case.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaskQueueEvent {
+    Enqueued { task: QueuedTask },
+    Reordered { task_ids: Vec<u32> },
+    Cancelled { task: QueuedTask },
+    Started { task: QueuedTask, session_id: u32 },
+    Failed { task: QueuedTask, session_id: Option<u32>, error: String },
+}
+
+#[derive(Serialize, Deserialize)]
+struct TaskQueueFile {
+    version: u32,
+    next_id: u32,
+    tasks: Vec<QueuedTask>,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u32,
+    /// Tasks waiting for a slot, next first.
+    tasks: Vec<QueuedTask>,
+    /// Tasks taken off the queue whose session is still being created;
+    /// each will occupy a scheduler slot once it exists. Saved ahead of the
+    /// queue until then, so a restart starts them again.
+    starting: Vec<QueuedTask>,
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+/// Backlog of agent tasks, started as sessions while `LaunchScheduler` has
+/// free slots.
+///
+/// Tasks are started in queue order whenever a slot frees up: registered
+/// as a `SessionListener`, the queue is rechecked on every status change
+/// and removal, after the scheduler has started its own queued sessions.
+/// Sessions already waiting in the scheduler's queue go first. With no cap
+/// on working sessions, tasks start as soon as they are enqueued. Persisted
+/// to `task-queue.json`; a task whose session was still being created when
+/// the app quit is queued again, first, on the next launch. Cheap to clone;
+/// registered as a listener and placed in Tauri managed state.
+#[derive(Clone)]
+pub struct TaskQueue {
+    inner: Arc<Inner>,
+}
+
+impl TaskQueue {
+    /// Loads the queue from the data dir, starting empty if the file is
+    /// missing or unreadable. Nothing is started until `dispatch` is called.
+    pub fn load(app_handle: AppHandle) -> Self {
+        let path = storage::data_dir().join("task-queue.json");
+        let state = match storage::load_json::<TaskQueueFile>(&path) {
+            Ok(Some(file)) if file.version <= TASK_QUEUE_VERSION => State {
+                next_id: file.next_id,
+                tasks: file.tasks,
+                starting: Vec::new(),
+            },
+            Ok(Some(file)) => {
+                log::warn!("Ignoring task queue with unknown version {}", file.version);
+                State::default()
+            }
+            Ok(None) => State::default(),
+            Err(e) => {
+                log::error!("Failed to load task queue {}: {e}", path.display());
+                State::default()
+            }
+        };
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                path,
+                state: Mutex::new(state),
+            }),
+        }
+    }
+
+    /// Returns the queued tasks, next first.
+    pub fn tasks(&self) -> Vec<QueuedTask> {
+        self.lock().tasks.clone()
+    }
+
+    /// Appends a task and starts it right away if a slot is free.
+    pub fn enqueue(
+        &self,
+        description: String,
+        repo_path: String,
+        branch_spec: BranchSpec,
+        mode: AiMode,
+        profile: LaunchProfile,
+    ) -> Result<QueuedTask, String> {
+        if description.trim().is_empty() {
+            return Err("Task description must not be empty".to_string());
+        }
+        if mode == AiMode::Plain {
+            return Err("Tasks need an agent; Plain sessions cannot run them".to_string());
+        }
+        let task = {
+            let mut state = self.lock();
+            state.next_id += 1;
+            let task = QueuedTask {
+                id: state.next_id,
+                description,
+                repo_path,
+                branch_spec,
+                mode,
+                profile,
+                created_at: clock::now_secs(),
+            };
+            state.tasks.push(task.clone());
+            self.save(&state);
+            task
+        };
+        log::info!("Task {} queued", task.id);
+        self.emit(TaskQueueEvent::Enqueued { task: task.clone() });
+        self.dispatch();
+        Ok(task)
+    }
+
+    /// Moves the given tasks to the front of the queue in the given order;
+    /// the rest keep their relative order behind them. Unknown IDs are
+    /// ignored. Returns the new queue.
+    pub fn reorder(&self, task_ids: &[u32]) -> Vec<QueuedTask> {
+        let tasks = {
+            let mut state = self.lock();
+            let mut rest = std::mem::take(&mut state.tasks);
+            let mut ordered = Vec::with_capacity(rest.len());
+            for id in task_ids {
+                if let Some(i) = rest.iter().position(|t| t.id == *id) {
+                    ordered.push(rest.remove(i));
+                }
+            }
+            ordered.append(&mut rest);
+            state.tasks = ordered;
+            self.save(&state);
+            state.tasks.clone()
+        };
+        self.emit(TaskQueueEvent::Reordered {
+            task_ids: tasks.iter().map(|t| t.id).collect(),
+        });
+        tasks
+    }
+
+    /// Removes a task that has not started yet and returns it.
+    pub fn cancel(&self, task_id: u32) -> Option<QueuedTask> {
+        let task = {
+            let mut state = self.lock();
+            let i = state.tasks.iter().position(|t| t.id == task_id)?;
+            let task = state.tasks.remove(i);
+            self.save(&state);
+            task
+        };
+        log::info!("Task {task_id} cancelled");
+        self.emit(TaskQueueEvent::Cancelled { task: task.clone() });
+        Some(task)
+    }
+
+    /// Starts queued tasks, oldest first, while the scheduler has free
+    /// slots for their provider. A task whose provider is cooling down does
+    /// not hold up those behind it.
+    pub fn dispatch(&self) {
+        let scheduler = self.inner.app_handle.state::<LaunchScheduler>();
+        loop {
+            let task = {
+                let mut state = self.lock();
+                let starting = state.starting.len();
+                let Some(i) = state
+                    .tasks
+                    .iter()
+                    .position(|task| scheduler.free_slots(&task.mode).is_none_or(|free| free > starting))
+                else {
+                    return;
+                };
+                let task = state.tasks.remove(i);
+                state.starting.push(task.clone());
+                self.save(&state);
+                task
+            };
+            let queue = self.clone();
+            tauri::async_runtime::spawn(async move { queue.start(task).await });
+        }
+    }
+
+    /// Creates the task's session, then hands the task to its agent once it
+    /// is ready.
+    async fn start(&self, task: QueuedTask) {
+        let app = &self.inner.app_handle;
+        log::info!("Starting task {}", task.id);
+        let created = orchestrator::create_full_session(
+            app.clone(),
+            &app.state::<WorktreeManager>(),
+            &app.state::<ProcessManager>(),
+            &app.state::<SessionManager>(),
+            &app.state::<LaunchScheduler>(),
+            Path::new(&task.repo_path),
+            task.branch_spec.clone(),
+            task.mode.clone(),
+            task.profile.clone(),
+            None,
+        )
+        .await;
+        {
+            let mut state = self.lock();
+            state.starting.retain(|t| t.id != task.id);
+            self.save(&state);
+        }
+
+        let session = match created {
+            Ok(session) => session,
+            Err(error) => {
+                log::warn!("Failed to start task {}: {error}", task.id);
+                self.emit(TaskQueueEvent::Failed {
+                    task,
+                    session_id: None,
+                    error,
+                });
+                self.dispatch();
+                return;
+            }
+        };
+        let title: String = task
+            .description
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .chars()
+            .take(MAX_TITLE_CHARS)
+            .collect();
+        app.state::<SessionManager>()
+            .modify(session.id, |s| s.title = Some(title));
+        self.emit(TaskQueueEvent::Started {
+            task: task.clone(),
+            session_id: session.id,
+        });
+        self.dispatch();
+
+        if let Err(error) = orchestrator::send_prompt_when_ready(app, session.id, &task.description).await {
+            log::warn!("Failed to send task {} to session {}: {error}", task.id, session.id);
+            self.emit(TaskQueueEvent::Failed {
+                task,
+                session_id: Some(session.id),
+                error,
+            });
+        }
+    }
+
+    fn emit(&self, event: TaskQueueEvent) {
+        let _ = self.inner.app_handle.emit(TASK_QUEUE_CHANGED_EVENT, event);
+    }
+
+    fn save(&self, state: &State) {
+        let file = TaskQueueFile {
+            version: TASK_QUEUE_VERSION,
+            next_id: state.next_id,
+            tasks: state.starting.iter().chain(&state.tasks).cloned().collect(),
+        };
+        if let Err(e) = storage::save_json(&self.inner.path, &file) {
+            log::error!("Failed to save task queue {}: {e}", self.inner.path.display());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.inner.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl SessionListener for TaskQueue {
+    fn on_status_changed(&self, _session: &SessionConfig, _transition: &StatusTransition) {
+        self.dispatch();
+    }
+
+    fn on_removed(&self, _session: &SessionConfig) {
+        self.dispatch();
+    }
+}
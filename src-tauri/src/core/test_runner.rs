@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use super::clock;
+use super::error::{AppError, ErrorCode};
+use super::repo_config::RepoConfig;
+use super::session_manager::{SessionConfig, SessionManager};
+use super::worktree_manager::WorktreeManager;
+
+/// Longest test output kept, in bytes; the end is kept, since that is
+/// where test runners summarize.
+const MAX_TEST_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Longest a test command may run before it is killed.
+const TEST_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Event emitted with a `TestOutputLine` for every line a test run prints.
+pub const TEST_OUTPUT_EVENT: &str = "test-output";
+
+/// Test runner whose summary was recognized in a run's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TestFramework {
+    Cargo,
+    Pytest,
+    Jest,
+}
+
+/// Test counts parsed from a run's output. `skipped` includes tests the
+/// runner reports as ignored or todo; pytest errors count as failed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+pub struct TestSummary {
+    pub framework: TestFramework,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+}
+
+/// Output and exit status of a test command. `exit_code` is `None` if the
+/// command was killed, by a signal or after `TEST_TIMEOUT`. `summary` is
+/// `None` if no known runner's summary was found in the output.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TestRun {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    /// Combined stdout and stderr, cut from the front if too long.
+    pub output: String,
+    #[serde(default)]
+    pub summary: Option<TestSummary>,
+}
+
+impl TestRun {
+    /// Whether the command succeeded.
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Outcome of a session's latest `run_tests`, kept on the session.
+/// `finished_at` is a Unix epoch timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TestResult {
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub finished_at: i64,
+    pub summary: Option<TestSummary>,
+}
+
+/// Payload of `test-output`: a line printed by the test run in
+/// `worktree_path`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TestOutputLine {
+    pub worktree_path: String,
+    pub line: String,
+}
+
+/// Runs `command` with `sh -c` in `dir`, with `env` set, calling `on_line`
+/// with every line of its stdout and stderr as it is printed. The output
+/// is kept, cut to `MAX_TEST_OUTPUT_BYTES`, and parsed for test counts.
+///
+/// The command runs in its own process group, which is killed as a whole
+/// after `TEST_TIMEOUT`, so test processes started by the shell do not
+/// outlive it.
+pub async fn run(
+    command: &str,
+    dir: &Path,
+    env: BTreeMap<String, String>,
+    mut on_line: impl FnMut(&str),
+) -> io::Result<TestRun> {
+    let started = Instant::now();
+    let mut child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .envs(env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .kill_on_drop(true)
+        .spawn()?;
+    let pgid = child.id().map(|pid| pid as i32);
+    let mut stdout = child.stdout.take().map(|out| BufReader::new(out).split(b'\n'));
+    let mut stderr = child.stderr.take().map(|err| BufReader::new(err).split(b'\n'));
+
+    let mut output = String::new();
+    let finished = tokio::time::timeout(TEST_TIMEOUT, async {
+        while stdout.is_some() || stderr.is_some() {
+            let (from_stdout, segment) = tokio::select! {
+                segment = async { stdout.as_mut()?.next_segment().await.transpose() }, if stdout.is_some() => {
+                    (true, segment)
+                }
+                segment = async { stderr.as_mut()?.next_segment().await.transpose() }, if stderr.is_some() => {
+                    (false, segment)
+                }
+            };
+            match segment {
+                Some(segment) => {
+                    let segment = segment?;
+                    let text = String::from_utf8_lossy(&segment);
+                    let line = text.trim_end_matches('\r');
+                    on_line(line);
+                    output.push_str(line);
+                    output.push('\n');
+                    if output.len() > 2 * MAX_TEST_OUTPUT_BYTES {
+                        output = truncate_start(std::mem::take(&mut output), MAX_TEST_OUTPUT_BYTES);
+                    }
+                }
+                None if from_stdout => stdout = None,
+                None => stderr = None,
+            }
+        }
+        child.wait().await
+    })
+    .await;
+    let (exit_code, timed_out) = match finished {
+        Ok(status) => (status?.code(), false),
+        Err(_) => {
+            // `kill_on_drop` would reach only `sh`
+            if let Some(pgid) = pgid {
+                if unsafe { libc::kill(-pgid, libc::SIGKILL) } != 0 {
+                    log::warn!("Failed to kill test command '{command}' (pgid={pgid}): {}", io::Error::last_os_error());
+                }
+            }
+            let _ = child.wait().await;
+            (None, true)
+        }
+    };
+    Ok(TestRun {
+        command: command.to_string(),
+        exit_code,
+        timed_out,
+        duration_ms: started.elapsed().as_millis() as u64,
+        summary: parse_summary(&output),
+        output: truncate_start(output, MAX_TEST_OUTPUT_BYTES),
+    })
+}
+
+/// Runs the test command of the worktree at `worktree_path`, emitting its
+/// output on `test-output` line by line, and returns the run. Fails with
+/// `UnmanagedPath` unless the path is a worktree Maestro manages.
+///
+/// The command is the test command of the worktree's session, else
+/// `agent.test_command` of the worktree's `.maestro/config.toml`. The
+/// session's profile environment is applied, and the outcome is stored on
+/// the session as its `test_result`.
+pub async fn run_tests(app: &AppHandle, worktree_path: &Path) -> Result<TestRun, AppError> {
+    if !worktree_path.is_dir() {
+        return Err(AppError::not_found(format!("{} is not a directory", worktree_path.display()))
+            .with("path", worktree_path.display()));
+    }
+    let worktrees = app.state::<WorktreeManager>();
+    let repo_path = worktrees.repo_of(worktree_path).await.ok_or_else(|| {
+        AppError::new(ErrorCode::UnmanagedPath, format!("{} is not a git worktree", worktree_path.display()))
+            .with("path", worktree_path.display())
+    })?;
+    let worktree_path = &worktrees.ensure_managed(&repo_path, worktree_path).await?;
+    let session = session_of(app, worktree_path);
+    let command = session
+        .as_ref()
+        .and_then(|s| s.profile.test_command.clone())
+        .or_else(|| RepoConfig::for_repo(worktree_path).agent.test_command)
+        .filter(|command| !command.trim().is_empty())
+        .ok_or_else(|| {
+            AppError::invalid_argument(format!(
+                "No test command is configured for {}; set agent.test_command in .maestro/config.toml",
+                worktree_path.display()
+            ))
+            .with("path", worktree_path.display())
+        })?;
+    run_and_record(app, worktree_path, &command).await
+}
+
+/// Runs `command` in the worktree at `worktree_path` as `run_tests` does,
+/// with the environment of the worktree's session, and stores the outcome
+/// on the session.
+pub async fn run_and_record(app: &AppHandle, worktree_path: &Path, command: &str) -> Result<TestRun, AppError> {
+    let session = session_of(app, worktree_path);
+    let env = session.as_ref().map(|s| s.profile.shell_env()).unwrap_or_default();
+
+    let worktree = worktree_path.to_string_lossy().into_owned();
+    let run = run(command, worktree_path, env, |line| {
+        let payload = TestOutputLine {
+            worktree_path: worktree.clone(),
+            line: line.to_string(),
+        };
+        let _ = app.emit(TEST_OUTPUT_EVENT, &payload);
+    })
+    .await
+    .map_err(|e| {
+        AppError::new(ErrorCode::SpawnFailed, format!("Failed to run '{command}': {e}")).with("command", command)
+    })?;
+
+    if let Some(session) = session {
+        let result = TestResult {
+            command: run.command.clone(),
+            passed: run.passed(),
+            exit_code: run.exit_code,
+            timed_out: run.timed_out,
+            duration_ms: run.duration_ms,
+            finished_at: clock::now_secs(),
+            summary: run.summary,
+        };
+        app.state::<SessionManager>().modify(session.id, |s| s.test_result = Some(result));
+    }
+    Ok(run)
+}
+
+/// Finds the session whose worktree is `worktree_path`, comparing the
+/// canonical paths so symlinks and `..` components do not hide it.
+fn session_of(app: &AppHandle, worktree_path: &Path) -> Option<SessionConfig> {
+    let canonical = worktree_path.canonicalize().ok()?;
+    app.state::<SessionManager>().all_sessions().into_iter().find(|s| {
+        s.worktree_path
+            .as_deref()
+            .and_then(|path| Path::new(path).canonicalize().ok())
+            .is_some_and(|path| path == canonical)
+    })
+}
+
+/// Finds the summary of a cargo, pytest, or jest run in its output. cargo
+/// prints one `test result:` line per test binary, which are added up; for
+/// the others the last summary wins.
+pub fn parse_summary(output: &str) -> Option<TestSummary> {
+    let cargo = Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored")
+        .expect("valid cargo pattern");
+    let jest = Regex::new(r"^Tests:\s+(.*\d+ total)").expect("valid jest pattern");
+    let pytest = Regex::new(r"^=* ?(.*\d+ (?:passed|failed|skipped|errors?)\b.*?) in [\d.]+s\b.*$")
+        .expect("valid pytest pattern");
+    let count = Regex::new(r"(\d+) (\w+)").expect("valid count pattern");
+
+    let mut summary: Option<TestSummary> = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(caps) = cargo.captures(line) {
+            let total = summary
+                .filter(|s| s.framework == TestFramework::Cargo)
+                .unwrap_or(TestSummary {
+                    framework: TestFramework::Cargo,
+                    passed: 0,
+                    failed: 0,
+                    skipped: 0,
+                });
+            let n = |i: usize| caps[i].parse::<u32>().unwrap_or(0);
+            summary = Some(TestSummary {
+                passed: total.passed + n(1),
+                failed: total.failed + n(2),
+                skipped: total.skipped + n(3),
+                ..total
+            });
+            continue;
+        }
+        let (framework, counts) = if let Some(caps) = jest.captures(line) {
+            (TestFramework::Jest, caps.get(1))
+        } else if let Some(caps) = pytest.captures(line) {
+            (TestFramework::Pytest, caps.get(1))
+        } else {
+            continue;
+        };
+        let mut parsed = TestSummary {
+            framework,
+            passed: 0,
+            failed: 0,
+            skipped: 0,
+        };
+        for caps in count.captures_iter(counts.map_or("", |m| m.as_str())) {
+            let n = caps[1].parse::<u32>().unwrap_or(0);
+            match &caps[2] {
+                "passed" => parsed.passed += n,
+                "failed" | "error" | "errors" => parsed.failed += n,
+                "skipped" | "todo" | "xfailed" | "deselected" => parsed.skipped += n,
+                _ => {}
+            }
+        }
+        summary = Some(parsed);
+    }
+    summary
+}
+
+/// Keeps the last `max` bytes of `text`, cut at a character boundary.
+fn truncate_start(text: String, max: usize) -> String {
+    if text.len() <= max {
+        return text;
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    text[start..].to_string()
+}
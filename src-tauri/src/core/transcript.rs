@@ -0,0 +1,253 @@
+use std::borrow::Cow;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::agent_adapter::AgentEvent;
+use super::clock;
+use super::session_manager::SessionConfig;
+use super::storage;
+
+/// Matches returned by `TranscriptStore::search` when no limit is given.
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+/// Transcripts kept on disk; those of the oldest runs are deleted first.
+const MAX_TRANSCRIPTS: usize = 1000;
+
+/// One recorded event. `at` is a Unix epoch timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TranscriptEntry {
+    pub at: i64,
+    #[serde(flatten)]
+    pub event: AgentEvent,
+}
+
+/// A transcript entry matching a search. `transcript_path` identifies the
+/// run, since session IDs are reused after removal.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TranscriptMatch {
+    pub session_id: u32,
+    pub transcript_path: String,
+    pub entry: TranscriptEntry,
+}
+
+/// Directory holding every transcript.
+fn transcripts_dir() -> PathBuf {
+    storage::data_dir().join("transcripts")
+}
+
+/// Returns where the session's transcript is (or would be) stored. The
+/// creation time is part of the name, so a reused ID starts a new file.
+pub fn transcript_path(session: &SessionConfig) -> PathBuf {
+    transcripts_dir().join(format!("{}-{}.jsonl", session.id, session.created_at))
+}
+
+/// Append-only record of every agent conversation, one JSON Lines file per
+/// session run under `transcripts/` in the data dir.
+///
+/// Entries are the same `AgentEvent`s emitted to the frontend: prompts,
+/// assistant text, tool calls (including the edits they make), tool
+/// results, and costs. Files outlive their session, so the archive can
+/// point at them and `search` covers finished runs too, until more than
+/// `MAX_TRANSCRIPTS` runs have one and the oldest are deleted. Cheap to
+/// clone.
+#[derive(Clone)]
+pub struct TranscriptStore {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// Serializes appends so concurrent events never interleave lines.
+    write_lock: Mutex<()>,
+}
+
+impl Default for TranscriptStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TranscriptStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                write_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Appends an event to the session's transcript. Starting a new
+    /// transcript deletes the oldest beyond `MAX_TRANSCRIPTS`.
+    pub fn append(&self, session: &SessionConfig, event: &AgentEvent) -> io::Result<()> {
+        let entry = TranscriptEntry {
+            at: clock::now_secs(),
+            event: event.clone(),
+        };
+        let mut line = serde_json::to_vec(&entry).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        line.push(b'\n');
+
+        let _guard = self.lock();
+        std::fs::create_dir_all(transcripts_dir())?;
+        let path = transcript_path(session);
+        let started = !path.exists();
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?
+            .write_all(&line)?;
+        if started {
+            prune_transcripts();
+        }
+        Ok(())
+    }
+
+    /// Returns the session's transcript in order, empty if nothing was
+    /// recorded. Lines that fail to parse (e.g. one cut short by a crash)
+    /// are skipped.
+    pub fn get(&self, session: &SessionConfig) -> io::Result<Vec<TranscriptEntry>> {
+        let file = match std::fs::File::open(transcript_path(session)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect())
+    }
+
+    /// Finds entries across all transcripts, live and archived, whose text
+    /// contains `query` (case-insensitive). Newest runs come first; within a
+    /// run, entries are in order. Unreadable files are skipped.
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Vec<TranscriptMatch> {
+        let needle = query.to_lowercase();
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        if needle.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut runs = transcript_runs();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.0));
+
+        let mut matches = Vec::new();
+        for (_, session_id, path) in runs {
+            let Ok(file) = std::fs::File::open(&path) else {
+                continue;
+            };
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                let Ok(entry) = serde_json::from_str::<TranscriptEntry>(&line) else {
+                    continue;
+                };
+                if !searchable_text(&entry.event).to_lowercase().contains(&needle) {
+                    continue;
+                }
+                matches.push(TranscriptMatch {
+                    session_id,
+                    transcript_path: path.to_string_lossy().into_owned(),
+                    entry,
+                });
+                if matches.len() == limit {
+                    return matches;
+                }
+            }
+        }
+        matches
+    }
+
+    /// Returns the agent costs recorded between `since` and `until` (Unix
+    /// seconds, inclusive) across all transcripts, as `(at, usd)` pairs in
+    /// no particular order. Runs started after `until` or last written
+    /// before `since` are not read.
+    pub fn costs(&self, since: i64, until: i64) -> Vec<(i64, f64)> {
+        let Ok(entries) = std::fs::read_dir(transcripts_dir()) else {
+            return Vec::new();
+        };
+        let mut costs = Vec::new();
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let created_at = path
+                .file_stem()
+                .and_then(|stem| stem.to_str()?.split_once('-')?.1.parse::<i64>().ok());
+            let modified_at = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64);
+            if created_at.is_some_and(|at| at > until) || modified_at.is_some_and(|at| at < since) {
+                continue;
+            }
+            let Ok(file) = std::fs::File::open(&path) else {
+                continue;
+            };
+            for line in BufReader::new(file).lines().map_while(Result::ok) {
+                if let Ok(TranscriptEntry {
+                    at,
+                    event: AgentEvent::Cost { usd },
+                }) = serde_json::from_str(&line)
+                {
+                    if (since..=until).contains(&at) {
+                        costs.push((at, usd));
+                    }
+                }
+            }
+        }
+        costs
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        match self.inner.write_lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Every transcript as `(created_at, session_id, path)`, in no particular
+/// order; empty if the directory cannot be read.
+fn transcript_runs() -> Vec<(i64, u32, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(transcripts_dir()) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|e| {
+            let path = e.path();
+            let (id, created_at) = path.file_stem()?.to_str()?.split_once('-')?;
+            Some((created_at.parse().ok()?, id.parse().ok()?, path))
+        })
+        .collect()
+}
+
+/// Deletes the transcripts of all but the newest `MAX_TRANSCRIPTS` runs.
+fn prune_transcripts() {
+    let mut runs = transcript_runs();
+    let excess = runs.len().saturating_sub(MAX_TRANSCRIPTS);
+    if excess == 0 {
+        return;
+    }
+    runs.sort_by_key(|run| run.0);
+    for (_, _, path) in &runs[..excess] {
+        if let Err(e) = std::fs::remove_file(path) {
+            log::warn!("Failed to remove old transcript {}: {e}", path.display());
+        }
+    }
+}
+
+/// The text of an event that `search` matches against.
+pub(super) fn searchable_text(event: &AgentEvent) -> Cow<'_, str> {
+    match event {
+        AgentEvent::Prompt { text } | AgentEvent::AssistantText { text } => Cow::Borrowed(text),
+        AgentEvent::ToolCall { name, input, .. } => Cow::Owned(format!("{name} {input}")),
+        AgentEvent::ToolResult { content, .. } => Cow::Borrowed(content),
+        AgentEvent::Throttled { message, .. } => Cow::Borrowed(message),
+        AgentEvent::AgentSession { .. } | AgentEvent::Cost { .. } | AgentEvent::Usage { .. } => {
+            Cow::Borrowed("")
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{TrayIcon, TrayIconBuilder};
+use tauri::{AppHandle, Emitter, Listener, Manager, Wry};
+
+use super::deep_link::DeepLink;
+use super::launch::{self, LaunchRequest, LAUNCH_REQUEST_EVENT};
+use super::process_manager::ProcessManager;
+use super::scheduler::{LaunchScheduler, LAUNCHES_PAUSED_EVENT};
+use super::session_manager::{SessionConfig, SessionListener, SessionManager, SessionStatus, StatusTransition};
+
+const FOCUS_ITEM: &str = "focus-needs-input";
+const PAUSE_ITEM: &str = "pause-launches";
+const SHOW_ITEM: &str = "show";
+const QUIT_ITEM: &str = "quit";
+
+/// Tray icon summarizing session state while Maestro runs in the
+/// background.
+///
+/// Its tooltip and first menu line count the sessions `Working` and those
+/// in `NeedsInput`. The menu brings the first session needing input into
+/// view (through `launch-request`, as a `maestro://session` link would),
+/// pauses or resumes launches (`LaunchScheduler::set_paused`), shows the
+/// main window, or quits once every agent's terminal is closed. Closing the
+/// main window only hides it while the tray is there. Registered as a
+/// `SessionListener`; refreshed on every status change and on
+/// `launches-paused`. Created by the app only, not by headless backends.
+#[derive(Clone)]
+pub struct SystemTray {
+    app_handle: AppHandle,
+    icon: TrayIcon<Wry>,
+    summary: MenuItem<Wry>,
+    focus: MenuItem<Wry>,
+    pause: CheckMenuItem<Wry>,
+}
+
+impl SystemTray {
+    /// Creates the tray icon and hooks it into session changes.
+    pub fn install(app: &AppHandle) -> tauri::Result<()> {
+        let summary = MenuItem::with_id(app, "summary", "", false, None::<&str>)?;
+        let focus = MenuItem::with_id(app, FOCUS_ITEM, "Focus session needing input", false, None::<&str>)?;
+        let pause = CheckMenuItem::with_id(app, PAUSE_ITEM, "Pause new launches", true, false, None::<&str>)?;
+        let show = MenuItem::with_id(app, SHOW_ITEM, "Show Maestro", true, None::<&str>)?;
+        let quit = MenuItem::with_id(app, QUIT_ITEM, "Quit Maestro", true, None::<&str>)?;
+        let menu = Menu::with_items(
+            app,
+            &[
+                &summary,
+                &PredefinedMenuItem::separator(app)?,
+                &focus,
+                &pause,
+                &PredefinedMenuItem::separator(app)?,
+                &show,
+                &quit,
+            ],
+        )?;
+        let mut builder = TrayIconBuilder::with_id("main")
+            .menu(&menu)
+            .tooltip("Maestro")
+            .show_menu_on_left_click(true)
+            .on_menu_event(on_menu_event);
+        if let Some(icon) = app.default_window_icon() {
+            builder = builder.icon(icon.clone());
+        }
+        let icon = builder.build(app)?;
+
+        let tray = Self {
+            app_handle: app.clone(),
+            icon,
+            summary,
+            focus,
+            pause,
+        };
+        app.state::<SessionManager>().add_listener(Arc::new(tray.clone()));
+        let listener = tray.clone();
+        app.listen(LAUNCHES_PAUSED_EVENT, move |_| listener.refresh());
+        tray.update();
+        launch::hide_main_window_on_close(app);
+        Ok(())
+    }
+
+    /// Schedules `update` on the main thread, which owns the tray, without
+    /// waiting for it.
+    fn refresh(&self) {
+        let tray = self.clone();
+        if let Err(e) = self.app_handle.run_on_main_thread(move || tray.update()) {
+            log::warn!("Failed to refresh tray icon: {e}");
+        }
+    }
+
+    fn update(&self) {
+        let sessions = self.app_handle.state::<SessionManager>().all_sessions();
+        let count = |status| sessions.iter().filter(|s| s.status == status).count();
+        let needs_input = count(SessionStatus::NeedsInput);
+        let summary = format!("{} working / {needs_input} need input", count(SessionStatus::Working));
+        let paused = self.app_handle.state::<LaunchScheduler>().is_paused();
+        let tooltip = if paused {
+            format!("Maestro: {summary} (launches paused)")
+        } else {
+            format!("Maestro: {summary}")
+        };
+        let result = self
+            .summary
+            .set_text(&summary)
+            .and_then(|_| self.focus.set_enabled(needs_input > 0))
+            .and_then(|_| self.pause.set_checked(paused))
+            .and_then(|_| self.icon.set_tooltip(Some(tooltip)));
+        if let Err(e) = result {
+            log::warn!("Failed to update tray icon: {e}");
+        }
+    }
+}
+
+impl SessionListener for SystemTray {
+    fn on_status_changed(&self, _session: &SessionConfig, _transition: &StatusTransition) {
+        self.refresh();
+    }
+
+    fn on_removed(&self, _session: &SessionConfig) {
+        self.refresh();
+    }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        FOCUS_ITEM => {
+            let Some(session) = app
+                .state::<SessionManager>()
+                .all_sessions()
+                .into_iter()
+                .find(|s| s.status == SessionStatus::NeedsInput)
+            else {
+                return;
+            };
+            launch::show_main_window(app);
+            let request = LaunchRequest {
+                repo_paths: Vec::new(),
+                links: vec![DeepLink::OpenSession { session_id: session.id }],
+            };
+            let _ = app.emit(LAUNCH_REQUEST_EVENT, &request);
+        }
+        PAUSE_ITEM => {
+            let scheduler = app.state::<LaunchScheduler>();
+            scheduler.set_paused(!scheduler.is_paused());
+        }
+        SHOW_ITEM => launch::show_main_window(app),
+        QUIT_ITEM => {
+            log::info!("Quitting from the tray");
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                app.state::<ProcessManager>().kill_all().await;
+                app.exit(0);
+            });
+        }
+        _ => {}
+    }
+}
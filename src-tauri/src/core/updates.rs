@@ -0,0 +1,128 @@
+use std::io;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::backup::version_triple;
+use super::settings::SettingsStore;
+
+/// Latest published release of Maestro, as the GitHub API returns it.
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/lliWcWill/maestro-linux/releases/latest";
+
+/// Longest the release feed may take to answer.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Event emitted with the `UpdateInfo` when the check at startup finds a
+/// newer release.
+pub const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+
+/// What the release feed says about the latest release. `notes` is the
+/// release's Markdown description. `download_url` is its `.deb` package,
+/// the format Maestro is bundled in, or the release page if it has none.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub name: Option<String>,
+    pub notes: String,
+    pub published_at: Option<String>,
+    pub release_url: String,
+    pub download_url: String,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    name: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Asks GitHub for the latest release and compares it with this build.
+///
+/// Fetched with `curl`, which every desktop Linux has, so the app needs no
+/// TLS stack of its own. Pre-releases and drafts are never offered, as the
+/// feed only returns the latest full release. Updating in place is left to
+/// the package manager, which owns `.deb` installs.
+pub async fn check() -> Result<UpdateInfo, String> {
+    let output = tokio::process::Command::new("curl")
+        .args(["-fsSL", "--max-time", &CHECK_TIMEOUT.as_secs().to_string()])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(["-A", concat!("maestro-linux/", env!("CARGO_PKG_VERSION"))])
+        .arg(LATEST_RELEASE_URL)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(CHECK_TIMEOUT + Duration::from_secs(5), output)
+        .await
+        .map_err(|_| format!("Update check timed out after {}s", CHECK_TIMEOUT.as_secs()))?
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => "curl is not installed".to_string(),
+            _ => format!("Failed to run curl: {e}"),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to fetch the latest release: {}", stderr.trim()));
+    }
+    let release: Release =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("Invalid release feed: {e}"))?;
+    Ok(compare(release, env!("CARGO_PKG_VERSION")))
+}
+
+fn compare(release: Release, current: &str) -> UpdateInfo {
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    let update_available = match (version_triple(&latest), version_triple(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    };
+    let download_url = release
+        .assets
+        .iter()
+        .find(|asset| asset.name.ends_with(".deb"))
+        .map_or_else(|| release.html_url.clone(), |asset| asset.browser_download_url.clone());
+    UpdateInfo {
+        current_version: current.to_string(),
+        latest_version: latest,
+        update_available,
+        name: release.name.filter(|name| !name.trim().is_empty()),
+        notes: release.body.unwrap_or_default(),
+        published_at: release.published_at,
+        release_url: release.html_url,
+        download_url,
+    }
+}
+
+/// Checks for updates in the background, unless the settings turn it off,
+/// and emits `update-available` if a newer release is out. A failed check
+/// is only logged. Called by the app once setup finished, not by headless
+/// backends.
+pub fn check_at_startup(app: &AppHandle) {
+    if !app.state::<SettingsStore>().get().check_for_updates {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match check().await {
+            Ok(info) if info.update_available => {
+                log::info!("Maestro {} is available (running {})", info.latest_version, info.current_version);
+                let _ = app.emit(UPDATE_AVAILABLE_EVENT, &info);
+            }
+            Ok(_) => log::debug!("Maestro is up to date"),
+            Err(e) => log::info!("Update check failed: {e}"),
+        }
+    });
+}
@@ -0,0 +1,347 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use super::audit::AuditLog;
+use super::clock;
+use super::orchestrator;
+use super::process_manager::ProcessManager;
+use super::scheduler::LaunchScheduler;
+use super::session_manager::{
+    AiMode, SessionConfig, SessionListener, SessionManager, SessionStatus, StatusCause, StatusTransition,
+};
+use super::storage;
+use super::windows;
+
+/// Current on-disk format of `watchdog-policy.json`.
+const WATCHDOG_POLICY_VERSION: u32 = 1;
+
+/// Time between checks for stalled sessions.
+const STALL_CHECK_INTERVAL_SECS: u64 = 30;
+
+/// Window over which `max_restarts` is counted.
+const RESTART_WINDOW_SECS: i64 = 60 * 60;
+
+/// Event emitted whenever the watchdog finds a crashed or stalled agent.
+pub const AGENT_WATCHDOG_EVENT: &str = "agent-watchdog";
+
+/// How the watchdog reacts to agents that die or go quiet.
+///
+/// An agent has crashed when a `Working` session drops back to its shell
+/// prompt or its shell exits with an error; it has stalled when a `Working`
+/// session prints nothing for `stall_secs` (`None` disables stall checks).
+/// Either is always reported; with `restart_on_crash` or
+/// `restart_on_stall`, the agent is also relaunched on its previous
+/// conversation in the same worktree, as by `orchestrator::resume_agent`,
+/// at most `max_restarts` times per session per hour. A stalled agent's
+/// shell is killed first. Fields missing from a saved policy take their
+/// default values.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct WatchdogPolicy {
+    pub stall_secs: Option<u64>,
+    pub restart_on_crash: bool,
+    pub restart_on_stall: bool,
+    pub max_restarts: u32,
+}
+
+impl Default for WatchdogPolicy {
+    fn default() -> Self {
+        Self {
+            stall_secs: Some(30 * 60),
+            restart_on_crash: false,
+            restart_on_stall: false,
+            max_restarts: 3,
+        }
+    }
+}
+
+/// What the watchdog noticed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchdogTrigger {
+    Crashed,
+    Stalled,
+}
+
+/// Payload of the `agent-watchdog` event. `restarted` is `true` if a
+/// restart was requested; `restart_error` says why one failed or was not
+/// attempted despite the policy.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WatchdogAlert {
+    pub session_id: u32,
+    pub trigger: WatchdogTrigger,
+    pub detail: Option<String>,
+    pub restarted: bool,
+    pub restart_error: Option<String>,
+    pub at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WatchdogPolicyFile {
+    version: u32,
+    policy: WatchdogPolicy,
+}
+
+#[derive(Default)]
+struct Watch {
+    /// Sessions already reported as stalled, until they print again.
+    stalled: HashMap<u32, i64>,
+    /// Restart times per session within `RESTART_WINDOW_SECS`.
+    restarts: HashMap<u32, Vec<i64>>,
+    /// Sessions being restarted, whose status changes are expected.
+    restarting: HashSet<u32>,
+}
+
+struct Inner {
+    app_handle: AppHandle,
+    path: PathBuf,
+    policy: Mutex<WatchdogPolicy>,
+    watch: Mutex<Watch>,
+    changed: Notify,
+}
+
+/// Watches sessions running an agent for crashes and stalls and, as the
+/// persisted `WatchdogPolicy` allows, restarts their agents.
+///
+/// Registered as a `SessionListener` to see crashes; `spawn_stall_task`
+/// looks for stalls. Every finding is emitted on `agent-watchdog`. Cheap
+/// to clone; placed in Tauri managed state.
+#[derive(Clone)]
+pub struct AgentWatchdog {
+    inner: Arc<Inner>,
+}
+
+impl AgentWatchdog {
+    /// Loads the policy from the data dir, falling back to the default
+    /// (report only) if the file is missing or unreadable.
+    pub fn load(app_handle: AppHandle) -> Self {
+        let path = storage::data_dir().join("watchdog-policy.json");
+        let policy = match storage::load_json::<WatchdogPolicyFile>(&path) {
+            Ok(Some(file)) if file.version <= WATCHDOG_POLICY_VERSION => file.policy,
+            Ok(Some(file)) => {
+                log::warn!("Ignoring watchdog policy with unknown version {}", file.version);
+                WatchdogPolicy::default()
+            }
+            Ok(None) => WatchdogPolicy::default(),
+            Err(e) => {
+                log::error!("Failed to load watchdog policy {}: {e}", path.display());
+                WatchdogPolicy::default()
+            }
+        };
+        Self {
+            inner: Arc::new(Inner {
+                app_handle,
+                path,
+                policy: Mutex::new(policy),
+                watch: Mutex::new(Watch::default()),
+                changed: Notify::new(),
+            }),
+        }
+    }
+
+    /// Returns the current policy.
+    pub fn policy(&self) -> WatchdogPolicy {
+        self.lock_policy().clone()
+    }
+
+    /// Persists a new policy and wakes the stall task.
+    pub fn set_policy(&self, policy: WatchdogPolicy) -> io::Result<()> {
+        let mut current = self.lock_policy();
+        let file = WatchdogPolicyFile {
+            version: WATCHDOG_POLICY_VERSION,
+            policy: policy.clone(),
+        };
+        storage::save_json(&self.inner.path, &file)?;
+        *current = policy;
+        drop(current);
+        self.inner.changed.notify_one();
+        Ok(())
+    }
+
+    /// Reports every `Working` session silent for the policy's
+    /// `stall_secs`, once per stall.
+    fn check_stalls(&self, stall_secs: u64) {
+        let app = &self.inner.app_handle;
+        let sessions = app.state::<SessionManager>();
+        let now = clock::now_secs();
+        let mut stalled = Vec::new();
+        {
+            let mut watch = self.lock_watch();
+            for activity in sessions.activity() {
+                let quiet_since = activity.last_output_at.unwrap_or(0).max(activity.status_since);
+                let is_stalled = activity.status == SessionStatus::Working
+                    && now - quiet_since >= stall_secs as i64
+                    && !watch.restarting.contains(&activity.session_id);
+                if !is_stalled {
+                    watch.stalled.remove(&activity.session_id);
+                } else if watch.stalled.get(&activity.session_id) != Some(&quiet_since) {
+                    watch.stalled.insert(activity.session_id, quiet_since);
+                    stalled.push((activity.session_id, now - quiet_since));
+                }
+            }
+        }
+        for (session_id, quiet_secs) in stalled {
+            let Some(session) = sessions.get_session(session_id) else {
+                continue;
+            };
+            if session.mode == AiMode::Plain {
+                continue;
+            }
+            let detail = format!("no output for {} min", quiet_secs / 60);
+            self.handle(session, WatchdogTrigger::Stalled, Some(detail));
+        }
+    }
+
+    /// Reports a finding and restarts the agent if the policy says so.
+    fn handle(&self, session: SessionConfig, trigger: WatchdogTrigger, detail: Option<String>) {
+        let policy = self.policy();
+        let wanted = match trigger {
+            WatchdogTrigger::Crashed => policy.restart_on_crash,
+            WatchdogTrigger::Stalled => policy.restart_on_stall,
+        };
+        let now = clock::now_secs();
+        let mut alert = WatchdogAlert {
+            session_id: session.id,
+            trigger,
+            detail,
+            restarted: false,
+            restart_error: None,
+            at: now,
+        };
+        log::warn!("Session {}: agent {trigger:?} ({:?})", session.id, alert.detail);
+
+        if wanted {
+            let mut watch = self.lock_watch();
+            let restarts = watch.restarts.entry(session.id).or_default();
+            restarts.retain(|&at| now - at < RESTART_WINDOW_SECS);
+            if restarts.len() >= policy.max_restarts as usize {
+                alert.restart_error = Some(format!("restarted {} times in the last hour", restarts.len()));
+            } else {
+                restarts.push(now);
+                watch.restarting.insert(session.id);
+                alert.restarted = true;
+            }
+        }
+        let app = self.inner.app_handle.clone();
+        windows::emit_to_session(&app, session.id, AGENT_WATCHDOG_EVENT, &alert);
+        if !alert.restarted {
+            return;
+        }
+
+        let watchdog = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = restart(&app, &session, trigger).await;
+            watchdog.lock_watch().restarting.remove(&session.id);
+            let params = serde_json::json!({ "session_id": session.id, "trigger": trigger });
+            app.state::<AuditLog>().record_automation("watchdog", "resume_agent", params, &result);
+            if let Err(e) = result {
+                log::warn!("Session {}: watchdog restart failed: {e}", session.id);
+                alert.restarted = false;
+                alert.restart_error = Some(e);
+                windows::emit_to_session(&app, session.id, AGENT_WATCHDOG_EVENT, &alert);
+            }
+        });
+    }
+
+    /// Returns whether the session's agent is gone: its PTY is gone, or
+    /// the shell rather than a job started from it is in the foreground.
+    fn agent_exited(&self, session: &SessionConfig) -> bool {
+        let Some(pty_id) = session.pty_id else {
+            return true;
+        };
+        let processes = self.inner.app_handle.state::<ProcessManager>();
+        !matches!(processes.foreground_job(pty_id), Ok(Some(_)))
+    }
+
+    fn lock_policy(&self) -> MutexGuard<'_, WatchdogPolicy> {
+        match self.inner.policy.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn lock_watch(&self) -> MutexGuard<'_, Watch> {
+        match self.inner.watch.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Relaunches the session's agent on its previous conversation. A stalled
+/// agent cannot be trusted to react to input, so its shell is killed and
+/// `resume_agent` starts a new one.
+async fn restart(app: &AppHandle, session: &SessionConfig, trigger: WatchdogTrigger) -> Result<(), String> {
+    let processes = app.state::<ProcessManager>();
+    if let (WatchdogTrigger::Stalled, Some(pty_id)) = (trigger, session.pty_id) {
+        processes.kill_session(pty_id).await.map_err(|e| e.to_string())?;
+    }
+    orchestrator::resume_agent(
+        app.clone(),
+        &processes,
+        &app.state::<SessionManager>(),
+        &app.state::<LaunchScheduler>(),
+        session.id,
+    )
+    .await
+    .map(|_| log::info!("Session {}: watchdog restarted the agent", session.id))
+    .map_err(|e| e.message)
+}
+
+impl SessionListener for AgentWatchdog {
+    fn on_status_changed(&self, session: &SessionConfig, transition: &StatusTransition) {
+        if transition.from != Some(SessionStatus::Working) || session.mode == AiMode::Plain {
+            return;
+        }
+        let crashed = match (transition.to, transition.cause) {
+            (SessionStatus::Error, StatusCause::Exit) => true,
+            // Inferred from output alone, so only a crash once the shell is
+            // back in the foreground; a quiet agent that is still running
+            // must not get its launch command typed into it
+            (SessionStatus::Idle, StatusCause::AutoDetected) => self.agent_exited(session),
+            _ => false,
+        };
+        if crashed && !self.lock_watch().restarting.contains(&session.id) {
+            let detail = transition.detail.clone().or_else(|| Some("agent exited".to_string()));
+            self.handle(session.clone(), WatchdogTrigger::Crashed, detail);
+        }
+    }
+
+    fn on_removed(&self, session: &SessionConfig) {
+        let mut watch = self.lock_watch();
+        watch.stalled.remove(&session.id);
+        watch.restarts.remove(&session.id);
+    }
+}
+
+/// Spawns the background task that checks for stalled agents while the
+/// policy has `stall_secs` set.
+///
+/// Reads `AgentWatchdog` and `SessionManager` from managed state, so both
+/// must be registered before this is called from `setup`.
+pub fn spawn_stall_task(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let watchdog = app.state::<AgentWatchdog>();
+            let Some(stall_secs) = watchdog.policy().stall_secs.filter(|&secs| secs > 0) else {
+                watchdog.inner.changed.notified().await;
+                continue;
+            };
+
+            watchdog.check_stalls(stall_secs);
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(STALL_CHECK_INTERVAL_SECS)) => {}
+                _ = watchdog.inner.changed.notified() => {}
+            }
+        }
+    });
+}
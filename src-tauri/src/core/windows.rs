@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use super::error::AppError;
+use super::metrics;
+use super::session_manager::{SessionConfig, SessionListener, SessionManager};
+
+/// Event emitted with a `SessionWindowChange` whenever a session window
+/// opens or closes, so the main window can hand the session's terminal
+/// over to it and take it back.
+pub const SESSION_WINDOW_EVENT: &str = "session-window";
+
+/// Labels of session windows are this prefix and the session ID.
+const SESSION_WINDOW_PREFIX: &str = "session-";
+
+/// A session window opened (`open`) or closed.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SessionWindowChange {
+    pub session_id: u32,
+    pub label: String,
+    pub open: bool,
+}
+
+/// Secondary windows each showing one session, so an agent's terminal can
+/// live on another monitor while every window shares the same backend
+/// state.
+///
+/// A session window loads the frontend with `?session=<id>` and is labeled
+/// `session-<id>`; opening it again focuses it. Events about a single
+/// session sent through `emit_to_session` are routed to the windows
+/// showing it: the main window and the session's own. The window is closed
+/// when its session is removed. Cheap to clone; registered as a
+/// `SessionListener` and placed in Tauri managed state.
+#[derive(Clone)]
+pub struct SessionWindows {
+    app_handle: AppHandle,
+    /// Session shown by each open session window, by label.
+    bound: Arc<DashMap<String, u32>>,
+}
+
+impl SessionWindows {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            bound: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Opens, or brings to the front, the window of a session and returns
+    /// its label.
+    pub fn open(&self, session_id: u32) -> Result<String, AppError> {
+        let app = &self.app_handle;
+        let session = app
+            .state::<SessionManager>()
+            .get_session(session_id)
+            .ok_or_else(|| AppError::session_not_found(session_id))?;
+        let label = format!("{SESSION_WINDOW_PREFIX}{session_id}");
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+            return Ok(label);
+        }
+
+        let name = session
+            .title
+            .clone()
+            .or_else(|| session.branch.clone())
+            .unwrap_or_else(|| format!("Session {session_id}"));
+        let url = WebviewUrl::App(format!("index.html?session={session_id}").into());
+        let window = WebviewWindowBuilder::new(app, &label, url)
+            .title(format!("{name} - Maestro"))
+            .inner_size(1000.0, 700.0)
+            .min_inner_size(480.0, 320.0)
+            .decorations(false)
+            .build()
+            .map_err(|e| format!("Failed to open a window for session {session_id}: {e}"))?;
+        self.bound.insert(label.clone(), session_id);
+
+        let windows = self.clone();
+        let closed = label.clone();
+        window.on_window_event(move |event| {
+            if let WindowEvent::Destroyed = event {
+                if let Some((_, session_id)) = windows.bound.remove(&closed) {
+                    windows.notify(session_id, &closed, false);
+                }
+            }
+        });
+        log::info!("Session {session_id} opened in window {label}");
+        self.notify(session_id, &label, true);
+        Ok(label)
+    }
+
+    /// Returns the session a window shows, or `None` for the main window.
+    pub fn session_of(&self, label: &str) -> Option<u32> {
+        self.bound.get(label).map(|e| *e.value())
+    }
+
+    /// Returns the labels of the windows open for a session.
+    pub fn windows_of(&self, session_id: u32) -> Vec<String> {
+        self.bound
+            .iter()
+            .filter(|e| *e.value() == session_id)
+            .map(|e| e.key().clone())
+            .collect()
+    }
+
+    fn notify(&self, session_id: u32, label: &str, open: bool) {
+        let change = SessionWindowChange {
+            session_id,
+            label: label.to_string(),
+            open,
+        };
+        let _ = self.app_handle.emit(SESSION_WINDOW_EVENT, &change);
+    }
+}
+
+impl SessionListener for SessionWindows {
+    fn on_removed(&self, session: &SessionConfig) {
+        for label in self.windows_of(session.id) {
+            if let Some(window) = self.app_handle.get_webview_window(&label) {
+                let _ = window.close();
+            }
+        }
+    }
+}
+
+/// Emits an event about one session to the windows showing it: every
+/// window not bound to a session, and the session's own windows. As with
+/// any targeted Tauri event, this filters listeners registered on a window
+/// (`getCurrentWebviewWindow().listen`); global listeners get every event.
+/// Falls back to a plain emit before `SessionWindows` is managed.
+pub fn emit_to_session<S: Serialize + Clone>(app: &AppHandle, session_id: u32, event: &str, payload: S) {
+    metrics::registry().event(event);
+    let Some(windows) = app.try_state::<SessionWindows>() else {
+        let _ = app.emit(event, payload);
+        return;
+    };
+    let _ = app.emit_filter(event, payload, |target| match target {
+        EventTarget::Window { label } | EventTarget::Webview { label } | EventTarget::WebviewWindow { label } => {
+            windows.session_of(label).is_none_or(|bound| bound == session_id)
+        }
+        _ => true,
+    });
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use super::scratchpad::ScratchpadStore;
+use super::scrollback::Scrollback;
+use super::session_archive::{ArchiveFilter, ArchivedSession, SessionArchive};
+use super::session_manager::{SessionConfig, SessionManager};
+use super::transcript::{self, TranscriptStore};
+
+/// Hits `search_everything` returns when the caller gives no limit.
+const DEFAULT_LIMIT: usize = 200;
+
+/// Lines shown before and after a matching line.
+const CONTEXT_LINES: usize = 2;
+
+/// Longest line reported, in characters; longer ones are cut around the
+/// match.
+const MAX_LINE_CHARS: usize = 300;
+
+/// Where a hit was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum HitSource {
+    /// Recent terminal output of an open session.
+    Scrollback,
+    /// A session's notes, open or archived.
+    Notes,
+    /// An open session's scratchpad.
+    Scratchpad,
+    /// An agent transcript, of an open or removed session.
+    Transcript,
+}
+
+/// A line matching a `search_everything` query.
+///
+/// `session_id`, `title`, `branch`, and `repo_path` identify the session;
+/// `live` is whether it is still open, as IDs are reused after removal.
+/// `transcript_path` and `at` (Unix epoch time of the entry) are set for
+/// transcript hits. `line` is the 1-based line within the source text (the
+/// transcript entry, for transcripts), and `context` holds the matching
+/// line with up to `CONTEXT_LINES` around it.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WorkspaceHit {
+    pub source: HitSource,
+    pub session_id: u32,
+    pub title: Option<String>,
+    pub branch: Option<String>,
+    pub repo_path: String,
+    pub live: bool,
+    pub transcript_path: Option<String>,
+    pub at: Option<i64>,
+    pub line: usize,
+    pub text: String,
+    pub context: String,
+}
+
+/// The session a hit belongs to.
+struct HitSession {
+    session_id: u32,
+    title: Option<String>,
+    branch: Option<String>,
+    repo_path: String,
+    live: bool,
+}
+
+impl HitSession {
+    fn live(session: &SessionConfig) -> Self {
+        Self {
+            session_id: session.id,
+            title: session.title.clone(),
+            branch: session.branch.clone(),
+            repo_path: session.repo_path.clone(),
+            live: true,
+        }
+    }
+
+    fn archived(entry: &ArchivedSession) -> Self {
+        Self {
+            session_id: entry.session_id,
+            title: entry.title.clone(),
+            branch: entry.branch.clone(),
+            repo_path: entry.repo_path.clone(),
+            live: false,
+        }
+    }
+}
+
+/// Hits gathered so far, up to the limit.
+struct Hits {
+    needle: String,
+    limit: usize,
+    hits: Vec<WorkspaceHit>,
+}
+
+impl Hits {
+    fn is_full(&self) -> bool {
+        self.hits.len() >= self.limit
+    }
+
+    /// Adds a hit for every matching line of `text`.
+    fn scan(
+        &mut self,
+        source: HitSource,
+        session: &HitSession,
+        text: &str,
+        transcript_path: Option<&str>,
+        at: Option<i64>,
+    ) {
+        let lines: Vec<&str> = text.lines().collect();
+        for (i, line) in lines.iter().enumerate() {
+            if self.is_full() {
+                return;
+            }
+            let Some(at_char) = find(line, &self.needle) else {
+                continue;
+            };
+            let start = i.saturating_sub(CONTEXT_LINES);
+            let end = (i + CONTEXT_LINES + 1).min(lines.len());
+            let context: Vec<String> = (start..end)
+                .map(|j| if j == i { clip(line, at_char) } else { clip(lines[j], 0) })
+                .collect();
+            self.hits.push(WorkspaceHit {
+                source,
+                session_id: session.session_id,
+                title: session.title.clone(),
+                branch: session.branch.clone(),
+                repo_path: session.repo_path.clone(),
+                live: session.live,
+                transcript_path: transcript_path.map(str::to_string),
+                at,
+                line: i + 1,
+                text: clip(line, at_char),
+                context: context.join("\n"),
+            });
+        }
+    }
+}
+
+/// Searches everything Maestro keeps about its sessions for `query`
+/// (case-insensitive): the scrollback of open sessions, their notes and
+/// scratchpads, the notes of archived sessions, and every agent
+/// transcript, answering questions like which agent mentioned a failing
+/// test.
+///
+/// Hits come in that order, open sessions by ID and transcripts newest run
+/// first, and stop at `limit` (default `DEFAULT_LIMIT`). Scrollback covers
+/// only the output still buffered (see `Scrollback`). Reads every
+/// transcript, so call it off the async runtime.
+pub fn search_everything(app: &AppHandle, query: &str, limit: Option<usize>) -> Vec<WorkspaceHit> {
+    let mut hits = Hits {
+        needle: query.to_lowercase(),
+        limit: limit.unwrap_or(DEFAULT_LIMIT),
+        hits: Vec::new(),
+    };
+    if hits.needle.trim().is_empty() || hits.limit == 0 {
+        return Vec::new();
+    }
+
+    let mut sessions = app.state::<SessionManager>().all_sessions();
+    sessions.sort_by_key(|s| s.id);
+    let scrollback = app.try_state::<Scrollback>();
+    if let Some(scrollback) = &scrollback {
+        for session in &sessions {
+            if let Some(pty_id) = session.pty_id {
+                let output = scrollback.tail(pty_id, usize::MAX);
+                hits.scan(HitSource::Scrollback, &HitSession::live(session), &output, None, None);
+            }
+        }
+    }
+    let scratchpads = app.try_state::<ScratchpadStore>();
+    for session in &sessions {
+        let owner = HitSession::live(session);
+        hits.scan(HitSource::Notes, &owner, &session.notes, None, None);
+        match scratchpads.as_ref().map(|s| s.get(session.id)) {
+            Some(Ok(scratchpad)) => hits.scan(HitSource::Scratchpad, &owner, &scratchpad.content, None, None),
+            Some(Err(e)) => log::warn!("Failed to read scratchpad of session {}: {e}", session.id),
+            None => {}
+        }
+    }
+    let archived = app
+        .try_state::<SessionArchive>()
+        .map(|archive| archive.query(&ArchiveFilter::default()))
+        .unwrap_or_default();
+    for entry in &archived {
+        hits.scan(HitSource::Notes, &HitSession::archived(entry), &entry.notes, None, None);
+    }
+    if hits.is_full() {
+        return hits.hits;
+    }
+
+    let runs: HashMap<String, HitSession> = sessions
+        .iter()
+        .map(|s| (transcript::transcript_path(s).to_string_lossy().into_owned(), HitSession::live(s)))
+        .chain(archived.iter().filter_map(|e| {
+            e.transcript_path.clone().map(|path| (path, HitSession::archived(e)))
+        }))
+        .collect();
+    let remaining = hits.limit - hits.hits.len();
+    for found in app.state::<TranscriptStore>().search(query, Some(remaining)) {
+        if hits.is_full() {
+            break;
+        }
+        let owner = runs.get(&found.transcript_path);
+        let unknown = HitSession {
+            session_id: found.session_id,
+            title: None,
+            branch: None,
+            repo_path: String::new(),
+            live: false,
+        };
+        let text = transcript::searchable_text(&found.entry.event);
+        hits.scan(
+            HitSource::Transcript,
+            owner.unwrap_or(&unknown),
+            &text,
+            Some(&found.transcript_path),
+            Some(found.entry.at),
+        );
+    }
+    hits.hits
+}
+
+/// Returns the character index of `needle` (lowercase) in `line`, ignoring
+/// case.
+fn find(line: &str, needle: &str) -> Option<usize> {
+    let lower = line.to_lowercase();
+    let at = lower.find(needle)?;
+    Some(lower[..at].chars().count())
+}
+
+/// Cuts `line` to `MAX_LINE_CHARS`, keeping the character at `keep` in
+/// view.
+fn clip(line: &str, keep: usize) -> String {
+    let chars = line.chars().count();
+    if chars <= MAX_LINE_CHARS {
+        return line.to_string();
+    }
+    let start = keep.saturating_sub(MAX_LINE_CHARS / 3).min(chars - MAX_LINE_CHARS);
+    let mut clipped: String = line.chars().skip(start).take(MAX_LINE_CHARS).collect();
+    if start > 0 {
+        clipped.insert(0, '…');
+    }
+    if start + MAX_LINE_CHARS < chars {
+        clipped.push('…');
+    }
+    clipped
+}
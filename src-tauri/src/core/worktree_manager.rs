@@ -1,27 +1,66 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use specta::Type;
+use tokio::task::JoinSet;
 
-use crate::git::{Git, GitError, WorktreeInfo};
+use super::clock;
+use super::merge_gate::MergeGateReport;
+use super::session_lock::OperationLock;
+use super::storage;
+use super::worktree_registry::{WorktreeMetadata, WorktreeRegistry};
+use crate::git::archive::ARCHIVE_REF_PREFIX;
+use crate::git::{
+    CommitInfo, DiffSummary, Git, GitError, GitProgress, MergeStrategy, PushResult, WorktreeArchive,
+    WorktreeInfo,
+};
 
-fn worktree_base_dir() -> PathBuf {
-    directories::ProjectDirs::from("com", "maestro", "maestro")
-        .map(|p| p.data_dir().to_path_buf())
-        .unwrap_or_else(|| {
-            dirs_fallback()
-        })
-        .join("worktrees")
+/// Managed worktree directory chosen in the settings, if any.
+static WORKTREE_BASE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Directory holding every managed worktree, one subdirectory per repo:
+/// the one set by `set_worktree_base_dir`, else `<data dir>/worktrees`.
+pub fn worktree_base_dir() -> PathBuf {
+    WORKTREE_BASE_DIR
+        .get()
+        .cloned()
+        .unwrap_or_else(|| storage::data_dir().join("worktrees"))
+}
+
+/// Moves the managed worktree directory for the rest of the process. Only
+/// the first call has an effect, so it must come before any worktree is
+/// created.
+pub fn set_worktree_base_dir(dir: PathBuf) {
+    if WORKTREE_BASE_DIR.set(dir.clone()).is_err() {
+        log::warn!("Worktree base dir already set; ignoring {}", dir.display());
+    }
+}
+
+/// Returns whether the managed worktree directory in use holds any
+/// worktree.
+pub fn has_managed_worktrees() -> bool {
+    let Ok(repos) = std::fs::read_dir(worktree_base_dir()) else {
+        return false;
+    };
+    repos
+        .flatten()
+        .any(|repo| std::fs::read_dir(repo.path()).is_ok_and(|mut worktrees| worktrees.next().is_some()))
 }
 
-/// Fallback if ProjectDirs fails (e.g., no HOME set).
-/// This GUI app assumes a user session on a desktop environment where HOME is set.
-/// Panicking here is intentional to fail fast in headless/container/systemd scenarios.
-fn dirs_fallback() -> PathBuf {
-    std::env::var("HOME")
-        .map(PathBuf::from)
-        .map(|p| p.join(".local").join("share").join("maestro"))
-        .expect("HOME environment variable must be set for worktree management")
+/// Moves a directory to the freedesktop trash.
+async fn move_to_trash(path: &Path) -> Result<(), GitError> {
+    let target = path.to_path_buf();
+    let result = tokio::task::spawn_blocking(move || trash::delete(&target))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result.map_err(|e| e.to_string()));
+    result.map_err(|message| GitError::TrashFailed {
+        path: path.display().to_string(),
+        message,
+    })
 }
 
 /// Produces a 16-hex-char SHA-256 digest of the canonicalized repo path.
@@ -53,13 +92,178 @@ fn sanitize_branch(branch: &str) -> String {
     sanitized
 }
 
+/// Glanceable status of a single managed worktree, returned by `status_summary`.
+///
+/// `ahead`/`behind` are `None` when the branch has no upstream (or HEAD is
+/// detached). If any status check fails, `error` carries the message and the
+/// remaining fields hold whatever could be gathered, so one broken worktree
+/// does not hide the rest of the summary.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WorktreeStatus {
+    pub path: String,
+    pub branch: Option<String>,
+    pub head: String,
+    pub dirty_count: usize,
+    pub ahead: Option<usize>,
+    pub behind: Option<usize>,
+    pub last_commit: Option<CommitInfo>,
+    pub error: Option<String>,
+}
+
+impl WorktreeStatus {
+    /// Runs the checks of `collect` for any checkout, managed or not.
+    pub async fn of_checkout(path: &Path) -> Self {
+        let git = Git::new(path);
+        let head = git.run(&["rev-parse", "HEAD"]).await.map(|out| out.trimmed().to_string());
+        let branch = git
+            .run(&["symbolic-ref", "--short", "-q", "HEAD"])
+            .await
+            .ok()
+            .map(|out| out.trimmed().to_string());
+        let mut status = Self::collect(WorktreeInfo {
+            path: path.to_string_lossy().into_owned(),
+            head: head.as_ref().cloned().unwrap_or_default(),
+            branch,
+            is_bare: false,
+        })
+        .await;
+        if let Err(e) = head {
+            status.error.get_or_insert(e.to_string());
+        }
+        status
+    }
+
+    /// Runs the dirty-count, ahead/behind, and last-commit checks for one worktree.
+    async fn collect(wt: WorktreeInfo) -> Self {
+        let git = Git::new(&wt.path);
+        let mut status = WorktreeStatus {
+            path: wt.path,
+            branch: wt.branch,
+            head: wt.head,
+            dirty_count: 0,
+            ahead: None,
+            behind: None,
+            last_commit: None,
+            error: None,
+        };
+
+        let result: Result<(), GitError> = async {
+            status.dirty_count = git.uncommitted_count().await?;
+            if let Some((ahead, behind)) = git.ahead_behind().await? {
+                status.ahead = Some(ahead);
+                status.behind = Some(behind);
+            }
+            status.last_commit = git.commit_log(1, false).await?.into_iter().next();
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            status.error = Some(e.to_string());
+        }
+        status
+    }
+}
+
+/// Outcome of `integrate`: which branch landed where, and the resulting HEAD
+/// of the target branch in the main checkout. `cleanup_error` says why a
+/// requested cleanup did not finish. `gate` is the merge gate the merge
+/// passed, if one ran (see `merge_gate`).
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct IntegrateResult {
+    pub branch: String,
+    pub target_branch: String,
+    pub strategy: MergeStrategy,
+    pub head: String,
+    pub cleaned_up: bool,
+    pub cleanup_error: Option<String>,
+    pub gate: Option<MergeGateReport>,
+}
+
+/// Upper bound on the numeric suffix `create_unique` will try before giving up.
+const MAX_UNIQUE_SUFFIX: u32 = 100;
+
+/// A worktree created by `create_at`: `commit` is the full hash checked
+/// out, and `branch` the new branch, or `None` for a detached HEAD.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CommitWorktree {
+    pub path: String,
+    pub commit: String,
+    pub branch: Option<String>,
+}
+
+/// Branch and path of a worktree created by `create_unique`. `branch` differs
+/// from the requested name when a suffixed branch had to be created.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct UniqueWorktree {
+    pub branch: String,
+    pub path: String,
+}
+
+/// How `remove_bulk` treats worktrees with uncommitted changes.
+///
+/// `Force` removes everything as-is, `Archive` snapshots dirty worktrees
+/// before removing them, and `SkipDirty` leaves dirty worktrees in place and
+/// reports them as skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum RemovePolicy {
+    Force,
+    Archive,
+    SkipDirty,
+}
+
+/// Per-worktree outcome of `remove_bulk`. Exactly one of `removed`,
+/// `skipped`, or `error` describes what happened.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BulkRemoveResult {
+    pub path: String,
+    pub removed: bool,
+    pub skipped: bool,
+    pub archive: Option<WorktreeArchive>,
+    pub error: Option<String>,
+}
+
+/// What `prune` removed from a repository, or with `dry_run` would have.
+/// `stale_refs` are git's descriptions of the worktree entries it pruned,
+/// `orphaned_dirs` the directories in the managed directory that belonged
+/// to no worktree, and `failed` those of them that could not be deleted.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct PruneOutcome {
+    pub dry_run: bool,
+    pub stale_refs: Vec<String>,
+    pub orphaned_dirs: Vec<String>,
+    pub failed: Vec<PruneFailure>,
+}
+
+/// An orphaned directory `prune` failed to delete.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PruneFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// A managed worktree provisioned for a pull/merge request by `create_from_pr`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PrWorktree {
+    pub pr_number: u64,
+    pub branch: String,
+    pub path: String,
+}
+
 /// Manages Maestro-owned git worktrees under a deterministic, repo-specific
 /// directory inside XDG data dirs.
 ///
 /// Worktree paths are derived from a SHA-256 hash of the canonical repo path
 /// (truncated to 16 hex chars) so that different repos never collide, and a
 /// sanitized branch name so each branch gets its own subdirectory.
-pub struct WorktreeManager;
+///
+/// State kept: the set of repositories seen this run (used by background
+/// maintenance to know which repos to prune) and the persisted metadata
+/// registry recording each worktree's provenance.
+pub struct WorktreeManager {
+    known_repos: Mutex<HashSet<PathBuf>>,
+    registry: WorktreeRegistry,
+}
 
 impl Default for WorktreeManager {
     fn default() -> Self {
@@ -68,10 +272,94 @@ impl Default for WorktreeManager {
 }
 
 impl WorktreeManager {
-    /// Creates a new stateless manager. All path computation is pure and
-    /// deterministic from the repo path and branch name.
+    /// Creates a new manager and loads the worktree metadata registry. All
+    /// path computation is pure and deterministic from the repo path and
+    /// branch name.
     pub fn new() -> Self {
-        Self
+        Self {
+            known_repos: Mutex::new(HashSet::new()),
+            registry: WorktreeRegistry::load(),
+        }
+    }
+
+    /// Returns registry metadata (creating session, base ref, age, last
+    /// activity) for the repo's managed worktrees, dropping entries whose
+    /// directories have disappeared.
+    pub fn metadata(&self, repo_path: &Path) -> Vec<WorktreeMetadata> {
+        self.registry.forget_missing(repo_path);
+        self.registry.for_repo(repo_path)
+    }
+
+    /// Records which session owns a managed worktree. Returns `false` if the
+    /// path is not a registered worktree.
+    pub fn link_session(&self, wt_path: &Path, session_id: u32) -> bool {
+        self.registry.link_session(wt_path, session_id)
+    }
+
+    /// Returns the ref a managed worktree's branch was created from, if it
+    /// was created from one.
+    pub fn base_ref_of(&self, wt_path: &Path) -> Option<String> {
+        self.registry.get(wt_path)?.base_ref
+    }
+
+    /// Returns the main repository a worktree belongs to, from the registry
+    /// or, for worktrees Maestro did not create, git's common dir.
+    pub async fn repo_of(&self, wt_path: &Path) -> Option<PathBuf> {
+        if let Some(meta) = self.registry.get(wt_path) {
+            return Some(PathBuf::from(meta.repo_path));
+        }
+        let output = Git::new(wt_path)
+            .run(&["rev-parse", "--path-format=absolute", "--git-common-dir"])
+            .await
+            .ok()?;
+        Path::new(output.trimmed()).parent().map(Path::to_path_buf)
+    }
+
+    /// Records a repository as known so scheduled maintenance covers it.
+    fn remember_repo(&self, repo_path: &Path) {
+        match self.known_repos.lock() {
+            Ok(mut repos) => {
+                repos.insert(repo_path.to_path_buf());
+            }
+            Err(e) => log::warn!("Known-repos lock poisoned: {e}"),
+        }
+    }
+
+    /// Returns every repository with managed worktrees: those seen during this
+    /// run plus those discovered on disk.
+    ///
+    /// Discovery asks each subdirectory of the managed base dir for its
+    /// `--git-common-dir` and takes that directory's parent as the repo, so
+    /// repos are found again after a restart. Broken worktrees that git no
+    /// longer recognizes are skipped (pruning their repo removes them).
+    pub async fn known_repos(&self) -> Vec<PathBuf> {
+        let mut repos: HashSet<PathBuf> = match self.known_repos.lock() {
+            Ok(repos) => repos.clone(),
+            Err(_) => HashSet::new(),
+        };
+
+        if let Ok(mut hash_dirs) = tokio::fs::read_dir(worktree_base_dir()).await {
+            while let Ok(Some(hash_dir)) = hash_dirs.next_entry().await {
+                let Ok(mut worktrees) = tokio::fs::read_dir(hash_dir.path()).await else {
+                    continue;
+                };
+                while let Ok(Some(wt)) = worktrees.next_entry().await {
+                    let common_dir = Git::new(wt.path())
+                        .run(&["rev-parse", "--path-format=absolute", "--git-common-dir"])
+                        .await;
+                    if let Ok(output) = common_dir {
+                        if let Some(repo) = Path::new(output.trimmed()).parent() {
+                            repos.insert(repo.to_path_buf());
+                            break; // one worktree is enough to identify the repo
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut repos: Vec<PathBuf> = repos.into_iter().collect();
+        repos.sort();
+        repos
     }
 
     /// Compute the worktree path for a given repo + branch
@@ -81,17 +369,73 @@ impl WorktreeManager {
         worktree_base_dir().join(hash).join(sanitized)
     }
 
+    /// Canonicalizes `path` and verifies it lies strictly inside this repo's
+    /// managed directory (`worktree_base_dir()/<repo hash>`).
+    ///
+    /// Every force-removal goes through this guard so a bad path from the
+    /// frontend, a symlink, or a `..` component can never make Maestro delete
+    /// a directory it does not own. Returns `UnmanagedPath` otherwise.
+    pub async fn ensure_managed(&self, repo_path: &Path, path: &Path) -> Result<PathBuf, GitError> {
+        let unmanaged = || GitError::UnmanagedPath {
+            path: path.display().to_string(),
+        };
+
+        let managed_dir = worktree_base_dir().join(repo_hash(repo_path).await);
+        let managed_root = tokio::fs::canonicalize(&managed_dir)
+            .await
+            .map_err(|_| unmanaged())?;
+        let canonical = tokio::fs::canonicalize(path).await.map_err(|_| unmanaged())?;
+
+        if canonical != managed_root && canonical.starts_with(&managed_root) {
+            Ok(canonical)
+        } else {
+            Err(unmanaged())
+        }
+    }
+
     /// Creates a worktree for the given branch, returning its path on disk.
     ///
     /// Checks that the branch is not already checked out in another worktree
     /// before creating (returns `BranchAlreadyCheckedOut` if so). Parent
-    /// directories are created automatically. The worktree checks out the
-    /// existing branch -- no new branch is created.
+    /// directories are created automatically. Without `base_ref` the worktree
+    /// checks out the existing branch; with `base_ref` a new branch named
+    /// `branch` is created from it (`worktree add -b`), e.g. a fresh session
+    /// branch cut from `origin/main`.
     pub async fn create(
         &self,
         branch: &str,
+        base_ref: Option<&str>,
         repo_path: &Path,
     ) -> Result<PathBuf, GitError> {
+        self.create_with_progress(branch, base_ref, repo_path, |_| {}).await
+    }
+
+    /// Like `create`, reporting the progress of the checkout to
+    /// `on_progress`; see `Git::worktree_add_with_progress`. Neither has the
+    /// usual git timeout, since the checkout of thousands of files in a
+    /// large repository takes a while.
+    pub async fn create_with_progress(
+        &self,
+        branch: &str,
+        base_ref: Option<&str>,
+        repo_path: &Path,
+        on_progress: impl FnMut(GitProgress) + Send,
+    ) -> Result<PathBuf, GitError> {
+        let wt_path = self.prepare_create(branch, repo_path).await?;
+        let git = Git::new(repo_path);
+        match base_ref {
+            Some(base) => git.worktree_add_with_progress(&wt_path, Some(branch), Some(base), on_progress).await?,
+            None => git.worktree_add_with_progress(&wt_path, None, Some(branch), on_progress).await?,
+        };
+        self.registry.register(&wt_path, repo_path, Some(branch), base_ref);
+
+        Ok(wt_path)
+    }
+
+    /// Checks that `branch` is free to check out and creates the parent
+    /// directories of its worktree, returning the worktree path.
+    async fn prepare_create(&self, branch: &str, repo_path: &Path) -> Result<PathBuf, GitError> {
+        self.remember_repo(repo_path);
         let git = Git::new(repo_path);
 
         // Check if branch is already checked out in another worktree
@@ -116,30 +460,390 @@ impl WorktreeManager {
                 command: format!("create_dir_all {:?}", parent),
             })?;
         }
+        Ok(wt_path)
+    }
 
-        git.worktree_add(&wt_path, None, Some(branch)).await?;
+    /// Pushes a worktree's branch to `remote` and sets it as the upstream.
+    ///
+    /// Rejections come back in the result (`rejected` + git's summary) so
+    /// the UI can show why, e.g. a non-fast-forward after a rebase, which
+    /// `force_with_lease` pushes over; see `Git::push_branch`.
+    pub async fn publish(
+        &self,
+        repo_path: &Path,
+        wt_path: &Path,
+        remote: &str,
+        force_with_lease: bool,
+    ) -> Result<PushResult, GitError> {
+        let wt = self.find_worktree(repo_path, wt_path).await?;
+        let branch = wt.branch.ok_or_else(|| GitError::DetachedHead {
+            path: wt.path.clone(),
+        })?;
 
-        Ok(wt_path)
+        let result = Git::new(&wt.path).push_branch(remote, &branch, force_with_lease).await?;
+        self.registry.touch(Path::new(&wt.path), clock::now_secs());
+        if result.rejected {
+            log::warn!("Push of {branch} to {remote} rejected: {}", result.summary);
+        }
+        Ok(result)
+    }
+
+    /// Renames a managed worktree's branch to `new_branch` and moves its
+    /// directory to the matching sanitized path, returning the new path.
+    ///
+    /// The branch is renamed first; if moving the directory then fails, the
+    /// rename is rolled back so branch and directory names stay in sync.
+    /// `git worktree move` updates the gitdir links, and `worktree repair`
+    /// runs afterwards as a safety net.
+    pub async fn rename(&self, repo_path: &Path, wt_path: &Path, new_branch: &str) -> Result<PathBuf, GitError> {
+        let old_path = self.ensure_managed(repo_path, wt_path).await?;
+        let wt = self.find_worktree(repo_path, &old_path).await?;
+        let old_branch = wt.branch.ok_or_else(|| GitError::DetachedHead {
+            path: wt.path.clone(),
+        })?;
+
+        let new_path = self.worktree_path(repo_path, new_branch).await;
+        if tokio::fs::try_exists(&new_path).await.unwrap_or(false) {
+            return Err(GitError::BranchAlreadyCheckedOut {
+                branch: new_branch.to_string(),
+                path: new_path.display().to_string(),
+            });
+        }
+
+        let git = Git::new(repo_path);
+        git.rename_branch(&old_branch, new_branch).await?;
+        if let Err(e) = git.worktree_move(&old_path, &new_path).await {
+            if let Err(undo) = git.rename_branch(new_branch, &old_branch).await {
+                log::error!("Failed to roll back branch rename {new_branch} -> {old_branch}: {undo}");
+            }
+            return Err(e);
+        }
+        if let Err(e) = git.worktree_repair(&new_path).await {
+            log::warn!("worktree repair after move to {} failed: {e}", new_path.display());
+        }
+
+        self.registry.rename(&old_path, &new_path, new_branch);
+        log::info!("Renamed worktree {old_branch} -> {new_branch} ({})", new_path.display());
+        Ok(new_path)
+    }
+
+    /// Creates a worktree for `branch_base`, or for a fresh suffixed branch if
+    /// `branch_base` is already checked out elsewhere.
+    ///
+    /// When the base is free this behaves exactly like `create`. Otherwise it
+    /// tries `<base>-2`, `<base>-3`, ... and uses the first name that is
+    /// neither an existing branch nor an existing managed directory, creating
+    /// that branch from `branch_base`'s tip. This lets several agents start on
+    /// the same feature without colliding. The checkout's progress goes to
+    /// `on_progress`, as in `create_with_progress`. Returns `NamesExhausted`
+    /// if every suffix up to `MAX_UNIQUE_SUFFIX` is taken.
+    pub async fn create_unique(
+        &self,
+        branch_base: &str,
+        repo_path: &Path,
+        mut on_progress: impl FnMut(GitProgress) + Send,
+    ) -> Result<UniqueWorktree, GitError> {
+        match self.create_with_progress(branch_base, None, repo_path, &mut on_progress).await {
+            Ok(path) => {
+                return Ok(UniqueWorktree {
+                    branch: branch_base.to_string(),
+                    path: path.to_string_lossy().into_owned(),
+                })
+            }
+            Err(GitError::BranchAlreadyCheckedOut { .. }) => {}
+            Err(e) => return Err(e),
+        }
+
+        let git = Git::new(repo_path);
+        for n in 2..=MAX_UNIQUE_SUFFIX {
+            let candidate = format!("{branch_base}-{n}");
+            if git.branch_exists(&candidate).await? {
+                continue;
+            }
+            let wt_path = self.worktree_path(repo_path, &candidate).await;
+            if tokio::fs::try_exists(&wt_path).await.unwrap_or(false) {
+                continue;
+            }
+
+            let wt_path = self
+                .create_with_progress(&candidate, Some(branch_base), repo_path, &mut on_progress)
+                .await?;
+
+            return Ok(UniqueWorktree {
+                branch: candidate,
+                path: wt_path.to_string_lossy().into_owned(),
+            });
+        }
+
+        Err(GitError::NamesExhausted {
+            name: branch_base.to_string(),
+            max: MAX_UNIQUE_SUFFIX,
+        })
+    }
+
+    /// Creates a worktree at `commit_ish` (a commit hash, tag, or any other
+    /// revision), e.g. to reproduce a bug at `v1.4.2` in its own session.
+    ///
+    /// With `branch`, a new branch of that name is created from the commit,
+    /// as `create` does. Without, the worktree is left on a detached HEAD in
+    /// an `at-<commit_ish>` directory, suffixed `-2`, `-3`, ... if that is
+    /// taken (`NamesExhausted` if all of them are). Returns `UnknownRevision`
+    /// if `commit_ish` names no commit.
+    pub async fn create_at(
+        &self,
+        repo_path: &Path,
+        commit_ish: &str,
+        branch: Option<&str>,
+    ) -> Result<CommitWorktree, GitError> {
+        let git = Git::new(repo_path);
+        let commit = git.resolve_commit(commit_ish).await?;
+        if let Some(branch) = branch {
+            let path = self.create(branch, Some(commit_ish), repo_path).await?;
+            return Ok(CommitWorktree {
+                path: path.to_string_lossy().into_owned(),
+                commit,
+                branch: Some(branch.to_string()),
+            });
+        }
+
+        self.remember_repo(repo_path);
+        let name = format!("at-{commit_ish}");
+        let mut wt_path = self.worktree_path(repo_path, &name).await;
+        let mut n = 1;
+        while tokio::fs::try_exists(&wt_path).await.unwrap_or(false) {
+            n += 1;
+            if n > MAX_UNIQUE_SUFFIX {
+                return Err(GitError::NamesExhausted { name, max: MAX_UNIQUE_SUFFIX });
+            }
+            wt_path = self.worktree_path(repo_path, &format!("{name}-{n}")).await;
+        }
+        if let Some(parent) = wt_path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| GitError::SpawnError {
+                source: e,
+                command: format!("create_dir_all {:?}", parent),
+            })?;
+        }
+
+        git.worktree_add(&wt_path, None, Some(&commit)).await?;
+        self.registry.register(&wt_path, repo_path, None, Some(commit_ish));
+        log::info!("Created detached worktree at {commit_ish} ({})", wt_path.display());
+        Ok(CommitWorktree {
+            path: wt_path.to_string_lossy().into_owned(),
+            commit,
+            branch: None,
+        })
+    }
+
+    /// Fetches PR/MR `pr_number` from `origin` into a local `pr-<n>` branch and
+    /// provisions a managed worktree for it.
+    ///
+    /// Returns `BranchAlreadyCheckedOut` if the PR branch already has a
+    /// worktree, so repeated clicks do not fetch over a branch being reviewed.
+    pub async fn create_from_pr(&self, repo_path: &Path, pr_number: u64) -> Result<PrWorktree, GitError> {
+        let branch = format!("pr-{pr_number}");
+        let git = Git::new(repo_path);
+
+        if let Some(wt) = git
+            .worktree_list()
+            .await?
+            .into_iter()
+            .find(|wt| wt.branch.as_deref() == Some(branch.as_str()))
+        {
+            return Err(GitError::BranchAlreadyCheckedOut { branch, path: wt.path });
+        }
+
+        git.fetch_pull_request("origin", pr_number, &branch).await?;
+        let path = self.create(&branch, None, repo_path).await?;
+
+        Ok(PrWorktree {
+            pr_number,
+            branch,
+            path: path.to_string_lossy().into_owned(),
+        })
     }
 
     /// Force-removes a worktree and prunes its git ref, then attempts to
     /// clean up the empty parent directory (silently ignored if non-empty).
-    pub async fn remove(&self, repo_path: &Path, wt_path: &Path) -> Result<(), GitError> {
+    /// Only Maestro-managed worktrees can be removed; any other path returns
+    /// `UnmanagedPath` before anything is touched.
+    ///
+    /// Unless `permanent`, the directory is moved to the freedesktop trash
+    /// instead of being deleted, so its files can be restored from there
+    /// (the branch is kept either way); if that fails, nothing is removed.
+    ///
+    /// With `archive`, uncommitted and untracked changes are first snapshotted
+    /// via `archive` and the removal is aborted if the snapshot fails, so
+    /// agent work can never be destroyed without a recoverable copy. Returns
+    /// the snapshot, or `None` if archiving was off or the tree was clean.
+    pub async fn remove(
+        &self,
+        repo_path: &Path,
+        wt_path: &Path,
+        archive: bool,
+        permanent: bool,
+    ) -> Result<Option<WorktreeArchive>, GitError> {
+        let wt_path = self.ensure_managed(repo_path, wt_path).await?;
+
+        let snapshot = if archive {
+            self.archive(repo_path, &wt_path).await?
+        } else {
+            None
+        };
+
         let git = Git::new(repo_path);
-        git.worktree_remove(wt_path, true).await?;
+        if permanent {
+            git.worktree_remove(&wt_path, true).await?;
+        } else {
+            // git then prunes the worktree like any other whose directory
+            // went missing
+            move_to_trash(&wt_path).await?;
+        }
         git.worktree_prune().await?;
+        self.registry.forget(&wt_path);
 
         // Clean up empty parent directories
         if let Some(parent) = wt_path.parent() {
             let _ = tokio::fs::remove_dir(parent).await; // only succeeds if empty
         }
 
-        Ok(())
+        Ok(snapshot)
+    }
+
+    /// Removes several managed worktrees according to `policy`, reporting the
+    /// outcome of each one instead of stopping at the first failure.
+    ///
+    /// Removals run sequentially: git serializes worktree administration
+    /// through lock files in the shared `.git` dir, so parallel removals would
+    /// only race for those locks.
+    pub async fn remove_bulk(
+        &self,
+        repo_path: &Path,
+        paths: &[PathBuf],
+        policy: RemovePolicy,
+        permanent: bool,
+    ) -> Vec<BulkRemoveResult> {
+        let mut results = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let mut result = BulkRemoveResult {
+                path: path.display().to_string(),
+                removed: false,
+                skipped: false,
+                archive: None,
+                error: None,
+            };
+
+            if policy == RemovePolicy::SkipDirty {
+                match Git::new(path).uncommitted_count().await {
+                    Ok(0) => {}
+                    Ok(_) => {
+                        result.skipped = true;
+                        results.push(result);
+                        continue;
+                    }
+                    Err(e) => {
+                        result.error = Some(e.to_string());
+                        results.push(result);
+                        continue;
+                    }
+                }
+            }
+
+            match self.remove(repo_path, path, policy == RemovePolicy::Archive, permanent).await {
+                Ok(archive) => {
+                    result.removed = true;
+                    result.archive = archive;
+                }
+                Err(e) => result.error = Some(e.to_string()),
+            }
+            results.push(result);
+        }
+
+        results
+    }
+
+    /// Snapshots a worktree's uncommitted changes (including untracked files)
+    /// to `refs/maestro/archives/<branch>/<millis>` in the shared repository.
+    ///
+    /// Returns `None` without creating a ref if the worktree is clean, since
+    /// committed work stays reachable from its branch.
+    pub async fn archive(
+        &self,
+        repo_path: &Path,
+        wt_path: &Path,
+    ) -> Result<Option<WorktreeArchive>, GitError> {
+        let wt = self.find_worktree(repo_path, wt_path).await?;
+        let git = Git::new(&wt.path);
+        if git.uncommitted_count().await? == 0 {
+            return Ok(None);
+        }
+
+        let millis = clock::now_millis();
+        let label = sanitize_branch(wt.branch.as_deref().unwrap_or("detached"));
+        let ref_name = format!("{ARCHIVE_REF_PREFIX}{label}/{millis}");
+
+        let archive = git.snapshot_working_tree(&ref_name, wt.branch.as_deref()).await?;
+        log::info!("Archived worktree {} to {}", wt.path, archive.ref_name);
+        Ok(Some(archive))
+    }
+
+    /// Lists all worktree snapshots for the repo, newest first.
+    pub async fn list_archives(&self, repo_path: &Path) -> Result<Vec<WorktreeArchive>, GitError> {
+        Git::new(repo_path).list_archives().await
+    }
+
+    /// Restores a snapshot into a worktree for its original branch.
+    ///
+    /// Reuses the branch's existing worktree if there is one, otherwise
+    /// provisions a managed worktree (recreating the branch at the snapshot's
+    /// parent commit if it was deleted). The snapshot tree is checked out over
+    /// the working tree and the index reset, leaving the archived changes as
+    /// uncommitted modifications. Files deleted before archiving are not
+    /// deleted again. The target worktree must be clean.
+    pub async fn restore_archive(&self, repo_path: &Path, ref_name: &str) -> Result<PathBuf, GitError> {
+        let git = Git::new(repo_path);
+        let archive = git
+            .list_archives()
+            .await?
+            .into_iter()
+            .find(|a| a.ref_name == ref_name || a.commit == ref_name)
+            .ok_or_else(|| GitError::ArchiveNotFound(ref_name.to_string()))?;
+        let branch = archive.branch.clone().ok_or_else(|| GitError::DetachedHead {
+            path: archive.worktree_path.clone(),
+        })?;
+
+        let existing = git
+            .worktree_list()
+            .await?
+            .into_iter()
+            .find(|wt| wt.branch.as_deref() == Some(branch.as_str()));
+        let wt_path = match existing {
+            Some(wt) => PathBuf::from(wt.path),
+            None => {
+                if !git.branch_exists(&branch).await? {
+                    git.run(&["branch", &branch, &archive.parent]).await?;
+                }
+                self.create(&branch, None, repo_path).await?
+            }
+        };
+
+        let wt_git = Git::new(&wt_path);
+        if wt_git.uncommitted_count().await? > 0 {
+            return Err(GitError::DirtyWorkingTree {
+                path: wt_path.display().to_string(),
+            });
+        }
+        wt_git.run(&["checkout", &archive.commit, "--", "."]).await?;
+        wt_git.run(&["reset", "--quiet"]).await?;
+
+        log::info!("Restored {} into {}", archive.ref_name, wt_path.display());
+        Ok(wt_path)
     }
 
     /// Lists only worktrees that live under Maestro's managed base directory,
     /// filtering out the main worktree and any manually created worktrees.
     pub async fn list_managed(&self, repo_path: &Path) -> Result<Vec<WorktreeInfo>, GitError> {
+        self.remember_repo(repo_path);
         let git = Git::new(repo_path);
         let all = git.worktree_list().await?;
 
@@ -151,15 +855,182 @@ impl WorktreeManager {
             .collect())
     }
 
-    /// Prunes stale git worktree refs and removes orphaned directories.
+    /// Looks up the repo's worktree at `wt_path`, comparing canonicalized paths.
+    ///
+    /// Returns `WorktreeNotFound` if git does not list a worktree at that
+    /// location, which guards commands against being pointed at arbitrary dirs.
+    pub async fn find_worktree(&self, repo_path: &Path, wt_path: &Path) -> Result<WorktreeInfo, GitError> {
+        let target = tokio::fs::canonicalize(wt_path)
+            .await
+            .unwrap_or_else(|_| wt_path.to_path_buf());
+
+        for wt in Git::new(repo_path).worktree_list().await? {
+            let candidate = Path::new(&wt.path);
+            let canonical = tokio::fs::canonicalize(candidate)
+                .await
+                .unwrap_or_else(|_| candidate.to_path_buf());
+            if canonical == target {
+                return Ok(wt);
+            }
+        }
+
+        Err(GitError::WorktreeNotFound(wt_path.display().to_string()))
+    }
+
+    /// Diffs a worktree (committed, uncommitted, and untracked changes) against
+    /// the point where its branch diverged from `base_ref`.
+    pub async fn diff(
+        &self,
+        repo_path: &Path,
+        wt_path: &Path,
+        base_ref: &str,
+    ) -> Result<DiffSummary, GitError> {
+        let wt = self.find_worktree(repo_path, wt_path).await?;
+        self.registry.touch(Path::new(&wt.path), clock::now_secs());
+        Git::new(&wt.path).diff_against(base_ref).await
+    }
+
+    /// Merges a worktree's branch into the branch checked out in the main
+    /// checkout at `repo_path`.
+    ///
+    /// Preflight checks refuse to run when the worktree is detached, when
+    /// either checkout has uncommitted changes, or when the worktree branch is
+    /// the target itself. Conflicts are detected up front with `merge-tree`
+    /// and returned as `MergeConflict` before the main checkout is touched.
+    /// With `cleanup`, the worktree is removed and its (now merged) branch
+    /// deleted with `branch -d` afterwards; cleanup failures are logged rather
+    /// than failing the already-completed merge. Callers lock both checkouts
+    /// for the whole call (see `session_lock::OperationLock`), which covers
+    /// the cleanup.
+    ///
+    /// The branch tip is resolved once and exactly that commit is merged.
+    /// With `expected_head` (e.g. the commit the merge gate checked), fails
+    /// with `BranchMoved` if the branch no longer points at it.
+    pub async fn integrate(
+        &self,
+        repo_path: &Path,
+        wt_path: &Path,
+        strategy: MergeStrategy,
+        cleanup: bool,
+        expected_head: Option<&str>,
+    ) -> Result<IntegrateResult, GitError> {
+        let wt = self.find_worktree(repo_path, wt_path).await?;
+        let branch = wt.branch.clone().ok_or_else(|| GitError::DetachedHead {
+            path: wt.path.clone(),
+        })?;
+
+        let main = Git::new(repo_path);
+        let target_branch = main.current_branch().await?;
+        if target_branch == branch {
+            return Err(GitError::BranchAlreadyCheckedOut {
+                branch,
+                path: repo_path.display().to_string(),
+            });
+        }
+
+        if Git::new(&wt.path).uncommitted_count().await? > 0 {
+            return Err(GitError::DirtyWorkingTree { path: wt.path });
+        }
+        if main.uncommitted_count().await? > 0 {
+            return Err(GitError::DirtyWorkingTree {
+                path: repo_path.display().to_string(),
+            });
+        }
+
+        let tip = main.resolve_commit(&branch).await?;
+        if let Some(expected) = expected_head.filter(|expected| *expected != tip) {
+            return Err(GitError::BranchMoved {
+                branch,
+                expected: expected.to_string(),
+                actual: tip,
+            });
+        }
+
+        let preview = main.merge_preview(&tip, &target_branch).await?;
+        if preview.conflicts {
+            return Err(GitError::MergeConflict { files: preview.files });
+        }
+
+        let head = main.merge_branch(&branch, &tip, strategy).await?;
+        log::info!("Integrated {branch} into {target_branch} ({strategy:?}) at {head}");
+
+        let mut cleaned_up = false;
+        let mut cleanup_error = None;
+        if cleanup {
+            match self.remove(repo_path, Path::new(&wt.path), false, true).await {
+                Ok(_) => match main.delete_branch(&branch, false).await {
+                    Ok(()) => cleaned_up = true,
+                    Err(e) => cleanup_error = Some(format!("Failed to delete merged branch {branch}: {e}")),
+                },
+                Err(e) => cleanup_error = Some(format!("Failed to remove integrated worktree {}: {e}", wt.path)),
+            }
+        }
+        if let Some(ref e) = cleanup_error {
+            log::warn!("{e}");
+        }
+
+        Ok(IntegrateResult {
+            branch,
+            target_branch,
+            strategy,
+            head,
+            cleaned_up,
+            cleanup_error,
+            gate: None,
+        })
+    }
+
+    /// Collects dirty counts, ahead/behind, and last-commit info for every
+    /// managed worktree of the repo.
+    ///
+    /// Each worktree is checked on its own tokio task so the summary takes
+    /// roughly as long as the slowest worktree rather than the sum of all of
+    /// them. Results are sorted by path for a stable display order.
+    pub async fn status_summary(&self, repo_path: &Path) -> Result<Vec<WorktreeStatus>, GitError> {
+        let managed = self.list_managed(repo_path).await?;
+
+        let mut tasks = JoinSet::new();
+        for wt in managed {
+            tasks.spawn(WorktreeStatus::collect(wt));
+        }
+
+        let mut statuses = Vec::with_capacity(tasks.len());
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(status) => {
+                    if let Some(ref commit) = status.last_commit {
+                        self.registry.touch(Path::new(&status.path), commit.timestamp);
+                    }
+                    statuses.push(status);
+                }
+                Err(e) => log::warn!("Worktree status task failed: {e}"),
+            }
+        }
+        statuses.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(statuses)
+    }
+
+    /// Prunes stale git worktree refs and removes orphaned directories,
+    /// returning what was removed; with `dry_run`, only reports what would
+    /// be.
     ///
     /// First runs `git worktree prune`, then scans the managed directory for
     /// subdirectories that are no longer in git's worktree list. Orphaned
-    /// directories are deleted with `remove_dir_all`. No-ops gracefully if
-    /// the managed directory does not exist yet.
-    pub async fn prune(&self, repo_path: &Path) -> Result<(), GitError> {
+    /// directories are deleted with `remove_dir_all`, in parallel on blocking
+    /// threads, after passing the same managed-path guard as `remove`
+    /// (entries resolving elsewhere, e.g. via symlinks, are skipped).
+    /// Each is locked while it is deleted (see `session_lock::OperationLock`);
+    /// one another operation holds is left and reported in `failed`.
+    /// No-ops gracefully if the managed directory does not exist yet.
+    pub async fn prune(&self, repo_path: &Path, dry_run: bool) -> Result<PruneOutcome, GitError> {
+        self.remember_repo(repo_path);
         let git = Git::new(repo_path);
-        git.worktree_prune().await?;
+        let mut outcome = PruneOutcome {
+            dry_run,
+            stale_refs: git.worktree_prune_verbose(dry_run).await?,
+            ..PruneOutcome::default()
+        };
 
         // Scan managed directory for orphans not in git worktree list
         let hash = repo_hash(repo_path).await;
@@ -172,7 +1043,7 @@ impl WorktreeManager {
                 command: format!("try_exists {:?}", managed_dir),
             })?;
         if !managed_exists {
-            return Ok(());
+            return Ok(outcome);
         }
 
         let active_raw: Vec<String> = git
@@ -190,6 +1061,7 @@ impl WorktreeManager {
             active.insert(canonical.to_string_lossy().to_string());
         }
 
+        let mut orphans = Vec::new();
         if let Ok(mut entries) = tokio::fs::read_dir(&managed_dir).await {
             while let Ok(Some(entry)) = entries.next_entry().await {
                 let path = entry.path();
@@ -202,12 +1074,53 @@ impl WorktreeManager {
                     .map(|m| m.is_dir())
                     .unwrap_or(false);
                 if !active.contains(&entry_key) && is_dir {
-                    log::info!("Removing orphaned worktree dir: {}", path.display());
-                    let _ = tokio::fs::remove_dir_all(&path).await;
+                    if let Err(e) = self.ensure_managed(repo_path, &path).await {
+                        log::warn!("Skipping orphan outside managed dir: {e}");
+                        continue;
+                    }
+                    orphans.push(path);
                 }
             }
         }
+        if dry_run {
+            outcome.orphaned_dirs = orphans.iter().map(|p| p.to_string_lossy().into_owned()).collect();
+            return Ok(outcome);
+        }
+
+        let mut tasks = JoinSet::new();
+        for path in orphans {
+            let lock = match OperationLock::acquire_unmarked("prune", &[&path]) {
+                Ok(lock) => lock,
+                Err(e) => {
+                    outcome.failed.push(PruneFailure {
+                        path: path.to_string_lossy().into_owned(),
+                        error: e.message,
+                    });
+                    continue;
+                }
+            };
+            log::info!("Removing orphaned worktree dir: {}", path.display());
+            tasks.spawn_blocking(move || {
+                let _lock = lock;
+                let removed = std::fs::remove_dir_all(&path);
+                (path.to_string_lossy().into_owned(), removed)
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((path, Ok(()))) => outcome.orphaned_dirs.push(path),
+                Ok((path, Err(e))) => outcome.failed.push(PruneFailure {
+                    path,
+                    error: e.to_string(),
+                }),
+                Err(e) => log::warn!("Orphan removal task failed: {e}"),
+            }
+        }
+        outcome.orphaned_dirs.sort();
+        outcome.failed.sort_by(|a, b| a.path.cmp(&b.path));
+
+        self.registry.forget_missing(repo_path);
 
-        Ok(())
+        Ok(outcome)
     }
 }
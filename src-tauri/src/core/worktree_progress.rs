@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::error::AppError;
+use super::worktree_manager::WorktreeManager;
+use crate::git::{GitError, GitProgress};
+
+/// Operations whose latest state `progress` can return; finished ones are
+/// forgotten first, oldest first.
+const MAX_TRACKED_OPS: usize = 100;
+
+/// State of a background worktree operation, emitted on
+/// `worktree-progress-{op_id}` whenever it changes. `session_id` is set
+/// when the operation created a session in the worktree.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum WorktreeProgress {
+    /// Running, with no progress reported by git yet. Small checkouts
+    /// finish without reporting any.
+    Started,
+    Running { progress: GitProgress },
+    Finished { path: String, session_id: Option<u32> },
+    Failed { error: AppError },
+}
+
+impl WorktreeProgress {
+    fn is_done(&self) -> bool {
+        matches!(self, Self::Finished { .. } | Self::Failed { .. })
+    }
+}
+
+/// Latest state of each tracked operation, oldest first.
+fn ops() -> &'static Mutex<VecDeque<(String, WorktreeProgress)>> {
+    static OPS: OnceLock<Mutex<VecDeque<(String, WorktreeProgress)>>> = OnceLock::new();
+    OPS.get_or_init(Default::default)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Returns the latest state of operation `op_id`, for a caller that
+/// subscribed to its events late, or `None` if it is unknown or long
+/// finished.
+pub fn progress(op_id: &str) -> Option<WorktreeProgress> {
+    lock(ops()).iter().find(|(id, _)| id == op_id).map(|(_, state)| state.clone())
+}
+
+/// Records the new state of `op_id` and emits it.
+pub fn update(app: &AppHandle, op_id: &str, state: WorktreeProgress) {
+    {
+        let mut ops = lock(ops());
+        match ops.iter_mut().find(|(id, _)| id == op_id) {
+            Some((_, current)) => *current = state.clone(),
+            None => {
+                if ops.len() >= MAX_TRACKED_OPS {
+                    let oldest = ops.iter().position(|(_, s)| s.is_done()).unwrap_or(0);
+                    ops.remove(oldest);
+                }
+                ops.push_back((op_id.to_string(), state.clone()));
+            }
+        }
+    }
+    let _ = app.emit(&format!("worktree-progress-{op_id}"), &state);
+}
+
+/// Registers a new operation as `Started` and returns its ID.
+pub fn start_op(app: &AppHandle) -> String {
+    static NEXT_OP: AtomicU64 = AtomicU64::new(1);
+    let op_id = format!("create-{}", NEXT_OP.fetch_add(1, Ordering::Relaxed));
+    update(app, &op_id, WorktreeProgress::Started);
+    op_id
+}
+
+/// Runs `WorktreeManager::create_with_progress`, reporting the checkout's
+/// progress as `Running` on `op_id`, if given. The final state is left to
+/// the caller, which may have more steps to run.
+pub async fn create(
+    app: &AppHandle,
+    op_id: Option<&str>,
+    branch: &str,
+    base_ref: Option<&str>,
+    repo_path: &Path,
+) -> Result<PathBuf, GitError> {
+    app.state::<WorktreeManager>()
+        .create_with_progress(branch, base_ref, repo_path, |progress| {
+            if let Some(op_id) = op_id {
+                update(app, op_id, WorktreeProgress::Running { progress });
+            }
+        })
+        .await
+}
+
+/// Starts `WorktreeManager::create_with_progress` in the background and
+/// returns its operation ID at once. Progress, then the worktree path or
+/// the error, follow on `worktree-progress-{op_id}` as `WorktreeProgress`.
+pub fn start_create(app: &AppHandle, repo_path: PathBuf, branch: String, base_ref: Option<String>) -> String {
+    let op_id = start_op(app);
+    let app = app.clone();
+    let id = op_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let created = create(&app, Some(&id), &branch, base_ref.as_deref(), &repo_path).await;
+        let state = match created {
+            Ok(path) => WorktreeProgress::Finished {
+                path: path.to_string_lossy().into_owned(),
+                session_id: None,
+            },
+            Err(e) => {
+                log::warn!("Creating worktree for {branch} in {} failed: {e}", repo_path.display());
+                WorktreeProgress::Failed { error: e.into() }
+            }
+        };
+        update(&app, &id, state);
+    });
+    op_id
+}
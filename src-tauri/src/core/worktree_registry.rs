@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::clock;
+use super::storage;
+
+/// Current on-disk format of `worktree-registry.json`.
+const REGISTRY_VERSION: u32 = 1;
+
+/// Provenance and age of a single managed worktree.
+///
+/// Paths are stored canonicalized. `session_id` is `None` until a session is
+/// linked to the worktree. `created_at` and `last_activity` are Unix epoch
+/// seconds; `last_activity` advances whenever Maestro operates on the
+/// worktree or observes a newer commit in it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WorktreeMetadata {
+    pub path: String,
+    pub repo_path: String,
+    pub branch: Option<String>,
+    pub base_ref: Option<String>,
+    pub session_id: Option<u32>,
+    pub created_at: i64,
+    pub last_activity: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegistryFile {
+    version: u32,
+    worktrees: Vec<WorktreeMetadata>,
+}
+
+/// Persisted map from managed worktree path to its `WorktreeMetadata`.
+///
+/// Every change is written to disk on a blocking thread, off the async
+/// runtime; reads and updates that change nothing write nothing.
+/// Persistence failures are logged and never fail the worktree operation
+/// that triggered them, since the registry is informational.
+pub struct WorktreeRegistry {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    file: PathBuf,
+    entries: Mutex<HashMap<String, WorktreeMetadata>>,
+    /// Serializes saves. The entries are read inside it, so the last save
+    /// always writes the newest state.
+    write_lock: Mutex<()>,
+}
+
+impl WorktreeRegistry {
+    /// Loads the registry from the data dir, starting empty if the file is
+    /// missing or unreadable.
+    pub fn load() -> Self {
+        let file = storage::data_dir().join("worktree-registry.json");
+        let entries = match storage::load_json::<RegistryFile>(&file) {
+            Ok(Some(reg)) => reg
+                .worktrees
+                .into_iter()
+                .map(|m| (m.path.clone(), m))
+                .collect(),
+            Ok(None) => HashMap::new(),
+            Err(e) => {
+                log::error!("Failed to load worktree registry {}: {e}", file.display());
+                HashMap::new()
+            }
+        };
+        Self {
+            inner: Arc::new(Inner {
+                file,
+                entries: Mutex::new(entries),
+                write_lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Applies `f` to the entry map, which also says whether it changed
+    /// anything, and persists the result if it did.
+    fn update<R>(&self, f: impl FnOnce(&mut HashMap<String, WorktreeMetadata>) -> (R, bool)) -> R {
+        let (result, changed) = f(&mut self.inner.lock());
+        if changed {
+            let inner = self.inner.clone();
+            tauri::async_runtime::spawn_blocking(move || inner.save());
+        }
+        result
+    }
+
+    /// Records a newly created worktree, replacing any stale entry at that path.
+    pub fn register(&self, path: &Path, repo_path: &Path, branch: Option<&str>, base_ref: Option<&str>) {
+        let now = clock::now_secs();
+        let meta = WorktreeMetadata {
+            path: canonical(path),
+            repo_path: canonical(repo_path),
+            branch: branch.map(|b| b.to_string()),
+            base_ref: base_ref.map(|b| b.to_string()),
+            session_id: None,
+            created_at: now,
+            last_activity: now,
+        };
+        self.update(|entries| {
+            entries.insert(meta.path.clone(), meta);
+            ((), true)
+        });
+    }
+
+    /// Links a worktree to the session working in it. Returns `false` if the
+    /// worktree is not in the registry.
+    pub fn link_session(&self, path: &Path, session_id: u32) -> bool {
+        let key = canonical(path);
+        self.update(|entries| match entries.get_mut(&key) {
+            Some(meta) => {
+                meta.session_id = Some(session_id);
+                meta.last_activity = clock::now_secs();
+                (true, true)
+            }
+            None => (false, false),
+        })
+    }
+
+    /// Advances `last_activity` to `at` if it is newer than the stored value.
+    pub fn touch(&self, path: &Path, at: i64) {
+        let key = canonical(path);
+        self.update(|entries| match entries.get_mut(&key) {
+            Some(meta) if meta.last_activity < at => {
+                meta.last_activity = at;
+                ((), true)
+            }
+            _ => ((), false),
+        });
+    }
+
+    /// Re-keys an entry after its worktree moved and its branch was renamed.
+    pub fn rename(&self, old_path: &Path, new_path: &Path, new_branch: &str) {
+        let old_key = canonical(old_path);
+        let new_key = canonical(new_path);
+        self.update(|entries| match entries.remove(&old_key) {
+            Some(mut meta) => {
+                meta.path = new_key.clone();
+                meta.branch = Some(new_branch.to_string());
+                meta.last_activity = clock::now_secs();
+                entries.insert(new_key, meta);
+                ((), true)
+            }
+            None => ((), false),
+        });
+    }
+
+    /// Returns the entry for a worktree path, if registered.
+    pub fn get(&self, path: &Path) -> Option<WorktreeMetadata> {
+        let key = canonical(path);
+        self.inner.lock().get(&key).cloned()
+    }
+
+    /// Drops the entry for a removed worktree.
+    pub fn forget(&self, path: &Path) {
+        let key = canonical(path);
+        self.update(|entries| ((), entries.remove(&key).is_some()));
+    }
+
+    /// Drops entries of `repo_path` whose directory no longer exists.
+    pub fn forget_missing(&self, repo_path: &Path) {
+        let repo = canonical(repo_path);
+        self.update(|entries| {
+            let before = entries.len();
+            entries.retain(|path, meta| meta.repo_path != repo || Path::new(path).exists());
+            ((), entries.len() != before)
+        });
+    }
+
+    /// Returns all entries belonging to `repo_path`, sorted by creation time.
+    pub fn for_repo(&self, repo_path: &Path) -> Vec<WorktreeMetadata> {
+        let repo = canonical(repo_path);
+        let mut list: Vec<WorktreeMetadata> = self
+            .inner
+            .lock()
+            .values()
+            .filter(|m| m.repo_path == repo)
+            .cloned()
+            .collect();
+        list.sort_by_key(|m| m.created_at);
+        list
+    }
+}
+
+impl Inner {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, WorktreeMetadata>> {
+        match self.entries.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Writes the current entries to disk.
+    fn save(&self) {
+        let _write = match self.write_lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let mut worktrees: Vec<WorktreeMetadata> = self.lock().values().cloned().collect();
+        worktrees.sort_by(|a, b| a.path.cmp(&b.path));
+        let file = RegistryFile {
+            version: REGISTRY_VERSION,
+            worktrees,
+        };
+        if let Err(e) = storage::save_json(&self.file, &file) {
+            log::error!("Failed to save worktree registry: {e}");
+        }
+    }
+}
+
+/// Registry key for a path: canonicalized when it exists, raw otherwise.
+fn canonical(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
@@ -0,0 +1,146 @@
+use serde::Serialize;
+use specta::Type;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::error::GitError;
+use super::runner::Git;
+
+/// Ref namespace holding working-tree snapshots taken before destructive
+/// worktree removal. Refs are shared by all worktrees of a repository, so a
+/// snapshot outlives the worktree it was taken from.
+pub const ARCHIVE_REF_PREFIX: &str = "refs/maestro/archives/";
+
+/// Identity used for snapshot commits so archiving works even when the user
+/// has no `user.name`/`user.email` configured.
+const ARCHIVE_IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "Maestro"),
+    ("GIT_AUTHOR_EMAIL", "maestro@localhost"),
+    ("GIT_COMMITTER_NAME", "Maestro"),
+    ("GIT_COMMITTER_EMAIL", "maestro@localhost"),
+];
+
+/// A snapshot of a worktree's uncommitted state, stored as a commit whose
+/// parent is the worktree's HEAD at the time of archiving.
+///
+/// `branch` is `None` if the worktree was detached. `created_at` is a Unix
+/// epoch timestamp.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WorktreeArchive {
+    pub ref_name: String,
+    pub commit: String,
+    pub parent: String,
+    pub branch: Option<String>,
+    pub worktree_path: String,
+    pub created_at: i64,
+}
+
+impl Git {
+    /// Records every change in the working tree, including untracked (but not
+    /// ignored) files, as a commit stored under `ref_name`.
+    ///
+    /// Uses a scratch index (`GIT_INDEX_FILE`) seeded from HEAD, so neither the
+    /// real index nor the working tree is modified. The commit message body
+    /// carries `branch:` and `worktree:` lines that `list_archives` reads back.
+    pub async fn snapshot_working_tree(
+        &self,
+        ref_name: &str,
+        branch: Option<&str>,
+    ) -> Result<WorktreeArchive, GitError> {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let index_path = std::env::temp_dir().join(format!(
+            "maestro-archive-{}-{}.index",
+            std::process::id(),
+            created_at
+        ));
+        let index = index_path.to_string_lossy().to_string();
+        let env = [("GIT_INDEX_FILE", index.as_str())];
+
+        let result = async {
+            let parent = self.run(&["rev-parse", "HEAD"]).await?.trimmed().to_string();
+            self.run_with_env(&["read-tree", "HEAD"], &env).await?;
+            self.run_with_env(&["add", "-A"], &env).await?;
+            let tree = self.run_with_env(&["write-tree"], &env).await?;
+
+            let worktree_path = self.repo_path().display().to_string();
+            let message = format!(
+                "Maestro archive of {}\n\nbranch: {}\nworktree: {}\n",
+                branch.unwrap_or("detached HEAD"),
+                branch.unwrap_or(""),
+                worktree_path
+            );
+            let commit = self
+                .run_with_env(
+                    &["commit-tree", tree.trimmed(), "-p", &parent, "-m", &message],
+                    &ARCHIVE_IDENTITY,
+                )
+                .await?
+                .trimmed()
+                .to_string();
+            self.run(&["update-ref", ref_name, &commit]).await?;
+
+            Ok(WorktreeArchive {
+                ref_name: ref_name.to_string(),
+                commit,
+                parent,
+                branch: branch.map(|b| b.to_string()),
+                worktree_path,
+                created_at,
+            })
+        }
+        .await;
+
+        let _ = tokio::fs::remove_file(&index_path).await;
+        result
+    }
+
+    /// Lists all snapshots under `refs/maestro/archives/`, newest first.
+    ///
+    /// Uses `for-each-ref` with NUL field separators and an ASCII record
+    /// separator (0x1e) between entries, since commit bodies span lines.
+    pub async fn list_archives(&self) -> Result<Vec<WorktreeArchive>, GitError> {
+        let output = self
+            .run(&[
+                "for-each-ref",
+                "--sort=-creatordate",
+                "--format=%(refname)%00%(objectname)%00%(parent)%00%(creatordate:unix)%00%(contents:body)%1e",
+                ARCHIVE_REF_PREFIX,
+            ])
+            .await?;
+
+        let mut archives = Vec::new();
+        for record in output.stdout.split('\x1e') {
+            let record = record.trim_start_matches('\n');
+            if record.is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = record.splitn(5, '\0').collect();
+            if fields.len() < 5 {
+                continue;
+            }
+
+            let mut branch = None;
+            let mut worktree_path = String::new();
+            for line in fields[4].lines() {
+                if let Some(b) = line.strip_prefix("branch: ") {
+                    branch = Some(b.to_string()).filter(|b| !b.is_empty());
+                } else if let Some(p) = line.strip_prefix("worktree: ") {
+                    worktree_path = p.to_string();
+                }
+            }
+
+            archives.push(WorktreeArchive {
+                ref_name: fields[0].to_string(),
+                commit: fields[1].to_string(),
+                parent: fields[2].to_string(),
+                branch,
+                worktree_path,
+                created_at: fields[3].parse().unwrap_or(0),
+            });
+        }
+
+        Ok(archives)
+    }
+}
@@ -0,0 +1,410 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::error::GitError;
+use super::runner::Git;
+
+/// A single changed file between a base commit and the working tree.
+///
+/// `status` is git's one-letter status code (`A`, `M`, `D`, `R`, `C`, `T`),
+/// or `?` for untracked files. `old_path` is only set for renames and copies.
+/// `additions`/`deletions` are `None` for binary files.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DiffFile {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: String,
+    pub additions: Option<usize>,
+    pub deletions: Option<usize>,
+}
+
+/// Aggregate diffstat totals, equivalent to the last line of `git diff --stat`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct DiffStat {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Changes between a base ref and the current working tree, including
+/// uncommitted and untracked files.
+///
+/// `merge_base` is the commit the comparison starts from (the point the
+/// branch was cut from `base_ref`), so commits that landed on the base after
+/// the branch was created do not show up as changes.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DiffSummary {
+    pub base_ref: String,
+    pub merge_base: String,
+    pub files: Vec<DiffFile>,
+    pub stat: DiffStat,
+}
+
+/// Role of a line in a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// One line of a hunk, without its `+`/`-`/space prefix. Line numbers are
+/// 1-based; `old_line` is `None` for added lines and `new_line` for
+/// removed ones.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub text: String,
+}
+
+/// A hunk of a unified diff, as introduced by its `@@ -a,b +c,d @@` line.
+/// `section` is the text git printed after the range (usually the
+/// enclosing function), if any.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub section: Option<String>,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Differences between two files. `hunks` is empty if they are identical
+/// or `binary`; `stat` counts one changed file if they differ.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FileDiff {
+    pub old_path: String,
+    pub new_path: String,
+    pub binary: bool,
+    pub stat: DiffStat,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl Git {
+    /// Compares two files anywhere on disk with `git diff --no-index`,
+    /// tracked or not, with `context` lines around each change.
+    pub async fn diff_files(&self, old: &Path, new: &Path, context: u32) -> Result<FileDiff, GitError> {
+        let old_path = old.to_string_lossy().into_owned();
+        let new_path = new.to_string_lossy().into_owned();
+        let unified = format!("--unified={context}");
+        let args = [
+            "diff",
+            "--no-index",
+            "--no-color",
+            "--no-ext-diff",
+            &unified,
+            "--",
+            &old_path,
+            &new_path,
+        ];
+        // Exit code 1 means the files differ
+        let (code, output) = self.run_with_status(&args).await?;
+        if code != 0 && code != 1 {
+            return Err(GitError::CommandFailed {
+                code,
+                stderr: output.stderr.trim().to_string(),
+                command: self.command_string(&args),
+            });
+        }
+
+        let binary = output
+            .stdout
+            .lines()
+            .any(|line| line.starts_with("Binary files ") && line.ends_with(" differ"));
+        let hunks = parse_hunks(&output.stdout)?;
+        let count = |kind| hunks.iter().flat_map(|h| &h.lines).filter(|l| l.kind == kind).count();
+        let stat = DiffStat {
+            files_changed: usize::from(code == 1),
+            insertions: count(DiffLineKind::Added),
+            deletions: count(DiffLineKind::Removed),
+        };
+        Ok(FileDiff {
+            old_path,
+            new_path,
+            binary,
+            stat,
+            hunks,
+        })
+    }
+
+    /// Returns the best common ancestor of two refs via `git merge-base`.
+    pub async fn merge_base(&self, a: &str, b: &str) -> Result<String, GitError> {
+        let output = self.run(&["merge-base", a, b]).await?;
+        Ok(output.trimmed().to_string())
+    }
+
+    /// Diffs the working tree against the merge-base of HEAD and `base_ref`.
+    ///
+    /// Combines `git diff --name-status` and `--numstat` (both with `-z` and
+    /// rename detection) against the merge-base, then appends untracked files
+    /// from `ls-files --others --exclude-standard` with their line counts as
+    /// additions. Must be run against the worktree directory itself.
+    pub async fn diff_against(&self, base_ref: &str) -> Result<DiffSummary, GitError> {
+        let merge_base = self.merge_base(base_ref, "HEAD").await?;
+
+        let name_status = self
+            .run(&[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--name-status",
+                "-z",
+                "-M",
+                &merge_base,
+            ])
+            .await?;
+        let numstat = self
+            .run(&[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--numstat",
+                "-z",
+                "-M",
+                &merge_base,
+            ])
+            .await?;
+
+        let counts = parse_numstat_z(&numstat.stdout);
+        let mut files = parse_name_status_z(&name_status.stdout)?;
+        for file in &mut files {
+            if let Some(&(additions, deletions)) = counts.get(&file.path) {
+                file.additions = additions;
+                file.deletions = deletions;
+            }
+        }
+
+        let untracked = self
+            .run(&["ls-files", "--others", "--exclude-standard", "-z"])
+            .await?;
+        for path in untracked.stdout.split('\0').filter(|p| !p.is_empty()) {
+            let additions = count_text_lines(&self.repo_path().join(path)).await;
+            files.push(DiffFile {
+                path: path.to_string(),
+                old_path: None,
+                status: "?".to_string(),
+                additions,
+                deletions: additions.map(|_| 0),
+            });
+        }
+
+        let stat = DiffStat {
+            files_changed: files.len(),
+            insertions: files.iter().filter_map(|f| f.additions).sum(),
+            deletions: files.iter().filter_map(|f| f.deletions).sum(),
+        };
+
+        Ok(DiffSummary {
+            base_ref: base_ref.to_string(),
+            merge_base,
+            files,
+            stat,
+        })
+    }
+
+    /// Returns the patch of tracked changes, committed and uncommitted,
+    /// since HEAD diverged from `base_ref`. Untracked files are not
+    /// included; `diff_against` lists them.
+    pub async fn diff_patch(&self, base_ref: &str) -> Result<String, GitError> {
+        let merge_base = self.merge_base(base_ref, "HEAD").await?;
+        let output = self.run(&["diff", "--no-color", "-M", &merge_base]).await?;
+        Ok(output.stdout)
+    }
+
+    /// Returns a binary patch of every change since HEAD diverged from
+    /// `base_ref`, committed, uncommitted, and untracked, limited to `paths`
+    /// if any are given.
+    ///
+    /// Untracked files are staged in a scratch index (`GIT_INDEX_FILE`)
+    /// seeded from HEAD, like `snapshot_working_tree`, so the real index is
+    /// not modified.
+    pub async fn full_patch(&self, base_ref: &str, paths: &[String]) -> Result<String, GitError> {
+        let merge_base = self.merge_base(base_ref, "HEAD").await?;
+        let index_path = std::env::temp_dir().join(format!(
+            "maestro-patch-{}-{}.index",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+        let index = index_path.to_string_lossy().to_string();
+        let env = [("GIT_INDEX_FILE", index.as_str())];
+        let pathspec: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+        let result = async {
+            self.run_with_env(&["read-tree", "HEAD"], &env).await?;
+            let mut add = vec!["add", "-A", "--"];
+            add.extend(&pathspec);
+            self.run_with_env(&add, &env).await?;
+            let mut diff = vec!["diff", "--cached", "--binary", "--no-color", "-M", &merge_base, "--"];
+            diff.extend(&pathspec);
+            Ok(self.run_with_env(&diff, &env).await?.stdout)
+        }
+        .await;
+
+        let _ = tokio::fs::remove_file(&index_path).await;
+        result
+    }
+
+    /// Applies the patch in `patch_file` to the working tree and index with
+    /// `git apply --3way`, falling back to a three-way merge for hunks that
+    /// do not apply cleanly. Conflicts are left marked in the files.
+    pub async fn apply_patch(&self, patch_file: &Path) -> Result<(), GitError> {
+        let patch = patch_file.to_string_lossy();
+        self.run(&["apply", "--3way", "--whitespace=nowarn", &patch]).await?;
+        Ok(())
+    }
+}
+
+/// Parses the hunks of a unified diff of one file, skipping its header
+/// lines and `\ No newline at end of file` markers.
+fn parse_hunks(patch: &str) -> Result<Vec<DiffHunk>, GitError> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("@@ ") {
+            let hunk = parse_hunk_header(header).ok_or_else(|| GitError::ParseError {
+                message: format!("malformed hunk header '{line}'"),
+            })?;
+            old_line = hunk.old_start;
+            new_line = hunk.new_start;
+            hunks.push(hunk);
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+        let (kind, text) = match line.split_at_checked(1) {
+            Some((" ", text)) => (DiffLineKind::Context, text),
+            Some(("+", text)) => (DiffLineKind::Added, text),
+            Some(("-", text)) => (DiffLineKind::Removed, text),
+            _ if line.is_empty() => (DiffLineKind::Context, ""),
+            _ => continue,
+        };
+        let (old, new) = match kind {
+            DiffLineKind::Context => (Some(old_line), Some(new_line)),
+            DiffLineKind::Added => (None, Some(new_line)),
+            DiffLineKind::Removed => (Some(old_line), None),
+        };
+        old_line += u32::from(old.is_some());
+        new_line += u32::from(new.is_some());
+        hunk.lines.push(DiffLine {
+            kind,
+            old_line: old,
+            new_line: new,
+            text: text.to_string(),
+        });
+    }
+    Ok(hunks)
+}
+
+/// Parses `-a,b +c,d @@ section`, the rest of a hunk header after `@@ `.
+/// A missing count means one line.
+fn parse_hunk_header(header: &str) -> Option<DiffHunk> {
+    let (ranges, section) = header.split_once("@@")?;
+    let mut ranges = ranges.split_whitespace();
+    let range = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(ranges.next()?.strip_prefix('-')?)?;
+    let (new_start, new_lines) = range(ranges.next()?.strip_prefix('+')?)?;
+    let section = section.trim();
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section: (!section.is_empty()).then(|| section.to_string()),
+        lines: Vec::new(),
+    })
+}
+
+/// Parses `git diff --name-status -z` output.
+///
+/// Records are NUL-separated: a status token followed by one path, or two
+/// paths (old, new) for renames and copies whose status carries a similarity
+/// score such as `R087`.
+fn parse_name_status_z(stdout: &str) -> Result<Vec<DiffFile>, GitError> {
+    let mut files = Vec::new();
+    let mut fields = stdout.split('\0').filter(|f| !f.is_empty());
+
+    while let Some(status) = fields.next() {
+        let code = status.chars().next().unwrap_or('M').to_string();
+        let first = fields.next().ok_or_else(|| GitError::ParseError {
+            message: format!("missing path after status '{status}'"),
+        })?;
+
+        let (path, old_path) = if code == "R" || code == "C" {
+            let second = fields.next().ok_or_else(|| GitError::ParseError {
+                message: format!("missing destination path for '{first}'"),
+            })?;
+            (second.to_string(), Some(first.to_string()))
+        } else {
+            (first.to_string(), None)
+        };
+
+        files.push(DiffFile {
+            path,
+            old_path,
+            status: code,
+            additions: None,
+            deletions: None,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Parses `git diff --numstat -z` output into a map keyed by destination path.
+///
+/// Regular records are `added\tdeleted\tpath\0`; renames leave the path empty
+/// and follow with `old\0new\0`. Binary files report `-` for both counts,
+/// which maps to `None`.
+fn parse_numstat_z(stdout: &str) -> HashMap<String, (Option<usize>, Option<usize>)> {
+    let mut counts = HashMap::new();
+    let mut fields = stdout.split('\0');
+
+    while let Some(record) = fields.next() {
+        if record.is_empty() {
+            continue;
+        }
+        let mut parts = record.splitn(3, '\t');
+        let additions = parts.next().and_then(|v| v.parse().ok());
+        let deletions = parts.next().and_then(|v| v.parse().ok());
+        let path = match parts.next() {
+            Some(p) if !p.is_empty() => p.to_string(),
+            _ => {
+                // Rename/copy: old and new paths follow as separate fields
+                let _old = fields.next();
+                match fields.next() {
+                    Some(new) => new.to_string(),
+                    None => break,
+                }
+            }
+        };
+        counts.insert(path, (additions, deletions));
+    }
+
+    counts
+}
+
+/// Counts lines in an untracked file, returning `None` for binary or unreadable files.
+async fn count_text_lines(path: &Path) -> Option<usize> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    let trailing = usize::from(!bytes.is_empty() && !bytes.ends_with(b"\n"));
+    Some(newlines + trailing)
+}
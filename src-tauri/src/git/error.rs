@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
-/// All possible errors from git operations, serialized as a string to the
-/// Tauri frontend via the custom `Serialize` impl below.
+/// All possible errors from git operations. Commands hand them to the
+/// frontend as an `AppError` carrying the variant's fields as context;
+/// elsewhere they serialize as a string via the custom `Serialize` impl
+/// below.
 ///
 /// Variants cover the full lifecycle: binary not found, spawn failures,
 /// non-zero exits, output encoding issues, and domain-specific errors
@@ -47,9 +49,54 @@ pub enum GitError {
     #[error("branch '{branch}' already checked out at {path}")]
     BranchAlreadyCheckedOut { branch: String, path: String },
 
+    /// Every numbered variant of a worktree name, up to `-{max}`, is already
+    /// taken.
+    #[error("no free name for '{name}': suffixes up to -{max} are all taken")]
+    NamesExhausted { name: String, max: u32 },
+
     /// The specified worktree path does not exist in git's worktree list.
     #[error("worktree not found: {0}")]
     WorktreeNotFound(String),
+
+    /// A destructive operation was requested on a path outside Maestro's
+    /// managed worktree directory for the repository.
+    #[error("refusing to remove {path}: not a Maestro-managed worktree")]
+    UnmanagedPath { path: String },
+
+    /// A worktree could not be moved to the freedesktop trash; nothing was
+    /// removed.
+    #[error("failed to move {path} to the trash: {message}")]
+    TrashFailed { path: String, message: String },
+
+    /// No worktree archive exists with the given ref name or commit.
+    #[error("worktree archive not found: {0}")]
+    ArchiveNotFound(String),
+
+    /// An operation that needs a branch was pointed at a detached HEAD.
+    #[error("no branch checked out at {path} (detached HEAD)")]
+    DetachedHead { path: String },
+
+    /// The working tree has uncommitted changes that the operation would lose or
+    /// mix into its result.
+    #[error("working tree at {path} has uncommitted changes")]
+    DirtyWorkingTree { path: String },
+
+    /// A merge stopped (or would stop) on conflicts in the listed files.
+    #[error("merge conflicts in {}", files.join(", "))]
+    MergeConflict { files: Vec<String> },
+
+    /// A revision (commit, tag, or branch) does not name a commit.
+    #[error("unknown revision: {0}")]
+    UnknownRevision(String),
+
+    /// A branch no longer points at the commit an operation was checked
+    /// against.
+    #[error("branch '{branch}' moved from {expected} to {actual}")]
+    BranchMoved {
+        branch: String,
+        expected: String,
+        actual: String,
+    },
 }
 
 /// Serializes the error as its `Display` string so the frontend receives a
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::error::GitError;
+use super::runner::Git;
+
+/// Result of a dry-run merge computed by `merge_preview`.
+///
+/// `files` lists paths that would conflict; it is empty when the merge is
+/// clean. Nothing in any working tree or index is touched to produce this.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MergePreview {
+    pub branch: String,
+    pub target: String,
+    pub conflicts: bool,
+    pub files: Vec<String>,
+}
+
+/// How a branch is brought into its target by `merge_branch`.
+///
+/// `FastForward` refuses to create a merge commit and fails if the histories
+/// have diverged. `Merge` always records a merge commit (`--no-ff`) so the
+/// branch remains visible in the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum MergeStrategy {
+    FastForward,
+    Merge,
+}
+
+impl Git {
+    /// Merges `commit`, the tip of `branch` as seen by the caller, into the
+    /// currently checked-out branch, so commits added to the branch since
+    /// are left out. A merge commit is named after the branch.
+    ///
+    /// On conflicts the merge is aborted (restoring the pre-merge state) and
+    /// `MergeConflict` is returned with the unmerged file list, so the checkout
+    /// is never left half-merged. Returns the new HEAD SHA on success.
+    pub async fn merge_branch(&self, branch: &str, commit: &str, strategy: MergeStrategy) -> Result<String, GitError> {
+        let message = format!("Merge branch '{branch}'");
+        let args: &[&str] = match strategy {
+            MergeStrategy::FastForward => &["merge", "--ff-only", commit],
+            MergeStrategy::Merge => &["merge", "--no-ff", "-m", &message, commit],
+        };
+
+        if let Err(err) = self.run(args).await {
+            let conflicted = self.conflicted_files().await.unwrap_or_default();
+            if conflicted.is_empty() {
+                return Err(err);
+            }
+            if let Err(e) = self.run(&["merge", "--abort"]).await {
+                log::warn!("merge --abort failed in {:?}: {e}", self.repo_path());
+            }
+            return Err(GitError::MergeConflict { files: conflicted });
+        }
+
+        let head = self.run(&["rev-parse", "HEAD"]).await?;
+        Ok(head.trimmed().to_string())
+    }
+
+    /// Rebases the currently checked-out branch onto `onto`.
+    ///
+    /// Like `merge_branch`, a conflicting rebase is aborted, restoring the
+    /// branch, and `MergeConflict` returned with the files that conflicted
+    /// first. Returns the new HEAD SHA on success.
+    pub async fn rebase_onto(&self, onto: &str) -> Result<String, GitError> {
+        if let Err(err) = self.run(&["-c", "core.editor=true", "rebase", onto]).await {
+            let conflicted = self.conflicted_files().await.unwrap_or_default();
+            if conflicted.is_empty() {
+                return Err(err);
+            }
+            if let Err(e) = self.run(&["rebase", "--abort"]).await {
+                log::warn!("rebase --abort failed in {:?}: {e}", self.repo_path());
+            }
+            return Err(GitError::MergeConflict { files: conflicted });
+        }
+
+        let head = self.run(&["rev-parse", "HEAD"]).await?;
+        Ok(head.trimmed().to_string())
+    }
+
+    /// Computes whether merging `branch` into `target` would conflict, using
+    /// `git merge-tree --write-tree` (git >= 2.38).
+    ///
+    /// merge-tree performs the merge entirely in the object database: exit
+    /// code 0 means clean, 1 means conflicts, and with `--name-only` the lines
+    /// following the result tree OID are the conflicted paths. Any other exit
+    /// code is a real failure (unknown ref, unsupported git version).
+    pub async fn merge_preview(&self, branch: &str, target: &str) -> Result<MergePreview, GitError> {
+        let args = [
+            "merge-tree",
+            "--write-tree",
+            "--name-only",
+            "--no-messages",
+            target,
+            branch,
+        ];
+        let (code, output) = self.run_with_status(&args).await?;
+
+        let files = match code {
+            0 => Vec::new(),
+            1 => output
+                .stdout
+                .lines()
+                .skip(1) // result tree OID
+                .take_while(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect(),
+            _ => {
+                return Err(GitError::CommandFailed {
+                    code,
+                    stderr: output.stderr.trim().to_string(),
+                    command: self.command_string(&args),
+                })
+            }
+        };
+
+        Ok(MergePreview {
+            branch: branch.to_string(),
+            target: target.to_string(),
+            conflicts: code == 1,
+            files,
+        })
+    }
+
+    /// Lists files with unresolved merge conflicts in the working tree.
+    pub async fn conflicted_files(&self) -> Result<Vec<String>, GitError> {
+        let output = self
+            .run(&["diff", "--name-only", "--diff-filter=U"])
+            .await?;
+        Ok(output.lines().into_iter().map(|l| l.to_string()).collect())
+    }
+}
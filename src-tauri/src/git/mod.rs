@@ -1,7 +1,15 @@
+pub mod archive;
+pub mod diff;
 pub mod error;
+pub mod merge;
 pub mod ops;
+pub mod remote;
 pub mod runner;
 
+pub use archive::WorktreeArchive;
+pub use diff::{DiffStat, DiffSummary, FileDiff};
 pub use error::GitError;
+pub use merge::{MergePreview, MergeStrategy};
 pub use ops::{BranchInfo, CommitInfo, WorktreeInfo};
-pub use runner::Git;
+pub use remote::{PushResult, RemoteInfo};
+pub use runner::{Git, GitProgress};
@@ -1,15 +1,20 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::path::Path;
 
 use super::error::GitError;
-use super::runner::Git;
+use super::runner::{Git, GitOutput, GitProgress};
+
+/// Conventional default branch names, tried in order by `default_branch`
+/// when `origin/HEAD` is not set.
+const DEFAULT_BRANCH_CANDIDATES: [&str; 3] = ["main", "master", "trunk"];
 
 /// A local or remote branch returned by `list_branches`.
 ///
 /// Remote branches have `is_remote = true` and names like `origin/main`.
 /// Synthetic `HEAD` pointer entries (e.g. `origin/HEAD`) are filtered out
 /// during parsing and will never appear in results.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Type)]
 pub struct BranchInfo {
     pub name: String,
     pub is_remote: bool,
@@ -20,7 +25,7 @@ pub struct BranchInfo {
 ///
 /// `branch` is `None` for detached HEAD states or bare repositories.
 /// `head` contains the full commit SHA the worktree currently points to.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Type)]
 pub struct WorktreeInfo {
     pub path: String,
     pub head: String,
@@ -33,7 +38,7 @@ pub struct WorktreeInfo {
 /// `parent_hashes` is empty for root commits and contains multiple entries
 /// for merge commits. `timestamp` is a Unix epoch value from `%at`.
 /// `summary` is the first line of the commit message (`%s`).
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct CommitInfo {
     pub hash: String,
     pub short_hash: String,
@@ -123,6 +128,70 @@ impl Git {
         Ok(output.lines().len())
     }
 
+    /// Stages every change, untracked files included, and commits it with
+    /// `message`. Returns the new HEAD SHA.
+    pub async fn commit_all(&self, message: &str) -> Result<String, GitError> {
+        self.run(&["add", "-A"]).await?;
+        self.run(&["commit", "-m", message]).await?;
+        let head = self.run(&["rev-parse", "HEAD"]).await?;
+        Ok(head.trimmed().to_string())
+    }
+
+    /// Returns the untracked paths under `path` that ignore rules exclude,
+    /// relative to the directory this `Git` runs in. An ignored directory is
+    /// listed once, with a trailing `/`, instead of its contents.
+    pub async fn ignored_paths(&self, path: &str) -> Result<Vec<String>, GitError> {
+        let output = self
+            .run(&[
+                "ls-files",
+                "-z",
+                "--others",
+                "--ignored",
+                "--exclude-standard",
+                "--directory",
+                "--",
+                path,
+            ])
+            .await?;
+        Ok(output
+            .stdout
+            .split('\0')
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Returns `(ahead, behind)` commit counts of HEAD relative to its upstream.
+    ///
+    /// Uses `rev-list --left-right --count HEAD...@{upstream}`. Returns `None`
+    /// when the current branch has no upstream configured or HEAD is detached,
+    /// since neither case is an error from the caller's point of view.
+    pub async fn ahead_behind(&self) -> Result<Option<(usize, usize)>, GitError> {
+        let output = match self
+            .run(&["rev-list", "--left-right", "--count", "HEAD...@{upstream}"])
+            .await
+        {
+            Ok(output) => output,
+            Err(GitError::CommandFailed { ref stderr, .. })
+                if stderr.contains("no upstream configured")
+                    || stderr.contains("does not point to a branch") =>
+            {
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut counts = output.trimmed().split_whitespace();
+        let parse = |s: Option<&str>| -> Result<usize, GitError> {
+            s.and_then(|v| v.parse().ok()).ok_or_else(|| GitError::ParseError {
+                message: format!("unexpected rev-list count output: {:?}", output.trimmed()),
+            })
+        };
+        let ahead = parse(counts.next())?;
+        let behind = parse(counts.next())?;
+        Ok(Some((ahead, behind)))
+    }
+
     /// Lists all worktrees by parsing `git worktree list --porcelain`.
     ///
     /// Porcelain format uses blank-line-separated stanzas with `worktree`, `HEAD`,
@@ -185,28 +254,43 @@ impl Git {
         new_branch: Option<&str>,
         checkout_ref: Option<&str>,
     ) -> Result<WorktreeInfo, GitError> {
-        let path_str = path.to_string_lossy();
-        let mut args = vec!["worktree", "add"];
-
-        // Collect owned strings to extend their lifetime
-        let branch_flag;
-        if let Some(branch) = new_branch {
-            branch_flag = branch.to_string();
-            args.push("-b");
-            args.push(&branch_flag);
-        }
-
-        args.push(&path_str);
+        let args = worktree_add_args(path, new_branch, checkout_ref, true);
+        self.run(&args.iter().map(String::as_str).collect::<Vec<_>>()).await?;
+        self.worktree_info(path).await
+    }
 
-        let checkout_ref_owned;
-        if let Some(cr) = checkout_ref {
-            checkout_ref_owned = cr.to_string();
-            args.push(&checkout_ref_owned);
+    /// Like `worktree_add`, for repositories with many files: the worktree
+    /// is added without a checkout, then its files are checked out with
+    /// `git checkout --progress`, which reports progress to `on_progress`
+    /// and may take up to `PROGRESS_TIMEOUT` instead of the usual 30s. If
+    /// the checkout fails, the half-made worktree and the branch it created
+    /// are removed.
+    pub async fn worktree_add_with_progress(
+        &self,
+        path: &Path,
+        new_branch: Option<&str>,
+        checkout_ref: Option<&str>,
+        on_progress: impl FnMut(GitProgress) + Send,
+    ) -> Result<WorktreeInfo, GitError> {
+        let args = worktree_add_args(path, new_branch, checkout_ref, false);
+        self.run(&args.iter().map(String::as_str).collect::<Vec<_>>()).await?;
+        let checkout = Git::new(path)
+            .run_with_progress(&["checkout", "--progress", "--force"], on_progress)
+            .await;
+        if let Err(e) = checkout {
+            if let Err(cleanup) = self.worktree_remove(path, true).await {
+                log::warn!("Failed to remove worktree {} after its checkout failed: {cleanup}", path.display());
+            }
+            if let Some(branch) = new_branch {
+                let _ = self.run(&["branch", "-D", branch]).await;
+            }
+            return Err(e);
         }
+        self.worktree_info(path).await
+    }
 
-        self.run(&args).await?;
-
-        // Read back the created worktree info
+    /// Reads back the HEAD and branch of a newly added worktree.
+    async fn worktree_info(&self, path: &Path) -> Result<WorktreeInfo, GitError> {
         let head_output = self.run_in(path, &["rev-parse", "HEAD"]).await?;
         let branch_output = self.run_in(path, &["symbolic-ref", "--short", "HEAD"]).await;
 
@@ -244,12 +328,177 @@ impl Git {
         Ok(())
     }
 
+    /// Moves a worktree to a new location with `git worktree move`, which
+    /// rewrites the `.git` file and the admin dir's `gitdir` link.
+    pub async fn worktree_move(&self, from: &Path, to: &Path) -> Result<(), GitError> {
+        let from_str = from.to_string_lossy().to_string();
+        let to_str = to.to_string_lossy().to_string();
+        self.run(&["worktree", "move", &from_str, &to_str]).await?;
+        Ok(())
+    }
+
+    /// Repairs the two-way links between the repository and a worktree
+    /// (`git worktree repair`), e.g. after its directory moved.
+    pub async fn worktree_repair(&self, path: &Path) -> Result<(), GitError> {
+        let path_str = path.to_string_lossy().to_string();
+        self.run(&["worktree", "repair", &path_str]).await?;
+        Ok(())
+    }
+
     /// Prunes stale worktree references whose directories no longer exist on disk.
     pub async fn worktree_prune(&self) -> Result<(), GitError> {
         self.run(&["worktree", "prune"]).await?;
         Ok(())
     }
 
+    /// Like `worktree_prune`, returning git's line for every reference it
+    /// pruned, e.g. `worktrees/feat: gitdir file points to non-existent
+    /// location`. With `dry_run`, nothing is pruned and the lines say what
+    /// would be.
+    pub async fn worktree_prune_verbose(&self, dry_run: bool) -> Result<Vec<String>, GitError> {
+        let mut args = vec!["worktree", "prune", "--verbose"];
+        if dry_run {
+            args.push("--dry-run");
+        }
+        let output = self.run(&args).await?;
+        Ok(output
+            .stderr
+            .lines()
+            .filter_map(|line| line.strip_prefix("Removing "))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Fetches a pull/merge request head from `remote` into a local branch.
+    ///
+    /// Tries GitHub's `pull/<n>/head` ref first and falls back to GitLab's
+    /// `merge-requests/<n>/head` when the remote has no such ref. The update
+    /// is not forced, so an existing local branch that has diverged from the
+    /// request (e.g. after a force-push) is left alone and an error returned.
+    pub async fn fetch_pull_request(
+        &self,
+        remote: &str,
+        number: u64,
+        local_branch: &str,
+    ) -> Result<(), GitError> {
+        let github = format!("pull/{number}/head:refs/heads/{local_branch}");
+        match self.run(&["fetch", remote, &github]).await {
+            Err(GitError::CommandFailed { ref stderr, .. })
+                if stderr.contains("couldn't find remote ref") =>
+            {
+                let gitlab = format!("merge-requests/{number}/head:refs/heads/{local_branch}");
+                self.run(&["fetch", remote, &gitlab]).await?;
+                Ok(())
+            }
+            result => result.map(|_| ()),
+        }
+    }
+
+    /// Returns whether a local branch with this exact name exists.
+    pub async fn branch_exists(&self, branch: &str) -> Result<bool, GitError> {
+        self.ref_exists(&format!("refs/heads/{branch}")).await
+    }
+
+    /// Returns the full hash of the commit `rev` (a hash, tag, or branch)
+    /// points to; `UnknownRevision` if it names no commit.
+    pub async fn resolve_commit(&self, rev: &str) -> Result<String, GitError> {
+        let unknown = || GitError::UnknownRevision(rev.to_string());
+        if rev.is_empty() || rev.starts_with('-') {
+            return Err(unknown());
+        }
+        let (code, output) = self
+            .run_with_status(&["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+            .await?;
+        if code != 0 {
+            return Err(unknown());
+        }
+        Ok(output.trimmed().to_string())
+    }
+
+    async fn ref_exists(&self, full_ref: &str) -> Result<bool, GitError> {
+        let (code, _) = self
+            .run_with_status(&["rev-parse", "--verify", "--quiet", full_ref])
+            .await?;
+        Ok(code == 0)
+    }
+
+    /// Returns the name of the repository's default branch, as a ref that
+    /// resolves here.
+    ///
+    /// Uses the branch `origin/HEAD` points to (set by `clone` or
+    /// `remote set-head`); without it, the first of `main`, `master`, and
+    /// `trunk` that exists locally or on `origin`; failing that, the
+    /// current branch. A branch that exists only on `origin` is returned as
+    /// `origin/<branch>`.
+    pub async fn default_branch(&self) -> Result<String, GitError> {
+        let args = ["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"];
+        if let (0, output) = self.run_with_status(&args).await? {
+            let remote_branch = output.trimmed();
+            if let Some(branch) = remote_branch.strip_prefix("origin/") {
+                if self.branch_exists(branch).await? {
+                    return Ok(branch.to_string());
+                }
+                return Ok(remote_branch.to_string());
+            }
+        }
+        for candidate in DEFAULT_BRANCH_CANDIDATES {
+            if self.branch_exists(candidate).await? {
+                return Ok(candidate.to_string());
+            }
+            if self.ref_exists(&format!("refs/remotes/origin/{candidate}")).await? {
+                return Ok(format!("origin/{candidate}"));
+            }
+        }
+        self.current_branch().await
+    }
+
+    /// Returns the default branch of `remote` from its remote-tracking
+    /// branches: the one `<remote>/HEAD` points to, else the first of
+    /// `main`, `master`, and `trunk` that exists, else `None`.
+    pub async fn remote_default_branch(&self, remote: &str) -> Result<Option<String>, GitError> {
+        let head = format!("refs/remotes/{remote}/HEAD");
+        if let (0, output) = self.run_with_status(&["symbolic-ref", "--quiet", "--short", &head]).await? {
+            if let Some(branch) = output.trimmed().strip_prefix(&format!("{remote}/")) {
+                return Ok(Some(branch.to_string()));
+            }
+        }
+        for candidate in DEFAULT_BRANCH_CANDIDATES {
+            if self.ref_exists(&format!("refs/remotes/{remote}/{candidate}")).await? {
+                return Ok(Some(candidate.to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns whether every commit of `branch` is reachable from `target`,
+    /// i.e. the branch has been merged (`merge-base --is-ancestor`).
+    pub async fn is_merged(&self, branch: &str, target: &str) -> Result<bool, GitError> {
+        let args = ["merge-base", "--is-ancestor", branch, target];
+        match self.run_with_status(&args).await? {
+            (0, _) => Ok(true),
+            (1, _) => Ok(false),
+            (code, output) => Err(GitError::CommandFailed {
+                code,
+                stderr: output.stderr.trim().to_string(),
+                command: self.command_string(&args),
+            }),
+        }
+    }
+
+    /// Renames a local branch (`branch -m`). Fails if `new` already exists.
+    pub async fn rename_branch(&self, old: &str, new: &str) -> Result<(), GitError> {
+        self.run(&["branch", "-m", old, new]).await?;
+        Ok(())
+    }
+
+    /// Deletes a local branch. Without `force`, git refuses to delete a branch
+    /// that is not fully merged (`-d`); with `force` it uses `-D`.
+    pub async fn delete_branch(&self, branch: &str, force: bool) -> Result<(), GitError> {
+        let flag = if force { "-D" } else { "-d" };
+        self.run(&["branch", flag, branch]).await?;
+        Ok(())
+    }
+
     /// Returns up to `max_count` commits in topological order.
     ///
     /// Parses a pipe-delimited `git log` format with 7 fields. Lines with fewer
@@ -263,7 +512,7 @@ impl Git {
         let count_str = format!("-{}", max_count);
         let mut args = vec![
             "log",
-            "--format=%H|%h|%P|%an|%ae|%at|%s",
+            COMMIT_LOG_FORMAT,
             &count_str,
             "--topo-order",
         ];
@@ -272,32 +521,78 @@ impl Git {
         }
 
         let output = self.run(&args).await?;
+        Ok(parse_commit_log(&output))
+    }
 
-        let mut commits = Vec::new();
-        for line in output.lines() {
-            let parts: Vec<&str> = line.splitn(7, '|').collect();
-            if parts.len() < 7 {
-                continue;
-            }
+    /// Returns up to `max_count` commits on HEAD that are not on `base`,
+    /// newest first, in the same format as `commit_log`.
+    pub async fn commits_since(&self, base: &str, max_count: usize) -> Result<Vec<CommitInfo>, GitError> {
+        self.commits_between(base, "HEAD", max_count).await
+    }
 
-            let timestamp = parts[5].parse::<i64>().unwrap_or(0);
-            let parent_hashes: Vec<String> = if parts[2].is_empty() {
-                Vec::new()
-            } else {
-                parts[2].split(' ').map(|s| s.to_string()).collect()
-            };
-
-            commits.push(CommitInfo {
-                hash: parts[0].to_string(),
-                short_hash: parts[1].to_string(),
-                parent_hashes,
-                author_name: parts[3].to_string(),
-                author_email: parts[4].to_string(),
-                timestamp,
-                summary: parts[6].to_string(),
-            });
+    /// Returns up to `max_count` commits on `head` that are not on `base`,
+    /// newest first, in the same format as `commit_log`.
+    pub async fn commits_between(
+        &self,
+        base: &str,
+        head: &str,
+        max_count: usize,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        let count_str = format!("-{}", max_count);
+        let range = format!("{base}..{head}");
+        let output = self
+            .run(&["log", COMMIT_LOG_FORMAT, &count_str, "--topo-order", &range])
+            .await?;
+        Ok(parse_commit_log(&output))
+    }
+}
+
+/// Arguments of `git worktree add`; see `Git::worktree_add`. Without
+/// `checkout`, only the branch and worktree are set up (`--no-checkout`).
+fn worktree_add_args(path: &Path, new_branch: Option<&str>, checkout_ref: Option<&str>, checkout: bool) -> Vec<String> {
+    let mut args = vec!["worktree".to_string(), "add".to_string()];
+    if !checkout {
+        args.push("--no-checkout".to_string());
+    }
+    if let Some(branch) = new_branch {
+        args.push("-b".to_string());
+        args.push(branch.to_string());
+    }
+    args.push(path.to_string_lossy().into_owned());
+    if let Some(checkout_ref) = checkout_ref {
+        args.push(checkout_ref.to_string());
+    }
+    args
+}
+
+/// `git log` format parsed by `parse_commit_log`.
+const COMMIT_LOG_FORMAT: &str = "--format=%H|%h|%P|%an|%ae|%at|%s";
+
+/// Parses `git log` output in `COMMIT_LOG_FORMAT`, one commit per line.
+fn parse_commit_log(output: &GitOutput) -> Vec<CommitInfo> {
+    let mut commits = Vec::new();
+    for line in output.lines() {
+        let parts: Vec<&str> = line.splitn(7, '|').collect();
+        if parts.len() < 7 {
+            continue;
         }
 
-        Ok(commits)
+        let timestamp = parts[5].parse::<i64>().unwrap_or(0);
+        let parent_hashes: Vec<String> = if parts[2].is_empty() {
+            Vec::new()
+        } else {
+            parts[2].split(' ').map(|s| s.to_string()).collect()
+        };
+
+        commits.push(CommitInfo {
+            hash: parts[0].to_string(),
+            short_hash: parts[1].to_string(),
+            parent_hashes,
+            author_name: parts[3].to_string(),
+            author_email: parts[4].to_string(),
+            timestamp,
+            summary: parts[6].to_string(),
+        });
     }
+    commits
 }
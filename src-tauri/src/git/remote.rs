@@ -0,0 +1,123 @@
+use serde::Serialize;
+use specta::Type;
+
+use super::error::GitError;
+use super::runner::Git;
+
+/// Outcome of pushing a branch, parsed from `git push --porcelain`.
+///
+/// `flag` is git's one-character ref status (` ` fast-forward, `+` forced,
+/// `*` new ref, `=` up to date, `!` rejected). When `rejected` is true,
+/// `summary` carries git's reason (e.g. `[rejected] (non-fast-forward)`) and
+/// the upstream was not configured.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PushResult {
+    pub branch: String,
+    pub remote: String,
+    pub remote_ref: String,
+    pub tracking_ref: String,
+    pub flag: String,
+    pub summary: String,
+    pub rejected: bool,
+}
+
+/// A configured remote and its fetch URL.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub url: String,
+}
+
+impl Git {
+    /// Lists the configured remotes with their fetch URLs, in `git remote`
+    /// order.
+    pub async fn remotes(&self) -> Result<Vec<RemoteInfo>, GitError> {
+        let output = self.run(&["remote", "-v"]).await?;
+        Ok(output
+            .lines()
+            .into_iter()
+            .filter(|l| l.ends_with(" (fetch)"))
+            .filter_map(|l| {
+                let (name, rest) = l.split_once('\t')?;
+                let url = rest.strip_suffix(" (fetch)")?;
+                Some(RemoteInfo {
+                    name: name.to_string(),
+                    url: url.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Adds a remote named `name` pointing to `url`.
+    pub async fn add_remote(&self, name: &str, url: &str) -> Result<(), GitError> {
+        self.run(&["remote", "add", name, url]).await?;
+        Ok(())
+    }
+
+    /// Fetches every branch of `remote`, pruning remote-tracking branches
+    /// that no longer exist there, and points `<remote>/HEAD` at its default
+    /// branch.
+    pub async fn fetch_remote(&self, remote: &str) -> Result<(), GitError> {
+        self.run(&["fetch", "--prune", remote]).await?;
+        if let Err(e) = self.run(&["remote", "set-head", remote, "--auto"]).await {
+            log::warn!("Cannot determine the default branch of {remote}: {e}");
+        }
+        Ok(())
+    }
+
+    /// Returns the fetch URL configured for `remote`.
+    pub async fn remote_url(&self, remote: &str) -> Result<String, GitError> {
+        let output = self.run(&["remote", "get-url", remote]).await?;
+        Ok(output.trimmed().to_string())
+    }
+
+    /// Pushes a local branch to `remote` under the same name and sets it as
+    /// the branch's upstream (`push -u`). With `force_with_lease`, a remote
+    /// branch that was rewritten locally is overwritten, but only if it
+    /// still points where the remote-tracking branch says.
+    ///
+    /// Rejections are reported through `PushResult::rejected` rather than as
+    /// errors, since git still prints a parseable status line for them; only
+    /// failures without any ref status (auth, network, unknown remote) are
+    /// returned as `CommandFailed`.
+    pub async fn push_branch(&self, remote: &str, branch: &str, force_with_lease: bool) -> Result<PushResult, GitError> {
+        let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+        let mut args = vec!["push", "--porcelain", "-u"];
+        if force_with_lease {
+            args.push("--force-with-lease");
+        }
+        args.extend([remote, &refspec]);
+        let (code, output) = self.run_with_status(&args).await?;
+
+        let status_line = output
+            .stdout
+            .lines()
+            .find(|l| l.split('\t').nth(1).is_some_and(|r| r.starts_with("refs/heads/")));
+        let Some(line) = status_line else {
+            return Err(GitError::CommandFailed {
+                code,
+                stderr: output.stderr.trim().to_string(),
+                command: self.command_string(&args),
+            });
+        };
+
+        let mut fields = line.splitn(3, '\t');
+        let flag = fields.next().unwrap_or_default().to_string();
+        let remote_ref = fields
+            .next()
+            .and_then(|r| r.split(':').nth(1))
+            .unwrap_or_default()
+            .to_string();
+        let summary = fields.next().unwrap_or_default().to_string();
+
+        Ok(PushResult {
+            branch: branch.to_string(),
+            remote: remote.to_string(),
+            tracking_ref: format!("{remote}/{branch}"),
+            remote_ref,
+            rejected: flag == "!" || code != 0,
+            flag,
+            summary,
+        })
+    }
+}
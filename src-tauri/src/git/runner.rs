@@ -1,8 +1,31 @@
+use std::io;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Instant;
+
+use regex::Regex;
+use serde::Serialize;
+use specta::Type;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
 use super::error::GitError;
+use crate::core::metrics;
+
+/// Longest a command run with `run_with_progress` may take; these are the
+/// checkouts and fetches that can legitimately outlast the usual 30s.
+const PROGRESS_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// A progress line git printed, like `Updating files:  45% (4500/10000)`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct GitProgress {
+    /// What git is doing, e.g. `Updating files` or `Receiving objects`.
+    pub phase: String,
+    pub percent: u8,
+    pub done: u64,
+    pub total: u64,
+}
 
 /// Captured stdout/stderr from a completed git subprocess.
 ///
@@ -45,21 +68,188 @@ impl Git {
         }
     }
 
+    /// Returns the repository (or worktree) directory this runner targets.
+    pub fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
     /// Executes a git subcommand and returns its captured output.
     ///
     /// Returns `GitNotFound` if the git binary is missing, `SpawnError` for
     /// other I/O failures, and `CommandFailed` for non-zero exit codes.
     /// Both stdout and stderr are decoded as UTF-8 (returns `InvalidUtf8` on failure).
     pub async fn run(&self, args: &[&str]) -> Result<GitOutput, GitError> {
+        self.run_with_env(args, &[]).await
+    }
+
+    /// Executes a git subcommand and returns its exit code alongside the
+    /// captured output, without treating a non-zero exit as an error.
+    ///
+    /// Needed for plumbing commands that report results through the exit
+    /// code (e.g. `merge-tree` exits 1 on conflicts but still prints useful
+    /// stdout). Spawn, timeout, and UTF-8 failures are still errors. A process
+    /// killed by a signal reports code `-1`.
+    pub async fn run_with_status(&self, args: &[&str]) -> Result<(i32, GitOutput), GitError> {
+        self.exec(args, &[]).await
+    }
+
+    /// Like `run`, but with extra environment variables set on the git process
+    /// (e.g. `GIT_INDEX_FILE` to operate on a scratch index).
+    pub async fn run_with_env(
+        &self,
+        args: &[&str],
+        envs: &[(&str, &str)],
+    ) -> Result<GitOutput, GitError> {
+        let (code, output) = self.exec(args, envs).await?;
+
+        if code == 0 {
+            Ok(output)
+        } else {
+            Err(GitError::CommandFailed {
+                code,
+                stderr: output.stderr.trim().to_string(),
+                command: self.command_string(args),
+            })
+        }
+    }
+
+    /// Spawns git with the standard environment plus `envs` and returns the
+    /// exit code with the captured output, recording how long it ran in
+    /// `metrics`. Shared by all `run*` variants.
+    async fn exec(&self, args: &[&str], envs: &[(&str, &str)]) -> Result<(i32, GitOutput), GitError> {
+        let started = Instant::now();
+        let result = self.spawn(args, envs).await;
+        let failed = !matches!(result, Ok((0, _)));
+        metrics::registry().git_command(subcommand(args), started.elapsed(), failed);
+        result
+    }
+
+    /// Like `run`, but for long operations: calls `on_progress` with each
+    /// progress line git prints to stderr as it runs, and allows up to
+    /// `PROGRESS_TIMEOUT`. `args` must make git report progress even though
+    /// stderr is not a terminal, usually with `--progress`. Progress lines
+    /// are left out of the returned stderr.
+    pub async fn run_with_progress(
+        &self,
+        args: &[&str],
+        mut on_progress: impl FnMut(GitProgress) + Send,
+    ) -> Result<GitOutput, GitError> {
+        let started = Instant::now();
+        let result = self.spawn_with_progress(args, &mut on_progress).await;
+        let failed = !matches!(result, Ok((0, _)));
+        metrics::registry().git_command(subcommand(args), started.elapsed(), failed);
+        let (code, output) = result?;
+        if code == 0 {
+            Ok(output)
+        } else {
+            Err(GitError::CommandFailed {
+                code,
+                stderr: output.stderr.trim().to_string(),
+                command: self.command_string(args),
+            })
+        }
+    }
+
+    async fn spawn_with_progress(
+        &self,
+        args: &[&str],
+        on_progress: &mut (impl FnMut(GitProgress) + Send),
+    ) -> Result<(i32, GitOutput), GitError> {
+        let command_str = self.command_string(args);
+        let spawn_error = |source: io::Error| {
+            if source.kind() == io::ErrorKind::NotFound {
+                GitError::GitNotFound
+            } else {
+                GitError::SpawnError {
+                    source,
+                    command: command_str.clone(),
+                }
+            }
+        };
+        let mut child = self
+            .command(args, &[])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+        let mut stdout = child.stdout.take().ok_or_else(|| spawn_error(io::Error::other("no stdout")))?;
+        let mut stderr = child.stderr.take().ok_or_else(|| spawn_error(io::Error::other("no stderr")))?;
+        let progress = Regex::new(r"^([A-Za-z][\w ]*?):\s+(\d{1,3})% \((\d+)/(\d+)\)").expect("valid progress pattern");
+
+        let run = async {
+            let mut out = Vec::new();
+            let read_stderr = async {
+                // Progress redraws its line with `\r`, so both end a line
+                let mut kept = String::new();
+                let mut line = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = stderr.read(&mut buf).await?;
+                    let at_end = n == 0;
+                    for &byte in &buf[..n] {
+                        if byte != b'\r' && byte != b'\n' {
+                            line.push(byte);
+                            continue;
+                        }
+                        let text = String::from_utf8_lossy(&line).into_owned();
+                        line.clear();
+                        match progress.captures(&text) {
+                            Some(caps) => on_progress(GitProgress {
+                                phase: caps[1].to_string(),
+                                percent: caps[2].parse().unwrap_or(0),
+                                done: caps[3].parse().unwrap_or(0),
+                                total: caps[4].parse().unwrap_or(0),
+                            }),
+                            None if !text.trim().is_empty() => {
+                                kept.push_str(&text);
+                                kept.push('\n');
+                            }
+                            None => {}
+                        }
+                    }
+                    if at_end {
+                        kept.push_str(&String::from_utf8_lossy(&line));
+                        return Ok::<_, io::Error>(kept);
+                    }
+                }
+            };
+            let (_, stderr) = tokio::try_join!(stdout.read_to_end(&mut out), read_stderr)?;
+            let status = child.wait().await?;
+            Ok::<_, io::Error>((status, out, stderr))
+        };
+        let (status, stdout, stderr) = timeout(PROGRESS_TIMEOUT, run)
+            .await
+            .map_err(|_| GitError::CommandFailed {
+                code: -1,
+                stderr: format!("Command timed out after {}s: {command_str}", PROGRESS_TIMEOUT.as_secs()),
+                command: command_str.clone(),
+            })?
+            .map_err(|source| GitError::SpawnError {
+                source,
+                command: command_str.clone(),
+            })?;
+
+        let stdout = String::from_utf8(stdout)?;
+        Ok((status.code().unwrap_or(-1), GitOutput { stdout, stderr }))
+    }
+
+    /// A git command with the standard environment plus `envs`.
+    fn command(&self, args: &[&str], envs: &[(&str, &str)]) -> Command {
         let mut cmd = Command::new("git");
         cmd.arg("-C")
             .arg(&self.repo_path)
             .args(args)
             .env("GIT_TERMINAL_PROMPT", "0")
             .env("LC_ALL", "C")
+            .envs(envs.iter().copied())
             .kill_on_drop(true);
+        cmd
+    }
 
-        let command_str = format!("git -C {} {}", self.repo_path.display(), args.join(" "));
+    async fn spawn(&self, args: &[&str], envs: &[(&str, &str)]) -> Result<(i32, GitOutput), GitError> {
+        let mut cmd = self.command(args, envs);
+
+        let command_str = self.command_string(args);
 
         let output = timeout(Duration::from_secs(30), cmd.output())
             .await
@@ -82,15 +272,12 @@ impl Git {
         let stdout = String::from_utf8(output.stdout)?;
         let stderr = String::from_utf8(output.stderr)?;
 
-        if output.status.success() {
-            Ok(GitOutput { stdout, stderr })
-        } else {
-            Err(GitError::CommandFailed {
-                code: output.status.code().unwrap_or(-1),
-                stderr: stderr.trim().to_string(),
-                command: command_str,
-            })
-        }
+        Ok((output.status.code().unwrap_or(-1), GitOutput { stdout, stderr }))
+    }
+
+    /// Renders the command line for error messages and logs.
+    pub(crate) fn command_string(&self, args: &[&str]) -> String {
+        format!("git -C {} {}", self.repo_path.display(), args.join(" "))
     }
 
     /// Convenience wrapper that runs a git command in a different directory
@@ -99,3 +286,19 @@ impl Git {
         Git::new(path).run(args).await
     }
 }
+
+/// The git subcommand in `args`, skipping global options such as
+/// `-c key=value`.
+fn subcommand<'a>(args: &[&'a str]) -> &'a str {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "-c" | "-C" | "--git-dir" | "--work-tree" => {
+                args.next();
+            }
+            arg if arg.starts_with('-') => {}
+            arg => return arg,
+        }
+    }
+    "git"
+}
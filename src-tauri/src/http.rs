@@ -0,0 +1,189 @@
+use std::io::{self, Read};
+
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Longest request line or header accepted, in bytes.
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+
+/// Most headers accepted in one request.
+const MAX_HEADERS: usize = 100;
+
+/// An HTTP/1.1 request, as far as Maestro's embedded servers need it.
+/// Header names are lowercased; `path` excludes the query string.
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Returns the first header named `name` (lowercase).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns a query parameter's percent-decoded value.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| percent_decode(v))
+    }
+
+    /// Returns `true` if the request carries `Authorization: Bearer <token>`.
+    pub fn has_bearer(&self, token: &str) -> bool {
+        self.header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    }
+}
+
+/// Reads the request line, headers, and body. The inner `Err` is the status
+/// to answer a malformed request with.
+pub async fn read_request<R: AsyncBufReadExt + Unpin>(
+    reader: &mut R,
+) -> io::Result<Result<Request, &'static str>> {
+    let mut line = String::new();
+    (&mut *reader)
+        .take(MAX_HEADER_BYTES as u64)
+        .read_line(&mut line)
+        .await?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(Err("400 Bad Request"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers: Vec::new(),
+        body: Vec::new(),
+    };
+
+    loop {
+        line.clear();
+        let n = (&mut *reader)
+            .take(MAX_HEADER_BYTES as u64)
+            .read_line(&mut line)
+            .await?;
+        if n == 0 || request.headers.len() == MAX_HEADERS {
+            return Ok(Err("400 Bad Request"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Ok(Err("400 Bad Request"));
+        };
+        request
+            .headers
+            .push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+
+    let content_length = match request.header("content-length").map(str::parse::<usize>) {
+        Some(Ok(len)) => len,
+        Some(Err(_)) => return Ok(Err("400 Bad Request")),
+        None => 0,
+    };
+    if content_length > MAX_BODY_BYTES {
+        return Ok(Err("413 Payload Too Large"));
+    }
+    request.body = vec![0; content_length];
+    reader.read_exact(&mut request.body).await?;
+    Ok(Ok(request))
+}
+
+/// Writes a complete response, with `body` as JSON if given, and closes the
+/// connection.
+pub async fn write_response<W: AsyncWriteExt + Unpin>(
+    writer: &mut W,
+    status: &str,
+    body: Option<&Value>,
+) -> io::Result<()> {
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    write_body(writer, status, "application/json", &body).await
+}
+
+/// Writes a complete response with a body of `content_type`, omitted if
+/// empty, and closes the connection.
+pub async fn write_body<W: AsyncWriteExt + Unpin>(
+    writer: &mut W,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    let content_type = if body.is_empty() {
+        String::new()
+    } else {
+        format!("Content-Type: {content_type}\r\n")
+    };
+    let head = format!(
+        "HTTP/1.1 {status}\r\n{content_type}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body.as_bytes()).await?;
+    writer.shutdown().await
+}
+
+/// Returns `true` for an `Origin` on this machine.
+pub fn is_local_origin(origin: &str) -> bool {
+    let host = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .unwrap_or(origin);
+    let host = host.rsplit_once(':').map_or(host, |(host, _)| host);
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Returns 32 random hex characters, for use as a bearer token.
+pub fn new_token() -> io::Result<String> {
+    let mut bytes = [0u8; 16];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Decodes `%XX` escapes and `+` as space; malformed escapes are kept as is.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Compares without exiting early, so response timing does not reveal how
+/// much of a token was right.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
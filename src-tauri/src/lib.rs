@@ -1,78 +1,487 @@
+mod agent;
+mod api;
+mod cli;
 mod commands;
 mod core;
 mod git;
+mod http;
+mod mcp;
+
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+
+use tauri::Manager;
+
+use api::ApiServer;
 
 use core::ProcessManager;
+use core::agent_launcher::AgentLauncher;
+use core::agent_output::AgentOutputPipeline;
+use core::audit::AuditLog;
+use core::auto_push::AutoPusher;
+use core::ci_checks::CheckCache;
+use core::file_stream::LineIndexCache;
+use core::fs_watcher::FsWatcher;
+use core::guardrails::{GuardrailCleanup, Guardrails};
+use core::harvest::ResultHarvester;
+use core::healthcheck;
+use core::launch::{self, LaunchRequests};
+use core::maintenance::{self, PruneScheduler};
+use core::memory_budget::MemoryBudget;
+use core::notifier::SessionNotifier;
+use core::output_links::OutputLinks;
+use core::pipeline::PipelineRunner;
+use core::pr_description::PrTemplates;
+use core::provider_usage::UsageTracker;
+use core::retention::{self, RetentionManager};
+use core::scheduler::LaunchScheduler;
+use core::scratchpad::ScratchpadStore;
+use core::scrollback::Scrollback;
+use core::session_archive::SessionArchive;
 use core::session_manager::SessionManager;
+use core::settings::SettingsStore;
+use core::snippets::SnippetLibrary;
+use core::startup::{self, StartupProfiles};
+use core::status_inference::StatusInference;
+use core::task_queue::TaskQueue;
+use core::transcript::TranscriptStore;
+use core::tray::SystemTray;
+use core::updates;
+use core::watchdog::{self, AgentWatchdog};
+use core::windows::SessionWindows;
 use core::worktree_manager::WorktreeManager;
 
 /// Entry point for the Tauri application.
 ///
-/// Registers plugins (store, dialog), injects shared state (ProcessManager,
-/// SessionManager, WorktreeManager), verifies git availability at startup
-/// (non-fatal -- logs an error but does not abort), and mounts all IPC
-/// command handlers for the terminal, git, and session subsystems.
+/// - registers single-instance first: a second launch focuses this window and forwards its
+///   arguments on `launch-request` before doing anything else
+/// - registers deep-link, with `maestro://` links carried out by `deep_link::handle`
+/// - registers the store, dialog, and notification plugins
+/// - locks the data dir against `maestro-cli` runs (see `storage::lock_data_dir`)
+/// - moves a backup restored in the last run into place (see `backup::restore`)
+/// - installs the rotating file logger and the crash report panic hook
+/// - migrates persisted state to the current formats (see `migrations`)
+/// - injects shared state: ProcessManager, SessionManager, WorktreeManager, PruneScheduler,
+///   AgentLauncher, RetentionManager, TranscriptStore, AuditLog, Guardrails, CheckCache,
+///   PrTemplates, LineIndexCache, StartupProfiles, MemoryBudget, SnippetLibrary
+/// - loads the settings
+/// - runs the environment health check (non-fatal: problems are logged and emitted on
+///   `healthcheck-report`)
+/// - starts the MCP server and provider usage tracking
+/// - hooks status inference, the agent output pipeline, link extraction, and scrollback into PTY
+///   output
+/// - hooks the launch scheduler, session archive (which also keeps scratchpads), scrollback,
+///   pipeline runner, desktop notifier, auto-push, guardrail cleanup, result harvester, task queue,
+///   agent watchdog, session windows, and worktree file watcher into session changes
+/// - starts queued tasks and the optional HTTP API
+/// - starts the background worktree prune, session cleanup, and agent stall check tasks
+/// - mounts all IPC command handlers, counting their calls in `metrics`; `logging` times them
+/// - adds the tray icon with the session summary, after which closing the main window hides it
+///   (see `SystemTray`)
+/// - applies the startup profile, if one is set (see `startup`)
+/// - checks for a newer release (see `updates`)
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    // Only the app is single-instance: `maestro-cli` boots headless backends
+    // next to it. The plugin goes first so a second launch exits before any
+    // other setup touches the data dir.
+    let builder = tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(launch::forward))
+        .plugin(tauri_plugin_deep_link::init());
+    let builder = backend(builder).manage(LaunchRequests::from_env());
+    let app = builder
+        .build(context())
+        .expect("error while running Maestro");
+    if let Err(e) = SystemTray::install(app.handle()) {
+        log::error!("Failed to create tray icon: {e}");
+    }
+    startup::apply_at_startup(app.handle());
+    updates::check_at_startup(app.handle());
+    app.run(|_, _| {});
+}
+
+/// Entry point for `export-bindings`; returns the process exit code.
+///
+/// Writes the TypeScript bindings to `src/bindings.ts` in the frontend, or
+/// with `--check` fails if the file there differs from what would be
+/// written, so CI catches bindings that were not regenerated.
+pub fn run_export_bindings(args: Vec<String>) -> i32 {
+    let path = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../src/bindings.ts"));
+    let check = match args.as_slice() {
+        [] => false,
+        [flag] if flag == "--check" => true,
+        _ => {
+            eprintln!("Usage: export-bindings [--check]");
+            return 2;
+        }
+    };
+    if !check {
+        return match export_bindings(path) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("export-bindings: {e}");
+                1
+            }
+        };
+    }
+    let result = tempfile::NamedTempFile::new()
+        .map_err(|e| e.to_string())
+        .and_then(|file| export_bindings(file.path()).map(|()| file))
+        .and_then(|file| std::fs::read_to_string(file.path()).map_err(|e| e.to_string()));
+    match (result, std::fs::read_to_string(path)) {
+        (Ok(expected), Ok(committed)) if expected == committed => 0,
+        (Ok(_), _) => {
+            eprintln!("export-bindings: {} is out of date; run `cargo run --bin export-bindings`", path.display());
+            1
+        }
+        (Err(e), _) => {
+            eprintln!("export-bindings: {e}");
+            1
+        }
+    }
+}
+
+/// Entry point for `maestro-cli`; returns the process exit code.
+///
+/// See `cli` for the commands. Commands that run sessions boot the same
+/// backend as `run`, minus the window, through `run_headless`.
+pub fn run_cli(args: Vec<String>) -> i32 {
+    cli::run(args)
+}
+
+/// Boots the backend as `run` does but without opening a window, runs
+/// `task` once setup has finished, and exits with the code it returns.
+///
+/// The webview toolkit is still initialized, so a display is required
+/// (e.g. `xvfb-run` on a server). Fails if the app or another run holds
+/// the data dir.
+fn run_headless<F, Fut>(task: F) -> i32
+where
+    F: FnOnce(tauri::AppHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = i32> + Send + 'static,
+{
+    let mut context = context();
+    context.config_mut().app.windows.clear();
+    let app = backend(tauri::Builder::default())
+        .build(context)
+        .expect("error while starting Maestro");
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let code = task(handle.clone()).await;
+        handle.exit(code);
+    });
+    app.run_return(|_, _| {})
+}
+
+fn context() -> tauri::Context<tauri::Wry> {
+    tauri::generate_context!()
+}
+
+/// Adds the backend plugins, state, and setup to `builder`. Everything that
+/// touches the data dir runs in setup, after plugins registered earlier
+/// (single-instance) have had their say.
+fn backend(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wry> {
+    builder
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
-        .manage(ProcessManager::new())
-        .manage(SessionManager::new())
-        .manage(WorktreeManager::new())
-        .setup(|_app| {
-            // Verify git is available at startup (non-blocking with timeout)
-            tauri::async_runtime::spawn(async {
-                match tokio::time::timeout(
-                    std::time::Duration::from_secs(5),
-                    verify_git_available(),
-                )
-                .await
-                {
-                    Ok(Ok(version)) => log::info!("Git available: {version}"),
-                    Ok(Err(e)) => log::error!("Git not found: {e}. Git operations will fail."),
-                    Err(_) => log::error!("Git version check timed out after 5s"),
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            core::storage::lock_data_dir()?;
+            core::logging::init();
+            core::diagnostics::install_panic_hook();
+            core::backup::apply_pending_restore();
+            core::migrations::run();
+            app.manage(ProcessManager::new());
+            app.manage(SessionManager::load());
+            app.manage(WorktreeManager::new());
+            app.manage(PruneScheduler::new());
+            app.manage(AgentLauncher::load());
+            app.manage(RetentionManager::load());
+            app.manage(TranscriptStore::new());
+            app.manage(AuditLog::load());
+            app.manage(Guardrails::load());
+            app.manage(CheckCache::new());
+            app.manage(PrTemplates::load());
+            app.manage(LineIndexCache::new());
+            app.manage(StartupProfiles::load());
+            app.manage(MemoryBudget::new());
+            app.manage(SnippetLibrary::load());
+            let settings = SettingsStore::load(app.handle().clone());
+            healthcheck::spawn_startup_check(app.handle().clone());
+            app.state::<SessionManager>().attach_app_handle(app.handle().clone());
+            app.state::<ProcessManager>()
+                .add_listener(Arc::new(StatusInference::new(app.handle().clone())));
+            app.state::<ProcessManager>()
+                .add_listener(Arc::new(AgentOutputPipeline::new(app.handle().clone())));
+            app.state::<ProcessManager>()
+                .add_listener(Arc::new(OutputLinks::new(app.handle().clone())));
+            let scrollback = Scrollback::new(app.handle().clone());
+            app.state::<ProcessManager>().add_listener(Arc::new(scrollback.clone()));
+            app.state::<SessionManager>().add_listener(Arc::new(scrollback.clone()));
+            app.manage(scrollback);
+            match mcp::start(app.handle().clone()) {
+                Ok(info) => {
+                    app.manage(info);
+                }
+                Err(e) => log::error!("Failed to start MCP server: {e}"),
+            }
+            app.manage(UsageTracker::new(app.handle().clone()));
+            let scheduler = LaunchScheduler::new(app.handle().clone());
+            scheduler.set_max_working(settings.get().max_working_sessions);
+            app.manage(settings);
+            app.state::<SessionManager>().add_listener(Arc::new(scheduler.clone()));
+            app.manage(scheduler);
+            let scratchpads = ScratchpadStore::new();
+            let archive = SessionArchive::load(scratchpads.clone());
+            app.state::<SessionManager>().add_listener(Arc::new(archive.clone()));
+            app.manage(archive);
+            app.manage(scratchpads);
+            app.state::<SessionManager>()
+                .add_listener(Arc::new(PipelineRunner::new(app.handle().clone())));
+            app.state::<SessionManager>()
+                .add_listener(Arc::new(SessionNotifier::new(app.handle().clone())));
+            app.state::<SessionManager>()
+                .add_listener(Arc::new(AutoPusher::new(app.handle().clone())));
+            app.state::<SessionManager>()
+                .add_listener(Arc::new(GuardrailCleanup::new(app.handle().clone())));
+            let harvester = ResultHarvester::new(app.handle().clone());
+            app.state::<SessionManager>().add_listener(Arc::new(harvester.clone()));
+            app.manage(harvester);
+            let tasks = TaskQueue::load(app.handle().clone());
+            app.state::<SessionManager>().add_listener(Arc::new(tasks.clone()));
+            app.manage(tasks.clone());
+            tasks.dispatch();
+            let watchdog = AgentWatchdog::load(app.handle().clone());
+            app.state::<SessionManager>().add_listener(Arc::new(watchdog.clone()));
+            app.manage(watchdog);
+            let windows = SessionWindows::new(app.handle().clone());
+            app.state::<SessionManager>().add_listener(Arc::new(windows.clone()));
+            app.manage(windows);
+            let fs_watcher = FsWatcher::new(app.handle().clone());
+            app.state::<SessionManager>().add_listener(Arc::new(fs_watcher.clone()));
+            app.manage(fs_watcher);
+            match ApiServer::load(app.handle().clone()) {
+                Ok(api) => {
+                    if let Err(e) = api.start() {
+                        log::error!("Failed to start API server: {e}");
+                    }
+                    app.manage(api);
                 }
-            });
+                Err(e) => log::error!("Failed to load API server settings: {e}"),
+            }
+            if let Some(requests) = app.try_state::<LaunchRequests>() {
+                requests.handle(app.handle());
+            }
+            maintenance::spawn_prune_task(app.handle().clone());
+            retention::spawn_cleanup_task(app.handle().clone());
+            watchdog::spawn_stall_task(app.handle().clone());
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            // PTY commands (existing)
-            commands::terminal::spawn_shell,
-            commands::terminal::write_stdin,
-            commands::terminal::resize_pty,
-            commands::terminal::kill_session,
-            // Git commands (new)
-            commands::git::git_branches,
-            commands::git::git_current_branch,
-            commands::git::git_uncommitted_count,
-            commands::git::git_worktree_list,
-            commands::git::git_worktree_add,
-            commands::git::git_worktree_remove,
-            commands::git::git_commit_log,
-            // Session commands (new)
-            commands::session::get_sessions,
-            commands::session::create_session,
-            commands::session::update_session_status,
-            commands::session::assign_session_branch,
-            commands::session::remove_session,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running Maestro");
+        .invoke_handler(counted(specta_builder().invoke_handler()))
 }
 
-async fn verify_git_available() -> Result<String, String> {
-    let output = tokio::process::Command::new("git")
-        .arg("--version")
-        .kill_on_drop(true)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run git: {e}"))?;
-
-    if output.status.success() {
-        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        Ok(version)
-    } else {
-        Err("git --version returned non-zero".to_string())
+/// Wraps the invoke handler to count every IPC command in `metrics`. The
+/// handler only starts async commands, so their durations are taken from
+/// tauri's IPC spans by `logging` instead.
+fn counted(
+    handler: impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        core::metrics::registry().command_call(invoke.message.command());
+        handler(invoke)
     }
 }
+
+/// Registers every IPC command with tauri-specta, which builds the invoke
+/// handler and the TypeScript bindings from the same list.
+fn specta_builder() -> tauri_specta::Builder<tauri::Wry> {
+    tauri_specta::Builder::<tauri::Wry>::new().commands(tauri_specta::collect_commands![
+        // PTY commands (existing)
+        commands::terminal::spawn_shell,
+        commands::terminal::write_stdin,
+        commands::terminal::broadcast_stdin,
+        commands::terminal::broadcast_stdin_to_tag,
+        commands::terminal::resize_pty,
+        commands::terminal::kill_session,
+        commands::terminal::copy_last_output,
+        commands::terminal::add_mark,
+        commands::terminal::list_marks,
+        commands::terminal::get_output_at_mark,
+        // Git commands (new)
+        commands::git::git_branches,
+        commands::git::git_current_branch,
+        commands::git::git_default_branch,
+        commands::git::git_uncommitted_count,
+        commands::git::git_worktree_list,
+        commands::git::git_worktree_add,
+        commands::git::git_worktree_remove,
+        commands::git::git_commit_log,
+        // Worktree manager commands
+        commands::worktree::worktree_create,
+        commands::worktree::worktree_create_at,
+        commands::worktree::worktree_create_start,
+        commands::worktree::get_worktree_progress,
+        commands::worktree::worktree_status_summary,
+        commands::worktree::get_repo_config,
+        commands::worktree::get_repo_config_trust,
+        commands::worktree::trust_repo_config,
+        commands::worktree::worktree_diff,
+        commands::worktree::worktree_integrate,
+        commands::worktree::check_merge_gate,
+        commands::worktree::worktree_merge_preview,
+        commands::worktree::worktree_remove,
+        commands::worktree::worktree_remove_bulk,
+        commands::worktree::list_worktree_archives,
+        commands::worktree::restore_worktree_archive,
+        commands::worktree::get_prune_interval,
+        commands::worktree::set_prune_interval,
+        commands::worktree::prune_worktrees_now,
+        commands::worktree::worktree_from_pr,
+        commands::worktree::worktree_create_unique,
+        commands::worktree::worktree_metadata,
+        commands::worktree::worktree_rename,
+        commands::worktree::worktree_publish,
+        commands::worktree::run_tests,
+        // Session commands (new)
+        commands::session::get_sessions,
+        commands::session::create_session,
+        commands::session::create_full_session,
+        commands::session::spawn_session_shell,
+        commands::session::resume_agent,
+        commands::session::import_issue,
+        commands::session::get_fork_info,
+        commands::session::add_upstream_remote,
+        commands::session::rebase_session_on_upstream,
+        commands::session::generate_pr_body,
+        commands::session::get_pr_template,
+        commands::session::set_pr_template,
+        commands::session::create_pr,
+        commands::session::list_prs,
+        commands::session::get_branch_checks,
+        commands::session::get_pr_comments,
+        commands::session::send_pr_feedback,
+        commands::session::create_dependent_session,
+        commands::session::fanout_task,
+        commands::session::compare_session_group,
+        commands::session::set_session_profile,
+        commands::session::set_session_approval_policy,
+        commands::session::set_session_auto_push,
+        commands::session::get_guardrail_policy,
+        commands::session::set_guardrail_policy,
+        commands::session::get_held_commands,
+        commands::session::resolve_held_command,
+        commands::session::update_session_status,
+        commands::session::get_session_history,
+        commands::session::get_session_activity,
+        commands::session::get_board_state,
+        commands::session::assign_session_branch,
+        commands::session::update_session_details,
+        commands::session::get_session_scratchpad,
+        commands::session::set_session_scratchpad,
+        commands::session::get_session_result,
+        commands::session::copy_between_worktrees,
+        commands::session::patch_between_worktrees,
+        commands::session::reorder_sessions,
+        commands::session::pin_session,
+        commands::session::bind_session_pty,
+        commands::session::unbind_session_pty,
+        commands::session::remove_session,
+        commands::session::destroy_session,
+        commands::session::get_session_archive,
+        commands::session::get_activity_report,
+        commands::session::get_retention_policy,
+        commands::session::set_retention_policy,
+        commands::session::get_watchdog_policy,
+        commands::session::set_watchdog_policy,
+        commands::session::preview_session_cleanup,
+        commands::session::run_session_cleanup_now,
+        commands::session::get_agent_commands,
+        commands::session::set_agent_commands,
+        commands::session::launch_session_agent,
+        commands::session::send_agent_prompt,
+        commands::session::get_transcript,
+        commands::session::search_transcripts,
+        commands::session::search_everything,
+        commands::session::get_max_working_sessions,
+        commands::session::set_max_working_sessions,
+        commands::session::get_launch_queue,
+        commands::session::get_launches_paused,
+        commands::session::set_launches_paused,
+        commands::session::get_provider_usage,
+        commands::session::get_task_queue,
+        commands::session::enqueue_task,
+        commands::session::reorder_task_queue,
+        commands::session::cancel_task,
+        commands::session::list_custom_agents,
+        commands::session::save_custom_agent,
+        commands::session::delete_custom_agent,
+        // MCP server
+        commands::mcp::get_mcp_server_info,
+        // HTTP API
+        commands::api::get_api_server,
+        commands::api::configure_api_server,
+        commands::api::regenerate_api_token,
+        // Settings
+        commands::settings::get_settings,
+        commands::settings::update_settings,
+        commands::settings::backup_app_data,
+        commands::settings::restore_app_data,
+        commands::settings::check_for_updates,
+        // Logs and diagnostics
+        commands::logs::get_recent_logs,
+        commands::logs::set_log_level,
+        commands::logs::create_diagnostic_bundle,
+        commands::logs::run_healthcheck,
+        commands::logs::get_audit_log,
+        commands::logs::get_memory_usage,
+        commands::logs::get_metrics,
+        // Launch arguments
+        commands::launch::take_launch_request,
+        commands::launch::confirm_deep_link_task,
+        // Session windows
+        commands::windows::open_session_window,
+        commands::windows::get_window_session,
+        commands::windows::attach_session_terminal,
+        // Startup profiles
+        commands::startup::list_profiles,
+        commands::startup::save_profile,
+        commands::startup::delete_profile,
+        commands::startup::load_profile,
+        commands::startup::get_startup_profile,
+        commands::startup::set_startup_profile,
+        commands::startup::take_applied_profile,
+        // Snippets
+        commands::snippets::list_snippets,
+        commands::snippets::save_snippet,
+        commands::snippets::delete_snippet,
+        commands::snippets::send_snippet,
+        // File tree
+        commands::files::list_dir,
+        commands::files::recent_files,
+        commands::files::read_file,
+        commands::files::write_file,
+        commands::files::read_file_stream,
+        commands::files::read_file_lines,
+        commands::files::diff_files,
+        commands::files::search_repo,
+        commands::files::watch_worktree,
+        commands::files::unwatch_worktree,
+        commands::files::open_in_editor,
+        commands::files::reveal_in_file_manager,
+    ])
+}
+
+/// Writes the TypeScript types of every command, its arguments, and its
+/// results, with a typed `commands` wrapper around `invoke`, to `path`.
+/// 64-bit integers are typed as `number`, which is how they arrive from
+/// `invoke`. The file is not type-checked, as its generated helpers trip the
+/// frontend's unused-code lints.
+fn export_bindings(path: &Path) -> Result<(), String> {
+    let typescript = specta_typescript::Typescript::default()
+        .header("// @ts-nocheck")
+        .bigint(specta_typescript::BigIntExportBehavior::Number);
+    specta_builder()
+        .export(typescript, path)
+        .map_err(|e| format!("Failed to export TypeScript bindings to {}: {e}", path.display()))
+}
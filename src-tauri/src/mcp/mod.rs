@@ -0,0 +1,236 @@
+mod tools;
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+
+use crate::agent;
+use crate::core::session_manager::AiMode;
+use crate::core::storage;
+use crate::http::{self, write_response};
+
+/// Current on-disk format of `mcp-server.json`.
+const SERVER_FILE_VERSION: u32 = 1;
+
+/// Longest a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Protocol revisions this server accepts; the first is offered to clients
+/// asking for one it does not know.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// How to reach the running server.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct McpServerInfo {
+    pub url: String,
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ServerFile {
+    version: u32,
+    port: u16,
+    token: String,
+}
+
+/// Starts the embedded MCP (Model Context Protocol) server, through which
+/// agents running in sessions, and any other MCP client on this machine,
+/// can list, create, read, and prompt Maestro sessions.
+///
+/// The server speaks MCP's Streamable HTTP transport in its simplest form:
+/// every JSON-RPC message is POSTed to `/mcp` and answered with a single
+/// JSON response; no event streams are offered. It listens on 127.0.0.1
+/// only, and every request must carry `Authorization: Bearer <token>`.
+/// Address and token are saved to `mcp-server.json` in the data dir,
+/// readable only by the user, and reused on the next start when possible
+/// so client configurations keep working.
+///
+/// Binds synchronously and serves in the background. Call from `setup`
+/// before any shell is spawned and manage the returned info, so
+/// `agent_env` can hand the address to agent sessions.
+pub fn start(app_handle: AppHandle) -> io::Result<McpServerInfo> {
+    let path = storage::data_dir().join("mcp-server.json");
+    let previous = match storage::load_json::<ServerFile>(&path) {
+        Ok(Some(file)) if file.version <= SERVER_FILE_VERSION => Some(file),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Failed to read {}: {e}", path.display());
+            None
+        }
+    };
+
+    let listener = previous
+        .as_ref()
+        .and_then(|file| std::net::TcpListener::bind(("127.0.0.1", file.port)).ok())
+        .map(Ok)
+        .unwrap_or_else(|| std::net::TcpListener::bind(("127.0.0.1", 0)))?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+    let token = match previous {
+        Some(file) if !file.token.is_empty() => file.token,
+        _ => http::new_token()?,
+    };
+
+    save_server_file(&path, port, &token)?;
+    let info = McpServerInfo {
+        url: format!("http://127.0.0.1:{port}/mcp"),
+        port,
+        token,
+    };
+
+    let token = info.token.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("MCP server failed to start: {e}");
+                return;
+            }
+        };
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let app = app_handle.clone();
+                    let token = token.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = serve_connection(app, stream, &token).await {
+                            log::debug!("MCP connection error: {e}");
+                        }
+                    });
+                }
+                Err(e) => log::warn!("MCP server accept failed: {e}"),
+            }
+        }
+    });
+    log::info!("MCP server listening on {}", info.url);
+    Ok(info)
+}
+
+fn save_server_file(path: &Path, port: u16, token: &str) -> io::Result<()> {
+    let file = ServerFile {
+        version: SERVER_FILE_VERSION,
+        port,
+        token: token.to_string(),
+    };
+    storage::save_json_private(path, &file)
+}
+
+/// Variables telling an agent in `mode` how to reach the server:
+/// `MAESTRO_MCP_URL` and `MAESTRO_MCP_TOKEN`. Empty unless the server is
+/// running and the agent is an MCP client (see `AgentAdapter::uses_mcp`),
+/// so other shells never see the token.
+pub fn agent_env(app: &AppHandle, mode: &AiMode) -> BTreeMap<String, String> {
+    let mut env = BTreeMap::new();
+    let uses_mcp = agent::registry().get(mode).is_some_and(|adapter| adapter.uses_mcp());
+    if let (true, Some(info)) = (uses_mcp, app.try_state::<McpServerInfo>()) {
+        env.insert("MAESTRO_MCP_URL".to_string(), info.url.clone());
+        env.insert("MAESTRO_MCP_TOKEN".to_string(), info.token.clone());
+    }
+    env
+}
+
+/// Handles one HTTP request and closes the connection.
+async fn serve_connection(app: AppHandle, stream: TcpStream, token: &str) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let read = tokio::time::timeout(REQUEST_TIMEOUT, http::read_request(&mut reader)).await;
+    let request = match read {
+        Ok(read) => match read? {
+            Ok(request) => request,
+            Err(status) => return write_response(&mut writer, status, None).await,
+        },
+        Err(_) => return write_response(&mut writer, "408 Request Timeout", None).await,
+    };
+
+    if request.path != "/mcp" {
+        return write_response(&mut writer, "404 Not Found", None).await;
+    }
+    // Browsers send an Origin; a page on another site must not reach us
+    if request.header("origin").is_some_and(|o| !http::is_local_origin(o)) {
+        return write_response(&mut writer, "403 Forbidden", None).await;
+    }
+    if !request.has_bearer(token) {
+        return write_response(&mut writer, "401 Unauthorized", None).await;
+    }
+    if request.method != "POST" {
+        return write_response(&mut writer, "405 Method Not Allowed", None).await;
+    }
+
+    let message: Value = match serde_json::from_slice(&request.body) {
+        Ok(message) => message,
+        Err(e) => {
+            let error = error_response(Value::Null, -32700, &format!("Parse error: {e}"));
+            return write_response(&mut writer, "200 OK", Some(&error)).await;
+        }
+    };
+    let response = match message {
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for message in batch {
+                if let Some(response) = handle_message(&app, message).await {
+                    responses.push(response);
+                }
+            }
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        message => handle_message(&app, message).await,
+    };
+    match response {
+        Some(response) => write_response(&mut writer, "200 OK", Some(&response)).await,
+        None => write_response(&mut writer, "202 Accepted", None).await,
+    }
+}
+
+/// Answers one JSON-RPC message; `None` for notifications.
+async fn handle_message(app: &AppHandle, message: Value) -> Option<Value> {
+    let id = message.get("id").cloned()?;
+    let Some(method) = message["method"].as_str() else {
+        return Some(error_response(id, -32600, "Invalid request"));
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match method {
+        "initialize" => {
+            let requested = params["protocolVersion"].as_str().unwrap_or_default();
+            let version = PROTOCOL_VERSIONS
+                .iter()
+                .find(|v| **v == requested)
+                .unwrap_or(&PROTOCOL_VERSIONS[0]);
+            json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "maestro", "version": env!("CARGO_PKG_VERSION") }
+            })
+        }
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools::list() }),
+        "tools/call" => {
+            let Some(name) = params["name"].as_str() else {
+                return Some(error_response(id, -32602, "Missing tool name"));
+            };
+            let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+            let (text, is_error) = match tools::call(app, name, arguments).await {
+                Ok(text) => (text, false),
+                Err(e) => (e, true),
+            };
+            json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error
+            })
+        }
+        _ => return Some(error_response(id, -32601, &format!("Method not found: {method}"))),
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn error_response(id: Value, code: i32, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
@@ -0,0 +1,89 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::core::audit::{AuditLog, AuditSource};
+use crate::core::remote_control::{self, parse_args};
+
+/// Tools recorded in the audit log, since they start agents or drive them.
+const AUDITED_TOOLS: &[&str] = &["create_session", "send_prompt"];
+
+/// Tool descriptions and input schemas, as returned by `tools/list`.
+pub(super) fn list() -> Value {
+    json!([
+        {
+            "name": "list_sessions",
+            "description": "List Maestro sessions with their status, agent, branch, and worktree.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "repo_path": { "type": "string", "description": "Only sessions in this repository." }
+                }
+            }
+        },
+        {
+            "name": "create_session",
+            "description": "Create a session on a new worktree and start an agent in it, optionally sending it a first prompt.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "repo_path": { "type": "string" },
+                    "branch": { "type": "string", "description": "Branch for the worktree; suffixed if already checked out." },
+                    "base_ref": { "type": "string", "description": "Create the branch from this ref instead of checking out an existing branch." },
+                    "agent": { "type": "string", "description": "claude, gemini, codex, plain, or a custom agent name. Defaults to claude." },
+                    "prompt": { "type": "string", "description": "Task sent to the agent once it is ready." }
+                },
+                "required": ["repo_path", "branch"]
+            }
+        },
+        {
+            "name": "read_session_output",
+            "description": "Read the recent terminal output of a session, escape sequences removed.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "session_id": { "type": "integer" },
+                    "max_bytes": { "type": "integer", "description": "Defaults to 16384." }
+                },
+                "required": ["session_id"]
+            }
+        },
+        {
+            "name": "send_prompt",
+            "description": "Type a prompt into a session's running agent and submit it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "session_id": { "type": "integer" },
+                    "text": { "type": "string" },
+                    "files": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["session_id", "text"]
+            }
+        }
+    ])
+}
+
+/// Runs a tool and returns its result as text, or an error message for
+/// the caller. Calls of `AUDITED_TOOLS` are recorded in the audit log.
+pub(super) async fn call(app: &AppHandle, name: &str, arguments: Value) -> Result<String, String> {
+    if !AUDITED_TOOLS.contains(&name) {
+        return run(app, name, arguments).await;
+    }
+    let result = run(app, name, arguments.clone()).await;
+    app.state::<AuditLog>()
+        .record(AuditSource::Mcp, None, name, arguments, &result);
+    result
+}
+
+async fn run(app: &AppHandle, name: &str, arguments: Value) -> Result<String, String> {
+    let value = match name {
+        "list_sessions" => remote_control::list_sessions(app, parse_args(arguments)?)?,
+        "create_session" => remote_control::create_session(app, parse_args(arguments)?).await?,
+        "read_session_output" => {
+            return remote_control::read_session_output(app, parse_args(arguments)?)
+        }
+        "send_prompt" => return remote_control::send_prompt(app, parse_args(arguments)?).await,
+        _ => return Err(format!("Unknown tool '{name}'")),
+    };
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
@@ -1,5 +1,6 @@
-import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useRef, useState } from "react";
+import { commands } from "@/bindings";
+import { unwrap } from "@/lib/ipc";
 import { killSession } from "@/lib/terminal";
 import { useOpenProject } from "@/lib/useOpenProject";
 import { useSessionStore } from "@/stores/useSessionStore";
@@ -69,7 +70,7 @@ function App() {
       setCurrentBranch(undefined);
       return () => {};
     }
-    invoke<string>("git_current_branch", { repoPath: activeProjectPath })
+    unwrap(commands.gitCurrentBranch(activeProjectPath))
       .then((branch) => {
         if (!cancelled) setCurrentBranch(branch);
       })
@@ -0,0 +1,3281 @@
+// @ts-nocheck
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+/**
+ * Exposes `ProcessManager::spawn_shell` to the frontend.
+ * 
+ * Validates that `cwd` (if provided) exists and is a directory before
+ * forwarding to the process manager. Returns the new session ID.
+ * The frontend should listen on `pty-output-{id}` for shell output events.
+ */
+async spawnShell(cwd: string | null) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("spawn_shell", { cwd }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ProcessManager::write_stdin` to the frontend.
+ * Sends raw text (including control sequences like `\r`) to the PTY.
+ */
+async writeStdin(sessionId: number, data: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_stdin", { sessionId, data }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sends the same input to the PTYs of several Maestro sessions (note:
+ * session IDs, not PTY IDs), e.g. "continue\r" or Ctrl-C (`\u0003`).
+ * Every session is attempted; failures are reported per session.
+ */
+async broadcastStdin(sessionIds: number[], data: string) : Promise<Result<BroadcastResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("broadcast_stdin", { sessionIds, data }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Like `broadcast_stdin`, targeting every session tagged `tag`.
+ */
+async broadcastStdinToTag(tag: string, data: string) : Promise<Result<BroadcastResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("broadcast_stdin_to_tag", { tag, data }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ProcessManager::resize_pty` to the frontend.
+ * Rejects dimensions that are zero or exceed 500 to prevent misuse.
+ */
+async resizePty(sessionId: number, rows: number, cols: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resize_pty", { sessionId, rows, cols }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ProcessManager::kill_session` to the frontend.
+ * Gracefully terminates the PTY session (SIGTERM, then SIGKILL after 3s)
+ * and clears the PTY binding of whichever Maestro session owned it.
+ */
+async killSession(sessionId: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kill_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `clipboard::copy_last_output` to the frontend.
+ * Puts the session's last command output, or its last lines of scrollback,
+ * on the system clipboard and returns what was copied.
+ */
+async copyLastOutput(sessionId: number, mode: CopyMode) : Promise<Result<CopiedOutput, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("copy_last_output", { sessionId, mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Scrollback::add_mark` to the frontend.
+ * Marks the current end of the session's output with `label`, as a
+ * waypoint to come back to with `get_output_at_mark`.
+ */
+async addMark(sessionId: number, label: string) : Promise<Result<OutputMark, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_mark", { sessionId, label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Scrollback::marks` to the frontend.
+ * Returns the session's marks, its own and those added at each command,
+ * oldest first, or none if it has no terminal.
+ */
+async listMarks(sessionId: number) : Promise<Result<OutputMark[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_marks", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Scrollback::output_from_mark` to the frontend.
+ * Returns the session's output from a mark on, up to `max_bytes`, or its
+ * transcript since the mark once the output is no longer buffered.
+ */
+async getOutputAtMark(sessionId: number, markId: number, maxBytes: number | null) : Promise<Result<MarkedOutput, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_output_at_mark", { sessionId, markId, maxBytes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::list_branches` to the frontend.
+ * Returns all local and remote branches (excluding HEAD pointer entries).
+ */
+async gitBranches(repoPath: string) : Promise<Result<BranchInfo[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_branches", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::current_branch` to the frontend.
+ * Returns the branch name, or a short commit hash if HEAD is detached.
+ */
+async gitCurrentBranch(repoPath: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_current_branch", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::default_branch` to the frontend.
+ * Returns the branch `origin/HEAD` points to, or a conventional fallback.
+ */
+async gitDefaultBranch(repoPath: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_default_branch", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::uncommitted_count` to the frontend.
+ * Returns the number of dirty files (staged + unstaged + untracked).
+ */
+async gitUncommittedCount(repoPath: string) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_uncommitted_count", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::worktree_list` to the frontend.
+ * Returns all worktrees (including the main one) with path, HEAD, and branch info.
+ */
+async gitWorktreeList(repoPath: string) : Promise<Result<WorktreeInfo[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_worktree_list", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::worktree_add` to the frontend.
+ * Creates a new worktree at `path`, optionally on a new branch from `checkout_ref`.
+ */
+async gitWorktreeAdd(repoPath: string, path: string, newBranch: string | null, checkoutRef: string | null) : Promise<Result<WorktreeInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_worktree_add", { repoPath, path, newBranch, checkoutRef }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::worktree_remove` to the frontend.
+ * Removes a worktree directory; `force` bypasses uncommitted-changes checks.
+ * Fails with `Busy` while another operation holds the worktree.
+ */
+async gitWorktreeRemove(repoPath: string, path: string, force: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_worktree_remove", { repoPath, path, force }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::commit_log` to the frontend.
+ * Returns up to `max_count` commits in topological order across all or current branch.
+ */
+async gitCommitLog(repoPath: string, maxCount: number, allBranches: boolean) : Promise<Result<CommitInfo[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("git_commit_log", { repoPath, maxCount, allBranches }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::create` to the frontend.
+ * Checks out `branch` in a new managed worktree, or creates `branch` from
+ * `base_ref` when one is given. Returns the worktree path.
+ */
+async worktreeCreate(repoPath: string, branch: string, baseRef: string | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_create", { repoPath, branch, baseRef }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::create_at` to the frontend.
+ * Creates a worktree at a commit or tag, on a new `branch` if given, else
+ * on a detached HEAD.
+ */
+async worktreeCreateAt(repoPath: string, commitIsh: string, branch: string | null) : Promise<Result<CommitWorktree, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_create_at", { repoPath, commitIsh, branch }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `worktree_progress::start_create` to the frontend.
+ * Like `worktree_create`, but returns an operation ID at once and reports
+ * the checkout's progress on `worktree-progress-{op_id}`, for repositories
+ * with many files.
+ */
+async worktreeCreateStart(repoPath: string, branch: string, baseRef: string | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_create_start", { repoPath, branch, baseRef }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `worktree_progress::progress` to the frontend.
+ * Returns the latest state of a `worktree_create_start` operation, or
+ * `None` if it is unknown.
+ */
+async getWorktreeProgress(opId: string) : Promise<Result<WorktreeProgress | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_worktree_progress", { opId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::status_summary` to the frontend.
+ * Returns dirty counts, ahead/behind, and last commit for every managed worktree.
+ */
+async worktreeStatusSummary(repoPath: string) : Promise<Result<WorktreeStatus[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_status_summary", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `RepoConfig::load` to the frontend.
+ * Returns the repository's `.maestro/config.toml`, or `None` if it has
+ * none; an invalid file is an error, so the settings panel can show why.
+ */
+async getRepoConfig(repoPath: string) : Promise<Result<RepoConfig | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_repo_config", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `repo_config::trust_status` to the frontend.
+ * Returns whether the repository's `.maestro/config.toml` is approved to
+ * run its setup, startup script, and environment, with the hash to
+ * approve.
+ */
+async getRepoConfigTrust(repoPath: string) : Promise<Result<RepoConfigTrust, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_repo_config_trust", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `repo_config::trust` to the frontend.
+ * Approves the reviewed `.maestro/config.toml` whose hash is `hash`.
+ */
+async trustRepoConfig(repoPath: string, hash: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("trust_repo_config", { repoPath, hash }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::diff` to the frontend.
+ * Returns the diffstat and changed files of a worktree relative to `base_ref`.
+ */
+async worktreeDiff(repoPath: string, wtPath: string, baseRef: string) : Promise<Result<DiffSummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_diff", { repoPath, wtPath, baseRef }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::integrate` to the frontend.
+ * Merges the worktree branch into the main checkout's current branch and,
+ * if `cleanup` is set, removes the worktree and deletes the merged branch.
+ * The repository's merge gate runs first (see `merge_gate::check`); if a
+ * check fails, nothing is merged and `MergeGateFailed` names the failures;
+ * if the branch moves after the gate, `BranchMoved`.
+ * Both checkouts are locked meanwhile (see `session_lock::OperationLock`),
+ * failing with `Busy` if another operation holds either.
+ */
+async worktreeIntegrate(repoPath: string, wtPath: string, strategy: MergeStrategy, cleanup: boolean) : Promise<Result<IntegrateResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_integrate", { repoPath, wtPath, strategy, cleanup }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `merge_gate::check` to the frontend.
+ * Runs the merge gate without merging, reporting every check. Like the
+ * gate of `worktree_integrate`, it may commit the worktree's changes and
+ * run its tests.
+ */
+async checkMergeGate(repoPath: string, wtPath: string) : Promise<Result<MergeGateReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_merge_gate", { repoPath, wtPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Git::merge_preview` to the frontend.
+ * Reports whether merging `branch` into `target` would conflict, and in
+ * which files, without touching any working tree.
+ */
+async worktreeMergePreview(repoPath: string, branch: string, target: string) : Promise<Result<MergePreview, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_merge_preview", { repoPath, branch, target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::remove` to the frontend.
+ * Force-removes a worktree; with `archive`, uncommitted changes are
+ * snapshotted first and the snapshot (if any) is returned. The worktree
+ * goes to the trash unless `permanent` is set. Fails with `Busy` while
+ * another operation holds the worktree.
+ */
+async worktreeRemove(repoPath: string, wtPath: string, archive: boolean, permanent: boolean | null) : Promise<Result<WorktreeArchive | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_remove", { repoPath, wtPath, archive, permanent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::remove_bulk` to the frontend.
+ * Removes many managed worktrees in one call with per-path results.
+ */
+async worktreeRemoveBulk(repoPath: string, paths: string[], policy: RemovePolicy, permanent: boolean | null) : Promise<Result<BulkRemoveResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_remove_bulk", { repoPath, paths, policy, permanent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::list_archives` to the frontend.
+ * Returns all worktree snapshots for the repo, newest first.
+ */
+async listWorktreeArchives(repoPath: string) : Promise<Result<WorktreeArchive[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_worktree_archives", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::restore_archive` to the frontend.
+ * Returns the path of the worktree the snapshot was restored into.
+ */
+async restoreWorktreeArchive(repoPath: string, refName: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_worktree_archive", { repoPath, refName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the automatic prune interval in seconds (0 = disabled).
+ */
+async getPruneInterval() : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_prune_interval") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets the automatic prune interval in seconds; 0 disables scheduled pruning.
+ */
+async setPruneInterval(secs: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_prune_interval", { secs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Runs a prune pass over all known repos immediately, emitting the same
+ * `worktrees-pruned` event as the scheduled task, and returns the report.
+ * With `dry_run`, nothing is removed or emitted; the report lists what
+ * would be.
+ */
+async pruneWorktreesNow(dryRun: boolean | null) : Promise<Result<PruneReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("prune_worktrees_now", { dryRun }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::create_from_pr` to the frontend.
+ * Fetches the PR/MR head into a `pr-<n>` branch and opens a managed worktree on it.
+ */
+async worktreeFromPr(repoPath: string, prNumber: number) : Promise<Result<PrWorktree, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_from_pr", { repoPath, prNumber }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::create_unique` to the frontend.
+ * Creates a worktree for `branch`, falling back to a new `<branch>-N` branch
+ * cut from it if `branch` is already checked out.
+ */
+async worktreeCreateUnique(repoPath: string, branch: string) : Promise<Result<UniqueWorktree, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_create_unique", { repoPath, branch }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::metadata` to the frontend.
+ * Returns provenance (session, base ref, creation time, last activity) for
+ * each managed worktree of the repo.
+ */
+async worktreeMetadata(repoPath: string) : Promise<Result<WorktreeMetadata[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_metadata", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::rename` to the frontend.
+ * Renames the worktree's branch and moves its directory to match. Returns
+ * the new worktree path.
+ */
+async worktreeRename(repoPath: string, wtPath: string, newBranch: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_rename", { repoPath, wtPath, newBranch }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `WorktreeManager::publish` to the frontend.
+ * Pushes the worktree branch to `remote` with upstream tracking, with
+ * `--force-with-lease` if `force_with_lease` is set (default off).
+ */
+async worktreePublish(repoPath: string, wtPath: string, remote: string, forceWithLease: boolean | null) : Promise<Result<PushResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("worktree_publish", { repoPath, wtPath, remote, forceWithLease }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `test_runner::run_tests` to the frontend.
+ * Runs the worktree's test command; listen on `test-output` for its
+ * output as it is printed. The result is also stored on the worktree's
+ * session.
+ */
+async runTests(worktreePath: string) : Promise<Result<TestRun, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_tests", { worktreePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::all_sessions` to the frontend.
+ * Returns a snapshot of all active sessions in display order, or only
+ * those of `repo_path` when given.
+ */
+async getSessions(repoPath: string | null) : Promise<Result<SessionConfig[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sessions", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::create_session` to the frontend.
+ * Registers a new session with `Starting` status. Returns an error if
+ * `repo_path` is empty, the session ID already exists, or `mode` names an
+ * unknown custom agent.
+ */
+async createSession(id: number, mode: AiMode, repoPath: string) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_session", { id, mode, repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `orchestrator::create_full_session` to the frontend.
+ * Creates the worktree, spawns its shell, and registers the session in one
+ * call, rolling back every step if any of them fails. `profile` defaults to
+ * an empty launch profile. Returns an operation ID at once; the checkout's
+ * progress and the outcome, with the new session's ID, follow on
+ * `worktree-progress-{op_id}`.
+ */
+async createFullSession(repoPath: string, branchSpec: BranchSpec, mode: AiMode, profile: LaunchProfile | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_full_session", { repoPath, branchSpec, mode, profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `orchestrator::spawn_session_shell` to the frontend.
+ * Starts a shell, startup script, and agent for a session without a PTY.
+ * `resume` (default `false`) continues the agent's last conversation.
+ */
+async spawnSessionShell(sessionId: number, resume: boolean | null) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("spawn_session_shell", { sessionId, resume }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `orchestrator::resume_agent` to the frontend.
+ * Relaunches the session's agent on its previous conversation, spawning a
+ * new shell in the same worktree if the old one is gone.
+ */
+async resumeAgent(sessionId: number) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_agent", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `issues::import_issue` to the frontend.
+ * Fetches a GitHub or GitLab issue (URL or number) and creates a session
+ * on a branch named after it, with the issue as the agent's first prompt.
+ */
+async importIssue(repoPath: string, issueRef: string, agent: string | null, baseRef: string | null) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_issue", { repoPath, issueRef, agent, baseRef }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `forks::detect_fork` to the frontend.
+ * Describes the repository's remotes: where session branches are pushed
+ * and which ref they should be diffed and rebased against.
+ */
+async getForkInfo(repoPath: string) : Promise<Result<ForkInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_fork_info", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `forks::add_upstream_remote` to the frontend.
+ * Adds and fetches the `upstream` remote, by default the repository
+ * `origin` was forked from on GitHub.
+ */
+async addUpstreamRemote(repoPath: string, url: string | null) : Promise<Result<ForkInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_upstream_remote", { repoPath, url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `forks::rebase_session_on_upstream` to the frontend.
+ * Rebases the session's branch onto the freshly fetched default branch of
+ * `upstream` (or `origin`), returning the new HEAD. Fails with `Busy`
+ * while another operation holds the session's checkout.
+ */
+async rebaseSessionOnUpstream(sessionId: number) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rebase_session_on_upstream", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `pr_description::generate_pr_body` to the frontend.
+ * Returns a title and description for the session's pull request, built
+ * from its task, commits and diffstat, to edit before `create_pr`.
+ */
+async generatePrBody(sessionId: number) : Promise<Result<PrDraft, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("generate_pr_body", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `PrTemplates::template` to the frontend.
+ */
+async getPrTemplate(repoPath: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_pr_template", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `PrTemplates::set_template` to the frontend.
+ * A missing or blank `template` restores the default.
+ */
+async setPrTemplate(repoPath: string, template: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_pr_template", { repoPath, template }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `pull_request::create_pr` to the frontend.
+ * Pushes the session's branch and opens a GitHub pull request for it with
+ * `gh`, returning its number and URL.
+ */
+async createPr(sessionId: number, title: string, body: string, draft: boolean, base: string | null) : Promise<Result<PullRequest, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_pr", { sessionId, title, body, draft, base }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `pull_request::list_prs` to the frontend.
+ * Lists the repository's GitHub pull requests (open ones by default) with
+ * CI and review state, and the session working on each branch, if any.
+ */
+async listPrs(repoPath: string, state: PrState | null) : Promise<Result<PullRequestSummary[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_prs", { repoPath, state }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `CheckCache::get` to the frontend.
+ * Returns the CI checks of the branch's head commit on GitHub, cached for
+ * a minute unless `refresh` is set, and updates the `ci_status` of the
+ * sessions on the branch.
+ */
+async getBranchChecks(repoPath: string, branch: string, refresh: boolean | null) : Promise<Result<BranchChecks, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_branch_checks", { repoPath, branch, refresh }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `pull_request::get_pr_comments` to the frontend.
+ * Returns the review threads, anchored to files and lines, and the review
+ * summaries of a GitHub pull request.
+ */
+async getPrComments(repoPath: string, prNumber: number) : Promise<Result<PrFeedback, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_pr_comments", { repoPath, prNumber }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sends the unresolved review feedback on the session's pull request (all
+ * of it with `include_resolved`) to its agent as a follow-up task, as
+ * `send_agent_prompt` does, and returns the text sent.
+ */
+async sendPrFeedback(sessionId: number, includeResolved: boolean | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_pr_feedback", { sessionId, includeResolved }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `orchestrator::create_dependent_session` to the frontend.
+ * Registers a session that starts on a new `branch`, cut from the upstream
+ * session's branch, once the upstream session reaches `Done`.
+ */
+async createDependentSession(upstreamId: number, branch: string, mode: AiMode, profile: LaunchProfile | null) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_dependent_session", { upstreamId, branch, mode, profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `orchestrator::fanout_task` to the frontend.
+ * Starts one session per variant on fresh branches from `base_ref` and
+ * sends each the same `prompt`. `group` names the comparison group.
+ */
+async fanoutTask(repoPath: string, prompt: string, variants: FanoutVariant[], group: string | null, baseRef: string | null) : Promise<Result<FanoutResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fanout_task", { repoPath, prompt, variants, group, baseRef }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `orchestrator::compare_group` to the frontend.
+ * Returns each session of a fan-out with its diffstat, for side-by-side
+ * review.
+ */
+async compareSessionGroup(group: string) : Promise<Result<VariantResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("compare_session_group", { group }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces a session's launch profile. Takes effect the next time its
+ * shell is spawned.
+ */
+async setSessionProfile(sessionId: number, profile: LaunchProfile) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_profile", { sessionId, profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces a session's auto-approval policy. Takes effect with the next
+ * permission prompt.
+ */
+async setSessionApprovalPolicy(sessionId: number, policy: ApprovalPolicy) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_approval_policy", { sessionId, policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets or clears (`None`) where the session's branch is pushed once it
+ * reaches `Done`.
+ */
+async setSessionAutoPush(sessionId: number, autoPush: AutoPushConfig | null) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_auto_push", { sessionId, autoPush }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Guardrails::policy` to the frontend.
+ */
+async getGuardrailPolicy() : Promise<Result<GuardrailPolicy, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_guardrail_policy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Guardrails::set_policy` to the frontend.
+ * Listen on `guardrail-triggered` for matching agent commands.
+ */
+async setGuardrailPolicy(policy: GuardrailPolicy) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_guardrail_policy", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Guardrails::held` to the frontend.
+ */
+async getHeldCommands() : Promise<Result<HeldCommand[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_held_commands") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Guardrails::resolve` to the frontend.
+ * `approve` lets the held agent continue; otherwise it is terminated.
+ */
+async resolveHeldCommand(sessionId: number, approve: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resolve_held_command", { sessionId, approve }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::update_status` to the frontend.
+ * Returns `false` if the session does not exist (no error raised).
+ */
+async updateSessionStatus(sessionId: number, status: SessionStatus) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_session_status", { sessionId, status }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::history` to the frontend.
+ * Returns every recorded status change with its cause, plus the seconds
+ * spent in each status.
+ */
+async getSessionHistory(sessionId: number) : Promise<Result<SessionHistory, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_history", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::activity` to the frontend.
+ * Returns created/last-output times, time in current status, and total
+ * working time for every session.
+ */
+async getSessionActivity() : Promise<Result<SessionActivity[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_activity") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `board::board_state` to the frontend.
+ * Returns every session, or only those of `repo_path` when given, with
+ * its PTY liveness, activity, and checkout status in one call.
+ */
+async getBoardState(repoPath: string | null) : Promise<Result<BoardState, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_board_state", { repoPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::assign_branch` to the frontend.
+ * Links a session to a branch and optional worktree path, and records the
+ * session as the worktree's owner in the worktree registry. Returns an error
+ * string if the session does not exist.
+ */
+async assignSessionBranch(sessionId: number, branch: string, worktreePath: string | null) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("assign_session_branch", { sessionId, branch, worktreePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::update_details` to the frontend.
+ * Updates any of title, notes, and tags; omitted fields are left unchanged.
+ */
+async updateSessionDetails(sessionId: number, patch: SessionDetailsPatch) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_session_details", { sessionId, patch }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ScratchpadStore::get` to the frontend.
+ * Returns an empty scratchpad if the session has none yet.
+ */
+async getSessionScratchpad(sessionId: number) : Promise<Result<Scratchpad, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_scratchpad", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ScratchpadStore::set` to the frontend.
+ * Meant to be called on every (debounced) edit, so saves are cheap and
+ * only touch this session's file.
+ */
+async setSessionScratchpad(sessionId: number, content: string) : Promise<Result<Scratchpad, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_scratchpad", { sessionId, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ResultHarvester::get` to the frontend.
+ * Returns the result collected when the session last reached `Done`, or
+ * `None` if it has not yet.
+ */
+async getSessionResult(sessionId: number) : Promise<Result<SessionResult | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_result", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `handoff::copy_between_worktrees` to the frontend.
+ * Copies `paths`, relative to the worktree root, from one session's
+ * worktree into another's, reporting each path that failed.
+ */
+async copyBetweenWorktrees(srcSession: number, destSession: number, paths: string[]) : Promise<Result<CopyHandoff, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("copy_between_worktrees", { srcSession, destSession, paths }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `handoff::patch_between_worktrees` to the frontend.
+ * Applies the source session's changes, limited to `paths` if given, to
+ * the destination's worktree with `git apply --3way`.
+ */
+async patchBetweenWorktrees(srcSession: number, destSession: number, paths: string[] | null) : Promise<Result<PatchHandoff, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("patch_between_worktrees", { srcSession, destSession, paths }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::reorder` to the frontend.
+ * Takes session IDs in the desired order and returns the full, reordered list.
+ */
+async reorderSessions(ids: number[]) : Promise<Result<SessionConfig[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reorder_sessions", { ids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::set_pinned` to the frontend.
+ */
+async pinSession(sessionId: number, pinned: boolean) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pin_session", { sessionId, pinned }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::bind_pty` to the frontend.
+ * Records which PTY runs the session's terminal.
+ */
+async bindSessionPty(sessionId: number, ptyId: number) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("bind_session_pty", { sessionId, ptyId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::unbind_pty` to the frontend.
+ */
+async unbindSessionPty(sessionId: number) : Promise<Result<SessionConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unbind_session_pty", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionManager::remove_session` to the frontend.
+ * If the session has a bound PTY it is killed as well, so removing a
+ * session never leaves an orphaned shell behind. The session is recorded
+ * in the session archive. Returns the removed session config, or `None` if
+ * it was not found.
+ */
+async removeSession(sessionId: number) : Promise<Result<SessionConfig | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `orchestrator::destroy_session` to the frontend.
+ * Kills the session's PTY, optionally removes its worktree and deletes its
+ * branch, and removes the session, reporting which steps failed. The
+ * worktree goes to the trash unless `permanent` is set. Fails with `Busy`
+ * while another operation holds the session's checkout.
+ */
+async destroySession(sessionId: number, removeWorktree: boolean, deleteBranch: boolean, permanent: boolean | null) : Promise<Result<SessionTeardown, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("destroy_session", { sessionId, removeWorktree, deleteBranch, permanent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionArchive::query` to the frontend.
+ * Returns removed sessions matching `filter`, most recently ended first.
+ */
+async getSessionArchive(filter: ArchiveFilter | null) : Promise<Result<ArchivedSession[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_archive", { filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `activity_report::activity_report` to the frontend.
+ * `range` defaults to the last 30 days in UTC.
+ */
+async getActivityReport(range: ReportRange | null) : Promise<Result<ActivityReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_activity_report", { range }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `RetentionManager::policy` to the frontend.
+ */
+async getRetentionPolicy() : Promise<Result<RetentionPolicy, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_retention_policy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `RetentionManager::set_policy` to the frontend.
+ * Takes effect immediately; the cleanup task runs a pass right away if the
+ * new policy is active.
+ */
+async setRetentionPolicy(policy: RetentionPolicy) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_retention_policy", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentWatchdog::policy` to the frontend.
+ */
+async getWatchdogPolicy() : Promise<Result<WatchdogPolicy, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_watchdog_policy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentWatchdog::set_policy` to the frontend.
+ * Listen on `agent-watchdog` for crashed and stalled agents.
+ */
+async setWatchdogPolicy(policy: WatchdogPolicy) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_watchdog_policy", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Dry run of the retention policy: lists the sessions the next cleanup pass
+ * would remove. `policy` defaults to the saved one, so a draft can be
+ * previewed before saving it.
+ */
+async previewSessionCleanup(policy: RetentionPolicy | null) : Promise<Result<CleanupCandidate[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_session_cleanup", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Applies the saved retention policy immediately, emitting the same
+ * `sessions-cleaned` event as the background task, and returns the report.
+ */
+async runSessionCleanupNow() : Promise<Result<CleanupReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_session_cleanup_now") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentLauncher::commands` to the frontend.
+ */
+async getAgentCommands() : Promise<Result<AgentCommands, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_agent_commands") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentLauncher::set_commands` to the frontend.
+ * Replaces the per-mode command templates used to auto-launch agents,
+ * through the settings so `settings-changed` is emitted.
+ */
+async setAgentCommands(commands: AgentCommands) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_agent_commands", { commands }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LaunchScheduler::request_launch` to the frontend.
+ * Starts the agent CLI in a session whose PTY was bound by the frontend, or
+ * queues it if the working-session cap is reached. A launched outcome
+ * carries the command typed into the shell, or `None` if there was nothing
+ * to launch (no bound PTY, or a `Plain` session). `resume` (default
+ * `false`) continues the agent's last conversation where supported.
+ */
+async launchSessionAgent(sessionId: number, resume: boolean | null) : Promise<Result<LaunchOutcome, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("launch_session_agent", { sessionId, resume }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentLauncher::send_prompt` to the frontend.
+ * Types a task into the session's running agent and submits it, returning
+ * the text that was sent. `files` (default none) are referenced the way
+ * the agent understands. The prompt is recorded in the session's
+ * transcript and emitted as an agent event.
+ */
+async sendAgentPrompt(sessionId: number, text: string, files: string[] | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_agent_prompt", { sessionId, text, files }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `TranscriptStore::get` to the frontend.
+ * Returns everything recorded for the session's agent conversation.
+ */
+async getTranscript(sessionId: number) : Promise<Result<TranscriptEntry[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_transcript", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `TranscriptStore::search` to the frontend.
+ * Searches the transcripts of live and removed sessions alike.
+ */
+async searchTranscripts(query: string, limit: number | null) : Promise<Result<TranscriptMatch[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_transcripts", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `workspace_search::search_everything` to the frontend.
+ * Searches open sessions' scrollback, notes, and scratchpads, archived
+ * sessions' notes, and every transcript at once.
+ */
+async searchEverything(query: string, limit: number | null) : Promise<Result<WorkspaceHit[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_everything", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LaunchScheduler::max_working` to the frontend (0 = unlimited).
+ */
+async getMaxWorkingSessions() : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_max_working_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LaunchScheduler::set_max_working` to the frontend.
+ * The cap is saved in the settings. Raising it starts queued sessions, then
+ * queued tasks, immediately.
+ */
+async setMaxWorkingSessions(max: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_max_working_sessions", { max }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LaunchScheduler::queued` to the frontend.
+ * Returns the IDs of sessions waiting for a slot, oldest first.
+ */
+async getLaunchQueue() : Promise<Result<number[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_launch_queue") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LaunchScheduler::is_paused` to the frontend.
+ */
+async getLaunchesPaused() : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_launches_paused") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LaunchScheduler::set_paused` to the frontend.
+ * While paused, agent launches and queued tasks wait in the queue.
+ */
+async setLaunchesPaused(paused: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_launches_paused", { paused }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `UsageTracker::usage` to the frontend.
+ * Listen on `provider-usage-changed` for updates to one provider.
+ */
+async getProviderUsage() : Promise<Result<ProviderUsage[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_provider_usage") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `TaskQueue::tasks` to the frontend.
+ * Returns the tasks waiting for a slot, next first.
+ */
+async getTaskQueue() : Promise<Result<QueuedTask[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_task_queue") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `TaskQueue::enqueue` to the frontend.
+ * Queues `description` to run in a new session created as by
+ * `create_full_session`; it starts right away if a slot is free. `profile`
+ * defaults to an empty launch profile.
+ */
+async enqueueTask(description: string, repoPath: string, branchSpec: BranchSpec, mode: AiMode, profile: LaunchProfile | null) : Promise<Result<QueuedTask, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enqueue_task", { description, repoPath, branchSpec, mode, profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `TaskQueue::reorder` to the frontend.
+ * Moves the given tasks to the front in that order and returns the queue.
+ */
+async reorderTaskQueue(taskIds: number[]) : Promise<Result<QueuedTask[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reorder_task_queue", { taskIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `TaskQueue::cancel` to the frontend.
+ * Removes a task that has not started yet.
+ */
+async cancelTask(taskId: number) : Promise<Result<QueuedTask, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_task", { taskId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentLauncher::custom_agents` to the frontend.
+ */
+async listCustomAgents() : Promise<Result<CustomAgent[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_custom_agents") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentLauncher::save_custom_agent` to the frontend.
+ * Adds a custom agent or replaces the one with the same name.
+ */
+async saveCustomAgent(agent: CustomAgent) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_custom_agent", { agent }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AgentLauncher::delete_custom_agent` to the frontend.
+ * Returns `false` if no custom agent had that name.
+ */
+async deleteCustomAgent(name: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_custom_agent", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the MCP server's URL and bearer token, or `None` if it failed to
+ * start.
+ */
+async getMcpServerInfo() : Promise<Result<McpServerInfo | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_server_info") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ApiServer::status` to the frontend.
+ */
+async getApiServer() : Promise<Result<ApiServerStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_api_server") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ApiServer::configure` to the frontend.
+ */
+async configureApiServer(enabled: boolean, port: number) : Promise<Result<ApiServerStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("configure_api_server", { enabled, port }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `ApiServer::regenerate_token` to the frontend.
+ */
+async regenerateApiToken() : Promise<Result<ApiServerStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("regenerate_api_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SettingsStore::get` to the frontend.
+ */
+async getSettings() : Promise<Result<Settings, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SettingsStore::update` to the frontend.
+ * Returns the saved settings; listen on `settings-changed` for changes
+ * made elsewhere (e.g. the session cap from the session list).
+ */
+async updateSettings(settings: Settings) : Promise<Result<Settings, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `backup::backup` to the frontend.
+ */
+async backupAppData(dest: string) : Promise<Result<BackupSummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("backup_app_data", { dest }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `backup::restore` to the frontend.
+ * The restored data replaces the current data at the next start, so the
+ * frontend should offer to restart.
+ */
+async restoreAppData(src: string) : Promise<Result<BackupSummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_app_data", { src }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `updates::check` to the frontend.
+ */
+async checkForUpdates() : Promise<Result<UpdateInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_for_updates") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `logging::recent` to the frontend.
+ * Returns the latest records at `level` (default `info`) or more severe,
+ * oldest first. Older records are in the files under `logging::log_dir`.
+ */
+async getRecentLogs(level: LogLevel | null, limit: number | null) : Promise<Result<LogRecord[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_recent_logs", { level, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `logging::set_level` to the frontend.
+ * The level is saved in the settings, so it also applies after a restart;
+ * a level set through `RUST_LOG` takes precedence.
+ */
+async setLogLevel(level: LogLevel) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_log_level", { level }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `diagnostics::create_bundle` to the frontend.
+ * Returns where the zip was written, to reveal it or attach it to a bug
+ * report.
+ */
+async createDiagnosticBundle() : Promise<Result<DiagnosticBundle, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_diagnostic_bundle") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `healthcheck::run_healthcheck` to the frontend.
+ */
+async runHealthcheck() : Promise<Result<HealthReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_healthcheck") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `AuditLog::query` to the frontend.
+ * Returns the recorded commands matching `filter` (all by default), newest
+ * first.
+ */
+async getAuditLog(filter: AuditFilter | null) : Promise<Result<AuditEntry[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_audit_log", { filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `MemoryBudget::usage` to the frontend.
+ */
+async getMemoryUsage() : Promise<Result<MemoryUsage, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_memory_usage") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Metrics::snapshot` to the frontend.
+ */
+async getMetrics() : Promise<Result<MetricsSnapshot, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_metrics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LaunchRequests::take` to the frontend.
+ * Returns what Maestro's own command line asked to open, once its
+ * `maestro://` links are carried out; later launches arrive on
+ * `launch-request`.
+ */
+async takeLaunchRequest() : Promise<Result<LaunchRequest | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("take_launch_request") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `deep_link::confirm_task` to the frontend.
+ * Queues the task of a `maestro://task` link once the user accepted it.
+ */
+async confirmDeepLinkTask(proposal: TaskProposal) : Promise<Result<QueuedTask, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("confirm_deep_link_task", { proposal }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionWindows::open` to the frontend.
+ * Returns the window's label.
+ */
+async openSessionWindow(sessionId: number) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_session_window", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SessionWindows::session_of` to the frontend.
+ * Returns the session the calling window shows, or `None` for the main
+ * window.
+ */
+async getWindowSession() : Promise<Result<number | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_window_session") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `Scrollback::replay` to the frontend.
+ * Attaches the calling window to a session's terminal: the session the
+ * window shows if `session_id` is omitted, as a session window does when
+ * it opens, or the given one, as the main window does when a session
+ * window closes.
+ */
+async attachSessionTerminal(sessionId: number | null) : Promise<Result<SessionAttachment, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attach_session_terminal", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `StartupProfiles::list` to the frontend.
+ */
+async listProfiles() : Promise<Result<StartupProfile[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `StartupProfiles::save` to the frontend.
+ */
+async saveProfile(profile: StartupProfile) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_profile", { profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `StartupProfiles::delete` to the frontend.
+ */
+async deleteProfile(name: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `startup::apply` to the frontend.
+ * Starts the profile's sessions now; the frontend opens its repositories
+ * and restores its layout from the result.
+ */
+async loadProfile(name: string) : Promise<Result<AppliedProfile, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `StartupProfiles::startup` to the frontend.
+ */
+async getStartupProfile() : Promise<Result<string | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_startup_profile") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `StartupProfiles::set_startup` to the frontend.
+ */
+async setStartupProfile(name: string | null) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_startup_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `StartupProfiles::take_applied` to the frontend.
+ * Listen on `startup-profile-applied` before calling it, since the profile
+ * may still be starting.
+ */
+async takeAppliedProfile() : Promise<Result<AppliedProfile | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("take_applied_profile") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SnippetLibrary::list` to the frontend.
+ */
+async listSnippets() : Promise<Result<Snippet[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_snippets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SnippetLibrary::save` to the frontend.
+ * Pass `id` 0 to create a snippet; returns it with its ID.
+ */
+async saveSnippet(snippet: Snippet) : Promise<Result<Snippet, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_snippet", { snippet }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `SnippetLibrary::delete` to the frontend.
+ */
+async deleteSnippet(snippetId: number) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_snippet", { snippetId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `snippets::send_snippet` to the frontend.
+ * `vars` fills the snippet's placeholders (see `Snippet::placeholders`);
+ * returns the text sent.
+ */
+async sendSnippet(sessionId: number, snippetId: number, vars: Partial<{ [key in string]: string }> | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_snippet", { sessionId, snippetId, vars }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `files::list_dir` to the frontend.
+ * Lists a directory under `root` (usually a worktree) for the project
+ * tree. `path` defaults to `root` itself and `depth` to one level.
+ */
+async listDir(root: string, path: string | null, depth: number | null, filter: ListDirFilter | null) : Promise<Result<DirListing, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_dir", { root, path, depth, filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `files::recent_files` to the frontend.
+ * Returns the files changed on disk most recently in a worktree, whether
+ * or not they are staged.
+ */
+async recentFiles(worktreePath: string, since: number | null, limit: number | null) : Promise<Result<RecentFile[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("recent_files", { worktreePath, since, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `files::read_file` to the frontend.
+ * Only files inside open repositories and worktrees can be read.
+ */
+async readFile(path: string, maxBytes: number | null) : Promise<Result<FileContent, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_file", { path, maxBytes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `files::write_file` to the frontend.
+ * Only files inside open repositories and worktrees can be written.
+ */
+async writeFile(path: string, content: string) : Promise<Result<FileContent, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_file", { path, content }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LineIndexCache::read_file_stream` to the frontend.
+ * Reads a byte range of a large file; page through it from `end`.
+ */
+async readFileStream(path: string, offset: number, len: number) : Promise<Result<FileChunk, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_file_stream", { path, offset, len }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `LineIndexCache::read_file_lines` to the frontend.
+ * Reads lines of a large file by number.
+ */
+async readFileLines(path: string, startLine: number, count: number) : Promise<Result<FileLines, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_file_lines", { path, startLine, count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `files::diff_files` to the frontend.
+ * Returns the hunks between two files, tracked by git or not.
+ */
+async diffFiles(pathA: string, pathB: string) : Promise<Result<FileDiff, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_files", { pathA, pathB }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `search::search_repo` to the frontend.
+ * Listen on `search-results` for matches, tagged with `search_id`; the
+ * returned summary marks the end of the search.
+ */
+async searchRepo(searchId: number, root: string, query: string, options: SearchOptions | null) : Promise<Result<SearchSummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_repo", { searchId, root, query, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `FsWatcher::watch` to the frontend.
+ * Listen on `fs-changed-{session_id}` for the changed paths.
+ */
+async watchWorktree(sessionId: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("watch_worktree", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `FsWatcher::unwatch` to the frontend.
+ */
+async unwatchWorktree(sessionId: number) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unwatch_worktree", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `opener::open_in_editor` to the frontend.
+ * Returns the program the file was opened with.
+ */
+async openInEditor(path: string, line: number | null) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_in_editor", { path, line }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exposes `opener::reveal_in_file_manager` to the frontend.
+ */
+async revealInFileManager(path: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reveal_in_file_manager", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * Activity per day over a range, oldest day first with every day present,
+ * and the totals over the range. `errors` lists branches whose commits
+ * could not be counted, e.g. because they were deleted before their
+ * commits were recorded in the session archive.
+ */
+export type ActivityReport = { since: number; until: number; utc_offset_mins: number; days: DayActivity[]; total_sessions: number; total_working_secs: number; total_commits: number; total_cost_usd: number; errors: string[] }
+/**
+ * Shell command line used to start each agent CLI, typed into the session's
+ * shell so the user lands back at a prompt when the agent exits.
+ * 
+ * Templates may reference `{session_id}`, `{branch}`, and `{worktree}`;
+ * values are shell-quoted on expansion. An empty template disables
+ * auto-launch for that mode. Defaults come from each mode's `AgentAdapter`.
+ */
+export type AgentCommands = { claude: string; gemini: string; codex: string }
+/**
+ * The `[agent]` table: defaults for the sessions of the repository.
+ * 
+ * `default` is the agent started when none is chosen (`claude`,
+ * `gemini`, `codex`, `plain`, or a custom agent's name). The rest are
+ * merged into each new session's `LaunchProfile`, which wins on conflicts:
+ * its variables override `env`, its `extra_path` entries come first, and
+ * its startup script and test command replace these.
+ */
+export type AgentDefaults = { default: string | null; env: Partial<{ [key in string]: string }>; extra_path: string[]; startup_script: string | null; test_command: string | null }
+/**
+ * Which AI backend a session is configured to use.
+ * 
+ * `Plain` is a raw terminal with no AI agent attached, useful for
+ * manual shell work within a worktree. `Custom` names a user-defined agent
+ * registered with `AgentLauncher`.
+ */
+export type AiMode = "Claude" | "Gemini" | "Codex" | "Plain" | { Custom: string }
+/**
+ * The API server's settings and, while it runs, its address.
+ */
+export type ApiServerStatus = { settings: ApiSettings; listening_on: string | null }
+/**
+ * How the API server is configured. Disabled by default. It only listens
+ * on 127.0.0.1: the API speaks plain HTTP, so the token would cross the
+ * network in the clear; reach it from other devices through an SSH tunnel.
+ */
+export type ApiSettings = { enabled: boolean; port: number; token: string }
+/**
+ * Error returned by every IPC command: a machine-readable code, a
+ * human-readable message, and the values it is about (session ID, path,
+ * git command, ...) as `context`, omitted when empty.
+ * 
+ * Converts from `PtyError`, `GitError`, `io::Error`, and the plain
+ * `String` errors of subsystems that have no typed errors, so command
+ * handlers can use `?` on all of them. A `String` becomes `Failed`, so
+ * core functions whose failures the frontend tells apart (a missing
+ * session, a bad argument) return `AppError` themselves.
+ */
+export type AppError = { code: ErrorCode; message: string; context: Partial<{ [key in string]: string }> }
+/**
+ * Result of applying a profile: the profile itself, for its repositories
+ * and layout, the sessions on its branches (started now or already
+ * present), and why the others could not be started.
+ */
+export type AppliedProfile = { profile: StartupProfile; sessions: SessionConfig[]; failures: string[] }
+/**
+ * Which agent permission prompts a session answers on its own.
+ * 
+ * Rules use the form `Tool` or `Tool(pattern)`, where `pattern` matches
+ * the action's target (a command line or file path) and `*` matches any
+ * run of characters, e.g. `Read`, `Bash(npm test*)`, `Edit(src/*)`. A
+ * prompt is approved only if an `allow` rule matches, no `deny` rule
+ * does, and, for file tools, the path lies inside one of `path_scopes`.
+ * A command containing shell metacharacters (`;&|$()` and the like, or a
+ * newline) is only approved by an `allow` rule naming it exactly, so
+ * `Bash(npm test*)` does not approve `npm test && rm -rf ~`. Everything
+ * else is left to the user, so an empty policy always asks.
+ * 
+ * `path_scopes` are relative to the session's worktree unless absolute;
+ * empty means the worktree itself.
+ */
+export type ApprovalPolicy = { allow?: string[]; deny?: string[]; path_scopes?: string[] }
+/**
+ * Query for `SessionArchive::query`. Every set field must match; `repo_path`
+ * is compared component-wise and `text` is a case-insensitive substring
+ * match on title, branch, and notes. `since`/`until` bound `ended_at`
+ * (inclusive).
+ */
+export type ArchiveFilter = { status: SessionStatus | null; repo_path: string | null; branch: string | null; tag: string | null; text: string | null; since: number | null; until: number | null; limit: number | null }
+/**
+ * A commit made on a session's branch. `timestamp` is its author date in
+ * Unix seconds.
+ */
+export type ArchivedCommit = { hash: string; timestamp: number }
+/**
+ * Record of a session after it was removed.
+ * 
+ * Times are Unix seconds; `duration_secs` runs from creation to removal.
+ * `cost_usd` is `None` if the agent never reported a cost, and
+ * `transcript_path` if nothing was recorded in the session's transcript.
+ * `scratchpad_path` is where the session's scratchpad was moved on
+ * removal, `None` if it never saved one.
+ * `commits` are the commits made on the session's branch, recorded each
+ * time its agent stopped working so they still count once the branch is
+ * merged or deleted; `None` for sessions archived before they were
+ * recorded.
+ */
+export type ArchivedSession = { session_id: number; title: string | null; mode: AiMode; repo_path?: string; branch: string | null; worktree_path: string | null; tags: string[]; notes: string; final_status: SessionStatus; created_at: number; ended_at: number; duration_secs: number; working_secs: number; cost_usd?: number | null; transcript_path?: string | null; scratchpad_path?: string | null; commits?: ArchivedCommit[] | null }
+/**
+ * One recorded command. `origin` is the window label for `Window`
+ * commands, the client address for `Api` ones, and the task's name for
+ * `Automation` ones; `session_id` is taken
+ * from the parameters when they name a session. `error` is set if the
+ * command failed. `at` is a Unix epoch timestamp.
+ */
+export type AuditEntry = { at: number; source: AuditSource; origin: string | null; command: string; session_id: number | null; params: JsonValue; error: string | null }
+/**
+ * Which entries `AuditLog::query` returns. Every field set must match;
+ * `since` and `until` bound `at` inclusively.
+ */
+export type AuditFilter = { command: string | null; source: AuditSource | null; session_id: number | null; since: number | null; until: number | null; failed_only: boolean; limit: number | null }
+/**
+ * Who issued a recorded command: a window of the app, an agent through
+ * the MCP server, a client of the HTTP API, a `maestro://` link, or
+ * Maestro itself (scheduled prunes, retention cleanup, auto-push, merge
+ * cleanup, and watchdog restarts).
+ */
+export type AuditSource = "window" | "mcp" | "api" | "deep_link" | "automation"
+/**
+ * Per-session setting to push the branch when the session reaches
+ * `Done`. With `force_with_lease`, a branch the agent rewrote (e.g. by
+ * rebasing) is pushed over its remote copy unless someone else pushed to
+ * it meanwhile; a plain force push is never made.
+ */
+export type AutoPushConfig = { remote: string; force_with_lease?: boolean }
+/**
+ * A backup written by `backup` or staged by `restore`. `files` are the
+ * paths inside the data dir.
+ */
+export type BackupSummary = { path: string; size: number; app_version: string; created_at: number; files: string[] }
+/**
+ * One session as the board draws it.
+ * 
+ * `pty_alive` is whether the session's shell is still running.
+ * `checkout` is the git state of the directory the session works in (its
+ * worktree, else its repository), or `None` if it has neither; sessions
+ * sharing a directory get the same status.
+ */
+export type BoardEntry = { session: SessionConfig; pty_alive: boolean; activity: SessionActivity; checkout: WorktreeStatus | null }
+/**
+ * Everything the session board shows, in display order. `at` is the Unix
+ * epoch time the state was gathered.
+ */
+export type BoardState = { at: number; entries: BoardEntry[] }
+/**
+ * A check run (GitHub Actions and other apps) or a commit status
+ * (older CI integrations) reported for a commit.
+ */
+export type BranchCheck = { name: string; state: CheckState; url: string | null }
+/**
+ * Checks of the head commit of a branch on GitHub. `sha` is `None` and
+ * `checks` empty if the branch has not been pushed. `fetched_at` is Unix
+ * seconds.
+ */
+export type BranchChecks = { branch: string; sha: string | null; ci: CiStatus; checks: BranchCheck[]; fetched_at: number }
+/**
+ * A local or remote branch returned by `list_branches`.
+ * 
+ * Remote branches have `is_remote = true` and names like `origin/main`.
+ * Synthetic `HEAD` pointer entries (e.g. `origin/HEAD`) are filtered out
+ * during parsing and will never appear in results.
+ */
+export type BranchInfo = { name: string; is_remote: boolean; is_current: boolean }
+/**
+ * Which branch a new session's worktree should check out.
+ * 
+ * `Existing` checks out an existing branch, `New` creates `branch` from
+ * `base_ref`, and `Unique` behaves like `Existing` but falls back to a
+ * suffixed branch (`<branch>-2`, ...) if `branch` is already in use.
+ */
+export type BranchSpec = { Existing: { branch: string } } | { New: { branch: string; base_ref: string } } | { Unique: { branch: string } }
+/**
+ * Outcome of a broadcast write to one Maestro session. `error` is set if
+ * the session does not exist, has no bound PTY, or the write failed.
+ */
+export type BroadcastResult = { session_id: number; error: string | null }
+/**
+ * Per-worktree outcome of `remove_bulk`. Exactly one of `removed`,
+ * `skipped`, or `error` describes what happened.
+ */
+export type BulkRemoveResult = { path: string; removed: boolean; skipped: boolean; archive: WorktreeArchive | null; error: string | null }
+/**
+ * How the query's case is matched. `Smart` ignores case unless the query
+ * has an uppercase letter.
+ */
+export type CaseMode = "smart" | "sensitive" | "insensitive"
+/**
+ * Output waiting in the PTY pipeline: read from the PTYs and not yet
+ * emitted (`queued_output_bytes`), and kept by `Scrollback`.
+ */
+export type ChannelDepths = { ptys: number; queued_output_bytes: number; scrollback_bytes: number }
+/**
+ * Result of one check run or commit status.
+ */
+export type CheckState = "pending" | "passed" | "failed" | 
+/**
+ * Neutral or skipped; counts as neither passed nor failed.
+ */
+"skipped"
+/**
+ * Outcome of one check, from best to worst.
+ */
+export type CheckStatus = "ok" | "warning" | "error"
+/**
+ * Combined result of a pull request's or branch's checks and commit
+ * statuses; see `ci_checks::roll_up`. `None` if it has none.
+ */
+export type CiStatus = "none" | "pending" | "passing" | "failing"
+/**
+ * A `Done` session the policy would remove. `done_since` is a Unix epoch
+ * timestamp.
+ */
+export type CleanupCandidate = { session_id: number; title: string | null; branch: string | null; worktree_path: string | null; done_since: number; reason: CleanupReason }
+/**
+ * Which rule made a session eligible for cleanup.
+ */
+export type CleanupReason = "Expired" | "Merged"
+/**
+ * Payload of the `sessions-cleaned` event. `ran_at` is a Unix epoch timestamp.
+ */
+export type CleanupReport = { ran_at: number; results: CleanupResult[] }
+/**
+ * Outcome of cleaning up one candidate. `error` is set if the worktree
+ * could not be removed; the session itself is removed either way.
+ */
+export type CleanupResult = { candidate: CleanupCandidate; worktree_removed: boolean; error: string | null }
+/**
+ * A single commit entry parsed from `git log` output.
+ * 
+ * `parent_hashes` is empty for root commits and contains multiple entries
+ * for merge commits. `timestamp` is a Unix epoch value from `%at`.
+ * `summary` is the first line of the commit message (`%s`).
+ */
+export type CommitInfo = { hash: string; short_hash: string; parent_hashes: string[]; author_name: string; author_email: string; timestamp: number; summary: string }
+/**
+ * A worktree created by `create_at`: `commit` is the full hash checked
+ * out, and `branch` the new branch, or `None` for a detached HEAD.
+ */
+export type CommitWorktree = { path: string; commit: string; branch: string | null }
+/**
+ * What `copy_last_output` put on the clipboard, and with which program.
+ */
+export type CopiedOutput = { text: string; lines: number; program: string }
+/**
+ * Outcome of `copy_between_worktrees`: the paths copied, relative to the
+ * worktree roots, and those that failed.
+ */
+export type CopyHandoff = { copied: string[]; failed: HandoffFailure[] }
+/**
+ * What `copy_last_output` copies.
+ */
+export type CopyMode = 
+/**
+ * Output of the last command, found by shell-integration markers or
+ * shell prompts (see `Scrollback::last_command_output`).
+ */
+{ kind: "last_command" } | 
+/**
+ * The last `count` lines of scrollback, trailing blank lines dropped.
+ */
+{ kind: "lines"; count: number }
+/**
+ * A user-defined agent, selected with `AiMode::Custom(name)`.
+ * 
+ * `command` is the executable; each entry of `args` is a template with the
+ * same placeholders as `AgentCommands` and becomes one shell-quoted
+ * argument. `env` is set for the agent process only. `prompt_patterns` are
+ * regexes that, when matched near the end of the output, mark the session
+ * as `NeedsInput` in addition to the built-in patterns.
+ */
+export type CustomAgent = { name: string; command: string; args?: string[]; env?: Partial<{ [key in string]: string }>; prompt_patterns?: string[] }
+/**
+ * What happened on one day. `date` is `YYYY-MM-DD`.
+ * 
+ * `sessions_started` and `sessions_ended` count sessions created and
+ * removed that day. `working_secs` is time agents spent `Working`,
+ * `commits` the commits made on session branches (by author date), and
+ * `cost_usd` the agent cost reported that day.
+ */
+export type DayActivity = { date: string; sessions_started: number; sessions_ended: number; working_secs: number; commits: number; cost_usd: number }
+/**
+ * What a handled deep link leaves for the frontend to show.
+ * 
+ * The links are:
+ * - `maestro://open?repo=<path>` opens a repository (`OpenRepo`).
+ * - `maestro://session/<id>` brings a session into view (`OpenSession`).
+ * - `maestro://task?repo=<path>&prompt=<text>` proposes a task
+ * (`TaskProposed`), which is only queued once the user confirms it with
+ * `confirm_task`: any web page can open such a link. `repo` must be a
+ * repository Maestro already knows (see `is_known_repo`). Optional
+ * `agent` (`claude` by default, as for `remote_control::create_session`),
+ * `branch` (`task-<prompt slug>` by default), and `base` (the default
+ * branch) pick where it runs; a new branch is cut from `base`, an
+ * existing one is checked out.
+ */
+export type DeepLink = { action: "open_repo"; repo_path: string } | { action: "open_session"; session_id: number } | { action: "task_proposed"; proposal: TaskProposal }
+/**
+ * A diagnostic bundle written by `create_bundle`. `files` are the paths
+ * inside the zip.
+ */
+export type DiagnosticBundle = { path: string; size: number; files: string[] }
+/**
+ * A single changed file between a base commit and the working tree.
+ * 
+ * `status` is git's one-letter status code (`A`, `M`, `D`, `R`, `C`, `T`),
+ * or `?` for untracked files. `old_path` is only set for renames and copies.
+ * `additions`/`deletions` are `None` for binary files.
+ */
+export type DiffFile = { path: string; old_path: string | null; status: string; additions: number | null; deletions: number | null }
+/**
+ * A hunk of a unified diff, as introduced by its `@@ -a,b +c,d @@` line.
+ * `section` is the text git printed after the range (usually the
+ * enclosing function), if any.
+ */
+export type DiffHunk = { old_start: number; old_lines: number; new_start: number; new_lines: number; section: string | null; lines: DiffLine[] }
+/**
+ * One line of a hunk, without its `+`/`-`/space prefix. Line numbers are
+ * 1-based; `old_line` is `None` for added lines and `new_line` for
+ * removed ones.
+ */
+export type DiffLine = { kind: DiffLineKind; old_line: number | null; new_line: number | null; text: string }
+/**
+ * Role of a line in a hunk.
+ */
+export type DiffLineKind = "context" | "added" | "removed"
+/**
+ * Aggregate diffstat totals, equivalent to the last line of `git diff --stat`.
+ */
+export type DiffStat = { files_changed: number; insertions: number; deletions: number }
+/**
+ * Changes between a base ref and the current working tree, including
+ * uncommitted and untracked files.
+ * 
+ * `merge_base` is the commit the comparison starts from (the point the
+ * branch was cut from `base_ref`), so commits that landed on the base after
+ * the branch was created do not show up as changes.
+ */
+export type DiffSummary = { base_ref: string; merge_base: string; files: DiffFile[]; stat: DiffStat }
+/**
+ * A directory listing. `truncated` is set if `MAX_ENTRIES` was reached.
+ */
+export type DirListing = { path: string; entries: FileEntry[]; truncated: boolean }
+/**
+ * What a directory entry is. Symlinks are not followed.
+ */
+export type EntryKind = "file" | "dir" | "symlink"
+/**
+ * Discriminant for `AppError`, serialized to the frontend so it can branch
+ * on the kind of failure of any command. PTY and git errors keep their own
+ * kinds; `Failed` covers everything not yet given one.
+ */
+export type ErrorCode = "SessionNotFound" | "InvalidArgument" | "NotFound" | "AlreadyExists" | "Io" | "SpawnFailed" | "WriteFailed" | "ResizeFailed" | "KillFailed" | "SignalFailed" | "IdOverflow" | "GitNotFound" | "GitCommandFailed" | "GitKilled" | "GitSpawnFailed" | "GitInvalidOutput" | "NotARepo" | "BranchAlreadyCheckedOut" | "NamesExhausted" | "WorktreeNotFound" | "UnmanagedPath" | "TrashFailed" | "ArchiveNotFound" | "DetachedHead" | "DirtyWorkingTree" | "MergeConflict" | "MergeGateFailed" | "BranchMoved" | "Busy" | "Failed"
+/**
+ * Outcome of `fanout_task`. `errors` lists the variants that could not be
+ * started; the others keep running.
+ */
+export type FanoutResult = { group: string; base_ref: string; sessions: SessionConfig[]; errors: string[] }
+/**
+ * One agent configuration in a `fanout_task` comparison. Model choices
+ * and other agent settings go in `profile`, e.g. an environment variable
+ * the agent reads. `label` names the variant in the session title.
+ */
+export type FanoutVariant = { mode: AiMode; profile?: LaunchProfile; label?: string | null }
+/**
+ * Bytes of a chunk returned by `read_file_stream`. `text` is decoded
+ * lossily; `offset` and `end` are moved off UTF-8 continuation bytes so a
+ * character is never split between chunks, and the next chunk starts at
+ * `end`. `first_line` is the 0-based line `offset` falls on.
+ */
+export type FileChunk = { path: string; offset: number; end: number; size: number; eof: boolean; first_line: number; text: string }
+/**
+ * A file's content as read by `read_file`. `content` is `None` for binary
+ * files (those with a NUL byte near the start or not valid UTF-8).
+ * `truncated` is set if the file is longer than the bytes read; `size` is
+ * its full length and `modified` Unix seconds.
+ */
+export type FileContent = { path: string; size: number; modified: number | null; binary: boolean; truncated: boolean; content: string | null }
+/**
+ * Differences between two files. `hunks` is empty if they are identical
+ * or `binary`; `stat` counts one changed file if they differ.
+ */
+export type FileDiff = { old_path: string; new_path: string; binary: boolean; stat: DiffStat; hunks: DiffHunk[] }
+/**
+ * A file or directory in a listing. `path` is relative to the listing's
+ * root, with `/` separators; `modified` is Unix seconds. `children` is
+ * `None` for files, unreadable directories, and directories below the
+ * requested depth, so the tree can be expanded lazily with another
+ * `list_dir`.
+ */
+export type FileEntry = { name: string; path: string; kind: EntryKind; size: number; modified: number | null; hidden: boolean; ignored: boolean; children: FileEntry[] | null }
+/**
+ * Lines returned by `read_file_lines`, without line terminators.
+ * `total_lines` counts the lines indexed so far, which is all of them as
+ * of the call. A line longer than `MAX_CHUNK_BYTES` is cut, and
+ * `next_offset` then points into it.
+ */
+export type FileLines = { path: string; start_line: number; lines: string[]; total_lines: number; size: number; next_offset: number }
+/**
+ * Remote layout of a repository.
+ * 
+ * In a fork setup, `origin` is the user's fork, which session branches
+ * are pushed to, and `upstream` the original project, which they are
+ * diffed and rebased against: `base_ref` (e.g. `upstream/main`) is the
+ * ref to use as their base. Without `upstream`, `origin` plays both roles.
+ * `missing_upstream_url` is set when GitHub reports `origin` as a fork but
+ * no `upstream` remote is configured; see `add_upstream_remote`.
+ */
+export type ForkInfo = { remotes: RemoteInfo[]; is_fork: boolean; push_remote: string | null; base_remote: string | null; base_branch: string | null; base_ref: string | null; missing_upstream_url: string | null }
+/**
+ * Result of one check, with what it found.
+ */
+export type GateCheck = { check: GateCheckKind; status: GateStatus; detail: string }
+/**
+ * A check of the merge gate.
+ */
+export type GateCheckKind = 
+/**
+ * Neither checkout has uncommitted changes.
+ */
+"clean" | 
+/**
+ * The branch merges into the target without conflicts.
+ */
+"conflicts" | 
+/**
+ * The configured lint command succeeds.
+ */
+"lint" | 
+/**
+ * The test command succeeds.
+ */
+"tests"
+export type GateStatus = "passed" | "failed" | 
+/**
+ * Not configured, or not run because an earlier check failed.
+ */
+"skipped"
+/**
+ * A progress line git printed, like `Updating files:  45% (4500/10000)`.
+ */
+export type GitProgress = { 
+/**
+ * What git is doing, e.g. `Updating files` or `Receiving objects`.
+ */
+phase: string; percent: number; done: number; total: number }
+/**
+ * Commands agents must not run without the user looking first.
+ * 
+ * With `block_rm_outside_worktree`, a recursive or forced `rm` of a path
+ * outside the session's worktree (absolute, under `~`, or escaping with
+ * `..`) matches. With `protected_branches` non-empty, a forced `git push`
+ * to one of them matches; a push without a refspec counts as a push of
+ * the session's branch. With `block_pipe_to_shell`, piping `curl` or
+ * `wget` output into a shell matches. `patterns` are extra regexes matched
+ * against the whole command. Nothing is checked unless `enabled`.
+ * Fields missing from a saved policy take their default values.
+ */
+export type GuardrailPolicy = { enabled: boolean; block_rm_outside_worktree: boolean; protected_branches: string[]; block_pipe_to_shell: boolean; patterns: string[] }
+/**
+ * A path that could not be handed off, and why.
+ */
+export type HandoffFailure = { path: string; error: string }
+/**
+ * One check of `run_healthcheck`. `name` identifies it (`git`,
+ * `agent:claude`, ...); `detail` is the version found or what is wrong.
+ */
+export type HealthCheck = { name: string; status: CheckStatus; detail: string }
+/**
+ * All checks of `run_healthcheck`; `status` is the worst of them.
+ */
+export type HealthReport = { status: CheckStatus; checks: HealthCheck[] }
+/**
+ * An agent stopped by a guardrail, waiting for the user's decision.
+ */
+export type HeldCommand = { session_id: number; command: string; rule: string; held_at: number }
+/**
+ * Where a hit was found.
+ */
+export type HitSource = 
+/**
+ * Recent terminal output of an open session.
+ */
+"scrollback" | 
+/**
+ * A session's notes, open or archived.
+ */
+"notes" | 
+/**
+ * An open session's scratchpad.
+ */
+"scratchpad" | 
+/**
+ * An agent transcript, of an open or removed session.
+ */
+"transcript"
+/**
+ * Outcome of `integrate`: which branch landed where, and the resulting HEAD
+ * of the target branch in the main checkout. `cleanup_error` says why a
+ * requested cleanup did not finish. `gate` is the merge gate the merge
+ * passed, if one ran (see `merge_gate`).
+ */
+export type IntegrateResult = { branch: string; target_branch: string; strategy: MergeStrategy; head: string; cleaned_up: boolean; cleanup_error: string | null; gate: MergeGateReport | null }
+/**
+ * The issue a session was created from, kept so the pull request for its
+ * branch can refer to it.
+ */
+export type IssueLink = { tracker: IssueTracker; number: number; url: string }
+/**
+ * Where an issue lives.
+ */
+export type IssueTracker = "github" | "gitlab"
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * Result of asking the scheduler to start a session's agent.
+ * 
+ * `Launched` carries the command typed into the shell (`None` if the mode
+ * has nothing to launch); `Queued` carries the 1-based queue position.
+ */
+export type LaunchOutcome = { Launched: string | null } | { Queued: number }
+/**
+ * Environment applied when a session's shell is spawned.
+ * 
+ * `env` is set on the shell process and inherited by the agent;
+ * `extra_path` entries are prepended to `PATH` in order. `startup_script`
+ * is typed into the shell before the agent is launched. `test_command` is
+ * run in the worktree, with the same environment, to harvest the session's
+ * result once it is `Done`. Note that login profiles which reset `PATH`
+ * unconditionally will override `extra_path`.
+ */
+export type LaunchProfile = { env?: Partial<{ [key in string]: string }>; extra_path?: string[]; startup_script?: string | null; test_command?: string | null }
+/**
+ * What a launch of Maestro asked the frontend to open: repositories given
+ * on its command line, and what its `maestro://` links left to show once
+ * the backend carried them out.
+ */
+export type LaunchRequest = { repo_paths: string[]; links: DeepLink[] }
+/**
+ * Which entries `list_dir` skips. Hidden entries are those whose name
+ * starts with `.`; ignored ones are excluded by the repository's ignore
+ * rules. Both are skipped unless asked for.
+ */
+export type ListDirFilter = { show_hidden: boolean; show_ignored: boolean }
+/**
+ * Severity of a log record, most severe first. Filtering by a level keeps
+ * it and everything more severe.
+ */
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace"
+/**
+ * A log record as kept for `recent`. `at_ms` is Unix time in
+ * milliseconds; `message` includes any structured fields as `key=value`.
+ */
+export type LogRecord = { at_ms: number; level: LogLevel; target: string; message: string }
+/**
+ * Output of a PTY from a mark on, up to a size limit; `truncated` is set
+ * if more output follows. Once the output has left the buffer, `text` is
+ * the session's agent transcript since the mark was added instead, and
+ * `from_transcript` is set.
+ */
+export type MarkedOutput = { mark: OutputMark; text: string; truncated: boolean; from_transcript: boolean }
+/**
+ * How to reach the running server.
+ */
+export type McpServerInfo = { url: string; port: number; token: string }
+/**
+ * Bytes of PTY output held in memory, against the budget.
+ * 
+ * `channel_bytes` is output read from the PTYs and not yet emitted to the
+ * frontend; `scrollback_bytes` is what `Scrollback` keeps. `trimmed_ptys`
+ * lists the PTYs whose scrollback was trimmed, oldest activity first
+ * (empty unless this comes with `memory-budget-exceeded`).
+ */
+export type MemoryUsage = { channel_bytes: number; scrollback_bytes: number; budget_bytes: number; trimmed_ptys: number[] }
+/**
+ * The `[merge_gate]` table: checks `worktree_integrate` runs before
+ * merging a worktree (see `merge_gate`).
+ * 
+ * Uncommitted changes in the worktree are committed with
+ * `auto_commit_message` if `auto_commit` is set, and block the merge
+ * otherwise. `require_tests` runs `agent.test_command`, and
+ * `lint_command` is run in the worktree if set; both must succeed.
+ */
+export type MergeGateConfig = { auto_commit: boolean; auto_commit_message: string | null; require_tests: boolean; lint_command: string | null }
+/**
+ * Results of the merge gate, in the order the checks ran. `passed` is
+ * set if none failed. `head` is the branch commit that was checked.
+ */
+export type MergeGateReport = { branch: string; target_branch: string; head: string; passed: boolean; checks: GateCheck[] }
+/**
+ * Result of a dry-run merge computed by `merge_preview`.
+ * 
+ * `files` lists paths that would conflict; it is empty when the merge is
+ * clean. Nothing in any working tree or index is touched to produce this.
+ */
+export type MergePreview = { branch: string; target: string; conflicts: boolean; files: string[] }
+/**
+ * How a branch is brought into its target by `merge_branch`.
+ * 
+ * `FastForward` refuses to create a merge commit and fails if the histories
+ * have diverged. `Merge` always records a merge commit (`--no-ff`) so the
+ * branch remains visible in the graph.
+ */
+export type MergeStrategy = "FastForward" | "Merge"
+/**
+ * Everything `Metrics` measured, sorted by name within each list.
+ * 
+ * `events` counts the terminal events emitted by the PTY pipeline
+ * (`pty-output`, `pty-exit`) and the per-session events routed by
+ * `windows::emit_to_session`, by name without the PTY ID. `command_calls`
+ * counts every IPC command invoked; `commands` times every IPC command
+ * from the request to the response, counting error responses as
+ * failures. `git_commands` times git subprocesses by subcommand.
+ */
+export type MetricsSnapshot = { uptime_secs: number; events: RateStats[]; command_calls: RateStats[]; commands: TimingStats[]; git_commands: TimingStats[]; channels: ChannelDepths }
+/**
+ * A named position in a PTY's output. `offset` counts bytes of
+ * escape-stripped output since the PTY started, so it stays valid as old
+ * output is dropped; `at` is a Unix epoch timestamp. `auto` marks are added
+ * where a command's output starts, labelled with the command line: at
+ * `OUTPUT_START_MARKER` if the shell emits it, else after a line typed at
+ * a shell prompt (see `SHELL_PROMPT_PATTERN`).
+ */
+export type OutputMark = { id: number; label: string; offset: number; at: number; auto: boolean }
+/**
+ * Outcome of `patch_between_worktrees`. `files` are the paths the patch
+ * touched; `conflicts` those left with conflict markers in the
+ * destination, to be resolved there.
+ */
+export type PatchHandoff = { base_ref: string; files: string[]; conflicts: string[] }
+/**
+ * One comment of a review thread or of a review's summary.
+ * `author_association` is the author's relation to the repository, as
+ * GitHub reports it (`OWNER`, `MEMBER`, `COLLABORATOR`, `CONTRIBUTOR`,
+ * `NONE`, ...).
+ */
+export type PrComment = { author: string; author_association?: string; body: string; created_at: string; url: string }
+/**
+ * A generated pull request title and description, to be edited before
+ * being passed to `pull_request::create_pr`. `base` is the ref the branch
+ * was compared with.
+ */
+export type PrDraft = { session_id: number; title: string; body: string; base: string }
+/**
+ * Reviewer feedback on a pull request, returned by `get_pr_comments`.
+ */
+export type PrFeedback = { number: number; threads: ReviewThread[]; reviews: PrReview[] }
+/**
+ * A submitted review with a summary. `state` is `approved`,
+ * `changes_requested`, or `commented`; `author_association` is as for
+ * `PrComment`.
+ */
+export type PrReview = { author: string; author_association: string; state: string; body: string; submitted_at: string | null }
+/**
+ * Which pull requests `list_prs` returns.
+ */
+export type PrState = "open" | "closed" | "merged" | "all"
+/**
+ * A managed worktree provisioned for a pull/merge request by `create_from_pr`.
+ */
+export type PrWorktree = { pr_number: number; branch: string; path: string }
+/**
+ * Usage of one agent provider since Maestro started, as reported by its
+ * sessions' output. `cooldown_until` is set while new launches of the
+ * provider's agent are held back.
+ */
+export type ProviderUsage = { provider: string; runs: number; cost_usd: number; input_tokens: number; output_tokens: number; rate_limited: number; overloaded: number; quota_exceeded: number; last_throttle: ThrottleRecord | null; cooldown_until: number | null }
+/**
+ * An orphaned directory `prune` failed to delete.
+ */
+export type PruneFailure = { path: string; error: string }
+/**
+ * What `prune` removed from a repository, or with `dry_run` would have.
+ * `stale_refs` are git's descriptions of the worktree entries it pruned,
+ * `orphaned_dirs` the directories in the managed directory that belonged
+ * to no worktree, and `failed` those of them that could not be deleted.
+ */
+export type PruneOutcome = { dry_run: boolean; stale_refs: string[]; orphaned_dirs: string[]; failed: PruneFailure[] }
+/**
+ * Payload of the `worktrees-pruned` event. `ran_at` is a Unix epoch timestamp.
+ */
+export type PruneReport = { ran_at: number; dry_run: boolean; repos: RepoPruneResult[] }
+/**
+ * A GitHub pull request for a session's branch. `existing` is `true` if
+ * the branch already had an open pull request, which was returned instead
+ * of a new one.
+ */
+export type PullRequest = { session_id: number; number: number; url: string; branch: string; draft: boolean; existing: boolean }
+/**
+ * A pull request of a repository, as listed by `list_prs`. `state` is
+ * `open`, `closed`, or `merged`. `session_id` is the session working on
+ * `branch`, if any.
+ */
+export type PullRequestSummary = { number: number; title: string; url: string; branch: string; base: string; author: string; state: string; draft: boolean; ci: CiStatus; review: ReviewState; session_id: number | null }
+/**
+ * Outcome of pushing a branch, parsed from `git push --porcelain`.
+ * 
+ * `flag` is git's one-character ref status (` ` fast-forward, `+` forced,
+ * `*` new ref, `=` up to date, `!` rejected). When `rejected` is true,
+ * `summary` carries git's reason (e.g. `[rejected] (non-fast-forward)`) and
+ * the upstream was not configured.
+ */
+export type PushResult = { branch: string; remote: string; remote_ref: string; tracking_ref: string; flag: string; summary: string; rejected: boolean }
+/**
+ * A task waiting to be turned into a session. `created_at` is a Unix epoch
+ * timestamp.
+ * 
+ * The session is created like `orchestrator::create_full_session` with
+ * `repo_path`, `branch_spec`, `mode`, and `profile`, and `description` is
+ * sent to its agent as the first prompt.
+ */
+export type QueuedTask = { id: number; description: string; repo_path: string; branch_spec: BranchSpec; mode: AiMode; profile?: LaunchProfile; created_at: number }
+/**
+ * How often something happened: in total, and per second over the last
+ * minute.
+ */
+export type RateStats = { name: string; total: number; per_sec: number }
+/**
+ * A file found by `recent_files`. `path` is relative to the worktree and
+ * `modified` is Unix seconds.
+ */
+export type RecentFile = { path: string; size: number; modified: number }
+/**
+ * A configured remote and its fetch URL.
+ */
+export type RemoteInfo = { name: string; url: string }
+/**
+ * How `remove_bulk` treats worktrees with uncommitted changes.
+ * 
+ * `Force` removes everything as-is, `Archive` snapshots dirty worktrees
+ * before removing them, and `SkipDirty` leaves dirty worktrees in place and
+ * reports them as skipped.
+ */
+export type RemovePolicy = "Force" | "Archive" | "SkipDirty"
+/**
+ * Per-repository settings committed in `.maestro/config.toml`, so a team
+ * shares its Maestro workflow with the code. Layered over the global
+ * settings: whatever the file leaves out falls back to them.
+ * 
+ * A cloned repository is not trusted to run code: `setup`, the startup
+ * script, `env`, and `extra_path` are ignored (see `for_repo`) until the
+ * user approves the file's current content with `trust`, as direnv does.
+ * Any change to the file needs a new approval.
+ * 
+ * ```toml
+ * [worktree]
+ * base_branch = "develop"
+ * setup = ["npm ci"]
+ * shared_dirs = [".env"]
+ * shared_dir_strategy = "copy"
+ * 
+ * [agent]
+ * default = "codex"
+ * test_command = "npm test"
+ * env = { NODE_ENV = "development" }
+ * 
+ * [merge_gate]
+ * require_tests = true
+ * lint_command = "npm run lint"
+ * ```
+ */
+export type RepoConfig = { worktree: WorktreeDefaults; agent: AgentDefaults; merge_gate: MergeGateConfig }
+/**
+ * Whether the repository's configuration may run commands: `hash` is the
+ * SHA-256 of its current `.maestro/config.toml` (`None` if it has none),
+ * and `trusted` whether the user approved exactly that content.
+ */
+export type RepoConfigTrust = { hash: string | null; trusted: boolean }
+/**
+ * Outcome of pruning a single repository: what was removed, or `error`
+ * if the prune failed. `managed_worktrees` is the number left afterwards.
+ */
+export type RepoPruneResult = { repo_path: string; managed_worktrees: number; pruned: PruneOutcome | null; error: string | null }
+/**
+ * Period of `activity_report`. `since` and `until` are Unix seconds,
+ * defaulting to the last `DEFAULT_DAYS` days up to now. Days are
+ * calendar days at `utc_offset_mins` from UTC (pass the viewer's offset,
+ * e.g. 120 for UTC+2), so they match the dashboard's local dates.
+ */
+export type ReportRange = { since: number | null; until: number | null; utc_offset_mins: number }
+/**
+ * When finished sessions are removed automatically.
+ * 
+ * A `Done` session is eligible once it has been `Done` for `max_age_secs`,
+ * or, with `when_merged`, once its branch is merged into `merge_target`
+ * (the repository's base branch if unset, see
+ * `repo_config::base_branch`). With neither rule set the policy is
+ * inactive. `remove_worktrees` also removes the session's worktree;
+ * uncommitted changes are archived first, and a failed archive keeps the
+ * worktree.
+ */
+export type RetentionPolicy = { max_age_secs?: number | null; when_merged?: boolean; merge_target?: string | null; remove_worktrees?: boolean }
+/**
+ * Review decision of a pull request. `None` if the repository requires
+ * no reviews and none were given.
+ */
+export type ReviewState = "none" | "review_required" | "approved" | "changes_requested"
+/**
+ * A conversation anchored to a diff line. `line` is the line in the
+ * current version of `path` (`None` once the thread is outdated) and
+ * `original_line` the one it was written against; a multi-line comment
+ * starts at `start_line`.
+ */
+export type ReviewThread = { id: string; path: string; line: number | null; original_line: number | null; start_line: number | null; resolved: boolean; outdated: boolean; comments: PrComment[] }
+/**
+ * A session's scratchpad document. `updated_at` is a Unix epoch timestamp,
+ * `None` if the scratchpad was never saved.
+ */
+export type Scratchpad = { content: string; updated_at: number | null }
+/**
+ * Options of `search_repo`. The query is a literal string unless `regex`
+ * is set (Rust regex syntax). `globs` limit the files searched, as rg's
+ * `--glob` does (`!` excludes). Hidden files and files excluded by ignore
+ * rules are skipped unless included.
+ */
+export type SearchOptions = { case: CaseMode; regex: boolean; whole_word: boolean; globs: string[]; include_hidden: boolean; include_ignored: boolean; max_results: number | null }
+/**
+ * Outcome of a finished search. `truncated` is set if it stopped at the
+ * result limit.
+ */
+export type SearchSummary = { search_id: number; matches: number; files: number; truncated: boolean; elapsed_ms: number }
+/**
+ * Point-in-time activity summary of a session, for spotting stuck agents.
+ * 
+ * `status_since` is when the current status was entered; `working_secs`
+ * includes the ongoing `Working` period, if any. `idle_secs` is the time
+ * since the last terminal output (or since creation if there was none).
+ */
+export type SessionActivity = { session_id: number; title: string | null; branch: string | null; status: SessionStatus; created_at: number; last_output_at: number | null; status_since: number; secs_in_status: number; working_secs: number; idle_secs: number }
+/**
+ * What a window needs to show a session's terminal it did not show
+ * before: the session and its recent raw output to replay.
+ */
+export type SessionAttachment = { session: SessionConfig; output: string }
+/**
+ * Frontend-visible configuration and state for a single session.
+ * 
+ * `branch` and `worktree_path` are `None` until `assign_branch` is called,
+ * allowing sessions to be created before their worktree is ready.
+ * `pty_id` is the `ProcessManager` session running this session's terminal;
+ * it is never persisted across restarts since PTYs do not survive them.
+ * `position` and `pinned` define the user-controlled list order: pinned
+ * sessions first, then ascending `position`, ties broken by `id`.
+ * Timestamps are Unix seconds. `last_output_at` is refreshed on terminal
+ * output without emitting an event, and `working_secs` accumulates
+ * completed periods in `Working` (the current one is added by `activity`).
+ */
+export type SessionConfig = { id: number; mode: AiMode; 
+/**
+ * Repository the session works in. Empty only for sessions persisted
+ * before repositories were tracked.
+ */
+repo_path?: string; branch: string | null; status: SessionStatus; worktree_path: string | null; pty_id?: number | null; 
+/**
+ * User-chosen display name; `None` falls back to the default label.
+ */
+title?: string | null; 
+/**
+ * Freeform user notes.
+ */
+notes?: string; 
+/**
+ * User tags, trimmed and de-duplicated, in insertion order.
+ */
+tags?: string[]; position?: number; pinned?: boolean; profile?: LaunchProfile; 
+/**
+ * Agent permission prompts answered without the user; see
+ * `status_inference`.
+ */
+approval?: ApprovalPolicy; 
+/**
+ * Where the branch is pushed once the session is `Done`; `None` keeps
+ * it local. See `auto_push`.
+ */
+auto_push?: AutoPushConfig | null; created_at?: number; last_output_at?: number | null; working_secs?: number; 
+/**
+ * Session that must reach `Done` before this one is started; see
+ * `orchestrator::create_dependent_session`.
+ */
+depends_on?: number | null; 
+/**
+ * Total agent cost in US dollars, summed over the runs whose cost the
+ * agent reported. `None` if none was reported.
+ */
+cost_usd?: number | null; 
+/**
+ * The agent's own ID for its latest conversation, used to resume it.
+ */
+agent_session_id?: string | null; 
+/**
+ * Fan-out this session belongs to; see `orchestrator::fanout_task`.
+ */
+comparison_group?: string | null; 
+/**
+ * Issue the session was created from; see `issues::import_issue`.
+ */
+issue?: IssueLink | null; 
+/**
+ * Pull request opened for the session's branch; see
+ * `pull_request::create_pr`.
+ */
+pr_number?: number | null; pr_url?: string | null; 
+/**
+ * CI result of the branch when last fetched; see `ci_checks`.
+ */
+ci_status?: CiStatus | null; 
+/**
+ * Outcome of the latest `test_runner::run_tests` in the worktree.
+ */
+test_result?: TestResult | null; 
+/**
+ * Operation currently holding the session's checkout; see
+ * `session_lock::OperationLock`.
+ */
+lock?: SessionLock | null }
+/**
+ * Partial update for the user-editable session fields. `None` leaves a
+ * field unchanged; an empty `title` clears it.
+ */
+export type SessionDetailsPatch = { title: string | null; notes: string | null; tags: string[] | null }
+/**
+ * Status history of a session plus the total seconds spent in each status,
+ * with the current status counted up to now.
+ */
+export type SessionHistory = { session_id: number; transitions: StatusTransition[]; time_in_status: Partial<{ [key in SessionStatus]: number }> }
+/**
+ * A long operation holding a session's checkout, shown on the session
+ * while it runs. `since` is a Unix epoch timestamp.
+ */
+export type SessionLock = { operation: string; since: number }
+/**
+ * What a session produced, collected when it reached `Done`.
+ * `harvested_at` is a Unix epoch timestamp.
+ * 
+ * `diff` and `patch` compare the worktree with the point its branch left
+ * `base_ref`; `commits` are the branch's commits since then, newest first.
+ * Parts that could not be collected are `None` or empty, with the reason
+ * in `errors`.
+ */
+export type SessionResult = { session_id: number; harvested_at: number; branch: string | null; base_ref: string | null; diff: DiffSummary | null; patch: string | null; patch_truncated: boolean; commits: CommitInfo[]; test: TestRun | null; cost_usd: number | null; errors: string[] }
+/**
+ * Lifecycle state of a session, tracked for UI status indicators.
+ * 
+ * Transitions are driven by the frontend and, for agent sessions, by
+ * `StatusInference` watching terminal output; the backend does not enforce
+ * a state machine. Invalid transitions (e.g., `Done` -> `Working`) are
+ * allowed and the caller is responsible for correctness. `Blocked` means
+ * the session waits for the session in `depends_on` to reach `Done`;
+ * `Queued` means the agent launch is waiting for a free slot in
+ * `LaunchScheduler`.
+ */
+export type SessionStatus = "Blocked" | "Queued" | "Starting" | "Idle" | "Working" | "NeedsInput" | "Done" | "Error"
+/**
+ * Outcome of `destroy_session`. Each flag records a step that succeeded;
+ * `errors` lists the steps that failed, in the order they ran. `archive`
+ * is the snapshot of uncommitted changes taken before the worktree was
+ * removed, if there were any.
+ */
+export type SessionTeardown = { session_id: number; pty_killed: boolean; worktree_removed: boolean; archive: WorktreeArchive | null; branch_deleted: boolean; errors: string[] }
+/**
+ * Application settings edited from the settings panel.
+ * 
+ * `worktree_base_dir` overrides where managed worktrees are created
+ * (`<data dir>/worktrees` if unset) and takes effect at the next start.
+ * It cannot be changed while the current directory holds worktrees, which
+ * would no longer be managed.
+ * `kill_grace_secs` is how long a killed shell gets to exit before it is
+ * sent SIGKILL. `max_working_sessions` caps the agents working at once (0
+ * means unlimited). `agent_commands` are the templates used to launch each
+ * agent, owned by `AgentLauncher`. `log_level` is the most verbose level
+ * logged unless `RUST_LOG` names one. `memory_budget_mb` caps the PTY
+ * output held in memory across all sessions (0 means unlimited, see
+ * `MemoryBudget`). `check_for_updates` looks for a newer release at
+ * startup. Fields missing from a saved file take their default values.
+ */
+export type Settings = { worktree_base_dir: string | null; kill_grace_secs: number; max_working_sessions: number; agent_commands: AgentCommands; log_level: LogLevel; memory_budget_mb: number; check_for_updates: boolean }
+/**
+ * How `shared_dirs` reach a new worktree: linked to the main checkout's
+ * copy (shared by every worktree), or copied (each worktree gets its own).
+ */
+export type SharedDirStrategy = "symlink" | "copy"
+/**
+ * A reusable prompt or shell snippet.
+ * 
+ * `body` may hold `{{name}}` placeholders, filled from the variables given
+ * when it is sent. `{{branch}}`, `{{repo}}`, `{{worktree}}`, and
+ * `{{session}}` default to the target session's branch, repository,
+ * worktree, and ID. `id` is assigned on creation; pass 0 to create.
+ */
+export type Snippet = { id?: number; name: string; kind: SnippetKind; body: string; description?: string | null }
+/**
+ * Whether a snippet is sent to the session's agent as a prompt or typed
+ * into its shell as commands.
+ */
+export type SnippetKind = "prompt" | "shell"
+/**
+ * A named set of repositories to open and sessions to start together.
+ * `layout` holds the frontend's window layout hints and is stored as
+ * given.
+ */
+export type StartupProfile = { name: string; repo_paths?: string[]; sessions?: StartupSession[]; layout?: JsonValue | null }
+/**
+ * A session a startup profile starts, as `remote_control::create_session`
+ * does: `agent` is `claude` (the default), `gemini`, `codex`, `plain`, or
+ * a custom agent's name; without `base_ref` an existing `branch` is
+ * checked out, with it `branch` is created from `base_ref`; `prompt`, if
+ * given, is sent to the agent once it is ready.
+ */
+export type StartupSession = { repo_path: string; branch: string; base_ref?: string | null; agent?: string | null; prompt?: string | null }
+/**
+ * What triggered a status change: session creation, a frontend call,
+ * `StatusInference` reading terminal output, the session's shell exiting,
+ * or backend scheduling (launch queue or pipeline dependencies).
+ */
+export type StatusCause = "Created" | "Manual" | "AutoDetected" | "Exit" | "Scheduled"
+/**
+ * One entry in a session's status history. `from` is `None` for the
+ * initial status recorded at creation; `at` is Unix seconds. `detail` is
+ * optional context, such as the detected prompt line or the exit code.
+ */
+export type StatusTransition = { from: SessionStatus | null; to: SessionStatus; at: number; cause: StatusCause; detail?: string | null }
+/**
+ * A task a `maestro://task` link asks to queue, for the user to confirm.
+ */
+export type TaskProposal = { repo_path: string; prompt: string; agent: string | null; branch: string | null; base: string | null }
+/**
+ * Test runner whose summary was recognized in a run's output.
+ */
+export type TestFramework = "cargo" | "pytest" | "jest"
+/**
+ * Outcome of a session's latest `run_tests`, kept on the session.
+ * `finished_at` is a Unix epoch timestamp.
+ */
+export type TestResult = { command: string; passed: boolean; exit_code: number | null; timed_out: boolean; duration_ms: number; finished_at: number; summary: TestSummary | null }
+/**
+ * Output and exit status of a test command. `exit_code` is `None` if the
+ * command was killed, by a signal or after `TEST_TIMEOUT`. `summary` is
+ * `None` if no known runner's summary was found in the output.
+ */
+export type TestRun = { command: string; exit_code: number | null; timed_out: boolean; duration_ms: number; 
+/**
+ * Combined stdout and stderr, cut from the front if too long.
+ */
+output: string; summary?: TestSummary | null }
+/**
+ * Test counts parsed from a run's output. `skipped` includes tests the
+ * runner reports as ignored or todo; pytest errors count as failed.
+ */
+export type TestSummary = { framework: TestFramework; passed: number; failed: number; skipped: number }
+/**
+ * Why a provider turned a request away.
+ */
+export type ThrottleReason = 
+/**
+ * Too many requests (HTTP 429).
+ */
+"rate_limit" | 
+/**
+ * The provider is overloaded (HTTP 529 or 503).
+ */
+"overloaded" | 
+/**
+ * A usage limit or quota is used up; lasts until it resets.
+ */
+"quota"
+/**
+ * The most recent throttling error of a provider.
+ */
+export type ThrottleRecord = { reason: ThrottleReason; message: string; session_id: number; at: number }
+/**
+ * How long something took, over every run since startup. `failures` are
+ * runs that returned an error or, for git, exited non-zero.
+ */
+export type TimingStats = { name: string; count: number; failures: number; total_ms: number; mean_ms: number; max_ms: number }
+/**
+ * One recorded event. `at` is a Unix epoch timestamp.
+ */
+export type TranscriptEntry = (
+/**
+ * A task sent to the agent with `AgentLauncher::send_prompt`.
+ */
+{ kind: "prompt"; text: string } | 
+/**
+ * The agent's own identifier for the conversation, used to resume it.
+ */
+{ kind: "agent_session"; id: string } | 
+/**
+ * Text the agent wrote to the user.
+ */
+{ kind: "assistant_text"; text: string } | 
+/**
+ * A tool invocation; `id` pairs it with its `ToolResult`.
+ */
+{ kind: "tool_call"; id: string; name: string; input: JsonValue } | 
+/**
+ * The outcome of a tool invocation. `content` may be truncated.
+ */
+{ kind: "tool_result"; tool_use_id: string; content: string; is_error: boolean } | 
+/**
+ * Cost of the agent run that just ended, in US dollars.
+ */
+{ kind: "cost"; usd: number } | 
+/**
+ * Tokens used by the agent run that just ended.
+ */
+{ kind: "usage"; input_tokens: number; output_tokens: number } | 
+/**
+ * The provider refused or deferred a request; `message` is the line
+ * that said so. `retry_after_secs` is set if the message gave a delay.
+ */
+{ kind: "throttled"; reason: ThrottleReason; message: string; retry_after_secs: number | null }) & { at: number }
+/**
+ * A transcript entry matching a search. `transcript_path` identifies the
+ * run, since session IDs are reused after removal.
+ */
+export type TranscriptMatch = { session_id: number; transcript_path: string; entry: TranscriptEntry }
+/**
+ * Branch and path of a worktree created by `create_unique`. `branch` differs
+ * from the requested name when a suffixed branch had to be created.
+ */
+export type UniqueWorktree = { branch: string; path: string }
+/**
+ * What the release feed says about the latest release. `notes` is the
+ * release's Markdown description. `download_url` is its `.deb` package,
+ * the format Maestro is bundled in, or the release page if it has none.
+ */
+export type UpdateInfo = { current_version: string; latest_version: string; update_available: boolean; name: string | null; notes: string; published_at: string | null; release_url: string; download_url: string }
+/**
+ * One session of a comparison group as shown side by side with the
+ * others. `diffstat` covers committed, uncommitted, and untracked changes
+ * since the variant's branch was cut; `error` says why it is missing.
+ */
+export type VariantResult = { session_id: number; title: string | null; mode: AiMode; branch: string | null; status: SessionStatus; cost_usd: number | null; diffstat: DiffStat | null; error: string | null }
+/**
+ * How the watchdog reacts to agents that die or go quiet.
+ * 
+ * An agent has crashed when a `Working` session drops back to its shell
+ * prompt or its shell exits with an error; it has stalled when a `Working`
+ * session prints nothing for `stall_secs` (`None` disables stall checks).
+ * Either is always reported; with `restart_on_crash` or
+ * `restart_on_stall`, the agent is also relaunched on its previous
+ * conversation in the same worktree, as by `orchestrator::resume_agent`,
+ * at most `max_restarts` times per session per hour. A stalled agent's
+ * shell is killed first. Fields missing from a saved policy take their
+ * default values.
+ */
+export type WatchdogPolicy = { stall_secs: number | null; restart_on_crash: boolean; restart_on_stall: boolean; max_restarts: number }
+/**
+ * A line matching a `search_everything` query.
+ * 
+ * `session_id`, `title`, `branch`, and `repo_path` identify the session;
+ * `live` is whether it is still open, as IDs are reused after removal.
+ * `transcript_path` and `at` (Unix epoch time of the entry) are set for
+ * transcript hits. `line` is the 1-based line within the source text (the
+ * transcript entry, for transcripts), and `context` holds the matching
+ * line with up to `CONTEXT_LINES` around it.
+ */
+export type WorkspaceHit = { source: HitSource; session_id: number; title: string | null; branch: string | null; repo_path: string; live: boolean; transcript_path: string | null; at: number | null; line: number; text: string; context: string }
+/**
+ * A snapshot of a worktree's uncommitted state, stored as a commit whose
+ * parent is the worktree's HEAD at the time of archiving.
+ * 
+ * `branch` is `None` if the worktree was detached. `created_at` is a Unix
+ * epoch timestamp.
+ */
+export type WorktreeArchive = { ref_name: string; commit: string; parent: string; branch: string | null; worktree_path: string; created_at: number }
+/**
+ * The `[worktree]` table: how new worktrees of the repository are set up.
+ * 
+ * `base_branch` is the branch new branches start from when none is given
+ * (the repository's default branch if unset). `shared_dirs` are paths,
+ * relative to the root, brought over from the main checkout, e.g.
+ * `node_modules` or an untracked `.env`. `setup` commands are typed into
+ * the session's shell, in order, before its startup script and agent.
+ */
+export type WorktreeDefaults = { base_branch: string | null; setup: string[]; shared_dirs: string[]; shared_dir_strategy: SharedDirStrategy }
+/**
+ * Metadata for a single git worktree, parsed from `git worktree list --porcelain`.
+ * 
+ * `branch` is `None` for detached HEAD states or bare repositories.
+ * `head` contains the full commit SHA the worktree currently points to.
+ */
+export type WorktreeInfo = { path: string; head: string; branch: string | null; is_bare: boolean }
+/**
+ * Provenance and age of a single managed worktree.
+ * 
+ * Paths are stored canonicalized. `session_id` is `None` until a session is
+ * linked to the worktree. `created_at` and `last_activity` are Unix epoch
+ * seconds; `last_activity` advances whenever Maestro operates on the
+ * worktree or observes a newer commit in it.
+ */
+export type WorktreeMetadata = { path: string; repo_path: string; branch: string | null; base_ref: string | null; session_id: number | null; created_at: number; last_activity: number }
+/**
+ * State of a background worktree operation, emitted on
+ * `worktree-progress-{op_id}` whenever it changes. `session_id` is set
+ * when the operation created a session in the worktree.
+ */
+export type WorktreeProgress = 
+/**
+ * Running, with no progress reported by git yet. Small checkouts
+ * finish without reporting any.
+ */
+{ state: "started" } | { state: "running"; progress: GitProgress } | { state: "finished"; path: string; session_id: number | null } | { state: "failed"; error: AppError }
+/**
+ * Glanceable status of a single managed worktree, returned by `status_summary`.
+ * 
+ * `ahead`/`behind` are `None` when the branch has no upstream (or HEAD is
+ * detached). If any status check fails, `error` carries the message and the
+ * remaining fields hold whatever could be gathered, so one broken worktree
+ * does not hide the rest of the summary.
+ */
+export type WorktreeStatus = { path: string; branch: string | null; head: string; dirty_count: number; ahead: number | null; behind: number | null; last_commit: CommitInfo | null; error: string | null }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
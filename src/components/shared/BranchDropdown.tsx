@@ -1,12 +1,7 @@
-import { invoke } from "@tauri-apps/api/core";
 import { Check } from "lucide-react";
 import { useCallback, useEffect, useRef, useState } from "react";
-
-interface BranchInfo {
-  name: string;
-  is_remote: boolean;
-  is_current: boolean;
-}
+import { type BranchInfo, commands } from "@/bindings";
+import { unwrap } from "@/lib/ipc";
 
 interface BranchDropdownProps {
   repoPath: string;
@@ -40,7 +35,7 @@ export function BranchDropdown({
     setLoading(true);
     setError(null);
     try {
-      const result = await invoke<BranchInfo[]>("git_branches", { repoPath });
+      const result = await unwrap(commands.gitBranches(repoPath));
       if (!mountedRef.current) return;
       setBranches(result);
       const currentIdx = result.findIndex((b) => b.is_current);
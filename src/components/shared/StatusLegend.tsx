@@ -5,6 +5,8 @@ const STATUS_DEFS: {
   label: string;
   colorClass: string;
 }[] = [
+  { key: "Blocked", label: "Blocked", colorClass: "bg-gray-400" },
+  { key: "Queued", label: "Queued", colorClass: "bg-gray-400" },
   { key: "Starting", label: "Starting...", colorClass: "bg-orange-400" },
   { key: "Idle", label: "Idle", colorClass: "bg-blue-400" },
   { key: "Working", label: "Working", colorClass: "bg-emerald-400" },
@@ -21,6 +23,8 @@ export function StatusLegend() {
       return acc;
     },
     {
+      Blocked: 0,
+      Queued: 0,
       Starting: 0,
       Idle: 0,
       Working: 0,
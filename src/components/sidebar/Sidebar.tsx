@@ -26,7 +26,7 @@ import {
   Zap,
 } from "lucide-react";
 import { useCallback, useEffect, useRef, useState } from "react";
-import { type AiMode, type BackendSessionStatus, useSessionStore } from "@/stores/useSessionStore";
+import { type BackendSessionStatus, type BuiltinAiMode, useSessionStore } from "@/stores/useSessionStore";
 
 type SidebarTab = "config" | "processes";
 
@@ -49,6 +49,8 @@ const SIDEBAR_COLLAPSE_THRESHOLD = 60;
 const SIDEBAR_WIDTH_STEP = 4;
 
 const STATUS_DOT_CLASS: Record<BackendSessionStatus, string> = {
+  Blocked: "bg-maestro-muted",
+  Queued: "bg-maestro-muted",
   Starting: "bg-maestro-orange",
   Idle: "bg-maestro-accent",
   Working: "bg-maestro-green",
@@ -58,6 +60,8 @@ const STATUS_DOT_CLASS: Record<BackendSessionStatus, string> = {
 };
 
 const STATUS_LABEL: Record<BackendSessionStatus, string> = {
+  Blocked: "Blocked",
+  Queued: "Queued",
   Starting: "Starting",
   Idle: "Idle",
   Working: "Working",
@@ -397,8 +401,10 @@ function SessionsSection() {
 
 /* ── 4. Status ── */
 
-const AI_MODES: AiMode[] = ["Claude", "Gemini", "Codex", "Plain"];
+const AI_MODES: BuiltinAiMode[] = ["Claude", "Gemini", "Codex", "Plain"];
 const SESSION_STATUSES: BackendSessionStatus[] = [
+  "Blocked",
+  "Queued",
   "Starting",
   "Idle",
   "Working",
@@ -407,7 +413,7 @@ const SESSION_STATUSES: BackendSessionStatus[] = [
   "Error",
 ];
 
-const MODE_ICON: Record<AiMode, React.ElementType> = {
+const MODE_ICON: Record<BuiltinAiMode, React.ElementType> = {
   Claude: Bot,
   Gemini: Sparkles,
   Codex: Cpu,
@@ -419,11 +425,15 @@ function StatusSection() {
   const counts = sessions.reduce(
     (acc, session) => {
       acc.status[session.status] = (acc.status[session.status] ?? 0) + 1;
-      acc.mode[session.mode] = (acc.mode[session.mode] ?? 0) + 1;
+      if (typeof session.mode === "string") {
+        acc.mode[session.mode] = (acc.mode[session.mode] ?? 0) + 1;
+      }
       return acc;
     },
     {
       status: {
+        Blocked: 0,
+        Queued: 0,
         Starting: 0,
         Idle: 0,
         Working: 0,
@@ -436,7 +446,7 @@ function StatusSection() {
         Gemini: 0,
         Codex: 0,
         Plain: 0,
-      } as Record<AiMode, number>,
+      } as Record<BuiltinAiMode, number>,
     },
   );
 
@@ -5,7 +5,12 @@ import { useCallback, useEffect, useRef } from "react";
 import "@xterm/xterm/css/xterm.css";
 
 import { killSession, onPtyOutput, resizePty, writeStdin } from "@/lib/terminal";
-import { type AiMode, type BackendSessionStatus, useSessionStore } from "@/stores/useSessionStore";
+import {
+  type AiMode,
+  type BackendSessionStatus,
+  type BuiltinAiMode,
+  useSessionStore,
+} from "@/stores/useSessionStore";
 import { QuickActionPills } from "./QuickActionPills";
 import { type AIProvider, type SessionStatus, TerminalHeader } from "./TerminalHeader";
 
@@ -23,7 +28,10 @@ interface TerminalViewProps {
 
 /** Map backend AiMode to frontend AIProvider */
 function mapAiMode(mode: AiMode): AIProvider {
-  const map: Record<AiMode, AIProvider> = {
+  if (typeof mode !== "string") {
+    return "plain";
+  }
+  const map: Record<BuiltinAiMode, AIProvider> = {
     Claude: "claude",
     Gemini: "gemini",
     Codex: "codex",
@@ -40,6 +48,8 @@ function mapAiMode(mode: AiMode): AIProvider {
 /** Map backend SessionStatus to frontend SessionStatus */
 function mapStatus(status: BackendSessionStatus): SessionStatus {
   const map: Record<BackendSessionStatus, SessionStatus> = {
+    Blocked: "starting",
+    Queued: "starting",
     Starting: "starting",
     Idle: "idle",
     Working: "working",
@@ -0,0 +1,18 @@
+/**
+ * Helpers for the generated command bindings in `@/bindings`.
+ *
+ * The generated `commands.*` functions resolve to a `Result` instead of
+ * rejecting; `unwrap` turns an error result back into a rejected promise
+ * carrying the backend's `AppError`, as a plain `invoke` would.
+ */
+
+import type { AppError, Result } from "@/bindings";
+
+/** Resolves to the command's data, or rejects with its `AppError`. */
+export async function unwrap<T>(result: Promise<Result<T, AppError>>): Promise<T> {
+  const settled = await result;
+  if (settled.status === "error") {
+    throw settled.error;
+  }
+  return settled.data;
+}
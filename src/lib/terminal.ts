@@ -1,12 +1,14 @@
 /**
- * Thin wrappers around Tauri `invoke` / `listen` for PTY session management.
+ * Thin wrappers around the generated command bindings and Tauri `listen` for
+ * PTY session management.
  *
  * Each function maps 1:1 to a Rust `#[tauri::command]` handler. Errors are
  * propagated as rejected promises; callers are responsible for catch/logging.
  */
 
-import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { commands } from "@/bindings";
+import { unwrap } from "@/lib/ipc";
 
 /**
  * Spawns a new PTY shell session on the backend.
@@ -14,22 +16,22 @@ import { listen, type UnlistenFn } from "@tauri-apps/api/event";
  * @returns The numeric session ID assigned by the backend.
  */
 export async function spawnShell(cwd?: string): Promise<number> {
-  return invoke<number>("spawn_shell", { cwd: cwd ?? null });
+  return unwrap(commands.spawnShell(cwd ?? null));
 }
 
 /** Writes raw bytes to the PTY stdin of the given session. */
 export async function writeStdin(sessionId: number, data: string): Promise<void> {
-  return invoke("write_stdin", { sessionId, data });
+  await unwrap(commands.writeStdin(sessionId, data));
 }
 
 /** Notifies the backend PTY of a terminal dimension change (rows x cols). */
 export async function resizePty(sessionId: number, rows: number, cols: number): Promise<void> {
-  return invoke("resize_pty", { sessionId, rows, cols });
+  await unwrap(commands.resizePty(sessionId, rows, cols));
 }
 
 /** Terminates the backend PTY process and cleans up the session. */
 export async function killSession(sessionId: number): Promise<void> {
-  return invoke("kill_session", { sessionId });
+  await unwrap(commands.killSession(sessionId));
 }
 
 /**
@@ -1,36 +1,15 @@
-import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { create } from "zustand";
+import { type AiMode, commands, type SessionConfig, type SessionStatus } from "@/bindings";
+import { unwrap } from "@/lib/ipc";
 
-/** AI provider variants supported by the backend orchestrator. */
-export type AiMode = "Claude" | "Gemini" | "Codex" | "Plain";
+export type { AiMode, SessionConfig };
 
-/**
- * Backend-emitted session lifecycle states.
- * Must stay in sync with the Rust `SessionStatus` enum.
- */
-export type BackendSessionStatus =
-  | "Starting"
-  | "Idle"
-  | "Working"
-  | "NeedsInput"
-  | "Done"
-  | "Error";
+/** Backend-emitted session lifecycle states (the Rust `SessionStatus`). */
+export type BackendSessionStatus = SessionStatus;
 
-/**
- * Mirrors the Rust `SessionConfig` struct returned by `get_sessions`.
- *
- * @property id - Unique numeric session ID assigned by the backend.
- * @property branch - Git branch the session operates on, or null for the default branch.
- * @property worktree_path - Filesystem path to the git worktree, if one was created.
- */
-export interface SessionConfig {
-  id: number;
-  mode: AiMode;
-  branch: string | null;
-  status: BackendSessionStatus;
-  worktree_path: string | null;
-}
+/** The built-in agents, i.e. every `AiMode` but custom agents. */
+export type BuiltinAiMode = Exclude<AiMode, { Custom: string }>;
 
 /** Shape of the Tauri `session-status-changed` event payload. */
 interface SessionStatusPayload {
@@ -71,7 +50,7 @@ export const useSessionStore = create<SessionState>()((set) => ({
   fetchSessions: async () => {
     set({ isLoading: true, error: null });
     try {
-      const sessions = await invoke<SessionConfig[]>("get_sessions");
+      const sessions = await unwrap(commands.getSessions(null));
       set({ sessions, isLoading: false });
     } catch (err) {
       console.error("Failed to fetch sessions:", err);